@@ -0,0 +1,172 @@
+//! Standard session level messages, for dictionaries of venues which
+//! omit them (see [`Dictionary::add_missing_admin_messages`]).
+//!
+//! [`Dictionary::add_missing_admin_messages`]: crate::Dictionary::add_missing_admin_messages
+
+use crate::Version;
+
+/// Admin messages of FIX 4.2, also valid for FIX 4.3.
+const FIX42_MESSAGES: &str = "<fix>
+ <messages>
+  <message name='Heartbeat' msgtype='0' msgcat='admin'>
+   <field name='TestReqID' required='N'/>
+  </message>
+  <message name='TestRequest' msgtype='1' msgcat='admin'>
+   <field name='TestReqID' required='Y'/>
+  </message>
+  <message name='ResendRequest' msgtype='2' msgcat='admin'>
+   <field name='BeginSeqNo' required='Y'/>
+   <field name='EndSeqNo' required='Y'/>
+  </message>
+  <message name='Reject' msgtype='3' msgcat='admin'>
+   <field name='RefSeqNum' required='Y'/>
+   <field name='RefTagID' required='N'/>
+   <field name='RefMsgType' required='N'/>
+   <field name='SessionRejectReason' required='N'/>
+   <field name='Text' required='N'/>
+   <field name='EncodedTextLen' required='N'/>
+   <field name='EncodedText' required='N'/>
+  </message>
+  <message name='SequenceReset' msgtype='4' msgcat='admin'>
+   <field name='GapFillFlag' required='N'/>
+   <field name='NewSeqNo' required='Y'/>
+  </message>
+  <message name='Logout' msgtype='5' msgcat='admin'>
+   <field name='Text' required='N'/>
+   <field name='EncodedTextLen' required='N'/>
+   <field name='EncodedText' required='N'/>
+  </message>
+  <message name='Logon' msgtype='A' msgcat='admin'>
+   <field name='EncryptMethod' required='Y'/>
+   <field name='HeartBtInt' required='Y'/>
+   <field name='RawDataLength' required='N'/>
+   <field name='RawData' required='N'/>
+   <field name='ResetSeqNumFlag' required='N'/>
+   <field name='MaxMessageSize' required='N'/>
+   <group name='NoMsgTypes' required='N'>
+    <field name='RefMsgType' required='N'/>
+    <field name='MsgDirection' required='N'/>
+   </group>
+  </message>
+ </messages>
+</fix>";
+
+/// Admin messages of FIX 4.4.
+const FIX44_MESSAGES: &str = "<fix>
+ <messages>
+  <message name='Heartbeat' msgtype='0' msgcat='admin'>
+   <field name='TestReqID' required='N'/>
+  </message>
+  <message name='TestRequest' msgtype='1' msgcat='admin'>
+   <field name='TestReqID' required='Y'/>
+  </message>
+  <message name='ResendRequest' msgtype='2' msgcat='admin'>
+   <field name='BeginSeqNo' required='Y'/>
+   <field name='EndSeqNo' required='Y'/>
+  </message>
+  <message name='Reject' msgtype='3' msgcat='admin'>
+   <field name='RefSeqNum' required='Y'/>
+   <field name='RefTagID' required='N'/>
+   <field name='RefMsgType' required='N'/>
+   <field name='SessionRejectReason' required='N'/>
+   <field name='Text' required='N'/>
+   <field name='EncodedTextLen' required='N'/>
+   <field name='EncodedText' required='N'/>
+  </message>
+  <message name='SequenceReset' msgtype='4' msgcat='admin'>
+   <field name='GapFillFlag' required='N'/>
+   <field name='NewSeqNo' required='Y'/>
+  </message>
+  <message name='Logout' msgtype='5' msgcat='admin'>
+   <field name='Text' required='N'/>
+   <field name='EncodedTextLen' required='N'/>
+   <field name='EncodedText' required='N'/>
+  </message>
+  <message name='Logon' msgtype='A' msgcat='admin'>
+   <field name='EncryptMethod' required='Y'/>
+   <field name='HeartBtInt' required='Y'/>
+   <field name='RawDataLength' required='N'/>
+   <field name='RawData' required='N'/>
+   <field name='ResetSeqNumFlag' required='N'/>
+   <field name='NextExpectedMsgSeqNum' required='N'/>
+   <field name='MaxMessageSize' required='N'/>
+   <group name='NoMsgTypes' required='N'>
+    <field name='RefMsgType' required='N'/>
+    <field name='MsgDirection' required='N'/>
+   </group>
+   <field name='TestMessageIndicator' required='N'/>
+   <field name='Username' required='N'/>
+   <field name='Password' required='N'/>
+  </message>
+ </messages>
+</fix>";
+
+/// Fields used by admin messages, in their FIX 4.4 form.
+pub(crate) const FIELDS: &str = "<fix>
+ <fields>
+  <field number='7' name='BeginSeqNo' type='SEQNUM'/>
+  <field number='16' name='EndSeqNo' type='SEQNUM'/>
+  <field number='36' name='NewSeqNo' type='SEQNUM'/>
+  <field number='45' name='RefSeqNum' type='SEQNUM'/>
+  <field number='58' name='Text' type='STRING'/>
+  <field number='95' name='RawDataLength' type='LENGTH'/>
+  <field number='96' name='RawData' type='DATA'/>
+  <field number='98' name='EncryptMethod' type='INT'>
+   <value enum='0' description='NONE_OTHER'/>
+   <value enum='1' description='PKCS'/>
+   <value enum='2' description='DES'/>
+   <value enum='3' description='PKCS_DES'/>
+   <value enum='4' description='PGP_DES'/>
+   <value enum='5' description='PGP_DES_MD5'/>
+   <value enum='6' description='PEM_DES_MD5'/>
+  </field>
+  <field number='108' name='HeartBtInt' type='INT'/>
+  <field number='112' name='TestReqID' type='STRING'/>
+  <field number='123' name='GapFillFlag' type='BOOLEAN'/>
+  <field number='141' name='ResetSeqNumFlag' type='BOOLEAN'/>
+  <field number='354' name='EncodedTextLen' type='LENGTH'/>
+  <field number='355' name='EncodedText' type='DATA'/>
+  <field number='371' name='RefTagID' type='INT'/>
+  <field number='372' name='RefMsgType' type='STRING'/>
+  <field number='373' name='SessionRejectReason' type='INT'>
+   <value enum='0' description='INVALID_TAG_NUMBER'/>
+   <value enum='1' description='REQUIRED_TAG_MISSING'/>
+   <value enum='2' description='TAG_NOT_DEFINED_FOR_THIS_MESSAGE_TYPE'/>
+   <value enum='3' description='UNDEFINED_TAG'/>
+   <value enum='4' description='TAG_SPECIFIED_WITHOUT_A_VALUE'/>
+   <value enum='5' description='VALUE_IS_INCORRECT'/>
+   <value enum='6' description='INCORRECT_DATA_FORMAT_FOR_VALUE'/>
+   <value enum='7' description='DECRYPTION_PROBLEM'/>
+   <value enum='8' description='SIGNATURE_PROBLEM'/>
+   <value enum='9' description='COMPID_PROBLEM'/>
+   <value enum='10' description='SENDINGTIME_ACCURACY_PROBLEM'/>
+   <value enum='11' description='INVALID_MSGTYPE'/>
+   <value enum='12' description='XML_VALIDATION_ERROR'/>
+   <value enum='13' description='TAG_APPEARS_MORE_THAN_ONCE'/>
+   <value enum='14' description='TAG_SPECIFIED_OUT_OF_REQUIRED_ORDER'/>
+   <value enum='15' description='REPEATING_GROUP_FIELDS_OUT_OF_ORDER'/>
+   <value enum='16' description='INCORRECT_NUMINGROUP_COUNT_FOR_REPEATING_GROUP'/>
+   <value enum='17' description='NON_DATA_VALUE_INCLUDES_FIELD_DELIMITER'/>
+   <value enum='99' description='OTHER'/>
+  </field>
+  <field number='383' name='MaxMessageSize' type='LENGTH'/>
+  <field number='384' name='NoMsgTypes' type='NUMINGROUP'/>
+  <field number='385' name='MsgDirection' type='CHAR'>
+   <value enum='R' description='RECEIVE'/>
+   <value enum='S' description='SEND'/>
+  </field>
+  <field number='464' name='TestMessageIndicator' type='BOOLEAN'/>
+  <field number='553' name='Username' type='STRING'/>
+  <field number='554' name='Password' type='STRING'/>
+  <field number='789' name='NextExpectedMsgSeqNum' type='SEQNUM'/>
+ </fields>
+</fix>";
+
+/// Admin messages of legacy (not FIXT) FIX `version`, if known.
+pub(crate) fn messages(version: &Version) -> Option<&'static str> {
+    match (version.major, version.minor) {
+        (4, 2) | (4, 3) => Some(FIX42_MESSAGES),
+        (4, 4) => Some(FIX44_MESSAGES),
+        _ => None,
+    }
+}
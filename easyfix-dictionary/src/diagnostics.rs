@@ -0,0 +1,303 @@
+//! Non-fatal dictionary issues, reported by `Dictionary::diagnostics()`.
+
+use std::{collections::HashSet, fmt};
+
+use crate::{BasicType, Component, Dictionary, Location, Member, MemberKind};
+
+/// Class of non-fatal dictionary issue.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum WarningKind {
+    /// Field not referenced by any message, component, header or trailer.
+    UnusedField,
+    /// Field type doesn't match the way field is used, e.g. group counter
+    /// not defined as `NUMINGROUP`.
+    SuspiciousType,
+    /// Enum value without `description` attribute, value itself is used
+    /// instead.
+    MissingEnumDescription,
+    /// Name not following FIX conventions, e.g. not in `UpperCamelCase`.
+    NamingConvention,
+    /// Field defined more than once in message, group or component, e.g.
+    /// directly and through a component. Reported by code generator, which
+    /// keeps only one definition.
+    DuplicatedField,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Warning {
+    kind: WarningKind,
+    message: String,
+    location: Location,
+}
+
+impl Warning {
+    pub fn new(kind: WarningKind, message: String, location: &Location) -> Warning {
+        Warning {
+            kind,
+            message,
+            location: location.clone(),
+        }
+    }
+
+    pub fn kind(&self) -> WarningKind {
+        self.kind
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.message, self.location)
+    }
+}
+
+fn is_upper_camel_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+impl Dictionary {
+    /// Check processed dictionary for non-fatal issues.
+    ///
+    /// Warnings are not checked during processing, so this should be
+    /// called once all XML files are processed. Order of reported issues
+    /// is stable, so the output can be compared between runs.
+    pub fn diagnostics(&self) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+
+        let mut components: Vec<&Component> = self.header.iter().collect();
+        components.extend(&self.trailer);
+        components.extend(&self.components);
+        let mut messages: Vec<_> = self.messages.values().collect();
+        messages.sort_by(|a, b| a.name().cmp(b.name()));
+
+        let mut member_lists: Vec<(&str, &[Member])> = components
+            .iter()
+            .map(|component| (component.name(), component.members()))
+            .collect();
+        member_lists.extend(
+            messages
+                .iter()
+                .map(|message| (message.name(), message.members())),
+        );
+
+        let mut used_fields: HashSet<&str> = components
+            .iter()
+            .filter_map(|component| component.number_of_elements())
+            .map(Member::name)
+            .collect();
+        used_fields.extend(
+            member_lists
+                .iter()
+                .flat_map(|(_, members)| *members)
+                .filter(|member| member.kind() == MemberKind::Field)
+                .map(Member::name),
+        );
+
+        let mut fields: Vec<_> = self.fields.values().collect();
+        fields.sort_by_key(|field| field.number());
+        for field in fields {
+            if !used_fields.contains(field.name()) {
+                warnings.push(Warning::new(
+                    WarningKind::UnusedField,
+                    format!(
+                        "Field `{}` (tag {}) is not used by any message or component",
+                        field.name(),
+                        field.number()
+                    ),
+                    field.location(),
+                ));
+            }
+            if !is_upper_camel_case(field.name()) {
+                warnings.push(Warning::new(
+                    WarningKind::NamingConvention,
+                    format!("Field name `{}` is not in UpperCamelCase", field.name()),
+                    field.location(),
+                ));
+            }
+            for value in field.values().unwrap_or_default() {
+                if value.description.is_none() {
+                    warnings.push(Warning::new(
+                        WarningKind::MissingEnumDescription,
+                        format!(
+                            "Value `{}` of field `{}` has no description",
+                            value.value(),
+                            field.name()
+                        ),
+                        value.location(),
+                    ));
+                }
+            }
+        }
+
+        for component in &components {
+            if !is_upper_camel_case(component.name()) {
+                warnings.push(Warning::new(
+                    WarningKind::NamingConvention,
+                    format!(
+                        "Component name `{}` is not in UpperCamelCase",
+                        component.name()
+                    ),
+                    component.location(),
+                ));
+            }
+            let Some(number_of_elements) = component.number_of_elements() else {
+                continue;
+            };
+            if !number_of_elements.name().starts_with("No") {
+                warnings.push(Warning::new(
+                    WarningKind::NamingConvention,
+                    format!(
+                        "Group counter `{}` of `{}` doesn't start with `No`",
+                        number_of_elements.name(),
+                        component.name()
+                    ),
+                    number_of_elements.location(),
+                ));
+            }
+            if let Some(field) = self.fields_by_name.get(number_of_elements.name()) {
+                if field.type_() != BasicType::NumInGroup {
+                    warnings.push(Warning::new(
+                        WarningKind::SuspiciousType,
+                        format!(
+                            "Group counter `{}` of `{}` has type {:?} instead of NumInGroup",
+                            field.name(),
+                            component.name(),
+                            field.type_()
+                        ),
+                        number_of_elements.location(),
+                    ));
+                }
+            }
+        }
+
+        for message in &messages {
+            if !is_upper_camel_case(message.name()) {
+                warnings.push(Warning::new(
+                    WarningKind::NamingConvention,
+                    format!("Message name `{}` is not in UpperCamelCase", message.name()),
+                    message.location(),
+                ));
+            }
+        }
+
+        // Data fields are deserialized using length from preceding field,
+        // see `Dictionary::length_data_pairs()`
+        let (_, length_data_errors) = self.pair_length_data();
+        for error in length_data_errors {
+            warnings.push(Warning::new(
+                WarningKind::SuspiciousType,
+                error.message(),
+                error.location(),
+            ));
+        }
+
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WarningKind;
+    use crate::Dictionary;
+
+    const FIX_XML: &str = r#"<fix type='FIX' major='4' minor='4' servicepack='0'>
+ <header>
+  <field name='MsgType' required='Y'/>
+ </header>
+ <trailer>
+  <field name='CheckSum' required='Y'/>
+ </trailer>
+ <messages>
+  <message name='Heartbeat' msgtype='0' msgcat='admin'>
+   <field name='TestReqID' required='N'/>
+   <field name='RawData' required='N'/>
+  </message>
+  <message name='venue_order' msgtype='U1' msgcat='app'>
+   <component name='Legs' required='N'/>
+  </message>
+ </messages>
+ <components>
+  <component name='Legs'>
+   <group name='LegCount' required='N'>
+    <field name='TestReqID' required='Y'/>
+   </group>
+  </component>
+ </components>
+ <fields>
+  <field number='10' name='CheckSum' type='STRING'/>
+  <field number='35' name='MsgType' type='STRING'>
+   <value enum='0' description='HEARTBEAT'/>
+   <value enum='U1'/>
+  </field>
+  <field number='58' name='Text' type='STRING'/>
+  <field number='96' name='RawData' type='DATA'/>
+  <field number='112' name='TestReqID' type='STRING'/>
+  <field number='555' name='LegCount' type='INT'/>
+ </fields>
+</fix>"#;
+
+    #[test]
+    fn warnings_collected() {
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(FIX_XML).unwrap();
+
+        let warnings: Vec<_> = dictionary
+            .diagnostics()
+            .into_iter()
+            .map(|warning| (warning.kind(), warning.message().to_owned()))
+            .collect();
+        assert_eq!(
+            warnings,
+            [
+                (
+                    WarningKind::MissingEnumDescription,
+                    "Value `U1` of field `MsgType` has no description".to_owned()
+                ),
+                (
+                    WarningKind::UnusedField,
+                    "Field `Text` (tag 58) is not used by any message or component".to_owned()
+                ),
+                (
+                    WarningKind::NamingConvention,
+                    "Group counter `LegCount` of `Legs` doesn't start with `No`".to_owned()
+                ),
+                (
+                    WarningKind::SuspiciousType,
+                    "Group counter `LegCount` of `Legs` has type Int instead of NumInGroup"
+                        .to_owned()
+                ),
+                (
+                    WarningKind::NamingConvention,
+                    "Message name `venue_order` is not in UpperCamelCase".to_owned()
+                ),
+                (
+                    WarningKind::SuspiciousType,
+                    "Data field `RawData` of `Heartbeat` is not preceded by Length field"
+                        .to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn location_of_warning() {
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(FIX_XML).unwrap();
+
+        let warning = dictionary
+            .diagnostics()
+            .into_iter()
+            .find(|warning| warning.kind() == WarningKind::UnusedField)
+            .unwrap();
+        assert_eq!(warning.location().line(), 30);
+        assert_eq!(warning.location().path(), "/fix/fields/field[@name='Text']");
+    }
+}
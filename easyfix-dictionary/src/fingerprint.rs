@@ -0,0 +1,193 @@
+//! Dictionary fingerprint, reported by `Dictionary::fingerprint()`.
+//!
+//! Fingerprint is a hash of normalized dictionary, i.e. of everything that
+//! affects encoding of messages: versions, fields (with their types and
+//! enumerated values), components, groups and message layouts. Descriptions,
+//! locations and order of definitions of fields and messages are not
+//! included, so the same dictionary reformatted or reordered gets the same
+//! fingerprint.
+
+use std::fmt::Write;
+
+use crate::{Component, Dictionary, Member, MemberKind};
+
+/// FNV-1a 64, stable across Rust versions and platforms unlike
+/// `std::hash::DefaultHasher`.
+fn fnv1a_64(data: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    data.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+fn write_members(out: &mut String, members: &[Member]) {
+    for member in members {
+        let kind = match member.kind() {
+            MemberKind::Field => "field",
+            MemberKind::Component => "component",
+        };
+        let required = if member.required() { 'Y' } else { 'N' };
+        let _ = writeln!(out, "  {kind} {} {required}", member.name());
+    }
+}
+
+fn write_component(out: &mut String, component: &Component) {
+    match component.number_of_elements() {
+        Some(number_of_elements) => {
+            let required = if number_of_elements.required() {
+                'Y'
+            } else {
+                'N'
+            };
+            let _ = writeln!(
+                out,
+                "group {} {} {required}",
+                component.name(),
+                number_of_elements.name()
+            );
+        }
+        None => {
+            let _ = writeln!(out, "component {}", component.name());
+        }
+    }
+    write_members(out, component.members());
+}
+
+impl Dictionary {
+    /// Normalized, textual form of dictionary, input of
+    /// [`Dictionary::fingerprint`].
+    pub fn normalized_spec(&self) -> String {
+        let mut out = String::new();
+
+        if let Some(version) = &self.fixt_version {
+            let _ = writeln!(out, "fixt {version}");
+        }
+        if let Some(version) = &self.fix_version {
+            let _ = writeln!(out, "fix {version}");
+        }
+
+        let mut fields: Vec<_> = self.fields.values().collect();
+        fields.sort_by_key(|field| field.number());
+        for field in fields {
+            let _ = writeln!(
+                out,
+                "field {} {} {:?}",
+                field.number(),
+                field.name(),
+                field.type_()
+            );
+            if let Some(values) = field.values() {
+                let mut values: Vec<_> = values.iter().map(|value| value.value()).collect();
+                values.sort_unstable();
+                for value in values {
+                    let _ = writeln!(out, "  value {value}");
+                }
+            }
+        }
+
+        for (name, component) in [("header", &self.header), ("trailer", &self.trailer)] {
+            if let Some(component) = component {
+                let _ = writeln!(out, "{name}");
+                write_members(&mut out, component.members());
+            }
+        }
+
+        let mut components: Vec<_> = self.components.iter().collect();
+        components.sort_by_key(|component| component.name());
+        for component in components {
+            write_component(&mut out, component);
+        }
+
+        let mut messages: Vec<_> = self.messages.values().collect();
+        messages.sort_by_key(|message| message.msg_type().to_vec());
+        for message in messages {
+            let _ = writeln!(
+                out,
+                "message {} {} {:?}",
+                String::from_utf8_lossy(&message.msg_type()),
+                message.name(),
+                message.msg_cat()
+            );
+            write_members(&mut out, message.members());
+            if !message.header_overrides().is_empty() {
+                let _ = writeln!(out, " header");
+                write_members(&mut out, message.header_overrides());
+            }
+        }
+
+        out
+    }
+
+    /// Hash of [normalized](Dictionary::normalized_spec) dictionary, two
+    /// dictionaries encoding messages the same way have equal fingerprints.
+    pub fn fingerprint(&self) -> u64 {
+        fnv1a_64(self.normalized_spec().as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Dictionary;
+
+    fn dictionary(xml: &str) -> Dictionary {
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(xml).unwrap();
+        dictionary
+    }
+
+    fn xml(fields: &str, heartbeat: &str) -> String {
+        format!(
+            "<fix type='FIX' major='4' minor='4' servicepack='0'>
+             <header><field name='MsgType' required='Y'/></header>
+             <trailer><field name='CheckSum' required='Y'/></trailer>
+             <messages>
+              <message name='Heartbeat' msgtype='0' msgcat='admin'>{heartbeat}</message>
+             </messages>
+             <components/>
+             <fields>{fields}</fields>
+            </fix>"
+        )
+    }
+
+    const FIELDS: &str = "
+        <field number='10' name='CheckSum' type='STRING'/>
+        <field number='35' name='MsgType' type='STRING'>
+         <value enum='0' description='HEARTBEAT'/>
+        </field>
+        <field number='112' name='TestReqID' type='STRING'/>";
+
+    #[test]
+    fn fingerprint_ignores_descriptions_and_order() {
+        let base = dictionary(&xml(FIELDS, "<field name='TestReqID' required='N'/>"));
+        let reordered = dictionary(&xml(
+            "<field number='112' name='TestReqID' type='STRING'/>
+             <field number='35' name='MsgType' type='STRING'>
+              <value enum='0' description='HEARTBEAT_MSG'/>
+             </field>
+             <field number='10' name='CheckSum' type='STRING'/>",
+            "<field name='TestReqID' required='N'/>",
+        ));
+        assert_eq!(base.normalized_spec(), reordered.normalized_spec());
+        assert_eq!(base.fingerprint(), reordered.fingerprint());
+    }
+
+    #[test]
+    fn fingerprint_changed_by_layout() {
+        let base = dictionary(&xml(FIELDS, "<field name='TestReqID' required='N'/>")).fingerprint();
+        let required =
+            dictionary(&xml(FIELDS, "<field name='TestReqID' required='Y'/>")).fingerprint();
+        let retyped = dictionary(&xml(
+            &FIELDS.replace(
+                "name='TestReqID' type='STRING'",
+                "name='TestReqID' type='INT'",
+            ),
+            "<field name='TestReqID' required='N'/>",
+        ))
+        .fingerprint();
+        assert_ne!(base, required);
+        assert_ne!(base, retyped);
+        assert_ne!(required, retyped);
+    }
+}
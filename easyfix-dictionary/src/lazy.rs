@@ -0,0 +1,429 @@
+//! Dictionary materialized on first access.
+
+use std::{collections::HashMap, fs, ops::Range, path::Path, sync::OnceLock};
+
+use anyhow::{anyhow, Context as ErrorContext, Result};
+
+use crate::{xml::Element, Component, Field, Message, MsgType};
+
+enum Source {
+    Owned(String),
+    #[cfg(all(feature = "mmap", unix))]
+    Mapped(crate::mmap::Mmap),
+}
+
+impl Source {
+    /// Mapped file is not kept as `str`, so it's validated on access.
+    fn as_str(&self) -> Result<&str> {
+        match self {
+            Source::Owned(xml) => Ok(xml),
+            #[cfg(all(feature = "mmap", unix))]
+            Source::Mapped(mmap) => {
+                std::str::from_utf8(mmap.as_bytes()).context("Mapped file is not valid UTF-8")
+            }
+        }
+    }
+}
+
+/// Byte ranges of top level sections.
+#[derive(Debug, Default)]
+struct Index {
+    root_path: String,
+    header: Option<Range<usize>>,
+    trailer: Option<Range<usize>>,
+    messages: Option<Range<usize>>,
+    components: Option<Range<usize>>,
+    fields: Option<Range<usize>>,
+}
+
+impl Index {
+    fn new(xml: &str) -> Result<Index> {
+        let root_name = root_name(xml).context("No root element")?;
+        Ok(Index {
+            root_path: format!("/{root_name}"),
+            header: find_section(xml, "header"),
+            trailer: find_section(xml, "trailer"),
+            messages: find_section(xml, "messages"),
+            components: find_section(xml, "components"),
+            fields: find_section(xml, "fields"),
+        })
+    }
+}
+
+fn root_name(xml: &str) -> Option<&str> {
+    let mut rest = xml;
+    loop {
+        let start = rest.find('<')?;
+        rest = &rest[start + 1..];
+        if rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let end = rest.find(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')?;
+            return Some(&rest[..end]);
+        }
+    }
+}
+
+/// Find first `<name>...</name>` (or `<name/>`) element.
+///
+/// This is a plain text search, element names inside comments or CDATA
+/// are not skipped.
+fn find_section(xml: &str, name: &str) -> Option<Range<usize>> {
+    let open = format!("<{name}");
+    let close = format!("</{name}>");
+    let mut from = 0;
+    while let Some(pos) = xml[from..].find(&open) {
+        let start = from + pos;
+        from = start + open.len();
+        if !matches!(
+            xml.as_bytes().get(from),
+            Some(b'>' | b'/' | b' ' | b'\t' | b'\r' | b'\n')
+        ) {
+            // Different element with the same prefix, e.g. `<fieldsX>`
+            continue;
+        }
+        let tag_end = from + xml[from..].find('>')? + 1;
+        if xml[..tag_end].ends_with("/>") {
+            return Some(start..tag_end);
+        }
+        let end = tag_end + xml[tag_end..].find(&close)? + close.len();
+        return Some(start..end);
+    }
+    None
+}
+
+struct Fields {
+    by_number: HashMap<u16, Field>,
+    numbers_by_name: HashMap<String, u16>,
+}
+
+struct Structure {
+    header: Option<Component>,
+    trailer: Option<Component>,
+    messages: HashMap<MsgType, Message>,
+    components: HashMap<String, Component>,
+}
+
+/// Dictionary which indexes single FIX/FIXT XML file and parses its
+/// sections on first access.
+///
+/// Useful for tools which need only a few lookups, as e.g. field lookups
+/// parse only the `<fields>` section. Fields are materialized separately
+/// from messages and components (including header and trailer), which are
+/// materialized together.
+///
+/// Unlike [Dictionary](crate::Dictionary), `MsgType` values are not
+/// filtered to match defined messages.
+pub struct LazyDictionary {
+    source: Source,
+    index: Index,
+    fields: OnceLock<Result<Fields>>,
+    structure: OnceLock<Result<Structure>>,
+}
+
+impl LazyDictionary {
+    pub fn new(xml: String) -> Result<LazyDictionary> {
+        LazyDictionary::with_source(Source::Owned(xml))
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<LazyDictionary> {
+        let path = path.as_ref();
+        let xml = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read `{}`", path.display()))?;
+        LazyDictionary::new(xml)
+    }
+
+    /// Open XML file using memory mapping instead of reading it.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated (by this or any other
+    /// process) while the dictionary is alive. Mapped content is accessed
+    /// as `&[u8]`, so its modification is undefined behavior, and access
+    /// beyond truncated end of file raises SIGBUS.
+    #[cfg(all(feature = "mmap", unix))]
+    pub unsafe fn open_mmap(path: impl AsRef<Path>) -> Result<LazyDictionary> {
+        let path = path.as_ref();
+        // SAFETY: file is not modified, as required from the caller
+        let mmap = unsafe { crate::mmap::Mmap::open(path) }
+            .with_context(|| format!("Failed to map `{}`", path.display()))?;
+        LazyDictionary::with_source(Source::Mapped(mmap))
+            .with_context(|| format!("Failed to index `{}`", path.display()))
+    }
+
+    fn with_source(source: Source) -> Result<LazyDictionary> {
+        let index = Index::new(source.as_str()?)?;
+        Ok(LazyDictionary {
+            source,
+            index,
+            fields: OnceLock::new(),
+            structure: OnceLock::new(),
+        })
+    }
+
+    fn parse_section(&self, range: &Option<Range<usize>>) -> Result<Option<Element>> {
+        range
+            .clone()
+            .map(|range| {
+                Element::parse_fragment(self.source.as_str()?, range, &self.index.root_path)
+            })
+            .transpose()
+    }
+
+    fn materialize_fields(&self) -> Result<Fields> {
+        let mut by_number = HashMap::new();
+        let mut numbers_by_name = HashMap::new();
+        if let Some(section) = self.parse_section(&self.index.fields)? {
+            for element in section.get_child_elements() {
+                let field = Field::from_xml(element)?;
+                numbers_by_name.insert(field.name().to_owned(), field.number());
+                by_number.insert(field.number(), field);
+            }
+        }
+        Ok(Fields {
+            by_number,
+            numbers_by_name,
+        })
+    }
+
+    fn materialize_structure(&self) -> Result<Structure> {
+        let mut components = Vec::new();
+        let header = self
+            .parse_section(&self.index.header)?
+            .map(|element| Component::from_header_or_trailer(&element))
+            .transpose()?
+            .map(|(header, groups)| {
+                components.extend(groups);
+                header
+            });
+        let trailer = self
+            .parse_section(&self.index.trailer)?
+            .map(|element| Component::from_header_or_trailer(&element))
+            .transpose()?
+            .map(|(trailer, groups)| {
+                components.extend(groups);
+                trailer
+            });
+
+        let mut messages = HashMap::new();
+        if let Some(section) = self.parse_section(&self.index.messages)? {
+            for element in section.get_child_elements() {
+                let (message, groups) = Message::from_xml(element)?;
+                messages.insert(message.msg_type(), message);
+                components.extend(groups);
+            }
+        }
+        if let Some(section) = self.parse_section(&self.index.components)? {
+            for element in section.get_child_elements() {
+                let (component, groups) = Component::from_xml(element)?;
+                components.push(component);
+                components.extend(groups);
+            }
+        }
+
+        Ok(Structure {
+            header,
+            trailer,
+            messages,
+            components: components
+                .into_iter()
+                .map(|component| (component.name().to_owned(), component))
+                .collect(),
+        })
+    }
+
+    fn fields(&self) -> Result<&Fields> {
+        self.fields
+            .get_or_init(|| self.materialize_fields())
+            .as_ref()
+            .map_err(|err| anyhow!("{err:#}"))
+    }
+
+    fn structure(&self) -> Result<&Structure> {
+        self.structure
+            .get_or_init(|| self.materialize_structure())
+            .as_ref()
+            .map_err(|err| anyhow!("{err:#}"))
+    }
+
+    pub fn field(&self, number: u16) -> Result<Option<&Field>> {
+        Ok(self.fields()?.by_number.get(&number))
+    }
+
+    pub fn field_by_name(&self, name: &str) -> Result<Option<&Field>> {
+        let fields = self.fields()?;
+        Ok(fields
+            .numbers_by_name
+            .get(name)
+            .and_then(|number| fields.by_number.get(number)))
+    }
+
+    pub fn header(&self) -> Result<Option<&Component>> {
+        Ok(self.structure()?.header.as_ref())
+    }
+
+    pub fn trailer(&self) -> Result<Option<&Component>> {
+        Ok(self.structure()?.trailer.as_ref())
+    }
+
+    pub fn message(&self, msg_type: &MsgType) -> Result<Option<&Message>> {
+        Ok(self.structure()?.messages.get(msg_type))
+    }
+
+    pub fn component(&self, name: &str) -> Result<Option<&Component>> {
+        Ok(self.structure()?.components.get(name))
+    }
+
+    /// Returns `true` if fields section was already parsed.
+    pub fn fields_materialized(&self) -> bool {
+        self.fields.get().is_some()
+    }
+
+    /// Returns `true` if messages and components were already parsed.
+    pub fn messages_materialized(&self) -> bool {
+        self.structure.get().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::LazyDictionary;
+    use crate::{BasicType, MsgType};
+
+    const FIX_XML: &str = "<fix type='FIX' major='4' minor='4' servicepack='0'>
+ <header>
+  <field name='MsgType' required='Y'/>
+ </header>
+ <trailer>
+  <field name='CheckSum' required='Y'/>
+ </trailer>
+ <messages>
+  <message name='TestRequest' msgtype='1' msgcat='admin'>
+   <field name='TestReqID' required='Y'/>
+  </message>
+ </messages>
+ <components/>
+ <fields>
+  <field number='10' name='CheckSum' type='STRING'/>
+  <field number='35' name='MsgType' type='STRING'/>
+  <field number='112' name='TestReqID' type='STRING'/>
+ </fields>
+</fix>";
+
+    #[test]
+    fn fields_materialized_separately() {
+        let dictionary = LazyDictionary::new(FIX_XML.to_owned()).unwrap();
+        assert!(!dictionary.fields_materialized());
+
+        let field = dictionary.field_by_name("TestReqID").unwrap().unwrap();
+        assert_eq!(field.number(), 112);
+        assert_eq!(field.type_(), BasicType::String);
+        assert_eq!(field.location().line(), 17);
+        assert_eq!(
+            field.location().path(),
+            "/fix/fields/field[@name='TestReqID']"
+        );
+        assert!(dictionary.field(58).unwrap().is_none());
+
+        assert!(dictionary.fields_materialized());
+        assert!(!dictionary.messages_materialized());
+    }
+
+    #[test]
+    fn messages_materialized_on_access() {
+        let dictionary = LazyDictionary::new(FIX_XML.to_owned()).unwrap();
+
+        let message = dictionary
+            .message(&MsgType::from_str("1").unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(message.name(), "TestRequest");
+        assert_eq!(message.location().line(), 9);
+        assert_eq!(dictionary.header().unwrap().unwrap().members().len(), 1);
+        assert!(dictionary.component("Instrument").unwrap().is_none());
+
+        assert!(dictionary.messages_materialized());
+        assert!(!dictionary.fields_materialized());
+    }
+
+    #[test]
+    fn errors_reported_on_access() {
+        let xml = FIX_XML.replace("number='112'", "number='abc'");
+        let dictionary = LazyDictionary::new(xml).unwrap();
+        assert!(dictionary.message(&MsgType::from_str("1").unwrap()).is_ok());
+        let error = dictionary.field(112).unwrap_err();
+        assert!(error.to_string().contains(" at 17:3 "), "{error}");
+        assert!(dictionary.field(112).is_err());
+    }
+
+    #[test]
+    fn file_matches_full_dictionary() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../easyfix-messages/xml/FIX50SP2.xml"
+        );
+        let lazy = LazyDictionary::open(path).unwrap();
+        let mut dictionary = crate::Dictionary::new(None);
+        dictionary
+            .process_fix_xml(&std::fs::read_to_string(path).unwrap())
+            .unwrap();
+
+        assert_eq!(lazy.field(55).unwrap(), dictionary.fields().get(&55));
+        let msg_type = MsgType::from_str("D").unwrap();
+        assert_eq!(
+            lazy.message(&msg_type).unwrap(),
+            dictionary.message(&msg_type)
+        );
+        assert_eq!(
+            lazy.component("Instrument").unwrap(),
+            dictionary.component("Instrument")
+        );
+    }
+
+    #[cfg(all(feature = "mmap", unix))]
+    #[test]
+    fn mapped_file_matches_full_dictionary() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../easyfix-messages/xml/FIX50SP2.xml"
+        );
+        // SAFETY: dictionary of the repository is not modified by tests
+        let lazy = unsafe { LazyDictionary::open_mmap(path) }.unwrap();
+        let eager = LazyDictionary::open(path).unwrap();
+        assert_eq!(lazy.field(44).unwrap(), eager.field(44).unwrap());
+        assert_eq!(
+            lazy.component("Instrument").unwrap(),
+            eager.component("Instrument").unwrap()
+        );
+    }
+
+    #[cfg(all(feature = "mmap", unix))]
+    #[test]
+    fn invalid_utf8_mapped_file_refused() {
+        let path =
+            std::env::temp_dir().join(format!("easyfix-lazy-mmap-{}.xml", std::process::id()));
+        let mut xml = FIX_XML.as_bytes().to_vec();
+        xml.splice(0..0, *b"<!-- caf\xe9 -->\n");
+        std::fs::write(&path, xml).unwrap();
+
+        // SAFETY: file is private to this test
+        let result = unsafe { LazyDictionary::open_mmap(&path) };
+        std::fs::remove_file(&path).unwrap();
+        let error = result.err().expect("invalid UTF-8 accepted");
+        assert!(format!("{error:#}").contains("valid UTF-8"), "{error:#}");
+    }
+
+    #[test]
+    fn invalid_utf8_file_refused() {
+        let path = std::env::temp_dir().join(format!("easyfix-lazy-{}.xml", std::process::id()));
+        let mut xml = FIX_XML.as_bytes().to_vec();
+        // Latin-1 encoded `é`
+        xml.splice(0..0, *b"<!-- caf\xe9 -->\n");
+        std::fs::write(&path, xml).unwrap();
+
+        let result = LazyDictionary::open(&path);
+        std::fs::remove_file(&path).unwrap();
+        let error = result.err().expect("invalid UTF-8 accepted");
+        assert!(format!("{error:#}").contains("valid UTF-8"), "{error:#}");
+    }
+}
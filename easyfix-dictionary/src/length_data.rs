@@ -0,0 +1,231 @@
+//! Pairing of Data/XmlData fields with Length fields holding their length,
+//! reported by `Dictionary::length_data_pairs()`.
+
+use std::{error::Error, fmt};
+
+use crate::{BasicType, Component, Dictionary, Field, IndexMap, Location, MemberKind};
+
+/// Data/XmlData field which can't be paired with exactly one Length field.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LengthDataError {
+    /// Data field not immediately preceded by Length field.
+    Orphan {
+        data: String,
+        owner: String,
+        location: Location,
+    },
+    /// Data field preceded by other Length field than elsewhere, or Length
+    /// field followed by other Data field than elsewhere.
+    Ambiguous {
+        length: String,
+        data: String,
+        owner: String,
+        location: Location,
+    },
+}
+
+impl LengthDataError {
+    /// Location of Data field member in XML source.
+    pub fn location(&self) -> &Location {
+        match self {
+            LengthDataError::Orphan { location, .. }
+            | LengthDataError::Ambiguous { location, .. } => location,
+        }
+    }
+
+    /// Issue description, without location.
+    pub(crate) fn message(&self) -> String {
+        match self {
+            LengthDataError::Orphan { data, owner, .. } => {
+                format!("Data field `{data}` of `{owner}` is not preceded by Length field")
+            }
+            LengthDataError::Ambiguous {
+                length,
+                data,
+                owner,
+                ..
+            } => format!(
+                "Length field `{length}` and Data field `{data}` of `{owner}` \
+                 are paired differently elsewhere"
+            ),
+        }
+    }
+}
+
+impl fmt::Display for LengthDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.message(), self.location())
+    }
+}
+
+impl Error for LengthDataError {}
+
+impl Dictionary {
+    /// Data/XmlData fields (keys) and Length fields holding their length
+    /// (values), by tag.
+    ///
+    /// Data field is paired with Length field immediately preceding it in
+    /// header, trailer, component and message definitions, pairing must be
+    /// the same everywhere. Fails with all Data fields not paired this way,
+    /// so code using the pairing doesn't have to guess it from field names
+    /// or order.
+    pub fn length_data_pairs(&self) -> Result<IndexMap<u16, u16>, Vec<LengthDataError>> {
+        let (pairs, errors) = self.pair_length_data();
+        if errors.is_empty() {
+            Ok(pairs)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Pair Data fields with Length fields, collecting all issues.
+    pub(crate) fn pair_length_data(&self) -> (IndexMap<u16, u16>, Vec<LengthDataError>) {
+        let mut pairs = IndexMap::new();
+        let mut errors = Vec::new();
+
+        let mut components: Vec<&Component> = self.header.iter().collect();
+        components.extend(&self.trailer);
+        components.extend(&self.components);
+        let mut messages: Vec<_> = self.messages.values().collect();
+        messages.sort_by(|a, b| a.name().cmp(b.name()));
+        let member_lists = components
+            .iter()
+            .map(|component| (component.name(), component.members()))
+            .chain(
+                messages
+                    .iter()
+                    .map(|message| (message.name(), message.members())),
+            );
+
+        for (owner, members) in member_lists {
+            let mut previous: Option<&Field> = None;
+            for member in members {
+                let field = match member.kind() {
+                    // Undefined fields are not paired, they are reported
+                    // as errors anyway
+                    MemberKind::Field => self.fields_by_name.get(member.name()),
+                    MemberKind::Component => None,
+                };
+                if let Some(data) = field
+                    .filter(|field| matches!(field.type_(), BasicType::Data | BasicType::XmlData))
+                {
+                    match previous.filter(|field| field.type_() == BasicType::Length) {
+                        Some(length) => {
+                            let conflict = pairs.iter().any(|(data_tag, length_tag)| {
+                                (*data_tag == data.number()) != (*length_tag == length.number())
+                            });
+                            if conflict {
+                                errors.push(LengthDataError::Ambiguous {
+                                    length: length.name().to_owned(),
+                                    data: data.name().to_owned(),
+                                    owner: owner.to_owned(),
+                                    location: member.location().clone(),
+                                });
+                            } else {
+                                pairs.insert(data.number(), length.number());
+                            }
+                        }
+                        None => errors.push(LengthDataError::Orphan {
+                            data: data.name().to_owned(),
+                            owner: owner.to_owned(),
+                            location: member.location().clone(),
+                        }),
+                    }
+                }
+                previous = field;
+            }
+        }
+
+        (pairs, errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LengthDataError;
+    use crate::Dictionary;
+
+    const FIX_XML: &str = r#"<fix type='FIX' major='4' minor='4' servicepack='0'>
+ <header>
+  <field name='MsgType' required='Y'/>
+  <field name='SecureDataLen' required='N'/>
+  <field name='SecureData' required='N'/>
+ </header>
+ <trailer>
+  <field name='CheckSum' required='Y'/>
+ </trailer>
+ <messages>
+  <message name='Heartbeat' msgtype='0' msgcat='admin'/>
+  <message name='News' msgtype='B' msgcat='app'>
+   <field name='RawDataLength' required='N'/>
+   <field name='RawData' required='N'/>
+   <field name='EncodedTextLen' required='N'/>
+   <field name='Text' required='N'/>
+   <field name='EncodedText' required='N'/>
+  </message>
+  <message name='Email' msgtype='C' msgcat='app'>
+   <field name='EncodedTextLen' required='N'/>
+   <field name='RawData' required='N'/>
+  </message>
+ </messages>
+ <components/>
+ <fields>
+  <field number='10' name='CheckSum' type='STRING'/>
+  <field number='35' name='MsgType' type='STRING'>
+   <value enum='0' description='HEARTBEAT'/>
+   <value enum='B' description='NEWS'/>
+   <value enum='C' description='EMAIL'/>
+  </field>
+  <field number='58' name='Text' type='STRING'/>
+  <field number='90' name='SecureDataLen' type='LENGTH'/>
+  <field number='91' name='SecureData' type='DATA'/>
+  <field number='95' name='RawDataLength' type='LENGTH'/>
+  <field number='96' name='RawData' type='DATA'/>
+  <field number='354' name='EncodedTextLen' type='LENGTH'/>
+  <field number='355' name='EncodedText' type='DATA'/>
+ </fields>
+</fix>"#;
+
+    #[test]
+    fn orphan_and_ambiguous_data_fields_reported() {
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(FIX_XML).unwrap();
+
+        let errors = dictionary.length_data_pairs().unwrap_err();
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert!(matches!(
+            &errors[0],
+            LengthDataError::Ambiguous { length, data, owner, .. }
+                if length == "RawDataLength" && data == "RawData" && owner == "News"
+        ));
+        assert!(matches!(
+            &errors[1],
+            LengthDataError::Orphan { data, owner, .. }
+                if data == "EncodedText" && owner == "News"
+        ));
+        assert_eq!(errors[1].location().line(), 17);
+        assert_eq!(
+            errors[1].to_string(),
+            "Data field `EncodedText` of `News` is not preceded by Length field \
+             at 17:4 (/fix/messages/message[@name='News']/field[@name='EncodedText'])"
+        );
+    }
+
+    #[test]
+    fn data_fields_paired() {
+        let xml = FIX_XML
+            .replace("<field name='Text' required='N'/>", "")
+            .replace(
+                "<field name='EncodedTextLen' required='N'/>\n   <field name='RawData'",
+                "<field name='RawDataLength' required='N'/>\n   <field name='RawData'",
+            );
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(&xml).unwrap();
+
+        let pairs = dictionary.length_data_pairs().unwrap();
+        assert_eq!(
+            pairs.into_iter().collect::<Vec<_>>(),
+            [(91, 90), (96, 95), (355, 354)]
+        );
+    }
+}
@@ -1,4 +1,14 @@
-#![feature(type_alias_impl_trait)]
+mod admin;
+mod diagnostics;
+mod fingerprint;
+mod lazy;
+mod length_data;
+#[cfg(all(feature = "mmap", unix))]
+mod mmap;
+mod registry;
+mod reloadable;
+mod stats;
+mod xml;
 
 use std::{
     collections::HashMap,
@@ -9,36 +19,21 @@ use std::{
 };
 
 use anyhow::{anyhow, bail, Context as ErrorContext, Result};
+pub use indexmap::IndexMap;
 use strum_macros::AsRefStr;
-use xmltree::{Element, XMLNode};
 
-type ElementIterator<'a> = impl Iterator<Item = &'a Element>;
-
-trait XmlHelper {
-    fn get_attribute(&self, attribute: &str) -> Result<&str>;
-    fn get_child_element(&self, child: &str) -> Result<&Element>;
-    fn get_child_elements(&self) -> ElementIterator;
-}
-
-impl XmlHelper for Element {
-    fn get_attribute(&self, attribute: &str) -> Result<&str> {
-        self.attributes
-            .get(attribute)
-            .map(String::as_ref)
-            .ok_or_else(|| anyhow!("no `{}` attribute in `{}` element", attribute, self.name))
-    }
-
-    fn get_child_element(&self, child: &str) -> Result<&Element> {
-        self.get_child(child)
-            .ok_or_else(|| anyhow!("no `{}` child in `{}` element", child, self.name))
-    }
-
-    fn get_child_elements(&self) -> ElementIterator {
-        self.children.iter().filter_map(XMLNode::as_element)
-    }
-}
+use self::xml::Element;
+pub use self::{
+    diagnostics::{Warning, WarningKind},
+    lazy::LazyDictionary,
+    length_data::LengthDataError,
+    registry::DictionaryRegistry,
+    reloadable::{DictionaryUpdate, ReloadableDictionary},
+    stats::DictionaryStats,
+    xml::Location,
+};
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Version {
     major: u32,
     minor: u32,
@@ -74,6 +69,22 @@ impl Version {
     pub fn service_pack(&self) -> u32 {
         self.service_pack
     }
+
+    /// ApplVerID<1128> value of this FIX application version, `None` for
+    /// versions without one (e.g. FIXT session protocol versions).
+    pub fn appl_ver_id(&self) -> Option<&'static str> {
+        match (self.major, self.minor, self.service_pack) {
+            (4, 0, 0) => Some("2"),
+            (4, 1, 0) => Some("3"),
+            (4, 2, 0) => Some("4"),
+            (4, 3, 0) => Some("5"),
+            (4, 4, 0) => Some("6"),
+            (5, 0, 0) => Some("7"),
+            (5, 0, 1) => Some("8"),
+            (5, 0, 2) => Some("9"),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for Version {
@@ -97,24 +108,31 @@ pub struct Member {
     name: String,
     required: bool,
     kind: MemberKind,
+    location: Location,
 }
 
 impl Member {
     fn from_xml(element: &Element) -> Result<Member> {
         let name = element.get_attribute("name")?;
         if !name.is_ascii() {
-            bail!("Non ASCII characters in member name: {}", name);
+            bail!(
+                "Non ASCII characters in member name: {} at {}",
+                name,
+                element.location
+            );
         }
-        let required = deserialize_yes_no(element.get_attribute("required")?)?;
+        let required = deserialize_yes_no(element.get_attribute("required")?)
+            .with_context(|| format!("Invalid `required` attribute at {}", element.location))?;
         let kind = match element.name.as_ref() {
             "field" => MemberKind::Field,
             "component" | "group" => MemberKind::Component,
-            name => bail!("Unexpected member kind `{}`", name),
+            name => bail!("Unexpected member kind `{}` at {}", name, element.location),
         };
         Ok(Member {
             name: name.into(),
             required,
             kind,
+            location: element.location.clone(),
         })
     }
 
@@ -129,6 +147,62 @@ impl Member {
     pub fn kind(&self) -> MemberKind {
         self.kind
     }
+
+    /// Location of member definition in XML source.
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
+}
+
+/// Name of component synthesized for `<group>` defined inline,
+/// e.g. `NoLegs` group of `NewOrderMultileg` message becomes
+/// `NewOrderMultilegLegGrp` component.
+fn inline_group_name(owner: &str, group_name: &str) -> String {
+    let name = group_name.strip_prefix("No").unwrap_or(group_name);
+    let name = name.strip_suffix('s').unwrap_or(name);
+    format!("{owner}{name}Grp")
+}
+
+/// Process members of message, component or group.
+///
+/// Groups defined inline are converted to components named with
+/// `owner` prefix (see `inline_group_name()`) and appended to `groups`,
+/// so they are handled the same way as groups wrapped in components.
+fn members_from_xml<'a>(
+    elements: impl Iterator<Item = &'a Element>,
+    owner: &str,
+    groups: &mut Vec<Component>,
+) -> Result<Vec<Member>> {
+    let mut members = Vec::new();
+    for element in elements {
+        if element.name == "group" {
+            let number_of_elements = Member::from_xml(element)?;
+            let name = inline_group_name(owner, number_of_elements.name());
+            let group_members = members_from_xml(element.get_child_elements(), &name, groups)?;
+            if group_members.is_empty() {
+                bail!(
+                    "Empty member list in `{}` group at {}",
+                    number_of_elements.name(),
+                    element.location
+                );
+            }
+            members.push(Member {
+                name: name.clone(),
+                required: number_of_elements.required(),
+                kind: MemberKind::Component,
+                location: element.location.clone(),
+            });
+            groups.push(Component {
+                name,
+                number_of_elements: Some(number_of_elements),
+                members: group_members,
+                location: element.location.clone(),
+            });
+        } else {
+            members.push(Member::from_xml(element)?);
+        }
+    }
+    Ok(members)
 }
 
 #[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
@@ -212,28 +286,43 @@ impl FromStr for BasicType {
 #[derive(Clone, Debug, PartialEq)]
 pub struct Value {
     value: String,
-    description: String,
+    description: Option<String>,
+    location: Location,
 }
 
 impl Value {
     fn from_xml(element: &Element) -> Result<Value> {
         if element.name != "value" {
-            bail!("Expected `value` node, found `{}`", element.name);
+            bail!(
+                "Expected `value` node, found `{}` at {}",
+                element.name,
+                element.location
+            );
         }
 
         let value = element.get_attribute("enum")?;
         if !value.is_ascii() {
-            bail!("Non ASCII characters in enum value: {}", value);
+            bail!(
+                "Non ASCII characters in enum value: {} at {}",
+                value,
+                element.location
+            );
         }
 
-        let description = element.get_attribute("description")?;
-        if !description.is_ascii() {
-            bail!("Non ASCII characters in enum description: {}", description);
+        // Missing description is reported by `Dictionary::diagnostics()`
+        let description = element.attributes.get("description");
+        if let Some(description) = description.filter(|description| !description.is_ascii()) {
+            bail!(
+                "Non ASCII characters in enum description: {} at {}",
+                description,
+                element.location
+            );
         }
 
         Ok(Value {
             value: value.into(),
-            description: description.into(),
+            description: description.cloned(),
+            location: element.location.clone(),
         })
     }
 
@@ -241,8 +330,14 @@ impl Value {
         &self.value
     }
 
+    /// Value description, or the value itself when description is missing.
     pub fn description(&self) -> &str {
-        &self.description
+        self.description.as_deref().unwrap_or(&self.value)
+    }
+
+    /// Location of value definition in XML source.
+    pub fn location(&self) -> &Location {
+        &self.location
     }
 }
 
@@ -252,6 +347,7 @@ pub struct Field {
     number: u16,
     type_: BasicType,
     values: Option<Vec<Value>>,
+    location: Location,
 }
 
 impl Field {
@@ -262,17 +358,28 @@ impl Field {
             .collect::<Result<Vec<_>, _>>()?;
         let name = element.get_attribute("name")?;
         if !name.is_ascii() {
-            bail!("Non ASCII characters in field name: {}", name);
+            bail!(
+                "Non ASCII characters in field name: {} at {}",
+                name,
+                element.location
+            );
         }
         Ok(Field {
             name: name.into(),
-            number: element.get_attribute("number")?.parse()?,
-            type_: element.get_attribute("type")?.parse()?,
+            number: element
+                .get_attribute("number")?
+                .parse()
+                .with_context(|| format!("Invalid `number` attribute at {}", element.location))?,
+            type_: element
+                .get_attribute("type")?
+                .parse()
+                .with_context(|| format!("Invalid `type` attribute at {}", element.location))?,
             values: if values.is_empty() {
                 None
             } else {
                 Some(values)
             },
+            location: element.location.clone(),
         })
     }
 
@@ -291,6 +398,11 @@ impl Field {
     pub fn values(&self) -> Option<&[Value]> {
         self.values.as_deref()
     }
+
+    /// Location of field definition in XML source.
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
 }
 
 fn deserialize_yes_no(input: &str) -> Result<bool> {
@@ -309,23 +421,35 @@ pub struct Component {
     name: String,
     number_of_elements: Option<Member>,
     members: Vec<Member>,
+    location: Location,
 }
 
 impl Component {
-    fn from_xml(element: &Element) -> Result<Component> {
+    /// Returns component and components synthesized from groups defined
+    /// inline in its members.
+    fn from_xml(element: &Element) -> Result<(Component, Vec<Component>)> {
         if element.name != "component" {
-            bail!("Expected `component` node, found `{}`", element.name);
+            bail!(
+                "Expected `component` node, found `{}` at {}",
+                element.name,
+                element.location
+            );
         }
 
         let name = element.get_attribute("name")?.to_owned();
         if !name.is_ascii() {
-            bail!("Non ASCII characters in component name: {}", name);
+            bail!(
+                "Non ASCII characters in component name: {} at {}",
+                name,
+                element.location
+            );
         }
 
         let mut iter = element.get_child_elements().peekable();
 
+        // Component with a group as the only member is the group itself
         let number_of_elements = if let Some(child) = iter.peek() {
-            if child.name == "group" {
+            if child.name == "group" && element.get_child_elements().count() == 1 {
                 let member = Member::from_xml(child)?;
                 iter = child.get_child_elements().peekable();
                 Some(member)
@@ -333,16 +457,25 @@ impl Component {
                 None
             }
         } else {
-            bail!("Empty member list in `{}` component", name)
+            bail!(
+                "Empty member list in `{}` component at {}",
+                name,
+                element.location
+            )
         };
 
-        let members = iter.map(Member::from_xml).collect::<Result<Vec<_>, _>>()?;
+        let mut groups = Vec::new();
+        let members = members_from_xml(iter, &name, &mut groups)?;
 
-        Ok(Component {
-            name,
-            number_of_elements,
-            members,
-        })
+        Ok((
+            Component {
+                name,
+                number_of_elements,
+                members,
+                location: element.location.clone(),
+            },
+            groups,
+        ))
     }
 
     // All groups are defined as separate component with one member - the group itself.
@@ -351,44 +484,23 @@ impl Component {
         let name = match element.name.as_str() {
             "header" => "Header".to_owned(),
             "trailer" => "Trailer".to_owned(),
-            unexpected => bail!("Expected `header/trailer` node, found `{}`", unexpected),
+            unexpected => bail!(
+                "Expected `header/trailer` node, found `{}` at {}",
+                unexpected,
+                element.location
+            ),
         };
 
+        // Header groups are named without prefix, e.g. `HopGrp`
         let mut groups = Vec::new();
-        let mut members = Vec::new();
-        for member_element in element.get_child_elements() {
-            if member_element.name == "group" {
-                let group_name = member_element.get_attribute("name")?;
-                let group_name = if group_name.starts_with("No") && group_name.ends_with('s') {
-                    format!("{}Grp", &group_name[2..group_name.len() - 1])
-                } else {
-                    bail!("Malformed group name `{}`", group_name);
-                };
-                let number_of_elements = Some(Member::from_xml(member_element)?);
-                let group_members = member_element
-                    .get_child_elements()
-                    .map(Member::from_xml)
-                    .collect::<Result<Vec<_>, _>>()?;
-                groups.push(Component {
-                    name: group_name.clone(),
-                    number_of_elements,
-                    members: group_members,
-                });
-                let mut member_element = member_element.clone();
-                if let Some(name) = member_element.attributes.get_mut("name") {
-                    *name = group_name;
-                }
-                members.push(Member::from_xml(&member_element)?);
-            } else {
-                members.push(Member::from_xml(member_element)?);
-            }
-        }
+        let members = members_from_xml(element.get_child_elements(), "", &mut groups)?;
 
         Ok((
             Component {
                 name,
                 number_of_elements: None,
                 members,
+                location: element.location.clone(),
             },
             groups,
         ))
@@ -405,6 +517,11 @@ impl Component {
     pub fn members(&self) -> &[Member] {
         &self.members
     }
+
+    /// Location of component definition in XML source.
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -473,32 +590,72 @@ pub struct Message {
     msg_cat: MsgCat,
     msg_type: MsgType,
     members: Vec<Member>,
+    header_overrides: Vec<Member>,
+    location: Location,
 }
 
 impl Message {
-    fn from_xml(element: &Element) -> Result<Message> {
+    /// Returns message and components synthesized from groups defined
+    /// inline in its members.
+    fn from_xml(element: &Element) -> Result<(Message, Vec<Component>)> {
         if element.name != "message" {
-            bail!("Expected `message` node, found `{}`", element.name);
+            bail!(
+                "Expected `message` node, found `{}` at {}",
+                element.name,
+                element.location
+            );
         }
 
         let name = element.get_attribute("name")?.to_owned();
         if !name.is_ascii() {
-            bail!("Non ASCII characters in message name: {}", name);
+            bail!(
+                "Non ASCII characters in message name: {} at {}",
+                name,
+                element.location
+            );
         }
-        let msg_cat = element.get_attribute("msgcat")?.parse()?;
-        let msg_type = element.get_attribute("msgtype")?.parse()?;
+        let msg_cat = element
+            .get_attribute("msgcat")?
+            .parse()
+            .with_context(|| format!("Invalid `msgcat` attribute at {}", element.location))?;
+        let msg_type = element
+            .get_attribute("msgtype")?
+            .parse()
+            .with_context(|| format!("Invalid `msgtype` attribute at {}", element.location))?;
 
-        let members = element
-            .get_child_elements()
-            .map(Member::from_xml)
-            .collect::<Result<Vec<_>, _>>()?;
+        let mut groups = Vec::new();
+        let members = members_from_xml(
+            element.get_child_elements().filter(|e| e.name != "header"),
+            &name,
+            &mut groups,
+        )?;
 
-        Ok(Message {
-            name,
-            msg_cat,
-            msg_type,
-            members,
-        })
+        let mut header_overrides = Vec::new();
+        for header in element.get_child_elements().filter(|e| e.name == "header") {
+            for element in header.get_child_elements() {
+                let member = Member::from_xml(element)?;
+                if member.kind != MemberKind::Field {
+                    bail!(
+                        "Header override of `{}` at {} is not a field",
+                        member.name,
+                        member.location
+                    );
+                }
+                header_overrides.push(member);
+            }
+        }
+
+        Ok((
+            Message {
+                name,
+                msg_cat,
+                msg_type,
+                members,
+                header_overrides,
+                location: element.location.clone(),
+            },
+            groups,
+        ))
     }
 
     pub fn name(&self) -> &str {
@@ -516,6 +673,30 @@ impl Message {
     pub fn members(&self) -> &[Member] {
         &self.members
     }
+
+    /// Header fields required (or not) by this message regardless of
+    /// header definition, set with `header` node of `message` node, e.g.
+    /// `<header><field name='OnBehalfOfCompID' required='Y'/></header>`.
+    pub fn header_overrides(&self) -> &[Member] {
+        &self.header_overrides
+    }
+
+    /// Whether header field `name` is required in this message, taking
+    /// [`header_overrides`](Message::header_overrides) into account.
+    pub fn header_field_required(&self, header: &Component, name: &str) -> bool {
+        match self.header_overrides.iter().find(|o| o.name == name) {
+            Some(header_override) => header_override.required,
+            None => header
+                .members
+                .iter()
+                .any(|member| member.name == name && member.required),
+        }
+    }
+
+    /// Location of message definition in XML source.
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -524,12 +705,12 @@ pub struct Dictionary {
     fixt_version: Option<Version>,
     header: Option<Component>,
     trailer: Option<Component>,
-    messages: HashMap<MsgType, Message>,
+    messages: IndexMap<MsgType, Message>,
     flat_messages: HashMap<String, Message>,
     components: Vec<Component>,
     components_by_name: HashMap<String, Component>,
-    fields: HashMap<u16, Field>,
-    fields_by_name: HashMap<String, Field>,
+    fields: IndexMap<u16, Field>,
+    fields_by_name: IndexMap<String, Field>,
     reject_reason_overrides: HashMap<ParseRejectReason, String>,
 }
 
@@ -548,12 +729,12 @@ impl Dictionary {
             fix_version: None,
             header: None,
             trailer: None,
-            messages: HashMap::new(),
+            messages: IndexMap::new(),
             flat_messages: HashMap::new(),
             components: Vec::new(),
             components_by_name: HashMap::new(),
-            fields: HashMap::new(),
-            fields_by_name: HashMap::new(),
+            fields: IndexMap::new(),
+            fields_by_name: IndexMap::new(),
             reject_reason_overrides: optional_reject_reason_overrides.unwrap_or_default(),
         }
     }
@@ -637,26 +818,17 @@ impl Dictionary {
     }
 
     fn process_common(&mut self, root: &Element) -> Result<()> {
-        self.messages.extend(
-            root.get_child_element("messages")?
-                .get_child_elements()
-                .map(Message::from_xml)
-                .collect::<Result<Vec<_>, _>>()?
-                .into_iter()
-                .map(|m| (m.msg_type, m)),
-        );
+        for element in root.get_child_element("messages")?.get_child_elements() {
+            let (message, groups) = Message::from_xml(element)?;
+            self.messages.insert(message.msg_type, message);
+            self.components.extend(groups);
+        }
 
-        self.components.extend(
-            root.get_child_element("components")?
-                .get_child_elements()
-                .map(Component::from_xml)
-                .collect::<Result<Vec<_>>>()?,
-        );
-        self.components_by_name.extend(
-            self.components
-                .iter()
-                .map(|c| (c.name().to_owned(), c.clone())),
-        );
+        for element in root.get_child_element("components")?.get_child_elements() {
+            let (component, groups) = Component::from_xml(element)?;
+            self.components.push(component);
+            self.components.extend(groups);
+        }
 
         self.fields.extend(
             root.get_child_element("fields")?
@@ -667,6 +839,199 @@ impl Dictionary {
                 .map(|f| (f.number, f)),
         );
 
+        self.index_sections();
+        self.check_header_overrides()
+    }
+
+    /// Verify that header overrides of messages refer to fields defined
+    /// directly in header, if header is already known.
+    fn check_header_overrides(&self) -> Result<()> {
+        let Some(header) = &self.header else {
+            return Ok(());
+        };
+        for message in self.messages.values() {
+            for header_override in &message.header_overrides {
+                let defined = header.members.iter().any(|member| {
+                    member.kind == MemberKind::Field && member.name == header_override.name
+                });
+                if !defined {
+                    bail!(
+                        "Header override `{}` of `{}` message at {} is not a header field",
+                        header_override.name,
+                        message.name,
+                        header_override.location
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Process FIX XML description split across multiple documents, e.g.
+    /// fields in one file and messages in another.
+    ///
+    /// Every part has `fix` root of type `FIX` and any subset of
+    /// `messages`, `components` and `fields` nodes. Version attributes
+    /// have to be set in at least one part, parts setting them have to
+    /// agree. Sections are merged first and validated once all parts
+    /// are loaded, so messages may refer to fields and components defined
+    /// in other parts. Fields, components and messages defined in more
+    /// than one part are rejected.
+    pub fn process_fix_xml_parts(&mut self, xmls: &[&str]) -> Result<()> {
+        if self.fix_version.is_some() {
+            bail!("FIX XML already processed");
+        }
+
+        let mut version = None;
+        let mut messages: IndexMap<MsgType, Message> = IndexMap::new();
+        let mut components: Vec<Component> = Vec::new();
+        let mut component_names: HashMap<String, usize> = HashMap::new();
+        let mut fields: IndexMap<u16, Field> = IndexMap::new();
+        let mut field_names: HashMap<String, u16> = HashMap::new();
+        let (mut has_messages, mut has_fields) = (false, false);
+
+        for (idx, xml) in xmls.iter().enumerate() {
+            let root = Element::parse(xml.as_bytes())
+                .with_context(|| format!("Failed to parse FIX description part #{idx}"))?;
+            let type_ = root.get_attribute("type")?;
+            if type_ != "FIX" {
+                bail!("Unexpected FIX XML description type `{}`", type_);
+            }
+            if root.get_attribute("major").is_ok() {
+                let part_version = Version::from_xml(&root)?;
+                match &version {
+                    Some(version) if *version != part_version => bail!(
+                        "FIX version {} of part #{idx} doesn't match version {}",
+                        part_version,
+                        version
+                    ),
+                    Some(_) => {}
+                    None => version = Some(part_version),
+                }
+            }
+            let optional_child = |name| root.get_child_elements().find(|e| e.name == name);
+
+            if let Some(section) = optional_child("fields") {
+                has_fields = true;
+                for element in section.get_child_elements() {
+                    let field = Field::from_xml(element)?;
+                    if let Some(defined) = fields
+                        .get(&field.number)
+                        .or_else(|| field_names.get(&field.name).map(|tag| &fields[tag]))
+                    {
+                        bail!(
+                            "Field `{}` ({}) at {} already defined at {}",
+                            field.name,
+                            field.number,
+                            field.location,
+                            defined.location
+                        );
+                    }
+                    field_names.insert(field.name.clone(), field.number);
+                    fields.insert(field.number, field);
+                }
+            }
+
+            if let Some(section) = optional_child("components") {
+                for element in section.get_child_elements() {
+                    let (component, groups) = Component::from_xml(element)?;
+                    for component in std::iter::once(component).chain(groups) {
+                        if let Some(idx) = component_names.get(&component.name) {
+                            let defined = &components[*idx];
+                            bail!(
+                                "Component `{}` at {} already defined at {}",
+                                component.name,
+                                component.location,
+                                defined.location
+                            );
+                        }
+                        component_names.insert(component.name.clone(), components.len());
+                        components.push(component);
+                    }
+                }
+            }
+
+            if let Some(section) = optional_child("messages") {
+                has_messages = true;
+                for element in section.get_child_elements() {
+                    let (message, groups) = Message::from_xml(element)?;
+                    if let Some(defined) = messages.get(&message.msg_type) {
+                        bail!(
+                            "MsgType `{}` of `{}` message at {} already used by `{}` message",
+                            String::from_utf8_lossy(&message.msg_type),
+                            message.name,
+                            message.location,
+                            defined.name
+                        );
+                    }
+                    for group in groups {
+                        component_names.insert(group.name.clone(), components.len());
+                        components.push(group);
+                    }
+                    messages.insert(message.msg_type, message);
+                }
+            }
+        }
+
+        let Some(version) = version else {
+            bail!("FIX version not set in any FIX description part");
+        };
+        if !has_messages {
+            bail!("`messages` not defined in any FIX description part");
+        }
+        if !has_fields {
+            bail!("`fields` not defined in any FIX description part");
+        }
+
+        // Members may refer to fields and components of other parts, or
+        // of already processed FIXT XML
+        let members = messages
+            .values()
+            .flat_map(Message::members)
+            .chain(components.iter().flat_map(Component::members));
+        for member in members {
+            let defined = match member.kind() {
+                MemberKind::Field => {
+                    field_names.contains_key(member.name())
+                        || self.fields_by_name.contains_key(member.name())
+                }
+                MemberKind::Component => {
+                    component_names.contains_key(member.name())
+                        || self.components_by_name.contains_key(member.name())
+                }
+            };
+            if !defined {
+                bail!(
+                    "{:?} `{}` at {} not defined",
+                    member.kind(),
+                    member.name(),
+                    member.location()
+                );
+            }
+        }
+        match fields.get(&35).or_else(|| self.fields.get(&35)) {
+            Some(field) if field.values.is_some() => {}
+            Some(_) => bail!("MsgType enum fields not defined"),
+            None => bail!("MsgType field not defined"),
+        }
+
+        self.fix_version = Some(version);
+        self.messages.extend(messages);
+        self.components.extend(components);
+        self.fields.extend(fields);
+        self.index_sections();
+        self.check_header_overrides()
+    }
+
+    /// Index components and fields by name and drop MsgType<35> values
+    /// of undefined messages.
+    fn index_sections(&mut self) {
+        self.components_by_name.extend(
+            self.components
+                .iter()
+                .map(|c| (c.name().to_owned(), c.clone())),
+        );
+
         // XXX: Drop MsgType values which does not match Messages list
         let msg_type_field = self.fields.get_mut(&35).expect("MsgType field not defined");
         msg_type_field.values = Some(
@@ -688,8 +1053,201 @@ impl Dictionary {
                 .values()
                 .map(|f| (f.name().to_owned(), f.to_owned())),
         );
+    }
 
-        Ok(())
+    /// Add user-defined messages (e.g. venue specific `U1`), on top of
+    /// already processed FIX XML.
+    ///
+    /// XML root may have any name, it has to contain `messages` node
+    /// and optionally `components` and `fields` nodes, all in the same
+    /// format as in FIX XML. Fields and components of processed
+    /// dictionaries may be used by custom messages. MsgType<35> values
+    /// are added for every custom message, with message name used as
+    /// value description.
+    pub fn process_custom_messages_xml(&mut self, xml: &str) -> Result<()> {
+        let root = Element::parse(xml.as_bytes())
+            .context("Failed to parse custom messages description")?;
+        let optional_child = |name| root.get_child_elements().find(|e| e.name == name);
+
+        if let Some(fields) = optional_child("fields") {
+            for element in fields.get_child_elements() {
+                let field = Field::from_xml(element)?;
+                if self.fields.contains_key(&field.number)
+                    || self.fields_by_name.contains_key(&field.name)
+                {
+                    bail!(
+                        "Field `{}` ({}) at {} already defined",
+                        field.name,
+                        field.number,
+                        field.location
+                    );
+                }
+                self.fields_by_name
+                    .insert(field.name.clone(), field.clone());
+                self.fields.insert(field.number, field);
+            }
+        }
+
+        if let Some(components) = optional_child("components") {
+            for element in components.get_child_elements() {
+                let (component, groups) = Component::from_xml(element)?;
+                for component in std::iter::once(component).chain(groups) {
+                    if self.components_by_name.contains_key(component.name()) {
+                        bail!(
+                            "Component `{}` at {} already defined",
+                            component.name,
+                            component.location
+                        );
+                    }
+                    self.components_by_name
+                        .insert(component.name.clone(), component.clone());
+                    self.components.push(component);
+                }
+            }
+        }
+
+        let mut msg_type_values = Vec::new();
+        for element in root.get_child_element("messages")?.get_child_elements() {
+            let (message, groups) = Message::from_xml(element)?;
+            if let Some(defined) = self.messages.get(&message.msg_type) {
+                bail!(
+                    "MsgType `{}` of `{}` message at {} already used by `{}` message",
+                    String::from_utf8_lossy(&message.msg_type),
+                    message.name,
+                    message.location,
+                    defined.name
+                );
+            }
+            msg_type_values.push(Value {
+                value: String::from_utf8_lossy(&message.msg_type).into_owned(),
+                description: Some(message.name.clone()),
+                location: message.location.clone(),
+            });
+            self.messages.insert(message.msg_type, message);
+            self.components_by_name
+                .extend(groups.iter().map(|c| (c.name().to_owned(), c.clone())));
+            self.components.extend(groups);
+        }
+
+        let msg_type_field = self
+            .fields
+            .get_mut(&35)
+            .ok_or_else(|| anyhow!("MsgType field not defined"))?;
+        msg_type_field
+            .values
+            .get_or_insert_with(Vec::new)
+            .extend(msg_type_values);
+        self.fields_by_name
+            .insert(msg_type_field.name.clone(), msg_type_field.clone());
+
+        self.check_header_overrides()
+    }
+
+    /// Add standard admin messages (Heartbeat, TestRequest, ResendRequest,
+    /// Reject, SequenceReset, Logout and Logon) of processed FIX version,
+    /// which are missing in dictionary, e.g. venue FIX 4.2 XML describing
+    /// application messages only.
+    ///
+    /// Messages already defined are left untouched. Fields used by added
+    /// messages and not defined in dictionary are added in their FIX 4.4
+    /// form. MsgType<35> values are added for every added message, with
+    /// message name used as value description.
+    ///
+    /// Supported for FIX 4.2, 4.3 and 4.4 dictionaries (not FIXT ones,
+    /// FIXT XML always defines admin messages). Returns MsgTypes of added
+    /// messages.
+    pub fn add_missing_admin_messages(&mut self) -> Result<Vec<MsgType>> {
+        if self.fixt_version.is_some() {
+            bail!("Admin messages of FIXT dictionary are defined in FIXT XML");
+        }
+        let Some(version) = &self.fix_version else {
+            bail!("FIX XML not processed");
+        };
+        let Some(messages_xml) = admin::messages(version) else {
+            bail!("Standard admin messages of FIX {version} not available");
+        };
+
+        let root = Element::parse(messages_xml.as_bytes())?;
+        let mut messages = Vec::new();
+        let mut groups = Vec::new();
+        for element in root.get_child_element("messages")?.get_child_elements() {
+            let (message, message_groups) = Message::from_xml(element)?;
+            if !self.messages.contains_key(&message.msg_type) {
+                messages.push(message);
+                groups.extend(message_groups);
+            }
+        }
+
+        let root = Element::parse(admin::FIELDS.as_bytes())?;
+        let admin_fields = root
+            .get_child_element("fields")?
+            .get_child_elements()
+            .map(Field::from_xml)
+            .collect::<Result<Vec<_>, _>>()?;
+        let used_fields = messages
+            .iter()
+            .flat_map(Message::members)
+            .chain(groups.iter().flat_map(|group| {
+                group
+                    .members()
+                    .iter()
+                    .chain(group.number_of_elements.as_ref())
+            }))
+            .filter(|member| member.kind == MemberKind::Field);
+        for member in used_fields {
+            if self.fields_by_name.contains_key(&member.name) {
+                continue;
+            }
+            let field = admin_fields
+                .iter()
+                .find(|field| field.name == member.name)
+                .expect("admin message field not defined");
+            if let Some(defined) = self.fields.get(&field.number) {
+                bail!(
+                    "Field `{}` ({}) of admin messages already defined as `{}` at {}",
+                    field.name,
+                    field.number,
+                    defined.name,
+                    defined.location
+                );
+            }
+            self.fields_by_name
+                .insert(field.name.clone(), field.clone());
+            self.fields.insert(field.number, field.clone());
+        }
+
+        for group in groups {
+            if let Some(defined) = self.components_by_name.get(&group.name) {
+                bail!(
+                    "Component `{}` of admin messages already defined at {}",
+                    group.name,
+                    defined.location
+                );
+            }
+            self.components_by_name
+                .insert(group.name.clone(), group.clone());
+            self.components.push(group);
+        }
+
+        let msg_type_field = self
+            .fields
+            .get_mut(&35)
+            .ok_or_else(|| anyhow!("MsgType field not defined"))?;
+        let msg_type_values = msg_type_field.values.get_or_insert_with(Vec::new);
+        let mut added = Vec::new();
+        for message in messages {
+            msg_type_values.push(Value {
+                value: String::from_utf8_lossy(&message.msg_type).into_owned(),
+                description: Some(message.name.clone()),
+                location: message.location.clone(),
+            });
+            added.push(message.msg_type);
+            self.messages.insert(message.msg_type, message);
+        }
+        self.fields_by_name
+            .insert(msg_type_field.name.clone(), msg_type_field.clone());
+
+        Ok(added)
     }
 
     pub fn fixt_version(&self) -> Option<&Version> {
@@ -700,6 +1258,24 @@ impl Dictionary {
         self.fix_version.as_ref()
     }
 
+    /// BeginString<8> of messages described by this dictionary, e.g.
+    /// `FIXT.1.1` when FIXT XML was processed, `FIX.4.4` otherwise.
+    pub fn begin_string(&self) -> Option<String> {
+        let (protocol, version) = match (&self.fixt_version, &self.fix_version) {
+            (Some(fixt_version), _) => ("FIXT", fixt_version),
+            (None, Some(fix_version)) => ("FIX", fix_version),
+            (None, None) => return None,
+        };
+        Some(if version.service_pack == 0 {
+            format!("{}.{}.{}", protocol, version.major, version.minor)
+        } else {
+            format!(
+                "{}.{}.{}SP{}",
+                protocol, version.major, version.minor, version.service_pack
+            )
+        })
+    }
+
     pub fn header(&self) -> Result<&Component> {
         self.header
             .as_ref()
@@ -712,6 +1288,8 @@ impl Dictionary {
             .ok_or_else(|| anyhow!("Missing trailer"))
     }
 
+    /// Components, in order of definition, including ones synthesized
+    /// from groups defined inline.
     pub fn components(&self) -> &[Component] {
         &self.components
     }
@@ -724,15 +1302,18 @@ impl Dictionary {
         self.messages.get(name)
     }
 
-    pub fn messages(&self) -> &HashMap<MsgType, Message> {
+    /// Messages, in order of definition.
+    pub fn messages(&self) -> &IndexMap<MsgType, Message> {
         &self.messages
     }
 
-    pub fn fields(&self) -> &HashMap<u16, Field> {
+    /// Fields, in order of definition.
+    pub fn fields(&self) -> &IndexMap<u16, Field> {
         &self.fields
     }
 
-    pub fn fields_by_name(&self) -> &HashMap<String, Field> {
+    /// Fields, in order of definition.
+    pub fn fields_by_name(&self) -> &IndexMap<String, Field> {
         &self.fields_by_name
     }
 
@@ -764,7 +1345,7 @@ mod tests {
 
     use std::str::FromStr;
 
-    use super::MsgType;
+    use super::{xml::Element, Dictionary, MemberKind, Message, MsgType, Value};
 
     #[test]
     fn parse_msg_type() {
@@ -777,4 +1358,420 @@ mod tests {
         assert!(MsgType::from_str("AAA").is_err());
         assert!(MsgType::from_str("\0A").is_err());
     }
+
+    #[test]
+    fn definition_order_preserved() {
+        let xml = "<fix type='FIX' major='4' minor='4' servicepack='0'>
+             <header><field name='MsgType' required='Y'/></header>
+             <trailer><field name='CheckSum' required='Y'/></trailer>
+             <messages>
+              <message name='TestRequest' msgtype='1' msgcat='admin'/>
+              <message name='Heartbeat' msgtype='0' msgcat='admin'/>
+              <message name='Logon' msgtype='A' msgcat='admin'/>
+             </messages>
+             <components/>
+             <fields>
+              <field number='112' name='TestReqID' type='STRING'/>
+              <field number='35' name='MsgType' type='STRING'>
+               <value enum='A' description='LOGON'/>
+               <value enum='0' description='HEARTBEAT'/>
+               <value enum='1' description='TEST_REQUEST'/>
+              </field>
+              <field number='10' name='CheckSum' type='STRING'/>
+             </fields>
+            </fix>";
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(xml).unwrap();
+
+        let messages: Vec<_> = dictionary.messages().values().map(Message::name).collect();
+        assert_eq!(messages, ["TestRequest", "Heartbeat", "Logon"]);
+        let fields: Vec<_> = dictionary.fields().keys().copied().collect();
+        assert_eq!(fields, [112, 35, 10]);
+        let fields: Vec<_> = dictionary.fields_by_name().keys().collect();
+        assert_eq!(fields, ["TestReqID", "MsgType", "CheckSum"]);
+        let values: Vec<_> = dictionary.fields()[&35]
+            .values()
+            .unwrap()
+            .iter()
+            .map(Value::value)
+            .collect();
+        assert_eq!(values, ["A", "0", "1"]);
+    }
+
+    #[test]
+    fn header_overrides() {
+        let xml = |override_name| {
+            format!(
+                "<fix type='FIX' major='4' minor='4' servicepack='0'>
+                 <header>
+                  <field name='MsgType' required='Y'/>
+                  <field name='OnBehalfOfCompID' required='N'/>
+                 </header>
+                 <trailer><field name='CheckSum' required='Y'/></trailer>
+                 <messages>
+                  <message name='Heartbeat' msgtype='0' msgcat='admin'/>
+                  <message name='Logon' msgtype='A' msgcat='admin'>
+                   <header><field name='{override_name}' required='Y'/></header>
+                   <field name='TestReqID' required='N'/>
+                  </message>
+                 </messages>
+                 <components/>
+                 <fields>
+                  <field number='112' name='TestReqID' type='STRING'/>
+                  <field number='115' name='OnBehalfOfCompID' type='STRING'/>
+                  <field number='35' name='MsgType' type='STRING'>
+                   <value enum='A' description='LOGON'/>
+                   <value enum='0' description='HEARTBEAT'/>
+                  </field>
+                  <field number='10' name='CheckSum' type='STRING'/>
+                 </fields>
+                </fix>"
+            )
+        };
+        let mut dictionary = Dictionary::new(None);
+        dictionary
+            .process_legacy_fix_xml(&xml("OnBehalfOfCompID"))
+            .unwrap();
+        let header = dictionary.header().unwrap();
+        let logon = dictionary
+            .message(&MsgType::from_str("A").unwrap())
+            .unwrap();
+        let heartbeat = dictionary
+            .message(&MsgType::from_str("0").unwrap())
+            .unwrap();
+
+        // Override is not a message member
+        assert_eq!(logon.members().len(), 1);
+        assert_eq!(logon.header_overrides().len(), 1);
+        assert!(logon.header_field_required(header, "OnBehalfOfCompID"));
+        assert!(!heartbeat.header_field_required(header, "OnBehalfOfCompID"));
+        assert!(heartbeat.header_field_required(header, "MsgType"));
+
+        let mut dictionary = Dictionary::new(None);
+        let err = dictionary
+            .process_legacy_fix_xml(&xml("TestReqID"))
+            .unwrap_err();
+        assert!(err.to_string().contains("is not a header field"), "{err}");
+    }
+
+    #[test]
+    fn custom_messages_merged() {
+        let xml = "<fix type='FIX' major='4' minor='4' servicepack='0'>
+             <header><field name='MsgType' required='Y'/></header>
+             <trailer><field name='CheckSum' required='Y'/></trailer>
+             <messages>
+              <message name='Heartbeat' msgtype='0' msgcat='admin'/>
+             </messages>
+             <components/>
+             <fields>
+              <field number='35' name='MsgType' type='STRING'>
+               <value enum='0' description='HEARTBEAT'/>
+              </field>
+              <field number='10' name='CheckSum' type='STRING'/>
+             </fields>
+            </fix>";
+        let custom_xml = "<custom>
+             <messages>
+              <message name='VenueStatus' msgtype='U1' msgcat='app'>
+               <field name='VenueStatusText' required='Y'/>
+              </message>
+             </messages>
+             <fields>
+              <field number='5001' name='VenueStatusText' type='STRING'/>
+             </fields>
+            </custom>";
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(xml).unwrap();
+        dictionary.process_custom_messages_xml(custom_xml).unwrap();
+
+        let messages: Vec<_> = dictionary.messages().values().map(Message::name).collect();
+        assert_eq!(messages, ["Heartbeat", "VenueStatus"]);
+        assert!(dictionary.fields_by_name().contains_key("VenueStatusText"));
+        let values: Vec<_> = dictionary.fields_by_name()["MsgType"]
+            .values()
+            .unwrap()
+            .iter()
+            .map(|value| (value.value(), value.description()))
+            .collect();
+        assert_eq!(values, [("0", "HEARTBEAT"), ("U1", "VenueStatus")]);
+
+        // MsgType can't be redefined
+        let error = dictionary
+            .process_custom_messages_xml(
+                custom_xml
+                    .replace("5001", "5002")
+                    .replace("VenueStatusText", "Other")
+                    .as_str(),
+            )
+            .unwrap_err();
+        assert!(error.to_string().contains("already used"), "{error}");
+    }
+
+    #[test]
+    fn missing_admin_messages_added() {
+        let xml = "<fix type='FIX' major='4' minor='2' servicepack='0'>
+             <header><field name='MsgType' required='Y'/></header>
+             <trailer><field name='CheckSum' required='Y'/></trailer>
+             <messages>
+              <message name='Heartbeat' msgtype='0' msgcat='admin'/>
+              <message name='News' msgtype='B' msgcat='app'>
+               <field name='Text' required='Y'/>
+              </message>
+             </messages>
+             <components/>
+             <fields>
+              <field number='35' name='MsgType' type='STRING'>
+               <value enum='0' description='HEARTBEAT'/>
+               <value enum='B' description='NEWS'/>
+              </field>
+              <field number='58' name='Text' type='STRING'/>
+              <field number='10' name='CheckSum' type='STRING'/>
+             </fields>
+            </fix>";
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(xml).unwrap();
+
+        let added = dictionary.add_missing_admin_messages().unwrap();
+        assert_eq!(added.len(), 6);
+        let messages: Vec<_> = dictionary.messages().values().map(Message::name).collect();
+        assert_eq!(
+            messages,
+            [
+                "Heartbeat",
+                "News",
+                "TestRequest",
+                "ResendRequest",
+                "Reject",
+                "SequenceReset",
+                "Logout",
+                "Logon"
+            ]
+        );
+        // Heartbeat defined by dictionary is not replaced
+        let heartbeat = dictionary
+            .message(&MsgType::from_str("0").unwrap())
+            .unwrap();
+        assert!(heartbeat.members().is_empty());
+        // FIX 4.4 only fields are not used by FIX 4.2 Logon
+        assert!(dictionary.fields_by_name().contains_key("HeartBtInt"));
+        assert!(!dictionary.fields_by_name().contains_key("Username"));
+        // Text defined by dictionary is kept
+        assert_eq!(dictionary.fields()[&58].location().line(), 16);
+        let logon_grp = dictionary.component("LogonMsgTypeGrp").unwrap();
+        assert_eq!(logon_grp.number_of_elements().unwrap().name(), "NoMsgTypes");
+        let values: Vec<_> = dictionary.fields_by_name()["MsgType"]
+            .values()
+            .unwrap()
+            .iter()
+            .map(Value::value)
+            .collect();
+        assert_eq!(values, ["0", "B", "1", "2", "3", "4", "5", "A"]);
+
+        assert!(dictionary.add_missing_admin_messages().unwrap().is_empty());
+
+        // Tag of admin field used by other field
+        let mut dictionary = Dictionary::new(None);
+        dictionary
+            .process_legacy_fix_xml(&xml.replace("'Text'", "'VenueText'"))
+            .unwrap();
+        let error = dictionary.add_missing_admin_messages().unwrap_err();
+        assert!(error.to_string().contains("already defined"), "{error}");
+
+        let mut dictionary = Dictionary::new(None);
+        dictionary
+            .process_legacy_fix_xml(&xml.replace("minor='2'", "minor='1'"))
+            .unwrap();
+        let error = dictionary.add_missing_admin_messages().unwrap_err();
+        assert!(error.to_string().contains("not available"), "{error}");
+    }
+
+    #[test]
+    fn fix_xml_parts_merged() {
+        let fixt_xml = "<fix type='FIXT' major='1' minor='1' servicepack='0'>
+             <header><field name='MsgType' required='Y'/></header>
+             <trailer><field name='CheckSum' required='Y'/></trailer>
+             <messages/>
+             <components/>
+             <fields>
+              <field number='35' name='MsgType' type='STRING'>
+               <value enum='0' description='HEARTBEAT'/>
+              </field>
+              <field number='10' name='CheckSum' type='STRING'/>
+             </fields>
+            </fix>";
+        let messages_xml = "<fix type='FIX' major='5' minor='0' servicepack='2'>
+             <messages>
+              <message name='News' msgtype='B' msgcat='app'>
+               <field name='Headline' required='Y'/>
+               <component name='Instrument' required='N'/>
+              </message>
+             </messages>
+             <components>
+              <component name='Instrument'>
+               <field name='Symbol' required='N'/>
+              </component>
+             </components>
+            </fix>";
+        let fields_xml = "<fix type='FIX'>
+             <fields>
+              <field number='35' name='MsgType' type='STRING'>
+               <value enum='B' description='NEWS'/>
+               <value enum='D' description='ORDER_SINGLE'/>
+              </field>
+              <field number='55' name='Symbol' type='STRING'/>
+              <field number='148' name='Headline' type='STRING'/>
+             </fields>
+            </fix>";
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_fixt_xml(fixt_xml).unwrap();
+        dictionary
+            .process_fix_xml_parts(&[messages_xml, fields_xml])
+            .unwrap();
+
+        assert_eq!(dictionary.fix_version().unwrap().to_string(), "5.0 SP2");
+        let messages: Vec<_> = dictionary.messages().values().map(Message::name).collect();
+        assert_eq!(messages, ["News"]);
+        assert!(dictionary.component("Instrument").is_some());
+        let fields: Vec<_> = dictionary.fields_by_name().keys().collect();
+        assert_eq!(fields, ["MsgType", "CheckSum", "Symbol", "Headline"]);
+        // MsgType values of undefined messages are dropped
+        let values: Vec<_> = dictionary.fields_by_name()["MsgType"]
+            .values()
+            .unwrap()
+            .iter()
+            .map(Value::value)
+            .collect();
+        assert_eq!(values, ["B"]);
+    }
+
+    #[test]
+    fn fix_xml_parts_validated() {
+        let messages_xml = "<fix type='FIX' major='4' minor='4' servicepack='0'>
+             <messages>
+              <message name='News' msgtype='B' msgcat='app'>
+               <field name='Headline' required='Y'/>
+              </message>
+             </messages>
+            </fix>";
+        let fields_xml = "<fix type='FIX'>
+             <fields>
+              <field number='35' name='MsgType' type='STRING'>
+               <value enum='B' description='NEWS'/>
+              </field>
+              <field number='148' name='Headline' type='STRING'/>
+             </fields>
+            </fix>";
+        let process = |parts: &[&str]| {
+            Dictionary::new(None)
+                .process_fix_xml_parts(parts)
+                .map_err(|err| err.to_string())
+        };
+
+        assert_eq!(process(&[messages_xml, fields_xml]), Ok(()));
+        // Field defined later is resolved
+        assert_eq!(process(&[fields_xml, messages_xml]), Ok(()));
+
+        let error = process(&[messages_xml]).unwrap_err();
+        assert!(error.contains("`fields` not defined"), "{error}");
+        let error = process(&[fields_xml]).unwrap_err();
+        assert!(error.contains("version not set"), "{error}");
+        let error = process(&[messages_xml, &fields_xml.replace("Headline", "Other")]).unwrap_err();
+        assert!(error.contains("`Headline`"), "{error}");
+        assert!(error.contains("not defined"), "{error}");
+        let error = process(&[messages_xml, fields_xml, fields_xml]).unwrap_err();
+        assert!(error.contains("already defined"), "{error}");
+        let error = process(&[
+            messages_xml,
+            &fields_xml.replace(
+                "<fix type='FIX'>",
+                "<fix type='FIX' major='5' minor='0' servicepack='0'>",
+            ),
+        ])
+        .unwrap_err();
+        assert!(error.contains("doesn't match"), "{error}");
+    }
+
+    #[test]
+    fn dictionary_is_send_and_sync() {
+        // Parsed dictionary may be shared between threads, e.g. by
+        // parallel build pipelines
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Dictionary>();
+    }
+
+    #[test]
+    fn inline_groups_converted_to_components() {
+        let xml = r#"
+            <message name='VenueOrder' msgtype='U1' msgcat='app'>
+                <field name='ClOrdID' required='Y'/>
+                <group name='NoLegs' required='N'>
+                    <field name='LegSymbol' required='Y'/>
+                    <group name='NoFees' required='N'>
+                        <field name='FeeType' required='Y'/>
+                    </group>
+                </group>
+            </message>"#;
+        let element = Element::parse(xml.as_bytes()).unwrap();
+        let (message, groups) = Message::from_xml(&element).unwrap();
+
+        let members = message.members();
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[1].name(), "VenueOrderLegGrp");
+        assert_eq!(members[1].kind(), MemberKind::Component);
+        assert!(!members[1].required());
+
+        let names: Vec<_> = groups.iter().map(|group| group.name()).collect();
+        assert_eq!(names, ["VenueOrderLegGrpFeeGrp", "VenueOrderLegGrp"]);
+        let leg_grp = &groups[1];
+        assert_eq!(leg_grp.number_of_elements().unwrap().name(), "NoLegs");
+        assert_eq!(leg_grp.members()[1].name(), "VenueOrderLegGrpFeeGrp");
+    }
+
+    #[test]
+    fn locations_tracked() {
+        let xml = "<fix>\n  <message name='VenueOrder' msgtype='U1' msgcat='app'>\n    <field name='ClOrdID' required='Y'/>\n    <group name='NoLegs' required='N'>\n      <field name='LegSymbol' required='Y'/>\n    </group>\n  </message>\n</fix>";
+        let element = Element::parse(xml.as_bytes()).unwrap();
+        let (message, groups) = Message::from_xml(&element.children[0]).unwrap();
+
+        assert_eq!(message.location().line(), 2);
+        assert_eq!(message.location().column(), 3);
+        assert_eq!(
+            message.location().path(),
+            "/fix/message[@name='VenueOrder']"
+        );
+
+        let cl_ord_id = message.members()[0].location();
+        assert_eq!(cl_ord_id.line(), 3);
+        assert_eq!(cl_ord_id.column(), 5);
+        assert_eq!(
+            cl_ord_id.path(),
+            "/fix/message[@name='VenueOrder']/field[@name='ClOrdID']"
+        );
+
+        let leg_symbol = groups[0].members()[0].location();
+        assert_eq!(leg_symbol.line(), 5);
+        assert_eq!(
+            leg_symbol.path(),
+            "/fix/message[@name='VenueOrder']/group[@name='NoLegs']/field[@name='LegSymbol']"
+        );
+    }
+
+    #[test]
+    fn errors_point_to_source() {
+        let xml = "<fix>\n  <message name='VenueOrder' msgtype='U1' msgcat='app'>\n    <field name='ClOrdID' required='X'/>\n  </message>\n</fix>";
+        let element = Element::parse(xml.as_bytes()).unwrap();
+        let error = Message::from_xml(&element.children[0]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid `required` attribute at 3:5 (/fix/message[@name='VenueOrder']/field[@name='ClOrdID'])"
+        );
+
+        let xml = "<fix>\n  <message name='VenueOrder' msgcat='app'/>\n</fix>";
+        let element = Element::parse(xml.as_bytes()).unwrap();
+        let error = Message::from_xml(&element.children[0]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no `msgtype` attribute in `message` element at 2:3 (/fix/message[@name='VenueOrder'])"
+        );
+    }
 }
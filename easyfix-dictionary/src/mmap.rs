@@ -0,0 +1,69 @@
+//! Read-only memory mapped file.
+
+use std::{fs::File, io, os::fd::AsRawFd, path::Path, ptr, slice};
+
+pub(crate) struct Mmap {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+// SAFETY: mapping is read-only and never modified after creation.
+unsafe impl Send for Mmap {}
+unsafe impl Sync for Mmap {}
+
+impl Mmap {
+    /// Map file at `path`.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the mapping is
+    /// alive, see [`LazyDictionary::open_mmap`](crate::LazyDictionary::open_mmap).
+    pub unsafe fn open(path: impl AsRef<Path>) -> io::Result<Mmap> {
+        let file = File::open(path)?;
+        let len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "file too large"))?;
+        if len == 0 {
+            // Zero length mapping is not allowed
+            return Ok(Mmap {
+                ptr: ptr::null_mut(),
+                len,
+            });
+        }
+        // SAFETY: file descriptor is valid for the duration of the call,
+        // mapping stays valid after the file is closed.
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Mmap { ptr, len })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        if self.len == 0 {
+            return &[];
+        }
+        // SAFETY: `ptr` points to `len` bytes mapped until `self` is
+        // dropped, not modified as required by `open()`.
+        unsafe { slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        if self.len != 0 {
+            // SAFETY: `ptr` and `len` describe mapping created in `open()`.
+            unsafe {
+                libc::munmap(self.ptr, self.len);
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! Dictionaries of several FIX versions shared by the whole process.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+use anyhow::{bail, Result};
+
+use crate::{Dictionary, Version};
+
+#[derive(Debug, Default)]
+struct Entries {
+    by_begin_string: HashMap<String, Arc<Dictionary>>,
+    by_appl_ver_id: HashMap<String, Arc<Dictionary>>,
+}
+
+/// Registry of dictionaries, keyed by BeginString<8> and ApplVerID<1128>,
+/// so message of any registered version can be handled with matching
+/// dictionary.
+///
+/// Dictionary is registered under its BeginString<8> (`FIXT.1.1` for
+/// FIXT dictionaries, e.g. `FIX.4.4` otherwise) and ApplVerID<1128> of its
+/// FIX version. Registering dictionary with already used key replaces
+/// the previous one, dictionaries taken before stay valid.
+#[derive(Debug, Default)]
+pub struct DictionaryRegistry {
+    entries: RwLock<Entries>,
+}
+
+impl DictionaryRegistry {
+    pub fn new() -> DictionaryRegistry {
+        DictionaryRegistry::default()
+    }
+
+    /// Registry shared by the whole process.
+    pub fn global() -> &'static DictionaryRegistry {
+        static GLOBAL: OnceLock<DictionaryRegistry> = OnceLock::new();
+        GLOBAL.get_or_init(DictionaryRegistry::new)
+    }
+
+    /// Register `dictionary` under its BeginString<8> and ApplVerID<1128>.
+    ///
+    /// Fails if dictionary has no FIX version set.
+    pub fn register(&self, dictionary: impl Into<Arc<Dictionary>>) -> Result<Arc<Dictionary>> {
+        let dictionary = dictionary.into();
+        let Some(begin_string) = dictionary.begin_string() else {
+            bail!("Neither FIX nor FIXT version defined");
+        };
+        let appl_ver_id = dictionary.fix_version().and_then(Version::appl_ver_id);
+
+        let mut entries = self.entries.write().unwrap();
+        entries
+            .by_begin_string
+            .insert(begin_string, dictionary.clone());
+        if let Some(appl_ver_id) = appl_ver_id {
+            entries
+                .by_appl_ver_id
+                .insert(appl_ver_id.to_owned(), dictionary.clone());
+        }
+        Ok(dictionary)
+    }
+
+    /// Register `dictionary` under additional ApplVerID<1128>, e.g. for
+    /// counterparty specific version set with CstmApplVerID<1129>.
+    pub fn register_appl_ver_id(&self, appl_ver_id: &str, dictionary: Arc<Dictionary>) {
+        self.entries
+            .write()
+            .unwrap()
+            .by_appl_ver_id
+            .insert(appl_ver_id.to_owned(), dictionary);
+    }
+
+    pub fn by_begin_string(&self, begin_string: &str) -> Option<Arc<Dictionary>> {
+        self.entries
+            .read()
+            .unwrap()
+            .by_begin_string
+            .get(begin_string)
+            .cloned()
+    }
+
+    pub fn by_appl_ver_id(&self, appl_ver_id: &str) -> Option<Arc<Dictionary>> {
+        self.entries
+            .read()
+            .unwrap()
+            .by_appl_ver_id
+            .get(appl_ver_id)
+            .cloned()
+    }
+
+    /// Dictionary of FIX application `version`.
+    pub fn by_version(&self, version: &Version) -> Option<Arc<Dictionary>> {
+        self.by_appl_ver_id(version.appl_ver_id()?)
+    }
+
+    /// Dictionary for message with given BeginString<8> and ApplVerID<1128>.
+    ///
+    /// `appl_ver_id` is taken from message or, when not set there, from
+    /// DefaultApplVerID<1137> of the session. Messages without it (e.g.
+    /// FIX.4.4 ones) are looked up by `begin_string`.
+    pub fn select(&self, begin_string: &str, appl_ver_id: Option<&str>) -> Option<Arc<Dictionary>> {
+        match appl_ver_id {
+            Some(appl_ver_id) => self.by_appl_ver_id(appl_ver_id),
+            None => self.by_begin_string(begin_string),
+        }
+    }
+
+    /// Remove all registered dictionaries.
+    pub fn clear(&self) {
+        *self.entries.write().unwrap() = Entries::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::DictionaryRegistry;
+    use crate::Dictionary;
+
+    fn fix_xml(major: u32, minor: u32, service_pack: u32) -> String {
+        format!(
+            "<fix type='FIX' major='{major}' minor='{minor}' servicepack='{service_pack}'>
+             <header><field name='MsgType' required='Y'/></header>
+             <trailer><field name='CheckSum' required='Y'/></trailer>
+             <messages>
+              <message name='Heartbeat' msgtype='0' msgcat='admin'/>
+             </messages>
+             <components/>
+             <fields>
+              <field number='10' name='CheckSum' type='STRING'/>
+              <field number='35' name='MsgType' type='STRING'>
+               <value enum='0' description='HEARTBEAT'/>
+              </field>
+             </fields>
+            </fix>"
+        )
+    }
+
+    fn fixt_xml() -> &'static str {
+        "<fix type='FIXT' major='1' minor='1' servicepack='0'>
+         <header><field name='MsgType' required='Y'/></header>
+         <trailer><field name='CheckSum' required='Y'/></trailer>
+         <messages/>
+         <components/>
+         <fields>
+          <field number='10' name='CheckSum' type='STRING'/>
+          <field number='35' name='MsgType' type='STRING'>
+           <value enum='0' description='HEARTBEAT'/>
+          </field>
+         </fields>
+        </fix>"
+    }
+
+    #[test]
+    fn lookup_by_begin_string_and_appl_ver_id() {
+        let registry = DictionaryRegistry::new();
+
+        let mut fix44 = Dictionary::new(None);
+        fix44.process_legacy_fix_xml(&fix_xml(4, 4, 0)).unwrap();
+        let fix44 = registry.register(fix44).unwrap();
+
+        let mut fix50sp2 = Dictionary::new(None);
+        fix50sp2.process_fixt_xml(fixt_xml()).unwrap();
+        fix50sp2.process_fix_xml(&fix_xml(5, 0, 2)).unwrap();
+        let fix50sp2 = registry.register(fix50sp2).unwrap();
+
+        let selected = registry.select("FIX.4.4", None).unwrap();
+        assert!(Arc::ptr_eq(&selected, &fix44));
+        let selected = registry.select("FIXT.1.1", Some("9")).unwrap();
+        assert!(Arc::ptr_eq(&selected, &fix50sp2));
+        let selected = registry.select("FIXT.1.1", None).unwrap();
+        assert!(Arc::ptr_eq(&selected, &fix50sp2));
+        let selected = registry.by_version(fix44.fix_version().unwrap()).unwrap();
+        assert!(Arc::ptr_eq(&selected, &fix44));
+        assert!(registry.select("FIXT.1.1", Some("8")).is_none());
+
+        registry.register_appl_ver_id("VENUE", fix50sp2.clone());
+        let selected = registry.by_appl_ver_id("VENUE").unwrap();
+        assert!(Arc::ptr_eq(&selected, &fix50sp2));
+
+        registry.clear();
+        assert!(registry.by_begin_string("FIX.4.4").is_none());
+    }
+
+    #[test]
+    fn registry_shared_between_threads() {
+        let mut dictionary = Dictionary::new(None);
+        dictionary
+            .process_legacy_fix_xml(&fix_xml(4, 2, 0))
+            .unwrap();
+        std::thread::spawn(move || DictionaryRegistry::global().register(dictionary))
+            .join()
+            .unwrap()
+            .unwrap();
+        assert!(DictionaryRegistry::global().by_appl_ver_id("4").is_some());
+    }
+}
@@ -0,0 +1,157 @@
+//! Dictionary which can be replaced at runtime.
+
+use std::sync::{mpsc, Arc, Mutex, RwLock};
+
+use anyhow::Result;
+
+use crate::Dictionary;
+
+/// Notification about dictionary replaced in [ReloadableDictionary].
+#[derive(Clone, Debug)]
+pub struct DictionaryUpdate {
+    /// Version of the new dictionary, initial dictionary has version 0.
+    pub version: u64,
+    pub dictionary: Arc<Dictionary>,
+}
+
+/// Shared dictionary handle, allowing to atomically swap dictionary,
+/// e.g. to apply venue specification patch in long-running process.
+///
+/// Readers take a snapshot with [load](ReloadableDictionary::load), which
+/// stays valid (and unchanged) after dictionary is replaced.
+#[derive(Debug)]
+pub struct ReloadableDictionary {
+    current: RwLock<DictionaryUpdate>,
+    subscribers: Mutex<Vec<mpsc::Sender<DictionaryUpdate>>>,
+}
+
+impl ReloadableDictionary {
+    pub fn new(dictionary: Dictionary) -> ReloadableDictionary {
+        ReloadableDictionary {
+            current: RwLock::new(DictionaryUpdate {
+                version: 0,
+                dictionary: Arc::new(dictionary),
+            }),
+            subscribers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Current dictionary.
+    pub fn load(&self) -> Arc<Dictionary> {
+        self.current.read().unwrap().dictionary.clone()
+    }
+
+    /// Version of current dictionary, incremented on every replacement.
+    pub fn version(&self) -> u64 {
+        self.current.read().unwrap().version
+    }
+
+    /// Replace current dictionary and notify subscribers.
+    ///
+    /// Returns version of the new dictionary.
+    pub fn store(&self, dictionary: Dictionary) -> u64 {
+        let mut current = self.current.write().unwrap();
+        *current = DictionaryUpdate {
+            version: current.version + 1,
+            dictionary: Arc::new(dictionary),
+        };
+        // Notify while holding the lock, so updates are delivered in order
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|subscriber| subscriber.send(current.clone()).is_ok());
+        current.version
+    }
+
+    /// Build new dictionary from XML descriptions and replace current one,
+    /// keeping its reject reason overrides.
+    ///
+    /// When `fixt_xml` is not given, `fix_xml` is processed as legacy
+    /// (pre FIXT) description. On error current dictionary is kept.
+    pub fn reload(&self, fixt_xml: Option<&str>, fix_xml: &str) -> Result<u64> {
+        let mut dictionary = Dictionary::new(Some(self.load().reject_reason_overrides().clone()));
+        if let Some(fixt_xml) = fixt_xml {
+            dictionary.process_fixt_xml(fixt_xml)?;
+            dictionary.process_fix_xml(fix_xml)?;
+        } else {
+            dictionary.process_legacy_fix_xml(fix_xml)?;
+        }
+        Ok(self.store(dictionary))
+    }
+
+    /// Receive notification about every future dictionary replacement.
+    ///
+    /// Subscription ends when receiver is dropped.
+    pub fn subscribe(&self) -> mpsc::Receiver<DictionaryUpdate> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.lock().unwrap().push(sender);
+        receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::ReloadableDictionary;
+    use crate::Dictionary;
+
+    fn fix_xml(extra_field: &str) -> String {
+        format!(
+            "<fix type='FIX' major='4' minor='4' servicepack='0'>
+             <header><field name='MsgType' required='Y'/></header>
+             <trailer><field name='CheckSum' required='Y'/></trailer>
+             <messages>
+              <message name='Heartbeat' msgtype='0' msgcat='admin'/>
+             </messages>
+             <components/>
+             <fields>
+              <field number='10' name='CheckSum' type='STRING'/>
+              <field number='35' name='MsgType' type='STRING'>
+               <value enum='0' description='HEARTBEAT'/>
+              </field>
+              {extra_field}
+             </fields>
+            </fix>"
+        )
+    }
+
+    fn dictionary(xml: &str) -> Dictionary {
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(xml).unwrap();
+        dictionary
+    }
+
+    #[test]
+    fn reload_swaps_dictionary() {
+        let reloadable = ReloadableDictionary::new(dictionary(&fix_xml("")));
+        let subscriber = reloadable.subscribe();
+        let snapshot = reloadable.load();
+        assert_eq!(reloadable.version(), 0);
+
+        let patched = fix_xml("<field number='9001' name='VenueFlag' type='STRING'/>");
+        assert_eq!(reloadable.reload(None, &patched).unwrap(), 1);
+
+        assert!(!snapshot.fields().contains_key(&9001));
+        assert!(reloadable.load().fields().contains_key(&9001));
+        assert_eq!(reloadable.version(), 1);
+
+        let update = subscriber.try_recv().unwrap();
+        assert_eq!(update.version, 1);
+        assert!(Arc::ptr_eq(&update.dictionary, &reloadable.load()));
+    }
+
+    #[test]
+    fn failed_reload_keeps_dictionary() {
+        let reloadable = ReloadableDictionary::new(dictionary(&fix_xml("")));
+        let subscriber = reloadable.subscribe();
+        let snapshot = reloadable.load();
+
+        let broken = fix_xml("<field number='abc' name='VenueFlag' type='STRING'/>");
+        assert!(reloadable.reload(None, &broken).is_err());
+
+        assert!(Arc::ptr_eq(&snapshot, &reloadable.load()));
+        assert_eq!(reloadable.version(), 0);
+        assert!(subscriber.try_recv().is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! Dictionary element counts, reported by `Dictionary::stats()`.
+
+use crate::{Dictionary, IndexMap, Member, MemberKind, MsgCat};
+
+/// Element counts of processed dictionary.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DictionaryStats {
+    /// Number of defined fields.
+    pub fields: usize,
+    /// Number of fields with enumerated values.
+    pub enums: usize,
+    /// Number of admin (session level) messages.
+    pub admin_messages: usize,
+    /// Number of app messages.
+    pub app_messages: usize,
+    /// Number of components, excluding groups.
+    pub components: usize,
+    /// Number of repeating groups, including groups defined inline.
+    pub groups: usize,
+    /// Maximum depth of nested groups found in header, trailer or any
+    /// message, 0 if there are no groups.
+    pub max_group_depth: usize,
+    /// Number of fields of each message (in order of definition), with
+    /// components and groups expanded. Header and trailer fields are not
+    /// included.
+    pub message_fields: IndexMap<String, usize>,
+}
+
+/// Fields count and groups depth of members.
+#[derive(Default)]
+struct MembersStats {
+    fields: usize,
+    group_depth: usize,
+}
+
+impl Dictionary {
+    /// Count elements of processed dictionary.
+    pub fn stats(&self) -> DictionaryStats {
+        let mut stats = DictionaryStats {
+            fields: self.fields.len(),
+            enums: self
+                .fields
+                .values()
+                .filter(|field| field.values().is_some())
+                .count(),
+            ..Default::default()
+        };
+
+        for component in &self.components {
+            if component.number_of_elements().is_some() {
+                stats.groups += 1;
+            } else {
+                stats.components += 1;
+            }
+        }
+
+        for component in self.header.iter().chain(&self.trailer) {
+            let members_stats = self.members_stats(component.members(), &mut Vec::new());
+            stats.max_group_depth = stats.max_group_depth.max(members_stats.group_depth);
+        }
+
+        for message in self.messages.values() {
+            match message.msg_cat() {
+                MsgCat::Admin => stats.admin_messages += 1,
+                MsgCat::App => stats.app_messages += 1,
+            }
+            let members_stats = self.members_stats(message.members(), &mut Vec::new());
+            stats.max_group_depth = stats.max_group_depth.max(members_stats.group_depth);
+            stats
+                .message_fields
+                .insert(message.name().to_owned(), members_stats.fields);
+        }
+
+        stats
+    }
+
+    /// Unknown and recursively included components are skipped.
+    fn members_stats<'a>(&'a self, members: &'a [Member], path: &mut Vec<&'a str>) -> MembersStats {
+        let mut stats = MembersStats::default();
+        for member in members {
+            match member.kind() {
+                MemberKind::Field => stats.fields += 1,
+                MemberKind::Component => {
+                    if path.contains(&member.name()) {
+                        continue;
+                    }
+                    let Some(component) = self.component(member.name()) else {
+                        continue;
+                    };
+                    path.push(member.name());
+                    let component_stats = self.members_stats(component.members(), path);
+                    path.pop();
+                    stats.fields += component_stats.fields;
+                    if component.number_of_elements().is_some() {
+                        // Group counter field
+                        stats.fields += 1;
+                        stats.group_depth = stats.group_depth.max(component_stats.group_depth + 1);
+                    } else {
+                        stats.group_depth = stats.group_depth.max(component_stats.group_depth);
+                    }
+                }
+            }
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Dictionary;
+
+    #[test]
+    fn elements_counted() {
+        let xml = "<fix type='FIX' major='4' minor='4' servicepack='0'>
+             <header>
+              <field name='MsgType' required='Y'/>
+              <group name='NoHops' required='N'>
+               <field name='HopCompID' required='N'/>
+              </group>
+             </header>
+             <trailer><field name='CheckSum' required='Y'/></trailer>
+             <messages>
+              <message name='Heartbeat' msgtype='0' msgcat='admin'>
+               <field name='TestReqID' required='N'/>
+              </message>
+              <message name='NewOrderMultileg' msgtype='AB' msgcat='app'>
+               <field name='ClOrdID' required='Y'/>
+               <component name='Instrument' required='Y'/>
+               <group name='NoLegs' required='N'>
+                <field name='LegSymbol' required='Y'/>
+                <component name='Instrument' required='N'/>
+               </group>
+              </message>
+             </messages>
+             <components>
+              <component name='Instrument'>
+               <field name='Symbol' required='Y'/>
+               <component name='SecAltIDGrp' required='N'/>
+              </component>
+              <component name='SecAltIDGrp'>
+               <group name='NoSecurityAltID' required='N'>
+                <field name='SecurityAltID' required='N'/>
+               </group>
+              </component>
+             </components>
+             <fields>
+              <field number='10' name='CheckSum' type='STRING'/>
+              <field number='11' name='ClOrdID' type='STRING'/>
+              <field number='35' name='MsgType' type='STRING'>
+               <value enum='0' description='HEARTBEAT'/>
+               <value enum='AB' description='NEW_ORDER_MULTILEG'/>
+              </field>
+              <field number='55' name='Symbol' type='STRING'/>
+              <field number='112' name='TestReqID' type='STRING'/>
+              <field number='454' name='NoSecurityAltID' type='NUMINGROUP'/>
+              <field number='455' name='SecurityAltID' type='STRING'/>
+              <field number='555' name='NoLegs' type='NUMINGROUP'/>
+              <field number='600' name='LegSymbol' type='STRING'/>
+              <field number='627' name='NoHops' type='NUMINGROUP'/>
+              <field number='628' name='HopCompID' type='STRING'/>
+             </fields>
+            </fix>";
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(xml).unwrap();
+
+        let stats = dictionary.stats();
+        assert_eq!(stats.fields, 11);
+        assert_eq!(stats.enums, 1);
+        assert_eq!(stats.admin_messages, 1);
+        assert_eq!(stats.app_messages, 1);
+        assert_eq!(stats.components, 1);
+        // HopGrp, NewOrderMultilegLegGrp and SecAltIDGrp
+        assert_eq!(stats.groups, 3);
+        // NoLegs -> Instrument -> NoSecurityAltID
+        assert_eq!(stats.max_group_depth, 2);
+        assert_eq!(
+            stats.message_fields.into_iter().collect::<Vec<_>>(),
+            [
+                ("Heartbeat".to_owned(), 1),
+                // ClOrdID, Instrument (3), NoLegs, LegSymbol, Instrument (3)
+                ("NewOrderMultileg".to_owned(), 9),
+            ]
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! Minimal XML tree, built on top of `xml-rs` reader, which (unlike
+//! `xmltree`) keeps source location of every element.
+
+use std::{collections::HashMap, fmt, io::Read, ops::Range};
+
+use anyhow::{anyhow, Context as ErrorContext, Result};
+use xml::{
+    common::Position,
+    reader::{EventReader, XmlEvent},
+};
+
+/// Position of element in XML source.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Location {
+    line: u64,
+    column: u64,
+    path: String,
+}
+
+impl Location {
+    /// Line number, starting from 1.
+    pub fn line(&self) -> u64 {
+        self.line
+    }
+
+    /// Column number, starting from 1.
+    pub fn column(&self) -> u64 {
+        self.column
+    }
+
+    /// Path of the element, e.g.
+    /// `/fix/messages/message[@name='NewOrderSingle']/field[@name='ClOrdID']`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{} ({})", self.line, self.column, self.path)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Element {
+    pub name: String,
+    pub attributes: HashMap<String, String>,
+    pub children: Vec<Element>,
+    pub location: Location,
+}
+
+impl Element {
+    pub fn parse(source: impl Read) -> Result<Element> {
+        Element::parse_at(source, 0, 0, "")
+    }
+
+    /// Parse element located at `range` of `xml`, with locations
+    /// relative to the whole `xml` and paths prefixed with `parent_path`.
+    pub fn parse_fragment(xml: &str, range: Range<usize>, parent_path: &str) -> Result<Element> {
+        let prefix = &xml[..range.start];
+        let line_offset = prefix.matches('\n').count() as u64;
+        let column_offset = prefix.len() - prefix.rfind('\n').map_or(0, |i| i + 1);
+        Element::parse_at(
+            xml[range].as_bytes(),
+            line_offset,
+            column_offset as u64,
+            parent_path,
+        )
+        .with_context(|| {
+            format!(
+                "Failed to parse XML fragment starting at line {}",
+                line_offset + 1
+            )
+        })
+    }
+
+    fn parse_at(
+        source: impl Read,
+        line_offset: u64,
+        column_offset: u64,
+        parent_path: &str,
+    ) -> Result<Element> {
+        let mut reader = EventReader::new(source);
+        let mut stack: Vec<Element> = Vec::new();
+        loop {
+            match reader.next()? {
+                XmlEvent::StartElement {
+                    name, attributes, ..
+                } => {
+                    let position = reader.position();
+                    let attributes: HashMap<_, _> = attributes
+                        .into_iter()
+                        .map(|attribute| (attribute.name.local_name, attribute.value))
+                        .collect();
+                    let parent_path = stack
+                        .last()
+                        .map_or(parent_path, |parent| parent.location.path.as_str());
+                    let path = match attributes.get("name") {
+                        Some(value) => {
+                            format!("{parent_path}/{}[@name='{value}']", name.local_name)
+                        }
+                        None => format!("{parent_path}/{}", name.local_name),
+                    };
+                    stack.push(Element {
+                        name: name.local_name,
+                        attributes,
+                        children: Vec::new(),
+                        location: Location {
+                            line: line_offset + position.row + 1,
+                            column: if position.row == 0 {
+                                column_offset + position.column + 1
+                            } else {
+                                position.column + 1
+                            },
+                            path,
+                        },
+                    });
+                }
+                XmlEvent::EndElement { .. } => {
+                    let element = stack.pop().context("Unbalanced XML elements")?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Ok(element),
+                    }
+                }
+                XmlEvent::EndDocument => return Err(anyhow!("No root element")),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn get_attribute(&self, attribute: &str) -> Result<&str> {
+        self.attributes
+            .get(attribute)
+            .map(String::as_ref)
+            .ok_or_else(|| {
+                anyhow!(
+                    "no `{}` attribute in `{}` element at {}",
+                    attribute,
+                    self.name,
+                    self.location
+                )
+            })
+    }
+
+    pub fn get_child_element(&self, child: &str) -> Result<&Element> {
+        self.children
+            .iter()
+            .find(|element| element.name == child)
+            .ok_or_else(|| {
+                anyhow!(
+                    "no `{}` child in `{}` element at {}",
+                    child,
+                    self.name,
+                    self.location
+                )
+            })
+    }
+
+    pub fn get_child_elements(&self) -> std::slice::Iter<'_, Element> {
+        self.children.iter()
+    }
+}
@@ -0,0 +1,77 @@
+#![feature(test)]
+
+extern crate test;
+
+use easyfix_messages::{
+    fields::{FixString, MsgType, UtcTimestamp},
+    messages::{
+        ExecutionReport, FixtMessage, Header, Heartbeat, Message, NewOrderSingle, Trailer,
+        BEGIN_STRING,
+    },
+};
+use test::{black_box, Bencher};
+
+fn fixt_message(body: Message) -> Vec<u8> {
+    FixtMessage {
+        header: Box::new(Header {
+            begin_string: BEGIN_STRING.to_owned(),
+            msg_type: body.msg_type(),
+            sender_comp_id: FixString::from_ascii_lossy(b"test_sender".to_vec()),
+            target_comp_id: FixString::from_ascii_lossy(b"test_target".to_vec()),
+            msg_seq_num: 12345,
+            sending_time: UtcTimestamp::now(),
+            ..Default::default()
+        }),
+        body: Box::new(body),
+        trailer: Box::new(Trailer::default()),
+    }
+    .serialize()
+}
+
+/// All one and two character MsgType<35> values, known or not.
+fn msg_type_codes() -> Vec<Vec<u8>> {
+    let chars = (b'0'..=b'9').chain(b'A'..=b'Z').chain(b'a'..=b'z');
+    let single = chars.clone().map(|c| vec![c]);
+    let double = (b'A'..=b'C').flat_map(|first| chars.clone().map(move |c| vec![first, c]));
+    single.chain(double).collect()
+}
+
+#[bench]
+fn msg_type_from_bytes(b: &mut Bencher) {
+    let codes = msg_type_codes();
+    b.bytes = codes.iter().map(|code| code.len() as u64).sum();
+    b.iter(|| {
+        codes
+            .iter()
+            .filter(|code| MsgType::from_bytes(black_box(code)).is_some())
+            .count()
+    });
+}
+
+#[bench]
+fn heartbeat_from_bytes(b: &mut Bencher) {
+    let input = fixt_message(Message::Heartbeat(Heartbeat { test_req_id: None }));
+    b.bytes = input.len() as u64;
+    b.iter(|| FixtMessage::from_bytes(black_box(&input)).unwrap());
+}
+
+#[bench]
+fn new_order_single_from_bytes(b: &mut Bencher) {
+    let input = fixt_message(Message::NewOrderSingle(NewOrderSingle {
+        cl_ord_id: FixString::from_ascii_lossy(b"order".to_vec()),
+        ..Default::default()
+    }));
+    b.bytes = input.len() as u64;
+    b.iter(|| FixtMessage::from_bytes(black_box(&input)).unwrap());
+}
+
+#[bench]
+fn execution_report_from_bytes(b: &mut Bencher) {
+    let input = fixt_message(Message::ExecutionReport(ExecutionReport {
+        order_id: FixString::from_ascii_lossy(b"order".to_vec()),
+        exec_id: FixString::from_ascii_lossy(b"exec".to_vec()),
+        ..Default::default()
+    }));
+    b.bytes = input.len() as u64;
+    b.iter(|| FixtMessage::from_bytes(black_box(&input)).unwrap());
+}
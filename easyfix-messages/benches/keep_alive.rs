@@ -0,0 +1,66 @@
+#![feature(test)]
+
+extern crate test;
+
+use easyfix_messages::{
+    fields::{FixString, MsgType, UtcTimestamp},
+    keep_alive::KeepAliveTemplate,
+    messages::{FixtMessage, Header, Heartbeat, Message, TestRequest, Trailer, BEGIN_STRING},
+};
+use test::{black_box, Bencher};
+
+fn header(msg_type: MsgType) -> Box<Header> {
+    Box::new(Header {
+        begin_string: BEGIN_STRING.to_owned(),
+        msg_type,
+        sender_comp_id: FixString::from_ascii_lossy(b"test_sender".to_vec()),
+        target_comp_id: FixString::from_ascii_lossy(b"test_target".to_vec()),
+        msg_seq_num: 12345,
+        sending_time: UtcTimestamp::now(),
+        ..Default::default()
+    })
+}
+
+fn heartbeat() -> Box<FixtMessage> {
+    Box::new(FixtMessage {
+        header: header(MsgType::Heartbeat),
+        body: Box::new(Message::Heartbeat(Heartbeat { test_req_id: None })),
+        trailer: Box::new(Trailer::default()),
+    })
+}
+
+fn test_request() -> Box<FixtMessage> {
+    Box::new(FixtMessage {
+        header: header(MsgType::TestRequest),
+        body: Box::new(Message::TestRequest(TestRequest {
+            test_req_id: FixString::from_ascii_lossy(b"20240101-12:00:00.000000000".to_vec()),
+        })),
+        trailer: Box::new(Trailer::default()),
+    })
+}
+
+#[bench]
+fn heartbeat_serializer(b: &mut Bencher) {
+    let msg = heartbeat();
+    b.iter(|| black_box(&msg).serialize());
+}
+
+#[bench]
+fn heartbeat_template(b: &mut Bencher) {
+    let msg = heartbeat();
+    let mut template = KeepAliveTemplate::new(*header(MsgType::Heartbeat)).unwrap();
+    b.iter(|| template.serialize(black_box(&msg)).unwrap());
+}
+
+#[bench]
+fn test_request_serializer(b: &mut Bencher) {
+    let msg = test_request();
+    b.iter(|| black_box(&msg).serialize());
+}
+
+#[bench]
+fn test_request_template(b: &mut Bencher) {
+    let msg = test_request();
+    let mut template = KeepAliveTemplate::new(*header(MsgType::Heartbeat)).unwrap();
+    b.iter(|| template.serialize(black_box(&msg)).unwrap());
+}
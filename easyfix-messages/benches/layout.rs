@@ -0,0 +1,279 @@
+//! Decode/encode performance and generated code size of messages generated
+//! with flattened or nested components.
+//!
+//! Layout is selected at build time with `FLATTEN_COMPONENTS` environment
+//! variable (see `build.rs`), run both variants to get comparison report:
+//!
+//! ```text
+//! FLATTEN_COMPONENTS=1 cargo bench --bench layout
+//! FLATTEN_COMPONENTS=0 cargo bench --bench layout
+//! ```
+//!
+//! Results of every run are stored in `layout-report` directory inside
+//! `target`, once both variants are there, comparison table is printed.
+//! Inputs are raw FIX messages, so unlike other benches this one doesn't
+//! depend on layout of generated structs. Without `--bench` argument (e.g.
+//! `cargo test --benches`) every case is run once, only to check it works.
+
+use std::{
+    collections::BTreeMap,
+    env, fmt, fs,
+    hint::black_box,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use easyfix_messages::messages::FixtMessage;
+
+const GENERATED_FILES: &[&str] = &[
+    "generated_fields.rs",
+    "generated_groups.rs",
+    "generated_messages.rs",
+];
+
+const SAMPLE_TIME: Duration = Duration::from_millis(10);
+const SAMPLES: usize = 50;
+
+/// Message with components: Parties (repeating group), Instrument and
+/// OrderQtyData.
+const NEW_ORDER_SINGLE: &[(u16, &str)] = &[
+    (35, "D"),
+    (11, "order"),
+    (453, "2"),
+    (448, "PARTY1"),
+    (447, "D"),
+    (452, "1"),
+    (448, "PARTY2"),
+    (447, "D"),
+    (452, "3"),
+    (1, "account"),
+    (55, "EURUSD"),
+    (48, "EU0009652759"),
+    (22, "4"),
+    (460, "4"),
+    (54, "1"),
+    (60, "20240102-12:00:00.000"),
+    (38, "1000000"),
+    (40, "2"),
+    (44, "1.08765"),
+    (59, "0"),
+];
+
+const EXECUTION_REPORT: &[(u16, &str)] = &[
+    (35, "8"),
+    (37, "order"),
+    (11, "clorder"),
+    (453, "1"),
+    (448, "PARTY1"),
+    (447, "D"),
+    (452, "1"),
+    (17, "exec"),
+    (150, "F"),
+    (39, "2"),
+    (1, "account"),
+    (55, "EURUSD"),
+    (48, "EU0009652759"),
+    (22, "4"),
+    (460, "4"),
+    (54, "1"),
+    (38, "1000000"),
+    (40, "2"),
+    (44, "1.08765"),
+    (32, "1000000"),
+    (31, "1.08765"),
+    (151, "0"),
+    (14, "1000000"),
+    (6, "1.08765"),
+    (60, "20240102-12:00:00.000"),
+];
+
+/// Instrument component followed by MDFullGrp repeating group.
+const MARKET_DATA_SNAPSHOT: &[(u16, &str)] = &[
+    (35, "W"),
+    (262, "request"),
+    (55, "EURUSD"),
+    (48, "EU0009652759"),
+    (22, "4"),
+    (460, "4"),
+    (268, "4"),
+    (269, "0"),
+    (270, "1.08760"),
+    (271, "1000000"),
+    (269, "0"),
+    (270, "1.08755"),
+    (271, "5000000"),
+    (269, "1"),
+    (270, "1.08770"),
+    (271, "1000000"),
+    (269, "1"),
+    (270, "1.08775"),
+    (271, "5000000"),
+];
+
+const CASES: &[(&str, &[(u16, &str)])] = &[
+    ("new_order_single", NEW_ORDER_SINGLE),
+    ("execution_report", EXECUTION_REPORT),
+    ("market_data_snapshot", MARKET_DATA_SNAPSHOT),
+];
+
+fn fixt_message(body: &[(u16, &str)]) -> Vec<u8> {
+    let mut fields = Vec::new();
+    let header = [
+        (49, "test_sender"),
+        (56, "test_target"),
+        (34, "12345"),
+        (52, "20240102-12:00:00.000"),
+    ];
+    // MsgType<35> goes first
+    for (tag, value) in body[..1].iter().chain(&header).chain(&body[1..]) {
+        fields.extend_from_slice(format!("{tag}={value}\x01").as_bytes());
+    }
+    let mut msg = format!("8=FIXT.1.1\x019={}\x01", fields.len()).into_bytes();
+    msg.extend_from_slice(&fields);
+    let checksum = msg.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    msg.extend_from_slice(format!("10={checksum:03}\x01").as_bytes());
+    msg
+}
+
+/// Median time of single `f` call.
+fn measure<T>(mut f: impl FnMut() -> T) -> Duration {
+    let mut iterations = 1;
+    loop {
+        let start = Instant::now();
+        for _ in 0..iterations {
+            black_box(f());
+        }
+        if start.elapsed() >= SAMPLE_TIME {
+            break;
+        }
+        iterations *= 2;
+    }
+    let mut samples: Vec<_> = (0..SAMPLES)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..iterations {
+                black_box(f());
+            }
+            start.elapsed() / iterations
+        })
+        .collect();
+    samples.sort();
+    samples[SAMPLES / 2]
+}
+
+/// Results of single variant, `key = value` lines, so they can be stored
+/// and compared with results of the other variant.
+#[derive(Default)]
+struct Report(BTreeMap<String, u64>);
+
+impl Report {
+    fn insert(&mut self, key: impl fmt::Display, value: u64) {
+        self.0.insert(key.to_string(), value);
+    }
+
+    fn load(path: &Path) -> Option<Report> {
+        let content = fs::read_to_string(path).ok()?;
+        let mut report = Report::default();
+        for line in content.lines() {
+            let (key, value) = line.split_once(" = ")?;
+            report.insert(key, value.parse().ok()?);
+        }
+        Some(report)
+    }
+
+    fn store(&self, path: &Path) {
+        let content: String = self
+            .0
+            .iter()
+            .map(|(key, value)| format!("{key} = {value}\n"))
+            .collect();
+        fs::write(path, content)
+            .unwrap_or_else(|err| panic!("failed to write {}: {err}", path.display()));
+    }
+}
+
+fn print_comparison(flattened: &Report, nested: &Report) {
+    println!();
+    println!(
+        "| {:<36} | {:>12} | {:>12} | {:>8} |",
+        "", "flattened", "nested", "ratio"
+    );
+    println!("|{:-<38}|{:->14}|{:->14}|{:->10}|", "", ":", ":", ":");
+    for (key, flattened_value) in &flattened.0 {
+        let Some(nested_value) = nested.0.get(key) else {
+            continue;
+        };
+        println!(
+            "| {key:<36} | {flattened_value:>12} | {nested_value:>12} | {:>8.2} |",
+            *nested_value as f64 / *flattened_value as f64
+        );
+    }
+}
+
+fn main() {
+    let bench = env::args().any(|arg| arg == "--bench");
+    let flatten_components = option_env!("FLATTEN_COMPONENTS")
+        .map_or(true, |value| !matches!(value.trim(), "0" | "false"));
+    let variant = if flatten_components {
+        "flattened"
+    } else {
+        "nested"
+    };
+
+    let mut report = Report::default();
+    for file in GENERATED_FILES {
+        let path = Path::new(env!("OUT_DIR")).join(file);
+        let content = fs::read_to_string(&path)
+            .unwrap_or_else(|err| panic!("failed to read {}: {err}", path.display()));
+        report.insert(format_args!("{file} bytes"), content.len() as u64);
+        report.insert(format_args!("{file} lines"), content.lines().count() as u64);
+    }
+
+    for (name, body) in CASES {
+        let msg = FixtMessage::from_bytes(&fixt_message(body))
+            .unwrap_or_else(|err| panic!("failed to decode {name}: {err:?}"));
+        // Serialized again, so BodyLength<9> is zero padded the same way
+        let input = msg.serialize();
+        assert_eq!(
+            FixtMessage::from_bytes(&input).unwrap().serialize(),
+            input,
+            "{name} changed in round trip"
+        );
+        if !bench {
+            println!("test {name} ... ok");
+            continue;
+        }
+        let decode = measure(|| FixtMessage::from_bytes(black_box(&input)).unwrap());
+        let encode = measure(|| black_box(&msg).serialize());
+        report.insert(format_args!("{name} decode ns"), decode.as_nanos() as u64);
+        report.insert(format_args!("{name} encode ns"), encode.as_nanos() as u64);
+    }
+    if !bench {
+        return;
+    }
+
+    println!("{variant} components:");
+    for (key, value) in &report.0 {
+        println!("  {key:<36} {value:>12}");
+    }
+
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("layout-report");
+    fs::create_dir_all(&dir)
+        .unwrap_or_else(|err| panic!("failed to create {}: {err}", dir.display()));
+    report.store(&dir.join(variant));
+    match (
+        Report::load(&dir.join("flattened")),
+        Report::load(&dir.join("nested")),
+    ) {
+        (Some(flattened), Some(nested)) => print_comparison(&flattened, &nested),
+        _ => println!(
+            "\nRun with FLATTEN_COMPONENTS={} to compare with {} components",
+            if flatten_components { 0 } else { 1 },
+            if flatten_components {
+                "nested"
+            } else {
+                "flattened"
+            },
+        ),
+    }
+}
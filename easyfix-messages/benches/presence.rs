@@ -0,0 +1,49 @@
+#![feature(test)]
+
+extern crate test;
+
+use easyfix_messages::{
+    fields::{FixString, OrdStatus, Side},
+    messages::ExecutionReport,
+};
+use test::{black_box, Bencher};
+
+/// Wide message with only a few of its optional fields set.
+fn execution_report() -> ExecutionReport {
+    ExecutionReport {
+        order_id: FixString::from_ascii_lossy(b"order".to_vec()),
+        exec_id: FixString::from_ascii_lossy(b"exec".to_vec()),
+        ord_status: OrdStatus::Filled,
+        side: Side::Buy,
+        symbol: Some(FixString::from_ascii_lossy(b"EURUSD".to_vec())),
+        ..Default::default()
+    }
+}
+
+#[bench]
+fn execution_report_presence(b: &mut Bencher) {
+    let msg = execution_report();
+    b.iter(|| black_box(&msg).presence().count());
+}
+
+#[bench]
+fn execution_report_presence_tags(b: &mut Bencher) {
+    let msg = execution_report();
+    b.iter(|| {
+        black_box(&msg)
+            .presence()
+            .tags(ExecutionReport::PRESENCE_TAGS)
+            .sum::<u16>()
+    });
+}
+
+#[bench]
+fn execution_report_is_set_all_tags(b: &mut Bencher) {
+    let msg = execution_report();
+    b.iter(|| {
+        ExecutionReport::PRESENCE_TAGS
+            .iter()
+            .filter(|tag| black_box(&msg).is_set(**tag))
+            .count()
+    });
+}
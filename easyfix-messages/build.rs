@@ -1,22 +1,72 @@
 use std::{env, path::PathBuf};
 
-use easyfix_messages_gen::generate_fix_messages;
+use easyfix_messages_gen::{generate_fix_messages_with_options, GeneratorOptions};
 
 fn main() {
     let dir = env::var("CARGO_MANIFEST_DIR").unwrap();
     let out_path = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR undefined"));
     let fixt_xml_path = env::var("FIXT_XML").unwrap_or_else(|_| format!("{}/xml/FIXT11.xml", dir));
     let fix_xml_path = env::var("FIX_XML").unwrap_or_else(|_| format!("{}/xml/FIX50SP2.xml", dir));
+    // User-defined messages, e.g. venue specific ones, merged into
+    // `Message` and `MsgType`
+    let custom_messages_xml_path = env::var("CUSTOM_MESSAGES_XML").ok();
+    // Comma separated tags of String fields holding UTF-8 text, e.g. "58,355"
+    let utf8_tags = env::var("UTF8_TAGS").ok();
+    // Comma separated `tag=codec` pairs, fields serialized with custom
+    // `FieldCodec`, e.g. "5001=crate::codec::ZeroPadded<6>"
+    let custom_codecs = env::var("CUSTOM_CODECS").ok();
+    // Set to skip formatting of generated code, so `rustfmt` is never run
+    let no_rustfmt = env::var_os("NO_RUSTFMT").is_some();
+    // Set to "0" or "false" to generate components as nested structs
+    // instead of flattening their fields into messages and groups
+    let flatten_components =
+        env::var("FLATTEN_COMPONENTS").map_or(true, |value| !matches!(value.trim(), "0" | "false"));
+    let field_presence = env::var_os("CARGO_FEATURE_FIELD_PRESENCE").is_some();
     println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=FIXT_XML");
+    println!("cargo:rerun-if-env-changed=FIX_XML");
+    println!("cargo:rerun-if-env-changed=CUSTOM_MESSAGES_XML");
+    println!("cargo:rerun-if-env-changed=UTF8_TAGS");
+    println!("cargo:rerun-if-env-changed=CUSTOM_CODECS");
+    println!("cargo:rerun-if-env-changed=NO_RUSTFMT");
+    println!("cargo:rerun-if-env-changed=FLATTEN_COMPONENTS");
     println!("cargo:rerun-if-changed={}", fixt_xml_path);
     println!("cargo:rerun-if-changed={}", fix_xml_path);
-    generate_fix_messages(
+    let mut options = GeneratorOptions::new()
+        .rustfmt(!no_rustfmt)
+        .flatten_components(flatten_components)
+        .field_presence(field_presence);
+    if let Some(custom_messages_xml_path) = custom_messages_xml_path {
+        println!("cargo:rerun-if-changed={}", custom_messages_xml_path);
+        options = options.custom_messages_file(custom_messages_xml_path);
+    }
+    if let Some(utf8_tags) = utf8_tags {
+        options = options.utf8_tags(utf8_tags.split(',').map(|tag| {
+            tag.trim()
+                .parse()
+                .unwrap_or_else(|_| panic!("invalid tag `{tag}` in UTF8_TAGS"))
+        }));
+    }
+    if let Some(custom_codecs) = custom_codecs {
+        for entry in custom_codecs
+            .split(',')
+            .filter(|entry| !entry.trim().is_empty())
+        {
+            let (tag, codec) = entry
+                .split_once('=')
+                .and_then(|(tag, codec)| Some((tag.trim().parse().ok()?, codec.trim())))
+                .unwrap_or_else(|| panic!("invalid entry `{entry}` in CUSTOM_CODECS"));
+            options = options.custom_codec(tag, codec);
+        }
+    }
+    generate_fix_messages_with_options(
         Some(fixt_xml_path),
         fix_xml_path,
         out_path.join("generated_fields.rs"),
         out_path.join("generated_groups.rs"),
         out_path.join("generated_messages.rs"),
         None,
+        &options,
     )
     .expect("failed to generate FIX messages");
 }
@@ -0,0 +1,165 @@
+//! Errors in dictionary found while preparing code generation.
+
+use std::{fmt, mem, path::PathBuf};
+
+use easyfix_dictionary::{BasicType, LengthDataError, Location};
+
+/// Kind of dictionary element.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ElementKind {
+    Field,
+    Component,
+    Group,
+    Header,
+    Trailer,
+}
+
+impl fmt::Display for ElementKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            ElementKind::Field => "Field",
+            ElementKind::Component => "Component",
+            ElementKind::Group => "Group",
+            ElementKind::Header => "Header",
+            ElementKind::Trailer => "Trailer",
+        };
+        f.write_str(kind)
+    }
+}
+
+/// Dictionary issue preventing code generation.
+///
+/// `owner` is the message (or `Header`, `Trailer`, group) being generated
+/// when the issue was found, `location` points to the element causing it,
+/// e.g. field of component used by `owner`.
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum GenerateError {
+    #[error("Neither FIX nor FIXT version defined")]
+    MissingVersion,
+    #[error("{0} not defined")]
+    MissingSection(ElementKind),
+    #[error("{kind} `{name}` used by `{owner}` at {location} not defined")]
+    Undefined {
+        kind: ElementKind,
+        name: String,
+        owner: String,
+        location: Location,
+    },
+    #[error("{kind} `{name}` used by `{owner}` at {location} has no members")]
+    Empty {
+        kind: ElementKind,
+        name: String,
+        owner: String,
+        location: Location,
+    },
+    #[error("Field `{name}` (tag {tag}) of `{owner}` at {location} is included by more than one component")]
+    AmbiguousField {
+        name: String,
+        tag: u16,
+        owner: String,
+        location: Location,
+    },
+    #[error("Field `{name}` (tag {tag}) used by `{owner}` at {location} is already defined in header or trailer")]
+    HeaderTrailerField {
+        name: String,
+        tag: u16,
+        owner: String,
+        location: Location,
+    },
+    #[error("Header field `{name}` can't be optional in `{owner}` at {location}")]
+    AlwaysRequired {
+        name: String,
+        owner: String,
+        location: Location,
+    },
+    #[error("Value `{value}` of field `{name}` at {location} is not valid {type_:?}")]
+    InvalidValue {
+        name: String,
+        value: String,
+        type_: BasicType,
+        location: Location,
+    },
+    #[error("Field `{name}` at {location} of type {type_:?} can't have enumerated values")]
+    InvalidEnumType {
+        name: String,
+        type_: BasicType,
+        location: Location,
+    },
+    #[error(transparent)]
+    LengthData(LengthDataError),
+    #[error("Custom codec `{codec}` of tag {tag} is not valid type path")]
+    InvalidCodecPath { tag: u16, codec: String },
+    #[error("Field `{name}` (tag {tag}) at {location} of type {type_:?} can't have custom codec")]
+    UnsupportedCodecField {
+        name: String,
+        tag: u16,
+        type_: BasicType,
+        location: Location,
+    },
+    #[error("Output directory `{}` is not valid UTF-8 path", .0.display())]
+    NonUtf8OutputDir(PathBuf),
+}
+
+impl GenerateError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            GenerateError::MissingVersion
+            | GenerateError::MissingSection(_)
+            | GenerateError::InvalidCodecPath { .. }
+            | GenerateError::NonUtf8OutputDir(_) => None,
+            GenerateError::Undefined { location, .. }
+            | GenerateError::Empty { location, .. }
+            | GenerateError::AmbiguousField { location, .. }
+            | GenerateError::HeaderTrailerField { location, .. }
+            | GenerateError::AlwaysRequired { location, .. }
+            | GenerateError::InvalidValue { location, .. }
+            | GenerateError::InvalidEnumType { location, .. }
+            | GenerateError::UnsupportedCodecField { location, .. } => Some(location),
+            GenerateError::LengthData(error) => Some(error.location()),
+        }
+    }
+}
+
+/// All dictionary issues found, so they can be fixed at once.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GenerateErrors(Vec<GenerateError>);
+
+impl GenerateErrors {
+    pub fn errors(&self) -> &[GenerateError] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Add `error`, unless the same issue was already reported, e.g. for
+    /// component used by many messages.
+    pub(crate) fn push(&mut self, error: GenerateError) {
+        let duplicate = self.0.iter().any(|reported| {
+            mem::discriminant(reported) == mem::discriminant(&error)
+                && reported.location() == error.location()
+        });
+        if !duplicate {
+            self.0.push(error);
+        }
+    }
+}
+
+impl From<GenerateError> for GenerateErrors {
+    fn from(error: GenerateError) -> GenerateErrors {
+        GenerateErrors(vec![error])
+    }
+}
+
+impl fmt::Display for GenerateErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} dictionary error(s) found", self.0.len())?;
+        for error in &self.0 {
+            write!(f, "\n - {error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for GenerateErrors {}
@@ -2,54 +2,250 @@ mod enumeration;
 mod member;
 mod structure;
 
-use std::{collections::HashMap, rc::Rc};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf, MAIN_SEPARATOR},
+    rc::Rc,
+};
 
 use convert_case::{Case, Casing};
-use easyfix_dictionary::{BasicType, Dictionary, Member, MemberKind, ParseRejectReason};
+use easyfix_dictionary::{
+    BasicType, Dictionary, IndexMap, Location, Member, MemberKind, ParseRejectReason, Value,
+    Warning, WarningKind,
+};
 use proc_macro2::{Ident, Literal, Span, TokenStream};
 use quote::quote;
 use strum::IntoEnumIterator;
 
-use self::structure::MessageProperties;
-use crate::gen::{
-    enumeration::EnumDesc,
-    member::{MemberDesc, SimpleMember},
-    structure::Struct,
+use self::structure::{generate_de_other_message, MessageProperties};
+use crate::{
+    error::{ElementKind, GenerateError, GenerateErrors},
+    gen::{
+        enumeration::EnumDesc,
+        member::{MemberDesc, SimpleMember},
+        structure::Struct,
+    },
+    naming::{Naming, Rename},
+    Derives, GeneratorOptions,
 };
 
 pub struct Generator {
     begin_string: Vec<u8>,
+    /// `Dictionary::fingerprint()` as 16 lowercase hex digits.
+    fingerprint: String,
     structs: Vec<Struct>,
+    header_members: Rc<Vec<MemberDesc>>,
+    trailer_members: Rc<Vec<MemberDesc>>,
     enums: Vec<EnumDesc>,
     fields_names: Vec<Ident>,
     fields_numbers: Vec<u16>,
+    /// Types of fields, in order of first appearance.
+    basic_types: Vec<BasicType>,
+    utf8_strings: bool,
+    /// Length fields and Data/XmlData fields which length they hold.
+    length_data_tags: BTreeMap<u16, u16>,
     reject_reason_overrides: HashMap<ParseRejectReason, String>,
+    enum_derives: Derives,
+    struct_derives: Derives,
+    required_constructors: bool,
+    field_presence: bool,
+    warnings: Vec<Warning>,
+    renames: Vec<Rename>,
+}
+
+/// Generate `#[derive(..)]` attributes, `base` derives are always present,
+/// `ord` ones are added when `PartialOrd` is requested.
+pub(crate) fn gen_derives(base: TokenStream, ord: TokenStream, derives: &Derives) -> TokenStream {
+    let hash = derives.hash.then(|| quote! { Hash, });
+    let ord = derives.partial_ord.then_some(ord);
+    let serde = derives.serde.then(|| {
+        quote! {
+            #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+            #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+        }
+    });
+    quote! {
+        #[derive(#base #hash #ord)]
+        #serde
+    }
 }
 
+/// Remove fields defined more than once in message, group or component,
+/// which happens when a field is defined directly and also included
+/// through a component.
+///
+/// The first definition is kept, except fields of not flattened
+/// components, which always take precedence over fields defined
+/// directly. Field included by two not flattened components can't be
+/// resolved and is reported as error.
+///
+/// Different fields generated with the same identifier (e.g. `NoLegs`
+/// and `NOLegs`) get their tag appended to all but the first one.
+fn dedup_members(
+    owner: &str,
+    location: &Location,
+    members_descs: Vec<MemberDesc>,
+    dictionary: &Dictionary,
+    naming: &Naming,
+    diagnostics: &mut Diagnostics,
+) -> Vec<MemberDesc> {
+    let field_name = |tag| {
+        dictionary
+            .fields()
+            .get(&tag)
+            .map_or("<unknown>", |field| field.name())
+    };
+
+    let mut component_tags = HashSet::new();
+    for member in &members_descs {
+        if let MemberDesc::Component(_) = member {
+            // Group and its `NumInGroup` counterpart share the tag
+            let leaf_tags: HashSet<_> = member
+                .leaves()
+                .into_iter()
+                .map(MemberDesc::tag_num)
+                .collect();
+            for tag in leaf_tags {
+                if !component_tags.insert(tag) {
+                    diagnostics.errors.push(GenerateError::AmbiguousField {
+                        name: field_name(tag).to_owned(),
+                        tag,
+                        owner: owner.to_owned(),
+                        location: location.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    let mut tags = HashSet::new();
+    let mut deduped = Vec::with_capacity(members_descs.len());
+    let mut group_kept = None;
+    for member in members_descs {
+        let tag = member.tag_num();
+        match member {
+            MemberDesc::Component(_) => {}
+            // Group is followed by its `NumInGroup` counterpart, which
+            // shares the decision made for the group
+            MemberDesc::Simple(_) if group_kept.is_some_and(|(group_tag, _)| group_tag == tag) => {
+                if let Some((_, false)) = group_kept.take() {
+                    continue;
+                }
+            }
+            _ if component_tags.contains(&tag) || !tags.insert(tag) => {
+                diagnostics.warnings.push(Warning::new(
+                    WarningKind::DuplicatedField,
+                    format!(
+                        "Duplicated field `{}` (tag {tag}) of `{owner}` ignored",
+                        field_name(tag)
+                    ),
+                    location,
+                ));
+                if let MemberDesc::Group(_) = member {
+                    group_kept = Some((tag, false));
+                }
+                continue;
+            }
+            MemberDesc::Group(_) => group_kept = Some((tag, true)),
+            _ => {}
+        }
+        deduped.push(member);
+    }
+
+    let mut idents = HashMap::new();
+    for member in deduped.iter_mut().flat_map(MemberDesc::simple_members_mut) {
+        let tag = *idents.entry(member.name().clone()).or_insert(member.tag());
+        if tag != member.tag() {
+            let ident = naming.collision(member.name(), member.tag());
+            member.set_name(ident);
+        }
+    }
+    deduped
+}
+
+/// Warnings and errors found while processing dictionary.
+#[derive(Default)]
+struct Diagnostics {
+    warnings: Vec<Warning>,
+    errors: GenerateErrors,
+}
+
+impl Diagnostics {
+    fn undefined(&mut self, kind: ElementKind, member: &Member, owner: &str) {
+        self.errors.push(GenerateError::Undefined {
+            kind,
+            name: member.name().to_owned(),
+            owner: owner.to_owned(),
+            location: member.location().clone(),
+        });
+    }
+}
+
+/// Convert dictionary `members` of `owner` (message, header, trailer or
+/// group) to `members_descs`.
+///
+/// Members which can't be generated are reported in `diagnostics` and
+/// skipped, so processing continues and all issues are found at once.
+#[expect(clippy::too_many_arguments)]
 fn process_members(
+    owner: &str,
     members: &[Member],
     dictionary: &Dictionary,
+    length_data_pairs: &IndexMap<u16, u16>,
     members_descs: &mut Vec<MemberDesc>,
-    groups: &mut HashMap<String, Struct>,
+    groups: &mut IndexMap<String, Struct>,
+    options: &GeneratorOptions,
+    naming: &Naming,
+    diagnostics: &mut Diagnostics,
 ) {
     let mut members = members.iter().peekable();
     while let Some(member) = members.next() {
         match member.kind() {
             MemberKind::Component => {
-                let component = dictionary
-                    .component(member.name())
-                    .expect("unknown component");
+                let Some(component) = dictionary.component(member.name()) else {
+                    diagnostics.undefined(ElementKind::Component, member, owner);
+                    continue;
+                };
                 if let Some(number_of_elements) = component.number_of_elements() {
-                    let number_of_elements_field = dictionary
-                        .fields_by_name()
-                        .get(number_of_elements.name())
-                        .expect("unknown field");
+                    let Some(number_of_elements_field) =
+                        dictionary.fields_by_name().get(number_of_elements.name())
+                    else {
+                        diagnostics.undefined(ElementKind::Field, number_of_elements, owner);
+                        continue;
+                    };
                     let mut group_members = Vec::new();
-                    process_members(component.members(), dictionary, &mut group_members, groups);
-                    assert_eq!(component.name(), member.name(), "Componen t name mismatch");
+                    process_members(
+                        owner,
+                        component.members(),
+                        dictionary,
+                        length_data_pairs,
+                        &mut group_members,
+                        groups,
+                        options,
+                        naming,
+                        diagnostics,
+                    );
+                    let group_members = dedup_members(
+                        component.name(),
+                        component.location(),
+                        group_members,
+                        dictionary,
+                        naming,
+                        diagnostics,
+                    );
+                    if group_members.is_empty() {
+                        diagnostics.errors.push(GenerateError::Empty {
+                            kind: ElementKind::Group,
+                            name: component.name().to_owned(),
+                            owner: owner.to_owned(),
+                            location: component.location().clone(),
+                        });
+                        continue;
+                    }
 
                     members_descs.push(MemberDesc::group(
                         SimpleMember::num_in_group(
+                            naming,
                             number_of_elements.name(),
                             number_of_elements_field.number(),
                             // When component holding group is required, group is also required, so `num in group` field is also required
@@ -57,6 +253,7 @@ fn process_members(
                             // number_of_elements.required(),
                         ),
                         SimpleMember::group(
+                            naming,
                             member.name(),
                             number_of_elements_field.number(),
                             member.required(),
@@ -64,12 +261,15 @@ fn process_members(
                         ),
                         group_members
                             .iter()
+                            .flat_map(MemberDesc::leaves)
                             .filter(|member| matches!(member, MemberDesc::Simple(_)))
                             //.map(|member| (member.tag_num(), member.required()))
                             .map(|member| member.tag_num())
                             .collect(),
+                        group_members.iter().flat_map(MemberDesc::tags).collect(),
                     ));
                     members_descs.push(MemberDesc::Simple(SimpleMember::group(
+                        naming,
                         member.name(),
                         number_of_elements_field.number(),
                         member.required(),
@@ -78,61 +278,128 @@ fn process_members(
 
                     groups
                         .entry(component.name().to_owned())
-                        .or_insert_with(|| Struct::new(component.name(), group_members, None));
+                        .or_insert_with(|| {
+                            Struct::new(naming.type_(component.name()), group_members, None)
+                        });
+                } else if options.flattens(component.name()) {
+                    process_members(
+                        owner,
+                        component.members(),
+                        dictionary,
+                        length_data_pairs,
+                        members_descs,
+                        groups,
+                        options,
+                        naming,
+                        diagnostics,
+                    );
                 } else {
-                    process_members(component.members(), dictionary, members_descs, groups);
+                    let mut component_members = Vec::new();
+                    process_members(
+                        owner,
+                        component.members(),
+                        dictionary,
+                        length_data_pairs,
+                        &mut component_members,
+                        groups,
+                        options,
+                        naming,
+                        diagnostics,
+                    );
+                    let component_members = dedup_members(
+                        component.name(),
+                        component.location(),
+                        component_members,
+                        dictionary,
+                        naming,
+                        diagnostics,
+                    );
+                    if component_members.is_empty() {
+                        diagnostics.errors.push(GenerateError::Empty {
+                            kind: ElementKind::Component,
+                            name: component.name().to_owned(),
+                            owner: owner.to_owned(),
+                            location: component.location().clone(),
+                        });
+                        continue;
+                    }
+                    members_descs.push(MemberDesc::component(
+                        naming,
+                        component.name(),
+                        member.required(),
+                        component_members.clone(),
+                    ));
+                    groups
+                        .entry(component.name().to_owned())
+                        .or_insert_with(|| {
+                            Struct::component(naming.type_(component.name()), component_members)
+                        });
                 }
             }
             MemberKind::Field => {
-                let field = dictionary
-                    .fields_by_name()
-                    .get(member.name())
-                    .ok_or_else(|| format!("unknown field `{}`", member.name()))
-                    .unwrap();
+                let Some(field) = dictionary.fields_by_name().get(member.name()) else {
+                    diagnostics.undefined(ElementKind::Field, member, owner);
+                    continue;
+                };
 
                 match field.type_() {
                     BasicType::Length => {
                         // Do not skip peeked value, it must be procesed separately
                         // to generate code for TagSpecifiedOutOfRequiredOrdern rejects.
-                        if let Some(next_member) = members.peek() {
-                            let next_field = dictionary
-                                .fields_by_name()
-                                .get(next_member.name())
-                                .expect("unknown field");
-                            if let BasicType::Data | BasicType::XmlData = next_field.type_() {
-                                members_descs.push(MemberDesc::custom_length(
-                                    SimpleMember::length(
-                                        member.name(),
-                                        field.number(),
-                                        member.required(),
-                                    ),
-                                    SimpleMember::field(
-                                        next_member.name(),
-                                        next_field.number(),
-                                        next_member.required(),
-                                        next_field.type_(),
-                                    ),
-                                ));
-                            } else {
-                                members_descs.push(MemberDesc::simple(
+                        let data = members.peek().and_then(|next_member| {
+                            let next_field = dictionary.fields_by_name().get(next_member.name())?;
+                            (length_data_pairs.get(&next_field.number()) == Some(&field.number()))
+                                .then_some((next_member, next_field))
+                        });
+                        if let Some((next_member, next_field)) = data {
+                            members_descs.push(MemberDesc::custom_length(
+                                SimpleMember::length(
+                                    naming,
                                     member.name(),
                                     field.number(),
                                     member.required(),
-                                    field.type_(),
-                                ))
-                            }
+                                ),
+                                SimpleMember::field(
+                                    naming,
+                                    next_member.name(),
+                                    next_field.number(),
+                                    next_member.required(),
+                                    next_field.type_(),
+                                ),
+                            ));
+                        } else {
+                            members_descs.push(MemberDesc::simple(
+                                naming,
+                                member.name(),
+                                field.number(),
+                                member.required(),
+                                field.type_(),
+                            ))
                         }
                     }
                     // Special case, to no create enumerations for boolean values
                     BasicType::Boolean => members_descs.push(MemberDesc::simple(
+                        naming,
                         member.name(),
                         field.number(),
                         member.required(),
                         BasicType::Boolean,
                     )),
+                    BasicType::String
+                        if field.values().is_none()
+                            && options.utf8_tags.contains(&field.number()) =>
+                    {
+                        members_descs.push(MemberDesc::utf8_string(
+                            naming,
+                            member.name(),
+                            field.number(),
+                            member.required(),
+                        ))
+                    }
                     type_ => {
                         if let Some(_values) = field.values() {
                             members_descs.push(MemberDesc::enumeration(
+                                naming,
                                 member.name(),
                                 field.number(),
                                 member.required(),
@@ -140,6 +407,7 @@ fn process_members(
                             ))
                         } else {
                             members_descs.push(MemberDesc::simple(
+                                naming,
                                 member.name(),
                                 field.number(),
                                 member.required(),
@@ -148,86 +416,296 @@ fn process_members(
                         }
                     }
                 }
+
+                if let Some(codec) = options.custom_codecs.get(&field.number()) {
+                    if matches!(field.number(), 8 | 9 | 10 | 34 | 35)
+                        || matches!(
+                            field.type_(),
+                            BasicType::Length
+                                | BasicType::NumInGroup
+                                | BasicType::Data
+                                | BasicType::XmlData
+                        )
+                    {
+                        diagnostics
+                            .errors
+                            .push(GenerateError::UnsupportedCodecField {
+                                name: field.name().to_owned(),
+                                tag: field.number(),
+                                type_: field.type_(),
+                                location: field.location().clone(),
+                            });
+                    } else if let (Ok(codec), Some(member_desc)) =
+                        (codec.parse::<TokenStream>(), members_descs.last_mut())
+                    {
+                        // Invalid path is reported by `Generator::new()`
+                        if member_desc.tag_num() == field.number() {
+                            member_desc.set_codec(codec);
+                        }
+                    }
+                }
             }
         }
     }
 }
 
-impl Generator {
-    pub fn new(dictionary: &Dictionary) -> Generator {
-        let (protocol, version) = if let Some(fixt_version) = dictionary.fixt_version() {
-            ("FIXT", fixt_version)
-        } else if let Some(fix_version) = dictionary.fix_version() {
-            ("FIX", fix_version)
-        } else {
-            panic!("Neither FIX nor FIXT version defined");
+/// Verify that enumerated `values` of field can be represented
+/// in generated enum of `type_`.
+fn check_enum_values(name: &str, type_: BasicType, values: &[Value]) -> Result<(), GenerateError> {
+    for value in values {
+        let valid = match type_ {
+            BasicType::String | BasicType::MultipleStringValue => true,
+            BasicType::Char | BasicType::MultipleCharValue => !value.value().is_empty(),
+            BasicType::Int => value.value().parse::<i64>().is_ok(),
+            BasicType::NumInGroup => value.value().parse::<u8>().is_ok(),
+            type_ => {
+                return Err(GenerateError::InvalidEnumType {
+                    name: name.to_owned(),
+                    type_,
+                    location: value.location().clone(),
+                })
+            }
         };
-        let begin_string = if version.service_pack() == 0 {
-            format!("{}.{}.{}", protocol, version.major(), version.minor())
-        } else {
-            format!(
-                "{}.{}.{}SP{}",
-                protocol,
-                version.major(),
-                version.minor(),
-                version.service_pack()
-            )
+        if !valid {
+            return Err(GenerateError::InvalidValue {
+                name: name.to_owned(),
+                value: value.value().to_owned(),
+                type_,
+                location: value.location().clone(),
+            });
         }
-        .into_bytes();
+    }
+    Ok(())
+}
+
+impl Generator {
+    /// Prepare code generation from `dictionary`.
+    ///
+    /// Fails with all issues found in dictionary, not only the first one.
+    pub fn new(
+        dictionary: &Dictionary,
+        options: &GeneratorOptions,
+    ) -> Result<Generator, GenerateErrors> {
+        let begin_string = dictionary
+            .begin_string()
+            .ok_or(GenerateError::MissingVersion)?
+            .into_bytes();
 
         let mut structs = Vec::new();
-        let mut groups = HashMap::new();
+        let mut groups = IndexMap::new();
+        let mut diagnostics = Diagnostics::default();
+        let naming = Naming::new(&options.naming);
+        // Data fields which can't be paired are reported, their values
+        // couldn't be deserialized
+        let length_data_pairs = dictionary.length_data_pairs().unwrap_or_else(|errors| {
+            for error in errors {
+                diagnostics.errors.push(GenerateError::LengthData(error));
+            }
+            IndexMap::new()
+        });
+        for (tag, codec) in &options.custom_codecs {
+            if codec.trim().is_empty() || codec.parse::<TokenStream>().is_err() {
+                diagnostics.errors.push(GenerateError::InvalidCodecPath {
+                    tag: *tag,
+                    codec: codec.clone(),
+                });
+            }
+        }
 
-        let header = dictionary.header().expect("Missing FIX header definition");
+        let header = dictionary
+            .header()
+            .map_err(|_| GenerateError::MissingSection(ElementKind::Header))?;
+        let trailer = dictionary
+            .trailer()
+            .map_err(|_| GenerateError::MissingSection(ElementKind::Trailer))?;
+        // Undefined fields are reported by `process_members()`
+        let header_field_tag = |name: &str| {
+            dictionary
+                .fields_by_name()
+                .get(name)
+                .map(|field| field.number())
+        };
+        // Header fields required by header definition, but not by some
+        // messages, are optional in `Header` struct and verified by
+        // messages requiring them
+        let relaxed_header_tags: HashSet<u16> = dictionary
+            .messages()
+            .values()
+            .flat_map(|msg| msg.header_overrides())
+            .filter(|header_override| {
+                !header_override.required()
+                    && header
+                        .members()
+                        .iter()
+                        .any(|member| member.name() == header_override.name() && member.required())
+            })
+            .filter_map(|header_override| header_field_tag(header_override.name()))
+            .collect();
+        for msg in dictionary.messages().values() {
+            for header_override in msg.header_overrides() {
+                let always_required = header_field_tag(header_override.name())
+                    .is_some_and(|tag| matches!(tag, 8 | 9 | 10 | 34 | 35));
+                if always_required && !header_override.required() {
+                    diagnostics.errors.push(GenerateError::AlwaysRequired {
+                        name: header_override.name().to_owned(),
+                        owner: msg.name().to_owned(),
+                        location: header_override.location().clone(),
+                    });
+                }
+            }
+        }
         let header_members = {
             let mut header_members = Vec::new();
             process_members(
+                header.name(),
                 header.members(),
                 dictionary,
+                &length_data_pairs,
                 &mut header_members,
                 &mut groups,
+                options,
+                &naming,
+                &mut diagnostics,
             );
-            structs.push(Struct::new(header.name(), header_members.clone(), None));
+            let header_members = dedup_members(
+                header.name(),
+                header.location(),
+                header_members,
+                dictionary,
+                &naming,
+                &mut diagnostics,
+            );
+            let mut header_members = header_members;
+            for member in &mut header_members {
+                if relaxed_header_tags.contains(&member.tag_num()) {
+                    member.relax_required();
+                }
+            }
+            structs.push(Struct::new(
+                Ident::new(&header.name().to_case(Case::UpperCamel), Span::call_site()),
+                header_members.clone(),
+                None,
+            ));
             Rc::new(header_members)
         };
 
-        let trailer = dictionary
-            .trailer()
-            .expect("Missing FIX trailer definition");
         let trailer_members = {
             let mut trailer_members = Vec::new();
             process_members(
+                trailer.name(),
                 trailer.members(),
                 dictionary,
+                &length_data_pairs,
                 &mut trailer_members,
                 &mut groups,
+                options,
+                &naming,
+                &mut diagnostics,
+            );
+            let trailer_members = dedup_members(
+                trailer.name(),
+                trailer.location(),
+                trailer_members,
+                dictionary,
+                &naming,
+                &mut diagnostics,
             );
-            structs.push(Struct::new(trailer.name(), trailer_members.clone(), None));
+            structs.push(Struct::new(
+                Ident::new(&trailer.name().to_case(Case::UpperCamel), Span::call_site()),
+                trailer_members.clone(),
+                None,
+            ));
             Rc::new(trailer_members)
         };
 
+        // Header and trailer fields are deserialized together with message
+        // body, so components reused in header can't be used by messages
+        let header_trailer_tags: HashSet<u16> = header_members
+            .iter()
+            .chain(trailer_members.iter())
+            .flat_map(MemberDesc::leaves)
+            .map(MemberDesc::tag_num)
+            .collect();
+
         for msg in dictionary.messages().values() {
             let mut members_descs = Vec::with_capacity(1 + msg.members().len() + 1);
             {
                 //members_descs.push(MemberDesc::header());
-                process_members(msg.members(), dictionary, &mut members_descs, &mut groups);
+                process_members(
+                    msg.name(),
+                    msg.members(),
+                    dictionary,
+                    &length_data_pairs,
+                    &mut members_descs,
+                    &mut groups,
+                    options,
+                    &naming,
+                    &mut diagnostics,
+                );
                 //members_descs.push(MemberDesc::trailer());
             }
+            let mut members_descs = dedup_members(
+                msg.name(),
+                msg.location(),
+                members_descs,
+                dictionary,
+                &naming,
+                &mut diagnostics,
+            );
+            for member in &mut members_descs {
+                member.set_repeated(&options.repeated_tags);
+            }
+
+            if let Some(tag) = members_descs
+                .iter()
+                .flat_map(MemberDesc::leaves)
+                .map(MemberDesc::tag_num)
+                .find(|tag| header_trailer_tags.contains(tag))
+            {
+                diagnostics.errors.push(GenerateError::HeaderTrailerField {
+                    name: dictionary.fields()[&tag].name().to_owned(),
+                    tag,
+                    owner: msg.name().to_owned(),
+                    location: msg.location().clone(),
+                });
+            }
+
+            // Only optional ones are verified, see `gen_required_check()`
+            let required_header_tags: HashSet<u16> = header
+                .members()
+                .iter()
+                .filter(|member| {
+                    member.kind() == MemberKind::Field
+                        && msg.header_field_required(header, member.name())
+                })
+                .filter_map(|member| header_field_tag(member.name()))
+                .collect();
+            let required_header = header_members
+                .iter()
+                .filter(|member| required_header_tags.contains(&member.tag_num()))
+                .cloned()
+                .collect();
 
             structs.push(Struct::new(
-                msg.name(),
+                naming.type_(msg.name()),
                 members_descs,
                 Some(MessageProperties {
                     msg_cat: msg.msg_cat(),
-                    _msg_type: msg.msg_type(),
+                    msg_type: msg.msg_type(),
                     header_members: header_members.clone(),
                     trailer_members: trailer_members.clone(),
+                    required_header,
                 }),
             ));
         }
 
         structs.extend(groups.into_values());
 
+        let length_data_tags = length_data_pairs
+            .iter()
+            .map(|(data_tag, len_tag)| (*len_tag, *data_tag))
+            .collect();
+
         let mut enums = Vec::new();
         for field in dictionary.fields().values() {
             // Don't map booleans into YES/NO enumeration
@@ -235,8 +713,12 @@ impl Generator {
                 continue;
             }
             if let Some(values) = field.values() {
-                let name = Ident::new(&field.name().to_case(Case::UpperCamel), Span::call_site());
-                enums.push(EnumDesc::new(name, field.type_(), values.to_vec()));
+                if let Err(error) = check_enum_values(field.name(), field.type_(), values) {
+                    diagnostics.errors.push(error);
+                    continue;
+                }
+                let name = naming.type_(field.name());
+                enums.push(EnumDesc::new(name, field));
             }
         }
 
@@ -244,36 +726,90 @@ impl Generator {
         fields.sort_by_key(|f| f.number());
         let (fields_names, fields_numbers) = fields
             .iter()
-            .map(|f| {
-                (
-                    Ident::new(&f.name().to_case(Case::UpperCamel), Span::call_site()),
-                    f.number(),
-                )
-            })
+            .map(|f| (naming.type_(f.name()), f.number()))
             .unzip();
+        let mut basic_types = Vec::new();
+        for field in &fields {
+            if !basic_types.contains(&field.type_()) {
+                basic_types.push(field.type_());
+            }
+        }
 
-        Generator {
+        let generator = Generator {
             begin_string,
+            fingerprint: format!("{:016x}", dictionary.fingerprint()),
             structs,
+            header_members,
+            trailer_members,
             enums,
             fields_names,
             fields_numbers,
+            basic_types,
+            utf8_strings: !options.utf8_tags.is_empty(),
+            length_data_tags,
             reject_reason_overrides: dictionary.reject_reason_overrides().clone(),
+            enum_derives: options.enum_derives,
+            struct_derives: options.struct_derives,
+            required_constructors: options.required_constructors,
+            field_presence: options.field_presence,
+            warnings: diagnostics.warnings,
+            renames: naming.into_renames(),
+        };
+        if diagnostics.errors.is_empty() {
+            Ok(generator)
+        } else {
+            Err(diagnostics.errors)
         }
     }
 
+    /// Non-fatal dictionary issues found while preparing code generation.
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Identifiers generated differently than default naming convention
+    /// would do, because of rename rules, reserved words or collisions.
+    pub fn renames(&self) -> &[Rename] {
+        &self.renames
+    }
+
+    /// Machine-readable (JSON) description of generated enums and structs,
+    /// for generating matching bindings in other languages.
+    pub fn generate_description(&self) -> String {
+        let description = serde_json::json!({
+            "begin_string": String::from_utf8_lossy(&self.begin_string),
+            "fingerprint": self.fingerprint,
+            "enums": self.enums.iter().map(EnumDesc::describe).collect::<Vec<_>>(),
+            "structs": self.structs.iter().map(Struct::describe).collect::<Vec<_>>(),
+            "renames": self.renames.iter().map(|rename| serde_json::json!({
+                "name": rename.name,
+                "kind": rename.kind.to_string(),
+                "ident": rename.ident,
+                "reason": rename.reason.to_string(),
+            })).collect::<Vec<_>>(),
+        });
+        serde_json::to_string_pretty(&description).expect("failed to serialize description")
+    }
+
     pub fn generate_fields(&self) -> TokenStream {
         let mut enums = Vec::new();
         for enum_ in &self.enums {
-            enums.push(enum_.generate());
+            enums.push(enum_.generate(&self.enum_derives));
         }
+        let mut enums_by_tag: Vec<_> = self.enums.iter().collect();
+        enums_by_tag.sort_by_key(|enum_| enum_.tag());
+        let enums_metadata = enums_by_tag.iter().map(|enum_| enum_.generate_metadata());
 
-        let mut reject_reason_map: HashMap<ParseRejectReason, String> = ParseRejectReason::iter()
-            .map(|reject_reason| (reject_reason, reject_reason.as_ref().to_string()))
+        let reject_reason_map: Vec<(ParseRejectReason, String)> = ParseRejectReason::iter()
+            .map(|reject_reason| {
+                let session_reject_reason = self
+                    .reject_reason_overrides
+                    .get(&reject_reason)
+                    .cloned()
+                    .unwrap_or_else(|| reject_reason.as_ref().to_owned());
+                (reject_reason, session_reject_reason)
+            })
             .collect();
-        for (key, value) in self.reject_reason_overrides.clone() {
-            reject_reason_map.insert(key, value);
-        }
 
         let reject_reason_vector: Vec<TokenStream> = reject_reason_map
 	    .iter()
@@ -284,6 +820,41 @@ impl Generator {
 	    })
 	    .collect();
 
+        let codec_tests = self.basic_types.iter().map(|basic_type| {
+            let name = Ident::new(
+                &format!("{basic_type:?}").to_case(Case::Snake),
+                Span::call_site(),
+            );
+            quote! {
+                #[test]
+                fn #name() {
+                    crate::codec_tests::#name()
+                }
+            }
+        });
+        let utf8_string_codec_test = self.utf8_strings.then(|| {
+            quote! {
+                #[test]
+                fn utf8_string() {
+                    crate::codec_tests::utf8_string()
+                }
+            }
+        });
+        let codec_tests = quote! {
+            /// Serializer/deserializer symmetry tests of types used by
+            /// dictionary fields.
+            #[cfg(test)]
+            mod codec_tests {
+                #[test]
+                fn tag_num() {
+                    crate::codec_tests::tag_num()
+                }
+
+                #(#codec_tests)*
+                #utf8_string_codec_test
+            }
+        };
+
         quote! {
         use crate::deserializer::ParseRejectReason;
 
@@ -294,39 +865,125 @@ impl Generator {
         }
 
             #(#enums)*
-        }
-    }
 
-    pub fn generate_groups(&self) -> TokenStream {
-        let mut groups_defs = Vec::new();
+            static ENUM_METADATA: &[EnumMetadata] = &[#(#enums_metadata,)*];
 
-        for struct_ in &self.structs {
-            if struct_.is_group() {
-                groups_defs.push(struct_.generate());
+            /// Metadata of all fields with enumerated values, ordered by tag,
+            /// see [`enum_metadata_by_tag`].
+            pub fn enum_metadata() -> &'static [EnumMetadata] {
+                ENUM_METADATA
             }
+
+            #codec_tests
         }
+    }
 
-        quote! {
+    /// Number of structs (messages, groups, `Header` and `Trailer`), see
+    /// [`Generator::generate_struct`].
+    pub fn structs_count(&self) -> usize {
+        self.structs.len()
+    }
+
+    /// Definition of struct `index`, independent of other structs.
+    ///
+    /// Generators built from the same dictionary and options have the same
+    /// structs, so definitions can be generated by many of them (one per
+    /// thread, as token streams are not `Send`) and passed to
+    /// [`Generator::generate_groups_with`] and
+    /// [`Generator::generate_messages_with`].
+    pub fn generate_struct(&self, index: usize) -> String {
+        self.structs[index]
+            .generate(
+                &self.struct_derives,
+                self.required_constructors,
+                self.field_presence,
+            )
+            .to_string()
+    }
+
+    /// Groups code, with definitions of all structs already generated,
+    /// see [`Generator::generate_struct`].
+    pub fn generate_groups_with(&self, struct_defs: &[String]) -> String {
+        let code = quote! {
         #[allow(unused_imports)]
             use crate::{
                 deserializer::{DeserializeError, Deserializer, ParseRejectReason},
                 fields::{self, basic_types::*, SessionRejectReason},
                 serializer::Serializer,
             };
+        };
 
-            #(#groups_defs)*
+        let mut code = code.to_string();
+        for (struct_, struct_def) in self.structs.iter().zip(struct_defs) {
+            if struct_.is_group() {
+                code.push('\n');
+                code.push_str(struct_def);
+            }
         }
+        code
+    }
+
+    /// Messages code, with structs generated sequentially.
+    #[cfg(test)]
+    pub fn generate_messages(&self) -> String {
+        let struct_defs: Vec<_> = (0..self.structs_count())
+            .map(|index| self.generate_struct(index))
+            .collect();
+        self.generate_messages_with(&struct_defs)
+    }
+
+    /// Messages code, with definitions of all structs already generated,
+    /// see [`Generator::generate_struct`].
+    pub fn generate_messages_with(&self, struct_defs: &[String]) -> String {
+        self.gen_messages(None, struct_defs).0
+    }
+
+    /// Generate messages code with each message, `Header` and `Trailer`
+    /// struct in separate module, so rustc can process them independently.
+    ///
+    /// Returns main code (declaring modules with absolute paths) and
+    /// list of module files to be placed in `dir`.
+    pub fn generate_split_messages(
+        &self,
+        dir: &Path,
+        struct_defs: &[String],
+    ) -> Result<(String, Vec<(PathBuf, String)>), GenerateError> {
+        let dir = dir
+            .to_str()
+            .ok_or_else(|| GenerateError::NonUtf8OutputDir(dir.to_owned()))?;
+        Ok(self.gen_messages(Some(dir), struct_defs))
     }
 
-    pub fn generate_messages(&self) -> TokenStream {
-        let mut structs_defs = Vec::new();
+    fn gen_messages(
+        &self,
+        split_dir: Option<&str>,
+        struct_defs: &[String],
+    ) -> (String, Vec<(PathBuf, String)>) {
+        let mut modules_decls = Vec::new();
+        let mut inline_defs = Vec::new();
+        let mut modules = Vec::new();
         let mut name = Vec::new();
         let mut impl_from_msg = Vec::new();
-        for struct_ in &self.structs {
+        for (struct_, struct_def) in self.structs.iter().zip(struct_defs) {
             let struct_name = struct_.name();
 
             if !struct_.is_group() {
-                structs_defs.push(struct_.generate());
+                if let Some(dir) = split_dir {
+                    let module = Ident::new(
+                        &struct_name.to_string().to_case(Case::Snake),
+                        Span::call_site(),
+                    );
+                    let module_path_str = format!("{dir}{MAIN_SEPARATOR}{module}.rs");
+                    let module_path = PathBuf::from(&module_path_str);
+                    modules_decls.push(quote! {
+                        #[path = #module_path_str]
+                        mod #module;
+                        pub use self::#module::*;
+                    });
+                    modules.push((module_path, format!("use super::*;\n{struct_def}")));
+                } else {
+                    inline_defs.push(struct_def.as_str());
+                }
             }
 
             if struct_.msg_props().is_some() {
@@ -343,6 +1000,7 @@ impl Generator {
         }
 
         let begin_string = Literal::byte_string(&self.begin_string);
+        let fingerprint = Literal::byte_string(self.fingerprint.as_bytes());
         let fields_names = &self.fields_names;
         let fields_names_as_bytes: Vec<_> = self
             .fields_names
@@ -350,27 +1008,79 @@ impl Generator {
             .map(|f| Literal::byte_string(f.to_string().as_bytes()))
             .collect();
         let fields_numbers = &self.fields_numbers;
+        let field_name = |tag: &u16| {
+            let idx = self
+                .fields_numbers
+                .iter()
+                .position(|number| number == tag)
+                .unwrap_or_else(|| panic!("unknown field tag {tag}"));
+            &self.fields_names[idx]
+        };
+        let (length_names, data_names): (Vec<_>, Vec<_>) = self
+            .length_data_tags
+            .iter()
+            .map(|(len_tag, data_tag)| (field_name(len_tag), field_name(data_tag)))
+            .unzip();
+        let field_tag_derives = gen_derives(
+            quote! { Clone, Copy, Debug, Eq, PartialEq, },
+            quote! { Ord, PartialOrd, },
+            &self.enum_derives,
+        );
+        let message_derives = gen_derives(
+            quote! { Clone, Debug, PartialEq, },
+            quote! { PartialOrd, },
+            &self.struct_derives,
+        );
+        let other_message_de =
+            generate_de_other_message(&self.header_members, &self.trailer_members);
+        let mut seen = HashSet::new();
+        let header_tags = self
+            .header_members
+            .iter()
+            .flat_map(MemberDesc::tags)
+            .filter(|tag| seen.insert(*tag))
+            .map(Literal::u16_suffixed)
+            .collect::<Vec<_>>();
+        let mut seen = HashSet::new();
+        let trailer_tags = self
+            .trailer_members
+            .iter()
+            .flat_map(MemberDesc::tags)
+            .filter(|tag| seen.insert(*tag))
+            .map(Literal::u16_suffixed)
+            .collect::<Vec<_>>();
         let fields_numbers_literals = self
             .fields_numbers
             .iter()
             .map(|num| Literal::u16_suffixed(*num))
             .collect::<Vec<_>>();
 
-        quote! {
+        let code = quote! {
         #[allow(unused_imports)]
             use crate::{
-                deserializer::{raw_message, DeserializeError, Deserializer, RawMessage, ParseRejectReason},
+                deserializer::{raw_message, DeserializeError, Deserializer, FieldError, RawMessage, ParseRejectReason},
                 fields::{self, basic_types::*, SessionRejectReason},
                 groups::*,
-                serializer::Serializer,
+                serializer::{SerializeError, Serializer},
             };
             use std::fmt;
 
             pub const BEGIN_STRING: &FixStr = unsafe { FixStr::from_ascii_unchecked(#begin_string) };
 
-            #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-            #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
-            #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+            /// Fingerprint of dictionary messages were generated from, hash of
+            /// normalized dictionary as 16 lowercase hex digits. Builds generated
+            /// from dictionaries encoding messages differently have different
+            /// fingerprints.
+            pub const DICTIONARY_FINGERPRINT: &FixStr =
+                unsafe { FixStr::from_ascii_unchecked(#fingerprint) };
+
+            /// Tags of all header fields, including group members.
+            pub const HEADER_TAGS: &[TagNum] = &[#(#header_tags,)*];
+
+            /// Tags of all trailer fields, including group members.
+            pub const TRAILER_TAGS: &[TagNum] = &[#(#trailer_tags,)*];
+
+            #field_tag_derives
             #[repr(u16)]
             pub enum FieldTag {
                 #(#fields_names = #fields_numbers,)*
@@ -399,6 +1109,15 @@ impl Generator {
                 pub const fn as_fix_str(&self) -> &'static FixStr {
                     unsafe { FixStr::from_ascii_unchecked(self.as_bytes()) }
                 }
+
+                /// Data/XmlData field which length is held by this Length
+                /// field, e.g. RawData<96> for RawDataLength<95>.
+                pub const fn data_field(&self) -> Option<FieldTag> {
+                    match self {
+                        #(FieldTag::#length_names => Some(FieldTag::#data_names),)*
+                        _ => None,
+                    }
+                }
             }
 
             impl ToFixString for FieldTag {
@@ -409,20 +1128,21 @@ impl Generator {
 
             use fields::MsgType;
 
-            #(#structs_defs)*
+            #(#modules_decls)*
 
-            #[derive(Clone, Debug)]
-            #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
-            #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+            #message_derives
             #[allow(clippy::large_enum_variant)]
             pub enum Message {
                 #(#name(#name),)*
+                /// Message of type not defined in dictionary, see `Deserializer::with_other_msg_types()`
+                Other(OtherMessage),
             }
 
             impl Message {
                 fn serialize(&self, serializer: &mut Serializer) {
                     match self {
                         #(Message::#name(msg) => msg.serialize(serializer),)*
+                        Message::Other(msg) => msg.serialize(serializer),
                     }
                 }
 
@@ -430,33 +1150,71 @@ impl Generator {
                     deserializer: &mut Deserializer,
                     begin_string: FixString,
                     body_length: Length,
-                    msg_type: MsgType
+                    msg_type: MsgType,
+                    parsed_header: Option<Box<Header>>,
                 ) -> Result<Box<FixtMessage>, DeserializeError> {
                     match msg_type {
                         #(
-                            MsgType::#name => Ok(#name::deserialize(deserializer, begin_string, body_length, msg_type)?),
+                            MsgType::#name => Ok(#name::deserialize(deserializer, begin_string, body_length, msg_type, parsed_header)?),
                         )*
+                        MsgType::Other(code) => Ok(OtherMessage::deserialize(deserializer, begin_string, body_length, code, parsed_header)?),
                     }
                 }
 
                 pub const fn msg_type(&self) -> MsgType {
                     match self {
                         #(Message::#name(_) => MsgType::#name,)*
+                        Message::Other(msg) => MsgType::Other(msg.msg_type),
+                    }
+                }
+
+                pub const fn msg_cat(&self) -> MsgCat {
+                    match self {
+                        #(Message::#name(_) => #name::MSG_CAT,)*
+                        Message::Other(_) => MsgCat::App,
+                    }
+                }
+
+                /// Tags of all body fields of `msg_type`, including group
+                /// members, `None` for types not defined in dictionary.
+                pub const fn body_tags(msg_type: MsgType) -> Option<&'static [TagNum]> {
+                    match msg_type {
+                        #(MsgType::#name => Some(#name::BODY_TAGS),)*
+                        MsgType::Other(_) => None,
+                    }
+                }
+            }
+
+            impl fields::Fields for Message {
+                fn visit_fields<'a>(&'a self, visitor: &mut dyn FnMut(TagNum, fields::FieldRef<'a>)) {
+                    match self {
+                        #(Message::#name(msg) => fields::Fields::visit_fields(msg, visitor),)*
+                        Message::Other(msg) => fields::Fields::visit_fields(msg, visitor),
                     }
                 }
+            }
 
+            impl MsgType {
+                /// Category of message of this type.
                 pub const fn msg_cat(&self) -> MsgCat {
                     match self {
-                        #(Message::#name(msg) => msg.msg_cat(),)*
+                        #(MsgType::#name => #name::MSG_CAT,)*
+                        MsgType::Other(_) => MsgCat::App,
                     }
                 }
             }
 
+            #other_message_de
+
             #(#impl_from_msg)*
 
-            #[derive(Clone, Debug)]
-            #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
-            #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+            impl From<OtherMessage> for Message {
+                fn from(msg: OtherMessage) -> Message {
+                    Message::Other(msg)
+                }
+            }
+
+            #message_derives
             pub struct FixtMessage {
                 pub header: Box<Header>,
                 pub body: Box<Message>,
@@ -472,9 +1230,43 @@ impl Generator {
                     serializer.take()
                 }
 
+                /// Serialize message using `serializer` configured with
+                /// non-default policies, e.g. `Serializer::with_empty_strings()`.
+                pub fn serialize_with(&self, mut serializer: Serializer) -> Result<Vec<u8>, SerializeError> {
+                    self.header.serialize(&mut serializer);
+                    self.body.serialize(&mut serializer);
+                    self.trailer.serialize(&mut serializer);
+                    serializer.finish()
+                }
+
                 pub fn deserialize(mut deserializer: Deserializer) -> Result<Box<FixtMessage>, DeserializeError> {
+                    FixtMessage::deserialize_from(&mut deserializer)
+                }
+
+                /// Deserialize message, skipping invalid values of optional
+                /// fields in application messages body instead of rejecting
+                /// the whole message, see `Deserializer::with_field_errors()`.
+                ///
+                /// When message is rejected anyway, the first skipped field
+                /// is reported, as it would be without tolerating field errors.
+                pub fn deserialize_with_field_errors(
+                    deserializer: Deserializer,
+                ) -> Result<(Box<FixtMessage>, Vec<FieldError>), DeserializeError> {
+                    let mut deserializer = deserializer.with_field_errors();
+                    let result = FixtMessage::deserialize_from(&mut deserializer);
+                    let field_errors = deserializer.take_field_errors();
+                    match (result, field_errors.first()) {
+                        (Ok(msg), _) => Ok((msg, field_errors)),
+                        (Err(_), Some(field_error)) => {
+                            Err(deserializer.reject(Some(field_error.tag), field_error.reason))
+                        }
+                        (Err(err), None) => Err(err),
+                    }
+                }
+
+                fn deserialize_from(deserializer: &mut Deserializer) -> Result<Box<FixtMessage>, DeserializeError> {
                     let begin_string = deserializer.begin_string();
-                    if begin_string != BEGIN_STRING {
+                    if begin_string != BEGIN_STRING && !deserializer.accepts_begin_string(&begin_string) {
                         return Err(DeserializeError::GarbledMessage("begin string mismatch".into()));
                     }
 
@@ -487,15 +1279,57 @@ impl Generator {
                     {
                         let msg_type_range = deserializer.deserialize_msg_type()?;
                         let msg_type_fixstr = deserializer.range_to_fixstr(msg_type_range);
-                        let Ok(msg_type) = MsgType::try_from(msg_type_fixstr) else {
-                            return Err(deserializer.reject(Some(35), ParseRejectReason::InvalidMsgtype));
-                        };
-                        msg_type
+                        match MsgType::try_from(msg_type_fixstr) {
+                            Ok(msg_type) => msg_type,
+                            Err(_) => match MsgTypeCode::from_fix_str(msg_type_fixstr) {
+                                Some(code) if deserializer.accepts_other_msg_types() => MsgType::Other(code),
+                                _ => return Err(deserializer.reject(Some(35), ParseRejectReason::InvalidMsgtype)),
+                            },
+                        }
                     } else {
                         return Err(DeserializeError::GarbledMessage("MsgType<35> not third tag".into()));
                     };
 
-                    Message::deserialize(&mut deserializer, begin_string, body_length, msg_type)
+                    let msg = Message::deserialize(deserializer, begin_string, body_length, msg_type, None)?;
+                    deserializer.check_required_tags(HEADER_TAGS, TRAILER_TAGS)?;
+                    Ok(msg)
+                }
+
+                /// Deserialize message body and trailer, taking already
+                /// parsed `header` instead of deserializing it again,
+                /// e.g. when message was routed by its header.
+                ///
+                /// Header fields are skipped without decoding, `header`
+                /// is expected to be parsed from the same message.
+                pub fn deserialize_body(
+                    mut deserializer: Deserializer,
+                    header: Header,
+                ) -> Result<Box<FixtMessage>, DeserializeError> {
+                    if let MsgType::Other(_) = header.msg_type {
+                        if !deserializer.accepts_other_msg_types() {
+                            return Err(deserializer.reject(Some(35), ParseRejectReason::InvalidMsgtype));
+                        }
+                    }
+                    deserializer.set_seq_num(header.msg_seq_num);
+                    deserializer.skip_header(HEADER_TAGS);
+                    let msg = Message::deserialize(
+                        &mut deserializer,
+                        header.begin_string.clone(),
+                        header.body_length,
+                        header.msg_type,
+                        Some(Box::new(header)),
+                    )?;
+                    deserializer.check_required_tags(HEADER_TAGS, TRAILER_TAGS)?;
+                    Ok(msg)
+                }
+
+                /// Deserialize message with already parsed `header`, see
+                /// [`FixtMessage::deserialize_body`].
+                pub fn from_raw_with_header(
+                    header: Header,
+                    raw_message: RawMessage,
+                ) -> Result<Box<FixtMessage>, DeserializeError> {
+                    FixtMessage::deserialize_body(Deserializer::from_raw_message(raw_message), header)
                 }
 
                 pub fn from_raw_message(raw_message: RawMessage) -> Result<Box<FixtMessage>, DeserializeError> {
@@ -528,7 +1362,22 @@ impl Generator {
                     self.body.msg_cat()
                 }
             }
+
+            impl fields::Fields for FixtMessage {
+                fn visit_fields<'a>(&'a self, visitor: &mut dyn FnMut(TagNum, fields::FieldRef<'a>)) {
+                    fields::Fields::visit_fields(&*self.header, visitor);
+                    fields::Fields::visit_fields(&*self.body, visitor);
+                    fields::Fields::visit_fields(&*self.trailer, visitor);
+                }
+            }
+        };
+
+        let mut code = code.to_string();
+        for struct_def in inline_defs {
+            code.push('\n');
+            code.push_str(struct_def);
         }
+        (code, modules)
     }
 }
 
@@ -551,3 +1400,674 @@ pub fn _formatted(tokens_stream: &TokenStream) {
     std::io::stdout().write_all(&output.stdout).unwrap();
     std::io::stderr().write_all(&output.stderr).unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use easyfix_dictionary::{Dictionary, LengthDataError, WarningKind};
+    use proc_macro2::Literal;
+
+    use super::Generator;
+    use crate::{ElementKind, GenerateError, GeneratorOptions, RenameReason};
+
+    const XML: &str = "<fix type='FIX' major='4' minor='4' servicepack='0'>
+         <header>
+          <field name='MsgType' required='Y'/>
+          <field name='SenderCompID' required='Y'/>
+          <field name='OnBehalfOfCompID' required='N'/>
+         </header>
+         <trailer><field name='CheckSum' required='Y'/></trailer>
+         <messages>
+          <message name='Heartbeat' msgtype='0' msgcat='admin'/>
+          <message name='Logon' msgtype='A' msgcat='admin'>
+           <header><field name='OnBehalfOfCompID' required='Y'/></header>
+          </message>
+          <message name='News' msgtype='B' msgcat='app'>
+           <header><field name='SenderCompID' required='N'/></header>
+          </message>
+         </messages>
+         <components/>
+         <fields>
+          <field number='49' name='SenderCompID' type='STRING'/>
+          <field number='115' name='OnBehalfOfCompID' type='STRING'/>
+          <field number='35' name='MsgType' type='STRING'>
+           <value enum='0' description='HEARTBEAT'/>
+           <value enum='A' description='LOGON'/>
+           <value enum='B' description='NEWS'/>
+          </field>
+          <field number='10' name='CheckSum' type='STRING'/>
+         </fields>
+        </fix>";
+
+    #[test]
+    fn header_overrides() {
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(XML).unwrap();
+        let generator = Generator::new(&dictionary, &GeneratorOptions::new()).unwrap();
+
+        // Not required by every message
+        let description: serde_json::Value =
+            serde_json::from_str(&generator.generate_description()).unwrap();
+        let header = &description["structs"][0];
+        assert_eq!(header["name"], "Header");
+        assert_eq!(header["members"][1]["name"], "sender_comp_id");
+        assert_eq!(header["members"][1]["required"], false);
+
+        let code = generator.generate_messages().to_string();
+        let check = |field: &str| format!("if header . {field} . is_none ()");
+        // Code from `deserialize()` of `msg` up to the next one (or the end)
+        let deserialize_fn = |msg: &str| {
+            let start = code
+                .find(&format!("impl {msg} {{"))
+                .expect("message not generated");
+            let start = start + code[start..].find("fn deserialize").unwrap();
+            let end = code[start + 1..]
+                .find("fn deserialize")
+                .map_or(code.len(), |len| start + len);
+            &code[start..end]
+        };
+        assert!(deserialize_fn("Heartbeat").contains(&check("sender_comp_id")));
+        assert!(!deserialize_fn("Heartbeat").contains(&check("on_behalf_of_comp_id")));
+        assert!(deserialize_fn("Logon").contains(&check("sender_comp_id")));
+        assert!(deserialize_fn("Logon").contains(&check("on_behalf_of_comp_id")));
+        assert!(!deserialize_fn("News").contains(&check("sender_comp_id")));
+    }
+
+    #[test]
+    fn dictionary_fingerprint() {
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(XML).unwrap();
+        let generator = Generator::new(&dictionary, &GeneratorOptions::new()).unwrap();
+        let fingerprint = format!("{:016x}", dictionary.fingerprint());
+
+        let description: serde_json::Value =
+            serde_json::from_str(&generator.generate_description()).unwrap();
+        assert_eq!(description["fingerprint"], fingerprint);
+
+        let code = generator.generate_messages().to_string();
+        let literal = Literal::byte_string(fingerprint.as_bytes()).to_string();
+        assert!(code.contains(&format!(
+            "pub const DICTIONARY_FINGERPRINT : & FixStr = unsafe {{ FixStr :: from_ascii_unchecked ({literal}) }}"
+        )));
+    }
+
+    #[test]
+    fn all_errors_reported() {
+        let xml = "<fix type='FIX' major='4' minor='4' servicepack='0'>
+             <header><field name='MsgType' required='Y'/></header>
+             <trailer><field name='CheckSum' required='Y'/></trailer>
+             <messages>
+              <message name='Heartbeat' msgtype='0' msgcat='admin'>
+               <header><field name='MsgType' required='N'/></header>
+              </message>
+              <message name='News' msgtype='B' msgcat='app'>
+               <field name='Headline' required='Y'/>
+               <component name='Instrument' required='N'/>
+               <field name='Urgency' required='N'/>
+              </message>
+             </messages>
+             <components/>
+             <fields>
+              <field number='35' name='MsgType' type='STRING'>
+               <value enum='0' description='HEARTBEAT'/>
+               <value enum='B' description='NEWS'/>
+              </field>
+              <field number='61' name='Urgency' type='INT'>
+               <value enum='N' description='NORMAL'/>
+              </field>
+              <field number='10' name='CheckSum' type='STRING'/>
+             </fields>
+            </fix>";
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(xml).unwrap();
+        let Err(errors) = Generator::new(&dictionary, &GeneratorOptions::new()) else {
+            panic!("broken dictionary accepted");
+        };
+
+        assert_eq!(errors.errors().len(), 4, "{errors}");
+        assert!(matches!(
+            &errors.errors()[0],
+            GenerateError::AlwaysRequired { name, owner, .. }
+                if name == "MsgType" && owner == "Heartbeat"
+        ));
+        assert!(matches!(
+            &errors.errors()[1],
+            GenerateError::Undefined { kind: ElementKind::Field, name, owner, .. }
+                if name == "Headline" && owner == "News"
+        ));
+        assert!(matches!(
+            &errors.errors()[2],
+            GenerateError::Undefined { kind: ElementKind::Component, name, .. }
+                if name == "Instrument"
+        ));
+        assert!(matches!(
+            &errors.errors()[3],
+            GenerateError::InvalidValue { name, value, .. } if name == "Urgency" && value == "N"
+        ));
+    }
+
+    #[test]
+    fn header_component_field_reused_by_message() {
+        let xml = "<fix type='FIX' major='4' minor='4' servicepack='0'>
+             <header>
+              <field name='MsgType' required='Y'/>
+              <component name='Routing' required='N'/>
+             </header>
+             <trailer><field name='CheckSum' required='Y'/></trailer>
+             <messages>
+              <message name='Heartbeat' msgtype='0' msgcat='admin'/>
+              <message name='News' msgtype='B' msgcat='app'>
+               <field name='Headline' required='Y'/>
+               <field name='DeliverToCompID' required='N'/>
+              </message>
+             </messages>
+             <components>
+              <component name='Routing'>
+               <field name='DeliverToCompID' required='N'/>
+              </component>
+             </components>
+             <fields>
+              <field number='35' name='MsgType' type='STRING'>
+               <value enum='0' description='HEARTBEAT'/>
+               <value enum='B' description='NEWS'/>
+              </field>
+              <field number='128' name='DeliverToCompID' type='STRING'/>
+              <field number='148' name='Headline' type='STRING'/>
+              <field number='10' name='CheckSum' type='STRING'/>
+             </fields>
+            </fix>";
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(xml).unwrap();
+        let Err(errors) = Generator::new(&dictionary, &GeneratorOptions::new()) else {
+            panic!("header field reused by message accepted");
+        };
+        assert_eq!(errors.errors().len(), 1, "{errors}");
+        assert!(matches!(
+            &errors.errors()[0],
+            GenerateError::HeaderTrailerField { name, tag: 128, owner, .. }
+                if name == "DeliverToCompID" && owner == "News"
+        ));
+
+        let xml = xml.replace(
+            "<field name='DeliverToCompID' required='N'/>\n              </message>",
+            "</message>",
+        );
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(&xml).unwrap();
+        let generator = Generator::new(&dictionary, &GeneratorOptions::new()).unwrap();
+        let description: serde_json::Value =
+            serde_json::from_str(&generator.generate_description()).unwrap();
+        let header = &description["structs"][0];
+        assert_eq!(header["name"], "Header");
+        assert_eq!(header["members"][1]["name"], "deliver_to_comp_id");
+    }
+
+    #[test]
+    fn data_fields_paired_by_dictionary() {
+        let xml = "<fix type='FIX' major='4' minor='4' servicepack='0'>
+             <header><field name='MsgType' required='Y'/></header>
+             <trailer><field name='CheckSum' required='Y'/></trailer>
+             <messages>
+              <message name='News' msgtype='B' msgcat='app'>
+               <field name='RawDataLength' required='N'/>
+               <field name='RawData' required='N'/>
+              </message>
+              <message name='Email' msgtype='C' msgcat='app'>
+               <field name='RawData' required='N'/>
+              </message>
+             </messages>
+             <components/>
+             <fields>
+              <field number='35' name='MsgType' type='STRING'>
+               <value enum='B' description='NEWS'/>
+               <value enum='C' description='EMAIL'/>
+              </field>
+              <field number='95' name='RawDataLength' type='LENGTH'/>
+              <field number='96' name='RawData' type='DATA'/>
+              <field number='10' name='CheckSum' type='STRING'/>
+             </fields>
+            </fix>";
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(xml).unwrap();
+        let Err(errors) = Generator::new(&dictionary, &GeneratorOptions::new()) else {
+            panic!("orphan Data field accepted");
+        };
+        assert_eq!(errors.errors().len(), 1, "{errors}");
+        assert!(matches!(
+            &errors.errors()[0],
+            GenerateError::LengthData(LengthDataError::Orphan { data, owner, .. })
+                if data == "RawData" && owner == "Email"
+        ));
+
+        let xml = xml.replace(
+            "<message name='Email' msgtype='C' msgcat='app'>",
+            "<message name='Email' msgtype='C' msgcat='app'>
+               <field name='RawDataLength' required='N'/>",
+        );
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(&xml).unwrap();
+        let generator = Generator::new(&dictionary, &GeneratorOptions::new()).unwrap();
+        // Length written from Data field value in both messages
+        let code = generator.generate_messages().to_string();
+        assert_eq!(
+            code.matches("serializer . serialize_length (& (raw_data . len () as u16))")
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn missing_admin_messages_generated() {
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(XML).unwrap();
+        dictionary.add_missing_admin_messages().unwrap();
+        let generator = Generator::new(&dictionary, &GeneratorOptions::new()).unwrap();
+
+        let description: serde_json::Value =
+            serde_json::from_str(&generator.generate_description()).unwrap();
+        let messages: Vec<_> = description["structs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|desc| !desc["msg_type"].is_null())
+            .map(|desc| desc["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "Heartbeat",
+                "Logon",
+                "News",
+                "TestRequest",
+                "ResendRequest",
+                "Reject",
+                "SequenceReset",
+                "Logout"
+            ]
+        );
+        let code = generator.generate_messages().to_string();
+        assert!(code.contains("pub struct SequenceReset"));
+    }
+
+    #[test]
+    fn components_flattened_selectively() {
+        let xml = "<fix type='FIX' major='4' minor='4' servicepack='0'>
+             <header><field name='MsgType' required='Y'/></header>
+             <trailer><field name='CheckSum' required='Y'/></trailer>
+             <messages>
+              <message name='News' msgtype='B' msgcat='app'>
+               <field name='Headline' required='Y'/>
+               <component name='Instrument' required='N'/>
+               <component name='Issuer' required='N'/>
+              </message>
+             </messages>
+             <components>
+              <component name='Instrument'>
+               <field name='Symbol' required='Y'/>
+              </component>
+              <component name='Issuer'>
+               <field name='IssuerName' required='Y'/>
+              </component>
+             </components>
+             <fields>
+              <field number='35' name='MsgType' type='STRING'>
+               <value enum='B' description='NEWS'/>
+              </field>
+              <field number='55' name='Symbol' type='STRING'/>
+              <field number='106' name='IssuerName' type='STRING'/>
+              <field number='148' name='Headline' type='STRING'/>
+              <field number='10' name='CheckSum' type='STRING'/>
+             </fields>
+            </fix>";
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(xml).unwrap();
+        let members = |options: GeneratorOptions| {
+            let generator = Generator::new(&dictionary, &options).unwrap();
+            let description: serde_json::Value =
+                serde_json::from_str(&generator.generate_description()).unwrap();
+            description["structs"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|desc| desc["name"] == "News")
+                .unwrap()["members"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|member| member["name"].as_str().unwrap().to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            members(GeneratorOptions::new().flatten_component("Issuer", false)),
+            ["headline", "symbol", "issuer"]
+        );
+        assert_eq!(
+            members(
+                GeneratorOptions::new()
+                    .flatten_components(false)
+                    .flatten_component("Instrument", true)
+            ),
+            ["headline", "symbol", "issuer"]
+        );
+        assert_eq!(
+            members(GeneratorOptions::new().flatten_components(false)),
+            ["headline", "instrument", "issuer"]
+        );
+    }
+
+    #[test]
+    fn field_presence() {
+        let xml = "<fix type='FIX' major='4' minor='4' servicepack='0'>
+             <header><field name='MsgType' required='Y'/></header>
+             <trailer><field name='CheckSum' required='Y'/></trailer>
+             <messages>
+              <message name='Heartbeat' msgtype='0' msgcat='admin'>
+               <field name='TestReqID' required='Y'/>
+              </message>
+              <message name='News' msgtype='B' msgcat='app'>
+               <field name='Headline' required='Y'/>
+               <field name='Urgency' required='N'/>
+              </message>
+             </messages>
+             <components/>
+             <fields>
+              <field number='35' name='MsgType' type='STRING'>
+               <value enum='0' description='HEARTBEAT'/>
+               <value enum='B' description='NEWS'/>
+              </field>
+              <field number='61' name='Urgency' type='CHAR'/>
+              <field number='112' name='TestReqID' type='STRING'/>
+              <field number='148' name='Headline' type='STRING'/>
+              <field number='10' name='CheckSum' type='STRING'/>
+             </fields>
+            </fix>";
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(xml).unwrap();
+
+        let generator = Generator::new(&dictionary, &GeneratorOptions::new()).unwrap();
+        let code = generator.generate_messages().to_string();
+        assert!(!code.contains("PRESENCE_TAGS"));
+        assert!(!code.contains("fn is_set"));
+
+        let options = GeneratorOptions::new().field_presence(true);
+        let generator = Generator::new(&dictionary, &options).unwrap();
+        let code = generator.generate_messages().to_string();
+        assert!(code.contains("pub const PRESENCE_TAGS : & [TagNum] = & [112u16 ,] ;"));
+        assert!(code.contains("matches ! (tag , 112u16)"));
+        assert!(code.contains(
+            "match tag { 148u16 => true , 61u16 => self . urgency . is_some () , _ => false , }"
+        ));
+    }
+
+    #[test]
+    fn duplicated_members_dropped() {
+        let xml = "<fix type='FIX' major='4' minor='4' servicepack='0'>
+             <header><field name='MsgType' required='Y'/></header>
+             <trailer><field name='CheckSum' required='Y'/></trailer>
+             <messages>
+              <message name='News' msgtype='B' msgcat='app'>
+               <field name='Headline' required='Y'/>
+               <field name='Symbol' required='N'/>
+               <component name='Instrument' required='N'/>
+               <field name='Headline' required='Y'/>
+              </message>
+             </messages>
+             <components>
+              <component name='Instrument'>
+               <field name='Symbol' required='Y'/>
+               <field name='SecurityID' required='N'/>
+              </component>
+             </components>
+             <fields>
+              <field number='35' name='MsgType' type='STRING'>
+               <value enum='B' description='NEWS'/>
+              </field>
+              <field number='48' name='SecurityID' type='STRING'/>
+              <field number='55' name='Symbol' type='STRING'/>
+              <field number='148' name='Headline' type='STRING'/>
+              <field number='10' name='CheckSum' type='STRING'/>
+             </fields>
+            </fix>";
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(xml).unwrap();
+        let generate = |options: GeneratorOptions| {
+            let generator = Generator::new(&dictionary, &options).unwrap();
+            let description: serde_json::Value =
+                serde_json::from_str(&generator.generate_description()).unwrap();
+            let members = description["structs"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|desc| desc["name"] == "News")
+                .unwrap()["members"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|member| member["name"].as_str().unwrap().to_owned())
+                .collect::<Vec<_>>();
+            (members, generator.warnings().to_vec())
+        };
+
+        // First definition kept
+        let (members, warnings) = generate(GeneratorOptions::new());
+        assert_eq!(members, ["headline", "symbol", "security_id"]);
+        assert_eq!(warnings.len(), 2, "{warnings:?}");
+        assert!(warnings
+            .iter()
+            .all(|warning| warning.kind() == WarningKind::DuplicatedField));
+        assert_eq!(
+            warnings[0].message(),
+            "Duplicated field `Symbol` (tag 55) of `News` ignored"
+        );
+        assert_eq!(
+            warnings[1].message(),
+            "Duplicated field `Headline` (tag 148) of `News` ignored"
+        );
+        assert_eq!(warnings[0].location().line(), 5);
+
+        // Fields of not flattened component take precedence
+        let (members, warnings) = generate(GeneratorOptions::new().flatten_components(false));
+        assert_eq!(members, ["headline", "instrument"]);
+        assert_eq!(warnings.len(), 2, "{warnings:?}");
+        assert_eq!(
+            warnings[0].message(),
+            "Duplicated field `Symbol` (tag 55) of `News` ignored"
+        );
+    }
+
+    #[test]
+    fn renames() {
+        let xml = "<fix type='FIX' major='4' minor='4' servicepack='0'>
+             <header><field name='MsgType' required='Y'/></header>
+             <trailer><field name='CheckSum' required='Y'/></trailer>
+             <messages>
+              <message name='Quote' msgtype='S' msgcat='app'>
+               <field name='Type' required='N'/>
+               <field name='Yield' required='N'/>
+               <field name='SecurityID' required='N'/>
+               <field name='SecurityId' required='N'/>
+              </message>
+             </messages>
+             <components/>
+             <fields>
+              <field number='35' name='MsgType' type='STRING'>
+               <value enum='S' description='QUOTE'/>
+              </field>
+              <field number='48' name='SecurityID' type='STRING'/>
+              <field number='236' name='Yield' type='PERCENTAGE'/>
+              <field number='5000' name='Type' type='STRING'/>
+              <field number='5001' name='SecurityId' type='STRING'/>
+              <field number='10' name='CheckSum' type='STRING'/>
+             </fields>
+            </fix>";
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(xml).unwrap();
+        let options = GeneratorOptions::new().rename("Yield", "YieldValue");
+        let generator = Generator::new(&dictionary, &options).unwrap();
+
+        let description: serde_json::Value =
+            serde_json::from_str(&generator.generate_description()).unwrap();
+        let quote = &description["structs"][2];
+        assert_eq!(quote["name"], "Quote");
+        let members: Vec<_> = quote["members"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|member| member["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            members,
+            ["type_", "yield_value", "security_id", "security_id_5001"]
+        );
+
+        let reasons: Vec<_> = generator
+            .renames()
+            .iter()
+            .map(|rename| (rename.name.as_str(), rename.ident.as_str(), rename.reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            [
+                ("Type", "type_", RenameReason::Reserved),
+                ("Yield", "yield_value", RenameReason::Rule),
+                ("Yield", "YieldValue", RenameReason::Rule),
+                ("security_id", "security_id_5001", RenameReason::Collision),
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_codecs() {
+        let xml = "<fix type='FIX' major='4' minor='4' servicepack='0'>
+             <header>
+              <field name='MsgType' required='Y'/>
+              <field name='MsgSeqNum' required='Y'/>
+             </header>
+             <trailer><field name='CheckSum' required='Y'/></trailer>
+             <messages>
+              <message name='News' msgtype='B' msgcat='app'>
+               <field name='Urgency' required='Y'/>
+               <field name='Quantity' required='N'/>
+               <field name='RawDataLength' required='N'/>
+               <field name='RawData' required='N'/>
+              </message>
+             </messages>
+             <components/>
+             <fields>
+              <field number='35' name='MsgType' type='STRING'>
+               <value enum='B' description='NEWS'/>
+              </field>
+              <field number='34' name='MsgSeqNum' type='SEQNUM'/>
+              <field number='61' name='Urgency' type='INT'/>
+              <field number='53' name='Quantity' type='QTY'/>
+              <field number='95' name='RawDataLength' type='LENGTH'/>
+              <field number='96' name='RawData' type='DATA'/>
+              <field number='10' name='CheckSum' type='STRING'/>
+             </fields>
+            </fix>";
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(xml).unwrap();
+
+        let options = GeneratorOptions::new()
+            .custom_codec(61, "crate::codec::ZeroPadded<6>")
+            .custom_codec(53, "Lots");
+        let generator = Generator::new(&dictionary, &options).unwrap();
+        let description: serde_json::Value =
+            serde_json::from_str(&generator.generate_description()).unwrap();
+        let news = &description["structs"][2];
+        assert_eq!(news["name"], "News");
+        assert_eq!(
+            news["members"][0]["codec"],
+            "crate :: codec :: ZeroPadded < 6 >"
+        );
+        assert_eq!(news["members"][1]["codec"], "Lots");
+        let code = generator.generate_messages().to_string();
+        let codec = "< crate :: codec :: ZeroPadded < 6 > as crate :: codec :: FieldCodec < Int >>";
+        assert!(code.contains(&format!(
+            "{codec} :: serialize (serializer , & self . urgency)"
+        )));
+        assert!(code.contains(&format!("{codec} :: deserialize (deserializer)")));
+        assert!(code.contains(
+            "< Lots as crate :: codec :: FieldCodec < Qty >> :: serialize (serializer , quantity)"
+        ));
+
+        let options = GeneratorOptions::new()
+            .custom_codec(34, "crate::codec::ZeroPadded<6>")
+            .custom_codec(95, "Lots")
+            .custom_codec(61, "Lots[");
+        let Err(errors) = Generator::new(&dictionary, &options) else {
+            panic!("unsupported codecs accepted");
+        };
+        assert_eq!(errors.errors().len(), 3, "{errors}");
+        assert!(errors.errors().iter().any(|error| matches!(
+            error,
+            GenerateError::InvalidCodecPath { tag: 61, codec } if codec == "Lots["
+        )));
+        for unsupported in [34, 95] {
+            assert!(errors.errors().iter().any(|error| matches!(
+                error,
+                GenerateError::UnsupportedCodecField { tag, .. } if *tag == unsupported
+            )));
+        }
+    }
+
+    #[test]
+    fn enum_display_names() {
+        let xml = XML.replace(
+            "<field number='10' name='CheckSum' type='STRING'/>",
+            "<field number='10' name='CheckSum' type='STRING'/>
+             <field number='39' name='OrdStatus' type='CHAR'>
+              <value enum='1' description='PARTIALLY_FILLED'/>
+              <value enum='A' description='PendingNew'/>
+             </field>",
+        );
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(&xml).unwrap();
+        let generator = Generator::new(&dictionary, &GeneratorOptions::new()).unwrap();
+
+        let description: serde_json::Value =
+            serde_json::from_str(&generator.generate_description()).unwrap();
+        let ord_status = description["enums"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|enum_| enum_["name"] == "OrdStatus")
+            .unwrap();
+        assert_eq!(ord_status["values"][0]["display_name"], "Partially filled");
+        assert_eq!(ord_status["values"][1]["display_name"], "Pending new");
+
+        let code = generator.generate_fields().to_string();
+        assert!(code.contains("OrdStatus :: PartiallyFilled => \"Partially filled\""));
+        // Registry ordered by tag
+        let msg_type = code.find("name : \"MsgType\" , tag : 35u16").unwrap();
+        let ord_status = code.find("name : \"OrdStatus\" , tag : 39u16").unwrap();
+        assert!(msg_type < ord_status);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn split_messages_into_non_utf8_dir() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::Path};
+
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_legacy_fix_xml(XML).unwrap();
+        let generator = Generator::new(&dictionary, &GeneratorOptions::new()).unwrap();
+        let struct_defs: Vec<_> = (0..generator.structs_count())
+            .map(|index| generator.generate_struct(index))
+            .collect();
+
+        let dir = Path::new(OsStr::from_bytes(b"/tmp/messages\xff"));
+        assert_eq!(
+            generator.generate_split_messages(dir, &struct_defs),
+            Err(GenerateError::NonUtf8OutputDir(dir.to_owned()))
+        );
+
+        let (code, modules) = generator
+            .generate_split_messages(Path::new("/tmp/messages"), &struct_defs)
+            .unwrap();
+        assert!(code.contains("# [path = \"/tmp/messages/news.rs\"]"));
+        assert!(modules
+            .iter()
+            .any(|(path, _)| path == Path::new("/tmp/messages/news.rs")));
+    }
+}
@@ -1,25 +1,75 @@
 use convert_case::{Case, Casing};
-use easyfix_dictionary::{BasicType, Value};
+use easyfix_dictionary::{BasicType, Field, Value};
 use proc_macro2::{Ident, Literal, Span, TokenStream};
 use quote::quote;
+use serde_json::{json, Value as JsonValue};
 
-use crate::gen::member::Type;
+use crate::{
+    gen::{gen_derives, member::Type},
+    Derives,
+};
+
+fn variant_ident(value: &Value) -> String {
+    let mut variant_name = value.description().to_case(Case::UpperCamel);
+    if variant_name.as_bytes()[0].is_ascii_digit() {
+        variant_name.insert(0, '_');
+    }
+    variant_name
+}
+
+/// Human-readable name of value, e.g. `Partially filled` for
+/// `PARTIALLY_FILLED` description.
+pub(crate) fn display_name(value: &Value) -> String {
+    let description = value.description();
+    let words = if description.bytes().any(|b| b.is_ascii_lowercase()) {
+        // CamelCase descriptions of custom dictionaries
+        description.to_case(Case::Lower)
+    } else {
+        description.replace('_', " ").to_lowercase()
+    };
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => words,
+    }
+}
 
 pub struct EnumDesc {
     name: Ident,
+    field_name: String,
+    tag: u16,
     type_: BasicType,
     // (VarianName, VariantValue, VariantValueAsBytes)
     values: Vec<Value>,
 }
 impl EnumDesc {
-    pub fn new(name: Ident, type_: BasicType, values: Vec<Value>) -> EnumDesc {
+    pub fn new(name: Ident, field: &Field) -> EnumDesc {
         EnumDesc {
             name,
-            type_,
-            values,
+            field_name: field.name().to_owned(),
+            tag: field.number(),
+            type_: field.type_(),
+            values: field.values().unwrap_or_default().to_vec(),
         }
     }
 
+    pub fn tag(&self) -> u16 {
+        self.tag
+    }
+
+    /// Machine-readable description of generated enum.
+    pub fn describe(&self) -> JsonValue {
+        json!({
+            "name": self.name.to_string(),
+            "type": format!("{:?}", self.type_),
+            "values": self.values.iter().map(|value| json!({
+                "value": value.value(),
+                "variant": variant_ident(value),
+                "display_name": display_name(value),
+            })).collect::<Vec<_>>(),
+        })
+    }
+
     fn literal_ctr(&self, value: &str) -> Literal {
         match self.type_ {
             BasicType::String | BasicType::MultipleStringValue => {
@@ -34,7 +84,7 @@ impl EnumDesc {
         }
     }
 
-    pub fn generate(&self) -> TokenStream {
+    pub fn generate(&self, derives: &Derives) -> TokenStream {
         let name = &self.name;
         let type_ = match self.type_ {
             t @ (BasicType::Int | BasicType::NumInGroup | BasicType::Char) => {
@@ -48,17 +98,9 @@ impl EnumDesc {
         let mut variant_name = Vec::with_capacity(self.values.len());
         let mut variant_value = Vec::with_capacity(self.values.len());
         let mut variant_value_as_bytes = Vec::with_capacity(self.values.len());
+        let mut variant_display_name = Vec::with_capacity(self.values.len());
         for value in &self.values {
-            let v_name = Ident::new(
-                &{
-                    let mut variant_name = value.description().to_case(Case::UpperCamel);
-                    if variant_name.as_bytes()[0].is_ascii_digit() {
-                        variant_name.insert(0, '_');
-                    }
-                    variant_name
-                },
-                Span::call_site(),
-            );
+            let v_name = Ident::new(&variant_ident(value), Span::call_site());
             let v_value = self.literal_ctr(value.value());
             let v_value_as_bytes = Literal::byte_string(value.value().as_bytes());
 
@@ -70,6 +112,7 @@ impl EnumDesc {
             variant_name.push(v_name.clone());
             variant_value.push(v_value.clone());
             variant_value_as_bytes.push(v_value_as_bytes.clone());
+            variant_display_name.push(display_name(value));
         }
         let try_from_match_input = if matches!(
             self.type_,
@@ -79,24 +122,53 @@ impl EnumDesc {
         } else {
             quote! { match input }
         };
-        let derives = if name == "MsgType" {
+        // MsgType has additional variant for message types not known at
+        // generation time, so its values are not always `'static`
+        let is_msg_type = name == "MsgType";
+        let other_variant = is_msg_type.then(|| {
             quote! {
-                #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
-                #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
-                #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+                /// Value not defined in dictionary, see `Deserializer::with_other_msg_types()`
+                Other(MsgTypeCode),
             }
-        } else {
+        });
+        let other_as_bytes = is_msg_type.then(|| quote! { #name::Other(code) => code.as_bytes(), });
+        let other_display_name = is_msg_type.then(|| quote! { #name::Other(_) => "Other", });
+        let lifetime = (!is_msg_type).then(|| quote! { 'static });
+        let impl_into_static_bytes = (!is_msg_type).then(|| {
             quote! {
-                #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
-                #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
-                #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+                impl From<#name> for &'static [u8] {
+                    fn from(input: #name) -> &'static [u8] {
+                        input.as_bytes()
+                    }
+                }
             }
+        });
+        let derives = if is_msg_type {
+            // MsgType is commonly used as a map key, keep `Hash` regardless of options
+            Derives {
+                hash: true,
+                ..*derives
+            }
+        } else {
+            *derives
+        };
+        let derives = gen_derives(
+            quote! { Clone, Copy, Debug, Default, Eq, PartialEq, },
+            quote! { Ord, PartialOrd, },
+            &derives,
+        );
+        let repr = if self.values.len() <= 256 {
+            quote! { #[repr(u8)] }
+        } else {
+            quote! { #[repr(u16)] }
         };
         quote! {
             #derives
+            #repr
             pub enum #name {
                 #[default]
                 #(#variant_def,)*
+                #other_variant
             }
 
             impl #name {
@@ -111,15 +183,25 @@ impl EnumDesc {
                     #name::from_bytes(input.as_bytes())
                 }
 
-                pub const fn as_bytes(&self) -> &'static [u8] {
+                pub const fn as_bytes(&self) -> &#lifetime [u8] {
                     match self {
                         #(#name::#variant_name => #variant_value_as_bytes,)*
+                        #other_as_bytes
                     }
                 }
 
-                pub const fn as_fix_str(&self) -> &'static FixStr {
+                pub const fn as_fix_str(&self) -> &#lifetime FixStr {
                     unsafe { FixStr::from_ascii_unchecked(self.as_bytes()) }
                 }
+
+                /// Human-readable name derived from dictionary description
+                /// of the value, e.g. for GUIs and reports.
+                pub const fn display_name(&self) -> &'static str {
+                    match self {
+                        #(#name::#variant_name => #variant_display_name,)*
+                        #other_display_name
+                    }
+                }
             }
 
             impl ToFixString for #name {
@@ -139,11 +221,32 @@ impl EnumDesc {
                 }
             }
 
-            impl From<#name> for &'static [u8] {
-                fn from(input: #name) -> &'static [u8] {
-                    input.as_bytes()
+            #impl_into_static_bytes
+        }
+    }
+
+    /// `EnumMetadata` entry of `enum_metadata()` registry.
+    pub fn generate_metadata(&self) -> TokenStream {
+        let field_name = &self.field_name;
+        let tag = self.tag;
+        let values = self.values.iter().map(|value| {
+            let value_as_bytes = Literal::byte_string(value.value().as_bytes());
+            let variant = variant_ident(value);
+            let display_name = display_name(value);
+            quote! {
+                EnumValueMetadata {
+                    value: unsafe { FixStr::from_ascii_unchecked(#value_as_bytes) },
+                    variant: #variant,
+                    display_name: #display_name,
                 }
             }
+        });
+        quote! {
+            EnumMetadata {
+                name: #field_name,
+                tag: #tag,
+                values: &[#(#values,)*],
+            }
         }
     }
 }
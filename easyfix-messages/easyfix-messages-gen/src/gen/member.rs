@@ -1,13 +1,30 @@
-use convert_case::{Case, Casing};
+use std::collections::HashSet;
+
 use easyfix_dictionary::BasicType;
 use proc_macro2::{Ident, Literal, Span, TokenStream};
 use quote::quote;
+use serde_json::{json, Value};
+
+use crate::naming::Naming;
+
+/// Part of message deserialized by generated code, it decides how
+/// invalid, duplicated and misplaced field values are handled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Section {
+    Header,
+    Trailer,
+    Group,
+    AdminBody,
+    AppBody,
+}
 
 #[derive(Debug, Clone)]
 pub enum Type {
     Basic(BasicType),
     Group(Ident),
     Enum((Ident, BasicType)),
+    /// String field holding UTF-8 text, see `GeneratorOptions::utf8_tags`.
+    Utf8String,
 }
 
 impl Type {
@@ -15,18 +32,12 @@ impl Type {
         Type::Basic(basic_type)
     }
 
-    pub fn group(name: &str) -> Type {
-        Type::Group(Ident::new(
-            &name.to_case(Case::UpperCamel),
-            Span::call_site(),
-        ))
+    pub fn group(naming: &Naming, name: &str) -> Type {
+        Type::Group(naming.type_(name))
     }
 
-    pub fn enumeration(name: &str, basic_type: BasicType) -> Type {
-        Type::Enum((
-            Ident::new(&name.to_case(Case::UpperCamel), Span::call_site()),
-            basic_type,
-        ))
+    pub fn enumeration(naming: &Naming, name: &str, basic_type: BasicType) -> Type {
+        Type::Enum((naming.type_(name), basic_type))
     }
 
     pub fn gen_type(&self) -> TokenStream {
@@ -60,6 +71,7 @@ impl Type {
             Type::Basic(BasicType::UtcTimestamp) => quote! { UtcTimestamp },
             Type::Basic(BasicType::XmlData) => quote! { XmlData },
             Type::Group(name) => quote! { Vec<#name> },
+            Type::Utf8String => quote! { Utf8String },
             // TODO: in case of enum based on NumInGroup, it seems that max
             //       group members cound should be limited to max enum value
             Type::Enum((
@@ -127,6 +139,7 @@ impl Type {
             }
             Type::Basic(BasicType::XmlData) => None,
             Type::Group(_) => None,
+            Type::Utf8String => Some(quote! { serializer.serialize_utf8_string }),
             Type::Enum((
                 _,
                 BasicType::Int | BasicType::NumInGroup | BasicType::Char | BasicType::String,
@@ -141,6 +154,43 @@ impl Type {
         }
     }
 
+    /// Generate `FieldRef` of field value, `value` is reference to it.
+    /// `None` for groups, which are visited entry by entry, and Data/XmlData,
+    /// visited together with preceding Length field.
+    fn gen_field_ref(&self, value: &TokenStream) -> Option<TokenStream> {
+        match self {
+            Type::Basic(
+                basic_type @ (BasicType::Amt
+                | BasicType::Boolean
+                | BasicType::Char
+                | BasicType::Country
+                | BasicType::Currency
+                | BasicType::Float
+                | BasicType::Int
+                | BasicType::Length
+                | BasicType::NumInGroup
+                | BasicType::Percentage
+                | BasicType::Price
+                | BasicType::PriceOffset
+                | BasicType::Qty
+                | BasicType::SeqNum),
+            ) => {
+                let variant = Ident::new(&format!("{basic_type:?}"), Span::call_site());
+                Some(quote! { fields::FieldRef::#variant(*#value) })
+            }
+            Type::Basic(BasicType::Data | BasicType::XmlData) | Type::Group(_) => None,
+            Type::Basic(basic_type) => {
+                let variant = Ident::new(&format!("{basic_type:?}"), Span::call_site());
+                Some(quote! { fields::FieldRef::#variant(#value) })
+            }
+            Type::Utf8String => Some(quote! { fields::FieldRef::Utf8String(#value) }),
+            Type::Enum((_, BasicType::MultipleCharValue | BasicType::MultipleStringValue)) => {
+                Some(quote! { fields::FieldRef::EnumValues(#value) })
+            }
+            Type::Enum(_) => Some(quote! { fields::FieldRef::Enum(#value.as_fix_str()) }),
+        }
+    }
+
     fn gen_deserialize(&self) -> TokenStream {
         match self {
             Type::Basic(BasicType::Amt) => quote! { deserializer.deserialize_amt() },
@@ -204,6 +254,7 @@ impl Type {
             Type::Group(name) => {
                 quote! { #name::deserialize(deserializer, num_in_group_tag, expected_tags, last_run) }
             }
+            Type::Utf8String => quote! { deserializer.deserialize_utf8_string() },
             Type::Enum((_, BasicType::Int)) => {
                 quote! { deserializer.deserialize_int_enum() }
             }
@@ -230,49 +281,76 @@ impl Type {
     }
 }
 
-// TODO: check agains all rust keywords
-const RESERVED: &[&str] = &["yield"];
-
-fn is_reserved(input: &str) -> bool {
-    RESERVED.iter().any(|r| r.eq_ignore_ascii_case(input))
-}
-
 #[derive(Debug, Clone)]
 pub struct SimpleMember {
     name: Ident,
     tag: u16,
     required: bool,
+    /// Optional field allowed to appear more than once, collected in `Vec`.
+    repeated: bool,
     type_: Type,
+    /// Path of `FieldCodec` used instead of serializer/deserializer method
+    /// of `type_`, see `GeneratorOptions::custom_codec`.
+    codec: Option<TokenStream>,
 }
 
 impl SimpleMember {
-    fn new(name: &str, tag: u16, required: bool, type_: Type) -> SimpleMember {
-        let mut name = name.to_case(Case::Snake);
-        if is_reserved(&name) {
-            name.push('_');
-        }
+    fn new(naming: &Naming, name: &str, tag: u16, required: bool, type_: Type) -> SimpleMember {
         SimpleMember {
-            name: Ident::new(&name, Span::call_site()),
+            name: naming.member(name),
             tag,
             required,
+            repeated: false,
             type_,
+            codec: None,
         }
     }
 
-    pub fn field(name: &str, tag: u16, required: bool, type_: BasicType) -> SimpleMember {
-        SimpleMember::new(name, tag, required, Type::basic_type(type_))
+    pub fn field(
+        naming: &Naming,
+        name: &str,
+        tag: u16,
+        required: bool,
+        type_: BasicType,
+    ) -> SimpleMember {
+        SimpleMember::new(naming, name, tag, required, Type::basic_type(type_))
+    }
+
+    fn utf8_string_field(naming: &Naming, name: &str, tag: u16, required: bool) -> SimpleMember {
+        SimpleMember::new(naming, name, tag, required, Type::Utf8String)
+    }
+
+    fn enum_field(
+        naming: &Naming,
+        name: &str,
+        tag: u16,
+        required: bool,
+        type_: BasicType,
+    ) -> SimpleMember {
+        let type_ = Type::enumeration(naming, name, type_);
+        SimpleMember::new(naming, name, tag, required, type_)
+    }
+
+    pub fn length(naming: &Naming, name: &str, tag: u16, required: bool) -> SimpleMember {
+        let type_ = Type::basic_type(BasicType::Length);
+        SimpleMember::new(naming, name, tag, required, type_)
     }
 
-    fn enum_field(name: &str, tag: u16, required: bool, type_: BasicType) -> SimpleMember {
-        SimpleMember::new(name, tag, required, Type::enumeration(name, type_))
+    pub fn num_in_group(naming: &Naming, name: &str, tag: u16, required: bool) -> SimpleMember {
+        let type_ = Type::basic_type(BasicType::NumInGroup);
+        SimpleMember::new(naming, name, tag, required, type_)
     }
 
-    pub fn length(name: &str, tag: u16, required: bool) -> SimpleMember {
-        SimpleMember::new(name, tag, required, Type::basic_type(BasicType::Length))
+    pub fn name(&self) -> &Ident {
+        &self.name
     }
 
-    pub fn num_in_group(name: &str, tag: u16, required: bool) -> SimpleMember {
-        SimpleMember::new(name, tag, required, Type::basic_type(BasicType::NumInGroup))
+    pub fn set_name(&mut self, name: Ident) {
+        self.name = name;
+    }
+
+    pub fn tag(&self) -> u16 {
+        self.tag
     }
 
     /// Create `SimpleMember` object of `Group` type.
@@ -282,8 +360,35 @@ impl SimpleMember {
     /// * `name` - group name
     /// * `tag` - tag number of NumInGroup associated field
     /// * `required` - if group presence is required
-    pub fn group(name: &str, tag: u16, required: bool) -> SimpleMember {
-        SimpleMember::new(name, tag, required, Type::group(name))
+    pub fn group(naming: &Naming, name: &str, tag: u16, required: bool) -> SimpleMember {
+        SimpleMember::new(naming, name, tag, required, Type::group(naming, name))
+    }
+
+    fn describe(&self) -> Value {
+        let mut desc = json!({
+            "name": self.name.to_string(),
+            "tag": self.tag,
+            "required": self.required,
+        });
+        if self.repeated {
+            desc["repeated"] = json!(true);
+        }
+        if let Some(codec) = &self.codec {
+            desc["codec"] = json!(codec.to_string());
+        }
+        match &self.type_ {
+            Type::Basic(basic_type) => desc["type"] = json!(format!("{basic_type:?}")),
+            Type::Group(name) => {
+                desc["type"] = json!("Group");
+                desc["group"] = json!(name.to_string());
+            }
+            Type::Enum((name, basic_type)) => {
+                desc["type"] = json!(format!("{basic_type:?}"));
+                desc["enum"] = json!(name.to_string());
+            }
+            Type::Utf8String => desc["type"] = json!("Utf8String"),
+        }
+        desc
     }
 
     /// Generate member definition for use in structs definitions.
@@ -296,6 +401,12 @@ impl SimpleMember {
                 #[doc = #doc_comment]
                 pub #name: #type_
             }
+        } else if self.repeated {
+            let doc_comment = format!("Tag {}, may appear more than once.", self.tag);
+            quote! {
+                #[doc = #doc_comment]
+                pub #name: Vec<#type_>
+            }
         } else {
             quote! {
                 #[doc = #doc_comment]
@@ -304,30 +415,84 @@ impl SimpleMember {
         }
     }
 
+    /// Generate serialization of `value` (reference to field value) with
+    /// custom codec or `serialize_type` method of serializer.
+    fn gen_serialize_value(&self, serialize_type: &TokenStream, value: TokenStream) -> TokenStream {
+        match &self.codec {
+            Some(codec) => {
+                let type_ = self.type_.gen_type();
+                quote! { <#codec as crate::codec::FieldCodec<#type_>>::serialize(serializer, #value) }
+            }
+            None => quote! { #serialize_type(#value) },
+        }
+    }
+
     fn gen_serialize(&self) -> Option<TokenStream> {
         if let Some(serialize_type) = self.type_.gen_serialize() {
             if self.tag == 9 {
-                Some(quote! { serializer.serialize_body_len() })
+                Some(quote! { serializer.serialize_body_len(); })
             } else if self.tag == 10 {
-                Some(quote! { serializer.serialize_checksum() })
+                Some(quote! { serializer.serialize_checksum(); })
             } else {
                 let name = &self.name;
                 let tag = Literal::byte_string(format!("{}=", self.tag).as_bytes());
-                if self.required {
+                let serialize_required =
+                    self.gen_serialize_value(&serialize_type, quote! { &self.#name });
+                let serialize_optional =
+                    self.gen_serialize_value(&serialize_type, quote! { #name });
+                if self.required && self.tag == 1137 {
+                    // DefaultApplVerID<1137> exists only in FIXT session protocol
+                    Some(quote! {
+                        if serializer.is_fixt() {
+                            serializer.output_mut().extend_from_slice(#tag);
+                            #serialize_required;
+                            serializer.output_mut().push(b'\x01');
+                        }
+                    })
+                } else if self.tag == 35 {
+                    // MsgType<35> value of `MsgType::Other` is not `'static`,
+                    // so `serialize_enum()` can't be used
                     Some(quote! {
-                        //serializer.serialize_tag_num(#tag);
                         serializer.output_mut().extend_from_slice(#tag);
-                        #serialize_type(&self.#name);
+                        serializer.output_mut().extend_from_slice(self.#name.as_bytes());
                         serializer.output_mut().push(b'\x01');
                     })
-                } else {
+                } else if self.required {
                     Some(quote! {
-                        if let Some(#name) = &self.#name {
-                            serializer.output_mut().extend_from_slice(#tag);
-                            #serialize_type(#name);
-                            serializer.output_mut().push(b'\x01');
-                        }
+                        //serializer.serialize_tag_num(#tag);
+                        serializer.output_mut().extend_from_slice(#tag);
+                        #serialize_required;
+                        serializer.output_mut().push(b'\x01');
                     })
+                } else {
+                    let mut serialize = quote! {
+                        serializer.output_mut().extend_from_slice(#tag);
+                        #serialize_optional;
+                        serializer.output_mut().push(b'\x01');
+                    };
+                    // Empty optional strings are handled according to
+                    // serializer policy
+                    if let Type::Basic(BasicType::String) | Type::Utf8String = self.type_ {
+                        let tag_num = self.tag;
+                        serialize = quote! {
+                            if serializer.include_string(#tag_num, #name) {
+                                #serialize
+                            }
+                        };
+                    }
+                    if self.repeated {
+                        Some(quote! {
+                            for #name in &self.#name {
+                                #serialize
+                            }
+                        })
+                    } else {
+                        Some(quote! {
+                            if let Some(#name) = &self.#name {
+                                #serialize
+                            }
+                        })
+                    }
                 }
             }
         } else {
@@ -335,6 +500,36 @@ impl SimpleMember {
         }
     }
 
+    /// Generate call of `visitor` with value of this field, see
+    /// `Fields::visit_fields()`.
+    fn gen_visit(&self) -> Option<TokenStream> {
+        // Calculated by serializer
+        if matches!(self.tag, 9 | 10) {
+            return None;
+        }
+        let name = &self.name;
+        let tag = self.tag;
+        let field_ref = self.type_.gen_field_ref(&quote! { #name })?;
+        if self.required {
+            Some(quote! {
+                let #name = &self.#name;
+                visitor(#tag, #field_ref);
+            })
+        } else if self.repeated {
+            Some(quote! {
+                for #name in &self.#name {
+                    visitor(#tag, #field_ref);
+                }
+            })
+        } else {
+            Some(quote! {
+                if let Some(#name) = &self.#name {
+                    visitor(#tag, #field_ref);
+                }
+            })
+        }
+    }
+
     /// Generate mutable optional variables set to None for further
     /// processig in deserializer loop.
     ///
@@ -355,6 +550,7 @@ impl SimpleMember {
             // 9 => quote! { let #name = deserializer.body_length(); },
             8 | 9 | 35 => quote! {},
             10 => quote! { let #name = deserializer.check_sum(); },
+            _ if self.repeated => quote! { let mut #name: Vec<#type_> = Vec::new(); },
             // 35 => quote! {
             //     // Check if MsgType(35) is the third tag in a message.
             //     let #name = if let Some(35) = deserializer
@@ -371,10 +567,33 @@ impl SimpleMember {
         }
     }
 
-    fn gen_deserialize_value(&self) -> Option<TokenStream> {
+    /// In application message body invalid value of optional field is
+    /// passed to `Deserializer::field_error()` instead of rejecting
+    /// the message. In any message body repeated field is passed to
+    /// `Deserializer::duplicate_tag()`.
+    fn gen_deserialize_value(&self, section: Section) -> Option<TokenStream> {
         let name = &self.name;
         let tag = self.tag;
-        let deserialize = self.type_.gen_deserialize();
+        let deserialize = match &self.codec {
+            Some(codec) => {
+                let type_ = self.type_.gen_type();
+                quote! { <#codec as crate::codec::FieldCodec<#type_>>::deserialize(deserializer) }
+            }
+            None => self.type_.gen_deserialize(),
+        };
+        let tolerant = section == Section::AppBody && !self.required;
+        let check_duplicate = match section {
+            Section::AdminBody | Section::AppBody => quote! {
+                if #name.is_some() && deserializer.duplicate_tag(#tag)? {
+                    continue;
+                }
+            },
+            Section::Header | Section::Trailer | Section::Group => quote! {
+                if #name.is_some() {
+                    return Err(deserializer.reject(Some(#tag), ParseRejectReason::TagAppearsMoreThanOnce));
+                }
+            },
+        };
         match (&self.type_, self.tag) {
             // TODO: is it OK?
             (_, 8 | 9 | 10 | 35) => Some(quote! {
@@ -402,10 +621,24 @@ impl SimpleMember {
             (Type::Basic(BasicType::Data | BasicType::XmlData), _) => Some(quote! {
                 return Err(deserializer.reject(Some(tag), ParseRejectReason::TagSpecifiedOutOfRequiredOrder));
             }),
-            _ => Some(quote! {
-                if #name.is_some() {
-                    return Err(deserializer.reject(Some(#tag), ParseRejectReason::TagAppearsMoreThanOnce));
+            _ if self.repeated && tolerant => Some(quote! {
+                match #deserialize {
+                    Ok(value) => #name.push(value),
+                    Err(err) => deserializer.field_error(err)?,
+                }
+            }),
+            _ if self.repeated => Some(quote! {
+                #name.push(#deserialize?);
+            }),
+            _ if tolerant => Some(quote! {
+                #check_duplicate
+                match #deserialize {
+                    Ok(value) => #name = Some(value),
+                    Err(err) => deserializer.field_error(err)?,
                 }
+            }),
+            _ => Some(quote! {
+                #check_duplicate
                 #name = Some(#deserialize?);
             }),
         }
@@ -415,7 +648,16 @@ impl SimpleMember {
     fn gen_deserialize_struct_entries(&self) -> TokenStream {
         let name = &self.name;
         let tag = self.tag;
-        if self.required && !matches!(self.tag, 8 | 9 | 10 | 35) {
+        if self.required && self.tag == 1137 {
+            // DefaultApplVerID<1137> exists only in FIXT session protocol
+            quote! {
+                #name: match #name {
+                    Some(#name) => #name,
+                    None if !deserializer.is_fixt() => Default::default(),
+                    None => return Err(deserializer.reject(Some(#tag), ParseRejectReason::RequiredTagMissing)),
+                }
+            }
+        } else if self.required && !matches!(self.tag, 8 | 9 | 10 | 35) {
             quote! {
                 #name: #name.ok_or_else(|| deserializer.reject(Some(#tag), ParseRejectReason::RequiredTagMissing))?
             }
@@ -438,6 +680,17 @@ pub struct GroupMember {
     num_in_group: SimpleMember,
     group_body: SimpleMember,
     expected_tags: Vec<u16>,
+    /// Tags of all group members, including members of nested groups
+    member_tags: Vec<u16>,
+}
+
+/// Non-repeating component generated as a separate struct.
+#[derive(Debug, Clone)]
+pub struct ComponentMember {
+    name: Ident,
+    type_: Ident,
+    required: bool,
+    members: Vec<MemberDesc>,
 }
 
 #[derive(Debug, Clone)]
@@ -445,15 +698,32 @@ pub enum MemberDesc {
     Simple(SimpleMember),
     CustomLength(CustomLengthMember),
     Group(GroupMember),
+    Component(ComponentMember),
 }
 
 impl MemberDesc {
-    pub fn simple(name: &str, tag: u16, required: bool, type_: BasicType) -> MemberDesc {
-        MemberDesc::Simple(SimpleMember::field(name, tag, required, type_))
+    pub fn simple(
+        naming: &Naming,
+        name: &str,
+        tag: u16,
+        required: bool,
+        type_: BasicType,
+    ) -> MemberDesc {
+        MemberDesc::Simple(SimpleMember::field(naming, name, tag, required, type_))
+    }
+
+    pub fn utf8_string(naming: &Naming, name: &str, tag: u16, required: bool) -> MemberDesc {
+        MemberDesc::Simple(SimpleMember::utf8_string_field(naming, name, tag, required))
     }
 
-    pub fn enumeration(name: &str, tag: u16, required: bool, type_: BasicType) -> MemberDesc {
-        MemberDesc::Simple(SimpleMember::enum_field(name, tag, required, type_))
+    pub fn enumeration(
+        naming: &Naming,
+        name: &str,
+        tag: u16,
+        required: bool,
+        type_: BasicType,
+    ) -> MemberDesc {
+        MemberDesc::Simple(SimpleMember::enum_field(naming, name, tag, required, type_))
     }
 
     pub fn custom_length(len: SimpleMember, value: SimpleMember) -> MemberDesc {
@@ -464,11 +734,35 @@ impl MemberDesc {
         num_in_group: SimpleMember,
         group_body: SimpleMember,
         expected_tags: Vec<u16>,
+        member_tags: Vec<u16>,
     ) -> MemberDesc {
         MemberDesc::Group(GroupMember {
             num_in_group,
             group_body,
             expected_tags,
+            member_tags,
+        })
+    }
+
+    /// Create `Component` member.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - component name
+    /// * `required` - if component presence is required
+    /// * `members` - component members, must not be empty
+    pub fn component(
+        naming: &Naming,
+        name: &str,
+        required: bool,
+        members: Vec<MemberDesc>,
+    ) -> MemberDesc {
+        assert!(!members.is_empty(), "Empty component {name}");
+        MemberDesc::Component(ComponentMember {
+            name: naming.member(name),
+            type_: naming.type_(name),
+            required,
+            members,
         })
     }
 
@@ -477,6 +771,187 @@ impl MemberDesc {
             MemberDesc::Simple(member) => member.tag,
             MemberDesc::CustomLength(member) => member.len.tag,
             MemberDesc::Group(member) => member.num_in_group.tag,
+            MemberDesc::Component(member) => member.members[0].tag_num(),
+        }
+    }
+
+    /// Members named after fields, i.e. all except component ones.
+    pub fn simple_members_mut(&mut self) -> Vec<&mut SimpleMember> {
+        match self {
+            MemberDesc::Simple(member) => vec![member],
+            MemberDesc::CustomLength(member) => vec![&mut member.len, &mut member.value],
+            MemberDesc::Group(member) => vec![&mut member.num_in_group, &mut member.group_body],
+            MemberDesc::Component(_) => Vec::new(),
+        }
+    }
+
+    /// Collect values of optional field in `Vec` when its tag is one of
+    /// `tags`, so the field may appear in message more than once.
+    ///
+    /// Required, Length, Data and NumInGroup fields are never repeated.
+    pub fn set_repeated(&mut self, tags: &HashSet<u16>) {
+        if let MemberDesc::Simple(member) = self {
+            if !member.required
+                && tags.contains(&member.tag)
+                && !matches!(
+                    member.type_,
+                    Type::Group(_)
+                        | Type::Basic(
+                            BasicType::Length
+                                | BasicType::NumInGroup
+                                | BasicType::Data
+                                | BasicType::XmlData
+                        )
+                )
+            {
+                member.repeated = true;
+            }
+        }
+    }
+
+    /// Serialize and deserialize field with custom `codec`.
+    pub fn set_codec(&mut self, codec: TokenStream) {
+        if let MemberDesc::Simple(member) = self {
+            member.codec = Some(codec);
+        }
+    }
+
+    /// Make required field optional, so it may be required only by some
+    /// messages, see `gen_required_check()`.
+    pub fn relax_required(&mut self) {
+        if let MemberDesc::Simple(member) = self {
+            member.required = false;
+        }
+    }
+
+    /// Generate check rejecting message with RequiredTagMissing reason
+    /// when optional field of `base` struct has no value.
+    pub fn gen_required_check(&self, base: &TokenStream) -> Option<TokenStream> {
+        match self {
+            MemberDesc::Simple(member) if !member.required && !member.repeated => {
+                let name = &member.name;
+                let tag = member.tag;
+                Some(quote! {
+                    if #base.#name.is_none() {
+                        return Err(deserializer.reject(Some(#tag), ParseRejectReason::RequiredTagMissing));
+                    }
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Members with components expanded, in order of appearance.
+    ///
+    /// Deserializer works on this flat view, as component fields are
+    /// not delimited on the wire.
+    pub fn leaves(&self) -> Vec<&MemberDesc> {
+        match self {
+            MemberDesc::Component(member) => {
+                member.members.iter().flat_map(MemberDesc::leaves).collect()
+            }
+            member => vec![member],
+        }
+    }
+
+    /// Tags of all fields of this member, including group members.
+    pub fn tags(&self) -> Vec<u16> {
+        match self {
+            MemberDesc::Simple(member) => vec![member.tag],
+            MemberDesc::CustomLength(member) => vec![member.len.tag, member.value.tag],
+            MemberDesc::Group(member) => std::iter::once(member.num_in_group.tag)
+                .chain(member.member_tags.iter().copied())
+                .collect(),
+            MemberDesc::Component(member) => {
+                member.members.iter().flat_map(MemberDesc::tags).collect()
+            }
+        }
+    }
+
+    /// Generate presence checks of fields of this member, with components
+    /// expanded, as pairs of tag and expression evaluating to `true` when
+    /// field has value (`None` when it's always set). `base` is expression
+    /// of struct holding the member.
+    pub fn gen_presence(&self, base: &TokenStream) -> Vec<(u16, Option<TokenStream>)> {
+        match self {
+            MemberDesc::Simple(member) => {
+                let name = &member.name;
+                let check = if member.required {
+                    None
+                } else if member.repeated {
+                    Some(quote! { !#base.#name.is_empty() })
+                } else {
+                    Some(quote! { #base.#name.is_some() })
+                };
+                vec![(member.tag, check)]
+            }
+            MemberDesc::CustomLength(CustomLengthMember { len, value }) => {
+                let name = &value.name;
+                let check = (!len.required).then(|| quote! { #base.#name.is_some() });
+                vec![(len.tag, check.clone()), (value.tag, check)]
+            }
+            MemberDesc::Group(GroupMember {
+                num_in_group,
+                group_body,
+                ..
+            }) => {
+                let name = &group_body.name;
+                let check = (!num_in_group.required).then(|| quote! { #base.#name.is_some() });
+                vec![(num_in_group.tag, check)]
+            }
+            MemberDesc::Component(ComponentMember {
+                name,
+                required,
+                members,
+                ..
+            }) => {
+                if *required {
+                    let base = quote! { #base.#name };
+                    members
+                        .iter()
+                        .flat_map(|member| member.gen_presence(&base))
+                        .collect()
+                } else {
+                    let inner_base = quote! { #name };
+                    members
+                        .iter()
+                        .flat_map(|member| member.gen_presence(&inner_base))
+                        .map(|(tag, check)| {
+                            let check = match check {
+                                Some(check) => quote! {
+                                    #base.#name.as_ref().is_some_and(|#name| #check)
+                                },
+                                None => quote! { #base.#name.is_some() },
+                            };
+                            (tag, Some(check))
+                        })
+                        .collect()
+                }
+            }
+        }
+    }
+
+    /// Machine-readable description of struct members generated for this
+    /// member, in order of definition.
+    pub fn describe(&self) -> Vec<Value> {
+        match self {
+            MemberDesc::Simple(member) => vec![member.describe()],
+            MemberDesc::CustomLength(member) => {
+                vec![member.len.describe(), member.value.describe()]
+            }
+            // Described by following member of group type
+            MemberDesc::Group(_) => Vec::new(),
+            MemberDesc::Component(ComponentMember {
+                name,
+                type_,
+                required,
+                ..
+            }) => vec![json!({
+                "name": name.to_string(),
+                "required": required,
+                "type": "Component",
+                "component": type_.to_string(),
+            })],
         }
     }
 
@@ -485,6 +960,25 @@ impl MemberDesc {
         match self {
             MemberDesc::Simple(member) => Some(member.gen_definition()),
             MemberDesc::CustomLength(_) | MemberDesc::Group(_) => None,
+            MemberDesc::Component(ComponentMember {
+                name,
+                type_,
+                required,
+                ..
+            }) => {
+                let doc_comment = format!("Component `{}`.", type_);
+                if *required {
+                    Some(quote! {
+                        #[doc = #doc_comment]
+                        pub #name: #type_
+                    })
+                } else {
+                    Some(quote! {
+                        #[doc = #doc_comment]
+                        pub #name: Option<#type_>
+                    })
+                }
+            }
         }
     }
 
@@ -572,6 +1066,136 @@ impl MemberDesc {
                     })
                 }
             }
+            MemberDesc::Component(ComponentMember { name, required, .. }) => {
+                if *required {
+                    Some(quote! {
+                        self.#name.serialize(serializer);
+                    })
+                } else {
+                    Some(quote! {
+                        if let Some(#name) = &self.#name {
+                            #name.serialize(serializer);
+                        }
+                    })
+                }
+            }
+        }
+    }
+
+    /// Generate calls of `visitor` with values of fields of this member,
+    /// see `Fields::visit_fields()`.
+    pub fn gen_visit(&self) -> Option<TokenStream> {
+        match self {
+            MemberDesc::Simple(member) => member.gen_visit(),
+            MemberDesc::CustomLength(CustomLengthMember { len, value }) => {
+                let len_tag = len.tag;
+                let value_tag = value.tag;
+                let value_name = &value.name;
+                let variant = match value.type_ {
+                    Type::Basic(BasicType::Data) => quote! { Data },
+                    Type::Basic(BasicType::XmlData) => quote! { XmlData },
+                    ref t => panic!("Unexpected type {:?} after `Length` field", t),
+                };
+                if len.required {
+                    Some(quote! {
+                        visitor(#len_tag, fields::FieldRef::Length(self.#value_name.len() as Length));
+                        visitor(#value_tag, fields::FieldRef::#variant(&self.#value_name));
+                    })
+                } else {
+                    Some(quote! {
+                        if let Some(#value_name) = &self.#value_name {
+                            visitor(#len_tag, fields::FieldRef::Length(#value_name.len() as Length));
+                            visitor(#value_tag, fields::FieldRef::#variant(#value_name));
+                        }
+                    })
+                }
+            }
+            MemberDesc::Group(GroupMember {
+                num_in_group,
+                group_body,
+                ..
+            }) => {
+                let num_in_group_tag = num_in_group.tag;
+                let group_name = &group_body.name;
+                if num_in_group.required {
+                    Some(quote! {
+                        visitor(#num_in_group_tag, fields::FieldRef::NumInGroup(self.#group_name.len() as NumInGroup));
+                        for entry in &self.#group_name {
+                            fields::Fields::visit_fields(entry, visitor);
+                        }
+                    })
+                } else {
+                    Some(quote! {
+                        if let Some(#group_name) = &self.#group_name {
+                            visitor(#num_in_group_tag, fields::FieldRef::NumInGroup(#group_name.len() as NumInGroup));
+                            for entry in #group_name {
+                                fields::Fields::visit_fields(entry, visitor);
+                            }
+                        }
+                    })
+                }
+            }
+            MemberDesc::Component(ComponentMember { name, required, .. }) => {
+                if *required {
+                    Some(quote! {
+                        fields::Fields::visit_fields(&self.#name, visitor);
+                    })
+                } else {
+                    Some(quote! {
+                        if let Some(#name) = &self.#name {
+                            fields::Fields::visit_fields(#name, visitor);
+                        }
+                    })
+                }
+            }
+        }
+    }
+
+    /// Generate constructor parameter of required member.
+    ///
+    /// BodyLength<9> and CheckSum<10> are calculated by serializer,
+    /// so they are never passed to constructor.
+    pub fn gen_constructor_param(&self) -> Option<TokenStream> {
+        match self {
+            MemberDesc::Simple(member) if member.required && !matches!(member.tag, 9 | 10) => {
+                let name = &member.name;
+                let type_ = member.type_.gen_type();
+                Some(quote! { #name: #type_ })
+            }
+            MemberDesc::Component(ComponentMember {
+                name,
+                type_,
+                required: true,
+                ..
+            }) => Some(quote! { #name: #type_ }),
+            _ => None,
+        }
+    }
+
+    /// Generate member initializer for use in constructor, members without
+    /// constructor parameter are empty.
+    pub fn gen_constructor_init(&self) -> Option<TokenStream> {
+        match self {
+            MemberDesc::Simple(member) => {
+                let name = &member.name;
+                if member.required && matches!(member.tag, 9 | 10) {
+                    Some(quote! { #name: Default::default() })
+                } else if member.required {
+                    Some(quote! { #name })
+                } else if member.repeated {
+                    Some(quote! { #name: Vec::new() })
+                } else {
+                    Some(quote! { #name: None })
+                }
+            }
+            MemberDesc::CustomLength(_) | MemberDesc::Group(_) => None,
+            MemberDesc::Component(ComponentMember { name, required, .. }) => {
+                if *required {
+                    Some(quote! { #name })
+                } else {
+                    Some(quote! { #name: None })
+                }
+            }
         }
     }
 
@@ -584,12 +1208,16 @@ impl MemberDesc {
             MemberDesc::Simple(member) => member.gen_opt_variables(),
             MemberDesc::CustomLength(member) => member.len.gen_opt_variables(),
             MemberDesc::Group(member) => member.num_in_group.gen_opt_variables(),
+            MemberDesc::Component(member) => {
+                let variables = member.members.iter().map(MemberDesc::gen_opt_variables);
+                quote! { #(#variables)* }
+            }
         }
     }
 
-    pub fn gen_deserialize_value(&self) -> Option<TokenStream> {
+    pub fn gen_deserialize_value(&self, section: Section) -> Option<TokenStream> {
         match self {
-            MemberDesc::Simple(member) => member.gen_deserialize_value(),
+            MemberDesc::Simple(member) => member.gen_deserialize_value(section),
             MemberDesc::CustomLength(CustomLengthMember {
                 len: SimpleMember {
                     name, tag, type_, ..
@@ -638,6 +1266,7 @@ impl MemberDesc {
                         ..
                     },
                 expected_tags,
+                ..
             }) => {
                 let deserialize = type_.gen_deserialize();
                 let group_deserialize = group_type.gen_deserialize();
@@ -653,6 +1282,7 @@ impl MemberDesc {
                         return Err(deserializer.reject(Some(#tag), ParseRejectReason::TagAppearsMoreThanOnce));
                     }
                     let num_in_group_tag = #tag;
+                    deserializer.enter_group(num_in_group_tag, len)?;
                     let expected_tags = &[#(#expected_tags),*];
                     let mut #group_name_local = Vec::with_capacity(len as usize);
                     let last_run = false;
@@ -661,25 +1291,48 @@ impl MemberDesc {
                     }
                     let last_run = true;
                     #group_name_local.push(#group_deserialize?);
+                    deserializer.leave_group();
                     #group_name = Some(#group_name_local);
                 })
             }
+            // Component fields are matched one by one, see `leaves()`
+            MemberDesc::Component(_) => None,
         }
     }
 
-    pub fn gen_deserialize_match_entries(&self) -> Option<TokenStream> {
+    pub fn gen_deserialize_match_entries(&self, section: Section) -> Option<TokenStream> {
         let tag = match self {
             MemberDesc::Simple(member) => member.tag,
             MemberDesc::CustomLength(CustomLengthMember { len, .. }) => len.tag,
             MemberDesc::Group(GroupMember { num_in_group, .. }) => num_in_group.tag,
+            MemberDesc::Component(member) => {
+                let entries: Vec<_> = member
+                    .members
+                    .iter()
+                    .filter_map(|member| member.gen_deserialize_match_entries(section))
+                    .collect();
+                return (!entries.is_empty()).then(|| quote! { #(#entries),* });
+            }
         };
-        self.gen_deserialize_value().map(|deserialize_value| {
-            quote! {
-                #tag => {
-                    #deserialize_value
+        // Header fields have to precede body fields
+        let check_order = match section {
+            Section::Header => quote! {
+                if body_started {
+                    return Err(deserializer.reject(Some(#tag), ParseRejectReason::TagSpecifiedOutOfRequiredOrder));
                 }
-            }
-        })
+            },
+            Section::AdminBody | Section::AppBody => quote! { body_started = true; },
+            Section::Trailer | Section::Group => quote! {},
+        };
+        self.gen_deserialize_value(section)
+            .map(|deserialize_value| {
+                quote! {
+                    #tag => {
+                        #check_order
+                        #deserialize_value
+                    }
+                }
+            })
     }
 
     /// Generate code used to initialize structure.
@@ -687,6 +1340,37 @@ impl MemberDesc {
         match self {
             MemberDesc::Simple(member) => Some(member.gen_deserialize_struct_entries()),
             MemberDesc::CustomLength(_) | MemberDesc::Group(_) => None,
+            MemberDesc::Component(ComponentMember {
+                name,
+                type_,
+                required,
+                members,
+            }) => {
+                let entries = members
+                    .iter()
+                    .filter_map(MemberDesc::gen_deserialize_struct_entries);
+                if *required {
+                    Some(quote! {
+                        #name: #type_ { #(#entries,)* }
+                    })
+                } else {
+                    // Optional component is present when any of its fields is,
+                    // required fields are checked only in that case
+                    let present = self.leaves().into_iter().filter_map(|member| match member {
+                        MemberDesc::Simple(SimpleMember { name, .. }) => {
+                            Some(quote! { #name.is_some() })
+                        }
+                        _ => None,
+                    });
+                    Some(quote! {
+                        #name: if #(#present)||* {
+                            Some(#type_ { #(#entries,)* })
+                        } else {
+                            None
+                        }
+                    })
+                }
+            }
         }
     }
 }
@@ -1,23 +1,32 @@
-use std::rc::Rc;
+use std::{collections::HashSet, rc::Rc};
 
-use convert_case::{Case, Casing};
 use easyfix_dictionary::{MsgCat, MsgType};
-use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro2::{Ident, Literal, Span, TokenStream};
 use quote::quote;
+use serde_json::{json, Value};
 
-use crate::gen::member::MemberDesc;
+use crate::{
+    gen::{
+        gen_derives,
+        member::{MemberDesc, Section},
+    },
+    Derives,
+};
 
 pub struct MessageProperties {
     pub msg_cat: MsgCat,
-    pub _msg_type: MsgType,
+    pub msg_type: MsgType,
     pub header_members: Rc<Vec<MemberDesc>>,
     pub trailer_members: Rc<Vec<MemberDesc>>,
+    /// Optional header fields required by this message.
+    pub required_header: Vec<MemberDesc>,
 }
 
 pub struct Struct {
     name: Ident,
     members: Vec<MemberDesc>,
     msg_props: Option<MessageProperties>,
+    component: bool,
 }
 
 /*
@@ -44,14 +53,24 @@ struct MessageStruct {
 
 impl Struct {
     pub fn new(
-        name: &str,
+        name: Ident,
         members: Vec<MemberDesc>,
         msg_props: Option<MessageProperties>,
     ) -> Struct {
         Struct {
-            name: Ident::new(&name.to_case(Case::UpperCamel), Span::call_site()),
+            name,
             members,
             msg_props,
+            component: false,
+        }
+    }
+
+    /// Struct of non-repeating component, shared by all messages and groups
+    /// using it. It is (de)serialized as a part of its parent.
+    pub fn component(name: Ident, members: Vec<MemberDesc>) -> Struct {
+        Struct {
+            component: true,
+            ..Struct::new(name, members, None)
         }
     }
 
@@ -63,10 +82,35 @@ impl Struct {
         self.msg_props.as_ref()
     }
 
+    /// Group or component struct, generated in `groups` module.
     pub fn is_group(&self) -> bool {
         self.msg_props.is_none() && self.name != "Header" && self.name != "Trailer"
     }
 
+    /// Machine-readable description of generated struct.
+    pub fn describe(&self) -> Value {
+        let kind = if self.msg_props.is_some() {
+            "Message"
+        } else if self.component {
+            "Component"
+        } else if self.is_group() {
+            "Group"
+        } else {
+            // Header or Trailer
+            "Struct"
+        };
+        let mut desc = json!({
+            "name": self.name.to_string(),
+            "kind": kind,
+            "members": self.members.iter().flat_map(MemberDesc::describe).collect::<Vec<_>>(),
+        });
+        if let Some(props) = &self.msg_props {
+            desc["msg_type"] = json!(String::from_utf8_lossy(&props.msg_type));
+            desc["msg_cat"] = json!(format!("{:?}", props.msg_cat));
+        }
+        desc
+    }
+
     fn generate_serialize(&self) -> Vec<TokenStream> {
         self.members
             .iter()
@@ -74,19 +118,122 @@ impl Struct {
             .collect()
     }
 
+    /// Generate `Fields` implementation.
+    fn generate_visit(&self) -> TokenStream {
+        let name = &self.name;
+        let visit: Vec<_> = self
+            .members
+            .iter()
+            .filter_map(|member| member.gen_visit())
+            .collect();
+        let visitor = if visit.is_empty() {
+            quote! { _visitor }
+        } else {
+            quote! { visitor }
+        };
+        quote! {
+            impl fields::Fields for #name {
+                fn visit_fields<'a>(&'a self, #visitor: &mut dyn FnMut(TagNum, fields::FieldRef<'a>)) {
+                    #(#visit)*
+                }
+            }
+        }
+    }
+
+    /// Generate `PRESENCE_TAGS`, `presence()` and `is_set()`.
+    fn generate_presence(&self) -> TokenStream {
+        let base = quote! { self };
+        let mut seen = HashSet::new();
+        let checks: Vec<_> = self
+            .members
+            .iter()
+            .flat_map(|member| member.gen_presence(&base))
+            .filter(|(tag, _)| seen.insert(*tag))
+            .collect();
+        let words = checks.len().div_ceil(64);
+        let tags: Vec<_> = checks
+            .iter()
+            .map(|(tag, _)| Literal::u16_suffixed(*tag))
+            .collect();
+        let set_bits = checks.iter().enumerate().map(|(i, (_, check))| {
+            let i = Literal::usize_unsuffixed(i);
+            match check {
+                Some(check) => quote! {
+                    if #check {
+                        presence.set(#i);
+                    }
+                },
+                None => quote! { presence.set(#i); },
+            }
+        });
+        let (always_set, checked): (Vec<_>, Vec<_>) = checks
+            .iter()
+            .zip(&tags)
+            .partition(|((_, check), _)| check.is_none());
+        let always_set: Vec<_> = always_set.into_iter().map(|(_, tag)| tag).collect();
+        let is_set = if checked.is_empty() {
+            if always_set.is_empty() {
+                quote! {
+                    let _ = tag;
+                    false
+                }
+            } else {
+                quote! { matches!(tag, #(#always_set)|*) }
+            }
+        } else {
+            let always_set_arm =
+                (!always_set.is_empty()).then(|| quote! { #(#always_set)|* => true, });
+            let checked_arms = checked
+                .into_iter()
+                .map(|((_, check), tag)| quote! { #tag => #check, });
+            quote! {
+                match tag {
+                    #always_set_arm
+                    #(#checked_arms)*
+                    _ => false,
+                }
+            }
+        };
+        quote! {
+            /// Tags of fields tracked by [`Self::presence`], including
+            /// fields of components, bit `i` stands for `PRESENCE_TAGS[i]`.
+            pub const PRESENCE_TAGS: &[TagNum] = &[#(#tags,)*];
+
+            /// Bitmap of fields with value.
+            pub fn presence(&self) -> fields::FieldPresence<#words> {
+                let mut presence = fields::FieldPresence::new();
+                #(#set_bits)*
+                presence
+            }
+
+            /// Check if field has value, `false` for tags not defined
+            /// for this struct. Fields of groups entries are not checked,
+            /// only their NumInGroup field.
+            #[allow(clippy::manual_range_patterns)]
+            pub fn is_set(&self, tag: TagNum) -> bool {
+                #is_set
+            }
+        }
+    }
+
     fn generate_de_group(&self) -> TokenStream {
         let name = &self.name;
         let mut variables_definitions = Vec::with_capacity(self.members.len());
         let mut de_struct_entries = Vec::with_capacity(self.members.len());
         let mut de_match_entries = Vec::with_capacity(self.members.len());
-        let Some((first_member, members)) = self.members.split_first() else {
+        let leaves: Vec<_> = self.members.iter().flat_map(MemberDesc::leaves).collect();
+        let Some((first_member, members)) = leaves.split_first() else {
             panic!("Empty group {name}");
         };
-        for member in members {
+        for member in &leaves {
             variables_definitions.push(member.gen_opt_variables());
-            if let Some(de_match_entry) = member.gen_deserialize_match_entries() {
+        }
+        for member in members {
+            if let Some(de_match_entry) = member.gen_deserialize_match_entries(Section::Group) {
                 de_match_entries.push(de_match_entry);
             }
+        }
+        for member in &self.members {
             if let Some(de_struct_entry) = member.gen_deserialize_struct_entries() {
                 de_struct_entries.push(de_struct_entry);
             }
@@ -95,11 +242,7 @@ impl Struct {
         //let mut first_member = first_member.clone();
         //first_member.set_required(true);
         let first_member_tag = first_member.tag_num();
-        let first_member_deserialize_value = first_member.gen_deserialize_value();
-        let first_member_def = first_member.gen_opt_variables();
-        let first_member_struct_entry = first_member
-            .gen_deserialize_struct_entries()
-            .map(|entry| quote! { #entry, });
+        let first_member_deserialize_value = first_member.gen_deserialize_value(Section::Group);
 
         let deserialize_loop = if members.is_empty() {
             // No members - no loop
@@ -158,17 +301,26 @@ impl Struct {
                 last_run: bool,
             ) -> Result<#name, DeserializeError> {
                 // Check if tag of first group member is present
-                #first_member_def
                 #(#variables_definitions)*
-                if let Some(#first_member_tag) = deserializer.deserialize_tag_num()? {
-                    #first_member_deserialize_value
-                } else {
+                match deserializer.deserialize_tag_num()? {
+                    Some(#first_member_tag) => {
+                        #first_member_deserialize_value
+                    }
                     // if not, return error as first group member is always required (even when
                     // defined as optional)
-                    return Err(deserializer.reject(
-                        Some(#first_member_tag),
-                        ParseRejectReason::RequiredTagMissing)
-                    )
+                    Some(tag) if expected_tags.contains(&tag) => {
+                        return Err(deserializer.reject(
+                            Some(#first_member_tag),
+                            ParseRejectReason::RequiredTagMissing)
+                        )
+                    }
+                    // Fewer entries than NumInGroup says
+                    _ => {
+                        return Err(deserializer.reject(
+                            Some(num_in_group_tag),
+                            ParseRejectReason::IncorrectNumingroupCountForRepeatingGroup,
+                        ))
+                    }
                 };
 
                 let mut processed_tags = Vec::with_capacity(expected_tags.len());
@@ -181,7 +333,6 @@ impl Struct {
                 #deserialize_loop
 
                 Ok(#name {
-                    #first_member_struct_entry
                     #(#de_struct_entries,)*
                 })
             }
@@ -190,76 +341,84 @@ impl Struct {
 
     fn generate_de_message(&self) -> TokenStream {
         let name = &self.name;
-        let mut variables_definitions = Vec::with_capacity(self.members.len());
-        let mut de_header_entries = Vec::with_capacity(self.members.len());
-        let mut de_struct_entries = Vec::with_capacity(self.members.len());
-        let mut de_trailer_entries = Vec::with_capacity(self.members.len());
-        let mut de_match_entries = Vec::with_capacity(self.members.len()); //self.generate_de_match_entries();
-        for member in self.msg_props().unwrap().header_members.iter() {
-            variables_definitions.push(member.gen_opt_variables());
-            if let Some(de_match_entry) = member.gen_deserialize_match_entries() {
-                de_match_entries.push(de_match_entry);
-            }
-            if let Some(de_struct_entry) = member.gen_deserialize_struct_entries() {
-                de_header_entries.push(de_struct_entry);
-            }
-        }
-        for member in &self.members {
-            variables_definitions.push(member.gen_opt_variables());
-            if let Some(de_match_entry) = member.gen_deserialize_match_entries() {
-                de_match_entries.push(de_match_entry);
-            }
-            if let Some(de_struct_entry) = member.gen_deserialize_struct_entries() {
-                de_struct_entries.push(de_struct_entry);
-            }
-        }
-        for member in self.msg_props().unwrap().trailer_members.iter() {
-            variables_definitions.push(member.gen_opt_variables());
-            if let Some(de_match_entry) = member.gen_deserialize_match_entries() {
-                de_match_entries.push(de_match_entry);
-            }
-            if let Some(de_struct_entry) = member.gen_deserialize_struct_entries() {
-                de_trailer_entries.push(de_struct_entry);
-            }
-        }
-        quote! {
-            fn deserialize(
-                deserializer: &mut Deserializer,
-                begin_string: FixString,
-                body_length: Length,
-                msg_type: MsgType
-            ) -> Result<Box<FixtMessage>, DeserializeError> {
-                #(#variables_definitions)*
-                while let Some(tag) = deserializer.deserialize_tag_num()? {
-                    match tag {
-                        #(#de_match_entries,)*
-                        tag => {
-                            if FieldTag::from_tag_num(tag).is_some() {
-                                return Err(deserializer.reject(Some(tag), ParseRejectReason::TagNotDefinedForThisMessageType));
-                            } else {
-                                return Err(deserializer.reject(Some(tag), ParseRejectReason::UndefinedTag
-                ));
-                            }
-                        },
-                    }
+        let props = self.msg_props().unwrap();
+        // Invalid values of optional fields may be tolerated only in body
+        // of application messages
+        let section = if props.msg_cat == MsgCat::App {
+            Section::AppBody
+        } else {
+            Section::AdminBody
+        };
+        let de_struct_entries = self
+            .members
+            .iter()
+            .filter_map(MemberDesc::gen_deserialize_struct_entries);
+        gen_de_message(
+            &self.members,
+            section,
+            &props.header_members,
+            &props.trailer_members,
+            &props.required_header,
+            quote! { msg_type: MsgType },
+            quote! {},
+            quote! {
+                Message::#name(#name {
+                    #(#de_struct_entries,)*
+                })
+            },
+            quote! {
+                if FieldTag::from_tag_num(tag).is_some() {
+                    return Err(deserializer.reject(Some(tag), ParseRejectReason::TagNotDefinedForThisMessageType));
+                } else {
+                    return Err(deserializer.reject(Some(tag), ParseRejectReason::InvalidTagNumber));
                 }
-                Ok(Box::new(FixtMessage {
-                    header: Box::new(Header {
-                        #(#de_header_entries,)*
-                    }),
-                    body: Box::new(Message::#name(#name {
-                        #(#de_struct_entries,)*
-                    })),
-                    trailer: Box::new(Trailer {
-                        #(#de_trailer_entries,)*
-                    })
-                }))
-            }
-        }
+            },
+        )
     }
 
-    pub fn generate(&self) -> TokenStream {
+    /// Generate struct definition, with `new()` constructor taking values
+    /// of required members instead of `Default` implementation when
+    /// `required_constructors` is set and struct has any required member.
+    /// Field presence accessors are generated when `field_presence` is set.
+    pub fn generate(
+        &self,
+        derives: &Derives,
+        required_constructors: bool,
+        field_presence: bool,
+    ) -> TokenStream {
         let name = &self.name;
+        let constructor_params: Vec<_> = self
+            .members
+            .iter()
+            .filter_map(MemberDesc::gen_constructor_param)
+            .collect();
+        let fn_new = (required_constructors && !constructor_params.is_empty()).then(|| {
+            let inits = self
+                .members
+                .iter()
+                .filter_map(MemberDesc::gen_constructor_init);
+            quote! {
+                #[allow(clippy::too_many_arguments)]
+                pub fn new(#(#constructor_params),*) -> #name {
+                    #name {
+                        #(#inits,)*
+                    }
+                }
+            }
+        });
+        let derives = if fn_new.is_some() {
+            gen_derives(
+                quote! { Clone, Debug, PartialEq, },
+                quote! { PartialOrd, },
+                derives,
+            )
+        } else {
+            gen_derives(
+                quote! { Clone, Debug, Default, PartialEq, },
+                quote! { PartialOrd, },
+                derives,
+            )
+        };
 
         let mut members_definitions = Vec::with_capacity(self.members.len());
         for member in &self.members {
@@ -268,7 +427,7 @@ impl Struct {
             }
         }
 
-        let fn_deserialize = if self.name == "Header" || self.name == "Trailer" {
+        let fn_deserialize = if self.name == "Header" || self.name == "Trailer" || self.component {
             None
         } else if self.is_group() {
             Some(self.generate_de_group())
@@ -277,16 +436,31 @@ impl Struct {
         };
 
         let serialize = self.generate_serialize();
+        let presence = field_presence.then(|| self.generate_presence());
+        let visit = self.generate_visit();
 
         let fn_msg_type_msg_cat = if let Some(props) = self.msg_props() {
             let msg_cat = Ident::new(&format!("{:?}", props.msg_cat), Span::call_site());
+            let mut seen = HashSet::new();
+            let body_tags = self
+                .members
+                .iter()
+                .flat_map(MemberDesc::tags)
+                .filter(|tag| seen.insert(*tag))
+                .map(Literal::u16_suffixed);
             Some(quote! {
+                pub const MSG_TYPE: MsgType = MsgType::#name;
+                pub const MSG_CAT: MsgCat = MsgCat::#msg_cat;
+
+                /// Tags of all body fields, including group members.
+                pub const BODY_TAGS: &[TagNum] = &[#(#body_tags,)*];
+
                 pub const fn msg_type(&self) -> MsgType {
-                    MsgType::#name
+                    Self::MSG_TYPE
                 }
 
                 pub const fn msg_cat(&self) -> MsgCat {
-                    MsgCat::#msg_cat
+                    Self::MSG_CAT
                 }
             })
         } else {
@@ -294,22 +468,158 @@ impl Struct {
         };
 
         quote! {
-            #[derive(Clone, Debug, Default)]
-            #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
-            #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+            #derives
             pub struct #name {
                 #(#members_definitions,)*
             }
 
             impl #name {
+                #fn_new
+
                 pub(crate) fn serialize(&self, serializer: &mut Serializer) {
-                    #(#serialize;)*
+                    #(#serialize)*
                 }
 
                 #fn_deserialize
 
+                #presence
+
                 #fn_msg_type_msg_cat
             }
+
+            #visit
+        }
+    }
+}
+
+/// Generate `OtherMessage::deserialize()`, for messages of type not known
+/// at generation time. Header and trailer are deserialized as in other
+/// messages, body fields are kept as raw strings in order of appearance.
+pub fn generate_de_other_message(
+    header_members: &[MemberDesc],
+    trailer_members: &[MemberDesc],
+) -> TokenStream {
+    let fn_deserialize = gen_de_message(
+        &[],
+        Section::AppBody,
+        header_members,
+        trailer_members,
+        &[],
+        quote! { code: MsgTypeCode },
+        quote! {
+            let msg_type = MsgType::Other(code);
+            let mut fields = Vec::new();
+        },
+        quote! {
+            Message::Other(OtherMessage {
+                msg_type: code,
+                fields,
+            })
+        },
+        quote! {
+            body_started = true;
+            fields.push((tag, deserializer.deserialize_string()?));
+        },
+    );
+    quote! {
+        impl OtherMessage {
+            #fn_deserialize
+        }
+    }
+}
+
+/// Generate `deserialize()` function of message, body fields are
+/// deserialized according to `members`, tags not matching any member
+/// (nor header or trailer one) are handled by `unknown_tag` code.
+///
+/// When `parsed_header` is given, deserializer is expected to be past
+/// header fields (see `Deserializer::skip_header()`), so any header
+/// field is out of order.
+///
+/// Optional header fields listed in `required_header` are verified once
+/// header is complete.
+#[allow(clippy::too_many_arguments)]
+fn gen_de_message(
+    members: &[MemberDesc],
+    section: Section,
+    header_members: &[MemberDesc],
+    trailer_members: &[MemberDesc],
+    required_header: &[MemberDesc],
+    msg_type_param: TokenStream,
+    prelude: TokenStream,
+    body: TokenStream,
+    unknown_tag: TokenStream,
+) -> TokenStream {
+    let mut variables_definitions = Vec::with_capacity(members.len());
+    let mut de_header_entries = Vec::with_capacity(members.len());
+    let mut de_trailer_entries = Vec::with_capacity(members.len());
+    let mut de_match_entries = Vec::with_capacity(members.len());
+    for member in header_members.iter().flat_map(MemberDesc::leaves) {
+        variables_definitions.push(member.gen_opt_variables());
+        if let Some(de_match_entry) = member.gen_deserialize_match_entries(Section::Header) {
+            de_match_entries.push(de_match_entry);
+        }
+    }
+    for member in header_members.iter() {
+        if let Some(de_struct_entry) = member.gen_deserialize_struct_entries() {
+            de_header_entries.push(de_struct_entry);
+        }
+    }
+    for member in members.iter().flat_map(MemberDesc::leaves) {
+        variables_definitions.push(member.gen_opt_variables());
+        if let Some(de_match_entry) = member.gen_deserialize_match_entries(section) {
+            de_match_entries.push(de_match_entry);
+        }
+    }
+    for member in trailer_members.iter().flat_map(MemberDesc::leaves) {
+        variables_definitions.push(member.gen_opt_variables());
+        if let Some(de_match_entry) = member.gen_deserialize_match_entries(Section::Trailer) {
+            de_match_entries.push(de_match_entry);
+        }
+    }
+    for member in trailer_members.iter() {
+        if let Some(de_struct_entry) = member.gen_deserialize_struct_entries() {
+            de_trailer_entries.push(de_struct_entry);
+        }
+    }
+    let required_header_checks = required_header
+        .iter()
+        .filter_map(|member| member.gen_required_check(&quote! { header }));
+    quote! {
+        // Not every message has body fields read in the loop
+        #[allow(unused_mut, unused_assignments)]
+        pub(crate) fn deserialize(
+            deserializer: &mut Deserializer,
+            begin_string: FixString,
+            body_length: Length,
+            #msg_type_param,
+            parsed_header: Option<Box<Header>>,
+        ) -> Result<Box<FixtMessage>, DeserializeError> {
+            #prelude
+            #(#variables_definitions)*
+            let mut body_started = parsed_header.is_some();
+            while let Some(tag) = deserializer.deserialize_tag_num()? {
+                match tag {
+                    #(#de_match_entries,)*
+                    tag => {
+                        #unknown_tag
+                    },
+                }
+            }
+            let header = match parsed_header {
+                Some(header) => header,
+                None => Box::new(Header {
+                    #(#de_header_entries,)*
+                }),
+            };
+            #(#required_header_checks)*
+            Ok(Box::new(FixtMessage {
+                header,
+                body: Box::new(#body),
+                trailer: Box::new(Trailer {
+                    #(#de_trailer_entries,)*
+                })
+            }))
         }
     }
 }
@@ -1,36 +1,68 @@
+mod error;
 mod gen;
+mod naming;
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     error::Error,
     fs,
     io::prelude::*,
-    path::Path,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
     process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
     time::Instant,
 };
 
 pub use easyfix_dictionary as dictionary;
 use easyfix_dictionary::{Dictionary, ParseRejectReason};
-use proc_macro2::TokenStream;
 
-use crate::gen::Generator;
+pub use crate::{
+    error::{ElementKind, GenerateError, GenerateErrors},
+    naming::{IdentKind, Rename, RenameReason, ReservedEscape},
+};
+use crate::{gen::Generator, naming::NamingRules};
 
-fn create_source_file(
-    tokens_stream: TokenStream,
-    source_file: impl AsRef<Path>,
-) -> Result<(), Box<dyn Error + 'static>> {
-    let code = tokens_stream.to_string();
+/// Check if `rustfmt` can be run, warn when it can't, so code is written
+/// unformatted instead of failing the build.
+fn rustfmt_available() -> bool {
+    match Command::new("rustfmt")
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+    {
+        Ok(status) if status.success() => true,
+        Ok(status) => {
+            println!("cargo:warning=rustfmt unusable ({status}), generated code is not formatted");
+            false
+        }
+        Err(err) => {
+            println!(
+                "cargo:warning=rustfmt not available ({err}), generated code is not formatted"
+            );
+            false
+        }
+    }
+}
 
-    let output = if true {
+fn create_source_file(
+    code: String,
+    source_file: &Path,
+    rustfmt: bool,
+) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+    let output = if rustfmt {
         let start = Instant::now();
-        #[expect(clippy::zombie_processes)]
         let mut rustfmt = Command::new("rustfmt")
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .spawn()
-            .expect("failed to run rustfmt");
+            .spawn()?;
         rustfmt.stdin.take().unwrap().write_all(code.as_bytes())?;
         let output = rustfmt.wait_with_output()?;
         eprintln!("rustfmt output status: {:?}", output.status);
@@ -51,17 +83,95 @@ fn create_source_file(
         code.into_bytes()
     };
 
-    let mut file = fs::File::create(source_file.as_ref())?;
+    let mut file = fs::File::create(source_file)?;
     file.write_all(&output)?;
-    eprintln!(
-        "{}: {} bytes written",
-        source_file.as_ref().display(),
-        output.len()
-    );
+    eprintln!("{}: {} bytes written", source_file.display(), output.len());
 
     Ok(())
 }
 
+/// Format (when `rustfmt` is set) and write sources using as many threads
+/// as there are CPUs.
+fn create_source_files(
+    sources: Vec<(String, PathBuf)>,
+    rustfmt: bool,
+) -> Result<(), Box<dyn Error + 'static>> {
+    let workers = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(sources.len());
+    let sources = Mutex::new(sources.into_iter());
+    // Lock is released before formatting
+    let next_source = || sources.lock().unwrap().next();
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| -> Result<(), Box<dyn Error + Send + Sync>> {
+                    while let Some((code, source_file)) = next_source() {
+                        create_source_file(code, &source_file, rustfmt)?;
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .try_for_each(|handle| handle.join().expect("source file thread panicked"))
+    })
+    .map_err(|err| -> Box<dyn Error> { err })
+}
+
+/// Generate definitions of all structs (see [`Generator::generate_struct`])
+/// using up to `workers` threads.
+///
+/// Token streams are not `Send`, so `generator` is used only by the current
+/// thread, the others build their own from read-only `dictionary`. It pays
+/// off for big dictionaries, where building generator takes a fraction of
+/// generating code.
+fn generate_structs(
+    generator: &Generator,
+    dictionary: &Dictionary,
+    options: &GeneratorOptions,
+    workers: usize,
+) -> Result<Vec<String>, GenerateErrors> {
+    let count = generator.structs_count();
+    let workers = workers.min(count);
+    let next_index = AtomicUsize::new(0);
+    let generate = |generator: &Generator| {
+        let mut struct_defs = Vec::new();
+        loop {
+            let index = next_index.fetch_add(1, Ordering::Relaxed);
+            if index >= count {
+                return struct_defs;
+            }
+            struct_defs.push((index, generator.generate_struct(index)));
+        }
+    };
+    let mut struct_defs = thread::scope(|scope| {
+        let handles: Vec<_> = (1..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let generator = Generator::new(dictionary, options)?;
+                    assert_eq!(generator.structs_count(), count, "structs differ");
+                    Ok(generate(&generator))
+                })
+            })
+            .collect();
+        let mut struct_defs = generate(generator);
+        for handle in handles {
+            let worker_defs: Result<_, GenerateErrors> =
+                handle.join().expect("struct generation thread panicked");
+            struct_defs.extend(worker_defs?);
+        }
+        Ok::<_, GenerateErrors>(struct_defs)
+    })?;
+
+    struct_defs.sort_unstable_by_key(|(index, _)| *index);
+    Ok(struct_defs
+        .into_iter()
+        .map(|(_, struct_def)| struct_def)
+        .collect())
+}
+
 fn log_duration<T>(msg: &str, action: impl FnOnce() -> T) -> T {
     let start = Instant::now();
     let result = action();
@@ -70,6 +180,284 @@ fn log_duration<T>(msg: &str, action: impl FnOnce() -> T) -> T {
     result
 }
 
+/// Optional derives of generated types.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Derives {
+    /// Derive `Hash`.
+    pub hash: bool,
+    /// Derive `PartialOrd` (and `Ord` for enums).
+    pub partial_ord: bool,
+    /// Derive `serde::Serialize`/`serde::Deserialize` when `serialize`/
+    /// `deserialize` feature of the crate including generated code is enabled.
+    pub serde: bool,
+}
+
+/// Code generation options.
+#[derive(Clone, Debug)]
+pub struct GeneratorOptions {
+    flatten_components: bool,
+    flattened_components: HashMap<String, bool>,
+    split_messages: bool,
+    description_file: Option<PathBuf>,
+    enum_derives: Derives,
+    struct_derives: Derives,
+    repeated_tags: HashSet<u16>,
+    utf8_tags: HashSet<u16>,
+    custom_codecs: HashMap<u16, String>,
+    required_constructors: bool,
+    field_presence: bool,
+    custom_messages_file: Option<PathBuf>,
+    add_missing_admin_messages: bool,
+    naming: NamingRules,
+    rustfmt: bool,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> GeneratorOptions {
+        GeneratorOptions {
+            flatten_components: true,
+            flattened_components: HashMap::new(),
+            split_messages: false,
+            description_file: None,
+            enum_derives: Derives {
+                hash: true,
+                partial_ord: false,
+                serde: true,
+            },
+            struct_derives: Derives {
+                hash: false,
+                partial_ord: false,
+                serde: true,
+            },
+            repeated_tags: HashSet::new(),
+            utf8_tags: HashSet::new(),
+            custom_codecs: HashMap::new(),
+            required_constructors: false,
+            field_presence: false,
+            custom_messages_file: None,
+            add_missing_admin_messages: false,
+            naming: NamingRules::default(),
+            rustfmt: true,
+        }
+    }
+}
+
+impl GeneratorOptions {
+    pub fn new() -> GeneratorOptions {
+        GeneratorOptions::default()
+    }
+
+    /// When set (default), fields of non-repeating components are generated
+    /// directly in every message, group, `Header` and `Trailer` using them.
+    ///
+    /// Otherwise each component is generated once, as a struct in the groups
+    /// file, and referenced by a member named after the component
+    /// (e.g. `instrument: Instrument`, `Option<Instrument>` when optional).
+    pub fn flatten_components(mut self, flatten_components: bool) -> GeneratorOptions {
+        self.flatten_components = flatten_components;
+        self
+    }
+
+    /// Override [`flatten_components`] for component `name`, e.g. flatten
+    /// `Instrument` but keep `Parties` as separate struct. Components
+    /// wrapping repeating groups are never flattened.
+    ///
+    /// [`flatten_components`]: GeneratorOptions::flatten_components
+    pub fn flatten_component(mut self, name: impl Into<String>, flatten: bool) -> GeneratorOptions {
+        self.flattened_components.insert(name.into(), flatten);
+        self
+    }
+
+    /// Whether fields of non-repeating component `name` are generated
+    /// directly in its parent.
+    fn flattens(&self, name: &str) -> bool {
+        self.flattened_components
+            .get(name)
+            .copied()
+            .unwrap_or(self.flatten_components)
+    }
+
+    /// When set, every message (and `Header`/`Trailer`) is generated in
+    /// separate module file, placed in directory named after messages file
+    /// without extension (e.g. `generated_messages/new_order_single.rs`).
+    /// Messages file declares these modules and re-exports their content,
+    /// so it is still the only file to include.
+    ///
+    /// Disabled by default. Smaller modules give rustc more room for
+    /// incremental and parallel compilation.
+    pub fn split_messages(mut self, split_messages: bool) -> GeneratorOptions {
+        self.split_messages = split_messages;
+        self
+    }
+
+    /// When set, JSON description of generated enums, messages, groups and
+    /// components (with their members, tags and types) is written to given
+    /// file, so bindings in other languages can be generated from exactly
+    /// the same definitions.
+    pub fn description_file(mut self, description_file: impl Into<PathBuf>) -> GeneratorOptions {
+        self.description_file = Some(description_file.into());
+        self
+    }
+
+    /// Derives of field enums and `FieldTag`, in addition to
+    /// `Clone, Copy, Debug, Default, Eq, PartialEq`.
+    ///
+    /// By default `Hash` and serde derives are enabled. `MsgType` always
+    /// derives `Hash`.
+    pub fn enum_derives(mut self, enum_derives: Derives) -> GeneratorOptions {
+        self.enum_derives = enum_derives;
+        self
+    }
+
+    /// Derives of messages, groups, `Message` and `FixtMessage`, in addition
+    /// to `Clone, Debug, PartialEq` (and `Default` for structs, unless
+    /// replaced by constructor, see [`required_constructors`]).
+    ///
+    /// [`required_constructors`]: GeneratorOptions::required_constructors
+    ///
+    /// By default only serde derives are enabled.
+    pub fn struct_derives(mut self, struct_derives: Derives) -> GeneratorOptions {
+        self.struct_derives = struct_derives;
+        self
+    }
+
+    /// Tags of optional message body fields allowed to appear more than
+    /// once (e.g. repeated Text<58>), generated as `Vec` of values
+    /// in order of appearance instead of `Option`.
+    ///
+    /// Applies to fields defined directly in messages (or in flattened
+    /// components), header, trailer and group fields are never repeated.
+    pub fn repeated_tags(mut self, tags: impl IntoIterator<Item = u16>) -> GeneratorOptions {
+        self.repeated_tags = tags.into_iter().collect();
+        self
+    }
+
+    /// Tags of String fields (e.g. Text<58>) holding UTF-8 text instead of
+    /// ASCII, generated as `Utf8String`, which allows any character except
+    /// control ones. Fields with enumerated values are not affected.
+    ///
+    /// Applies to header, trailer, message, component and group fields.
+    pub fn utf8_tags(mut self, tags: impl IntoIterator<Item = u16>) -> GeneratorOptions {
+        self.utf8_tags = tags.into_iter().collect();
+        self
+    }
+
+    /// Serialize and deserialize values of field `tag` with `codec`, path of
+    /// type implementing `FieldCodec` (see `easyfix_messages::codec`) for
+    /// value type of the field, e.g. `crate::codec::ZeroPadded<6>` for
+    /// Int field written with leading zeros. Path is resolved in module
+    /// including generated code.
+    ///
+    /// Applies to header, trailer, message, component and group fields,
+    /// except BeginString<8>, BodyLength<9>, CheckSum<10>, MsgSeqNum<34>,
+    /// MsgType<35> and fields of Length, NumInGroup, Data and XmlData type.
+    pub fn custom_codec(mut self, tag: u16, codec: impl Into<String>) -> GeneratorOptions {
+        self.custom_codecs.insert(tag, codec.into());
+        self
+    }
+
+    /// When set, messages, groups, components and `Header` with required
+    /// fields get `new()` constructor taking values of all required fields
+    /// (in order of definition) and don't implement `Default`, so it is not
+    /// possible to create message with required field silently left empty.
+    /// Optional fields are initialized to `None` (or empty `Vec`).
+    ///
+    /// Structs without required fields still implement `Default`.
+    /// Disabled by default.
+    pub fn required_constructors(mut self, required_constructors: bool) -> GeneratorOptions {
+        self.required_constructors = required_constructors;
+        self
+    }
+
+    /// When set, messages, groups, `Header` and `Trailer` get
+    /// `PRESENCE_TAGS`, `presence()` returning `FieldPresence` bitmap of
+    /// fields with value, and `is_set(tag)`.
+    ///
+    /// The bitmap is computed from field values on every `presence()` call,
+    /// it's not stored in structs nor used by serialization. Disabled by
+    /// default.
+    pub fn field_presence(mut self, field_presence: bool) -> GeneratorOptions {
+        self.field_presence = field_presence;
+        self
+    }
+
+    /// When set, user-defined messages (e.g. venue specific `U1`) are read
+    /// from given XML file and generated together with messages of FIX XML,
+    /// as `Message` and `MsgType` variants named after them.
+    ///
+    /// File has `messages` node and optionally `components` and `fields`
+    /// nodes, in the same format as FIX XML, see
+    /// [`Dictionary::process_custom_messages_xml`].
+    pub fn custom_messages_file(
+        mut self,
+        custom_messages_file: impl Into<PathBuf>,
+    ) -> GeneratorOptions {
+        self.custom_messages_file = Some(custom_messages_file.into());
+        self
+    }
+
+    /// When set, standard admin messages (Heartbeat, Logon, etc.) missing
+    /// in FIX XML are added before generation, so session code can be used
+    /// with dictionaries of venues omitting them (e.g. FIX 4.2 XML
+    /// describing application messages only).
+    ///
+    /// Supported for FIX 4.2, 4.3 and 4.4, see
+    /// [`Dictionary::add_missing_admin_messages`]. Disabled by default.
+    pub fn add_missing_admin_messages(
+        mut self,
+        add_missing_admin_messages: bool,
+    ) -> GeneratorOptions {
+        self.add_missing_admin_messages = add_missing_admin_messages;
+        self
+    }
+
+    /// Generate identifiers of dictionary element `name` (field, component,
+    /// group or message) as if it was named `new_name`, e.g. rename
+    /// `Yield` to `YieldValue` to get `yield_value` members and `YieldValue`
+    /// enum instead of `yield_` and `Yield`.
+    ///
+    /// `Header` and `Trailer` are never renamed.
+    pub fn rename(
+        mut self,
+        name: impl Into<String>,
+        new_name: impl Into<String>,
+    ) -> GeneratorOptions {
+        self.naming.renames.insert(name.into(), new_name.into());
+        self
+    }
+
+    /// Identifiers escaped (see [`reserved_escape`]) in addition to Rust
+    /// keywords, e.g. names of types used by generated code, when they
+    /// collide with names from dictionary.
+    ///
+    /// [`reserved_escape`]: GeneratorOptions::reserved_escape
+    pub fn reserved_words(
+        mut self,
+        words: impl IntoIterator<Item = impl Into<String>>,
+    ) -> GeneratorOptions {
+        self.naming.reserved_words = words.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// How identifiers equal to reserved word are escaped, by default `_`
+    /// suffix is appended (e.g. `yield_`).
+    pub fn reserved_escape(mut self, reserved_escape: ReservedEscape) -> GeneratorOptions {
+        self.naming.reserved_escape = reserved_escape;
+        self
+    }
+
+    /// When set (default), generated sources are formatted with `rustfmt`
+    /// found in `PATH`. If it's missing, sources are written unformatted
+    /// and a cargo warning is printed.
+    ///
+    /// When cleared, no subprocess is spawned at all, e.g. in hermetic
+    /// build sandboxes not allowing it.
+    pub fn rustfmt(mut self, rustfmt: bool) -> GeneratorOptions {
+        self.rustfmt = rustfmt;
+        self
+    }
+}
+
 pub fn generate_fix_messages(
     fixt_xml_path: Option<impl AsRef<Path>>,
     fix_xml_path: impl AsRef<Path>,
@@ -77,6 +465,26 @@ pub fn generate_fix_messages(
     groups_file: impl AsRef<Path>,
     messages_file: impl AsRef<Path>,
     reject_reason_overrides: Option<HashMap<ParseRejectReason, String>>,
+) -> std::result::Result<(), Box<dyn std::error::Error + 'static>> {
+    generate_fix_messages_with_options(
+        fixt_xml_path,
+        fix_xml_path,
+        fields_file,
+        groups_file,
+        messages_file,
+        reject_reason_overrides,
+        &GeneratorOptions::default(),
+    )
+}
+
+pub fn generate_fix_messages_with_options(
+    fixt_xml_path: Option<impl AsRef<Path>>,
+    fix_xml_path: impl AsRef<Path>,
+    fields_file: impl AsRef<Path>,
+    groups_file: impl AsRef<Path>,
+    messages_file: impl AsRef<Path>,
+    reject_reason_overrides: Option<HashMap<ParseRejectReason, String>>,
+    options: &GeneratorOptions,
 ) -> std::result::Result<(), Box<dyn std::error::Error + 'static>> {
     eprintln!("fields file path: {}", fields_file.as_ref().display());
     eprintln!("groups file path: {}", groups_file.as_ref().display());
@@ -97,20 +505,113 @@ pub fn generate_fix_messages(
         })?;
     }
 
-    let generator = log_duration("Generator ready", || Generator::new(&dictionary));
+    if let Some(custom_messages_file) = &options.custom_messages_file {
+        log_duration("Custom messages XML processed", || {
+            let custom_xml = fs::read_to_string(custom_messages_file)?;
+            dictionary.process_custom_messages_xml(&custom_xml)
+        })?;
+    }
+
+    if options.add_missing_admin_messages {
+        let added = dictionary.add_missing_admin_messages()?;
+        for msg_type in added {
+            let message = dictionary.message(&msg_type).expect("admin message added");
+            eprintln!("Admin message `{}` added", message.name());
+        }
+    }
 
-    create_source_file(
-        log_duration("Fields token stream", || generator.generate_fields()),
-        fields_file,
-    )?;
-    create_source_file(
-        log_duration("Groups token stream", || generator.generate_groups()),
-        groups_file,
-    )?;
-    create_source_file(
-        log_duration("Messages token stream", || generator.generate_messages()),
-        messages_file,
-    )?;
+    let generator = log_duration("Generator ready", || Generator::new(&dictionary, options))?;
+    // Expected to be called from build script, so report warnings to cargo
+    for warning in generator.warnings() {
+        println!("cargo:warning={warning}");
+    }
+    for rename in generator.renames() {
+        eprintln!("{rename}");
+    }
+
+    let struct_defs = log_duration("Structs generated", || {
+        let workers = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        generate_structs(&generator, &dictionary, options, workers)
+    })?;
+    let mut sources = vec![
+        (
+            log_duration("Fields token stream", || generator.generate_fields()).to_string(),
+            fields_file.as_ref().to_owned(),
+        ),
+        (
+            generator.generate_groups_with(&struct_defs),
+            groups_file.as_ref().to_owned(),
+        ),
+    ];
+    if options.split_messages {
+        let messages_dir = messages_file.as_ref().with_extension("");
+        fs::create_dir_all(&messages_dir)?;
+        let (messages, modules) = log_duration("Messages token stream", || {
+            generator.generate_split_messages(&messages_dir, &struct_defs)
+        })?;
+        sources.push((messages, messages_file.as_ref().to_owned()));
+        sources.extend(modules.into_iter().map(|(path, module)| (module, path)));
+    } else {
+        sources.push((
+            log_duration("Messages token stream", || {
+                generator.generate_messages_with(&struct_defs)
+            }),
+            messages_file.as_ref().to_owned(),
+        ));
+    }
+    create_source_files(sources, options.rustfmt && rustfmt_available())?;
+
+    if let Some(description_file) = &options.description_file {
+        let description = log_duration("Description", || generator.generate_description());
+        fs::write(description_file, description)?;
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use easyfix_dictionary::Dictionary;
+
+    use super::{create_source_files, generate_structs};
+    use crate::{gen::Generator, GeneratorOptions};
+
+    #[test]
+    fn structs_generated_concurrently() {
+        let xml = |name: &str| {
+            fs::read_to_string(format!("{}/../xml/{name}", env!("CARGO_MANIFEST_DIR"))).unwrap()
+        };
+        let mut dictionary = Dictionary::new(None);
+        dictionary.process_fixt_xml(&xml("FIXT11.xml")).unwrap();
+        dictionary.process_fix_xml(&xml("FIX50SP2.xml")).unwrap();
+        let options = GeneratorOptions::new();
+        let generator = Generator::new(&dictionary, &options).unwrap();
+        let sequential: Vec<_> = (0..generator.structs_count())
+            .map(|index| generator.generate_struct(index))
+            .collect();
+
+        let concurrent = generate_structs(&generator, &dictionary, &options, 4).unwrap();
+        assert!(concurrent.len() > 1);
+        assert_eq!(concurrent, sequential);
+    }
+
+    #[test]
+    fn sources_written_unformatted_without_rustfmt() {
+        let dir = env::temp_dir().join(format!("easyfix-gen-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let code = "pub struct Heartbeat { test_req_id : Option < FixString > , }";
+        let sources = (0..3)
+            .map(|i| (code.to_owned(), dir.join(format!("source_{i}.rs"))))
+            .collect();
+
+        create_source_files(sources, false).unwrap();
+
+        for i in 0..3 {
+            let written = fs::read_to_string(dir.join(format!("source_{i}.rs"))).unwrap();
+            assert_eq!(written, code);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
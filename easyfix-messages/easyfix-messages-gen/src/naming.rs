@@ -0,0 +1,230 @@
+//! Identifiers of generated code derived from dictionary names.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+};
+
+use convert_case::{Case, Casing};
+use proc_macro2::{Ident, Span};
+
+/// Rust keywords (strict, reserved and weak ones usable only in some
+/// contexts), generated members named after them are escaped.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "union", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// How generated identifiers equal to Rust keywords (or words set with
+/// [`GeneratorOptions::reserved_words`]) are escaped.
+///
+/// [`GeneratorOptions::reserved_words`]: crate::GeneratorOptions::reserved_words
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReservedEscape {
+    /// Append given suffix, e.g. `yield_` (default, with `_`).
+    Suffix(String),
+    /// Prepend given prefix, e.g. `fix_yield` with `fix_`.
+    Prefix(String),
+}
+
+impl Default for ReservedEscape {
+    fn default() -> ReservedEscape {
+        ReservedEscape::Suffix("_".to_owned())
+    }
+}
+
+/// Kind of generated identifier.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum IdentKind {
+    /// Struct member, e.g. `cl_ord_id`.
+    Member,
+    /// Message, group, component or field enum type, e.g. `NewOrderSingle`.
+    Type,
+}
+
+impl fmt::Display for IdentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdentKind::Member => f.write_str("member"),
+            IdentKind::Type => f.write_str("type"),
+        }
+    }
+}
+
+/// Why generated identifier differs from default convention.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum RenameReason {
+    /// Name set with [`GeneratorOptions::rename`].
+    ///
+    /// [`GeneratorOptions::rename`]: crate::GeneratorOptions::rename
+    Rule,
+    /// Identifier is reserved word.
+    Reserved,
+    /// Other member of the same struct has the same identifier, tag number
+    /// was appended.
+    Collision,
+}
+
+impl fmt::Display for RenameReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenameReason::Rule => f.write_str("rename rule"),
+            RenameReason::Reserved => f.write_str("reserved word"),
+            RenameReason::Collision => f.write_str("name collision"),
+        }
+    }
+}
+
+/// Identifier generated differently than default convention would do.
+///
+/// All renames are printed to build script output and listed in
+/// [`GeneratorOptions::description_file`].
+///
+/// [`GeneratorOptions::description_file`]: crate::GeneratorOptions::description_file
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Rename {
+    /// Name of element in dictionary.
+    pub name: String,
+    pub kind: IdentKind,
+    /// Generated identifier.
+    pub ident: String,
+    pub reason: RenameReason,
+}
+
+impl fmt::Display for Rename {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` generated as {} `{}` ({})",
+            self.name, self.kind, self.ident, self.reason
+        )
+    }
+}
+
+/// Naming rules set in `GeneratorOptions`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct NamingRules {
+    pub renames: HashMap<String, String>,
+    pub reserved_words: HashSet<String>,
+    pub reserved_escape: ReservedEscape,
+}
+
+/// Converts dictionary names to identifiers and records all renames.
+pub(crate) struct Naming<'a> {
+    rules: &'a NamingRules,
+    renames: RefCell<BTreeSet<Rename>>,
+    /// Names converted to `snake_case` and `UpperCamelCase`, as conversion
+    /// is slow and the same fields are used by many messages.
+    converted: RefCell<HashMap<(String, bool), String>>,
+}
+
+impl<'a> Naming<'a> {
+    pub fn new(rules: &'a NamingRules) -> Naming<'a> {
+        Naming {
+            rules,
+            renames: RefCell::default(),
+            converted: RefCell::default(),
+        }
+    }
+
+    /// Identifier of struct member named after dictionary element.
+    pub fn member(&self, name: &str) -> Ident {
+        self.ident(name, IdentKind::Member, Case::Snake)
+    }
+
+    /// Identifier of type named after dictionary element.
+    pub fn type_(&self, name: &str) -> Ident {
+        self.ident(name, IdentKind::Type, Case::UpperCamel)
+    }
+
+    /// Identifier of member colliding with other one, made unique with
+    /// `tag` of the member.
+    pub fn collision(&self, name: &Ident, tag: u16) -> Ident {
+        let ident = format!("{name}_{tag}");
+        self.record(
+            name.to_string(),
+            IdentKind::Member,
+            &ident,
+            RenameReason::Collision,
+        );
+        Ident::new(&ident, Span::call_site())
+    }
+
+    pub fn into_renames(self) -> Vec<Rename> {
+        self.renames.into_inner().into_iter().collect()
+    }
+
+    fn ident(&self, name: &str, kind: IdentKind, case: Case) -> Ident {
+        let mut ident = match self.rules.renames.get(name) {
+            Some(renamed) => {
+                let ident = self.convert(renamed, case);
+                self.record(name.to_owned(), kind, &ident, RenameReason::Rule);
+                ident
+            }
+            None => self.convert(name, case),
+        };
+        if KEYWORDS.contains(&ident.as_str()) || self.rules.reserved_words.contains(&ident) {
+            match &self.rules.reserved_escape {
+                ReservedEscape::Suffix(suffix) => ident.push_str(suffix),
+                ReservedEscape::Prefix(prefix) => ident.insert_str(0, prefix),
+            }
+            self.record(name.to_owned(), kind, &ident, RenameReason::Reserved);
+        }
+        Ident::new(&ident, Span::call_site())
+    }
+
+    fn convert(&self, name: &str, case: Case) -> String {
+        self.converted
+            .borrow_mut()
+            .entry((name.to_owned(), case == Case::Snake))
+            .or_insert_with(|| name.to_case(case))
+            .clone()
+    }
+
+    fn record(&self, name: String, kind: IdentKind, ident: &str, reason: RenameReason) {
+        self.renames.borrow_mut().insert(Rename {
+            name,
+            kind,
+            ident: ident.to_owned(),
+            reason,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IdentKind, Naming, NamingRules, RenameReason, ReservedEscape};
+
+    #[test]
+    fn rules_and_reserved_words() {
+        let mut rules = NamingRules::default();
+        rules
+            .renames
+            .insert("Yield".to_owned(), "YieldValue".to_owned());
+        rules.reserved_words.insert("Header".to_owned());
+        let naming = Naming::new(&rules);
+
+        assert_eq!(naming.member("Yield"), "yield_value");
+        assert_eq!(naming.type_("Yield"), "YieldValue");
+        assert_eq!(naming.member("Type"), "type_");
+        assert_eq!(naming.member("SecurityType"), "security_type");
+        assert_eq!(naming.type_("Header"), "Header_");
+
+        let renames = naming.into_renames();
+        assert_eq!(renames.len(), 4);
+        assert_eq!(renames[1].name, "Type");
+        assert_eq!(renames[1].kind, IdentKind::Member);
+        assert_eq!(renames[1].ident, "type_");
+        assert_eq!(renames[1].reason, RenameReason::Reserved);
+
+        let rules = NamingRules {
+            reserved_escape: ReservedEscape::Prefix("fix_".to_owned()),
+            ..NamingRules::default()
+        };
+        assert_eq!(Naming::new(&rules).member("Yield"), "fix_yield");
+    }
+}
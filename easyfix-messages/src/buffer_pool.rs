@@ -0,0 +1,314 @@
+//! Pool of reusable byte buffers.
+//!
+//! Buffers are grouped by capacity. [`BufferPool::take`] returns buffer
+//! of the smallest configured capacity able to hold requested number of bytes,
+//! the buffer goes back to the pool when dropped, so in steady state
+//! no allocation is needed for Data/XmlData fields and IO buffers.
+
+use std::{
+    cmp, fmt, hash, mem, ops,
+    sync::{Arc, Mutex, Weak},
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug)]
+struct Bucket {
+    capacity: usize,
+    free: Mutex<Vec<Vec<u8>>>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    // Sorted by capacity
+    buckets: Vec<Bucket>,
+    max_buffers: usize,
+}
+
+impl Inner {
+    fn recycle(&self, mut buf: Vec<u8>) {
+        // Buffer could grow while in use, put it in the biggest bucket it fits
+        let Some(bucket) = self
+            .buckets
+            .iter()
+            .rev()
+            .find(|bucket| bucket.capacity <= buf.capacity())
+        else {
+            return;
+        };
+        let mut free = bucket.free.lock().expect("buffer pool poisoned");
+        if free.len() < self.max_buffers {
+            buf.clear();
+            free.push(buf);
+        }
+    }
+}
+
+/// Shared pool of byte buffers, cloning it is cheap.
+#[derive(Clone, Debug)]
+pub struct BufferPool {
+    inner: Arc<Inner>,
+}
+
+impl BufferPool {
+    /// Create pool of buffers of given `sizes` (capacities), keeping
+    /// at most `max_buffers` idle buffers of each size.
+    pub fn new(sizes: &[usize], max_buffers: usize) -> BufferPool {
+        let mut sizes = sizes.to_vec();
+        sizes.sort_unstable();
+        sizes.dedup();
+        BufferPool {
+            inner: Arc::new(Inner {
+                buckets: sizes
+                    .into_iter()
+                    .map(|capacity| Bucket {
+                        capacity,
+                        free: Mutex::new(Vec::new()),
+                    })
+                    .collect(),
+                max_buffers,
+            }),
+        }
+    }
+
+    /// Take empty buffer able to hold at least `len` bytes.
+    ///
+    /// When `len` exceeds the biggest configured size, buffer is allocated
+    /// as usual and not returned to the pool.
+    pub fn take(&self, len: usize) -> PooledBuf {
+        let Some(bucket) = self
+            .inner
+            .buckets
+            .iter()
+            .find(|bucket| bucket.capacity >= len)
+        else {
+            return PooledBuf::from(Vec::with_capacity(len));
+        };
+        let buf = bucket
+            .free
+            .lock()
+            .expect("buffer pool poisoned")
+            .pop()
+            .unwrap_or_else(|| Vec::with_capacity(bucket.capacity));
+        PooledBuf {
+            buf,
+            pool: Some(Arc::downgrade(&self.inner)),
+        }
+    }
+
+    /// Give buffer obtained with [`PooledBuf::into_vec`] (or any other
+    /// buffer) back to the pool.
+    pub fn recycle(&self, buf: Vec<u8>) {
+        self.inner.recycle(buf);
+    }
+
+    /// Number of idle buffers kept in the pool.
+    pub fn idle_buffers(&self) -> usize {
+        self.inner
+            .buckets
+            .iter()
+            .map(|bucket| bucket.free.lock().expect("buffer pool poisoned").len())
+            .sum()
+    }
+}
+
+impl<'de> Deserialize<'de> for BufferPool {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Config {
+            sizes: Vec<usize>,
+            max_buffers: usize,
+        }
+
+        let Config { sizes, max_buffers } = Config::deserialize(deserializer)?;
+        Ok(BufferPool::new(&sizes, max_buffers))
+    }
+}
+
+/// Byte buffer which is given back to its [`BufferPool`] when dropped.
+///
+/// Buffers created with `From`/`Default` or cloned ones don't belong
+/// to any pool and behave like plain `Vec<u8>`.
+#[derive(Default)]
+pub struct PooledBuf {
+    buf: Vec<u8>,
+    pool: Option<Weak<Inner>>,
+}
+
+impl PooledBuf {
+    /// Detach buffer from the pool.
+    pub fn into_vec(mut self) -> Vec<u8> {
+        self.pool = None;
+        mem::take(&mut self.buf)
+    }
+}
+
+impl Drop for PooledBuf {
+    fn drop(&mut self) {
+        if let Some(pool) = self.pool.take().and_then(|pool| pool.upgrade()) {
+            pool.recycle(mem::take(&mut self.buf));
+        }
+    }
+}
+
+impl Clone for PooledBuf {
+    fn clone(&self) -> Self {
+        PooledBuf::from(self.buf.clone())
+    }
+}
+
+impl fmt::Debug for PooledBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.buf.fmt(f)
+    }
+}
+
+impl PartialEq for PooledBuf {
+    fn eq(&self, other: &Self) -> bool {
+        self.buf == other.buf
+    }
+}
+
+impl Eq for PooledBuf {}
+
+impl PartialEq<[u8]> for PooledBuf {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.buf == other
+    }
+}
+
+impl PartialEq<Vec<u8>> for PooledBuf {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        self.buf == *other
+    }
+}
+
+impl PartialOrd for PooledBuf {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PooledBuf {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.buf.cmp(&other.buf)
+    }
+}
+
+impl hash::Hash for PooledBuf {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.buf.hash(state)
+    }
+}
+
+impl ops::Deref for PooledBuf {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Vec<u8> {
+        &self.buf
+    }
+}
+
+impl ops::DerefMut for PooledBuf {
+    fn deref_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.buf
+    }
+}
+
+impl AsRef<[u8]> for PooledBuf {
+    fn as_ref(&self) -> &[u8] {
+        &self.buf
+    }
+}
+
+impl From<Vec<u8>> for PooledBuf {
+    fn from(buf: Vec<u8>) -> PooledBuf {
+        PooledBuf { buf, pool: None }
+    }
+}
+
+impl From<&[u8]> for PooledBuf {
+    fn from(buf: &[u8]) -> PooledBuf {
+        PooledBuf::from(buf.to_vec())
+    }
+}
+
+impl Serialize for PooledBuf {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.buf.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PooledBuf {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::deserialize(deserializer).map(PooledBuf::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffer_reused() {
+        let pool = BufferPool::new(&[1024, 64], 4);
+
+        let mut buf = pool.take(10);
+        assert_eq!(buf.capacity(), 64);
+        buf.extend_from_slice(b"hello");
+        let ptr = buf.as_ptr();
+        drop(buf);
+        assert_eq!(pool.idle_buffers(), 1);
+
+        let buf = pool.take(64);
+        assert!(buf.is_empty());
+        assert_eq!(buf.as_ptr(), ptr);
+        assert_eq!(pool.idle_buffers(), 0);
+
+        let buf = pool.take(65);
+        assert_eq!(buf.capacity(), 1024);
+    }
+
+    #[test]
+    fn oversized_buffer_not_pooled() {
+        let pool = BufferPool::new(&[64], 4);
+        drop(pool.take(100));
+        assert_eq!(pool.idle_buffers(), 0);
+    }
+
+    #[test]
+    fn idle_buffers_limited() {
+        let pool = BufferPool::new(&[64], 2);
+        let bufs: Vec<_> = (0..3).map(|_| pool.take(64)).collect();
+        drop(bufs);
+        assert_eq!(pool.idle_buffers(), 2);
+    }
+
+    #[test]
+    fn detached_buffers_not_pooled() {
+        let pool = BufferPool::new(&[64], 2);
+        let buf = pool.take(64);
+        drop(buf.clone());
+        let vec = buf.into_vec();
+        assert_eq!(pool.idle_buffers(), 0);
+        pool.recycle(vec);
+        assert_eq!(pool.idle_buffers(), 1);
+    }
+
+    #[test]
+    fn buffer_outlives_pool() {
+        let pool = BufferPool::new(&[64], 2);
+        let buf = pool.take(64);
+        drop(pool);
+        drop(buf);
+    }
+}
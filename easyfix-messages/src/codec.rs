@@ -0,0 +1,121 @@
+//! Custom serialization of field values, e.g. venue specific format of
+//! single field.
+//!
+//! Codec is assigned to a tag at generation time, with
+//! `GeneratorOptions::custom_codec` of `easyfix-messages-gen`. Generated code
+//! calls it instead of serializer/deserializer method of field type, value
+//! type of the field doesn't change.
+
+use std::iter;
+
+use crate::{
+    deserializer::{DeserializeError, Deserializer},
+    fields::{Int, SeqNum},
+    serializer::Serializer,
+};
+
+/// Serialization of values of type `T`.
+///
+/// Only the value is handled by codec, tag and SOH delimiter are written
+/// (and consumed) by generated code.
+pub trait FieldCodec<T> {
+    /// Write `value` to [`Serializer::output_mut`].
+    fn serialize(serializer: &mut Serializer, value: &T);
+
+    /// Read value of the current field, up to (and including) its SOH
+    /// delimiter, e.g. with one of `Deserializer::deserialize_*` methods.
+    /// Invalid value should be reported with [`Deserializer::reject`].
+    fn deserialize(deserializer: &mut Deserializer) -> Result<T, DeserializeError>;
+}
+
+/// Integer left padded with zeros to at least `WIDTH` characters
+/// (including sign), e.g. `000042` with `ZeroPadded<6>`. Leading zeros are
+/// accepted by deserializer anyway.
+#[derive(Clone, Copy, Debug)]
+pub struct ZeroPadded<const WIDTH: usize>;
+
+fn write_zero_padded(output: &mut Vec<u8>, negative: bool, digits: &str, width: usize) {
+    if negative {
+        output.push(b'-');
+    }
+    let padding = width
+        .saturating_sub(usize::from(negative))
+        .saturating_sub(digits.len());
+    output.extend(iter::repeat_n(b'0', padding));
+    output.extend_from_slice(digits.as_bytes());
+}
+
+impl<const WIDTH: usize> FieldCodec<Int> for ZeroPadded<WIDTH> {
+    fn serialize(serializer: &mut Serializer, value: &Int) {
+        let mut buffer = itoa::Buffer::new();
+        let digits = buffer.format(value.unsigned_abs());
+        write_zero_padded(serializer.output_mut(), *value < 0, digits, WIDTH);
+    }
+
+    fn deserialize(deserializer: &mut Deserializer) -> Result<Int, DeserializeError> {
+        deserializer.deserialize_int()
+    }
+}
+
+impl<const WIDTH: usize> FieldCodec<SeqNum> for ZeroPadded<WIDTH> {
+    fn serialize(serializer: &mut Serializer, value: &SeqNum) {
+        let mut buffer = itoa::Buffer::new();
+        write_zero_padded(serializer.output_mut(), false, buffer.format(*value), WIDTH);
+    }
+
+    fn deserialize(deserializer: &mut Deserializer) -> Result<SeqNum, DeserializeError> {
+        deserializer.deserialize_seq_num()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FieldCodec, ZeroPadded};
+    use crate::{
+        deserializer::{Deserializer, RawMessage},
+        fields::{Int, SeqNum},
+        messages::BEGIN_STRING,
+        serializer::Serializer,
+    };
+
+    fn serialize<T, C: FieldCodec<T>>(value: T) -> String {
+        let mut serializer = Serializer::new();
+        C::serialize(&mut serializer, &value);
+        String::from_utf8(serializer.take()).unwrap()
+    }
+
+    fn deserialize<T, C: FieldCodec<T>>(input: &str) -> T {
+        let body = format!("{input}\x01");
+        let mut deserializer = Deserializer::from_raw_message(RawMessage {
+            begin_string: BEGIN_STRING,
+            body: body.as_bytes(),
+            checksum: 0,
+        });
+        let value = C::deserialize(&mut deserializer).unwrap();
+        assert_eq!(deserializer.deserialize_tag_num().unwrap(), None);
+        value
+    }
+
+    #[test]
+    fn zero_padded_int() {
+        for (value, output) in [
+            (42, "000042"),
+            (-42, "-00042"),
+            (0, "000000"),
+            (1234567, "1234567"),
+            (Int::MIN, "-9223372036854775808"),
+        ] {
+            assert_eq!(serialize::<Int, ZeroPadded<6>>(value), output);
+            assert_eq!(deserialize::<Int, ZeroPadded<6>>(output), value);
+        }
+    }
+
+    #[test]
+    fn zero_padded_seq_num() {
+        for (value, output) in [(7, "0007"), (12345, "12345")] {
+            assert_eq!(serialize::<SeqNum, ZeroPadded<4>>(value), output);
+            assert_eq!(deserialize::<SeqNum, ZeroPadded<4>>(output), value);
+        }
+        assert_eq!(serialize::<SeqNum, ZeroPadded<0>>(0), "0");
+    }
+}
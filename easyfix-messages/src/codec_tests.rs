@@ -0,0 +1,338 @@
+//! Serializer/deserializer symmetry checks of basic types, run by tests
+//! generated for each type used by dictionary fields.
+
+// Types not used by dictionary have no test generated
+#![allow(dead_code)]
+
+use std::fmt::Debug;
+
+use crate::{
+    deserializer::{DeserializeError, Deserializer, RawMessage},
+    fields::basic_types::*,
+    messages::BEGIN_STRING,
+    serializer::Serializer,
+};
+
+fn deserializer(body: &[u8]) -> Deserializer<'_> {
+    let mut deserializer = Deserializer::from_raw_message(RawMessage {
+        begin_string: BEGIN_STRING,
+        body,
+        checksum: 0,
+    });
+    deserializer.set_seq_num(1);
+    deserializer
+}
+
+/// Check that each of `inputs` is deserialized and serialized back
+/// unchanged, and that deserializer consumes value with its delimiter.
+fn check<T: Debug + PartialEq>(
+    inputs: &[&[u8]],
+    deserialize: fn(&mut Deserializer) -> Result<T, DeserializeError>,
+    serialize: fn(&mut Serializer, &T),
+) {
+    for input in inputs {
+        let body = [input, b"\x01".as_slice()].concat();
+        let mut deserializer = deserializer(&body);
+        let value = deserialize(&mut deserializer)
+            .unwrap_or_else(|err| panic!("{}: {err}", String::from_utf8_lossy(input)));
+        assert_eq!(deserializer.deserialize_tag_num().unwrap(), None);
+
+        let mut serializer = Serializer::new();
+        serialize(&mut serializer, &value);
+        let output = serializer.take();
+        assert_eq!(
+            String::from_utf8_lossy(&output),
+            String::from_utf8_lossy(input),
+            "{value:?}"
+        );
+    }
+}
+
+pub(crate) fn tag_num() {
+    for tag in [1, 8, TagNum::MAX] {
+        let mut serializer = Serializer::new();
+        serializer.serialize_tag_num(&tag);
+        let mut output = serializer.take();
+        output.push(b'=');
+        assert_eq!(
+            deserializer(&output).deserialize_tag_num().unwrap(),
+            Some(tag)
+        );
+    }
+    assert!(deserializer(b"65536=").deserialize_tag_num().is_err());
+}
+
+pub(crate) fn int() {
+    check(
+        &[b"0", b"-1", b"9223372036854775807", b"-9223372036854775808"],
+        |deserializer| deserializer.deserialize_int(),
+        Serializer::serialize_int,
+    );
+}
+
+pub(crate) fn seq_num() {
+    check(
+        &[b"1", b"4294967295"],
+        |deserializer| deserializer.deserialize_seq_num(),
+        Serializer::serialize_seq_num,
+    );
+}
+
+pub(crate) fn num_in_group() {
+    check(
+        &[b"1", b"255"],
+        |deserializer| deserializer.deserialize_num_in_group(),
+        Serializer::serialize_num_in_group,
+    );
+}
+
+pub(crate) fn length() {
+    check(
+        &[b"1", b"65535"],
+        |deserializer| deserializer.deserialize_length(),
+        Serializer::serialize_length,
+    );
+}
+
+const DECIMALS: &[&[u8]] = &[
+    b"0",
+    b"-1.5",
+    b"1.50",
+    b"79228162514264337593543950335",
+    b"-79228162514264337593543950335",
+    b"7.9228162514264337593543950335",
+    b"0.0000000000000000000000000001",
+];
+
+pub(crate) fn float() {
+    check(
+        DECIMALS,
+        |deserializer| deserializer.deserialize_float(),
+        Serializer::serialize_float,
+    );
+}
+
+pub(crate) fn qty() {
+    check(
+        DECIMALS,
+        |deserializer| deserializer.deserialize_qty(),
+        Serializer::serialize_qty,
+    );
+}
+
+pub(crate) fn price() {
+    check(
+        DECIMALS,
+        |deserializer| deserializer.deserialize_price(),
+        Serializer::serialize_price,
+    );
+}
+
+pub(crate) fn price_offset() {
+    check(
+        DECIMALS,
+        |deserializer| deserializer.deserialize_price_offset(),
+        Serializer::serialize_price_offset,
+    );
+}
+
+pub(crate) fn amt() {
+    check(
+        DECIMALS,
+        |deserializer| deserializer.deserialize_amt(),
+        Serializer::serialize_amt,
+    );
+}
+
+pub(crate) fn percentage() {
+    check(
+        DECIMALS,
+        |deserializer| deserializer.deserialize_percentage(),
+        Serializer::serialize_percentage,
+    );
+}
+
+pub(crate) fn boolean() {
+    check(
+        &[b"Y", b"N"],
+        |deserializer| deserializer.deserialize_boolean(),
+        Serializer::serialize_boolean,
+    );
+}
+
+pub(crate) fn char() {
+    check(
+        &[b"!", b"0", b"~"],
+        |deserializer| deserializer.deserialize_char(),
+        Serializer::serialize_char,
+    );
+}
+
+pub(crate) fn multiple_char_value() {
+    check(
+        &[b"A", b"A B ~"],
+        |deserializer| deserializer.deserialize_multiple_char_value(),
+        Serializer::serialize_multiple_char_value,
+    );
+}
+
+pub(crate) fn string() {
+    let long = [b'x'; 4096];
+    check(
+        &[b"x", b" leading and trailing spaces ", &long],
+        |deserializer| deserializer.deserialize_string(),
+        |serializer, value| serializer.serialize_string(value),
+    );
+}
+
+pub(crate) fn utf8_string() {
+    check(
+        &[
+            "x".as_bytes(),
+            "Zażółć gęślą jaźń".as_bytes(),
+            "東京".as_bytes(),
+        ],
+        |deserializer| deserializer.deserialize_utf8_string(),
+        Serializer::serialize_utf8_string,
+    );
+}
+
+pub(crate) fn multiple_string_value() {
+    check(
+        &[b"AV", b"AV AN A"],
+        |deserializer| deserializer.deserialize_multiple_string_value(),
+        Serializer::serialize_multiple_string_value,
+    );
+}
+
+pub(crate) fn country() {
+    check(
+        &[b"PL", b"US"],
+        |deserializer| deserializer.deserialize_country(),
+        Serializer::serialize_country,
+    );
+}
+
+pub(crate) fn currency() {
+    check(
+        &[b"PLN", b"USD"],
+        |deserializer| deserializer.deserialize_currency(),
+        Serializer::serialize_currency,
+    );
+}
+
+pub(crate) fn exchange() {
+    check(
+        &[b"XNYS", b"XWAR"],
+        |deserializer| deserializer.deserialize_exchange(),
+        Serializer::serialize_exchange,
+    );
+}
+
+pub(crate) fn month_year() {
+    check(
+        &[b"202401", b"20240131", b"202401w5"],
+        |deserializer| deserializer.deserialize_month_year(),
+        Serializer::serialize_month_year,
+    );
+}
+
+pub(crate) fn language() {
+    check(
+        &[b"en", b"pl"],
+        |deserializer| deserializer.deserialize_language(),
+        Serializer::serialize_language,
+    );
+}
+
+pub(crate) fn utc_timestamp() {
+    check(
+        &[
+            b"19700101-00:00:00",
+            b"20240229-12:34:56.789",
+            b"20240229-12:34:56.789123",
+            b"20240229-12:34:56.789123456",
+            // Leap second
+            b"20161231-23:59:60.999999999",
+            b"99991231-23:59:59.999999999",
+        ],
+        |deserializer| deserializer.deserialize_utc_timestamp(),
+        Serializer::serialize_utc_timestamp,
+    );
+}
+
+pub(crate) fn utc_time_only() {
+    check(
+        &[
+            b"00:00:00",
+            b"12:34:56.789",
+            b"23:59:60.999",
+            b"23:59:59.999999999",
+        ],
+        |deserializer| deserializer.deserialize_utc_time_only(),
+        Serializer::serialize_utc_time_only,
+    );
+}
+
+pub(crate) fn utc_date_only() {
+    check(
+        &[b"19700101", b"20240229", b"99991231"],
+        |deserializer| deserializer.deserialize_utc_date_only(),
+        Serializer::serialize_utc_date_only,
+    );
+}
+
+pub(crate) fn local_mkt_date() {
+    check(
+        &[b"19700101", b"20240229", b"99991231"],
+        |deserializer| deserializer.deserialize_local_mkt_date(),
+        Serializer::serialize_local_mkt_date,
+    );
+}
+
+pub(crate) fn tz_timestamp() {
+    check(
+        &[
+            b"20060901-07:39Z",
+            b"20060901-02:39-05",
+            b"20060901-13:09+05:30",
+            b"20060901-07:39:15.123Z",
+        ],
+        |deserializer| deserializer.deserialize_tz_timestamp(),
+        Serializer::serialize_tz_timestamp,
+    );
+}
+
+pub(crate) fn tz_time_only() {
+    check(
+        &[b"07:39Z", b"02:39-05", b"13:09+05:30", b"07:39:15Z"],
+        |deserializer| deserializer.deserialize_tz_timeonly(),
+        Serializer::serialize_tz_timeonly,
+    );
+}
+
+pub(crate) fn data() {
+    for input in [b"".as_slice(), b"\x01binary\x01data\x01"] {
+        let body = [input, b"\x01".as_slice()].concat();
+        let mut deserializer = deserializer(&body);
+        let data = deserializer.deserialize_data(input.len()).unwrap();
+        assert_eq!(deserializer.deserialize_tag_num().unwrap(), None);
+
+        let mut serializer = Serializer::new();
+        serializer.serialize_data(&data);
+        assert_eq!(serializer.take(), input);
+    }
+}
+
+pub(crate) fn xml_data() {
+    for input in [b"<a/>".as_slice(), b"<a>\x01</a>"] {
+        let body = [input, b"\x01".as_slice()].concat();
+        let mut deserializer = deserializer(&body);
+        let xml_data = deserializer.deserialize_xml(input.len()).unwrap();
+        assert_eq!(deserializer.deserialize_tag_num().unwrap(), None);
+
+        let mut serializer = Serializer::new();
+        serializer.serialize_xml(&xml_data);
+        assert_eq!(serializer.take(), input);
+    }
+}
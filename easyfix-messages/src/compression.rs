@@ -0,0 +1,263 @@
+//! Transparent gzip compression of Data fields (e.g. RawData<96>), for
+//! counterparties sending compressed payloads.
+//!
+//! Compressed fields are configured by tag of Data field, optionally only
+//! when flag field is set to given value. Raw message is rewritten before
+//! it's deserialized, so application code receives uncompressed bytes,
+//! and after it's serialized, with Length field (e.g. RawDataLength<95>),
+//! BodyLength<9> and CheckSum<10> updated.
+//!
+//! ```ignore
+//! let compression = Compression::new().field(96, Some((20001, fix_str!("Y"))));
+//! let msg = compression.decode(&raw_msg)?;
+//! let raw_msg = compression.encode(&msg)?;
+//! ```
+
+use std::io::{self, Read, Write};
+
+use flate2::{read::GzDecoder, write::GzEncoder};
+
+use crate::{
+    deserializer::{raw_message, DeserializeError, RawMessageError},
+    fields::{FixStr, FixString, Length, TagNum},
+    messages::{FieldTag, FixtMessage},
+    serializer::Serializer,
+    tag_value::{TagValueError, TagValueIter},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum CompressionError {
+    #[error("invalid field: {0}")]
+    InvalidField(#[from] TagValueError),
+    #[error("invalid message: {0}")]
+    InvalidMessage(#[from] RawMessageError),
+    #[error("invalid message: {0}")]
+    Deserialize(#[from] DeserializeError),
+    /// Value of tag couldn't be compressed or decompressed.
+    #[error("gzip error (tag={0}): {1}")]
+    Gzip(TagNum, io::Error),
+    /// Value of tag doesn't fit in Length field after transformation.
+    #[error("value too large (tag={0}): {1} bytes")]
+    TooLarge(TagNum, usize),
+}
+
+#[derive(Clone, Debug)]
+struct CompressedField {
+    data_tag: TagNum,
+    flag: Option<(TagNum, FixString)>,
+}
+
+/// Data fields compressed with gzip.
+#[derive(Clone, Debug, Default)]
+pub struct Compression {
+    fields: Vec<CompressedField>,
+    level: flate2::Compression,
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    Compress,
+    Decompress,
+}
+
+impl Compression {
+    pub fn new() -> Compression {
+        Compression::default()
+    }
+
+    /// Compress Data field `data_tag`, when `flag` is set only in messages
+    /// with flag field set to given value.
+    pub fn field(mut self, data_tag: TagNum, flag: Option<(TagNum, &FixStr)>) -> Compression {
+        self.fields.push(CompressedField {
+            data_tag,
+            flag: flag.map(|(tag, value)| (tag, value.to_owned())),
+        });
+        self
+    }
+
+    /// Compression level used by [`Compression::compress`], default when
+    /// not set.
+    pub fn level(mut self, level: u32) -> Compression {
+        self.level = flate2::Compression::new(level);
+        self
+    }
+
+    /// Decompress configured fields of serialized message.
+    pub fn decompress(&self, msg: &[u8]) -> Result<Vec<u8>, CompressionError> {
+        self.transform(msg, Direction::Decompress)
+    }
+
+    /// Compress configured fields of serialized message.
+    pub fn compress(&self, msg: &[u8]) -> Result<Vec<u8>, CompressionError> {
+        self.transform(msg, Direction::Compress)
+    }
+
+    /// Decompress configured fields and deserialize message.
+    pub fn decode(&self, msg: &[u8]) -> Result<Box<FixtMessage>, CompressionError> {
+        let msg = self.decompress(msg)?;
+        let (_, raw_msg) = raw_message(&msg)?;
+        Ok(FixtMessage::from_raw_message(raw_msg)?)
+    }
+
+    /// Serialize message and compress configured fields.
+    pub fn encode(&self, msg: &FixtMessage) -> Result<Vec<u8>, CompressionError> {
+        self.compress(&msg.serialize())
+    }
+
+    fn transform(&self, msg: &[u8], direction: Direction) -> Result<Vec<u8>, CompressionError> {
+        let (_, raw_msg) = raw_message(msg)?;
+        let fields = TagValueIter::new(raw_msg.body).collect::<Result<Vec<_>, _>>()?;
+        let data_tags: Vec<TagNum> = self
+            .fields
+            .iter()
+            .filter(|field| match &field.flag {
+                Some((flag_tag, flag_value)) => fields
+                    .iter()
+                    .any(|(tag, value)| tag == flag_tag && *value == flag_value.as_bytes()),
+                None => true,
+            })
+            .map(|field| field.data_tag)
+            .collect();
+
+        let mut body = Vec::with_capacity(raw_msg.body.len());
+        let mut fields = fields.into_iter().peekable();
+        while let Some((tag, value)) = fields.next() {
+            let data_tag = FieldTag::from_tag_num(tag)
+                .and_then(|tag| tag.data_field())
+                .map(|data_field| data_field as TagNum)
+                .filter(|data_tag| data_tags.contains(data_tag));
+            match (data_tag, fields.peek()) {
+                (Some(data_tag), Some((next_tag, data))) if *next_tag == data_tag => {
+                    let data = self
+                        .transform_value(data, direction)
+                        .map_err(|err| CompressionError::Gzip(data_tag, err))?;
+                    if Length::try_from(data.len()).is_err() {
+                        return Err(CompressionError::TooLarge(data_tag, data.len()));
+                    }
+                    write!(body, "{tag}={}\x01{data_tag}=", data.len()).unwrap();
+                    body.extend_from_slice(&data);
+                    body.push(b'\x01');
+                    fields.next();
+                }
+                _ => {
+                    write!(body, "{tag}=").unwrap();
+                    body.extend_from_slice(value);
+                    body.push(b'\x01');
+                }
+            }
+        }
+
+        // BodyLength<9> and CheckSum<10> formatted as by generated code
+        let mut serializer = Serializer::new();
+        serializer.output_mut().extend_from_slice(b"8=");
+        serializer
+            .output_mut()
+            .extend_from_slice(raw_msg.begin_string.as_bytes());
+        serializer.output_mut().push(b'\x01');
+        serializer.serialize_body_len();
+        serializer.output_mut().extend_from_slice(&body);
+        serializer.serialize_checksum();
+        Ok(serializer.take())
+    }
+
+    fn transform_value(&self, value: &[u8], direction: Direction) -> io::Result<Vec<u8>> {
+        match direction {
+            Direction::Compress => {
+                let mut encoder = GzEncoder::new(Vec::new(), self.level);
+                encoder.write_all(value)?;
+                encoder.finish()
+            }
+            Direction::Decompress => {
+                let mut output = Vec::new();
+                GzDecoder::new(value).read_to_end(&mut output)?;
+                Ok(output)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fields::{MsgType, UtcTimestamp},
+        groups::LinesOfTextGrp,
+        messages::{Header, Message, News, Trailer, BEGIN_STRING},
+    };
+
+    fn news(raw_data: &[u8]) -> FixtMessage {
+        FixtMessage {
+            header: Box::new(Header {
+                begin_string: BEGIN_STRING.to_owned(),
+                msg_type: MsgType::News,
+                sender_comp_id: FixString::from_ascii_lossy(b"sender".to_vec()),
+                target_comp_id: FixString::from_ascii_lossy(b"target".to_vec()),
+                msg_seq_num: 1,
+                sending_time: UtcTimestamp::now(),
+                ..Default::default()
+            }),
+            body: Box::new(Message::News(News {
+                headline: FixString::from_ascii_lossy(b"compressed".to_vec()),
+                lines_of_text_grp: vec![LinesOfTextGrp {
+                    text: FixString::from_ascii_lossy(b"see raw data".to_vec()),
+                    ..Default::default()
+                }],
+                raw_data: Some(raw_data.to_vec().into()),
+                ..Default::default()
+            })),
+            trailer: Box::new(Trailer::default()),
+        }
+    }
+
+    #[test]
+    fn data_field_compressed_and_decompressed() {
+        let compression = Compression::new().field(FieldTag::RawData as TagNum, None);
+        // Binary payload, with SOH inside
+        let payload: Vec<u8> = b"payload\x01".repeat(100);
+        let msg = news(&payload);
+
+        let compressed = compression.encode(&msg).unwrap();
+        assert!(compressed.len() < msg.serialize().len());
+        // Valid message on its own, with compressed payload
+        let (_, raw_msg) = raw_message(&compressed).unwrap();
+        let Message::News(news_msg) = *FixtMessage::from_raw_message(raw_msg).unwrap().body else {
+            panic!("unexpected message");
+        };
+        let raw_data = news_msg.raw_data.as_ref().map(|data| &data[..]);
+        assert_ne!(raw_data, Some(payload.as_slice()));
+
+        let decoded = compression.decode(&compressed).unwrap();
+        assert_eq!(decoded.serialize(), msg.serialize());
+    }
+
+    #[test]
+    fn data_field_compressed_only_when_flagged() {
+        let compression = Compression::new().field(
+            FieldTag::RawData as TagNum,
+            Some((
+                FieldTag::Urgency as TagNum,
+                FixStr::from_ascii(b"2").unwrap(),
+            )),
+        );
+        let msg = news(b"payload");
+        assert_eq!(
+            compression.compress(&msg.serialize()).unwrap(),
+            msg.serialize()
+        );
+
+        let mut msg = news(b"payload");
+        if let Message::News(news) = &mut *msg.body {
+            news.urgency = Some(crate::fields::Urgency::Background);
+        }
+        let compressed = compression.compress(&msg.serialize()).unwrap();
+        assert_ne!(compressed, msg.serialize());
+        assert_eq!(
+            compression.decompress(&compressed).unwrap(),
+            msg.serialize()
+        );
+        assert!(matches!(
+            compression.decompress(&msg.serialize()),
+            Err(CompressionError::Gzip(96, _))
+        ));
+    }
+}
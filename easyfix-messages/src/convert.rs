@@ -0,0 +1,172 @@
+//! Conversion of messages between FIX versions, e.g. in gateway bridging
+//! FIX 4.4 and FIX 5.0SP2 counterparties.
+//!
+//! Each FIX version is generated into separate crate (e.g. this crate
+//! built with different `FIX_XML`), so messages of two versions don't
+//! share field types and can't be converted with `From` implementations.
+//! Conversion goes through the wire format instead: message serialized
+//! by other version is re-encoded with BeginString<8> of this version,
+//! fields not defined for the message type here are dropped and reported,
+//! the rest is deserialized as message of this version.
+//!
+//! ```ignore
+//! let (msg, report) = fix50::convert::convert(&fix44_msg.serialize())?;
+//! if !report.dropped.is_empty() {
+//!     warn!("fields dropped by conversion: {:?}", report.dropped);
+//! }
+//! ```
+//!
+//! Values are converted as they are, so enumerated value not defined
+//! in this version fails deserialization, as it would when received
+//! from counterparty.
+
+use crate::{
+    deserializer::{raw_message, DeserializeError, Deserializer, RawMessageError},
+    fields::{FixStr, MsgType, TagNum},
+    messages::{FixtMessage, Message, BEGIN_STRING, HEADER_TAGS},
+    serializer::Serializer,
+    tag_value::TagValueError,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConversionError {
+    #[error("invalid message: {0}")]
+    RawMessage(#[from] RawMessageError),
+    #[error("invalid field: {0}")]
+    TagValue(#[from] TagValueError),
+    #[error("MsgType<35> missing")]
+    MissingMsgType,
+    #[error("MsgType<35> {0} not defined")]
+    UnknownMsgType(String),
+    #[error("deserialization failed: {0}")]
+    Deserialize(#[from] DeserializeError),
+}
+
+/// Fields dropped by conversion.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ConversionReport {
+    /// Tags of fields not defined for converted message type, in order
+    /// of appearance
+    pub dropped: Vec<TagNum>,
+}
+
+/// Re-encode serialized message of other FIX version with BeginString<8>
+/// of this version, keeping only header fields and body fields defined
+/// for its message type.
+pub fn reencode(msg: &[u8]) -> Result<(Vec<u8>, ConversionReport), ConversionError> {
+    let (_, raw_msg) = raw_message(msg)?;
+    let mut fields = raw_msg.tag_values();
+    let body_tags = match fields.next().transpose()? {
+        Some((35, msg_type)) => FixStr::from_ascii(msg_type)
+            .ok()
+            .and_then(|msg_type| MsgType::try_from(msg_type).ok())
+            .and_then(Message::body_tags)
+            .ok_or_else(|| {
+                ConversionError::UnknownMsgType(String::from_utf8_lossy(msg_type).into_owned())
+            })?,
+        _ => return Err(ConversionError::MissingMsgType),
+    };
+
+    let mut serializer = Serializer::with_capacity(msg.len());
+    let output = serializer.output_mut();
+    output.extend_from_slice(b"8=");
+    output.extend_from_slice(BEGIN_STRING.as_bytes());
+    output.push(b'\x01');
+    serializer.serialize_body_len();
+
+    let mut report = ConversionReport::default();
+    for field in raw_msg.tag_values() {
+        let (tag, value) = field?;
+        if tag == 35 || HEADER_TAGS.contains(&tag) || body_tags.contains(&tag) {
+            serializer.serialize_tag_num(&tag);
+            let output = serializer.output_mut();
+            output.push(b'=');
+            output.extend_from_slice(value);
+            output.push(b'\x01');
+        } else {
+            report.dropped.push(tag);
+        }
+    }
+    serializer.serialize_checksum();
+    Ok((serializer.take(), report))
+}
+
+/// Convert serialized message of other FIX version into message of this
+/// version, see [`reencode`].
+pub fn convert(msg: &[u8]) -> Result<(Box<FixtMessage>, ConversionReport), ConversionError> {
+    let (msg, report) = reencode(msg)?;
+    let (_, raw_msg) = raw_message(&msg)?;
+    let msg = FixtMessage::deserialize(Deserializer::from_raw_message(raw_msg))?;
+    Ok((msg, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serialize message fields with given BeginString<8>.
+    fn message(begin_string: &str, fields: &[(TagNum, &str)]) -> Vec<u8> {
+        let mut serializer = Serializer::new();
+        let output = serializer.output_mut();
+        output.extend_from_slice(b"8=");
+        output.extend_from_slice(begin_string.as_bytes());
+        output.push(b'\x01');
+        serializer.serialize_body_len();
+        for (tag, value) in fields {
+            serializer.serialize_tag_num(tag);
+            let output = serializer.output_mut();
+            output.push(b'=');
+            output.extend_from_slice(value.as_bytes());
+            output.push(b'\x01');
+        }
+        serializer.serialize_checksum();
+        serializer.take()
+    }
+
+    const HEADER: &[(TagNum, &str)] = &[
+        (35, "0"),
+        (49, "SENDER"),
+        (56, "TARGET"),
+        (34, "2"),
+        (52, "20240229-12:00:00.000"),
+    ];
+
+    #[test]
+    fn undefined_fields_dropped() {
+        // TestReqID<112> is defined for Heartbeat<0>, ClOrdID<11>
+        // and tag 9999 are not
+        let fields: Vec<_> = HEADER
+            .iter()
+            .copied()
+            .chain([(112, "test"), (11, "order"), (9999, "custom")])
+            .collect();
+        let (msg, report) = convert(&message("FIX.4.4", &fields)).unwrap();
+        assert_eq!(report.dropped, [11, 9999]);
+        assert_eq!(msg.header.begin_string, BEGIN_STRING);
+        assert_eq!(msg.header.msg_seq_num, 2);
+        let Message::Heartbeat(heartbeat) = *msg.body else {
+            panic!("unexpected message {msg:?}");
+        };
+        assert_eq!(
+            heartbeat.test_req_id.as_deref(),
+            Some(FixStr::from_ascii(b"test").unwrap())
+        );
+    }
+
+    #[test]
+    fn reencoded_message_valid() {
+        let (msg, report) = reencode(&message("FIX.4.4", HEADER)).unwrap();
+        assert!(report.dropped.is_empty());
+        assert_eq!(msg, message(BEGIN_STRING.as_utf8(), HEADER));
+    }
+
+    #[test]
+    fn unknown_msg_type_rejected() {
+        let mut fields = HEADER.to_vec();
+        fields[0] = (35, "ZZZ");
+        assert!(matches!(
+            reencode(&message("FIX.4.4", &fields)),
+            Err(ConversionError::UnknownMsgType(msg_type)) if msg_type == "ZZZ"
+        ));
+    }
+}
@@ -1,8 +1,11 @@
-use std::{error::Error, fmt};
+use std::{error::Error, fmt, ops::Range};
 
 use anyhow::Result;
+use bytes::{Bytes, BytesMut};
 
-use crate::fields::basic_types::*;
+#[cfg(feature = "pooled-data")]
+use crate::buffer_pool::BufferPool;
+use crate::{fields::basic_types::*, tag_value::TagValueIter};
 
 #[derive(Debug)]
 pub enum DeserializeError {
@@ -14,6 +17,9 @@ pub enum DeserializeError {
         seq_num: SeqNum,
         tag: Option<TagNum>,
         reason: ParseRejectReason,
+        /// Both occurrences of field rejected with TagAppearsMoreThanOnce
+        /// reason.
+        duplicate: Option<Box<DuplicateTag>>,
     },
 }
 
@@ -72,6 +78,112 @@ pub enum ParseRejectReason {
     CompidProblem,
 }
 
+/// Invalid value of optional field, skipped by deserializer instead of
+/// rejecting the whole message, see [`Deserializer::with_field_errors`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FieldError {
+    pub tag: TagNum,
+    pub reason: ParseRejectReason,
+}
+
+/// Field appearing more than once in message, attached to
+/// TagAppearsMoreThanOnce reject as evidence for the counterparty.
+///
+/// Offsets are positions of values in message body, counted from the first
+/// byte following BodyLength<9> field.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DuplicateTag {
+    pub tag: TagNum,
+    pub first_value: Bytes,
+    pub first_offset: usize,
+    pub second_value: Bytes,
+    pub second_offset: usize,
+}
+
+impl fmt::Display for DuplicateTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tag {} at offset {} ({:?}) and {} ({:?})",
+            self.tag,
+            self.first_offset,
+            String::from_utf8_lossy(&self.first_value),
+            self.second_offset,
+            String::from_utf8_lossy(&self.second_value),
+        )
+    }
+}
+
+/// Handling of field appearing more than once in message body,
+/// see [`Deserializer::with_duplicate_tags`].
+///
+/// Header, trailer and repeating group fields are always rejected,
+/// as well as fields which can't be repeated by definition (Length,
+/// Data, NumInGroup).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum DuplicateTagPolicy {
+    /// Reject message with TagAppearsMoreThanOnce reason.
+    #[default]
+    Reject,
+    /// Keep the first value, ignore the following ones.
+    KeepFirst,
+    /// Keep the last value.
+    KeepLast,
+}
+
+/// Limits of repeating groups, see [`Deserializer::with_group_limits`].
+///
+/// Group with NumInGroup value or nesting depth exceeding the limits is
+/// rejected with IncorrectNumingroupCountForRepeatingGroup reason.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct GroupLimits {
+    /// Maximum NumInGroup value.
+    pub max_entries: NumInGroup,
+    /// Maximum depth of nested groups, group not nested in other group
+    /// has depth 1.
+    pub max_depth: u8,
+}
+
+impl Default for GroupLimits {
+    fn default() -> GroupLimits {
+        GroupLimits {
+            max_entries: NumInGroup::MAX,
+            max_depth: u8::MAX,
+        }
+    }
+}
+
+/// Header and trailer fields required in addition to ones required by
+/// the dictionary, see [`Deserializer::with_required_tags`].
+///
+/// Fields have to be defined in the dictionary header or trailer (as
+/// optional ones), so their values are validated when deserialized,
+/// tags not defined there are always reported as missing.
+/// BeginString<8>, BodyLength<9> and CheckSum<10> are always verified
+/// and don't have to be listed.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct RequiredTags {
+    /// Required header fields.
+    pub header: Vec<TagNum>,
+    /// Required trailer fields.
+    pub trailer: Vec<TagNum>,
+    /// Require fields to appear in order of `header` and `trailer`
+    /// lists, other fields may appear between them.
+    pub ordered: bool,
+}
+
+impl RequiredTags {
+    pub fn is_empty(&self) -> bool {
+        self.header.is_empty() && self.trailer.is_empty()
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 enum DeserializeErrorInternal {
     #[error("Incomplete")]
@@ -144,6 +256,24 @@ fn deserialize_str(bytes: &[u8]) -> Result<(&[u8], &FixStr), DeserializeErrorInt
     Err(DeserializeErrorInternal::Incomplete)
 }
 
+fn deserialize_utf8_str(bytes: &[u8]) -> Result<(&[u8], &str), DeserializeErrorInternal> {
+    // SOH is never a part of multi-byte UTF-8 sequence
+    let Some(end) = bytes.iter().position(|b| *b == b'\x01') else {
+        return Err(DeserializeErrorInternal::Incomplete);
+    };
+    if end == 0 {
+        return Err(DeserializeErrorInternal::Error(
+            ParseRejectReason::TagSpecifiedWithoutAValue,
+        ));
+    }
+    match std::str::from_utf8(&bytes[..end]) {
+        Ok(value) if !value.chars().any(char::is_control) => Ok((&bytes[end + 1..], value)),
+        _ => Err(DeserializeErrorInternal::Error(
+            ParseRejectReason::ValueIsIncorrect,
+        )),
+    }
+}
+
 fn deserialize_length(bytes: &[u8]) -> Result<(&[u8], Length), DeserializeErrorInternal> {
     let mut value: Length = 0;
     for (i, b) in bytes.iter().enumerate() {
@@ -206,6 +336,13 @@ impl From<DeserializeErrorInternal> for RawMessageError {
     }
 }
 
+/// Frame the first message at the beginning of `bytes`.
+///
+/// Message end is determined by BodyLength<9> only, body is never scanned
+/// for SOH, so Data/XmlData fields containing SOH or `10=` are framed
+/// correctly. [`RawMessageError::Incomplete`] is returned until the whole
+/// message, including CheckSum<10>, is available, regardless of where
+/// the data is split.
 pub fn raw_message(bytes: &[u8]) -> Result<(&[u8], RawMessage), RawMessageError> {
     let orig_bytes = bytes;
 
@@ -243,9 +380,140 @@ pub fn raw_message(bytes: &[u8]) -> Result<(&[u8], RawMessage), RawMessageError>
     ))
 }
 
+/// CheckSum<10> or BodyLength<9> value not matching message content, see
+/// [`framing_mismatch`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FramingMismatch {
+    CheckSum {
+        calculated: u8,
+        received: u8,
+    },
+    /// `calculated` is the distance between BodyLength<9> and the first
+    /// CheckSum<10> field found
+    BodyLength {
+        calculated: usize,
+        received: usize,
+    },
+}
+
+/// Find out why message at the beginning of `bytes` was rejected by
+/// [`raw_message`] as garbled or with invalid checksum, e.g. to diagnose
+/// counterparty encoder.
+///
+/// Returns the mismatch and offset of CheckSum<10> field in `bytes`.
+/// `None` is returned when message doesn't start with well formed
+/// BeginString<8> and BodyLength<9> fields, CheckSum<10> field is not
+/// found or both values match.
+pub fn framing_mismatch(bytes: &[u8]) -> Option<(FramingMismatch, usize)> {
+    let rest = deserialize_tag(bytes, b"8=").ok()?;
+    let (rest, _) = deserialize_str(rest).ok()?;
+    let rest = deserialize_tag(rest, b"9=").ok()?;
+    let (rest, body_length) = deserialize_length(rest).ok()?;
+    let body_start = bytes.len() - rest.len();
+
+    // Unlike `raw_message()`, body is scanned for SOH, so Data/XmlData
+    // fields containing `10=` may be taken for CheckSum<10>
+    let received = usize::from(body_length);
+    let calculated = rest.windows(4).position(|w| w == b"\x0110=")? + 1;
+    let checksum_start = body_start + calculated;
+    if calculated != received {
+        return Some((
+            FramingMismatch::BodyLength {
+                calculated,
+                received,
+            },
+            checksum_start,
+        ));
+    }
+
+    let (_, received) = deserialize_checksum(&bytes[checksum_start + 3..]).ok()?;
+    let calculated = bytes[..checksum_start]
+        .iter()
+        .fold(0, |acc: u8, x| acc.wrapping_add(*x));
+    (calculated != received).then_some((
+        FramingMismatch::CheckSum {
+            calculated,
+            received,
+        },
+        checksum_start,
+    ))
+}
+
+/// Raw message split off the read buffer, sharing memory with it instead
+/// of copying, see [`split_raw_message`].
+#[derive(Clone, Debug)]
+pub struct RawMessageBytes {
+    message: Bytes,
+    begin_string: Range<usize>,
+    body: Range<usize>,
+    checksum: u8,
+}
+
+impl RawMessageBytes {
+    /// Whole message, as received.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.message
+    }
+
+    /// Whole message, as received.
+    pub fn into_bytes(self) -> Bytes {
+        self.message
+    }
+
+    /// Message body, between BodyLength<9> and CheckSum<10> fields.
+    pub fn body(&self) -> Bytes {
+        self.message.slice(self.body.clone())
+    }
+
+    /// Borrow message for deserialization with
+    /// [`Deserializer::from_raw_message`].
+    pub fn as_raw_message(&self) -> RawMessage<'_> {
+        RawMessage {
+            // SAFETY: Validated by `raw_message()` in `split_raw_message()`
+            begin_string: unsafe {
+                FixStr::from_ascii_unchecked(&self.message[self.begin_string.clone()])
+            },
+            body: &self.message[self.body.clone()],
+            checksum: self.checksum,
+        }
+    }
+}
+
+/// Split the first complete message off the beginning of `buffer`.
+///
+/// Unlike [`raw_message`], the message is not borrowed from `buffer`,
+/// it shares the underlying memory, so it can outlive the next read
+/// to the buffer without copying. On error `buffer` is left untouched.
+pub fn split_raw_message(buffer: &mut BytesMut) -> Result<RawMessageBytes, RawMessageError> {
+    let (leftover, raw_msg) = raw_message(buffer)?;
+    let offset = |slice: &[u8]| slice.as_ptr() as usize - buffer.as_ptr() as usize;
+    let begin_string_start = offset(raw_msg.begin_string.as_bytes());
+    let begin_string = begin_string_start..begin_string_start + raw_msg.begin_string.len();
+    let body_start = offset(raw_msg.body);
+    let body = body_start..body_start + raw_msg.body.len();
+    let checksum = raw_msg.checksum;
+    let msg_len = buffer.len() - leftover.len();
+    Ok(RawMessageBytes {
+        message: buffer.split_to(msg_len).freeze(),
+        begin_string,
+        body,
+        checksum,
+    })
+}
+
 // TODO:
 // enum GarbledReason
 
+/// Map second 60 (leap second) to second 59 with nanoseconds overflow,
+/// as leap second is represented by `chrono`.
+fn leap_second(sec: u32, nanos: u32) -> Option<(u32, u32)> {
+    match sec {
+        0..=59 => Some((sec, nanos)),
+        60 => Some((59, nanos + 1_000_000_000)),
+        _ => None,
+    }
+}
+
 #[derive(Debug)]
 pub struct Deserializer<'de> {
     raw_message: RawMessage<'de>,
@@ -256,10 +524,22 @@ pub struct Deserializer<'de> {
     // Used to put tag back to deserializer, when switching to deserialization
     // another message section.
     tmp_tag: Option<TagNum>,
+    // Value of the current tag, used to skip it in case of field error.
+    value_start: &'de [u8],
+    #[cfg(feature = "pooled-data")]
+    buffer_pool: Option<&'de BufferPool>,
+    field_errors: Option<Vec<FieldError>>,
+    duplicate_tags: DuplicateTagPolicy,
+    begin_strings: &'de [FixString],
+    other_msg_types: bool,
+    required_tags: Option<&'de RequiredTags>,
+    group_limits: GroupLimits,
+    /// Depth of group being deserialized, 0 outside of groups
+    group_depth: u8,
 }
 
-impl Deserializer<'_> {
-    pub fn from_raw_message(raw_message: RawMessage) -> Deserializer {
+impl<'de> Deserializer<'de> {
+    pub fn from_raw_message(raw_message: RawMessage<'de>) -> Deserializer<'de> {
         let buf = raw_message.body;
         Deserializer {
             raw_message,
@@ -268,6 +548,264 @@ impl Deserializer<'_> {
             seq_num: None,
             current_tag: None,
             tmp_tag: None,
+            value_start: buf,
+            #[cfg(feature = "pooled-data")]
+            buffer_pool: None,
+            field_errors: None,
+            duplicate_tags: DuplicateTagPolicy::Reject,
+            begin_strings: &[],
+            other_msg_types: false,
+            required_tags: None,
+            group_limits: GroupLimits::default(),
+            group_depth: 0,
+        }
+    }
+
+    /// Accept messages with any of given BeginString<8> values, in addition
+    /// to BeginString<8> of generated messages.
+    ///
+    /// Messages of other FIX versions are deserialized using generated
+    /// messages definitions, e.g. FIX.4.4 messages using FIX 5.0 SP2 ones.
+    pub fn with_begin_strings(mut self, begin_strings: &'de [FixString]) -> Deserializer<'de> {
+        self.begin_strings = begin_strings;
+        self
+    }
+
+    /// Check if `begin_string` is one of values accepted in addition to
+    /// BeginString<8> of generated messages.
+    pub fn accepts_begin_string(&self, begin_string: &FixStr) -> bool {
+        self.begin_strings
+            .iter()
+            .any(|accepted| accepted == begin_string)
+    }
+
+    /// Deserialize messages of types not defined in dictionary as
+    /// `Message::Other`, instead of rejecting them with InvalidMsgType
+    /// reason.
+    pub fn with_other_msg_types(mut self) -> Deserializer<'de> {
+        self.other_msg_types = true;
+        self
+    }
+
+    /// Check if messages of types not defined in dictionary are accepted,
+    /// see [`with_other_msg_types`](Deserializer::with_other_msg_types).
+    pub fn accepts_other_msg_types(&self) -> bool {
+        self.other_msg_types
+    }
+
+    /// Require header and trailer fields in addition to ones required
+    /// by the dictionary.
+    pub fn with_required_tags(mut self, required_tags: &'de RequiredTags) -> Deserializer<'de> {
+        self.required_tags = Some(required_tags);
+        self
+    }
+
+    /// Verify presence (and order, if requested) of fields set with
+    /// [`with_required_tags`](Deserializer::with_required_tags).
+    ///
+    /// Called once message is deserialized, `header_tags` and
+    /// `trailer_tags` are all tags of dictionary header and trailer.
+    /// Missing field is rejected with RequiredTagMissing reason, field
+    /// out of order or outside of its section with
+    /// TagSpecifiedOutOfRequiredOrder reason.
+    pub fn check_required_tags(
+        &mut self,
+        header_tags: &[TagNum],
+        trailer_tags: &[TagNum],
+    ) -> Result<(), DeserializeError> {
+        let Some(required_tags) = self.required_tags.filter(|tags| !tags.is_empty()) else {
+            return Ok(());
+        };
+
+        let mut header = Vec::new();
+        let mut body = Vec::new();
+        let mut trailer = Vec::new();
+        for (tag, _) in self.raw_message.tag_values().filter_map(Result::ok) {
+            if !trailer.is_empty() || trailer_tags.contains(&tag) {
+                trailer.push(tag);
+            } else if body.is_empty() && header_tags.contains(&tag) {
+                header.push(tag);
+            } else {
+                body.push(tag);
+            }
+        }
+
+        for (required, present) in [
+            (&required_tags.header, &header),
+            (&required_tags.trailer, &trailer),
+        ] {
+            let mut last_idx = 0;
+            // Checked when raw message is parsed
+            for tag in required.iter().filter(|tag| !matches!(tag, 8..=10)) {
+                let Some(idx) = present.iter().position(|present| present == tag) else {
+                    let reason =
+                        if header.contains(tag) || body.contains(tag) || trailer.contains(tag) {
+                            ParseRejectReason::TagSpecifiedOutOfRequiredOrder
+                        } else {
+                            ParseRejectReason::RequiredTagMissing
+                        };
+                    return Err(self.reject(Some(*tag), reason));
+                };
+                if required_tags.ordered && idx < last_idx {
+                    return Err(self.reject(
+                        Some(*tag),
+                        ParseRejectReason::TagSpecifiedOutOfRequiredOrder,
+                    ));
+                }
+                last_idx = idx;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check if message uses FIXT session protocol (BeginString<8> FIXT.x.y).
+    ///
+    /// Otherwise DefaultApplVerID<1137> is not required in Logon<A>.
+    pub fn is_fixt(&self) -> bool {
+        self.raw_message
+            .begin_string
+            .as_bytes()
+            .starts_with(b"FIXT")
+    }
+
+    /// Take buffers for Data/XmlData fields from `buffer_pool`.
+    #[cfg(feature = "pooled-data")]
+    pub fn with_buffer_pool(mut self, buffer_pool: &'de BufferPool) -> Deserializer<'de> {
+        self.buffer_pool = Some(buffer_pool);
+        self
+    }
+
+    /// Skip invalid values of optional fields in application messages body
+    /// instead of rejecting the whole message.
+    ///
+    /// Skipped fields are collected and can be retrieved with
+    /// [`take_field_errors`](Deserializer::take_field_errors).
+    pub fn with_field_errors(mut self) -> Deserializer<'de> {
+        self.field_errors = Some(Vec::new());
+        self
+    }
+
+    /// Handle failed deserialization of optional field value.
+    ///
+    /// If field errors are tolerated and `error` is caused by invalid value,
+    /// the field is skipped and error is recorded, otherwise `error` is
+    /// returned back.
+    pub fn field_error(&mut self, error: DeserializeError) -> Result<(), DeserializeError> {
+        let Some(field_errors) = &mut self.field_errors else {
+            return Err(error);
+        };
+        let DeserializeError::Reject {
+            tag: Some(tag),
+            reason:
+                reason @ (ParseRejectReason::ValueIsIncorrect
+                | ParseRejectReason::TagSpecifiedWithoutAValue
+                | ParseRejectReason::IncorrectDataFormatForValue),
+            ..
+        } = error
+        else {
+            return Err(error);
+        };
+        // Without known MsgSeqNum<34> input position is not reliable,
+        // see `try_find_msg_seq_num()`
+        if self.seq_num.is_none() {
+            return Err(error);
+        }
+        let Some(value_end) = self.value_start.iter().position(|b| *b == b'\x01') else {
+            return Err(error);
+        };
+        self.buf = &self.value_start[value_end + 1..];
+        field_errors.push(FieldError { tag, reason });
+        Ok(())
+    }
+
+    /// Handle fields appearing more than once in message body according
+    /// to `policy`, instead of rejecting the message.
+    pub fn with_duplicate_tags(mut self, policy: DuplicateTagPolicy) -> Deserializer<'de> {
+        self.duplicate_tags = policy;
+        self
+    }
+
+    /// Limit NumInGroup values and nesting depth of repeating groups.
+    pub fn with_group_limits(mut self, group_limits: GroupLimits) -> Deserializer<'de> {
+        self.group_limits = group_limits;
+        self
+    }
+
+    /// Start deserialization of group with `len` entries, called once
+    /// its NumInGroup value is deserialized.
+    ///
+    /// Besides [`GroupLimits`], `len` is verified against remaining
+    /// data, so entries are not allocated for group which can't fit
+    /// in the message.
+    pub fn enter_group(
+        &mut self,
+        num_in_group_tag: TagNum,
+        len: NumInGroup,
+    ) -> Result<(), DeserializeError> {
+        // The shortest entry is a single field, e.g. `1=A<SOH>`
+        const MIN_ENTRY_LEN: usize = 4;
+        if len > self.group_limits.max_entries
+            || self.group_depth >= self.group_limits.max_depth
+            || usize::from(len) > self.buf.len() / MIN_ENTRY_LEN
+        {
+            return Err(self.reject(
+                Some(num_in_group_tag),
+                ParseRejectReason::IncorrectNumingroupCountForRepeatingGroup,
+            ));
+        }
+        self.group_depth += 1;
+        Ok(())
+    }
+
+    /// Finish deserialization of group started with
+    /// [`enter_group`](Deserializer::enter_group).
+    pub fn leave_group(&mut self) {
+        self.group_depth -= 1;
+    }
+
+    /// Handle repeated occurrence of message body field.
+    ///
+    /// Returns `true` when value was skipped and the previous one must be
+    /// kept, `false` when value should be deserialized and replace
+    /// the previous one, or TagAppearsMoreThanOnce reject.
+    pub fn duplicate_tag(&mut self, tag: TagNum) -> Result<bool, DeserializeError> {
+        match self.duplicate_tags {
+            DuplicateTagPolicy::Reject => {
+                Err(self.reject(Some(tag), ParseRejectReason::TagAppearsMoreThanOnce))
+            }
+            DuplicateTagPolicy::KeepFirst => {
+                let Some(value_end) = self.buf.iter().position(|b| *b == b'\x01') else {
+                    return Err(self.reject(Some(tag), ParseRejectReason::TagAppearsMoreThanOnce));
+                };
+                self.buf = &self.buf[value_end + 1..];
+                Ok(true)
+            }
+            DuplicateTagPolicy::KeepLast => Ok(false),
+        }
+    }
+
+    /// Take fields skipped so far because of invalid values.
+    pub fn take_field_errors(&mut self) -> Vec<FieldError> {
+        self.field_errors
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    #[cfg(not(feature = "pooled-data"))]
+    fn data_buf(&self, data: &[u8]) -> Data {
+        data.into()
+    }
+
+    #[cfg(feature = "pooled-data")]
+    fn data_buf(&self, data: &[u8]) -> Data {
+        match self.buffer_pool {
+            Some(buffer_pool) => {
+                let mut buf = buffer_pool.take(data.len());
+                buf.extend_from_slice(data);
+                buf
+            }
+            None => data.into(),
         }
     }
 
@@ -300,6 +838,7 @@ impl Deserializer<'_> {
             .position(|window| window == seq_num_tag)
             .ok_or(DeserializeError::Logout)?;
         self.buf = &self.buf[start_index + seq_num_tag.len()..];
+        self.current_tag = Some(34);
 
         self.deserialize_seq_num()
     }
@@ -314,6 +853,13 @@ impl Deserializer<'_> {
             }
         };
 
+        let duplicate = match (tag, reason) {
+            (Some(tag), ParseRejectReason::TagAppearsMoreThanOnce) => {
+                self.find_duplicate(tag).map(Box::new)
+            }
+            _ => None,
+        };
+
         DeserializeError::Reject {
             msg_type: self.msg_type.clone().map(|msg_type| {
                 FixString::from_ascii_lossy(self.raw_message.body[msg_type].to_vec())
@@ -321,9 +867,41 @@ impl Deserializer<'_> {
             seq_num,
             tag,
             reason,
+            duplicate,
         }
     }
 
+    /// Find value of `tag` being deserialized and its previous occurrence.
+    ///
+    /// Returns `None` when the previous occurrence is not in message body,
+    /// e.g. for repeated BeginString<8>.
+    fn find_duplicate(&self, tag: TagNum) -> Option<DuplicateTag> {
+        let body = self.raw_message.body;
+        let second_offset = body.len() - self.value_start.len();
+        let mut first = None;
+        for (field_tag, value) in self.raw_message.tag_values().map_while(Result::ok) {
+            let offset = value.as_ptr() as usize - body.as_ptr() as usize;
+            if field_tag != tag {
+                continue;
+            }
+            if offset < second_offset {
+                first = Some((offset, value));
+            } else if offset == second_offset {
+                let (first_offset, first_value) = first?;
+                return Some(DuplicateTag {
+                    tag,
+                    first_value: Bytes::copy_from_slice(first_value),
+                    first_offset,
+                    second_value: Bytes::copy_from_slice(value),
+                    second_offset,
+                });
+            } else {
+                break;
+            }
+        }
+        None
+    }
+
     pub fn repeating_group_fields_out_of_order(
         &mut self,
         expected_tags: &[u16],
@@ -352,6 +930,32 @@ impl Deserializer<'_> {
         self.reject(None, ParseRejectReason::RepeatingGroupFieldsOutOfOrder)
     }
 
+    /// Move past leading fields with any of `header_tags`, without
+    /// deserializing their values, see `FixtMessage::deserialize_body()`.
+    ///
+    /// Stops at the first other field, or at the first malformed one,
+    /// which is then reported when deserialized.
+    pub fn skip_header(&mut self, header_tags: &[TagNum]) {
+        let mut fields = TagValueIter::new(self.buf);
+        loop {
+            let remaining = fields.remaining();
+            match fields.next() {
+                Some(Ok((tag, value))) if header_tags.contains(&tag) => {
+                    if tag == 35 {
+                        let start =
+                            unsafe { value.as_ptr().offset_from(self.raw_message.body.as_ptr()) }
+                                as usize;
+                        self.msg_type = Some(start..start + value.len());
+                    }
+                }
+                _ => {
+                    self.buf = remaining;
+                    break;
+                }
+            }
+        }
+    }
+
     pub fn put_tag(&mut self, tag: TagNum) {
         self.tmp_tag = Some(tag);
     }
@@ -413,6 +1017,7 @@ impl Deserializer<'_> {
                     } else {
                         self.current_tag = Some(value);
                         self.buf = &self.buf[i + 1..];
+                        self.value_start = self.buf;
                         return Ok(Some(value));
                     }
                 }
@@ -462,21 +1067,29 @@ impl Deserializer<'_> {
             _ => false,
         };
 
+        // Negative value is accumulated as it is, so Int::MIN fits
         let mut value: Int = 0;
         for i in 0..self.buf.len() {
             // SAFETY: i is between 0 and self.buf.len()
             match unsafe { self.buf.get_unchecked(i) } {
                 n @ b'0'..=b'9' => {
+                    let digit = (n - b'0') as Int;
                     value = value
                         .checked_mul(10)
-                        .and_then(|v| v.checked_add((n - b'0') as Int))
+                        .and_then(|v| {
+                            if negative {
+                                v.checked_sub(digit)
+                            } else {
+                                v.checked_add(digit)
+                            }
+                        })
                         .ok_or_else(|| {
                             self.reject(self.current_tag, ParseRejectReason::ValueIsIncorrect)
                         })?;
                 }
                 b'\x01' => {
                     self.buf = &self.buf[i + 1..];
-                    return Ok(if negative { -value } else { value });
+                    return Ok(value);
                 }
                 _ => {
                     return Err(self.reject(
@@ -504,7 +1117,9 @@ impl Deserializer<'_> {
                     self.current_tag
                 )))
             }
-            [b'\x01', ..] => return Err(DeserializeError::Logout),
+            [b'\x01', ..] => {
+                return Err(self.seq_num_error(ParseRejectReason::TagSpecifiedWithoutAValue))
+            }
             _ => {}
         }
 
@@ -516,14 +1131,14 @@ impl Deserializer<'_> {
                     value = value
                         .checked_mul(10)
                         .and_then(|v| v.checked_add((n - b'0') as SeqNum))
-                        .ok_or(DeserializeError::Logout)?;
+                        .ok_or_else(|| self.seq_num_error(ParseRejectReason::ValueIsIncorrect))?;
                 }
                 b'\x01' => {
                     self.buf = &self.buf[i + 1..];
                     // XXX: Accept `0` as EndSeqNum<16> uses `0` as infinite
                     return Ok(value);
                 }
-                _ => return Err(DeserializeError::Logout),
+                _ => return Err(self.seq_num_error(ParseRejectReason::IncorrectDataFormatForValue)),
             }
         }
 
@@ -533,6 +1148,16 @@ impl Deserializer<'_> {
         )))
     }
 
+    /// Invalid MsgSeqNum<34> can't be rejected, other SeqNum fields
+    /// (e.g. BeginSeqNo<7>) are rejected with given `reason`.
+    fn seq_num_error(&mut self, reason: ParseRejectReason) -> DeserializeError {
+        if self.current_tag == Some(34) {
+            DeserializeError::Logout
+        } else {
+            self.reject(self.current_tag, reason)
+        }
+    }
+
     /// Deserialize sequence of character digits without commas or decimals.
     /// Value must be positive.
     pub fn deserialize_num_in_group(&mut self) -> Result<NumInGroup, DeserializeError> {
@@ -679,7 +1304,8 @@ impl Deserializer<'_> {
             _ => (false, self.buf),
         };
 
-        let mut num: i64 = 0;
+        // Decimal mantissa is 96 bits wide, range is checked at the end
+        let mut num: i128 = 0;
         let mut scale = None;
         for i in 0..buf.len() {
             // SAFETY: i is between 0 and buf.len()
@@ -687,7 +1313,7 @@ impl Deserializer<'_> {
                 n @ b'0'..=b'9' => {
                     num = num
                         .checked_mul(10)
-                        .and_then(|v| v.checked_add((n - b'0') as i64))
+                        .and_then(|v| v.checked_add((n - b'0') as i128))
                         .ok_or_else(|| {
                             self.reject(self.current_tag, ParseRejectReason::ValueIsIncorrect)
                         })?;
@@ -705,10 +1331,11 @@ impl Deserializer<'_> {
                     scale = Some(0);
                 }
                 b'\x01' => {
-                    self.buf = &self.buf[i + 1..];
-                    let scale = scale.unwrap_or(0);
-                    // TODO: Limit scale (28 or more panics!)
-                    return Ok(Decimal::new(if negative { -num } else { num }, scale));
+                    self.buf = &buf[i + 1..];
+                    let num = if negative { -num } else { num };
+                    return Decimal::try_from_i128_with_scale(num, scale.unwrap_or(0)).map_err(
+                        |_| self.reject(self.current_tag, ParseRejectReason::ValueIsIncorrect),
+                    );
                 }
                 _ => {
                     return Err(self.reject(
@@ -899,6 +1526,24 @@ impl Deserializer<'_> {
         self.deserialize_str().map(FixString::from)
     }
 
+    /// Deserialize UTF-8 free-format string, any character except control
+    /// characters is allowed.
+    pub fn deserialize_utf8_string(&mut self) -> Result<Utf8String, DeserializeError> {
+        match deserialize_utf8_str(self.buf) {
+            Ok((leftover, value)) => {
+                self.buf = leftover;
+                // Validated above
+                Ok(Utf8String::try_from(value).expect("invalid UTF-8 string"))
+            }
+            Err(DeserializeErrorInternal::Incomplete) => Err(DeserializeError::GarbledMessage(
+                format!("no more data to parse tag {:?}", self.current_tag),
+            )),
+            Err(DeserializeErrorInternal::Error(reason)) => {
+                Err(self.reject(self.current_tag, reason))
+            }
+        }
+    }
+
     /// Deserialize string containing one or more space-delimited multiple
     /// character values, e.g. “AV AN A”.
     pub fn deserialize_multiple_string_value(
@@ -1072,6 +1717,11 @@ impl Deserializer<'_> {
                 self.current_tag,
                 ParseRejectReason::TagSpecifiedWithoutAValue,
             )),
+            [a, b, c, d, e, f, b'\x01', buf @ ..] => {
+                self.buf = buf;
+                // TODO
+                Ok([*a, *b, *c, *d, *e, *f].into())
+            }
             [a, b, c, d, e, f, g, h, b'\x01', buf @ ..] => {
                 self.buf = buf;
                 // TODO
@@ -1232,9 +1882,8 @@ impl Deserializer<'_> {
                 // Minute
                 mm1 @ b'0'..=b'5', mm0 @ b'0'..=b'9',
                 b':',
-                // TODO: leap second!
                 // Second
-                s1 @ b'0'..=b'5', s0 @ b'0'..=b'9',
+                s1 @ b'0'..=b'6', s0 @ b'0'..=b'9',
                 ..
             ] => {
                 self.buf = &self.buf[17..];
@@ -1250,10 +1899,10 @@ impl Deserializer<'_> {
                 let min = (mm1 - b'0') as u32 * 10 + (mm0 - b'0') as u32;
                 let sec = (s1 - b'0') as u32 * 10 + (s0 - b'0') as u32;
                 let (fraction_of_second, precision) = self.deserialize_fraction_of_second()?;
-                let naive_date_time = naive_date
-                    .and_hms_nano_opt(hour, min, sec, fraction_of_second)
+                let naive_date_time = leap_second(sec, fraction_of_second)
+                    .and_then(|(sec, nanos)| naive_date.and_hms_nano_opt(hour, min, sec, nanos))
                     .ok_or_else(|| self.reject(self.current_tag, ParseRejectReason::ValueIsIncorrect))?;
-                let timestamp = Utc.from_utc_datetime(&naive_date_time);
+                let timestamp = naive_date_time.and_utc();
 
                 match precision {
                     0 => Ok(UtcTimestamp::with_secs(timestamp)),
@@ -1286,7 +1935,6 @@ impl Deserializer<'_> {
     ///        is not conveyed), it may include 3 digits to convey
     ///        milliseconds, 6 digits to convey microseconds, 9 digits
     ///        to convey nanoseconds, 12 digits to convey picoseconds;
-    ///        // TODO: set precision!
     pub fn deserialize_utc_time_only(&mut self) -> Result<UtcTimeOnly, DeserializeError> {
         match self.buf {
             [] => {
@@ -1307,57 +1955,27 @@ impl Deserializer<'_> {
                 // minutes
                 m1 @ b'0'..=b'5', m0 @ b'0'..=b'9', b':',
                 // seconds
-                s1 @ b'0'..=b'5', s0 @ b'0'..=b'9', b':',
+                s1 @ b'0'..=b'6', s0 @ b'0'..=b'9',
                 ..
             ] =>
             {
                 let h = (h1 - b'0') * 10 + (h0 - b'0');
                 let m = (m1 - b'0') * 10 + (m0 - b'0');
                 let s = (s1 - b'0') * 10 + (s0 - b'0');
-                self.buf = &self.buf[9..];
-                let (ns, precision) = self.deserialize_fraction_of_second()?;
-                let timestamp = NaiveTime::from_hms_nano_opt(h.into(), m.into(), s.into(), ns)
-                    .ok_or_else(|| self.reject(self.current_tag, ParseRejectReason::ValueIsIncorrect));
-                match timestamp {
-                    Ok(timestamp) => {
-                        match precision {
-                            0 => Ok(UtcTimeOnly::with_secs(timestamp)),
-                            3 => Ok(UtcTimeOnly::with_millis(timestamp)),
-                            6 => Ok(UtcTimeOnly::with_micros(timestamp)),
-                            9 => Ok(UtcTimeOnly::with_nanos(timestamp)),
-                            // XXX: Types from `chrono` crate can't hold
-                            //      time at picosecond resolution
-                            12 => Ok(UtcTimeOnly::with_nanos(timestamp)),
-                            _ => Err(self.reject(self.current_tag, ParseRejectReason::IncorrectDataFormatForValue)),
-                        }
-                    },
-                    Err(err) => Err(err)
-                }
-            }
-            // Leap second case
-            [h1 @ b'0'..=b'2', h0 @ b'0'..=b'9', b':', m1 @ b'0'..=b'5', m0 @ b'0'..=b'9', b':', b'6', b'0', b':'] =>
-            {
-                let h = (h1 - b'0') * 10 + (h0 - b'0');
-                let m = (m1 - b'0') * 10 + (m0 - b'0');
-                let s = 60;
-                self.buf = &self.buf[9..];
+                self.buf = &self.buf[8..];
                 let (ns, precision) = self.deserialize_fraction_of_second()?;
-                let timestamp = NaiveTime::from_hms_nano_opt(h.into(), m.into(), s, ns)
-                    .ok_or_else(|| self.reject(self.current_tag, ParseRejectReason::ValueIsIncorrect));
-                match timestamp {
-                    Ok(timestamp) => {
-                        match precision {
-                            0 => Ok(UtcTimeOnly::with_secs(timestamp)),
-                            3 => Ok(UtcTimeOnly::with_millis(timestamp)),
-                            6 => Ok(UtcTimeOnly::with_micros(timestamp)),
-                            9 => Ok(UtcTimeOnly::with_nanos(timestamp)),
-                            // XXX: Types from `chrono` crate can't hold
-                            //      time at picosecond resolution
-                            12 => Ok(UtcTimeOnly::with_nanos(timestamp)),
-                            _ => Err(self.reject(self.current_tag, ParseRejectReason::IncorrectDataFormatForValue)),
-                        }
-                    },
-                    Err(err) => Err(err)
+                let timestamp = leap_second(s.into(), ns)
+                    .and_then(|(s, ns)| NaiveTime::from_hms_nano_opt(h.into(), m.into(), s, ns))
+                    .ok_or_else(|| self.reject(self.current_tag, ParseRejectReason::ValueIsIncorrect))?;
+                match precision {
+                    0 => Ok(UtcTimeOnly::with_secs(timestamp)),
+                    3 => Ok(UtcTimeOnly::with_millis(timestamp)),
+                    6 => Ok(UtcTimeOnly::with_micros(timestamp)),
+                    9 => Ok(UtcTimeOnly::with_nanos(timestamp)),
+                    // XXX: Types from `chrono` crate can't hold
+                    //      time at picosecond resolution
+                    12 => Ok(UtcTimeOnly::with_nanos(timestamp)),
+                    _ => Err(self.reject(self.current_tag, ParseRejectReason::IncorrectDataFormatForValue)),
                 }
             }
             _ => Err(self.reject(self.current_tag, ParseRejectReason::IncorrectDataFormatForValue)),
@@ -1550,8 +2168,7 @@ impl Deserializer<'_> {
         for i in 0..self.buf.len() {
             // SAFETY: i is between 0 and buf.len()
             if let b'\x01' = unsafe { self.buf.get_unchecked(i) } {
-                // -1 to drop separator, separator on idx 0 is checked separately
-                let data = &self.buf[0..i - 1];
+                let data = &self.buf[0..i];
                 self.buf = &self.buf[i + 1..];
                 // TODO
                 return Ok(data.into());
@@ -1600,8 +2217,7 @@ impl Deserializer<'_> {
         for i in 0..self.buf.len() {
             // SAFETY: i is between 0 and buf.len()
             if let b'\x01' = unsafe { self.buf.get_unchecked(i) } {
-                // -1 to drop separator, separator on idx 0 is checked separately
-                let data = &self.buf[0..i - 1];
+                let data = &self.buf[0..i];
                 self.buf = &self.buf[i + 1..];
                 return Ok(data.into());
             }
@@ -1659,7 +2275,7 @@ impl Deserializer<'_> {
         }
 
         // SAFETY: length checked above
-        if let b'\x01' = unsafe { *self.buf.get_unchecked(len + 1) } {
+        if unsafe { *self.buf.get_unchecked(len) } != b'\x01' {
             // Missing separator
             return Err(DeserializeError::GarbledMessage(format!(
                 "missing tag ({:?}) separator",
@@ -1667,10 +2283,10 @@ impl Deserializer<'_> {
             )));
         }
 
-        let data = &self.buf[0..len];
+        let data = self.data_buf(&self.buf[0..len]);
         // Skip data and separator
         self.buf = &self.buf[len + 1..];
-        Ok(data.into())
+        Ok(data)
     }
 
     /// Deserialize XML document with characterstring repertoire specified
@@ -1709,7 +2325,7 @@ impl Deserializer<'_> {
         }
 
         // SAFETY: length checked above
-        if let b'\x01' = unsafe { *self.buf.get_unchecked(len + 1) } {
+        if unsafe { *self.buf.get_unchecked(len) } != b'\x01' {
             // Missing separator
             return Err(DeserializeError::GarbledMessage(format!(
                 "missing tag ({:?}) separator",
@@ -1718,10 +2334,10 @@ impl Deserializer<'_> {
         }
 
         // TODO: XML validation, ParseRejectReason::XmlValidationError when invalid
-        let xml = &self.buf[0..len];
+        let xml = self.data_buf(&self.buf[0..len]);
         // Skip XML and separator
         self.buf = &self.buf[len + 1..];
-        Ok(xml.into())
+        Ok(xml)
     }
 
     // fn deserialize_tenor(input: &[u8]) -> Result<Tenor, ParseRejectReason>;
@@ -1792,12 +2408,14 @@ mod tests {
     use std::str::FromStr;
 
     use assert_matches::assert_matches;
-    use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
 
-    use super::{deserialize_tag, raw_message, Deserializer, RawMessage};
+    use super::{
+        deserialize_tag, raw_message, DeserializeError, Deserializer, DuplicateTagPolicy,
+        GroupLimits, RawMessage,
+    };
     use crate::{
         deserializer::{deserialize_checksum, RawMessageError},
-        fields::{LocalMktDate, Price, TimePrecision},
+        fields::{LocalMktDate, NaiveDate, NaiveTime, Price, TimePrecision, UtcDateTime},
         messages::BEGIN_STRING,
     };
 
@@ -1923,6 +2541,16 @@ mod tests {
             seq_num: Some(1),
             current_tag: None,
             tmp_tag: None,
+            value_start: body,
+            #[cfg(feature = "pooled-data")]
+            buffer_pool: None,
+            field_errors: None,
+            duplicate_tags: DuplicateTagPolicy::Reject,
+            begin_strings: &[],
+            other_msg_types: false,
+            required_tags: None,
+            group_limits: GroupLimits::default(),
+            group_depth: 0,
         }
     }
 
@@ -1948,6 +2576,24 @@ mod tests {
         assert_eq!(deserializer.buf, &[b'\x00']);
     }
 
+    #[test]
+    fn deserialize_utf8_string_ok() {
+        let input = "żółw 東京\x01\x00".as_bytes();
+        let mut utf8_deserializer = deserializer(input);
+        let buf = utf8_deserializer
+            .deserialize_utf8_string()
+            .expect("failed to deserialize UTF-8 string");
+        assert_eq!(buf, "żółw 東京");
+        assert_eq!(utf8_deserializer.buf, b"\x00");
+
+        for input in [&b"\xc5\x01"[..], b"a\x7fb\x01", b"\x01"] {
+            assert_matches!(
+                deserializer(input).deserialize_utf8_string(),
+                Err(DeserializeError::Reject { .. })
+            );
+        }
+    }
+
     #[test]
     fn deserialize_utc_timestamp_ok() {
         let input = b"20190605-11:51:27\x01\x00";
@@ -1955,12 +2601,11 @@ mod tests {
         let utc_timestamp = deserializer
             .deserialize_utc_timestamp()
             .expect("failed to deserialize utc timestamp");
-        let date_time: DateTime<Utc> = Utc.from_utc_datetime(
-            &NaiveDate::from_ymd_opt(2019, 6, 5)
-                .unwrap()
-                .and_hms_opt(11, 51, 27)
-                .unwrap(),
-        );
+        let date_time: UtcDateTime = NaiveDate::from_ymd_opt(2019, 6, 5)
+            .unwrap()
+            .and_hms_opt(11, 51, 27)
+            .unwrap()
+            .and_utc();
         assert_eq!(utc_timestamp.timestamp(), date_time);
         assert_eq!(utc_timestamp.precision(), TimePrecision::Secs);
         assert_eq!(deserializer.buf, &[b'\x00']);
@@ -1973,12 +2618,11 @@ mod tests {
         let utc_timestamp = deserializer
             .deserialize_utc_timestamp()
             .expect("failed to deserialize utc timestamp");
-        let date_time = Utc.from_utc_datetime(
-            &NaiveDate::from_ymd_opt(2019, 6, 5)
-                .unwrap()
-                .and_hms_milli_opt(11, 51, 27, 848)
-                .unwrap(),
-        );
+        let date_time = NaiveDate::from_ymd_opt(2019, 6, 5)
+            .unwrap()
+            .and_hms_milli_opt(11, 51, 27, 848)
+            .unwrap()
+            .and_utc();
         assert_eq!(utc_timestamp.timestamp(), date_time);
         assert_eq!(utc_timestamp.precision(), TimePrecision::Millis);
         assert_eq!(deserializer.buf, &[b'\x00']);
@@ -1991,12 +2635,11 @@ mod tests {
         let utc_timestamp = deserializer
             .deserialize_utc_timestamp()
             .expect("failed to deserialize utc timestamp");
-        let date_time = Utc.from_utc_datetime(
-            &NaiveDate::from_ymd_opt(2019, 6, 5)
-                .unwrap()
-                .and_hms_micro_opt(11, 51, 27, 848757)
-                .unwrap(),
-        );
+        let date_time = NaiveDate::from_ymd_opt(2019, 6, 5)
+            .unwrap()
+            .and_hms_micro_opt(11, 51, 27, 848757)
+            .unwrap()
+            .and_utc();
         assert_eq!(utc_timestamp.timestamp(), date_time);
         assert_eq!(utc_timestamp.precision(), TimePrecision::Micros);
         assert_eq!(deserializer.buf, &[b'\x00']);
@@ -2009,12 +2652,11 @@ mod tests {
         let utc_timestamp = deserializer
             .deserialize_utc_timestamp()
             .expect("failed to deserialize utc timestamp");
-        let date_time = Utc.from_utc_datetime(
-            &NaiveDate::from_ymd_opt(2019, 6, 5)
-                .unwrap()
-                .and_hms_nano_opt(11, 51, 27, 848757123)
-                .unwrap(),
-        );
+        let date_time = NaiveDate::from_ymd_opt(2019, 6, 5)
+            .unwrap()
+            .and_hms_nano_opt(11, 51, 27, 848757123)
+            .unwrap()
+            .and_utc();
         assert_eq!(utc_timestamp.timestamp(), date_time);
         assert_eq!(utc_timestamp.precision(), TimePrecision::Nanos);
         assert_eq!(deserializer.buf, &[b'\x00']);
@@ -2027,19 +2669,16 @@ mod tests {
         let utc_timestamp = deserializer
             .deserialize_utc_timestamp()
             .expect("failed to deserialize utc timestamp");
-        let date_time = Utc.from_utc_datetime(
-            &NaiveDate::from_ymd_opt(2019, 6, 5)
-                .unwrap()
-                .and_hms_nano_opt(11, 51, 27, 848757123)
-                .unwrap(),
-        );
+        let date_time = NaiveDate::from_ymd_opt(2019, 6, 5)
+            .unwrap()
+            .and_hms_nano_opt(11, 51, 27, 848757123)
+            .unwrap()
+            .and_utc();
         assert_eq!(utc_timestamp.timestamp(), date_time);
         assert_eq!(utc_timestamp.precision(), TimePrecision::Nanos);
         assert_eq!(deserializer.buf, &[b'\x00']);
     }
 
-    /// FIXME: it seems that timeonly deserialization has not been working for some time now
-    #[ignore]
     #[test]
     fn deserialize_utc_timeonly_ok() {
         let input = b"11:51:27\x01\x00";
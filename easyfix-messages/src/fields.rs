@@ -1,4 +1,14 @@
 pub(crate) mod basic_types;
+#[cfg(not(feature = "chrono"))]
+pub(crate) mod datetime;
+#[cfg(not(feature = "rust_decimal"))]
+pub(crate) mod decimal;
+mod field_ref;
+mod metadata;
+mod presence;
 pub use basic_types::*;
+pub use field_ref::{EnumValues, FieldRef, Fields};
+pub use metadata::{enum_metadata_by_tag, EnumMetadata, EnumValueMetadata};
+pub use presence::FieldPresence;
 
 include!(concat!(env!("OUT_DIR"), "/generated_fields.rs"));
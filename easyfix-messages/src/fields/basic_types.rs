@@ -1,10 +1,17 @@
-use std::{borrow, fmt, mem, ops};
+use std::{
+    borrow, fmt,
+    hash::{Hash, Hasher},
+    mem, ops,
+};
 
-use chrono::Timelike;
+#[cfg(feature = "chrono")]
 pub use chrono::{
     format::{DelayedFormat, StrftimeItems},
     DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
 };
+#[cfg(feature = "chrono")]
+use chrono::{Datelike, Timelike};
+#[cfg(feature = "rust_decimal")]
 pub use rust_decimal::Decimal;
 use serde::{
     de::{self, Visitor},
@@ -29,8 +36,30 @@ pub type Boolean = bool;
 pub type Char = u8;
 pub type MultipleCharValue = Vec<Char>;
 
+/// Owned FIX string.
+///
+/// With `shared-strings` feature it is backed by reference counted buffer,
+/// so cloning it (e.g. when message is cloned for resend or drop copy) does
+/// not copy the content. Mutating methods copy the content only when
+/// the buffer is shared with other clone.
 #[derive(Clone, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct FixString(Vec<u8>);
+pub struct FixString(FixStringBuf);
+
+#[cfg(not(feature = "shared-strings"))]
+type FixStringBuf = Vec<u8>;
+#[cfg(feature = "shared-strings")]
+type FixStringBuf = bytes::Bytes;
+
+// Conversions are no-op unless `shared-strings` is enabled
+#[allow(clippy::useless_conversion)]
+fn buf_from_vec(buf: Vec<u8>) -> FixStringBuf {
+    buf.into()
+}
+
+#[allow(clippy::useless_conversion)]
+fn buf_into_vec(buf: FixStringBuf) -> Vec<u8> {
+    buf.into()
+}
 
 #[derive(Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[repr(transparent)]
@@ -38,24 +67,55 @@ pub struct FixStr([u8]);
 
 pub type MultipleStringValue = Vec<FixString>;
 
-pub use crate::{country::Country, currency::Currency};
+#[cfg(not(feature = "chrono"))]
+pub use super::datetime::{NaiveDate, NaiveDateTime, NaiveTime, Utc, UtcDateTime};
+#[cfg(not(feature = "rust_decimal"))]
+pub use super::decimal::{Decimal, DecimalError};
+pub use crate::{buffer_pool::PooledBuf, country::Country, currency::Currency};
+
+/// Point in time in UTC, `chrono::DateTime<Utc>` unless `chrono` feature
+/// is disabled.
+#[cfg(feature = "chrono")]
+pub type UtcDateTime = DateTime<Utc>;
 pub type Exchange = [u8; 4];
 // TODO: don't use Vec here
 pub type MonthYear = Vec<u8>;
 pub type Language = [u8; 2];
 
-#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
 pub enum TimePrecision {
     Secs = 0,
     Millis = 3,
     Micros = 6,
-    #[default]
     Nanos = 9,
 }
 
+impl TimePrecision {
+    /// Precision of timestamps created with [`UtcTimestamp::now`] and
+    /// [`UtcTimestamp::default`], selected with `timestamp-secs`,
+    /// `timestamp-millis` or `timestamp-micros` crate feature. When more
+    /// than one is enabled, the coarsest one is used, nanoseconds when
+    /// none is enabled.
+    pub const DEFAULT: TimePrecision = if cfg!(feature = "timestamp-secs") {
+        TimePrecision::Secs
+    } else if cfg!(feature = "timestamp-millis") {
+        TimePrecision::Millis
+    } else if cfg!(feature = "timestamp-micros") {
+        TimePrecision::Micros
+    } else {
+        TimePrecision::Nanos
+    };
+}
+
+impl Default for TimePrecision {
+    fn default() -> TimePrecision {
+        TimePrecision::DEFAULT
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct UtcTimestamp {
-    timestamp: DateTime<Utc>,
+    timestamp: UtcDateTime,
     precision: TimePrecision,
 }
 
@@ -74,6 +134,11 @@ pub type TzTimestamp = Vec<u8>;
 pub type TzTimeOnly = Vec<u8>;
 
 pub type Length = u16;
+/// Buffer taken from [`BufferPool`](crate::buffer_pool::BufferPool) when
+/// deserializer has one set, see `pooled-data` feature.
+#[cfg(feature = "pooled-data")]
+pub type Data = PooledBuf;
+#[cfg(not(feature = "pooled-data"))]
 pub type Data = Vec<u8>;
 pub type XmlData = Data;
 
@@ -302,13 +367,13 @@ impl<const N: usize> PartialEq<[u8; N]> for &FixStr {
 
 impl<const N: usize> PartialEq<[u8; N]> for FixString {
     fn eq(&self, other: &[u8; N]) -> bool {
-        self.0.eq(other)
+        self.as_bytes().eq(&other[..])
     }
 }
 
 impl<const N: usize> PartialEq<&'_ [u8; N]> for FixString {
     fn eq(&self, other: &&[u8; N]) -> bool {
-        self.0.eq(other)
+        self.as_bytes().eq(&other[..])
     }
 }
 
@@ -326,11 +391,11 @@ macro_rules! fix_format {
 // TODO: Optional feature for ISO 8859-1 encoded strings
 impl FixString {
     pub const fn new() -> FixString {
-        FixString(Vec::new())
+        FixString(FixStringBuf::new())
     }
 
     pub fn with_capacity(capacity: usize) -> FixString {
-        FixString(Vec::with_capacity(capacity))
+        FixString(buf_from_vec(Vec::with_capacity(capacity)))
     }
 
     /// Converts a vector of bytes to a `FixString`.
@@ -372,7 +437,7 @@ impl FixString {
                 return Err(FixStringError { idx: i, value: c });
             }
         }
-        Ok(FixString(buf))
+        Ok(FixString(buf_from_vec(buf)))
     }
 
     /// Converts a vector of bytes to a `FixString` without checking that the
@@ -389,7 +454,7 @@ impl FixString {
     /// memory unsafety issues with future users of the `FixString`,
     /// as the rest of the library assumes that `FixString`s are valid ASCII.
     pub unsafe fn from_ascii_unchecked(buf: Vec<u8>) -> FixString {
-        FixString(buf)
+        FixString(buf_from_vec(buf))
     }
 
     /// Converts a slice of bytes to a `FixString`, replacing invalid
@@ -402,7 +467,7 @@ impl FixString {
                 *c = b'?';
             }
         }
-        FixString(buf)
+        FixString(buf_from_vec(buf))
     }
 
     pub fn as_utf8(&self) -> &str {
@@ -412,11 +477,39 @@ impl FixString {
 
     pub fn into_utf8(self) -> String {
         // SAFETY: ASCII is always valid UTF-8
-        unsafe { String::from_utf8_unchecked(self.0) }
+        unsafe { String::from_utf8_unchecked(self.into_bytes()) }
     }
 
+    /// Converts a `FixString` into a byte vector, without copying unless
+    /// the buffer is shared with other clone.
     pub fn into_bytes(self) -> Vec<u8> {
-        self.0
+        buf_into_vec(self.0)
+    }
+
+    /// Appends given string to the end of this `FixString`.
+    pub fn push_fix_str(&mut self, fix_str: &FixStr) {
+        self.update(|buf| buf.extend_from_slice(fix_str.as_bytes()));
+    }
+
+    /// Shortens this `FixString` to the specified length, has no effect
+    /// when `len` is greater than the current length.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.update(|buf| buf.truncate(len));
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Modify content of owned buffer (copy of shared one).
+    ///
+    /// `update` must keep the buffer ASCII only.
+    fn update(&mut self, update: impl FnOnce(&mut Vec<u8>)) {
+        let mut buf = mem::take(self).into_bytes();
+        update(&mut buf);
+        self.0 = buf_from_vec(buf);
     }
 
     pub fn len(&self) -> usize {
@@ -481,7 +574,7 @@ impl From<&FixStr> for FixString {
 impl From<FixString> for String {
     fn from(input: FixString) -> String {
         // SAFETY: FixString consists of ASCII characters only thus it's valid UTF-8
-        unsafe { String::from_utf8_unchecked(input.0) }
+        unsafe { String::from_utf8_unchecked(input.into_bytes()) }
     }
 }
 
@@ -528,7 +621,18 @@ impl<const N: usize> TryFrom<[u8; N]> for FixString {
 
 impl<const N: usize> From<&[u8; N]> for FixString {
     fn from(input: &[u8; N]) -> FixString {
-        FixString(input.as_slice().into())
+        FixString(buf_from_vec(input.to_vec()))
+    }
+}
+
+impl TryFrom<bytes::Bytes> for FixString {
+    type Error = FixStringError;
+
+    /// With `shared-strings` feature the buffer is used without copying.
+    #[allow(clippy::useless_conversion)]
+    fn try_from(buf: bytes::Bytes) -> Result<FixString, Self::Error> {
+        FixStr::from_ascii(&buf)?;
+        Ok(FixString(buf.into()))
     }
 }
 
@@ -567,6 +671,253 @@ impl Serialize for FixString {
     }
 }
 
+/// Owned string of any UTF-8 characters except control ones, for
+/// free-text fields (e.g. Text<58>) of counterparties accepting non-ASCII
+/// text. Used instead of [`FixString`] for fields selected with
+/// `UTF8_TAGS` when messages are generated.
+///
+/// Length of value on the wire (and in BodyLength<9>) is its length
+/// in bytes, not in characters.
+#[derive(Clone, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Utf8String(String);
+
+fn check_utf8_string(input: &str) -> Result<(), FixStringError> {
+    match input.char_indices().find(|(_, c)| c.is_control()) {
+        Some((idx, _)) => Err(FixStringError {
+            idx,
+            value: input.as_bytes()[idx],
+        }),
+        None => Ok(()),
+    }
+}
+
+impl Utf8String {
+    pub const fn new() -> Utf8String {
+        Utf8String(String::new())
+    }
+
+    /// Converts a vector of bytes to a `Utf8String`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the bytes are not valid UTF-8 or contain control
+    /// character (e.g. SOH), with index of the first invalid byte.
+    pub fn from_utf8(buf: Vec<u8>) -> Result<Utf8String, FixStringError> {
+        let input = String::from_utf8(buf).map_err(|err| {
+            let idx = err.utf8_error().valid_up_to();
+            FixStringError {
+                idx,
+                value: err.as_bytes()[idx],
+            }
+        })?;
+        check_utf8_string(&input)?;
+        Ok(Utf8String(input))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// Returns the length of string in bytes.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Display for Utf8String {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Debug for Utf8String {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Utf8String({:?})", self.0)
+    }
+}
+
+impl ops::Deref for Utf8String {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Utf8String {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Utf8String {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl From<&FixStr> for Utf8String {
+    /// DEL (0x7f) allowed in `FixStr` is replaced with U+FFFD.
+    fn from(input: &FixStr) -> Utf8String {
+        Utf8String(input.as_utf8().replace('\x7f', "\u{fffd}"))
+    }
+}
+
+impl From<Utf8String> for String {
+    fn from(input: Utf8String) -> String {
+        input.0
+    }
+}
+
+impl TryFrom<&str> for Utf8String {
+    type Error = FixStringError;
+
+    fn try_from(input: &str) -> Result<Utf8String, Self::Error> {
+        check_utf8_string(input)?;
+        Ok(Utf8String(input.to_owned()))
+    }
+}
+
+impl TryFrom<String> for Utf8String {
+    type Error = FixStringError;
+
+    fn try_from(input: String) -> Result<Utf8String, Self::Error> {
+        check_utf8_string(&input)?;
+        Ok(Utf8String(input))
+    }
+}
+
+impl TryFrom<Vec<u8>> for Utf8String {
+    type Error = FixStringError;
+
+    fn try_from(buf: Vec<u8>) -> Result<Utf8String, Self::Error> {
+        Utf8String::from_utf8(buf)
+    }
+}
+
+impl PartialEq<str> for Utf8String {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Utf8String {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl<'de> Deserialize<'de> for Utf8String {
+    fn deserialize<D>(deserializer: D) -> Result<Utf8String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .try_into()
+            .map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for Utf8String {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+/// Raw MsgType<35> value of message type not defined in dictionary,
+/// held inline, so `MsgType::Other` stays `Copy`.
+///
+/// Only alphanumeric values of up to [`MsgTypeCode::MAX_LEN`] characters
+/// are representable.
+#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct MsgTypeCode {
+    len: u8,
+    buf: [u8; MsgTypeCode::MAX_LEN],
+}
+
+impl MsgTypeCode {
+    pub const MAX_LEN: usize = 8;
+
+    pub const fn from_bytes(input: &[u8]) -> Option<MsgTypeCode> {
+        if input.is_empty() || input.len() > MsgTypeCode::MAX_LEN {
+            return None;
+        }
+        let mut buf = [0; MsgTypeCode::MAX_LEN];
+        let mut i = 0;
+        while i < input.len() {
+            if !input[i].is_ascii_alphanumeric() {
+                return None;
+            }
+            buf[i] = input[i];
+            i += 1;
+        }
+        Some(MsgTypeCode {
+            len: input.len() as u8,
+            buf,
+        })
+    }
+
+    pub const fn from_fix_str(input: &FixStr) -> Option<MsgTypeCode> {
+        MsgTypeCode::from_bytes(input.as_bytes())
+    }
+
+    pub const fn as_bytes(&self) -> &[u8] {
+        self.buf.split_at(self.len as usize).0
+    }
+
+    pub const fn as_fix_str(&self) -> &FixStr {
+        // SAFETY: only alphanumeric ASCII characters are accepted
+        unsafe { FixStr::from_ascii_unchecked(self.as_bytes()) }
+    }
+}
+
+impl fmt::Display for MsgTypeCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_fix_str().fmt(f)
+    }
+}
+
+impl fmt::Debug for MsgTypeCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MsgTypeCode(\"{}\")", self)
+    }
+}
+
+impl Serialize for MsgTypeCode {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_fix_str().as_utf8())
+    }
+}
+
+impl<'de> Deserialize<'de> for MsgTypeCode {
+    fn deserialize<D>(deserializer: D) -> Result<MsgTypeCode, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let code = String::deserialize(deserializer)?;
+        MsgTypeCode::from_bytes(code.as_bytes())
+            .ok_or_else(|| de::Error::custom(format!("invalid MsgType `{code}`")))
+    }
+}
+
 pub trait ToFixString {
     fn to_fix_string(&self) -> FixString;
 }
@@ -616,8 +967,11 @@ where
     //     }
     // }
 
-    let [b'.', buf @ ..] = buf else {
-        return Err(de::Error::custom("incorrecct data format for UtcTimestamp"));
+    let buf = match buf {
+        // Whole seconds
+        [] => return Ok((0, 0)),
+        [b'.', buf @ ..] => buf,
+        _ => return Err(de::Error::custom("incorrecct data format for UtcTimestamp")),
     };
 
     let mut fraction_of_second: u64 = 0;
@@ -723,7 +1077,7 @@ impl Visitor<'_> for UtcTimestampVisitor {
                 let naive_date_time = naive_date
                     .and_hms_nano_opt(hour, min, sec, fraction_of_second)
                     .ok_or_else(|| de::Error::custom("incorrecct data format for UtcTimestamp"))?;
-                let timestamp = Utc.from_utc_datetime(&naive_date_time);
+                let timestamp = naive_date_time.and_utc();
 
                 match precision {
                     0 => Ok(UtcTimestamp::with_secs(timestamp)),
@@ -755,8 +1109,7 @@ impl Serialize for UtcTimestamp {
     where
         S: Serializer,
     {
-        let formatted_timestamp = self.format_precisely().to_string();
-        serializer.serialize_str(&formatted_timestamp)
+        serializer.serialize_str(&self.to_string())
     }
 }
 
@@ -780,20 +1133,29 @@ impl Ord for UtcTimestamp {
     }
 }
 
+// Precision is ignored, consistently with `PartialEq`
+impl Hash for UtcTimestamp {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.timestamp.hash(state)
+    }
+}
+
 impl fmt::Display for UtcTimestamp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let result = self.format_precisely().to_string();
-        write!(f, "{}", result)
+        let mut output = Vec::with_capacity(32);
+        crate::serializer::push_utc_timestamp(&mut output, self);
+        // SAFETY: only ASCII digits and separators are written
+        f.write_str(unsafe { std::str::from_utf8_unchecked(&output) })
     }
 }
 
 impl UtcTimestamp {
     pub const MAX_UTC: UtcTimestamp = UtcTimestamp {
-        timestamp: DateTime::<Utc>::MAX_UTC,
+        timestamp: UtcDateTime::MAX_UTC,
         precision: TimePrecision::Nanos,
     };
     pub const MIN_UTC: UtcTimestamp = UtcTimestamp {
-        timestamp: DateTime::<Utc>::MIN_UTC,
+        timestamp: UtcDateTime::MIN_UTC,
         precision: TimePrecision::Nanos,
     };
 
@@ -804,7 +1166,7 @@ impl UtcTimestamp {
 
     /// Creates UtcTimestamp with given time precision
     /// input's precision is adjusted to requested one
-    pub fn with_precision(date_time: DateTime<Utc>, precision: TimePrecision) -> UtcTimestamp {
+    pub fn with_precision(date_time: UtcDateTime, precision: TimePrecision) -> UtcTimestamp {
         match precision {
             TimePrecision::Secs => UtcTimestamp::with_secs(date_time),
             TimePrecision::Millis => UtcTimestamp::with_millis(date_time),
@@ -813,13 +1175,13 @@ impl UtcTimestamp {
         }
     }
 
-    fn timestamp_from_secs_and_nsecs(secs: i64, nsecs: u32) -> DateTime<Utc> {
-        DateTime::from_timestamp(secs, nsecs).unwrap()
+    fn timestamp_from_secs_and_nsecs(secs: i64, nsecs: u32) -> UtcDateTime {
+        UtcDateTime::from_timestamp(secs, nsecs).unwrap()
     }
 
     /// Creates UtcTimestamp with time precision set to full seconds
     /// input's precision is adjusted to requested one
-    pub fn with_secs(date_time: DateTime<Utc>) -> UtcTimestamp {
+    pub fn with_secs(date_time: UtcDateTime) -> UtcTimestamp {
         let secs = date_time.timestamp();
         UtcTimestamp {
             timestamp: Self::timestamp_from_secs_and_nsecs(secs, 0),
@@ -833,7 +1195,7 @@ impl UtcTimestamp {
 
     /// Creates UtcTimestamp with time precision set to milliseconds
     /// input's precision is adjusted to requested one
-    pub fn with_millis(date_time: DateTime<Utc>) -> UtcTimestamp {
+    pub fn with_millis(date_time: UtcDateTime) -> UtcTimestamp {
         let secs = date_time.timestamp();
         let nsecs = date_time.timestamp_subsec_millis() * 1_000_000;
         UtcTimestamp {
@@ -844,7 +1206,7 @@ impl UtcTimestamp {
 
     /// Creates UtcTimestamp with time precision set to microseconds
     /// input's precision is adjusted to requested one
-    pub fn with_micros(date_time: DateTime<Utc>) -> UtcTimestamp {
+    pub fn with_micros(date_time: UtcDateTime) -> UtcTimestamp {
         let secs = date_time.timestamp();
         let nsecs = date_time.timestamp_subsec_micros() * 1_000;
         UtcTimestamp {
@@ -855,7 +1217,7 @@ impl UtcTimestamp {
 
     /// Creates UtcTimestamp with time precision set to nanoseconds
     /// input's precision is adjusted to requested one
-    pub fn with_nanos(date_time: DateTime<Utc>) -> UtcTimestamp {
+    pub fn with_nanos(date_time: UtcDateTime) -> UtcTimestamp {
         let secs = date_time.timestamp();
         let nsecs = date_time.timestamp_subsec_nanos();
         UtcTimestamp {
@@ -865,6 +1227,7 @@ impl UtcTimestamp {
     }
 
     /// Formats timestamp with precision set inside the struct
+    #[cfg(feature = "chrono")]
     pub fn format_precisely(&self) -> DelayedFormat<StrftimeItems> {
         match self.precision {
             TimePrecision::Secs => self.format("%Y%m%d-%H:%M:%S"),
@@ -874,11 +1237,12 @@ impl UtcTimestamp {
         }
     }
 
+    #[cfg(feature = "chrono")]
     pub fn format<'a>(&self, fmt: &'a str) -> DelayedFormat<StrftimeItems<'a>> {
         self.timestamp.format(fmt)
     }
 
-    pub fn timestamp(&self) -> DateTime<Utc> {
+    pub fn timestamp(&self) -> UtcDateTime {
         self.timestamp
     }
 
@@ -887,6 +1251,33 @@ impl UtcTimestamp {
     }
 }
 
+impl PartialEq for UtcTimeOnly {
+    fn eq(&self, other: &Self) -> bool {
+        self.timestamp == other.timestamp
+    }
+}
+
+impl Eq for UtcTimeOnly {}
+
+impl PartialOrd for UtcTimeOnly {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for UtcTimeOnly {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.timestamp.cmp(&other.timestamp)
+    }
+}
+
+// Precision is ignored, consistently with `PartialEq`
+impl Hash for UtcTimeOnly {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.timestamp.hash(state)
+    }
+}
+
 impl UtcTimeOnly {
     /// Creates UtcTimeOnly with time precision set to full seconds
     /// input's precision is adjusted to requested one
@@ -901,7 +1292,9 @@ impl UtcTimeOnly {
     /// input's precision is adjusted to requested one
     pub fn with_millis(time: NaiveTime) -> UtcTimeOnly {
         UtcTimeOnly {
-            timestamp: time.with_nanosecond(time.nanosecond() / 1_000_000).unwrap(),
+            timestamp: time
+                .with_nanosecond(time.nanosecond() / 1_000_000 * 1_000_000)
+                .unwrap(),
             precision: TimePrecision::Millis,
         }
     }
@@ -910,7 +1303,9 @@ impl UtcTimeOnly {
     /// input's precision is adjusted to requested one
     pub fn with_micros(time: NaiveTime) -> UtcTimeOnly {
         UtcTimeOnly {
-            timestamp: time.with_nanosecond(time.nanosecond() / 1_000).unwrap(),
+            timestamp: time
+                .with_nanosecond(time.nanosecond() / 1_000 * 1_000)
+                .unwrap(),
             precision: TimePrecision::Micros,
         }
     }
@@ -924,6 +1319,7 @@ impl UtcTimeOnly {
         }
     }
 
+    #[cfg(feature = "chrono")]
     pub fn format<'a>(&self, fmt: &'a str) -> DelayedFormat<StrftimeItems<'a>> {
         self.timestamp.format(fmt)
     }
@@ -937,10 +1333,175 @@ impl UtcTimeOnly {
     }
 }
 
+/// Append `date_time` to `output` in UTCTimestamp `YYYYMMDD-HH:MM:SS[.sss*]`
+/// format, with number of fraction digits set by `precision`, the same
+/// way as timestamp fields are serialized.
+pub fn write_utc_timestamp(
+    output: &mut Vec<u8>,
+    date_time: &UtcDateTime,
+    precision: TimePrecision,
+) {
+    crate::serializer::push_utc_timestamp(
+        output,
+        &UtcTimestamp::with_precision(*date_time, precision),
+    );
+}
+
+/// Parse UTCTimestamp in `YYYYMMDD-HH:MM:SS[.sss*]` format, precision
+/// is set by number of fraction digits.
+pub fn parse_utc_timestamp(input: &[u8]) -> Option<UtcTimestamp> {
+    let input = std::str::from_utf8(input).ok()?;
+    UtcTimestampVisitor
+        .visit_str::<de::value::Error>(input)
+        .ok()
+}
+
+/// Append `date` to `output` in LocalMktDate `YYYYMMDD` format.
+pub fn write_local_mkt_date(output: &mut Vec<u8>, date: &LocalMktDate) {
+    crate::serializer::push_date(output, date.year(), date.month(), date.day());
+}
+
+/// Parse LocalMktDate in `YYYYMMDD` format.
+pub fn parse_local_mkt_date(input: &[u8]) -> Option<LocalMktDate> {
+    let digits: [u8; 8] = input.try_into().ok()?;
+    if !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let number = |digits: &[u8]| {
+        digits
+            .iter()
+            .fold(0, |acc, digit| acc * 10 + u32::from(digit - b'0'))
+    };
+    LocalMktDate::from_ymd_opt(
+        number(&digits[..4]) as i32,
+        number(&digits[4..6]),
+        number(&digits[6..]),
+    )
+}
+
+/// Append decimal representation of integer `value` to `output`.
+pub fn write_int<I: itoa::Integer>(output: &mut Vec<u8>, value: I) {
+    let mut buffer = itoa::Buffer::new();
+    output.extend_from_slice(buffer.format(value).as_bytes());
+}
+
+/// Append representation of `value` to `output`, formatted the same way
+/// as `Decimal::to_string()` but without intermediate allocation.
+pub fn write_decimal(output: &mut Vec<u8>, value: &Decimal) {
+    if value.is_sign_negative() {
+        output.push(b'-');
+    }
+    let mut buffer = itoa::Buffer::new();
+    let digits = buffer.format(value.mantissa().unsigned_abs()).as_bytes();
+    let scale = value.scale() as usize;
+    if scale == 0 {
+        output.extend_from_slice(digits);
+    } else if digits.len() > scale {
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        output.extend_from_slice(integer);
+        output.push(b'.');
+        output.extend_from_slice(fraction);
+    } else {
+        output.extend_from_slice(b"0.");
+        output.resize(output.len() + scale - digits.len(), b'0');
+        output.extend_from_slice(digits);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn write_decimal_same_as_to_string() {
+        for (mantissa, scale) in [
+            (0, 0),
+            (0, 3),
+            (1, 0),
+            (-1, 0),
+            (5, 3),
+            (-5, 3),
+            (12345, 2),
+            (-12345, 5),
+            (12345, 7),
+            (i64::MAX, 0),
+            (i64::MIN, 10),
+            (i64::MAX, 28),
+        ] {
+            let value = Decimal::new(mantissa, scale);
+            let mut output = Vec::new();
+            write_decimal(&mut output, &value);
+            assert_eq!(output, value.to_string().as_bytes());
+        }
+
+        let max = Decimal::MAX;
+        let mut output = Vec::new();
+        write_decimal(&mut output, &max);
+        assert_eq!(output, max.to_string().as_bytes());
+    }
+
+    #[test]
+    fn utf8_string_validated() {
+        let text = Utf8String::try_from("Zażółć gęślą jaźń").unwrap();
+        assert_eq!(text.len(), 26);
+        assert_eq!(text.chars().count(), 17);
+        assert_eq!(
+            Utf8String::from_utf8(text.as_bytes().to_vec()).unwrap(),
+            text
+        );
+
+        let err = Utf8String::try_from("ab\x01c").unwrap_err();
+        assert_eq!((err.idx(), err.value()), (2, 0x01));
+        let err = Utf8String::try_from("ab\u{85}").unwrap_err();
+        assert_eq!((err.idx(), err.value()), (2, 0xc2));
+        let err = Utf8String::from_utf8(b"ab\xc5".to_vec()).unwrap_err();
+        assert_eq!((err.idx(), err.value()), (2, 0xc5));
+    }
+
+    #[test]
+    fn utc_timestamp_write_and_parse() {
+        let date_time = NaiveDate::from_ymd_opt(2024, 3, 5)
+            .unwrap()
+            .and_hms_nano_opt(7, 8, 9, 123_456_789)
+            .unwrap()
+            .and_utc();
+        for (precision, expected) in [
+            (TimePrecision::Secs, &b"20240305-07:08:09"[..]),
+            (TimePrecision::Millis, b"20240305-07:08:09.123"),
+            (TimePrecision::Micros, b"20240305-07:08:09.123456"),
+            (TimePrecision::Nanos, b"20240305-07:08:09.123456789"),
+        ] {
+            let mut output = Vec::new();
+            write_utc_timestamp(&mut output, &date_time, precision);
+            assert_eq!(output, expected);
+            let parsed = parse_utc_timestamp(&output).unwrap();
+            assert_eq!(parsed.precision(), precision);
+            assert_eq!(parsed, UtcTimestamp::with_precision(date_time, precision));
+        }
+        assert_eq!(parse_utc_timestamp(b"20240305-07:08:09.12"), None);
+        assert_eq!(parse_utc_timestamp(b"20241305-07:08:09"), None);
+    }
+
+    #[test]
+    fn local_mkt_date_write_and_parse() {
+        let date = LocalMktDate::from_ymd_opt(2024, 2, 29).unwrap();
+        let mut output = Vec::new();
+        write_local_mkt_date(&mut output, &date);
+        assert_eq!(output, b"20240229");
+        assert_eq!(parse_local_mkt_date(&output), Some(date));
+        assert_eq!(parse_local_mkt_date(b"20230229"), None);
+        assert_eq!(parse_local_mkt_date(b"2024022"), None);
+        assert_eq!(parse_local_mkt_date(b"2024-2-29"), None);
+    }
+
+    #[test]
+    fn write_int_ok() {
+        let mut output = b"34=".to_vec();
+        write_int(&mut output, 1234u32);
+        write_int(&mut output, -5i64);
+        assert_eq!(output, b"34=1234-5");
+    }
+
     #[test]
     fn fix_string_fail_on_ctrl_character() {
         let buf = b"Hello\x01world!".to_vec();
@@ -965,9 +1526,56 @@ mod tests {
         assert_eq!(FixString::from_ascii_lossy(buf), "Hello?world!");
     }
 
+    #[test]
+    fn fix_string_mutation() {
+        let mut value = FixString::from(b"Hello");
+        value.push_fix_str(FixStr::from_ascii(b" world!").unwrap());
+        assert_eq!(value, "Hello world!");
+        value.truncate(5);
+        assert_eq!(value, "Hello");
+        value.truncate(10);
+        assert_eq!(value, "Hello");
+        value.clear();
+        assert!(value.is_empty());
+    }
+
+    #[cfg(feature = "shared-strings")]
+    #[test]
+    fn fix_string_shared_copy_on_write() {
+        let original = FixString::from(b"Hello");
+        let mut copy = original.clone();
+        assert_eq!(copy.as_bytes().as_ptr(), original.as_bytes().as_ptr());
+
+        copy.push_fix_str(FixStr::from_ascii(b" world!").unwrap());
+        assert_eq!(original, "Hello");
+        assert_eq!(copy, "Hello world!");
+        assert_ne!(copy.as_bytes().as_ptr(), original.as_bytes().as_ptr());
+    }
+
+    #[cfg(not(any(
+        feature = "timestamp-secs",
+        feature = "timestamp-millis",
+        feature = "timestamp-micros"
+    )))]
     #[test]
     fn utc_timestamp_default_precision_nanos() {
         let now = UtcTimestamp::now();
         assert_eq!(now.precision(), TimePrecision::Nanos);
     }
+
+    #[test]
+    fn utc_timestamp_default_precision_from_features() {
+        let now = UtcTimestamp::now();
+        assert_eq!(now.precision(), TimePrecision::DEFAULT);
+        assert_eq!(UtcTimestamp::default().precision(), TimePrecision::DEFAULT);
+        let expected_len = match TimePrecision::DEFAULT {
+            TimePrecision::Secs => 17,
+            TimePrecision::Millis => 21,
+            TimePrecision::Micros => 24,
+            TimePrecision::Nanos => 27,
+        };
+        let mut output = Vec::new();
+        write_utc_timestamp(&mut output, &now.timestamp(), now.precision());
+        assert_eq!(output.len(), expected_len);
+    }
 }
@@ -0,0 +1,474 @@
+//! Minimal date and time types, used when `chrono` feature is disabled.
+//!
+//! Only the subset of `chrono` API needed by FIX basic types is provided,
+//! with the same method names, so code working on [`UtcDateTime`],
+//! [`NaiveDate`] and [`NaiveTime`] compiles with both backends. Calendar
+//! dates are backed by `time::Date`. Supported years are 0000-9999
+//! (the range representable in FIX).
+//!
+//! Like in `chrono`, leap second is represented as nanoseconds
+//! of second 59 exceeding 999_999_999.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use time::{Date, Month, OffsetDateTime};
+
+const SECS_PER_DAY: i64 = 86_400;
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+// 1970-01-01
+const UNIX_EPOCH_JULIAN_DAY: i64 = OffsetDateTime::UNIX_EPOCH.date().to_julian_day() as i64;
+// 0000-01-01 and 9999-12-31
+const MIN_DAYS: i64 = NaiveDate::MIN.days();
+const MAX_DAYS: i64 = NaiveDate::MAX.days();
+
+const fn month_from_number(month: u32) -> Option<Month> {
+    let month = match month {
+        1 => Month::January,
+        2 => Month::February,
+        3 => Month::March,
+        4 => Month::April,
+        5 => Month::May,
+        6 => Month::June,
+        7 => Month::July,
+        8 => Month::August,
+        9 => Month::September,
+        10 => Month::October,
+        11 => Month::November,
+        12 => Month::December,
+        _ => return None,
+    };
+    Some(month)
+}
+
+/// Calendar date without time zone.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct NaiveDate(Date);
+
+impl NaiveDate {
+    pub const MAX: NaiveDate = match NaiveDate::from_ymd_opt(9999, 12, 31) {
+        Some(date) => date,
+        None => unreachable!(),
+    };
+    pub const MIN: NaiveDate = match NaiveDate::from_ymd_opt(0, 1, 1) {
+        Some(date) => date,
+        None => unreachable!(),
+    };
+
+    pub const fn from_ymd_opt(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+        // `time::Date` supports wider range of years
+        if year < 0 || year > 9999 || day > u8::MAX as u32 {
+            return None;
+        }
+        let Some(month) = month_from_number(month) else {
+            return None;
+        };
+        match Date::from_calendar_date(year, month, day as u8) {
+            Ok(date) => Some(NaiveDate(date)),
+            Err(_) => None,
+        }
+    }
+
+    /// Date `days` after 1970-01-01, must be in supported range.
+    const fn from_days(days: i64) -> NaiveDate {
+        match Date::from_julian_day((days + UNIX_EPOCH_JULIAN_DAY) as i32) {
+            Ok(date) => NaiveDate(date),
+            Err(_) => panic!("date out of range"),
+        }
+    }
+
+    /// Number of days since 1970-01-01.
+    const fn days(&self) -> i64 {
+        self.0.to_julian_day() as i64 - UNIX_EPOCH_JULIAN_DAY
+    }
+
+    pub const fn year(&self) -> i32 {
+        self.0.year()
+    }
+
+    pub const fn month(&self) -> u32 {
+        self.0.month() as u32
+    }
+
+    pub const fn day(&self) -> u32 {
+        self.0.day() as u32
+    }
+
+    pub const fn and_time(&self, time: NaiveTime) -> NaiveDateTime {
+        NaiveDateTime { date: *self, time }
+    }
+
+    pub const fn and_hms_opt(&self, hour: u32, min: u32, sec: u32) -> Option<NaiveDateTime> {
+        self.and_hms_nano_opt(hour, min, sec, 0)
+    }
+
+    pub const fn and_hms_milli_opt(
+        &self,
+        hour: u32,
+        min: u32,
+        sec: u32,
+        milli: u32,
+    ) -> Option<NaiveDateTime> {
+        if milli >= 2_000 {
+            return None;
+        }
+        self.and_hms_nano_opt(hour, min, sec, milli * 1_000_000)
+    }
+
+    pub const fn and_hms_micro_opt(
+        &self,
+        hour: u32,
+        min: u32,
+        sec: u32,
+        micro: u32,
+    ) -> Option<NaiveDateTime> {
+        if micro >= 2_000_000 {
+            return None;
+        }
+        self.and_hms_nano_opt(hour, min, sec, micro * 1_000)
+    }
+
+    pub const fn and_hms_nano_opt(
+        &self,
+        hour: u32,
+        min: u32,
+        sec: u32,
+        nano: u32,
+    ) -> Option<NaiveDateTime> {
+        match NaiveTime::from_hms_nano_opt(hour, min, sec, nano) {
+            Some(time) => Some(self.and_time(time)),
+            None => None,
+        }
+    }
+}
+
+impl Default for NaiveDate {
+    /// 1970-01-01, same as in `chrono`.
+    fn default() -> NaiveDate {
+        NaiveDate::from_days(0)
+    }
+}
+
+impl fmt::Display for NaiveDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}",
+            self.year(),
+            self.month(),
+            self.day()
+        )
+    }
+}
+
+/// Time of day with nanosecond precision, without time zone.
+#[derive(
+    Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize,
+)]
+pub struct NaiveTime {
+    secs: u32,
+    nanos: u32,
+}
+
+impl NaiveTime {
+    pub const MIN: NaiveTime = NaiveTime { secs: 0, nanos: 0 };
+
+    pub const fn from_hms_opt(hour: u32, min: u32, sec: u32) -> Option<NaiveTime> {
+        NaiveTime::from_hms_nano_opt(hour, min, sec, 0)
+    }
+
+    pub const fn from_hms_milli_opt(
+        hour: u32,
+        min: u32,
+        sec: u32,
+        milli: u32,
+    ) -> Option<NaiveTime> {
+        if milli >= 2_000 {
+            return None;
+        }
+        NaiveTime::from_hms_nano_opt(hour, min, sec, milli * 1_000_000)
+    }
+
+    pub const fn from_hms_nano_opt(hour: u32, min: u32, sec: u32, nano: u32) -> Option<NaiveTime> {
+        if hour >= 24 || min >= 60 || sec >= 60 || nano >= 2 * NANOS_PER_SEC {
+            return None;
+        }
+        // Leap second
+        if nano >= NANOS_PER_SEC && sec != 59 {
+            return None;
+        }
+        Some(NaiveTime {
+            secs: hour * 3600 + min * 60 + sec,
+            nanos: nano,
+        })
+    }
+
+    pub const fn hour(&self) -> u32 {
+        self.secs / 3600
+    }
+
+    pub const fn minute(&self) -> u32 {
+        self.secs / 60 % 60
+    }
+
+    pub const fn second(&self) -> u32 {
+        self.secs % 60
+    }
+
+    pub const fn nanosecond(&self) -> u32 {
+        self.nanos
+    }
+
+    pub const fn num_seconds_from_midnight(&self) -> u32 {
+        self.secs
+    }
+
+    pub const fn with_nanosecond(&self, nano: u32) -> Option<NaiveTime> {
+        NaiveTime::from_hms_nano_opt(self.hour(), self.minute(), self.second(), nano)
+    }
+}
+
+impl fmt::Display for NaiveTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (sec, nano) = if self.nanos >= NANOS_PER_SEC {
+            (self.second() + 1, self.nanos - NANOS_PER_SEC)
+        } else {
+            (self.second(), self.nanos)
+        };
+        write!(f, "{:02}:{:02}:{:02}", self.hour(), self.minute(), sec)?;
+        if nano > 0 {
+            write!(f, ".{:09}", nano)?;
+        }
+        Ok(())
+    }
+}
+
+/// Date and time without time zone.
+#[derive(
+    Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize,
+)]
+pub struct NaiveDateTime {
+    date: NaiveDate,
+    time: NaiveTime,
+}
+
+impl NaiveDateTime {
+    pub const fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    pub const fn time(&self) -> NaiveTime {
+        self.time
+    }
+
+    /// Interpret as UTC date and time.
+    pub const fn and_utc(&self) -> UtcDateTime {
+        UtcDateTime {
+            secs: self.date.days() * SECS_PER_DAY + self.time.secs as i64,
+            nanos: self.time.nanos,
+        }
+    }
+}
+
+/// Point in time, as number of seconds and nanoseconds since
+/// the Unix epoch (1970-01-01 00:00:00 UTC).
+#[derive(
+    Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize,
+)]
+pub struct UtcDateTime {
+    secs: i64,
+    nanos: u32,
+}
+
+impl UtcDateTime {
+    pub const MAX_UTC: UtcDateTime = UtcDateTime {
+        secs: MAX_DAYS * SECS_PER_DAY + SECS_PER_DAY - 1,
+        nanos: NANOS_PER_SEC - 1,
+    };
+    pub const MIN_UTC: UtcDateTime = UtcDateTime {
+        secs: MIN_DAYS * SECS_PER_DAY,
+        nanos: 0,
+    };
+
+    /// Returns `None` when out of supported range or when `nsecs` represents
+    /// leap second not at the end of a minute.
+    pub const fn from_timestamp(secs: i64, nsecs: u32) -> Option<UtcDateTime> {
+        if secs < UtcDateTime::MIN_UTC.secs || secs > UtcDateTime::MAX_UTC.secs {
+            return None;
+        }
+        if nsecs >= 2 * NANOS_PER_SEC || (nsecs >= NANOS_PER_SEC && secs.rem_euclid(60) != 59) {
+            return None;
+        }
+        Some(UtcDateTime { secs, nanos: nsecs })
+    }
+
+    /// Seconds since the Unix epoch.
+    pub const fn timestamp(&self) -> i64 {
+        self.secs
+    }
+
+    pub const fn timestamp_subsec_millis(&self) -> u32 {
+        self.nanos / 1_000_000
+    }
+
+    pub const fn timestamp_subsec_micros(&self) -> u32 {
+        self.nanos / 1_000
+    }
+
+    pub const fn timestamp_subsec_nanos(&self) -> u32 {
+        self.nanos
+    }
+
+    pub const fn date_naive(&self) -> NaiveDate {
+        NaiveDate::from_days(self.secs.div_euclid(SECS_PER_DAY))
+    }
+
+    pub const fn time(&self) -> NaiveTime {
+        NaiveTime {
+            secs: self.secs.rem_euclid(SECS_PER_DAY) as u32,
+            nanos: self.nanos,
+        }
+    }
+
+    pub const fn naive_utc(&self) -> NaiveDateTime {
+        NaiveDateTime {
+            date: self.date_naive(),
+            time: self.time(),
+        }
+    }
+
+    pub const fn year(&self) -> i32 {
+        self.date_naive().year()
+    }
+
+    pub const fn month(&self) -> u32 {
+        self.date_naive().month()
+    }
+
+    pub const fn day(&self) -> u32 {
+        self.date_naive().day()
+    }
+
+    pub const fn hour(&self) -> u32 {
+        self.time().hour()
+    }
+
+    pub const fn minute(&self) -> u32 {
+        self.time().minute()
+    }
+
+    pub const fn second(&self) -> u32 {
+        self.time().second()
+    }
+
+    pub const fn nanosecond(&self) -> u32 {
+        self.nanos
+    }
+}
+
+impl fmt::Display for UtcDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} UTC", self.date_naive(), self.time())
+    }
+}
+
+/// UTC time zone, source of current time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Utc;
+
+impl Utc {
+    pub fn now() -> UtcDateTime {
+        let now = OffsetDateTime::now_utc();
+        UtcDateTime {
+            secs: now.unix_timestamp(),
+            nanos: now.nanosecond(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_roundtrip() {
+        for days in (MIN_DAYS..MAX_DAYS).step_by(97) {
+            let date = NaiveDate::from_days(days);
+            assert_eq!(
+                NaiveDate::from_ymd_opt(date.year(), date.month(), date.day()),
+                Some(date)
+            );
+            assert_eq!(date.days(), days);
+        }
+    }
+
+    #[test]
+    fn known_dates() {
+        assert_eq!(
+            NaiveDate::default(),
+            NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()
+        );
+        assert_eq!(
+            NaiveDate::from_days(19_782),
+            NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+        );
+        assert_eq!(
+            NaiveDate::from_days(-1),
+            NaiveDate::from_ymd_opt(1969, 12, 31).unwrap()
+        );
+        assert_eq!(NaiveDate::from_days(MIN_DAYS), NaiveDate::MIN);
+        assert_eq!(NaiveDate::from_days(MAX_DAYS), NaiveDate::MAX);
+    }
+
+    #[test]
+    fn invalid_dates() {
+        assert!(NaiveDate::from_ymd_opt(2023, 2, 29).is_none());
+        assert!(NaiveDate::from_ymd_opt(1900, 2, 29).is_none());
+        assert!(NaiveDate::from_ymd_opt(2000, 2, 29).is_some());
+        assert!(NaiveDate::from_ymd_opt(2024, 4, 31).is_none());
+        assert!(NaiveDate::from_ymd_opt(2024, 13, 1).is_none());
+        assert!(NaiveDate::from_ymd_opt(2024, 0, 1).is_none());
+        assert!(NaiveDate::from_ymd_opt(10_000, 1, 1).is_none());
+    }
+
+    #[test]
+    fn date_time_parts() {
+        let date_time = NaiveDate::from_ymd_opt(2019, 6, 5)
+            .unwrap()
+            .and_hms_nano_opt(11, 51, 27, 848_757_123)
+            .unwrap()
+            .and_utc();
+        assert_eq!(date_time.timestamp(), 1_559_735_487);
+        assert_eq!(
+            (date_time.year(), date_time.month(), date_time.day()),
+            (2019, 6, 5)
+        );
+        assert_eq!(
+            (date_time.hour(), date_time.minute(), date_time.second()),
+            (11, 51, 27)
+        );
+        assert_eq!(date_time.nanosecond(), 848_757_123);
+        assert_eq!(date_time.timestamp_subsec_millis(), 848);
+        assert_eq!(
+            UtcDateTime::from_timestamp(1_559_735_487, 848_757_123),
+            Some(date_time)
+        );
+    }
+
+    #[test]
+    fn leap_second() {
+        let time = NaiveTime::from_hms_nano_opt(23, 59, 59, 1_500_000_000).unwrap();
+        assert_eq!(time.to_string(), "23:59:60.500000000");
+        assert!(NaiveTime::from_hms_nano_opt(23, 59, 58, 1_500_000_000).is_none());
+        assert!(UtcDateTime::from_timestamp(59, 1_000_000_000).is_some());
+        assert!(UtcDateTime::from_timestamp(58, 1_000_000_000).is_none());
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert!(UtcDateTime::from_timestamp(UtcDateTime::MAX_UTC.timestamp() + 1, 0).is_none());
+        assert!(UtcDateTime::from_timestamp(UtcDateTime::MIN_UTC.timestamp() - 1, 0).is_none());
+        assert_eq!(UtcDateTime::MIN_UTC.date_naive(), NaiveDate::MIN);
+        assert_eq!(UtcDateTime::MAX_UTC.date_naive(), NaiveDate::MAX);
+    }
+}
@@ -0,0 +1,275 @@
+//! Minimal decimal type, used when `rust_decimal` feature is disabled.
+//!
+//! Only the subset of `rust_decimal` API needed by FIX basic types is
+//! provided, with the same method names. Like in `rust_decimal`, value is
+//! 96-bit integer mantissa scaled by power of 10 between 0 and 28, and
+//! values differing only in trailing zeros (e.g. `1.5` and `1.50`) are
+//! equal.
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+const MAX_MANTISSA: i128 = (1 << 96) - 1;
+const MAX_SCALE: u32 = 28;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum DecimalError {
+    #[error("value exceeds 96-bit mantissa")]
+    ExceedsMaximumPossibleValue,
+    #[error("scale {0} exceeds maximum of 28")]
+    ScaleExceedsMaximumPrecision(u32),
+    #[error("invalid decimal number")]
+    Invalid,
+}
+
+/// Decimal number, `mantissa * 10^-scale`.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+impl Decimal {
+    pub const MAX: Decimal = Decimal {
+        mantissa: MAX_MANTISSA,
+        scale: 0,
+    };
+    pub const MIN: Decimal = Decimal {
+        mantissa: -MAX_MANTISSA,
+        scale: 0,
+    };
+    pub const ZERO: Decimal = Decimal {
+        mantissa: 0,
+        scale: 0,
+    };
+
+    /// Panics if `scale` exceeds 28.
+    pub const fn new(num: i64, scale: u32) -> Decimal {
+        assert!(scale <= MAX_SCALE, "scale exceeds maximum of 28");
+        Decimal {
+            mantissa: num as i128,
+            scale,
+        }
+    }
+
+    pub const fn try_from_i128_with_scale(num: i128, scale: u32) -> Result<Decimal, DecimalError> {
+        if scale > MAX_SCALE {
+            Err(DecimalError::ScaleExceedsMaximumPrecision(scale))
+        } else if num > MAX_MANTISSA || num < -MAX_MANTISSA {
+            Err(DecimalError::ExceedsMaximumPossibleValue)
+        } else {
+            Ok(Decimal {
+                mantissa: num,
+                scale,
+            })
+        }
+    }
+
+    pub const fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    pub const fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    pub const fn is_sign_negative(&self) -> bool {
+        self.mantissa < 0
+    }
+
+    pub const fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    /// The same value with trailing zeros removed.
+    pub const fn normalize(&self) -> Decimal {
+        let mut mantissa = self.mantissa;
+        let mut scale = self.scale;
+        if mantissa == 0 {
+            scale = 0;
+        }
+        while scale > 0 && mantissa % 10 == 0 {
+            mantissa /= 10;
+            scale -= 1;
+        }
+        Decimal { mantissa, scale }
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Decimal) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Decimal) -> Ordering {
+        // Rescale value with smaller scale to the scale of the other one
+        let (lhs, rhs) = (self.normalize(), other.normalize());
+        if lhs.scale == rhs.scale {
+            return lhs.mantissa.cmp(&rhs.mantissa);
+        }
+        let (low, high, swapped) = if lhs.scale < rhs.scale {
+            (lhs, rhs, false)
+        } else {
+            (rhs, lhs, true)
+        };
+        let ordering = match 10i128
+            .checked_pow(high.scale - low.scale)
+            .and_then(|factor| low.mantissa.checked_mul(factor))
+        {
+            Some(rescaled) => rescaled.cmp(&high.mantissa),
+            // Magnitude of rescaled value exceeds any 96-bit mantissa
+            None => low.mantissa.cmp(&0),
+        };
+        if swapped {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let normalized = self.normalize();
+        normalized.mantissa.hash(state);
+        normalized.scale.hash(state);
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($int:ty),*) => {
+        $(
+            impl From<$int> for Decimal {
+                fn from(value: $int) -> Decimal {
+                    Decimal {
+                        mantissa: value as i128,
+                        scale: 0,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl FromStr for Decimal {
+    type Err = DecimalError;
+
+    fn from_str(s: &str) -> Result<Decimal, DecimalError> {
+        let (negative, digits) = match s.as_bytes() {
+            [b'-', digits @ ..] => (true, digits),
+            [b'+', digits @ ..] => (false, digits),
+            digits => (false, digits),
+        };
+        if digits.is_empty() || digits == b"." {
+            return Err(DecimalError::Invalid);
+        }
+        let mut mantissa: i128 = 0;
+        let mut scale = None;
+        for byte in digits {
+            match byte {
+                b'0'..=b'9' => {
+                    mantissa = mantissa
+                        .checked_mul(10)
+                        .and_then(|mantissa| mantissa.checked_add(i128::from(byte - b'0')))
+                        .ok_or(DecimalError::ExceedsMaximumPossibleValue)?;
+                    if let Some(scale) = scale.as_mut() {
+                        *scale += 1;
+                    }
+                }
+                b'.' if scale.is_none() => scale = Some(0),
+                _ => return Err(DecimalError::Invalid),
+            }
+        }
+        let mantissa = if negative { -mantissa } else { mantissa };
+        Decimal::try_from_i128_with_scale(mantissa, scale.unwrap_or(0))
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        let sign = if self.is_sign_negative() { "-" } else { "" };
+        if scale == 0 {
+            write!(f, "{sign}{digits}")
+        } else if digits.len() > scale {
+            let (integer, fraction) = digits.split_at(digits.len() - scale);
+            write!(f, "{sign}{integer}.{fraction}")
+        } else {
+            write!(f, "{sign}0.{digits:0>scale$}")
+        }
+    }
+}
+
+impl fmt::Debug for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::{Decimal, DecimalError};
+
+    #[test]
+    fn parse_and_display() {
+        for (input, output) in [
+            ("0", "0"),
+            ("97", "97"),
+            ("97.", "97"),
+            ("97.0347", "97.0347"),
+            ("-1.50", "-1.50"),
+            ("0.005", "0.005"),
+            ("-.5", "-0.5"),
+            (
+                "79228162514264337593543950335",
+                "79228162514264337593543950335",
+            ),
+        ] {
+            assert_eq!(Decimal::from_str(input).unwrap().to_string(), output);
+        }
+        for input in ["", "-", ".", "1.2.3", "1e5", "12a"] {
+            assert_eq!(Decimal::from_str(input), Err(DecimalError::Invalid));
+        }
+        assert_eq!(
+            Decimal::from_str("79228162514264337593543950336"),
+            Err(DecimalError::ExceedsMaximumPossibleValue)
+        );
+        assert_eq!(
+            Decimal::try_from_i128_with_scale(1, 29),
+            Err(DecimalError::ScaleExceedsMaximumPrecision(29))
+        );
+    }
+
+    #[test]
+    fn compared_by_value() {
+        assert_eq!(Decimal::new(15, 1), Decimal::new(150, 2));
+        assert_eq!(Decimal::new(0, 5), Decimal::ZERO);
+        assert_eq!(Decimal::new(150, 2).normalize().to_string(), "1.5");
+        assert!(Decimal::new(15, 1) < Decimal::new(151, 2));
+        assert!(Decimal::new(-2, 0) < Decimal::new(-15, 1));
+        assert!(Decimal::MAX > Decimal::new(1, 28));
+        assert!(Decimal::MIN < Decimal::new(-1, 28));
+        assert!(Decimal::new(1, 28) > Decimal::ZERO);
+    }
+}
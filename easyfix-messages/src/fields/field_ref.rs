@@ -0,0 +1,105 @@
+use std::fmt;
+
+use super::{
+    Amt, Boolean, Char, Country, Currency, Exchange, FixStr, FixString, Float, Int, Language,
+    Length, LocalMktDate, NumInGroup, Percentage, Price, PriceOffset, Qty, SeqNum, TagNum,
+    UtcDateOnly, UtcTimeOnly, UtcTimestamp,
+};
+
+/// Borrowed value of field, passed to [`Fields::visit_fields`] visitor.
+///
+/// Variants are named after FIX types, fields with enumerated values
+/// are passed as [`FieldRef::Enum`] or [`FieldRef::EnumValues`].
+#[derive(Clone, Copy, Debug)]
+pub enum FieldRef<'a> {
+    Amt(Amt),
+    Boolean(Boolean),
+    Char(Char),
+    Country(Country),
+    Currency(Currency),
+    Data(&'a [u8]),
+    Exchange(&'a Exchange),
+    Float(Float),
+    Int(Int),
+    Language(&'a Language),
+    Length(Length),
+    LocalMktDate(&'a LocalMktDate),
+    MonthYear(&'a [u8]),
+    MultipleCharValue(&'a [Char]),
+    MultipleStringValue(&'a [FixString]),
+    NumInGroup(NumInGroup),
+    Percentage(Percentage),
+    Price(Price),
+    PriceOffset(PriceOffset),
+    Qty(Qty),
+    SeqNum(SeqNum),
+    String(&'a FixStr),
+    TzTimeOnly(&'a [u8]),
+    TzTimestamp(&'a [u8]),
+    UtcDateOnly(&'a UtcDateOnly),
+    UtcTimeOnly(&'a UtcTimeOnly),
+    UtcTimestamp(&'a UtcTimestamp),
+    /// String field holding UTF-8 text.
+    Utf8String(&'a str),
+    XmlData(&'a [u8]),
+    /// Enumerated value, as it's serialized.
+    Enum(&'a FixStr),
+    /// Enumerated values of MultipleCharValue/MultipleStringValue field.
+    EnumValues(&'a dyn EnumValues),
+}
+
+/// Values of MultipleCharValue/MultipleStringValue field with enumerated
+/// values, see [`FieldRef::EnumValues`].
+pub trait EnumValues: fmt::Debug {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Value at `index`, as it's serialized.
+    fn get(&self, index: usize) -> Option<&'static FixStr>;
+}
+
+impl<T> EnumValues for Vec<T>
+where
+    T: Copy + fmt::Debug + Into<&'static [u8]>,
+{
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn get(&self, index: usize) -> Option<&'static FixStr> {
+        self.as_slice().get(index).map(|value| {
+            // SAFETY: enumerated values are ASCII, as defined in dictionary
+            unsafe { FixStr::from_ascii_unchecked((*value).into()) }
+        })
+    }
+}
+
+impl dyn EnumValues + '_ {
+    pub fn iter(&self) -> impl Iterator<Item = &'static FixStr> + '_ {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+}
+
+/// Access to fields of messages, groups and components without
+/// serializing them, e.g. for loggers, hashers or converters.
+pub trait Fields {
+    /// Call `visitor` with tag and value of every field with value, in
+    /// order of serialization. NumInGroup field is followed by fields of
+    /// all group entries.
+    ///
+    /// BodyLength<9> and CheckSum<10> are skipped, as they are calculated
+    /// by serializer.
+    fn visit_fields<'a>(&'a self, visitor: &mut dyn FnMut(TagNum, FieldRef<'a>));
+
+    /// Call `visitor` for every field with value, see
+    /// [`Fields::visit_fields`].
+    fn for_each_field<'a>(&'a self, mut visitor: impl FnMut(TagNum, FieldRef<'a>))
+    where
+        Self: Sized,
+    {
+        self.visit_fields(&mut visitor);
+    }
+}
@@ -0,0 +1,40 @@
+use super::{enum_metadata, FixStr, TagNum};
+
+/// Field with enumerated values, as defined in dictionary, see
+/// [`enum_metadata`](super::enum_metadata).
+#[derive(Clone, Copy, Debug)]
+pub struct EnumMetadata {
+    /// Field name, e.g. `OrdStatus`
+    pub name: &'static str,
+    pub tag: TagNum,
+    /// Values in dictionary order
+    pub values: &'static [EnumValueMetadata],
+}
+
+/// Enumerated value of field, see [`EnumMetadata`].
+#[derive(Clone, Copy, Debug)]
+pub struct EnumValueMetadata {
+    /// Value as it's serialized, e.g. `1`
+    pub value: &'static FixStr,
+    /// Variant of generated enum, e.g. `PartiallyFilled`
+    pub variant: &'static str,
+    /// Human-readable name, e.g. `Partially filled`, the same as returned
+    /// by `display_name()` of generated enum
+    pub display_name: &'static str,
+}
+
+impl EnumMetadata {
+    /// Metadata of value as it's serialized.
+    pub fn value(&self, value: &FixStr) -> Option<&'static EnumValueMetadata> {
+        self.values.iter().find(|metadata| metadata.value == value)
+    }
+}
+
+/// Metadata of field `tag`, `None` if field has no enumerated values.
+pub fn enum_metadata_by_tag(tag: TagNum) -> Option<&'static EnumMetadata> {
+    let metadata = enum_metadata();
+    metadata
+        .binary_search_by_key(&tag, |metadata| metadata.tag)
+        .ok()
+        .map(|index| &metadata[index])
+}
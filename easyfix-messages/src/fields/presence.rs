@@ -0,0 +1,82 @@
+use super::TagNum;
+
+/// Bitmap of fields with value, returned by generated `presence()`
+/// of messages, groups and components.
+///
+/// Bit `i` stands for `i`-th tag of `PRESENCE_TAGS` of the struct,
+/// so set fields can be walked without matching every `Option`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FieldPresence<const N: usize>([u64; N]);
+
+impl<const N: usize> FieldPresence<N> {
+    pub const fn new() -> FieldPresence<N> {
+        FieldPresence([0; N])
+    }
+
+    #[inline]
+    pub fn set(&mut self, index: usize) {
+        self.0[index / 64] |= 1 << (index % 64);
+    }
+
+    #[inline]
+    pub fn get(&self, index: usize) -> bool {
+        self.0
+            .get(index / 64)
+            .is_some_and(|word| word & (1 << (index % 64)) != 0)
+    }
+
+    /// Number of fields with value.
+    pub fn count(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|word| *word == 0)
+    }
+
+    /// Indices of set bits, in increasing order.
+    pub fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(i, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+
+    /// Tags of fields with value, `tags` is `PRESENCE_TAGS` of the struct.
+    pub fn tags<'a>(&'a self, tags: &'a [TagNum]) -> impl Iterator<Item = TagNum> + 'a {
+        self.indices().map(|index| tags[index])
+    }
+}
+
+impl<const N: usize> Default for FieldPresence<N> {
+    fn default() -> FieldPresence<N> {
+        FieldPresence::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FieldPresence;
+
+    #[test]
+    fn bits_across_words() {
+        let mut presence = FieldPresence::<3>::new();
+        assert!(presence.is_empty());
+        for index in [0, 63, 64, 130] {
+            presence.set(index);
+        }
+        assert_eq!(presence.count(), 4);
+        assert!(presence.get(64));
+        assert!(!presence.get(65));
+        assert!(!presence.get(1000));
+        assert_eq!(presence.indices().collect::<Vec<_>>(), [0, 63, 64, 130]);
+        assert_eq!(presence.tags(&[1; 192]).collect::<Vec<_>>(), [1, 1, 1, 1]);
+    }
+}
@@ -0,0 +1,198 @@
+//! Stable content hash of messages, e.g. for deduplication of resent
+//! messages or audit chains.
+//!
+//! Fingerprint is computed from field values visited with
+//! [`Fields::visit_fields`], not from serialized message, so it doesn't
+//! depend on serialization details like timestamp precision, trailing
+//! zeros of decimals or empty strings policy. Volatile header fields
+//! (e.g. MsgSeqNum<34> or SendingTime<52>), which differ between
+//! transmissions of the same message, are excluded by default.
+//!
+//! Hash is FNV-1a (128-bit), stable between builds and platforms, but
+//! not cryptographic, so it doesn't protect against deliberate collisions.
+
+use std::{collections::HashSet, fmt};
+
+#[cfg(feature = "chrono")]
+use chrono::{Datelike, Timelike};
+
+use crate::fields::{FieldRef, Fields, NaiveDate, TagNum};
+
+/// Fields excluded from fingerprint by default: BodyLength<9>,
+/// CheckSum<10>, MsgSeqNum<34>, PossDupFlag<43>, SendingTime<52>,
+/// PossResend<97>, OrigSendingTime<122> and LastMsgSeqNumProcessed<369>.
+pub const VOLATILE_TAGS: &[TagNum] = &[9, 10, 34, 43, 52, 97, 122, 369];
+
+const FNV_OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+const FNV_PRIME: u128 = 0x0000000001000000000000000000013b;
+
+/// Content hash of message, see [module documentation](self).
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Fingerprint(u128);
+
+impl Fingerprint {
+    pub const fn from_u128(value: u128) -> Fingerprint {
+        Fingerprint(value)
+    }
+
+    pub const fn as_u128(&self) -> u128 {
+        self.0
+    }
+}
+
+/// Lowercase hex, 32 digits.
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+/// Fingerprint calculation with configurable set of excluded fields.
+#[derive(Clone, Debug)]
+pub struct Fingerprinter {
+    excluded_tags: HashSet<TagNum>,
+}
+
+impl Default for Fingerprinter {
+    /// Fingerprinter excluding [`VOLATILE_TAGS`].
+    fn default() -> Fingerprinter {
+        Fingerprinter::with_excluded_tags(VOLATILE_TAGS.iter().copied())
+    }
+}
+
+impl Fingerprinter {
+    pub fn new() -> Fingerprinter {
+        Fingerprinter::default()
+    }
+
+    /// Fingerprinter excluding exactly given `tags`, replacing default
+    /// [`VOLATILE_TAGS`].
+    pub fn with_excluded_tags(tags: impl IntoIterator<Item = TagNum>) -> Fingerprinter {
+        Fingerprinter {
+            excluded_tags: tags.into_iter().collect(),
+        }
+    }
+
+    /// Exclude also field `tag`, e.g. venue specific transmission
+    /// timestamp. Fields of group entries are excluded too.
+    pub fn exclude(mut self, tag: TagNum) -> Fingerprinter {
+        self.excluded_tags.insert(tag);
+        self
+    }
+
+    /// Include field `tag` excluded by default.
+    pub fn include(mut self, tag: TagNum) -> Fingerprinter {
+        self.excluded_tags.remove(&tag);
+        self
+    }
+
+    /// Fingerprint of message, group or component.
+    ///
+    /// Every field is hashed as its tag and canonical form of its value,
+    /// NumInGroup fields (hashed with number of entries) delimit group
+    /// entries, so equal values moved between entries change fingerprint.
+    pub fn fingerprint<F: Fields + ?Sized>(&self, fields: &F) -> Fingerprint {
+        let mut hasher = Hasher(FNV_OFFSET_BASIS);
+        let mut value = Vec::new();
+        fields.visit_fields(&mut |tag, field| {
+            if self.excluded_tags.contains(&tag) {
+                return;
+            }
+            value.clear();
+            canonical_value(&mut value, field);
+            hasher.write(&tag.to_le_bytes());
+            hasher.write(&(value.len() as u32).to_le_bytes());
+            hasher.write(&value);
+        });
+        Fingerprint(hasher.0)
+    }
+}
+
+struct Hasher(u128);
+
+impl Hasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u128::from(*byte);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+}
+
+fn push_int(output: &mut Vec<u8>, value: impl itoa::Integer) {
+    output.extend_from_slice(itoa::Buffer::new().format(value).as_bytes());
+}
+
+fn push_date(output: &mut Vec<u8>, date: &NaiveDate) {
+    push_int(output, date.year());
+    output.push(b'-');
+    push_int(output, date.month());
+    output.push(b'-');
+    push_int(output, date.day());
+}
+
+fn push_values<'a>(output: &mut Vec<u8>, values: impl Iterator<Item = &'a [u8]>) {
+    for (i, value) in values.enumerate() {
+        if i > 0 {
+            output.push(b' ');
+        }
+        output.extend_from_slice(value);
+    }
+}
+
+/// Value in form independent of serialization details: decimals are
+/// normalized and timestamps written with nanoseconds, regardless of
+/// their precision.
+fn canonical_value(output: &mut Vec<u8>, value: FieldRef<'_>) {
+    match value {
+        FieldRef::Amt(value)
+        | FieldRef::Float(value)
+        | FieldRef::Percentage(value)
+        | FieldRef::Price(value)
+        | FieldRef::PriceOffset(value)
+        | FieldRef::Qty(value) => {
+            output.extend_from_slice(value.normalize().to_string().as_bytes())
+        }
+        FieldRef::Boolean(value) => output.push(if value { b'Y' } else { b'N' }),
+        FieldRef::Char(value) => output.push(value),
+        FieldRef::Country(value) => output.extend_from_slice(value.to_bytes()),
+        FieldRef::Currency(value) => output.extend_from_slice(value.to_bytes()),
+        FieldRef::Exchange(value) => output.extend_from_slice(value),
+        FieldRef::Int(value) => push_int(output, value),
+        FieldRef::Language(value) => output.extend_from_slice(value),
+        FieldRef::Length(value) => push_int(output, value),
+        FieldRef::LocalMktDate(value) | FieldRef::UtcDateOnly(value) => push_date(output, value),
+        FieldRef::MultipleCharValue(values) => {
+            push_values(output, values.iter().map(std::slice::from_ref))
+        }
+        FieldRef::MultipleStringValue(values) => {
+            push_values(output, values.iter().map(|value| value.as_bytes()))
+        }
+        FieldRef::NumInGroup(value) => push_int(output, value),
+        FieldRef::SeqNum(value) => push_int(output, value),
+        FieldRef::String(value) | FieldRef::Enum(value) => {
+            output.extend_from_slice(value.as_bytes())
+        }
+        FieldRef::Data(value)
+        | FieldRef::MonthYear(value)
+        | FieldRef::TzTimeOnly(value)
+        | FieldRef::TzTimestamp(value)
+        | FieldRef::XmlData(value) => output.extend_from_slice(value),
+        FieldRef::UtcTimeOnly(value) => {
+            let time = value.timestamp();
+            push_int(output, time.num_seconds_from_midnight());
+            output.push(b'.');
+            push_int(output, time.nanosecond());
+        }
+        FieldRef::UtcTimestamp(value) => {
+            let timestamp = value.timestamp();
+            push_int(output, timestamp.timestamp());
+            output.push(b'.');
+            push_int(output, timestamp.timestamp_subsec_nanos());
+        }
+        FieldRef::Utf8String(value) => output.extend_from_slice(value.as_bytes()),
+        FieldRef::EnumValues(values) => {
+            push_values(output, values.iter().map(|value| value.as_bytes()))
+        }
+    }
+}
@@ -1,2 +1,13 @@
+//! Repeating groups.
+//!
+//! Each repeating group component from the dictionary is generated once,
+//! as a struct named after the component (e.g. [`Parties`], [`MsgTypeGrp`]),
+//! and shared by all messages and groups using it, so values can be moved
+//! between messages without conversion.
+//!
+//! When code is generated with `flatten_components` disabled (for all
+//! components or selected ones with `flatten_component`), structs of
+//! non-repeating components (e.g. `Instrument`) live here as well.
+
 #![allow(unused_assignments)]
 include!(concat!(env!("OUT_DIR"), "/generated_groups.rs"));
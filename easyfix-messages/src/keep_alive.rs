@@ -0,0 +1,174 @@
+use std::ops::Range;
+
+use crate::{
+    fields::{FixStr, MsgType, TagNum, UtcTimestamp},
+    messages::{FixtMessage, Header, Heartbeat, Message, Trailer},
+    serializer::Serializer,
+};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum KeepAliveTemplateError {
+    #[error("tag {0} not found in serialized header")]
+    TagNotFound(TagNum),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Slot {
+    MsgSeqNum,
+    SendingTime,
+}
+
+/// Find range of `tag` field value, starting at `from`.
+fn find_value(
+    buf: &[u8],
+    from: usize,
+    tag: TagNum,
+) -> Result<Range<usize>, KeepAliveTemplateError> {
+    let pattern = format!("\x01{tag}=");
+    let start = buf[from..]
+        .windows(pattern.len())
+        .position(|window| window == pattern.as_bytes())
+        .map(|pos| from + pos + pattern.len())
+        .ok_or(KeepAliveTemplateError::TagNotFound(tag))?;
+    let len = buf[start..]
+        .iter()
+        .position(|b| *b == b'\x01')
+        .ok_or(KeepAliveTemplateError::TagNotFound(tag))?;
+    Ok(start..start + len)
+}
+
+/// Preallocated serialization of Heartbeat<0> and TestRequest<1> messages.
+///
+/// Header is serialized once, when template is created. Later only
+/// MsgType<35>, MsgSeqNum<34> and SendingTime<52> are written at cached
+/// positions, followed by message body, BodyLength<9> and CheckSum<10> are
+/// computed as usual.
+#[derive(Clone, Debug)]
+pub struct KeepAliveTemplate {
+    header: Header,
+    begin_string: Vec<u8>,
+    segments: Vec<(Vec<u8>, Slot)>,
+    tail: Vec<u8>,
+}
+
+impl KeepAliveTemplate {
+    /// Create template for messages with given `header`.
+    ///
+    /// Values of MsgType<35>, MsgSeqNum<34> and SendingTime<52> are ignored.
+    pub fn new(mut header: Header) -> Result<KeepAliveTemplate, KeepAliveTemplateError> {
+        header.msg_type = MsgType::Heartbeat;
+        header.body_length = 0;
+        header.msg_seq_num = 0;
+        header.sending_time = UtcTimestamp::MIN_UTC;
+
+        let buf = FixtMessage {
+            header: Box::new(header.clone()),
+            body: Box::new(Message::Heartbeat(Heartbeat::default())),
+            trailer: Box::new(Trailer::default()),
+        }
+        .serialize();
+
+        let body_length = find_value(&buf, 0, 9)?;
+        let begin_string = buf[..body_length.start - 2].to_vec();
+        let header_start = find_value(&buf, body_length.end, 35)?.end + 1;
+        // CheckSum<10> is always the last field and has fixed length
+        let checksum_start = buf.len() - b"10=000\x01".len();
+
+        let mut slots = [
+            (find_value(&buf, header_start - 1, 34)?, Slot::MsgSeqNum),
+            (find_value(&buf, header_start - 1, 52)?, Slot::SendingTime),
+        ];
+        slots.sort_by_key(|(value, _)| value.start);
+
+        let mut segments = Vec::with_capacity(slots.len());
+        let mut cursor = header_start;
+        for (value, slot) in slots {
+            segments.push((buf[cursor..value.start].to_vec(), slot));
+            cursor = value.end;
+        }
+
+        Ok(KeepAliveTemplate {
+            header,
+            begin_string,
+            segments,
+            tail: buf[cursor..checksum_start].to_vec(),
+        })
+    }
+
+    /// Serialize `msg` using template.
+    ///
+    /// Returns `None` when message is neither Heartbeat<0> nor TestRequest<1>
+    /// or when it has header or trailer different than the template one
+    /// or empty TestReqID<112>.
+    /// Such message has to be serialized with [`FixtMessage::serialize`].
+    pub fn serialize(&mut self, msg: &FixtMessage) -> Option<Vec<u8>> {
+        let test_req_id = match &*msg.body {
+            Message::Heartbeat(heartbeat) => heartbeat.test_req_id.as_deref(),
+            Message::TestRequest(test_request) => Some(&*test_request.test_req_id),
+            _ => return None,
+        };
+        // Empty value is handled according to serializer policy
+        if test_req_id.is_some_and(FixStr::is_empty) {
+            return None;
+        }
+
+        #[allow(clippy::needless_update)]
+        if *msg.trailer
+            != (Trailer {
+                check_sum: msg.trailer.check_sum.clone(),
+                ..Default::default()
+            })
+        {
+            return None;
+        }
+
+        // Only fields written at cached positions may differ
+        self.header.msg_type = msg.header.msg_type;
+        self.header.body_length = msg.header.body_length;
+        self.header.msg_seq_num = msg.header.msg_seq_num;
+        self.header.sending_time = msg.header.sending_time;
+        if *msg.header != self.header {
+            return None;
+        }
+
+        match &*msg.body {
+            Message::Heartbeat(heartbeat) => Some(
+                self.serialize_parts(&msg.header, |serializer| heartbeat.serialize(serializer)),
+            ),
+            Message::TestRequest(test_request) => Some(
+                self.serialize_parts(&msg.header, |serializer| test_request.serialize(serializer)),
+            ),
+            _ => None,
+        }
+    }
+
+    fn serialize_parts(
+        &self,
+        header: &Header,
+        serialize_body: impl FnOnce(&mut Serializer),
+    ) -> Vec<u8> {
+        let mut serializer = Serializer::new();
+        serializer
+            .output_mut()
+            .extend_from_slice(&self.begin_string);
+        serializer.serialize_body_len();
+        serializer.output_mut().extend_from_slice(b"35=");
+        serializer
+            .output_mut()
+            .extend_from_slice(header.msg_type.as_fix_str().as_bytes());
+        serializer.output_mut().push(b'\x01');
+        for (literal, slot) in &self.segments {
+            serializer.output_mut().extend_from_slice(literal);
+            match slot {
+                Slot::MsgSeqNum => serializer.serialize_seq_num(&header.msg_seq_num),
+                Slot::SendingTime => serializer.serialize_utc_timestamp(&header.sending_time),
+            }
+        }
+        serializer.output_mut().extend_from_slice(&self.tail);
+        // Body is serialized as usual, so fields added to message
+        // definition are not lost
+        serialize_body(&mut serializer);
+        serializer.serialize_checksum();
+        serializer.take()
+    }
+}
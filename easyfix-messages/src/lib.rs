@@ -1,7 +1,24 @@
+#[cfg(not(any(feature = "chrono", feature = "time")))]
+compile_error!("either `chrono` or `time` feature must be enabled");
+
+pub mod buffer_pool;
+pub mod codec;
+#[cfg(test)]
+mod codec_tests;
+#[cfg(feature = "compression")]
+pub mod compression;
+pub mod convert;
 pub mod country;
 pub mod currency;
 pub mod deserializer;
 pub mod fields;
+pub mod fingerprint;
 pub mod groups;
+pub mod keep_alive;
 pub mod messages;
 pub mod serializer;
+#[cfg(feature = "stream")]
+pub mod stream;
+pub mod tag_value;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
@@ -1,3 +1,5 @@
+use crate::fingerprint::{Fingerprint, Fingerprinter};
+
 /// Message category
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum MsgCat {
@@ -7,4 +9,64 @@ pub enum MsgCat {
     App,
 }
 
+/// Application message of type not defined in dictionary, deserialized
+/// only when `Deserializer::with_other_msg_types()` is set.
+///
+/// Body fields are kept as strings, in order of appearance. As their
+/// types are unknown, Data/XmlData fields containing SOH characters
+/// and repeating groups structure are not recognized.
+#[derive(Clone, Debug, Hash, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct OtherMessage {
+    pub msg_type: MsgTypeCode,
+    pub fields: Vec<(TagNum, FixString)>,
+}
+
+impl OtherMessage {
+    pub const MSG_CAT: MsgCat = MsgCat::App;
+
+    pub const fn msg_type(&self) -> MsgType {
+        MsgType::Other(self.msg_type)
+    }
+
+    pub const fn msg_cat(&self) -> MsgCat {
+        Self::MSG_CAT
+    }
+
+    /// Value of the first occurrence of field `tag`.
+    pub fn field(&self, tag: TagNum) -> Option<&FixStr> {
+        self.fields
+            .iter()
+            .find(|(field_tag, _)| *field_tag == tag)
+            .map(|(_, value)| value.as_ref())
+    }
+
+    pub(crate) fn serialize(&self, serializer: &mut Serializer) {
+        for (tag, value) in &self.fields {
+            serializer.serialize_tag_num(tag);
+            serializer.output_mut().push(b'=');
+            serializer.serialize_string(value);
+            serializer.output_mut().push(b'\x01');
+        }
+    }
+}
+
+impl FixtMessage {
+    /// Content hash of message excluding volatile fields (e.g.
+    /// SendingTime<52>), equal for every transmission of the same message,
+    /// see [`crate::fingerprint`].
+    pub fn fingerprint(&self) -> Fingerprint {
+        Fingerprinter::default().fingerprint(self)
+    }
+}
+
+impl fields::Fields for OtherMessage {
+    fn visit_fields<'a>(&'a self, visitor: &mut dyn FnMut(TagNum, fields::FieldRef<'a>)) {
+        for (tag, value) in &self.fields {
+            visitor(*tag, fields::FieldRef::String(value));
+        }
+    }
+}
+
 include!(concat!(env!("OUT_DIR"), "/generated_messages.rs"));
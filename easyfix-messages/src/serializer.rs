@@ -1,5 +1,7 @@
-use std::io::Write;
+use std::{cell::Cell, io::Write};
 
+#[cfg(feature = "chrono")]
+use chrono::{Datelike, Timelike};
 use tracing::warn;
 
 use crate::fields::basic_types::*;
@@ -20,7 +22,139 @@ const MAX_BODY_LEN_DIGITS: usize = if MAX_MSG_SIZE < 10000 {
     panic!("MAX_MSG_SIZE too big");
 };
 
+const TIMESTAMP_PREFIX_LEN: usize = b"YYYYMMDD-HH:MM:".len();
+
+thread_local! {
+    /// Formatted `YYYYMMDD-HH:MM:` prefix of recently serialized timestamp
+    /// and its Unix time in minutes.
+    ///
+    /// Timestamps of consecutive messages usually differ only in seconds
+    /// and fractions of seconds, so date, hour and minute segments are
+    /// formatted once per minute.
+    static TIMESTAMP_PREFIX: Cell<(i64, [u8; TIMESTAMP_PREFIX_LEN])> =
+        const { Cell::new((i64::MIN, [0; TIMESTAMP_PREFIX_LEN])) };
+}
+
+fn push_digits(output: &mut Vec<u8>, value: u32, width: u32) {
+    for exp in (0..width).rev() {
+        output.push(b'0' + (value / 10u32.pow(exp) % 10) as u8);
+    }
+}
+
+/// Append year zero-padded to 4 digits, years outside 0-9999 are prefixed
+/// with sign (same as `%Y` of `chrono`).
+fn push_year(output: &mut Vec<u8>, year: i32) {
+    if (0..=9999).contains(&year) {
+        push_digits(output, year as u32, 4);
+    } else {
+        write!(output, "{:+05}", year).expect("year serialization failed");
+    }
+}
+
+pub(crate) fn push_date(output: &mut Vec<u8>, year: i32, month: u32, day: u32) {
+    push_year(output, year);
+    push_digits(output, month, 2);
+    push_digits(output, day, 2);
+}
+
+/// Append `HH:MM:SS`, return nanoseconds of the second.
+///
+/// Leap second (represented as nanoseconds overflow) is written as `60`.
+fn push_time(output: &mut Vec<u8>, hour: u32, minute: u32, second: u32, nanos: u32) -> u32 {
+    let (second, nanos) = if nanos >= 1_000_000_000 {
+        (second + 1, nanos - 1_000_000_000)
+    } else {
+        (second, nanos)
+    };
+    push_digits(output, hour, 2);
+    output.push(b':');
+    push_digits(output, minute, 2);
+    output.push(b':');
+    push_digits(output, second, 2);
+    nanos
+}
+
+/// Append `.` and fraction of second with `precision` digits, nothing
+/// in case of zero precision.
+fn push_fraction(output: &mut Vec<u8>, nanos: u32, precision: u32) {
+    if precision > 0 {
+        output.push(b'.');
+        push_digits(output, nanos / 10u32.pow(9 - precision), precision);
+    }
+}
+
+fn push_timestamp_prefix(output: &mut Vec<u8>, timestamp: &UtcDateTime) {
+    push_date(output, timestamp.year(), timestamp.month(), timestamp.day());
+    output.push(b'-');
+    push_digits(output, timestamp.hour(), 2);
+    output.push(b':');
+    push_digits(output, timestamp.minute(), 2);
+    output.push(b':');
+}
+
+/// Append UTCTimestamp in `YYYYMMDD-HH:MM:SS[.sss*]` format, with number
+/// of fraction digits set by timestamp precision.
+pub(crate) fn push_utc_timestamp(output: &mut Vec<u8>, input: &UtcTimestamp) {
+    let timestamp = input.timestamp();
+    if (0..=9999).contains(&timestamp.year()) {
+        let minute = timestamp.timestamp().div_euclid(60);
+        let prefix = TIMESTAMP_PREFIX.with(|cache| match cache.get() {
+            (cached_minute, prefix) if cached_minute == minute => prefix,
+            _ => {
+                let mut prefix = Vec::with_capacity(TIMESTAMP_PREFIX_LEN);
+                push_timestamp_prefix(&mut prefix, &timestamp);
+                let prefix = prefix.try_into().expect("timestamp prefix length");
+                cache.set((minute, prefix));
+                prefix
+            }
+        });
+        output.extend_from_slice(&prefix);
+    } else {
+        push_timestamp_prefix(output, &timestamp);
+    }
+
+    // Leap second is represented as nanoseconds overflow
+    let nanos = timestamp.nanosecond();
+    let (second, nanos) = if nanos >= 1_000_000_000 {
+        (timestamp.second() + 1, nanos - 1_000_000_000)
+    } else {
+        (timestamp.second(), nanos)
+    };
+    push_digits(output, second, 2);
+    push_fraction(output, nanos, input.precision() as u32);
+}
+
 // TODO: SerializeError: Empty Vec/Group, `0` on SeqNum,TagNum,NumInGroup,Length
+#[derive(Clone, Copy, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum SerializeError {
+    #[error("empty value (tag={0})")]
+    EmptyValue(TagNum),
+}
+
+impl SerializeError {
+    /// Tag of the field which failed to serialize.
+    pub fn tag(&self) -> TagNum {
+        match self {
+            SerializeError::EmptyValue(tag) => *tag,
+        }
+    }
+}
+
+/// Handling of empty values of optional String fields. FIX forbids empty
+/// values, but some systems expect empty tags to be sent anyway.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum EmptyStringPolicy {
+    /// Send empty field as it is, with warning logged.
+    #[default]
+    SendAsIs,
+    /// Omit empty field.
+    Skip,
+    /// Omit empty field and fail serialization, see
+    /// [`Serializer::finish`].
+    Error,
+}
 
 impl Default for Serializer {
     fn default() -> Self {
@@ -32,18 +166,34 @@ pub struct Serializer {
     output: Vec<u8>,
     body_start_idx: usize,
     current_tag_num: TagNum,
+    empty_strings: EmptyStringPolicy,
+    error: Option<SerializeError>,
 }
 
 impl Serializer {
     pub fn new() -> Serializer {
+        // Allocate for max message size, to prevent vector reallocation.
+        Serializer::with_capacity(MAX_MSG_SIZE)
+    }
+
+    /// Create serializer with output buffer of given initial `capacity`,
+    /// buffer grows when message doesn't fit.
+    pub fn with_capacity(capacity: usize) -> Serializer {
         Serializer {
-            // Allocate for max message size, to prevent vector reallocation.
-            output: Vec::with_capacity(MAX_MSG_SIZE),
+            output: Vec::with_capacity(capacity),
             body_start_idx: 0,
             current_tag_num: 0,
+            empty_strings: EmptyStringPolicy::SendAsIs,
+            error: None,
         }
     }
 
+    /// Handle empty values of optional String fields according to `policy`.
+    pub fn with_empty_strings(mut self, policy: EmptyStringPolicy) -> Serializer {
+        self.empty_strings = policy;
+        self
+    }
+
     pub fn output_mut(&mut self) -> &mut Vec<u8> {
         &mut self.output
     }
@@ -52,6 +202,39 @@ impl Serializer {
         self.output
     }
 
+    /// Take serialized message or the first error encountered.
+    pub fn finish(self) -> Result<Vec<u8>, SerializeError> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.output),
+        }
+    }
+
+    /// Check if message being serialized uses FIXT session protocol
+    /// (BeginString<8> FIXT.x.y), otherwise DefaultApplVerID<1137> is not
+    /// sent in Logon<A>.
+    pub fn is_fixt(&self) -> bool {
+        self.output.starts_with(b"8=FIXT")
+    }
+
+    /// Check if optional String field (`FixString` or `Utf8String`) should
+    /// be serialized, according to empty strings policy.
+    pub fn include_string(&mut self, tag_num: TagNum, value: &(impl AsRef<[u8]> + ?Sized)) -> bool {
+        self.current_tag_num = tag_num;
+        if !value.as_ref().is_empty() {
+            return true;
+        }
+        match self.empty_strings {
+            EmptyStringPolicy::SendAsIs => true,
+            EmptyStringPolicy::Skip => false,
+            EmptyStringPolicy::Error => {
+                self.error
+                    .get_or_insert(SerializeError::EmptyValue(tag_num));
+                false
+            }
+        }
+    }
+
     pub fn serialize_body_len(&mut self) {
         const BODY_LEN_PLACEHOLDER: &[u8] = match MAX_BODY_LEN_DIGITS {
             4 => b"9=0000\x01",
@@ -93,9 +276,7 @@ impl Serializer {
     /// Value must be positive and may not contain leading zeros.
     pub fn serialize_tag_num(&mut self, tag_num: &TagNum) {
         self.current_tag_num = *tag_num;
-        let mut buffer = itoa::Buffer::new();
-        self.output
-            .extend_from_slice(buffer.format(*tag_num).as_bytes());
+        write_int(&mut self.output, *tag_num);
     }
 
     /// Serialize sequence of character digits without commas or decimals
@@ -103,17 +284,13 @@ impl Serializer {
     /// The sign character utilizes one octet (i.e., positive int is “99999”
     /// while negative int is “-99999”).
     pub fn serialize_int(&mut self, int: &Int) {
-        let mut buffer = itoa::Buffer::new();
-        self.output
-            .extend_from_slice(buffer.format(*int).as_bytes());
+        write_int(&mut self.output, *int);
     }
 
     /// Serialize sequence of character digits without commas or decimals.
     /// Value must be positive.
     pub fn serialize_seq_num(&mut self, seq_num: &SeqNum) {
-        let mut buffer = itoa::Buffer::new();
-        self.output
-            .extend_from_slice(buffer.format(*seq_num).as_bytes());
+        write_int(&mut self.output, *seq_num);
     }
 
     /// Serialize sequence of character digits without commas or decimals.
@@ -122,17 +299,13 @@ impl Serializer {
         if *num_in_group == 0 {
             warn!("empty Group (tag={})", self.current_tag_num);
         }
-        let mut buffer = itoa::Buffer::new();
-        self.output
-            .extend_from_slice(buffer.format(*num_in_group).as_bytes());
+        write_int(&mut self.output, *num_in_group);
     }
 
     /// Serialize sequence of character digits without commas or decimals
     /// (values 1 to 31).
     pub fn serialize_day_of_month(&mut self, day_of_month: DayOfMonth) {
-        let mut buffer = itoa::Buffer::new();
-        self.output
-            .extend_from_slice(buffer.format(day_of_month).as_bytes());
+        write_int(&mut self.output, day_of_month);
     }
 
     /// Serialize sequence of character digits with optional decimal point
@@ -147,7 +320,7 @@ impl Serializer {
     /// The number of decimal places used should be a factor of business/market
     /// needs and mutual agreement between counterparties.
     pub fn serialize_float(&mut self, float: &Float) {
-        self.output.extend_from_slice(float.to_string().as_bytes())
+        write_decimal(&mut self.output, float)
     }
 
     pub fn serialize_qty(&mut self, qty: &Qty) {
@@ -222,6 +395,15 @@ impl Serializer {
         self.output.extend_from_slice(input.as_bytes());
     }
 
+    /// Serialize UTF-8 free-format string, its bytes are counted
+    /// in BodyLength<9> and CheckSum<10>.
+    pub fn serialize_utf8_string(&mut self, input: &Utf8String) {
+        if input.is_empty() {
+            warn!("empty Utf8String (tag={})", self.current_tag_num);
+        }
+        self.output.extend_from_slice(input.as_bytes());
+    }
+
     /// Serialize string containing one or more space-delimited multiple
     /// character values, e.g. “AV AN A”.
     pub fn serialize_multiple_string_value(&mut self, input: &MultipleStringValue) {
@@ -299,8 +481,7 @@ impl Serializer {
     ///        milliseconds, 6 digits to convey microseconds, 9 digits
     ///        to convey nanoseconds, 12 digits to convey picoseconds;
     pub fn serialize_utc_timestamp(&mut self, input: &UtcTimestamp) {
-        write!(self.output, "{}", input.format_precisely())
-            .expect("UtcTimestamp serialization failed")
+        push_utc_timestamp(&mut self.output, input);
     }
 
     /// Serialize string representing time-only represented in UTC
@@ -319,10 +500,16 @@ impl Serializer {
     ///        is not conveyed), it may include 3 digits to convey
     ///        milliseconds, 6 digits to convey microseconds, 9 digits
     ///        to convey nanoseconds, 12 digits to convey picoseconds;
-    ///        // TODO: set precision!
     pub fn serialize_utc_time_only(&mut self, input: &UtcTimeOnly) {
-        write!(self.output, "{}", input.format("%H:%M:%S.%f"))
-            .expect("UtcTimeOnly serialization failed")
+        let time = input.timestamp();
+        let nanos = push_time(
+            &mut self.output,
+            time.hour(),
+            time.minute(),
+            time.second(),
+            time.nanosecond(),
+        );
+        push_fraction(&mut self.output, nanos, input.precision() as u32);
     }
 
     /// Serialize date represented in UTC (Universal Time Coordinated)
@@ -333,7 +520,7 @@ impl Serializer {
     /// - MM = 01-12,
     /// - DD = 01-31.
     pub fn serialize_utc_date_only(&mut self, input: &UtcDateOnly) {
-        write!(self.output, "{}", input.format("%Y%m%d")).expect("UtcDateOnly serialization failed")
+        push_date(&mut self.output, input.year(), input.month(), input.day());
     }
 
     /// Serialize time local to a market center. Used where offset to UTC
@@ -347,8 +534,13 @@ impl Serializer {
     ///
     /// In general only the hour token is non-zero.
     pub fn serialize_local_mkt_time(&mut self, input: &LocalMktTime) {
-        write!(self.output, "{}", input.format("%H:%M:%S"))
-            .expect("LocalMktTime serialization failed")
+        push_time(
+            &mut self.output,
+            input.hour(),
+            input.minute(),
+            input.second(),
+            input.nanosecond(),
+        );
     }
 
     /// Serialize date of local market (as opposed to UTC) in YYYYMMDD
@@ -359,8 +551,7 @@ impl Serializer {
     /// - MM = 01-12,
     /// - DD = 01-31.
     pub fn serialize_local_mkt_date(&mut self, input: &LocalMktDate) {
-        write!(self.output, "{}", input.format("%Y%m%d"))
-            .expect("LocalMktDate serialization failed")
+        push_date(&mut self.output, input.year(), input.month(), input.day());
     }
 
     /// Serialize string representing a time/date combination representing
@@ -402,9 +593,7 @@ impl Serializer {
 
     /// Serialize sequence of character digits without commas or decimals.
     pub fn serialize_length(&mut self, length: &Length) {
-        let mut buffer = itoa::Buffer::new();
-        self.output
-            .extend_from_slice(buffer.format(*length).as_bytes());
+        write_int(&mut self.output, *length);
     }
 
     /// Serialize raw data with no format or content restrictions,
@@ -448,3 +637,137 @@ impl Serializer {
         self.output.pop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn serialize_utc_timestamp(input: &UtcTimestamp) -> String {
+        let mut serializer = Serializer::new();
+        serializer.serialize_utc_timestamp(input);
+        String::from_utf8(serializer.take()).unwrap()
+    }
+
+    fn utc(h: u32, m: u32, s: u32, nanos: u32) -> UtcDateTime {
+        NaiveDate::from_ymd_opt(2024, 2, 29)
+            .unwrap()
+            .and_hms_nano_opt(h, m, s, nanos)
+            .unwrap()
+            .and_utc()
+    }
+
+    #[test]
+    fn utc_timestamp() {
+        let timestamp = utc(23, 59, 59, 123_456_789);
+        assert_eq!(
+            serialize_utc_timestamp(&UtcTimestamp::with_secs(timestamp)),
+            "20240229-23:59:59"
+        );
+        assert_eq!(
+            serialize_utc_timestamp(&UtcTimestamp::with_millis(timestamp)),
+            "20240229-23:59:59.123"
+        );
+        assert_eq!(
+            serialize_utc_timestamp(&UtcTimestamp::with_nanos(timestamp)),
+            "20240229-23:59:59.123456789"
+        );
+        // leap second
+        assert_eq!(
+            serialize_utc_timestamp(&UtcTimestamp::with_micros(utc(23, 59, 59, 1_000_000_001))),
+            "20240229-23:59:60.000000"
+        );
+        assert_eq!(
+            UtcTimestamp::with_millis(timestamp).to_string(),
+            "20240229-23:59:59.123"
+        );
+    }
+
+    #[test]
+    fn date_and_time_only() {
+        let date = NaiveDate::from_ymd_opt(987, 6, 5).unwrap();
+        let time = NaiveTime::from_hms_nano_opt(1, 2, 3, 4_000).unwrap();
+        let mut serializer = Serializer::new();
+        serializer.serialize_utc_date_only(&date);
+        serializer.output_mut().push(b'|');
+        serializer.serialize_utc_time_only(&UtcTimeOnly::with_nanos(time));
+        serializer.output_mut().push(b'|');
+        serializer.serialize_local_mkt_date(&date);
+        serializer.output_mut().push(b'|');
+        serializer.serialize_local_mkt_time(&time);
+        assert_eq!(
+            serializer.take(),
+            b"09870605|01:02:03.000004000|09870605|01:02:03"
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn utc_timestamp_same_as_format_precisely() {
+        for timestamp in [
+            utc(23, 59, 58, 999_999_999),
+            utc(23, 59, 59, 123_456_789),
+            // leap second
+            utc(23, 59, 59, 1_000_000_001),
+            utc(0, 0, 0, 0),
+            utc(0, 0, 1, 7),
+            utc(0, 1, 0, 7),
+        ] {
+            for input in [
+                UtcTimestamp::with_secs(timestamp),
+                UtcTimestamp::with_millis(timestamp),
+                UtcTimestamp::with_micros(timestamp),
+                UtcTimestamp::with_nanos(timestamp),
+            ] {
+                assert_eq!(
+                    serialize_utc_timestamp(&input),
+                    input.format_precisely().to_string()
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn date_and_time_only_same_as_chrono_format() {
+        for (date, time) in [
+            ((2024, 2, 29), (23, 59, 59, 1_500_000_000)),
+            ((0, 1, 1), (0, 0, 0, 0)),
+            ((9999, 12, 31), (12, 34, 56, 789)),
+            ((-1, 1, 1), (1, 2, 3, 4)),
+            ((10_000, 1, 1), (1, 2, 3, 4)),
+        ] {
+            let date = NaiveDate::from_ymd_opt(date.0, date.1, date.2).unwrap();
+            let time = NaiveTime::from_hms_nano_opt(time.0, time.1, time.2, time.3).unwrap();
+
+            let mut serializer = Serializer::new();
+            serializer.serialize_utc_date_only(&date);
+            assert_eq!(
+                serializer.take(),
+                date.format("%Y%m%d").to_string().as_bytes()
+            );
+
+            let mut serializer = Serializer::new();
+            serializer.serialize_utc_time_only(&UtcTimeOnly::with_nanos(time));
+            assert_eq!(
+                serializer.take(),
+                time.format("%H:%M:%S.%f").to_string().as_bytes()
+            );
+
+            let mut serializer = Serializer::new();
+            serializer.serialize_local_mkt_time(&time);
+            assert_eq!(
+                serializer.take(),
+                time.format("%H:%M:%S").to_string().as_bytes()
+            );
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn utc_timestamp_out_of_range() {
+        assert_eq!(
+            serialize_utc_timestamp(&UtcTimestamp::MIN_UTC),
+            UtcTimestamp::MIN_UTC.format_precisely().to_string()
+        );
+    }
+}
@@ -0,0 +1,158 @@
+//! Decoding of messages read from async byte stream, e.g. socket or file,
+//! without session layer.
+
+use std::io;
+
+use bytes::{Buf, BytesMut};
+use futures_core::Stream;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::{
+    deserializer::{raw_message, split_raw_message, DeserializeError, RawMessageError},
+    messages::FixtMessage,
+};
+
+const READ_BUFFER_SIZE: usize = 8 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DecodeError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// Data not framed as FIX message, skipped up to the next message.
+    #[error("garbled data ({0}), {1} bytes skipped")]
+    Garbled(RawMessageError, usize),
+    /// Message framed correctly, but its content is invalid.
+    #[error("invalid message: {0}")]
+    Deserialize(#[from] DeserializeError),
+    /// Stream ended in the middle of message.
+    #[error("stream ended with {0} bytes of incomplete message")]
+    Truncated(usize),
+}
+
+/// Drop data up to the beginning of the next message candidate, return
+/// number of bytes dropped.
+fn skip_garbled_data(buffer: &mut BytesMut) -> usize {
+    let skipped = (1..buffer.len())
+        .find(|i| {
+            matches!(
+                raw_message(&buffer[*i..]),
+                Ok(_) | Err(RawMessageError::Incomplete)
+            )
+        })
+        .unwrap_or(buffer.len());
+    buffer.advance(skipped);
+    skipped
+}
+
+/// Decode messages from `reader` until it's exhausted.
+///
+/// Messages split between reads are handled, as well as many messages
+/// received at once. Errors don't terminate the stream, except of I/O
+/// errors, garbled data is skipped up to the next message.
+///
+/// ```ignore
+/// let mut messages = std::pin::pin!(decode_stream(socket));
+/// while let Some(msg) = messages.next().await {
+///     println!("{:?}", msg?.msg_type());
+/// }
+/// ```
+pub fn decode_stream(
+    reader: impl AsyncRead,
+) -> impl Stream<Item = Result<Box<FixtMessage>, DecodeError>> {
+    async_stream::stream! {
+        let mut reader = std::pin::pin!(reader);
+        let mut buffer = BytesMut::with_capacity(READ_BUFFER_SIZE);
+        loop {
+            loop {
+                match split_raw_message(&mut buffer) {
+                    Ok(raw_msg) => {
+                        yield FixtMessage::from_raw_message(raw_msg.as_raw_message())
+                            .map_err(DecodeError::from);
+                    }
+                    Err(RawMessageError::Incomplete) => break,
+                    Err(err) => {
+                        let skipped = skip_garbled_data(&mut buffer);
+                        yield Err(DecodeError::Garbled(err, skipped));
+                    }
+                }
+            }
+            buffer.reserve(READ_BUFFER_SIZE);
+            match reader.read_buf(&mut buffer).await {
+                Ok(0) => {
+                    if !buffer.is_empty() {
+                        yield Err(DecodeError::Truncated(buffer.len()));
+                    }
+                    break;
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    yield Err(DecodeError::Io(err));
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use tokio::io::AsyncWriteExt;
+
+    use super::*;
+    use crate::{
+        fields::{FixString, MsgType, UtcTimestamp},
+        messages::{Header, Heartbeat, Message, Trailer, BEGIN_STRING},
+    };
+
+    fn heartbeat(msg_seq_num: u32) -> Vec<u8> {
+        FixtMessage {
+            header: Box::new(Header {
+                begin_string: BEGIN_STRING.to_owned(),
+                msg_type: MsgType::Heartbeat,
+                sender_comp_id: FixString::from_ascii_lossy(b"sender".to_vec()),
+                target_comp_id: FixString::from_ascii_lossy(b"target".to_vec()),
+                msg_seq_num,
+                sending_time: UtcTimestamp::now(),
+                ..Default::default()
+            }),
+            body: Box::new(Message::Heartbeat(Heartbeat { test_req_id: None })),
+            trailer: Box::new(Trailer::default()),
+        }
+        .serialize()
+    }
+
+    #[tokio::test]
+    async fn messages_split_between_reads() {
+        let (mut writer, reader) = tokio::io::duplex(64);
+        let mut input = heartbeat(1);
+        input.extend_from_slice(b"garbage");
+        input.extend(heartbeat(2));
+        input.extend(heartbeat(3));
+        input.extend_from_slice(b"8=FIXT.1.1\x019=");
+        tokio::spawn(async move {
+            // Less than a message per write
+            for chunk in input.chunks(7) {
+                writer.write_all(chunk).await.unwrap();
+            }
+        });
+
+        let mut results: Vec<_> = decode_stream(reader).collect().await;
+        assert!(
+            matches!(results.pop(), Some(Err(DecodeError::Truncated(13)))),
+            "{results:?}"
+        );
+        let mut seq_nums = Vec::new();
+        let mut skipped = 0;
+        for result in results {
+            match result {
+                Ok(msg) => seq_nums.push(msg.header.msg_seq_num),
+                // Garbage may be split between reads
+                Err(DecodeError::Garbled(_, len)) => skipped += len,
+                Err(err) => panic!("unexpected error: {err}"),
+            }
+        }
+        assert_eq!(seq_nums, [1, 2, 3]);
+        assert_eq!(skipped, 7);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{deserializer::RawMessage, fields::TagNum, messages::FieldTag};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum TagValueError {
+    #[error("invalid tag number")]
+    InvalidTag,
+    #[error("value not terminated with SOH")]
+    MissingDelimiter,
+}
+
+/// Iterator over `(tag, value)` pairs of raw message body, without
+/// deserializing the message.
+///
+/// Value of Data/XmlData field is taken with length held by preceding
+/// Length field (e.g. RawDataLength<95> for RawData<96>), so it may
+/// contain SOH. Values are not validated in any other way.
+///
+/// Iteration stops after the first error.
+#[derive(Clone, Debug)]
+pub struct TagValueIter<'a> {
+    buf: &'a [u8],
+    /// Data/XmlData field expected next and its length
+    data_field: Option<(TagNum, usize)>,
+}
+
+impl<'a> TagValueIter<'a> {
+    /// Iterate over `tag=value<SOH>` fields in `body`.
+    pub fn new(body: &'a [u8]) -> TagValueIter<'a> {
+        TagValueIter {
+            buf: body,
+            data_field: None,
+        }
+    }
+
+    /// Input left to scan.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.buf
+    }
+
+    fn next_field(&mut self) -> Result<(TagNum, &'a [u8]), TagValueError> {
+        let Some(eq_pos) = self.buf.iter().position(|b| *b == b'=') else {
+            return Err(TagValueError::InvalidTag);
+        };
+        let tag = parse_num(&self.buf[..eq_pos])
+            .and_then(|tag| TagNum::try_from(tag).ok())
+            .filter(|tag| *tag != 0)
+            .ok_or(TagValueError::InvalidTag)?;
+        let buf = &self.buf[eq_pos + 1..];
+
+        let value_len = match self.data_field.take() {
+            Some((data_tag, len)) if data_tag == tag => {
+                if buf.get(len) != Some(&b'\x01') {
+                    return Err(TagValueError::MissingDelimiter);
+                }
+                len
+            }
+            _ => buf
+                .iter()
+                .position(|b| *b == b'\x01')
+                .ok_or(TagValueError::MissingDelimiter)?,
+        };
+        let value = &buf[..value_len];
+        self.buf = &buf[value_len + 1..];
+
+        if let Some(data_field) = FieldTag::from_tag_num(tag).and_then(|tag| tag.data_field()) {
+            // Invalid length is left to the deserializer, value is then
+            // scanned up to SOH
+            self.data_field = parse_num(value).map(|len| (data_field as TagNum, len));
+        }
+        Ok((tag, value))
+    }
+}
+
+impl<'a> Iterator for TagValueIter<'a> {
+    type Item = Result<(TagNum, &'a [u8]), TagValueError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        let result = self.next_field();
+        if result.is_err() {
+            self.buf = &[];
+        }
+        Some(result)
+    }
+}
+
+impl<'a> RawMessage<'a> {
+    /// Iterate over message body fields, see [`TagValueIter`].
+    pub fn tag_values(&self) -> TagValueIter<'a> {
+        TagValueIter::new(self.body)
+    }
+}
+
+fn parse_num(input: &[u8]) -> Option<usize> {
+    if input.is_empty() || input.len() > 9 || !input.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    Some(
+        input
+            .iter()
+            .fold(0, |acc, digit| acc * 10 + usize::from(digit - b'0')),
+    )
+}
@@ -0,0 +1,79 @@
+//! Helpers for tests and documentation examples, where messages are written
+//! with `|` instead of SOH delimiter, e.g.
+//! `8=FIXT.1.1|9=0055|35=0|...|10=102|`.
+//!
+//! Test-only, available with `test-util` feature. Delimiters are replaced
+//! blindly, so `|` characters in field values (including Data fields) are
+//! replaced as well. Production code paths accept SOH only.
+//!
+//! ```ignore
+//! let fixture = "8=FIXT.1.1|9=0055|35=0|49=sender|56=target|34=1|\
+//!                52=20240101-00:00:00.000|10=102|";
+//! let msg = from_piped(fixture)?;
+//! assert_eq!(to_piped(&msg), fixture);
+//! ```
+
+use crate::{deserializer::DeserializeError, messages::FixtMessage};
+
+/// Input with every `|` replaced by SOH, already SOH-delimited input is
+/// returned unchanged.
+pub fn soh_from_pipes(input: impl AsRef<[u8]>) -> Vec<u8> {
+    input
+        .as_ref()
+        .iter()
+        .map(|b| if *b == b'|' { b'\x01' } else { *b })
+        .collect()
+}
+
+/// Input with every SOH replaced by `|`, non-UTF-8 bytes replaced with
+/// `U+FFFD`.
+pub fn pipes_from_soh(input: impl AsRef<[u8]>) -> String {
+    let output: Vec<u8> = input
+        .as_ref()
+        .iter()
+        .map(|b| if *b == b'\x01' { b'|' } else { *b })
+        .collect();
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+/// Deserialize message delimited with `|` or SOH.
+///
+/// CheckSum<10> is verified against SOH-delimited message, as it would be
+/// sent.
+pub fn from_piped(input: impl AsRef<[u8]>) -> Result<Box<FixtMessage>, DeserializeError> {
+    FixtMessage::from_bytes(&soh_from_pipes(input))
+}
+
+/// Serialize message delimited with `|`.
+pub fn to_piped(msg: &FixtMessage) -> String {
+    pipes_from_soh(msg.serialize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_piped, pipes_from_soh, soh_from_pipes, to_piped};
+    use crate::{fields::MsgType, messages::Message};
+
+    const HEARTBEAT: &str = "8=FIXT.1.1|9=0055|35=0|49=sender|56=target|34=1|\
+                             52=20240101-00:00:00.000|10=102|";
+
+    #[test]
+    fn both_delimiters_accepted() {
+        let soh = soh_from_pipes(HEARTBEAT);
+        assert!(!soh.contains(&b'|'));
+        assert_eq!(soh_from_pipes(&soh), soh);
+        assert_eq!(pipes_from_soh(&soh), HEARTBEAT);
+
+        for input in [HEARTBEAT.as_bytes(), &soh] {
+            let msg = from_piped(input).unwrap();
+            assert_eq!(msg.header.msg_type, MsgType::Heartbeat);
+            assert!(matches!(*msg.body, Message::Heartbeat(_)));
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let msg = from_piped(HEARTBEAT).unwrap();
+        assert_eq!(to_piped(&msg), HEARTBEAT);
+    }
+}
@@ -1,12 +1,25 @@
 use assert_matches::assert_matches;
+use bytes::{Bytes, BytesMut};
 use easyfix_messages::{
-    deserializer::{DeserializeError, ParseRejectReason},
+    deserializer::{
+        framing_mismatch, raw_message, split_raw_message, DeserializeError, Deserializer,
+        DuplicateTag, DuplicateTagPolicy, FieldError, FramingMismatch, GroupLimits,
+        ParseRejectReason, RawMessageError, RequiredTags,
+    },
     fields::{
-        DefaultApplVerId, EncryptMethod, FixString, MsgDirection, MsgType, ToFixString, Utc,
+        enum_metadata, enum_metadata_by_tag, Decimal, DefaultApplVerId, EncryptMethod, FieldRef,
+        Fields, FixString, MsgDirection, MsgType, MsgTypeCode, OrdStatus, Side, ToFixString, Utc,
         UtcTimestamp,
     },
-    groups::MsgTypeGrp,
-    messages::{FixtMessage, Header, Heartbeat, Logon, Message, Trailer, BEGIN_STRING},
+    fingerprint::Fingerprinter,
+    groups::{MsgTypeGrp, Parties, PtysSubGrp},
+    keep_alive::KeepAliveTemplate,
+    messages::{
+        ExecutionReport, FixtMessage, Header, Heartbeat, Logon, Message, MsgCat, NewOrderSingle,
+        OtherMessage, TestRequest, Trailer, BEGIN_STRING,
+    },
+    serializer::{EmptyStringPolicy, SerializeError, Serializer},
+    tag_value::{TagValueError, TagValueIter},
 };
 
 fn header(msg_type: MsgType) -> Box<Header> {
@@ -59,7 +72,11 @@ fn logon_msg_type_grp_no_present() {
         test_message_indicator: None,
         username: None,
         password: None,
+        new_password: None,
+        session_status: None,
         default_appl_ver_id: DefaultApplVerId::Fix50Sp2,
+        text: None,
+        encoded_text: None,
         msg_type_grp: None,
     })));
     let serialized = msg.serialize();
@@ -78,7 +95,11 @@ fn logon_msg_type_grp_present_with_two_entries_1() {
         test_message_indicator: None,
         username: None,
         password: None,
+        new_password: None,
+        session_status: None,
         default_appl_ver_id: DefaultApplVerId::Fix50Sp2,
+        text: None,
+        encoded_text: None,
         msg_type_grp: Some(vec![
             MsgTypeGrp {
                 ref_msg_type: Some(MsgType::NewOrderSingle.to_fix_string()),
@@ -114,7 +135,11 @@ fn logon_msg_type_grp_present_with_two_entries_2() {
         test_message_indicator: None,
         username: None,
         password: None,
+        new_password: None,
+        session_status: None,
         default_appl_ver_id: DefaultApplVerId::Fix50Sp2,
+        text: None,
+        encoded_text: None,
         msg_type_grp: Some(vec![
             MsgTypeGrp {
                 ref_msg_type: Some(MsgType::NewOrderSingle.to_fix_string()),
@@ -138,6 +163,455 @@ fn logon_msg_type_grp_present_with_two_entries_2() {
     FixtMessage::from_bytes(&serialized).expect("Deserialization failed");
 }
 
+#[cfg(feature = "pooled-data")]
+#[test]
+fn logon_raw_data_from_buffer_pool() {
+    let msg = fixt_message(Box::new(Message::Logon(Logon {
+        encrypt_method: EncryptMethod::NoneOther,
+        heart_bt_int: 30,
+        raw_data: Some(b"raw\x01data".to_vec().into()),
+        reset_seq_num_flag: Some(true),
+        default_appl_ver_id: DefaultApplVerId::Fix50Sp2,
+        ..Default::default()
+    })));
+    let serialized = msg.serialize();
+    let buffer_pool = easyfix_messages::buffer_pool::BufferPool::new(&[64], 1);
+
+    for _ in 0..2 {
+        let (_, raw_msg) = raw_message(&serialized).expect("Invalid message");
+        let deserializer = Deserializer::from_raw_message(raw_msg).with_buffer_pool(&buffer_pool);
+        let deserialized = FixtMessage::deserialize(deserializer).expect("Deserialization failed");
+        assert_eq!(deserialized.body, msg.body);
+        assert_eq!(buffer_pool.idle_buffers(), 0);
+        drop(deserialized);
+        assert_eq!(buffer_pool.idle_buffers(), 1);
+    }
+}
+
+#[test]
+fn tag_values_with_data_field() {
+    let msg = fixt_message(Box::new(Message::Logon(Logon {
+        encrypt_method: EncryptMethod::NoneOther,
+        heart_bt_int: 30,
+        raw_data: Some(b"raw\x0110=data".as_slice().into()),
+        default_appl_ver_id: DefaultApplVerId::Fix50Sp2,
+        ..Default::default()
+    })));
+    let serialized = msg.serialize();
+    let (_, raw_msg) = raw_message(&serialized).expect("Invalid message");
+
+    let fields = raw_msg
+        .tag_values()
+        .collect::<Result<Vec<_>, _>>()
+        .expect("Scan failed");
+    let tags: Vec<_> = fields.iter().map(|(tag, _)| *tag).collect();
+    assert_eq!(tags, [35, 49, 56, 34, 52, 98, 108, 95, 96, 1137]);
+    assert_eq!(fields[0], (35, &b"A"[..]));
+    assert_eq!(fields[7], (95, &b"11"[..]));
+    assert_eq!(fields[8], (96, &b"raw\x0110=data"[..]));
+}
+
+#[test]
+fn tag_values_garbled() {
+    let mut fields = TagValueIter::new(b"35=0\x0149test\x0156=x\x01");
+    assert_eq!(fields.next(), Some(Ok((35, &b"0"[..]))));
+    assert_eq!(fields.next(), Some(Err(TagValueError::InvalidTag)));
+    assert_eq!(fields.next(), None);
+
+    // Data shorter than its length
+    let mut fields = TagValueIter::new(b"95=10\x0196=short\x01");
+    assert_eq!(fields.next(), Some(Ok((95, &b"10"[..]))));
+    assert_eq!(fields.next(), Some(Err(TagValueError::MissingDelimiter)));
+}
+
+#[test]
+fn group_type_shared_between_messages() {
+    let parties = vec![Parties {
+        party_id: Some(FixString::from_ascii_lossy(b"party".to_vec())),
+        ..Default::default()
+    }];
+    let order = fixt_message(Box::new(Message::NewOrderSingle(NewOrderSingle {
+        cl_ord_id: FixString::from_ascii_lossy(b"order".to_vec()),
+        parties: Some(parties.clone()),
+        ..Default::default()
+    })));
+    let order = FixtMessage::from_bytes(&order.serialize()).expect("Deserialization failed");
+    let Message::NewOrderSingle(order) = *order.body else {
+        panic!("unexpected message: {:?}", order.body);
+    };
+
+    // Parties of an order go to the execution report as they are
+    let report = fixt_message(Box::new(Message::ExecutionReport(ExecutionReport {
+        order_id: FixString::from_ascii_lossy(b"order".to_vec()),
+        exec_id: FixString::from_ascii_lossy(b"exec".to_vec()),
+        parties: order.parties,
+        ..Default::default()
+    })));
+    let report = FixtMessage::from_bytes(&report.serialize()).expect("Deserialization failed");
+    let Message::ExecutionReport(report) = *report.body else {
+        panic!("unexpected message: {:?}", report.body);
+    };
+    assert_eq!(report.parties, Some(parties));
+}
+
+/// Replace `from` with `to` (of the same length) in serialized message and
+/// fix the checksum.
+fn patch_message(msg: &FixtMessage, from: &str, to: &str) -> Vec<u8> {
+    assert_eq!(from.len(), to.len());
+    let serialized = String::from_utf8(msg.serialize()).unwrap();
+    assert!(serialized.contains(from));
+    let mut patched = serialized.replace(from, to).into_bytes();
+    let checksum_pos = patched.len() - "10=000\x01".len();
+    let checksum = patched[..checksum_pos]
+        .iter()
+        .fold(0u8, |acc, b| acc.wrapping_add(*b));
+    patched.splice(checksum_pos.., format!("10={checksum:03}\x01").into_bytes());
+    patched
+}
+
+fn order_with_qty_and_price() -> Box<FixtMessage> {
+    fixt_message(Box::new(Message::NewOrderSingle(NewOrderSingle {
+        cl_ord_id: FixString::from_ascii_lossy(b"order".to_vec()),
+        order_qty: Some(Decimal::from(5)),
+        price: Some(Decimal::from(10)),
+        ..Default::default()
+    })))
+}
+
+#[test]
+fn optional_field_error_collected() {
+    let input = patch_message(&order_with_qty_and_price(), "\x0138=5\x01", "\x0138=x\x01");
+
+    // Rejected by default
+    assert_matches!(
+        FixtMessage::from_bytes(&input),
+        Err(DeserializeError::Reject { tag: Some(38), .. })
+    );
+
+    let (_, raw_msg) = raw_message(&input).unwrap();
+    let (msg, field_errors) =
+        FixtMessage::deserialize_with_field_errors(Deserializer::from_raw_message(raw_msg))
+            .expect("Deserialization failed");
+    assert_matches!(
+        field_errors.as_slice(),
+        [FieldError {
+            tag: 38,
+            reason: ParseRejectReason::IncorrectDataFormatForValue
+        }]
+    );
+    let Message::NewOrderSingle(order) = *msg.body else {
+        panic!("unexpected message: {:?}", msg.body);
+    };
+    assert_eq!(order.order_qty, None);
+    // Fields following the invalid one are still deserialized
+    assert_eq!(order.price, Some(Decimal::from(10)));
+}
+
+#[test]
+fn required_field_error_rejected() {
+    let input = patch_message(&order_with_qty_and_price(), "\x0154=1\x01", "\x0154=Z\x01");
+    let (_, raw_msg) = raw_message(&input).unwrap();
+    assert_matches!(
+        FixtMessage::deserialize_with_field_errors(Deserializer::from_raw_message(raw_msg)),
+        Err(DeserializeError::Reject { tag: Some(54), .. })
+    );
+}
+
+/// NewOrderSingle<D> with OrderQty<38> appearing twice, 5 and then 7.
+fn order_with_duplicated_qty() -> Vec<u8> {
+    let mut msg = order_with_qty_and_price();
+    let Message::NewOrderSingle(order) = &mut *msg.body else {
+        unreachable!();
+    };
+    order.text = Some(FixString::from_ascii_lossy(b"a".to_vec()));
+    patch_message(&msg, "\x0158=a\x01", "\x0138=7\x01")
+}
+
+fn deserialize_order_qty(input: &[u8], policy: DuplicateTagPolicy) -> Option<Decimal> {
+    let (_, raw_msg) = raw_message(input).unwrap();
+    let msg = FixtMessage::deserialize(
+        Deserializer::from_raw_message(raw_msg).with_duplicate_tags(policy),
+    )
+    .expect("Deserialization failed");
+    let Message::NewOrderSingle(order) = *msg.body else {
+        panic!("unexpected message: {:?}", msg.body);
+    };
+    assert_eq!(order.price, Some(Decimal::from(10)));
+    order.order_qty
+}
+
+#[test]
+fn duplicate_tags() {
+    let input = order_with_duplicated_qty();
+
+    // Rejected by default
+    assert_matches!(
+        FixtMessage::from_bytes(&input),
+        Err(DeserializeError::Reject {
+            tag: Some(38),
+            reason: ParseRejectReason::TagAppearsMoreThanOnce,
+            ..
+        })
+    );
+
+    assert_eq!(
+        deserialize_order_qty(&input, DuplicateTagPolicy::KeepFirst),
+        Some(Decimal::from(5))
+    );
+    assert_eq!(
+        deserialize_order_qty(&input, DuplicateTagPolicy::KeepLast),
+        Some(Decimal::from(7))
+    );
+}
+
+#[test]
+fn duplicate_header_tag_rejected() {
+    let input = patch_message(
+        &order_with_qty_and_price(),
+        "\x0156=test_target\x01",
+        "\x0149=test_sender\x01",
+    );
+    let (_, raw_msg) = raw_message(&input).unwrap();
+    assert_matches!(
+        FixtMessage::deserialize(
+            Deserializer::from_raw_message(raw_msg)
+                .with_duplicate_tags(DuplicateTagPolicy::KeepLast)
+        ),
+        Err(DeserializeError::Reject {
+            tag: Some(49),
+            reason: ParseRejectReason::TagAppearsMoreThanOnce,
+            ..
+        })
+    );
+}
+
+/// Offset of `value` of field `tag` in message body.
+fn value_offset(body: &[u8], tag: &str, value: &str) -> usize {
+    let field = format!("\x01{tag}={value}\x01");
+    body.windows(field.len())
+        .position(|window| window == field.as_bytes())
+        .expect("field not found")
+        + tag.len()
+        + 2
+}
+
+#[test]
+fn duplicate_tag_evidence() {
+    let input = order_with_duplicated_qty();
+    let (_, raw_msg) = raw_message(&input).unwrap();
+    let body = raw_msg.body;
+    let Err(DeserializeError::Reject {
+        duplicate: Some(duplicate),
+        ..
+    }) = FixtMessage::from_bytes(&input)
+    else {
+        panic!("duplicate not reported");
+    };
+    assert_eq!(
+        *duplicate,
+        DuplicateTag {
+            tag: 38,
+            first_value: Bytes::from_static(b"5"),
+            first_offset: value_offset(body, "38", "5"),
+            second_value: Bytes::from_static(b"7"),
+            second_offset: value_offset(body, "38", "7"),
+        }
+    );
+    assert_eq!(&body[duplicate.first_offset..][..1], b"5");
+    assert_eq!(&body[duplicate.second_offset..][..1], b"7");
+
+    // Header field
+    let input = patch_message(
+        &order_with_qty_and_price(),
+        "\x0156=test_target\x01",
+        "\x0149=test_sender\x01",
+    );
+    let (_, raw_msg) = raw_message(&input).unwrap();
+    let Err(DeserializeError::Reject {
+        duplicate: Some(duplicate),
+        ..
+    }) = FixtMessage::from_bytes(&input)
+    else {
+        panic!("duplicate not reported");
+    };
+    assert_eq!(duplicate.tag, 49);
+    assert_eq!(duplicate.first_value, duplicate.second_value);
+    assert!(duplicate.first_offset < duplicate.second_offset);
+    assert_eq!(
+        &raw_msg.body[duplicate.second_offset..][..11],
+        b"test_sender"
+    );
+}
+
+#[test]
+fn empty_strings() {
+    let mut msg = order_with_qty_and_price();
+    let Message::NewOrderSingle(order) = &mut *msg.body else {
+        unreachable!();
+    };
+    order.text = Some(FixString::new());
+
+    // Sent as is by default
+    let serialized = msg.serialize();
+    assert!(serialized.windows(5).any(|field| field == b"\x0158=\x01"));
+    assert_eq!(
+        msg.serialize_with(Serializer::new()).as_deref(),
+        Ok(serialized.as_slice())
+    );
+
+    let serialized = msg
+        .serialize_with(Serializer::new().with_empty_strings(EmptyStringPolicy::Skip))
+        .unwrap();
+    assert!(!serialized.windows(4).any(|field| field == b"\x0158="));
+    let deserialized = FixtMessage::from_bytes(&serialized).expect("Deserialization failed");
+    let Message::NewOrderSingle(order) = *deserialized.body else {
+        panic!("unexpected message: {:?}", deserialized.body);
+    };
+    assert_eq!(order.text, None);
+
+    assert_eq!(
+        msg.serialize_with(Serializer::new().with_empty_strings(EmptyStringPolicy::Error)),
+        Err(SerializeError::EmptyValue(58))
+    );
+}
+
+#[test]
+fn split_raw_message_shares_buffer() {
+    let first = order_with_qty_and_price().serialize();
+    let second = fixt_message(Box::new(Message::Heartbeat(Heartbeat {
+        test_req_id: None,
+    })))
+    .serialize();
+    let mut buffer = BytesMut::from(&first[..]);
+    buffer.extend_from_slice(&second[..second.len() - 1]);
+
+    let buffer_start = buffer.as_ptr();
+    let raw_msg = split_raw_message(&mut buffer).expect("Split failed");
+    assert_eq!(raw_msg.as_bytes(), first.as_slice());
+    assert_eq!(raw_msg.as_bytes().as_ptr(), buffer_start);
+    assert!(raw_msg.body().starts_with(b"35=D\x01"));
+    let msg = FixtMessage::deserialize(Deserializer::from_raw_message(raw_msg.as_raw_message()))
+        .expect("Deserialization failed");
+    assert_eq!(msg.msg_type(), MsgType::NewOrderSingle);
+
+    // Incomplete message is left in the buffer
+    assert_matches!(
+        split_raw_message(&mut buffer),
+        Err(RawMessageError::Incomplete)
+    );
+    assert_eq!(&buffer[..], &second[..second.len() - 1]);
+    buffer.extend_from_slice(b"\x01");
+    let raw_msg = split_raw_message(&mut buffer).expect("Split failed");
+    assert_eq!(raw_msg.as_bytes(), second.as_slice());
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn framing_mismatch_diagnosed() {
+    let msg = fixt_message(Box::new(Message::Heartbeat(Heartbeat {
+        test_req_id: None,
+    })))
+    .serialize();
+    let checksum_start = msg.len() - 7;
+    assert_eq!(&msg[checksum_start..checksum_start + 3], b"10=");
+    assert_eq!(framing_mismatch(&msg), None);
+
+    let mut wrong_checksum = msg.clone();
+    let received = if &msg[checksum_start + 3..checksum_start + 6] == b"000" {
+        b"001"
+    } else {
+        b"000"
+    };
+    wrong_checksum[checksum_start + 3..checksum_start + 6].copy_from_slice(received);
+    assert_matches!(
+        raw_message(&wrong_checksum),
+        Err(RawMessageError::InvalidChecksum)
+    );
+    let calculated = msg[..checksum_start]
+        .iter()
+        .fold(0, |acc: u8, x| acc.wrapping_add(*x));
+    assert_eq!(
+        framing_mismatch(&wrong_checksum),
+        Some((
+            FramingMismatch::CheckSum {
+                calculated,
+                received: if received == b"000" { 0 } else { 1 },
+            },
+            checksum_start
+        ))
+    );
+
+    // BodyLength<9> one byte too short
+    let text = String::from_utf8(msg).unwrap();
+    let value = &text.split('\x01').nth(1).unwrap()[2..];
+    let body_length: usize = value.parse().unwrap();
+    let wrong_length = text.replacen(
+        &format!("\x019={value}\x01"),
+        &format!("\x019={:0width$}\x01", body_length - 1, width = value.len()),
+        1,
+    );
+    assert_matches!(
+        raw_message(wrong_length.as_bytes()),
+        Err(RawMessageError::Garbled)
+    );
+    assert_eq!(
+        framing_mismatch(wrong_length.as_bytes()),
+        Some((
+            FramingMismatch::BodyLength {
+                calculated: body_length,
+                received: body_length - 1,
+            },
+            checksum_start
+        ))
+    );
+}
+
+#[test]
+fn field_enums_as_map_keys() {
+    let mut qty_by_side = std::collections::HashMap::new();
+    *qty_by_side.entry(Side::Buy).or_insert(0) += 10;
+    *qty_by_side.entry(Side::Sell).or_insert(0) += 5;
+    *qty_by_side.entry(Side::Buy).or_insert(0) += 1;
+    assert_eq!(qty_by_side[&Side::Buy], 11);
+    assert_eq!(qty_by_side[&Side::Sell], 5);
+
+    assert_eq!(std::mem::size_of::<Side>(), 1);
+}
+
+#[test]
+fn enum_display_names() {
+    assert_eq!(
+        OrdStatus::PartiallyFilled.display_name(),
+        "Partially filled"
+    );
+    assert_eq!(MsgType::NewOrderSingle.display_name(), "New order single");
+
+    let metadata = enum_metadata();
+    assert!(metadata.windows(2).all(|pair| pair[0].tag < pair[1].tag));
+    let ord_status = enum_metadata_by_tag(39).unwrap();
+    assert_eq!(ord_status.name, "OrdStatus");
+    let value = ord_status
+        .value(OrdStatus::PartiallyFilled.as_fix_str())
+        .unwrap();
+    assert_eq!(value.variant, "PartiallyFilled");
+    assert_eq!(value.display_name, "Partially filled");
+    assert!(enum_metadata_by_tag(11).is_none());
+}
+
+#[test]
+fn msg_type_and_cat_constants() {
+    assert_eq!(NewOrderSingle::MSG_TYPE, MsgType::NewOrderSingle);
+    assert_eq!(NewOrderSingle::MSG_CAT, MsgCat::App);
+    assert_eq!(Heartbeat::MSG_CAT, MsgCat::Admin);
+
+    assert_eq!(MsgType::Logon.msg_cat(), MsgCat::Admin);
+    assert_eq!(MsgType::ExecutionReport.msg_cat(), MsgCat::App);
+
+    let msg = Message::TestRequest(TestRequest::default());
+    assert_eq!(msg.msg_type(), TestRequest::MSG_TYPE);
+    assert_eq!(msg.msg_cat(), msg.msg_type().msg_cat());
+}
+
 #[test]
 fn unknown_msg_type() {
     let msg_str = "8=FIXT.1.1|9=0077|35=UNKNOWN|49=test_sender|56=test_target|34=1|52=20230713-21:55:13.436187000|10=254|";
@@ -152,6 +626,140 @@ fn unknown_msg_type() {
     );
 }
 
+#[test]
+fn other_msg_type() {
+    let code = MsgTypeCode::from_bytes(b"U9").unwrap();
+    let msg = FixtMessage {
+        header: header(MsgType::Other(code)),
+        body: Box::new(Message::Other(OtherMessage {
+            msg_type: code,
+            fields: vec![
+                (5001, FixString::from_ascii_lossy(b"A".to_vec())),
+                (58, FixString::from_ascii_lossy(b"text".to_vec())),
+            ],
+        })),
+        trailer: trailer(),
+    };
+    let serialized = msg.serialize();
+    assert!(String::from_utf8_lossy(&serialized).contains("\x0135=U9\x01"));
+
+    let (_, raw_msg) = raw_message(&serialized).unwrap();
+    let deserialized =
+        FixtMessage::deserialize(Deserializer::from_raw_message(raw_msg).with_other_msg_types())
+            .unwrap();
+    assert_eq!(deserialized.msg_type(), MsgType::Other(code));
+    assert_eq!(deserialized.msg_type().as_bytes(), b"U9");
+    assert_eq!(deserialized.msg_cat(), MsgCat::App);
+    assert_eq!(deserialized.header.sender_comp_id, "test_sender");
+    let Message::Other(other) = &*deserialized.body else {
+        panic!("unexpected message: {deserialized:?}");
+    };
+    assert_eq!(other.field(5001).unwrap(), "A");
+    assert_eq!(other.field(58).unwrap(), "text");
+    assert_eq!(other.field(1), None);
+    assert_eq!(deserialized.serialize(), serialized);
+
+    // Rejected unless enabled
+    assert_matches!(
+        FixtMessage::from_bytes(&serialized),
+        Err(DeserializeError::Reject {
+            tag: Some(35),
+            reason: ParseRejectReason::InvalidMsgtype,
+            ..
+        })
+    );
+}
+
+#[test]
+fn required_tags() {
+    let deserialize = |msg: &FixtMessage, required_tags: &RequiredTags| {
+        let serialized = msg.serialize();
+        let (_, raw_msg) = raw_message(&serialized).unwrap();
+        FixtMessage::deserialize(
+            Deserializer::from_raw_message(raw_msg).with_required_tags(required_tags),
+        )
+        .map(|_| ())
+    };
+    let mut msg = fixt_message(Box::new(Message::Heartbeat(Heartbeat {
+        test_req_id: None,
+    })));
+    let required_tags = RequiredTags {
+        header: vec![56, 49, 50],
+        trailer: vec![10],
+        ordered: false,
+    };
+    assert_matches!(
+        deserialize(&msg, &required_tags),
+        Err(DeserializeError::Reject {
+            tag: Some(50),
+            reason: ParseRejectReason::RequiredTagMissing,
+            ..
+        })
+    );
+
+    msg.header.sender_sub_id = Some(FixString::from_ascii_lossy(b"desk".to_vec()));
+    assert_matches!(deserialize(&msg, &required_tags), Ok(()));
+
+    // SenderCompID<49> precedes TargetCompID<56>
+    let ordered = RequiredTags {
+        ordered: true,
+        ..required_tags
+    };
+    assert_matches!(
+        deserialize(&msg, &ordered),
+        Err(DeserializeError::Reject {
+            tag: Some(49),
+            reason: ParseRejectReason::TagSpecifiedOutOfRequiredOrder,
+            ..
+        })
+    );
+
+    // Header field required in trailer
+    let misplaced = RequiredTags {
+        header: Vec::new(),
+        trailer: vec![49],
+        ordered: false,
+    };
+    assert_matches!(
+        deserialize(&msg, &misplaced),
+        Err(DeserializeError::Reject {
+            tag: Some(49),
+            reason: ParseRejectReason::TagSpecifiedOutOfRequiredOrder,
+            ..
+        })
+    );
+}
+
+#[test]
+fn from_raw_with_header() {
+    let serialized = order_with_qty_and_price().serialize();
+    let expected = FixtMessage::from_bytes(&serialized).unwrap();
+
+    let (_, raw_msg) = raw_message(&serialized).unwrap();
+    let msg = FixtMessage::from_raw_with_header(*expected.header.clone(), raw_msg).unwrap();
+    assert_eq!(msg, expected);
+
+    // Header is taken as it is
+    let mut header = expected.header.clone();
+    header.sender_sub_id = Some(FixString::from_ascii_lossy(b"routed".to_vec()));
+    let (_, raw_msg) = raw_message(&serialized).unwrap();
+    let msg = FixtMessage::from_raw_with_header(*header.clone(), raw_msg).unwrap();
+    assert_eq!(msg.header, header);
+    assert_eq!(msg.body, expected.body);
+
+    // Header field following body one
+    let input = patch_message(&order_with_qty_and_price(), "\x0138=5\x01", "\x0150=5\x01");
+    let (_, raw_msg) = raw_message(&input).unwrap();
+    assert_matches!(
+        FixtMessage::from_raw_with_header(*expected.header.clone(), raw_msg),
+        Err(DeserializeError::Reject {
+            tag: Some(50),
+            reason: ParseRejectReason::TagSpecifiedOutOfRequiredOrder,
+            ..
+        })
+    );
+}
+
 #[test]
 fn known_msg_type() {
     let msg_str = "8=FIXT.1.1|9=0071|35=0|49=test_sender|56=test_target|34=1|52=20230713-21:55:13.436187000|10=248|";
@@ -159,3 +767,293 @@ fn known_msg_type() {
     let msg = FixtMessage::from_bytes(msg_str.replace("|", "\x01").as_bytes()).unwrap();
     assert_eq!(msg.header.msg_type, MsgType::Heartbeat);
 }
+
+#[test]
+fn keep_alive_template_matches_serializer() {
+    let mut template = KeepAliveTemplate::new(*header(MsgType::Heartbeat)).unwrap();
+    let test_req_id = || Some(FixString::from_ascii_lossy(b"test_req".to_vec()));
+
+    for body in [
+        Message::Heartbeat(Heartbeat { test_req_id: None }),
+        Message::Heartbeat(Heartbeat {
+            test_req_id: test_req_id(),
+        }),
+        Message::TestRequest(TestRequest {
+            test_req_id: test_req_id().unwrap(),
+        }),
+    ] {
+        let now = Utc::now();
+        for sending_time in [
+            UtcTimestamp::with_secs(now),
+            UtcTimestamp::with_millis(now),
+            UtcTimestamp::with_micros(now),
+            UtcTimestamp::with_nanos(now),
+        ] {
+            let mut msg = fixt_message(Box::new(body.clone()));
+            msg.header.msg_seq_num = 1234;
+            msg.header.sending_time = sending_time;
+            assert_eq!(template.serialize(&msg), Some(msg.serialize()));
+        }
+    }
+}
+
+#[test]
+fn keep_alive_template_header_mismatch() {
+    let mut template = KeepAliveTemplate::new(*header(MsgType::Heartbeat)).unwrap();
+
+    let mut msg = fixt_message(Box::new(Message::Heartbeat(Heartbeat {
+        test_req_id: None,
+    })));
+    msg.header.poss_dup_flag = Some(true);
+    assert_eq!(template.serialize(&msg), None);
+
+    let msg = fixt_message(Box::new(Message::Logon(Logon::default())));
+    assert_eq!(template.serialize(&msg), None);
+
+    // Left to serializer empty strings policy
+    let msg = fixt_message(Box::new(Message::Heartbeat(Heartbeat {
+        test_req_id: Some(FixString::new()),
+    })));
+    assert_eq!(template.serialize(&msg), None);
+}
+
+#[cfg(feature = "field-presence")]
+#[test]
+fn field_presence() {
+    let logon = Logon {
+        encrypt_method: EncryptMethod::NoneOther,
+        heart_bt_int: 30,
+        raw_data: Some(b"nonce".as_slice().into()),
+        reset_seq_num_flag: None,
+        next_expected_msg_seq_num: None,
+        max_message_size: None,
+        test_message_indicator: None,
+        username: Some(FixString::from_ascii_lossy(b"user".to_vec())),
+        password: None,
+        new_password: None,
+        session_status: None,
+        default_appl_ver_id: DefaultApplVerId::Fix50Sp2,
+        text: None,
+        encoded_text: None,
+        msg_type_grp: Some(Vec::new()),
+    };
+    let presence = logon.presence();
+    // EncryptMethod<98>, HeartBtInt<108>, RawDataLength<95>, RawData<96>,
+    // Username<553>, DefaultApplVerID<1137> and NoMsgTypes<384>
+    let mut tags: Vec<_> = presence.tags(Logon::PRESENCE_TAGS).collect();
+    tags.sort_unstable();
+    assert_eq!(tags, [95, 96, 98, 108, 384, 553, 1137]);
+    for tag in Logon::PRESENCE_TAGS {
+        assert_eq!(logon.is_set(*tag), tags.contains(tag), "tag {tag}");
+    }
+    // MsgType<372> is member of MsgTypeGrp entries
+    assert!(!logon.is_set(372));
+    assert!(!logon.is_set(9999));
+}
+
+/// NewOrderSingle<D> with two Parties entries, the first one with nested
+/// PtysSubGrp entry.
+fn order_with_nested_groups() -> Box<FixtMessage> {
+    fixt_message(Box::new(Message::NewOrderSingle(NewOrderSingle {
+        cl_ord_id: FixString::from_ascii_lossy(b"order".to_vec()),
+        parties: Some(vec![
+            Parties {
+                party_id: Some(FixString::from_ascii_lossy(b"party".to_vec())),
+                ptys_sub_grp: Some(vec![PtysSubGrp {
+                    party_sub_id: Some(FixString::from_ascii_lossy(b"sub".to_vec())),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            },
+            Parties {
+                party_id: Some(FixString::from_ascii_lossy(b"other".to_vec())),
+                ..Default::default()
+            },
+        ]),
+        ..Default::default()
+    })))
+}
+
+fn deserialize_with_group_limits(
+    input: &[u8],
+    group_limits: GroupLimits,
+) -> Result<Box<FixtMessage>, DeserializeError> {
+    let (_, raw_msg) = raw_message(input).unwrap();
+    FixtMessage::deserialize(
+        Deserializer::from_raw_message(raw_msg).with_group_limits(group_limits),
+    )
+}
+
+#[test]
+fn group_limits() {
+    let input = order_with_nested_groups().serialize();
+    assert!(deserialize_with_group_limits(&input, GroupLimits::default()).is_ok());
+
+    let max_entries = GroupLimits {
+        max_entries: 1,
+        ..Default::default()
+    };
+    assert_matches!(
+        deserialize_with_group_limits(&input, max_entries),
+        Err(DeserializeError::Reject {
+            tag: Some(453),
+            reason: ParseRejectReason::IncorrectNumingroupCountForRepeatingGroup,
+            ..
+        })
+    );
+
+    let max_depth = GroupLimits {
+        max_depth: 1,
+        ..Default::default()
+    };
+    assert_matches!(
+        deserialize_with_group_limits(&input, max_depth),
+        Err(DeserializeError::Reject {
+            tag: Some(802),
+            reason: ParseRejectReason::IncorrectNumingroupCountForRepeatingGroup,
+            ..
+        })
+    );
+}
+
+/// Replace `from` with `to` in serialized message, fixing BodyLength<9>
+/// and the checksum.
+fn patch_message_len(msg: &FixtMessage, from: &str, to: &str) -> Vec<u8> {
+    let serialized = String::from_utf8(msg.serialize()).unwrap();
+    assert!(serialized.contains(from));
+    let patched = serialized.replace(from, to);
+    let body_start = patched.find("\x0135=").unwrap() + 1;
+    let body_end = patched.len() - "10=000\x01".len();
+    let body = &patched[body_start..body_end];
+    let begin_string_end = patched.find("\x01").unwrap() + 1;
+    let mut patched =
+        format!("{}9={}\x01{body}", &patched[..begin_string_end], body.len()).into_bytes();
+    let checksum = patched.iter().fold(0u8, |acc, b| acc.wrapping_add(*b));
+    patched.extend_from_slice(format!("10={checksum:03}\x01").as_bytes());
+    patched
+}
+
+#[test]
+fn num_in_group_exceeding_message_rejected() {
+    // Entries would not fit in the rest of the message
+    let input = patch_message_len(
+        &order_with_nested_groups(),
+        "\x01453=2\x01",
+        "\x01453=200\x01",
+    );
+    assert_matches!(
+        FixtMessage::from_bytes(&input),
+        Err(DeserializeError::Reject {
+            tag: Some(453),
+            reason: ParseRejectReason::IncorrectNumingroupCountForRepeatingGroup,
+            ..
+        })
+    );
+}
+
+#[test]
+fn fields_visited_in_serialization_order() {
+    let msg = order_with_nested_groups();
+    let serialized = msg.serialize();
+    let (_, raw_msg) = raw_message(&serialized).expect("Invalid message");
+    let serialized_tags = raw_msg
+        .tag_values()
+        .map(|field| field.map(|(tag, _)| tag))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("Scan failed");
+
+    let mut tags = Vec::new();
+    msg.for_each_field(|tag, _| tags.push(tag));
+    // BeginString<8> is not part of raw message body
+    assert_eq!(tags[0], 8);
+    assert_eq!(tags[1..], serialized_tags);
+}
+
+#[test]
+fn field_refs() {
+    let msg = order_with_nested_groups();
+    let mut values = Vec::new();
+    msg.for_each_field(|tag, value| {
+        if matches!(tag, 35 | 34 | 453 | 448 | 523) {
+            values.push(format!("{tag}={value:?}"));
+        }
+    });
+    assert_eq!(
+        values,
+        [
+            "35=Enum(FixStr(\"D\"))",
+            "34=SeqNum(1)",
+            "453=NumInGroup(2)",
+            "448=String(FixStr(\"party\"))",
+            "523=String(FixStr(\"sub\"))",
+            "448=String(FixStr(\"other\"))",
+        ]
+    );
+
+    let logon = Logon {
+        raw_data: Some(b"nonce".as_slice().into()),
+        ..Default::default()
+    };
+    let mut fields = Vec::new();
+    logon.for_each_field(|tag, value| match value {
+        FieldRef::Length(len) => fields.push((tag, len.to_string().into_bytes())),
+        FieldRef::Data(data) => fields.push((tag, data.to_vec())),
+        _ => {}
+    });
+    assert_eq!(fields, [(95, b"5".to_vec()), (96, b"nonce".to_vec())]);
+}
+
+#[test]
+fn fingerprint_ignores_volatile_fields() {
+    let msg = order_with_qty_and_price();
+    let fingerprint = msg.fingerprint();
+
+    let mut resent = msg.clone();
+    resent.header.msg_seq_num = 10;
+    resent.header.poss_dup_flag = Some(true);
+    resent.header.orig_sending_time = Some(msg.header.sending_time);
+    resent.header.sending_time = UtcTimestamp::with_secs(Utc::now());
+    assert_eq!(resent.fingerprint(), fingerprint);
+
+    // The same value, serialized differently
+    let Message::NewOrderSingle(order) = &mut *resent.body else {
+        unreachable!()
+    };
+    order.price = Some(Decimal::new(1000, 2));
+    assert_eq!(resent.fingerprint(), fingerprint);
+
+    let mut changed = msg.clone();
+    let Message::NewOrderSingle(order) = &mut *changed.body else {
+        unreachable!()
+    };
+    order.price = Some(Decimal::from(11));
+    assert_ne!(changed.fingerprint(), fingerprint);
+}
+
+#[test]
+fn fingerprint_excluded_tags() {
+    let msg = order_with_qty_and_price();
+    let mut other = msg.clone();
+    other.header.msg_seq_num = 10;
+    let Message::NewOrderSingle(order) = &mut *other.body else {
+        unreachable!()
+    };
+    order.cl_ord_id = FixString::from_ascii_lossy(b"other".to_vec());
+
+    let with_seq_num = Fingerprinter::new().include(34);
+    assert_ne!(
+        with_seq_num.fingerprint(&*msg),
+        with_seq_num.fingerprint(&*other)
+    );
+    let without_cl_ord_id = Fingerprinter::new().exclude(11);
+    assert_eq!(
+        without_cl_ord_id.fingerprint(&*msg),
+        without_cl_ord_id.fingerprint(&*other)
+    );
+    // Body only
+    assert_eq!(
+        Fingerprinter::with_excluded_tags([11]).fingerprint(&*msg.body),
+        Fingerprinter::with_excluded_tags([11]).fingerprint(&*other.body)
+    );
+    assert_eq!(msg.fingerprint().to_string().len(), 32);
+}
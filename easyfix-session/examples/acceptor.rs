@@ -1,11 +1,6 @@
 use std::{collections::HashMap, net::SocketAddr, time::Duration};
 
-use chrono::NaiveTime;
-use easyfix_macros::fix_str;
-use easyfix_messages::{
-    fields::{FixStr, FixString},
-    messages::Header,
-};
+use easyfix_messages::{fields::FixString, messages::Header};
 use easyfix_session::{
     acceptor::{Acceptor, TcpConnection},
     application::{AsEvent, FixEvent},
@@ -15,7 +10,7 @@ use easyfix_session::{
 };
 use tokio::{runtime::Builder, task::LocalSet};
 use tokio_stream::StreamExt;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 async fn acceptor() {
     let settings = Settings {
@@ -24,6 +19,19 @@ async fn acceptor() {
         heartbeat_interval: Duration::from_secs(10),
         auto_disconnect_after_no_logon_received: Duration::from_secs(3),
         auto_disconnect_after_no_heartbeat: 3,
+        buffer_pool: None,
+        duplicate_tags: Default::default(),
+        other_msg_types: false,
+        required_tags: Default::default(),
+        max_bytes_before_logon: None,
+        pre_logon: Default::default(),
+        session_log: None,
+        tcp_keepalive: None,
+        read_idle: None,
+        buffers: Default::default(),
+        group_limits: Default::default(),
+        framing_errors: Default::default(),
+        capture_dir: None,
     };
 
     let mut acceptor = Acceptor::new(settings.clone(), Box::new(|_| InMemoryStorage::new()));
@@ -42,29 +50,9 @@ async fn acceptor() {
         acceptor.register_session(
             session_id.clone(),
             SessionSettings {
-                session_id,
                 // session_time: UtcTimeOnly::from_hms(8, 0, 0)..=UtcTimeOnly::from_hms(16, 0, 0),
                 //logon_time: UtcTimeOnly::from_hms(7, 30, 0)..=UtcTimeOnly::from_hms(16, 30, 0),
-                session_time: NaiveTime::from_hms_opt(0, 0, 0).unwrap()
-                    ..=NaiveTime::from_hms_opt(23, 59, 59).unwrap(),
-                logon_time: NaiveTime::from_hms_opt(0, 0, 0).unwrap()
-                    ..=NaiveTime::from_hms_opt(23, 59, 59).unwrap(),
-
-                send_redundant_resend_requests: false,
-                check_comp_id: true,
-                check_latency: true,
-                max_latency: Duration::from_secs(120),
-
-                reset_on_logon: false,
-                reset_on_logout: false,
-                reset_on_disconnect: false,
-                sender_default_appl_ver_id: fix_str!("9").to_owned(),
-                target_default_appl_ver_id: fix_str!("9").to_owned(),
-                persist: false,
-                refresh_on_logon: false,
-                enable_next_expected_msg_seq_num: true,
-
-                verify_logout: true,
+                ..SessionSettings::new(session_id)
             },
         );
     };
@@ -97,6 +85,15 @@ async fn acceptor() {
                 let _ = senders.get(&session_id).unwrap().send_raw(msg);
             }
             FixEvent::AdmMsgIn(msg, _responder) => info!("Adm input msg: {:?}", msg.msg_type()),
+            FixEvent::PartialAppMsgIn(msg, field_errors, _responder) => {
+                warn!(
+                    "App input msg: {:?} with invalid fields: {field_errors:?}",
+                    msg.msg_type()
+                );
+            }
+            FixEvent::TestAppMsgIn(msg, _responder) => {
+                warn!("Test app input msg: {:?}", msg.msg_type())
+            }
             FixEvent::AppMsgOut(msg, _responder) => {
                 info!("App output msg: {:?}", msg.msg_type());
                 _responder.do_not_send();
@@ -105,6 +102,59 @@ async fn acceptor() {
             FixEvent::DeserializeError(session_id, error) => {
                 error!("{session_id}: {error}");
             }
+            FixEvent::Resent(session_id, report) => info!("Resent: {session_id}, {report:?}"),
+            FixEvent::Reject(session_id, report) => warn!("Reject: {session_id}, {report:?}"),
+            FixEvent::SequenceResetConfirm {
+                session_id,
+                next_target_msg_seq_num,
+                new_seq_no,
+                confirmation,
+            } => {
+                warn!("SequenceReset: {session_id}, {next_target_msg_seq_num} -> {new_seq_no}");
+                confirmation.accept();
+            }
+            FixEvent::LogoutGrace(session_id, outcome) => {
+                info!("Logout grace: {session_id}, {outcome:?}")
+            }
+            FixEvent::LogonMismatch(session_id, mismatch) => {
+                error!("Logon mismatch: {session_id}, {mismatch:?}")
+            }
+            FixEvent::LogoutDetails(session_id, details) => {
+                warn!("Logout details: {session_id}, {details:?}")
+            }
+            FixEvent::StoreFailed(session_id, failure) => {
+                error!("Store failed: {session_id}, {failure:?}")
+            }
+            FixEvent::AppMsgExpired(session_id, expired) => {
+                warn!("App msg expired: {session_id}, {expired:?}")
+            }
+            FixEvent::AppMsgDiscarded(session_id, discarded) => {
+                warn!("App msg discarded: {session_id}, {discarded:?}")
+            }
+            FixEvent::BeginStringMismatch(session_id, mismatch) => {
+                error!("BeginString mismatch: {session_id}, {mismatch:?}")
+            }
+            FixEvent::SerializeFailed(session_id, failure) => {
+                error!("Serialize failed: {session_id}, {failure:?}")
+            }
+            FixEvent::HeartbeatReply { session_id, .. } => {
+                info!("Heartbeat reply: {session_id}")
+            }
+            FixEvent::ReadIdle(session_id, idle) => {
+                warn!("Read idle: {session_id}, {idle:?}")
+            }
+            FixEvent::MsgFlagged(session_id, flagged) => {
+                warn!("Message flagged: {session_id}, {flagged:?}")
+            }
+            FixEvent::LatencyBudgetExceeded(session_id, alert) => {
+                warn!("Latency budget exceeded: {session_id}, {alert:?}")
+            }
+            FixEvent::FramingError(session_id, error) => {
+                error!("Framing error: {session_id}, {error:?}")
+            }
+            FixEvent::SessionStatus(session_id, report) => {
+                info!("Session status: {session_id}, {report:?}")
+            }
         }
         // info!("{:?}", entry.as_event());
     }
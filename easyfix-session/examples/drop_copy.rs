@@ -0,0 +1,269 @@
+//! Drop copy, every ExecutionReport<8> sent to trading session is copied
+//! to drop copy session, with CopyMsgIndicator<797> set.
+//!
+//! Acceptor listens on `127.0.0.1:10061` for `TRADER` and `DROPCOPY`
+//! sessions. Every NewOrderSingle<D> received from `TRADER` is acknowledged
+//! with ExecutionReport<8>. Copies sent while `DROPCOPY` is not logged on
+//! are dropped.
+//!
+//! Usage: `cargo run --example drop_copy`
+//!
+//! The example is also run by `cargo test --examples`, with trading and
+//! drop copy [`Initiator`](easyfix_session::initiator::Initiator)s
+//! connected over in-memory streams.
+
+use std::{collections::HashMap, net::SocketAddr, time::Duration};
+
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{ExecType, FixStr, FixString, OrdStatus},
+    messages::{ExecutionReport, Message},
+};
+use easyfix_session::{
+    acceptor::{Acceptor, TcpConnection},
+    application::{AsEvent, FixEvent},
+    messages_storage::InMemoryStorage,
+    session_id::SessionId,
+    settings::{SessionSettings, Settings},
+    Sender,
+};
+use tokio::{runtime::Builder, task::LocalSet};
+use tokio_stream::StreamExt;
+use tracing::{info, warn};
+
+const ADDR: &str = "127.0.0.1:10061";
+
+const TRADER: &FixStr = fix_str!("TRADER");
+const DROPCOPY: &FixStr = fix_str!("DROPCOPY");
+
+fn settings(sender_comp_id: &FixStr) -> Settings {
+    Settings {
+        sender_comp_id: sender_comp_id.to_owned(),
+        sender_sub_id: None,
+        heartbeat_interval: Duration::from_secs(10),
+        auto_disconnect_after_no_logon_received: Duration::from_secs(3),
+        auto_disconnect_after_no_heartbeat: 3,
+        buffer_pool: None,
+        duplicate_tags: Default::default(),
+        other_msg_types: false,
+        required_tags: Default::default(),
+        max_bytes_before_logon: None,
+        pre_logon: Default::default(),
+        session_log: None,
+        tcp_keepalive: None,
+        read_idle: None,
+        buffers: Default::default(),
+        group_limits: Default::default(),
+        framing_errors: Default::default(),
+        capture_dir: None,
+    }
+}
+
+fn session_settings(sender_comp_id: &FixStr, target_comp_id: &FixStr) -> SessionSettings {
+    SessionSettings::new(SessionId::new(
+        fix_str!("FIXT.1.1").to_owned(),
+        sender_comp_id.to_owned(),
+        target_comp_id.to_owned(),
+    ))
+}
+
+fn acceptor() -> Acceptor<InMemoryStorage> {
+    let mut acceptor = Acceptor::new(
+        settings(fix_str!("SERVER")),
+        Box::new(|_| InMemoryStorage::new()),
+    );
+    for target_comp_id in [TRADER, DROPCOPY] {
+        let session_settings = session_settings(fix_str!("SERVER"), target_comp_id);
+        acceptor.register_session(session_settings.session_id.clone(), session_settings);
+    }
+    acceptor
+}
+
+/// Acknowledge orders of `TRADER` and copy sent execution reports
+/// to `DROPCOPY`.
+async fn serve(mut acceptor: Acceptor<InMemoryStorage>) {
+    let mut senders: HashMap<FixString, Sender> = HashMap::new();
+    let mut next_id = 1;
+    while let Some(mut entry) = acceptor.next().await {
+        match entry.as_event() {
+            FixEvent::Logon(session_id, sender) => {
+                info!("Logon: {session_id}");
+                senders.insert(session_id.target_comp_id().to_owned(), sender);
+            }
+            FixEvent::Logout(session_id, reason) => {
+                info!("Logout: {session_id}, reason: {reason:?}");
+                senders.remove(session_id.target_comp_id());
+            }
+            FixEvent::AppMsgIn(msg, responder) => match &*msg.body {
+                Message::NewOrderSingle(order) if msg.header.sender_comp_id == TRADER => {
+                    let execution_report = ExecutionReport {
+                        order_id: FixString::from_ascii_lossy(format!("O{next_id}").into_bytes()),
+                        exec_id: FixString::from_ascii_lossy(format!("E{next_id}").into_bytes()),
+                        cl_ord_id: Some(order.cl_ord_id.clone()),
+                        exec_type: ExecType::New,
+                        ord_status: OrdStatus::New,
+                        side: order.side,
+                        leaves_qty: order.order_qty.unwrap_or_default(),
+                        ..Default::default()
+                    };
+                    next_id += 1;
+                    if let Some(sender) = senders.get(TRADER) {
+                        let _ = sender.send(Box::new(Message::ExecutionReport(execution_report)));
+                    }
+                }
+                _ => responder.unsupported(&msg),
+            },
+            FixEvent::AppMsgOut(msg, _responder) if msg.header.target_comp_id == TRADER => {
+                if let Message::ExecutionReport(execution_report) = &*msg.body {
+                    let Some(sender) = senders.get(DROPCOPY) else {
+                        warn!("{DROPCOPY} not logged on, copy dropped");
+                        continue;
+                    };
+                    let copy = ExecutionReport {
+                        copy_msg_indicator: Some(true),
+                        ..execution_report.clone()
+                    };
+                    let _ = sender.send(Box::new(Message::ExecutionReport(copy)));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn main() {
+    tracing_subscriber::fmt::init();
+
+    let runtime = Builder::new_current_thread()
+        .enable_io()
+        .enable_time()
+        .build()
+        .unwrap();
+
+    LocalSet::new().block_on(&runtime, async {
+        let acceptor = acceptor();
+        let connection = TcpConnection::new(ADDR.parse::<SocketAddr>().unwrap())
+            .await
+            .unwrap();
+        acceptor.start(connection);
+        info!("Listening on {ADDR}");
+        serve(acceptor).await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use easyfix_messages::{
+        fields::{Decimal, Side, Utc, UtcTimestamp},
+        messages::{FixtMessage, NewOrderSingle},
+    };
+    use easyfix_session::{application::SessionSummary, initiator::Initiator};
+    use tokio::{sync::oneshot, task::JoinHandle, time::timeout};
+
+    use super::*;
+
+    /// Connect initiator of `sender_comp_id` session to `acceptor` over
+    /// in-memory stream.
+    fn connect(
+        acceptor: &Acceptor<InMemoryStorage>,
+        sender_comp_id: &FixStr,
+    ) -> (
+        Pin<Box<Initiator<InMemoryStorage>>>,
+        JoinHandle<SessionSummary>,
+    ) {
+        let (server, client) = tokio::io::duplex(64 * 1024);
+        let (reader, writer) = tokio::io::split(server);
+        tokio::task::spawn_local(acceptor.run_session_task(
+            SocketAddr::from(([127, 0, 0, 1], 0)),
+            reader,
+            writer,
+        ));
+        let initiator = Box::pin(Initiator::new(
+            settings(sender_comp_id),
+            session_settings(sender_comp_id, fix_str!("SERVER")),
+            InMemoryStorage::new(),
+        ));
+        let (reader, writer) = tokio::io::split(client);
+        let session = tokio::task::spawn_local(initiator.run_session_task(reader, writer));
+        (initiator, session)
+    }
+
+    /// Wait for Logon<A>, send `msg` if given, and return first received
+    /// application message.
+    async fn exchange(
+        mut initiator: Pin<Box<Initiator<InMemoryStorage>>>,
+        mut msg: Option<Box<Message>>,
+        mut logged_on: Option<oneshot::Sender<()>>,
+    ) -> Box<FixtMessage> {
+        while let Some(mut entry) = initiator.next().await {
+            match entry.as_event() {
+                FixEvent::Logon(_, sender) => {
+                    if let Some(msg) = msg.take() {
+                        sender.send(msg).unwrap();
+                    }
+                    if let Some(logged_on) = logged_on.take() {
+                        logged_on.send(()).unwrap();
+                    }
+                }
+                FixEvent::AppMsgIn(msg, _) => return msg,
+                _ => {}
+            }
+        }
+        panic!("events stream closed");
+    }
+
+    #[test]
+    fn execution_report_copied() {
+        let runtime = Builder::new_current_thread()
+            .enable_io()
+            .enable_time()
+            .build()
+            .unwrap();
+
+        LocalSet::new().block_on(&runtime, async {
+            let acceptor = acceptor();
+            let (drop_copy, _drop_copy_session) = connect(&acceptor, DROPCOPY);
+            let (trader, _trader_session) = connect(&acceptor, TRADER);
+            tokio::task::spawn_local(serve(acceptor));
+
+            // Order is sent once drop copy session is logged on
+            let (logged_on_tx, logged_on_rx) = oneshot::channel();
+            let drop_copy = tokio::task::spawn_local(exchange(drop_copy, None, Some(logged_on_tx)));
+            timeout(Duration::from_secs(5), logged_on_rx)
+                .await
+                .expect("drop copy logon timed out")
+                .unwrap();
+            let order = Box::new(Message::NewOrderSingle(NewOrderSingle {
+                cl_ord_id: fix_str!("ORDER1").to_owned(),
+                side: Side::Buy,
+                transact_time: UtcTimestamp::with_millis(Utc::now()),
+                order_qty: Some(Decimal::from(100)),
+                ..Default::default()
+            }));
+            let trader = tokio::task::spawn_local(exchange(trader, Some(order), None));
+
+            let (report, copy) = timeout(Duration::from_secs(5), async {
+                (trader.await.unwrap(), drop_copy.await.unwrap())
+            })
+            .await
+            .expect("execution reports timed out");
+            let (Message::ExecutionReport(report), Message::ExecutionReport(copy)) =
+                (*report.body, *copy.body)
+            else {
+                panic!("ExecutionReport<8> expected");
+            };
+            assert_eq!(report.cl_ord_id.as_deref(), Some(fix_str!("ORDER1")));
+            assert_eq!(report.leaves_qty, Decimal::from(100));
+            assert_eq!(report.copy_msg_indicator, None);
+            assert_eq!(
+                copy,
+                ExecutionReport {
+                    copy_msg_indicator: Some(true),
+                    ..report
+                }
+            );
+        });
+    }
+}
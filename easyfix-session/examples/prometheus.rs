@@ -0,0 +1,127 @@
+//! Acceptor with Prometheus metrics endpoint.
+//!
+//! Acceptor listens on `127.0.0.1:10052`, acceptor metrics and per-session
+//! statistics (labeled with `sender`, `target` and `qualifier`) are served
+//! in Prometheus text format on `http://127.0.0.1:9100/metrics`.
+//!
+//! Usage: `cargo run --example prometheus --features prometheus`
+
+use std::{net::SocketAddr, time::Duration};
+
+use easyfix_macros::fix_str;
+use easyfix_messages::fields::{FixStr, FixString};
+use easyfix_session::{
+    acceptor::{Acceptor, TcpConnection},
+    application::{AsEvent, FixEvent},
+    messages_storage::InMemoryStorage,
+    session_id::SessionId,
+    settings::{SessionSettings, Settings},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    runtime::Builder,
+    task::LocalSet,
+};
+use tokio_stream::StreamExt;
+use tracing::{info, warn};
+
+const FIX_ADDR: &str = "127.0.0.1:10052";
+const METRICS_ADDR: &str = "127.0.0.1:9100";
+
+fn session_settings(session_id: SessionId) -> SessionSettings {
+    SessionSettings::new(session_id)
+}
+
+/// Minimal HTTP/1.1 responder, any request gets metrics in response.
+async fn serve_metrics(mut stream: TcpStream, body: String) {
+    let mut request = [0; 1024];
+    if let Err(err) = stream.read(&mut request).await {
+        warn!("failed to read metrics request: {err}");
+        return;
+    }
+    let response = format!(
+        "HTTP/1.1 200 OK\r\n\
+         Content-Type: text/plain; version=0.0.4\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    );
+    if let Err(err) = stream.write_all(response.as_bytes()).await {
+        warn!("failed to write metrics response: {err}");
+    }
+}
+
+async fn acceptor() {
+    let settings = Settings {
+        sender_comp_id: FixString::from_ascii_lossy(b"easyfix-acceptor".to_vec()),
+        sender_sub_id: None,
+        heartbeat_interval: Duration::from_secs(10),
+        auto_disconnect_after_no_logon_received: Duration::from_secs(3),
+        auto_disconnect_after_no_heartbeat: 3,
+        buffer_pool: None,
+        duplicate_tags: Default::default(),
+        other_msg_types: false,
+        required_tags: Default::default(),
+        max_bytes_before_logon: None,
+        pre_logon: Default::default(),
+        session_log: None,
+        tcp_keepalive: None,
+        read_idle: None,
+        buffers: Default::default(),
+        group_limits: Default::default(),
+        framing_errors: Default::default(),
+        capture_dir: None,
+    };
+
+    let mut acceptor = Acceptor::new(settings.clone(), Box::new(|_| InMemoryStorage::new()));
+    for target_comp_id in ["client_1", "client_2"] {
+        let session_id = SessionId::new(
+            FixString::from_ascii_lossy(b"FIXT.1.1".to_vec()),
+            settings.sender_comp_id.clone(),
+            FixString::from_ascii_lossy(target_comp_id.as_bytes().to_vec()),
+        );
+        acceptor.register_session(session_id.clone(), session_settings(session_id));
+    }
+
+    let connection = TcpConnection::new(FIX_ADDR.parse::<SocketAddr>().unwrap())
+        .await
+        .unwrap();
+    acceptor.start(connection);
+    let metrics_listener = TcpListener::bind(METRICS_ADDR).await.unwrap();
+    info!("Metrics available on http://{METRICS_ADDR}/metrics");
+
+    loop {
+        tokio::select! {
+            entry = acceptor.next() => {
+                let Some(mut entry) = entry else {
+                    break;
+                };
+                if let FixEvent::Logout(session_id, reason) = entry.as_event() {
+                    info!("Logout: {session_id}, reason: {reason:?}");
+                }
+            }
+            accepted = metrics_listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    // Metrics are encoded here, as acceptor is not `Send`
+                    // nor shared with spawned tasks
+                    let body = acceptor.prometheus_metrics();
+                    tokio::task::spawn_local(serve_metrics(stream, body));
+                }
+                Err(err) => warn!("failed to accept metrics connection: {err}"),
+            },
+        }
+    }
+}
+
+fn main() {
+    tracing_subscriber::fmt::init();
+
+    let runtime = Builder::new_current_thread()
+        .enable_io()
+        .enable_time()
+        .build()
+        .unwrap();
+
+    LocalSet::new().block_on(&runtime, acceptor());
+}
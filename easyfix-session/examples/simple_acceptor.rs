@@ -0,0 +1,194 @@
+//! Minimal acceptor, every News<B> received is sent back to the client.
+//!
+//! Acceptor listens on `127.0.0.1:10060` for `CLIENT` session, other
+//! application messages are declined with BusinessMessageReject<j>.
+//!
+//! Usage: `cargo run --example simple_acceptor`, then
+//! `cargo run --example simple_initiator` to connect.
+//!
+//! The example is also run by `cargo test --examples`, with
+//! [`Initiator`](easyfix_session::initiator::Initiator) connected over
+//! in-memory stream.
+
+use std::{net::SocketAddr, time::Duration};
+
+use easyfix_macros::fix_str;
+use easyfix_messages::{fields::FixStr, messages::Message};
+use easyfix_session::{
+    acceptor::{Acceptor, TcpConnection},
+    application::{AsEvent, FixEvent},
+    messages_storage::InMemoryStorage,
+    session_id::SessionId,
+    settings::{SessionSettings, Settings},
+    Sender,
+};
+use tokio::{runtime::Builder, task::LocalSet};
+use tokio_stream::StreamExt;
+use tracing::{info, warn};
+
+const ADDR: &str = "127.0.0.1:10060";
+
+fn settings(sender_comp_id: &FixStr) -> Settings {
+    Settings {
+        sender_comp_id: sender_comp_id.to_owned(),
+        sender_sub_id: None,
+        heartbeat_interval: Duration::from_secs(10),
+        auto_disconnect_after_no_logon_received: Duration::from_secs(3),
+        auto_disconnect_after_no_heartbeat: 3,
+        buffer_pool: None,
+        duplicate_tags: Default::default(),
+        other_msg_types: false,
+        required_tags: Default::default(),
+        max_bytes_before_logon: None,
+        pre_logon: Default::default(),
+        session_log: None,
+        tcp_keepalive: None,
+        read_idle: None,
+        buffers: Default::default(),
+        group_limits: Default::default(),
+        framing_errors: Default::default(),
+        capture_dir: None,
+    }
+}
+
+fn session_settings(sender_comp_id: &FixStr, target_comp_id: &FixStr) -> SessionSettings {
+    SessionSettings::new(SessionId::new(
+        fix_str!("FIXT.1.1").to_owned(),
+        sender_comp_id.to_owned(),
+        target_comp_id.to_owned(),
+    ))
+}
+
+fn acceptor() -> Acceptor<InMemoryStorage> {
+    let mut acceptor = Acceptor::new(
+        settings(fix_str!("SERVER")),
+        Box::new(|_| InMemoryStorage::new()),
+    );
+    let session_settings = session_settings(fix_str!("SERVER"), fix_str!("CLIENT"));
+    acceptor.register_session(session_settings.session_id.clone(), session_settings);
+    acceptor
+}
+
+/// Handle session events, News<B> messages are sent back.
+async fn serve(mut acceptor: Acceptor<InMemoryStorage>) {
+    let mut sender: Option<Sender> = None;
+    while let Some(mut entry) = acceptor.next().await {
+        match entry.as_event() {
+            FixEvent::Logon(session_id, new_sender) => {
+                info!("Logon: {session_id}");
+                sender = Some(new_sender);
+            }
+            FixEvent::Logout(session_id, reason) => {
+                info!("Logout: {session_id}, reason: {reason:?}");
+                sender = None;
+            }
+            FixEvent::AppMsgIn(msg, responder) => match &*msg.body {
+                Message::News(news) => {
+                    info!("News: {}", news.headline);
+                    if let Some(sender) = &sender {
+                        if let Err(err) = sender.send(Box::new(Message::News(news.clone()))) {
+                            warn!("failed to send News<B> back: {err}");
+                        }
+                    }
+                }
+                _ => responder.unsupported(&msg),
+            },
+            _ => {}
+        }
+    }
+}
+
+fn main() {
+    tracing_subscriber::fmt::init();
+
+    let runtime = Builder::new_current_thread()
+        .enable_io()
+        .enable_time()
+        .build()
+        .unwrap();
+
+    LocalSet::new().block_on(&runtime, async {
+        let acceptor = acceptor();
+        let connection = TcpConnection::new(ADDR.parse::<SocketAddr>().unwrap())
+            .await
+            .unwrap();
+        acceptor.start(connection);
+        info!("Listening on {ADDR}");
+        serve(acceptor).await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use easyfix_messages::{fields::FixString, groups::LinesOfTextGrp, messages::News};
+    use easyfix_session::{initiator::Initiator, DisconnectReason};
+    use tokio::time::timeout;
+
+    use super::*;
+
+    #[test]
+    fn news_sent_back() {
+        let runtime = Builder::new_current_thread()
+            .enable_io()
+            .enable_time()
+            .build()
+            .unwrap();
+
+        LocalSet::new().block_on(&runtime, async {
+            let acceptor = acceptor();
+            let (server, client) = tokio::io::duplex(64 * 1024);
+            let (reader, writer) = tokio::io::split(server);
+            tokio::task::spawn_local(acceptor.run_session_task(
+                SocketAddr::from(([127, 0, 0, 1], 0)),
+                reader,
+                writer,
+            ));
+            tokio::task::spawn_local(serve(acceptor));
+
+            let initiator = Initiator::new(
+                settings(fix_str!("CLIENT")),
+                session_settings(fix_str!("CLIENT"), fix_str!("SERVER")),
+                InMemoryStorage::new(),
+            );
+            let (reader, writer) = tokio::io::split(client);
+            let session = tokio::task::spawn_local(initiator.run_session_task(reader, writer));
+            tokio::pin!(initiator);
+
+            let mut headline: Option<FixString> = None;
+            while let Some(mut entry) = timeout(Duration::from_secs(5), initiator.next())
+                .await
+                .expect("session timed out")
+            {
+                match entry.as_event() {
+                    FixEvent::Logon(_, sender) => {
+                        sender
+                            .send(Box::new(Message::News(News {
+                                headline: fix_str!("hello").to_owned(),
+                                lines_of_text_grp: vec![LinesOfTextGrp {
+                                    text: fix_str!("world").to_owned(),
+                                    ..Default::default()
+                                }],
+                                ..Default::default()
+                            })))
+                            .unwrap();
+                    }
+                    FixEvent::AppMsgIn(msg, _) => {
+                        if let Message::News(news) = *msg.body {
+                            headline = Some(news.headline);
+                        }
+                        initiator.logout(None, None);
+                    }
+                    FixEvent::Logout(..) => break,
+                    _ => {}
+                }
+            }
+
+            assert_eq!(headline.as_deref(), Some(fix_str!("hello")));
+            let summary = session.await.unwrap();
+            assert_eq!(
+                summary.disconnect_reason,
+                DisconnectReason::LocalRequestedLogout
+            );
+        });
+    }
+}
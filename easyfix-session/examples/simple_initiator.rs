@@ -0,0 +1,194 @@
+//! Minimal initiator, logs on, sends News<B>, waits until it's sent back
+//! and logs out.
+//!
+//! Initiator connects to `127.0.0.1:10060` as `CLIENT`.
+//!
+//! Usage: `cargo run --example simple_acceptor`, then
+//! `cargo run --example simple_initiator`.
+//!
+//! The example is also run by `cargo test --examples`, with
+//! [`Acceptor`](easyfix_session::acceptor::Acceptor) connected over
+//! in-memory stream.
+
+use std::{net::SocketAddr, pin::Pin, time::Duration};
+
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, FixString},
+    groups::LinesOfTextGrp,
+    messages::{Message, News},
+};
+use easyfix_session::{
+    application::{AsEvent, FixEvent},
+    initiator::Initiator,
+    messages_storage::InMemoryStorage,
+    session_id::SessionId,
+    settings::{SessionSettings, Settings},
+};
+use tokio::{runtime::Builder, task::LocalSet};
+use tokio_stream::StreamExt;
+use tracing::info;
+
+const ADDR: &str = "127.0.0.1:10060";
+
+fn settings(sender_comp_id: &FixStr) -> Settings {
+    Settings {
+        sender_comp_id: sender_comp_id.to_owned(),
+        sender_sub_id: None,
+        heartbeat_interval: Duration::from_secs(10),
+        auto_disconnect_after_no_logon_received: Duration::from_secs(3),
+        auto_disconnect_after_no_heartbeat: 3,
+        buffer_pool: None,
+        duplicate_tags: Default::default(),
+        other_msg_types: false,
+        required_tags: Default::default(),
+        max_bytes_before_logon: None,
+        pre_logon: Default::default(),
+        session_log: None,
+        tcp_keepalive: None,
+        read_idle: None,
+        buffers: Default::default(),
+        group_limits: Default::default(),
+        framing_errors: Default::default(),
+        capture_dir: None,
+    }
+}
+
+fn session_settings(sender_comp_id: &FixStr, target_comp_id: &FixStr) -> SessionSettings {
+    SessionSettings::new(SessionId::new(
+        fix_str!("FIXT.1.1").to_owned(),
+        sender_comp_id.to_owned(),
+        target_comp_id.to_owned(),
+    ))
+}
+
+fn initiator() -> Initiator<InMemoryStorage> {
+    Initiator::new(
+        settings(fix_str!("CLIENT")),
+        session_settings(fix_str!("CLIENT"), fix_str!("SERVER")),
+        InMemoryStorage::new(),
+    )
+}
+
+/// Send News<B> once logged on and log out when it's sent back, returns
+/// headline of received News<B>.
+async fn run(mut initiator: Pin<&mut Initiator<InMemoryStorage>>) -> Option<FixString> {
+    let mut headline = None;
+    while let Some(mut entry) = initiator.next().await {
+        match entry.as_event() {
+            FixEvent::Logon(session_id, sender) => {
+                info!("Logon: {session_id}");
+                let news = News {
+                    headline: fix_str!("hello").to_owned(),
+                    lines_of_text_grp: vec![LinesOfTextGrp {
+                        text: fix_str!("world").to_owned(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                };
+                if let Err(err) = sender.send(Box::new(Message::News(news))) {
+                    info!("failed to send News<B>: {err}");
+                    break;
+                }
+            }
+            FixEvent::AppMsgIn(msg, _responder) => {
+                if let Message::News(news) = *msg.body {
+                    info!("News: {}", news.headline);
+                    headline = Some(news.headline);
+                    initiator.logout(None, None);
+                }
+            }
+            FixEvent::Logout(session_id, reason) => {
+                info!("Logout: {session_id}, reason: {reason:?}");
+                break;
+            }
+            _ => {}
+        }
+    }
+    headline
+}
+
+fn main() {
+    tracing_subscriber::fmt::init();
+
+    let runtime = Builder::new_current_thread()
+        .enable_io()
+        .enable_time()
+        .build()
+        .unwrap();
+
+    LocalSet::new().block_on(&runtime, async {
+        let initiator = initiator();
+        tokio::pin!(initiator);
+        let session = initiator
+            .connect(ADDR.parse::<SocketAddr>().unwrap())
+            .await
+            .expect("failed to connect");
+        run(initiator.as_mut()).await;
+        let summary = session.await.unwrap();
+        info!("Session closed: {:?}", summary.disconnect_reason);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use easyfix_session::{acceptor::Acceptor, DisconnectReason, Sender};
+    use tokio::time::timeout;
+
+    use super::*;
+
+    /// Acceptor sending every received application message back.
+    async fn echo(mut acceptor: Acceptor<InMemoryStorage>) {
+        let mut sender: Option<Sender> = None;
+        while let Some(mut entry) = acceptor.next().await {
+            match entry.as_event() {
+                FixEvent::Logon(_, new_sender) => sender = Some(new_sender),
+                FixEvent::AppMsgIn(msg, _) => {
+                    let _ = sender.as_ref().unwrap().send(msg.body);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn logon_news_logout() {
+        let runtime = Builder::new_current_thread()
+            .enable_io()
+            .enable_time()
+            .build()
+            .unwrap();
+
+        LocalSet::new().block_on(&runtime, async {
+            let mut acceptor = Acceptor::new(
+                settings(fix_str!("SERVER")),
+                Box::new(|_| InMemoryStorage::new()),
+            );
+            let session_settings = session_settings(fix_str!("SERVER"), fix_str!("CLIENT"));
+            acceptor.register_session(session_settings.session_id.clone(), session_settings);
+            let (server, client) = tokio::io::duplex(64 * 1024);
+            let (reader, writer) = tokio::io::split(server);
+            tokio::task::spawn_local(acceptor.run_session_task(
+                SocketAddr::from(([127, 0, 0, 1], 0)),
+                reader,
+                writer,
+            ));
+            tokio::task::spawn_local(echo(acceptor));
+
+            let initiator = initiator();
+            tokio::pin!(initiator);
+            let (reader, writer) = tokio::io::split(client);
+            let session = tokio::task::spawn_local(initiator.run_session_task(reader, writer));
+            let headline = timeout(Duration::from_secs(5), run(initiator.as_mut()))
+                .await
+                .expect("session timed out");
+
+            assert_eq!(headline.as_deref(), Some(fix_str!("hello")));
+            let summary = session.await.unwrap();
+            assert_eq!(
+                summary.disconnect_reason,
+                DisconnectReason::LocalRequestedLogout
+            );
+        });
+    }
+}
@@ -0,0 +1,411 @@
+//! Long running stability test.
+//!
+//! Runs acceptor and initiator in a single process, initiator sends
+//! `News<B>` messages at configured rate, acceptor echoes them back.
+//! Periodically reports throughput, messages in flight, resident memory
+//! and MsgSeqNum<34>/echo ordering violations.
+//!
+//! Messages storage used on both sides is selected with `STORAGE`:
+//! - `memory` (default) - `InMemoryStorage`, sent messages are kept for
+//!   resend, so resident memory grows with number of sent messages,
+//! - `persistent` - `InMemoryStorage` with sequence numbers persisted in
+//!   files in temporary directory, exercises write-ahead allocation,
+//! - `null` - `NullStorage`, nothing is kept, so memory usage should stay
+//!   flat, any steady growth of resident memory means something leaks.
+//!
+//! Usage: `cargo run --release --example soak -- [MSG_PER_SEC] [DURATION_SECS] [REPORT_SECS] [STORAGE]`
+
+use std::{
+    cell::Cell,
+    net::SocketAddr,
+    path::PathBuf,
+    process::ExitCode,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, FixString, SeqNum, ToFixString},
+    groups::LinesOfTextGrp,
+    messages::{FixtMessage, Header, Message, News},
+};
+use easyfix_session::{
+    acceptor::{Acceptor, TcpConnection},
+    application::{AsEvent, FixEvent},
+    initiator::Initiator,
+    messages_storage::{InMemoryStorage, MessagesStorage, NullStorage, PersistentSeqNums},
+    session_id::SessionId,
+    settings::{SessionSettings, Settings},
+    Sender,
+};
+use tokio::{runtime::Builder, task::LocalSet, time};
+use tokio_stream::StreamExt;
+use tracing::{error, info, warn};
+
+const ADDR: &str = "127.0.0.1:10051";
+const TICKS_PER_SEC: u64 = 100;
+
+#[derive(Clone, Copy, Debug)]
+enum StorageKind {
+    Null,
+    Memory,
+    Persistent,
+}
+
+impl StorageKind {
+    fn parse(arg: &str) -> StorageKind {
+        match arg {
+            "null" => StorageKind::Null,
+            "memory" => StorageKind::Memory,
+            "persistent" => StorageKind::Persistent,
+            _ => panic!("invalid storage `{arg}`, expected null, memory or persistent"),
+        }
+    }
+}
+
+struct Config {
+    msg_per_sec: u64,
+    duration: Duration,
+    report_interval: Duration,
+    storage: StorageKind,
+}
+
+impl Config {
+    fn from_args() -> Config {
+        let mut args = std::env::args().skip(1);
+        let mut number = || {
+            args.next()
+                .map(|arg| arg.parse::<u64>().expect("invalid argument"))
+        };
+        let msg_per_sec = number().unwrap_or(1000);
+        let duration = Duration::from_secs(number().unwrap_or(3600));
+        let report_interval = Duration::from_secs(number().unwrap_or(10));
+        Config {
+            msg_per_sec,
+            duration,
+            report_interval,
+            storage: args
+                .next()
+                .map_or(StorageKind::Memory, |arg| StorageKind::parse(&arg)),
+        }
+    }
+}
+
+/// Sequence numbers file of `side` in temporary directory.
+fn seq_nums_path(side: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("easyfix-soak-{}-{side}", std::process::id()))
+}
+
+/// Storage with sequence numbers file of `side`, removed when opened for
+/// the first time, so every run starts from 1.
+fn persistent_storage(side: &str) -> PersistentSeqNums<InMemoryStorage> {
+    let path = seq_nums_path(side);
+    let _ = std::fs::remove_file(&path);
+    PersistentSeqNums::open(path, InMemoryStorage::new()).expect("failed to open seq nums file")
+}
+
+/// Checks that MsgSeqNum<34> of inbound messages increases by one.
+#[derive(Default)]
+struct SeqNumTracker {
+    last: Option<SeqNum>,
+    violations: u64,
+}
+
+impl SeqNumTracker {
+    fn track(&mut self, side: &str, msg: &FixtMessage) {
+        let seq_num = msg.header.msg_seq_num;
+        if let Some(last) = self.last {
+            // Resent messages (PossDupFlag<43>) and SequenceReset<4> are
+            // allowed to break the sequence
+            if seq_num != last + 1
+                && !msg.header.poss_dup_flag.unwrap_or(false)
+                && !matches!(&*msg.body, Message::SequenceReset(_))
+            {
+                warn!("{side}: MsgSeqNum<34> {seq_num} received after {last}");
+                self.violations += 1;
+            }
+        }
+        self.last = Some(seq_num);
+    }
+}
+
+fn session_settings(session_id: SessionId) -> SessionSettings {
+    SessionSettings {
+        enable_next_expected_msg_seq_num: false,
+        ..SessionSettings::new(session_id)
+    }
+}
+
+fn settings(sender_comp_id: &str) -> Settings {
+    Settings {
+        sender_comp_id: FixString::from_ascii_lossy(sender_comp_id.as_bytes().to_vec()),
+        sender_sub_id: None,
+        heartbeat_interval: Duration::from_secs(5),
+        auto_disconnect_after_no_logon_received: Duration::from_secs(3),
+        auto_disconnect_after_no_heartbeat: 3,
+        buffer_pool: None,
+        duplicate_tags: Default::default(),
+        other_msg_types: false,
+        required_tags: Default::default(),
+        max_bytes_before_logon: None,
+        pre_logon: Default::default(),
+        session_log: None,
+        tcp_keepalive: None,
+        read_idle: None,
+        buffers: Default::default(),
+        group_limits: Default::default(),
+        framing_errors: Default::default(),
+        capture_dir: None,
+    }
+}
+
+fn session_id(sender_comp_id: &str, target_comp_id: &str) -> SessionId {
+    SessionId::new(
+        FixString::from_ascii_lossy(b"FIXT.1.1".to_vec()),
+        FixString::from_ascii_lossy(sender_comp_id.as_bytes().to_vec()),
+        FixString::from_ascii_lossy(target_comp_id.as_bytes().to_vec()),
+    )
+}
+
+fn reverse_route(header: &mut Header) {
+    std::mem::swap(&mut header.sender_comp_id, &mut header.target_comp_id);
+    std::mem::swap(&mut header.sender_sub_id, &mut header.target_sub_id);
+}
+
+/// Resident set size in kB, Linux only.
+fn rss_kb() -> Option<u64> {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()
+}
+
+async fn run_acceptor<S: MessagesStorage + 'static>(
+    storage: impl Fn() -> S + 'static,
+    violations: Rc<Cell<u64>>,
+) {
+    let mut acceptor = Acceptor::new(settings("soak_acceptor"), Box::new(move |_| storage()));
+    let id = session_id("soak_acceptor", "soak_initiator");
+    acceptor.register_session(id.clone(), session_settings(id));
+
+    let connection = TcpConnection::new(ADDR.parse::<SocketAddr>().unwrap())
+        .await
+        .unwrap();
+    acceptor.start(connection);
+
+    let mut sender: Option<Sender> = None;
+    let mut tracker = SeqNumTracker::default();
+    while let Some(mut entry) = acceptor.next().await {
+        match entry.as_event() {
+            FixEvent::Logon(session_id, new_sender) => {
+                info!("acceptor: logon {session_id}");
+                sender = Some(new_sender);
+            }
+            FixEvent::Logout(session_id, reason) => {
+                info!("acceptor: logout {session_id}, reason: {reason:?}");
+                sender = None;
+            }
+            FixEvent::AppMsgIn(mut msg, _responder) => {
+                tracker.track("acceptor", &msg);
+                reverse_route(&mut msg.header);
+                if let Some(sender) = &sender {
+                    let _ = sender.send(msg.body);
+                }
+            }
+            FixEvent::AdmMsgIn(msg, _responder) => tracker.track("acceptor", &msg),
+            FixEvent::DeserializeError(session_id, error) => {
+                error!("acceptor: {session_id}: {error}");
+            }
+            _ => {}
+        }
+        violations.set(tracker.violations);
+    }
+}
+
+fn news(seq: u64) -> Box<Message> {
+    Box::new(Message::News(News {
+        headline: seq.to_fix_string(),
+        lines_of_text_grp: vec![LinesOfTextGrp {
+            text: fix_str!("soak").to_owned(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }))
+}
+
+async fn run_initiator<S: MessagesStorage + 'static>(
+    storage: S,
+    config: Config,
+    acceptor_violations: Rc<Cell<u64>>,
+) -> bool {
+    let id = session_id("soak_initiator", "soak_acceptor");
+    let initiator = Initiator::new(settings("soak_initiator"), session_settings(id), storage);
+    tokio::pin!(initiator);
+    initiator
+        .connect(ADDR.parse::<SocketAddr>().unwrap())
+        .await
+        .expect("failed to connect");
+
+    let msgs_per_tick = (config.msg_per_sec / TICKS_PER_SEC).max(1);
+    let mut send_interval = time::interval(Duration::from_millis(1000 / TICKS_PER_SEC));
+    let mut report_interval = time::interval(config.report_interval);
+    let deadline = time::sleep(config.duration);
+    tokio::pin!(deadline);
+
+    let start = Instant::now();
+    let mut sender: Option<Sender> = None;
+    let mut tracker = SeqNumTracker::default();
+    let mut sent = 0u64;
+    let mut echoed = 0u64;
+    let mut echo_violations = 0u64;
+    let mut last_report = (Instant::now(), 0u64);
+    let mut initial_rss = None;
+
+    loop {
+        tokio::select! {
+            _ = &mut deadline => break,
+            entry = initiator.next() => {
+                let Some(mut entry) = entry else {
+                    error!("initiator: event stream closed");
+                    break;
+                };
+                match entry.as_event() {
+                    FixEvent::Logon(session_id, new_sender) => {
+                        info!("initiator: logon {session_id}");
+                        sender = Some(new_sender);
+                    }
+                    FixEvent::Logout(session_id, reason) => {
+                        warn!("initiator: logout {session_id}, reason: {reason:?}");
+                        sender = None;
+                    }
+                    FixEvent::AppMsgIn(msg, _responder) => {
+                        tracker.track("initiator", &msg);
+                        match &*msg.body {
+                            Message::News(news) if news.headline == echoed.to_fix_string() => {}
+                            body => {
+                                warn!("initiator: expected echo of message {echoed}, got {body:?}");
+                                echo_violations += 1;
+                            }
+                        }
+                        echoed += 1;
+                    }
+                    FixEvent::AdmMsgIn(msg, _responder) => tracker.track("initiator", &msg),
+                    FixEvent::DeserializeError(session_id, error) => {
+                        error!("initiator: {session_id}: {error}");
+                    }
+                    _ => {}
+                }
+            }
+            _ = send_interval.tick() => {
+                if let Some(sender) = &sender {
+                    for _ in 0..msgs_per_tick {
+                        if sender.send(news(sent)).is_err() {
+                            break;
+                        }
+                        sent += 1;
+                    }
+                }
+            }
+            _ = report_interval.tick() => {
+                let rss = rss_kb();
+                initial_rss = initial_rss.or(rss);
+                let (last_time, last_echoed) = last_report;
+                let rate = (echoed - last_echoed) as f64 / last_time.elapsed().as_secs_f64();
+                last_report = (Instant::now(), echoed);
+                info!(
+                    "elapsed: {:?}, sent: {sent}, echoed: {echoed}, in flight: {}, \
+                     rate: {rate:.0} msg/s, rss: {} kB (initial {} kB), \
+                     seq num violations: {}/{}, echo violations: {echo_violations}",
+                    start.elapsed(),
+                    sent - echoed,
+                    rss.map_or_else(|| "n/a".to_owned(), |rss| rss.to_string()),
+                    initial_rss.map_or_else(|| "n/a".to_owned(), |rss| rss.to_string()),
+                    tracker.violations,
+                    acceptor_violations.get(),
+                );
+            }
+        }
+    }
+
+    let violations = tracker.violations + acceptor_violations.get() + echo_violations;
+    info!(
+        "finished after {:?}, sent: {sent}, echoed: {echoed}, violations: {violations}",
+        start.elapsed()
+    );
+    violations == 0 && echoed > 0
+}
+
+async fn run_pair<S: MessagesStorage + 'static>(
+    acceptor_storage: impl Fn() -> S + 'static,
+    initiator_storage: S,
+    config: Config,
+    acceptor_violations: Rc<Cell<u64>>,
+) -> bool {
+    tokio::task::spawn_local(run_acceptor(acceptor_storage, acceptor_violations.clone()));
+    // Give acceptor time to bind
+    time::sleep(Duration::from_millis(100)).await;
+    run_initiator(initiator_storage, config, acceptor_violations).await
+}
+
+fn main() -> ExitCode {
+    tracing_subscriber::fmt::init();
+
+    let config = Config::from_args();
+    info!(
+        "soak test: {} msg/s for {:?}, {:?} storage",
+        config.msg_per_sec, config.duration, config.storage
+    );
+
+    let runtime = Builder::new_current_thread()
+        .enable_io()
+        .enable_time()
+        .build()
+        .unwrap();
+
+    let storage = config.storage;
+    let local_set = LocalSet::new();
+    let ok = local_set.block_on(&runtime, async {
+        let violations = Rc::new(Cell::new(0));
+        match config.storage {
+            StorageKind::Null => {
+                run_pair(NullStorage::new, NullStorage::new(), config, violations).await
+            }
+            StorageKind::Memory => {
+                run_pair(
+                    InMemoryStorage::new,
+                    InMemoryStorage::new(),
+                    config,
+                    violations,
+                )
+                .await
+            }
+            StorageKind::Persistent => {
+                run_pair(
+                    || persistent_storage("acceptor"),
+                    persistent_storage("initiator"),
+                    config,
+                    violations,
+                )
+                .await
+            }
+        }
+    });
+
+    if let StorageKind::Persistent = storage {
+        for side in ["acceptor", "initiator"] {
+            let _ = std::fs::remove_file(seq_nums_path(side));
+        }
+    }
+
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
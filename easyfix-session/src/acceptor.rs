@@ -4,6 +4,7 @@ use std::{
     future::Future,
     io,
     net::SocketAddr,
+    path::Path,
     pin::Pin,
     rc::Rc,
     task::{Context, Poll},
@@ -19,15 +20,21 @@ use tokio::{
 };
 use tracing::{error, info, info_span, instrument, warn, Instrument};
 
+pub mod metrics;
+use metrics::{
+    AcceptorMetrics, ConnectionEvent, ConnectionEvents, ConnectionMonitor, SessionStats,
+};
+
 use crate::{
-    application::{events_channel, AsEvent, Emitter, EventStream},
-    io::acceptor_connection,
+    application::{events_channel, AsEvent, Emitter, EventBroadcast, EventStream, SessionSummary},
+    capture::{CaptureSettings, CapturedChunk, TrafficCapture},
+    io::{acceptor_connection, set_tcp_keepalive},
     messages_storage::MessagesStorage,
     session::Session,
     session_id::SessionId,
     session_state::State as SessionState,
-    settings::SessionSettings,
-    DisconnectReason, Settings,
+    settings::{SessionSettings, TcpKeepalive},
+    DisconnectReason, Error, PauseMode, SessionError, Settings,
 };
 
 #[allow(async_fn_in_trait)]
@@ -46,13 +53,24 @@ pub trait Connection {
 
 pub struct TcpConnection {
     listener: TcpListener,
+    keepalive: Option<TcpKeepalive>,
 }
 
 impl TcpConnection {
     pub async fn new(socket_addr: impl Into<SocketAddr>) -> Result<TcpConnection, io::Error> {
         let socket_addr = socket_addr.into();
         let listener = TcpListener::bind(&socket_addr).await?;
-        Ok(TcpConnection { listener })
+        Ok(TcpConnection {
+            listener,
+            keepalive: None,
+        })
+    }
+
+    /// Enable TCP keepalive probes on accepted connections, usually
+    /// [`Settings::tcp_keepalive`].
+    pub fn with_keepalive(mut self, keepalive: Option<TcpKeepalive>) -> TcpConnection {
+        self.keepalive = keepalive;
+        self
     }
 }
 
@@ -69,12 +87,16 @@ impl Connection for TcpConnection {
     > {
         let (tcp_stream, peer_addr) = self.listener.accept().await?;
         tcp_stream.set_nodelay(true)?;
+        if let Some(keepalive) = &self.keepalive {
+            set_tcp_keepalive(&tcp_stream, keepalive)?;
+        }
         let (reader, writer) = tcp_stream.into_split();
         Ok((reader, writer, peer_addr))
     }
 }
 
-type SessionMapInternal<S> = HashMap<SessionId, (SessionSettings, Rc<RefCell<SessionState<S>>>)>;
+type SessionEntry<S> = (SessionSettings, Rc<RefCell<SessionState<S>>>);
+type SessionMapInternal<S> = HashMap<SessionId, SessionEntry<S>>;
 
 pub struct SessionsMap<S> {
     map: SessionMapInternal<S>,
@@ -102,11 +124,55 @@ impl<S: MessagesStorage> SessionsMap<S> {
         );
     }
 
+    /// BeginString<8> values of registered sessions, connections are
+    /// accepted for any of them.
+    pub(crate) fn begin_strings(&self) -> Vec<FixString> {
+        let mut begin_strings: Vec<FixString> = Vec::new();
+        for session_id in self.map.keys() {
+            if !begin_strings.iter().any(|b| b == session_id.begin_string()) {
+                begin_strings.push(session_id.begin_string().to_owned());
+            }
+        }
+        begin_strings
+    }
+
+    /// Find session registered with given ID, when there is no exact match,
+    /// CompIDs are compared according to sessions `CompIdPolicy`.
     pub(crate) fn get_session(
         &self,
         session_id: &SessionId,
-    ) -> Option<(SessionSettings, Rc<RefCell<SessionState<S>>>)> {
-        self.map.get(session_id).cloned()
+    ) -> Option<(SessionId, SessionEntry<S>)> {
+        if let Some(entry) = self.map.get(session_id) {
+            return Some((session_id.clone(), entry.clone()));
+        }
+        self.map
+            .iter()
+            .find(|(registered_id, (session_settings, _))| {
+                session_settings
+                    .comp_id_policy
+                    .matches_session_id(registered_id, session_id)
+            })
+            .map(|(registered_id, entry)| (registered_id.clone(), entry.clone()))
+    }
+
+    /// Statistics of all registered sessions, sorted by session ID.
+    pub fn session_stats(&self) -> Vec<SessionStats> {
+        let mut stats: Vec<SessionStats> = self
+            .map
+            .iter()
+            .map(|(session_id, (_, state))| {
+                let state = state.borrow();
+                SessionStats {
+                    session_id: session_id.clone(),
+                    connected: !state.disconnected(),
+                    started: !state.stopped(),
+                    enabled: state.enabled(),
+                    state: state.snapshot(),
+                }
+            })
+            .collect();
+        stats.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+        stats
     }
 }
 
@@ -115,6 +181,7 @@ pub struct SessionTask<S> {
     sessions: Rc<RefCell<SessionsMap<S>>>,
     active_sessions: Rc<RefCell<ActiveSessionsMap<S>>>,
     emitter: Emitter,
+    monitor: ConnectionMonitor,
 }
 
 impl<S> Clone for SessionTask<S> {
@@ -124,6 +191,7 @@ impl<S> Clone for SessionTask<S> {
             sessions: self.sessions.clone(),
             active_sessions: self.active_sessions.clone(),
             emitter: self.emitter.clone(),
+            monitor: self.monitor.clone(),
         }
     }
 }
@@ -140,29 +208,35 @@ impl<S: MessagesStorage + 'static> SessionTask<S> {
             sessions,
             active_sessions,
             emitter,
+            monitor: ConnectionMonitor::new(),
         }
     }
 
+    /// Handle connection until it's closed, resolves to summary
+    /// of the session, or `None` when session was not established.
     pub async fn run(
         self,
         peer_addr: SocketAddr,
         reader: impl AsyncRead + Unpin + 'static,
         writer: impl AsyncWrite + Unpin + 'static,
-    ) {
+    ) -> Option<SessionSummary> {
         let span = info_span!("connection", %peer_addr);
 
         span.in_scope(|| {
             info!("---------------------------------------------------------");
             info!("New connection");
         });
+        self.monitor.emit(ConnectionEvent::Accepted { peer_addr });
 
-        acceptor_connection(
+        let summary = acceptor_connection(
+            peer_addr,
             reader,
             writer,
             self.settings,
             self.sessions,
             self.active_sessions,
             self.emitter,
+            self.monitor,
         )
         .instrument(span.clone())
         .await;
@@ -170,15 +244,196 @@ impl<S: MessagesStorage + 'static> SessionTask<S> {
         span.in_scope(|| {
             info!("Connection closed");
         });
+        summary
     }
 }
 
 pub(crate) type ActiveSessionsMap<S> = HashMap<SessionId, Rc<Session<S>>>;
 
+/// Runtime control of registered sessions, independent of each other
+/// and of the acceptor task, see [`Acceptor::admin_handle`].
+///
+/// State set here is kept until changed again, also across reconnections,
+/// and is reflected in [`SessionStats`].
+pub struct AdminHandle<S> {
+    sessions: Rc<RefCell<SessionsMap<S>>>,
+    active_sessions: Rc<RefCell<ActiveSessionsMap<S>>>,
+    capture_dir: Option<Rc<Path>>,
+}
+
+impl<S> Clone for AdminHandle<S> {
+    fn clone(&self) -> Self {
+        Self {
+            sessions: self.sessions.clone(),
+            active_sessions: self.active_sessions.clone(),
+            capture_dir: self.capture_dir.clone(),
+        }
+    }
+}
+
+impl<S: MessagesStorage> AdminHandle<S> {
+    fn with_state(
+        &self,
+        session_id: &SessionId,
+        f: impl FnOnce(&mut SessionState<S>),
+    ) -> Result<(), SessionError> {
+        let sessions = self.sessions.borrow();
+        let Some((_, state)) = sessions.map.get(session_id) else {
+            warn!("session {session_id} not registered");
+            return Err(SessionError::UnknownSession);
+        };
+        f(&mut state.borrow_mut());
+        Ok(())
+    }
+
+    fn with_session(
+        &self,
+        session_id: &SessionId,
+        f: impl FnOnce(&Session<S>, &mut SessionState<S>) -> Result<(), SessionError>,
+    ) -> Result<(), SessionError> {
+        if !self.sessions.borrow().map.contains_key(session_id) {
+            warn!("session {session_id} not registered");
+            return Err(SessionError::UnknownSession);
+        }
+        let active_sessions = self.active_sessions.borrow();
+        let Some(session) = active_sessions.get(session_id) else {
+            warn!("session {session_id} not connected");
+            return Err(SessionError::NotConnected);
+        };
+        let result = f(session, &mut session.state().borrow_mut());
+        result
+    }
+
+    /// Allow stopped session to log on again.
+    pub fn start(&self, session_id: &SessionId) -> Result<(), SessionError> {
+        info!("start session {session_id}");
+        self.with_state(session_id, |state| state.set_stopped(false))
+    }
+
+    /// Log out the session, when connected, and refuse its Logon<A> until
+    /// [`AdminHandle::start`] is called.
+    pub fn stop(
+        &self,
+        session_id: &SessionId,
+        reason: Option<FixString>,
+    ) -> Result<(), SessionError> {
+        info!("stop session {session_id}");
+        self.with_state(session_id, |state| state.set_stopped(true))?;
+        if let Some(session) = self.active_sessions.borrow().get(session_id) {
+            session.send_logout(&mut session.state().borrow_mut(), None, reason);
+        }
+        Ok(())
+    }
+
+    /// Accept Logon<A> of disabled session again.
+    pub fn enable(&self, session_id: &SessionId) -> Result<(), SessionError> {
+        info!("enable session {session_id}");
+        self.with_state(session_id, |state| state.set_enabled(true))
+    }
+
+    /// Refuse Logon<A> of the session, connection already established
+    /// is not affected.
+    pub fn disable(&self, session_id: &SessionId) -> Result<(), SessionError> {
+        info!("disable session {session_id}");
+        self.with_state(session_id, |state| state.set_enabled(false))
+    }
+
+    /// Reset sequence numbers of the session to 1, see [`Acceptor::reset`].
+    pub fn reset(&self, session_id: &SessionId) -> Result<(), SessionError> {
+        info!("reset session {session_id}");
+        self.with_state(session_id, |state| state.reset())
+    }
+
+    /// Send Logout<5> to connected session, connection is closed when
+    /// Logout<5> response is received.
+    pub fn logout(
+        &self,
+        session_id: &SessionId,
+        reason: Option<FixString>,
+    ) -> Result<(), SessionError> {
+        info!("logout session {session_id}");
+        self.with_session(session_id, |session, state| {
+            session.send_logout(state, None, reason);
+            Ok(())
+        })
+    }
+
+    /// Send ResendRequest<2> for messages from `begin_seq_num` to
+    /// `end_seq_num`, or up to the last received one when `end_seq_num`
+    /// is `None`.
+    pub fn request_resend(
+        &self,
+        session_id: &SessionId,
+        begin_seq_num: SeqNum,
+        end_seq_num: Option<SeqNum>,
+    ) -> Result<(), SessionError> {
+        info!("request resend of session {session_id}: {begin_seq_num}..{end_seq_num:?}");
+        self.with_session(session_id, |session, state| {
+            let end_seq_num =
+                end_seq_num.unwrap_or_else(|| state.next_target_msg_seq_num().saturating_sub(1));
+            if begin_seq_num == 0 || begin_seq_num > end_seq_num {
+                return Err(SessionError::InvalidResendRange);
+            }
+            session.request_resend(state, begin_seq_num, end_seq_num);
+            Ok(())
+        })
+    }
+
+    /// Start capturing raw traffic of the session, replacing capture
+    /// already running, see [`capture`](crate::capture).
+    pub fn start_capture(
+        &self,
+        session_id: &SessionId,
+        settings: CaptureSettings,
+    ) -> Result<(), Error> {
+        if !self.sessions.borrow().map.contains_key(session_id) {
+            warn!("session {session_id} not registered");
+            return Err(Error::SessionError(SessionError::UnknownSession));
+        }
+        info!("start capture of session {session_id}: {settings:?}");
+        let capture = TrafficCapture::start(settings, self.capture_dir.as_deref())?;
+        self.with_state(session_id, |state| {
+            state.capture().replace(Some(capture));
+        })
+        .map_err(Error::SessionError)
+    }
+
+    /// Stop capturing raw traffic of the session, returns chunks
+    /// captured in memory.
+    pub fn stop_capture(&self, session_id: &SessionId) -> Result<Vec<CapturedChunk>, SessionError> {
+        info!("stop capture of session {session_id}");
+        let mut chunks = Vec::new();
+        self.with_state(session_id, |state| {
+            if let Some(capture) = state.capture().take() {
+                chunks = capture.chunks();
+            }
+        })?;
+        Ok(chunks)
+    }
+
+    /// Chunks of the session captured in memory so far, capture keeps
+    /// running.
+    pub fn captured(&self, session_id: &SessionId) -> Result<Vec<CapturedChunk>, SessionError> {
+        let mut chunks = Vec::new();
+        self.with_state(session_id, |state| {
+            if let Some(capture) = state.capture().borrow().as_ref() {
+                chunks = capture.chunks();
+            }
+        })?;
+        Ok(chunks)
+    }
+
+    /// Statistics of all registered sessions, connected or not.
+    pub fn session_stats(&self) -> Vec<SessionStats> {
+        self.sessions.borrow().session_stats()
+    }
+}
+
 #[pin_project]
 pub struct Acceptor<S> {
     sessions: Rc<RefCell<SessionsMap<S>>>,
     active_sessions: Rc<RefCell<ActiveSessionsMap<S>>>,
+    capture_dir: Option<Rc<Path>>,
     session_task: SessionTask<S>,
     #[pin]
     event_stream: EventStream,
@@ -189,15 +444,17 @@ impl<S: MessagesStorage + 'static> Acceptor<S> {
         settings: Settings,
         message_storage_builder: Box<dyn Fn(&SessionId) -> S>,
     ) -> Acceptor<S> {
-        let (emitter, event_stream) = events_channel();
+        let (emitter, event_stream) = events_channel(&settings.buffers);
         let sessions = Rc::new(RefCell::new(SessionsMap::new(message_storage_builder)));
         let active_sessions = Rc::new(RefCell::new(HashMap::new()));
+        let capture_dir = settings.capture_dir.as_deref().map(Rc::from);
         let session_task_builder =
             SessionTask::new(settings, sessions.clone(), active_sessions.clone(), emitter);
 
         Acceptor {
             sessions,
             active_sessions,
+            capture_dir,
             session_task: session_task_builder,
             event_stream,
         }
@@ -213,6 +470,45 @@ impl<S: MessagesStorage + 'static> Acceptor<S> {
         self.sessions.clone()
     }
 
+    /// Handle to start, stop, enable and disable sessions at runtime,
+    /// usable after acceptor is moved to event processing task.
+    pub fn admin_handle(&self) -> AdminHandle<S> {
+        AdminHandle {
+            sessions: self.sessions.clone(),
+            active_sessions: self.active_sessions.clone(),
+            capture_dir: self.capture_dir.clone(),
+        }
+    }
+
+    /// Events of all sessions handled by this acceptor, available
+    /// to any number of subscribers.
+    pub fn events(&self) -> &EventBroadcast {
+        self.session_task.emitter.events()
+    }
+
+    /// Connection lifecycle events (accepted connections, logon outcome
+    /// and disconnection cause), emitted after this call.
+    pub fn connection_events(&self) -> ConnectionEvents {
+        self.session_task.monitor.subscribe()
+    }
+
+    /// Counters of connection lifecycle events.
+    pub fn metrics(&self) -> AcceptorMetrics {
+        self.session_task.monitor.metrics()
+    }
+
+    /// Statistics of all registered sessions, connected or not.
+    pub fn session_stats(&self) -> Vec<SessionStats> {
+        self.sessions.borrow().session_stats()
+    }
+
+    /// Acceptor metrics and session statistics in Prometheus text
+    /// exposition format, ready to be served on `/metrics` endpoint.
+    #[cfg(feature = "prometheus")]
+    pub fn prometheus_metrics(&self) -> String {
+        metrics::prometheus::encode(&self.metrics(), &self.session_stats())
+    }
+
     pub fn start(&self, connection: impl Connection + 'static) -> JoinHandle<()> {
         tokio::task::spawn_local(Self::server_task(connection, self.session_task.clone()))
     }
@@ -261,6 +557,32 @@ impl<S: MessagesStorage + 'static> Acceptor<S> {
         session.reset(&mut session.state().borrow_mut());
     }
 
+    /// Pause sending of application messages
+    ///
+    /// Heartbeats and other administrative messages keep flowing, so the
+    /// counterparty sees the session alive. Depending on `mode`, application
+    /// messages sent while paused are buffered or discarded.
+    pub fn pause(&self, session_id: &SessionId, mode: PauseMode) {
+        let active_sessions = self.active_sessions.borrow();
+        let Some(session) = active_sessions.get(session_id) else {
+            warn!("pause: session {session_id} not found");
+            return;
+        };
+
+        session.pause(&mut session.state().borrow_mut(), mode);
+    }
+
+    /// Resume paused session, buffered messages are sent immediately
+    pub fn resume(&self, session_id: &SessionId) {
+        let active_sessions = self.active_sessions.borrow();
+        let Some(session) = active_sessions.get(session_id) else {
+            warn!("resume: session {session_id} not found");
+            return;
+        };
+
+        session.resume(&mut session.state().borrow_mut());
+    }
+
     /// Sender seq_num getter
     #[instrument(skip(self))]
     pub fn next_sender_msg_seq_num(&self, session_id: &SessionId) -> SeqNum {
@@ -296,7 +618,12 @@ impl<S: MessagesStorage + 'static> Acceptor<S> {
                 Ok((reader, writer, peer_addr)) => {
                     tokio::task::spawn_local(session_task.clone().run(peer_addr, reader, writer));
                 }
-                Err(err) => error!("server task failed to accept incoming connection: {err}"),
+                Err(err) => {
+                    error!("server task failed to accept incoming connection: {err}");
+                    session_task
+                        .monitor
+                        .emit(ConnectionEvent::AcceptFailed { error: err.kind() });
+                }
             }
         }
     }
@@ -305,12 +632,13 @@ impl<S: MessagesStorage + 'static> Acceptor<S> {
         self.session_task.clone()
     }
 
+    /// Handle connection until it's closed, see [`SessionTask::run`].
     pub fn run_session_task(
         &self,
         peer_addr: SocketAddr,
         reader: impl AsyncRead + Unpin + 'static,
         writer: impl AsyncWrite + Unpin + 'static,
-    ) -> impl Future<Output = ()> {
+    ) -> impl Future<Output = Option<SessionSummary>> {
         self.session_task.clone().run(peer_addr, reader, writer)
     }
 }
@@ -0,0 +1,356 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io,
+    net::SocketAddr,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use easyfix_messages::fields::MsgType;
+use futures::Stream;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+use tracing::warn;
+
+use crate::{
+    application::SessionStateSnapshot, session_id::SessionId, settings::PreLogonAction,
+    DisconnectReason,
+};
+
+/// Reason why connection didn't result in established session.
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum LogonFailure {
+    /// Nothing received before logon timeout.
+    #[error("Never received logon from new connection.")]
+    Timeout,
+    /// Connection closed by peer before first message was received.
+    #[error("Connection closed before logon.")]
+    Closed,
+    /// More than `Settings::max_bytes_before_logon` bytes received without
+    /// complete first message.
+    #[error("Received more than {0} bytes before logon.")]
+    BufferLimitExceeded(usize),
+    /// First message could not be deserialized.
+    #[error("Failed to deserialize first message.")]
+    InvalidMessage,
+    /// First message points to session not registered in acceptor.
+    #[error("Unknown session id {0}.")]
+    UnknownSession(SessionId),
+    /// Session rejected the first message (e.g. it was not Logon<A>).
+    #[error("Session {0} rejected first message ({1:?}).")]
+    Rejected(SessionId, DisconnectReason),
+    /// I/O error before first message was received.
+    #[error("I/O error: {0}")]
+    Io(io::ErrorKind),
+}
+
+/// Connection lifecycle event of [`Acceptor`](super::Acceptor).
+#[derive(Clone, Debug)]
+pub enum ConnectionEvent {
+    /// New connection accepted.
+    Accepted { peer_addr: SocketAddr },
+    /// Listener failed to accept connection.
+    AcceptFailed { error: io::ErrorKind },
+    /// Logon<A> received and accepted.
+    LogonSucceeded {
+        peer_addr: SocketAddr,
+        session_id: SessionId,
+    },
+    /// Message other than Logon<A> received before session was
+    /// established, handled with `action` according to
+    /// [`PreLogonPolicy`](crate::settings::PreLogonPolicy).
+    PreLogonMessage {
+        peer_addr: SocketAddr,
+        /// `None` when message could not be deserialized
+        msg_type: Option<MsgType>,
+        action: PreLogonAction,
+    },
+    /// Connection closed before session was established.
+    LogonFailed {
+        peer_addr: SocketAddr,
+        reason: LogonFailure,
+    },
+    /// Connection of established session closed.
+    Disconnected {
+        peer_addr: SocketAddr,
+        session_id: SessionId,
+        reason: DisconnectReason,
+    },
+}
+
+/// Counters of [`ConnectionEvent`]s since acceptor was created.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AcceptorMetrics {
+    pub accepted: u64,
+    pub accept_failures: u64,
+    pub logons: u64,
+    pub logon_failures: u64,
+    pub pre_logon_messages: u64,
+    /// Connections currently open
+    pub open_connections: u64,
+    pub disconnects: HashMap<DisconnectReason, u64>,
+}
+
+impl AcceptorMetrics {
+    fn update(&mut self, event: &ConnectionEvent) {
+        match event {
+            ConnectionEvent::Accepted { .. } => {
+                self.accepted += 1;
+                self.open_connections += 1;
+            }
+            ConnectionEvent::AcceptFailed { .. } => self.accept_failures += 1,
+            ConnectionEvent::LogonSucceeded { .. } => self.logons += 1,
+            ConnectionEvent::PreLogonMessage { .. } => self.pre_logon_messages += 1,
+            ConnectionEvent::LogonFailed { reason, .. } => {
+                self.logon_failures += 1;
+                // Rejected session is disconnected as usual
+                if !matches!(reason, LogonFailure::Rejected(..)) {
+                    self.open_connections = self.open_connections.saturating_sub(1);
+                }
+            }
+            ConnectionEvent::Disconnected { reason, .. } => {
+                *self.disconnects.entry(*reason).or_default() += 1;
+                self.open_connections = self.open_connections.saturating_sub(1);
+            }
+        }
+    }
+}
+
+/// Statistics of session registered in [`Acceptor`](super::Acceptor).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionStats {
+    pub session_id: SessionId,
+    /// Session has active connection
+    pub connected: bool,
+    /// Session is not stopped, see [`AdminHandle::stop`](super::AdminHandle::stop)
+    pub started: bool,
+    /// Inbound Logon<A> is accepted, see
+    /// [`AdminHandle::disable`](super::AdminHandle::disable)
+    pub enabled: bool,
+    pub state: SessionStateSnapshot,
+}
+
+/// Source of [`ConnectionEvent`]s, shared by acceptor tasks.
+#[derive(Clone, Debug)]
+pub(crate) struct ConnectionMonitor {
+    sender: broadcast::Sender<ConnectionEvent>,
+    metrics: Rc<RefCell<AcceptorMetrics>>,
+}
+
+impl ConnectionMonitor {
+    /// Capacity of the events buffer, subscribers lagging more than that
+    /// miss the oldest events.
+    const CAPACITY: usize = 256;
+
+    pub(crate) fn new() -> ConnectionMonitor {
+        ConnectionMonitor {
+            sender: broadcast::channel(Self::CAPACITY).0,
+            metrics: Rc::new(RefCell::new(AcceptorMetrics::default())),
+        }
+    }
+
+    pub(crate) fn emit(&self, event: ConnectionEvent) {
+        self.metrics.borrow_mut().update(&event);
+        // Error means there are no subscribers
+        let _ = self.sender.send(event);
+    }
+
+    pub(crate) fn metrics(&self) -> AcceptorMetrics {
+        self.metrics.borrow().clone()
+    }
+
+    pub(crate) fn subscribe(&self) -> ConnectionEvents {
+        ConnectionEvents {
+            receiver: BroadcastStream::new(self.sender.subscribe()),
+        }
+    }
+}
+
+/// Stream of [`ConnectionEvent`]s emitted after subscription.
+#[derive(Debug)]
+pub struct ConnectionEvents {
+    receiver: BroadcastStream<ConnectionEvent>,
+}
+
+impl Stream for ConnectionEvents {
+    type Item = ConnectionEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match Pin::new(&mut self.receiver).poll_next(cx) {
+                Poll::Ready(Some(Ok(event))) => return Poll::Ready(Some(event)),
+                Poll::Ready(Some(Err(BroadcastStreamRecvError::Lagged(skipped)))) => {
+                    warn!("connection events subscriber lagged, {skipped} events skipped");
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "prometheus")]
+pub mod prometheus {
+    //! [Prometheus text exposition format](https://prometheus.io/docs/instrumenting/exposition_formats/)
+    //! of acceptor and sessions statistics.
+
+    use std::fmt::Write;
+
+    use super::{AcceptorMetrics, SessionStats};
+
+    /// Name, type, help and value getter of per-session metric.
+    type SessionMetric = (
+        &'static str,
+        &'static str,
+        &'static str,
+        fn(&SessionStats) -> u64,
+    );
+
+    fn escape(value: &str) -> String {
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    }
+
+    fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} {kind}");
+    }
+
+    /// Encode acceptor counters and per-session statistics, sessions
+    /// are labeled with `sender`, `target` and `qualifier`.
+    pub fn encode(metrics: &AcceptorMetrics, sessions: &[SessionStats]) -> String {
+        let mut out = String::new();
+
+        for (name, help, value) in [
+            (
+                "easyfix_accepted_connections_total",
+                "Connections accepted.",
+                metrics.accepted,
+            ),
+            (
+                "easyfix_accept_failures_total",
+                "Failures to accept connection.",
+                metrics.accept_failures,
+            ),
+            ("easyfix_logons_total", "Accepted logons.", metrics.logons),
+            (
+                "easyfix_logon_failures_total",
+                "Connections closed before session was established.",
+                metrics.logon_failures,
+            ),
+        ] {
+            header(&mut out, name, "counter", help);
+            let _ = writeln!(out, "{name} {value}");
+        }
+
+        header(
+            &mut out,
+            "easyfix_open_connections",
+            "gauge",
+            "Connections currently open.",
+        );
+        let _ = writeln!(out, "easyfix_open_connections {}", metrics.open_connections);
+
+        header(
+            &mut out,
+            "easyfix_disconnects_total",
+            "counter",
+            "Disconnections of established sessions.",
+        );
+        // Sorted for stable output
+        let mut disconnects: Vec<_> = metrics
+            .disconnects
+            .iter()
+            .map(|(reason, count)| (format!("{reason:?}"), count))
+            .collect();
+        disconnects.sort();
+        for (reason, count) in disconnects {
+            let _ = writeln!(
+                out,
+                "easyfix_disconnects_total{{reason=\"{reason}\"}} {count}"
+            );
+        }
+
+        let labels: Vec<String> = sessions
+            .iter()
+            .map(|stats| {
+                let session_id = &stats.session_id;
+                format!(
+                    "sender=\"{}\",target=\"{}\",qualifier=\"{}\"",
+                    escape(session_id.sender_comp_id().as_utf8()),
+                    escape(session_id.target_comp_id().as_utf8()),
+                    escape(session_id.session_qualifier()),
+                )
+            })
+            .collect();
+
+        let session_metrics: [SessionMetric; 9] = [
+            (
+                "easyfix_session_connected",
+                "gauge",
+                "Session has active connection.",
+                |stats| stats.connected.into(),
+            ),
+            (
+                "easyfix_session_started",
+                "gauge",
+                "Session is not stopped.",
+                |stats| stats.started.into(),
+            ),
+            (
+                "easyfix_session_enabled",
+                "gauge",
+                "Session accepts Logon.",
+                |stats| stats.enabled.into(),
+            ),
+            (
+                "easyfix_session_next_sender_msg_seq_num",
+                "gauge",
+                "Next outgoing MsgSeqNum.",
+                |stats| stats.state.next_sender_msg_seq_num.into(),
+            ),
+            (
+                "easyfix_session_next_target_msg_seq_num",
+                "gauge",
+                "Next expected incoming MsgSeqNum.",
+                |stats| stats.state.next_target_msg_seq_num.into(),
+            ),
+            (
+                "easyfix_session_messages_received_total",
+                "counter",
+                "Messages received.",
+                |stats| stats.state.messages_received,
+            ),
+            (
+                "easyfix_session_messages_sent_total",
+                "counter",
+                "Messages sent.",
+                |stats| stats.state.messages_sent,
+            ),
+            (
+                "easyfix_session_read_buffer_resizes_total",
+                "counter",
+                "Socket read buffer resizes.",
+                |stats| stats.state.read_buffer_resizes,
+            ),
+            (
+                "easyfix_session_serialize_buffer_resizes_total",
+                "counter",
+                "Serialization buffer resizes.",
+                |stats| stats.state.serialize_buffer_resizes,
+            ),
+        ];
+        for (name, kind, help, value) in session_metrics {
+            header(&mut out, name, kind, help);
+            for (stats, labels) in sessions.iter().zip(&labels) {
+                let _ = writeln!(out, "{name}{{{labels}}} {}", value(stats));
+            }
+        }
+
+        out
+    }
+}
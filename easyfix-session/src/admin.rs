@@ -0,0 +1,402 @@
+//! Textual admin commands for operations, served over TCP or Unix socket,
+//! so runbooks can be executed against running acceptor without custom
+//! code, e.g. with `nc` or `socat`.
+//!
+//! Every command is a single line, response is zero or more lines
+//! followed by `OK`, or by `ERR <reason>` when command failed.
+//!
+//! | Command                                          | Description                          |
+//! |--------------------------------------------------|--------------------------------------|
+//! | `list`                                           | Registered sessions with their state |
+//! | `seqnums <session>`                              | Next sender and target MsgSeqNum<34> |
+//! | `reset <session>`                                | Reset sequence numbers to 1          |
+//! | `logout <session> [text]`                        | Send Logout<5>                       |
+//! | `resend <session> <begin> [<end>]`               | Send ResendRequest<2>                |
+//! | `start <session>`                                | See [`AdminHandle::start`]           |
+//! | `stop <session> [text]`                          | See [`AdminHandle::stop`]            |
+//! | `enable <session>`                               | See [`AdminHandle::enable`]          |
+//! | `disable <session>`                              | See [`AdminHandle::disable`]         |
+//! | `capture <session> start [<max_bytes>] [<file>]` | See [`AdminHandle::start_capture`]   |
+//! | `capture <session> show`                         | See [`AdminHandle::captured`]        |
+//! | `capture <session> stop`                         | See [`AdminHandle::stop_capture`]    |
+//! | `loglevel <level>`                               | See [`AdminServer::with_log_level`]  |
+//! | `help`                                           | List of commands                     |
+//!
+//! `capture start` captures in memory, up to 1 MiB by default, or to
+//! `file` when given. File path is relative to
+//! [`Settings::capture_dir`](crate::settings::Settings::capture_dir),
+//! absolute paths and paths with `..` are refused, as are all file
+//! captures when the directory is not set. `capture show` and
+//! `capture stop` print chunks captured in memory, one per line as
+//! `<timestamp> <IN|OUT> <bytes>`, with bytes escaped.
+//!
+//! Commands longer than 4 KiB are refused with `ERR command too long`
+//! and connection is closed.
+//!
+//! Sessions are selected with `[SENDER:]TARGET[/QUALIFIER]`, e.g. `CLIENT`,
+//! or `SERVER:CLIENT/backup` when TargetCompID<56> alone is ambiguous.
+//!
+//! ```ignore
+//! let admin = AdminServer::new(acceptor.admin_handle());
+//! admin.start_tcp(TcpListener::bind("127.0.0.1:9000").await?);
+//! ```
+//!
+//! There is no authentication, listen on loopback interface or on Unix
+//! socket with restricted permissions only.
+
+use std::{io, path::PathBuf, rc::Rc};
+
+use easyfix_messages::fields::{FixString, SeqNum};
+#[cfg(unix)]
+use tokio::net::UnixListener;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    net::TcpListener,
+    task::JoinHandle,
+};
+use tracing::{error, info, warn};
+
+use crate::{
+    acceptor::{metrics::SessionStats, AdminHandle},
+    capture::{CaptureSettings, CaptureSink, CapturedChunk},
+    inspector::MsgDirection,
+    messages_storage::MessagesStorage,
+    session_id::SessionId,
+};
+
+const HELP: &str = "\
+list
+seqnums <session>
+reset <session>
+logout <session> [text]
+resend <session> <begin> [<end>]
+start <session>
+stop <session> [text]
+enable <session>
+disable <session>
+capture <session> start [<max_bytes>] [<file>]
+capture <session> show
+capture <session> stop
+loglevel <level>
+help";
+
+/// Capture limit when `capture start` is given no `max_bytes`.
+const DEFAULT_CAPTURE_BYTES: usize = 1024 * 1024;
+
+/// Longest command accepted, so client never sending new line can't make
+/// server buffer unlimited amount of data.
+const MAX_COMMAND_LEN: usize = 4096;
+
+type LogLevelHandler = dyn Fn(&str) -> Result<(), String>;
+
+/// Executes admin commands, see [module documentation](self).
+pub struct AdminServer<S> {
+    admin: AdminHandle<S>,
+    log_level: Option<Rc<LogLevelHandler>>,
+}
+
+impl<S> Clone for AdminServer<S> {
+    fn clone(&self) -> Self {
+        Self {
+            admin: self.admin.clone(),
+            log_level: self.log_level.clone(),
+        }
+    }
+}
+
+fn session_name(session_id: &SessionId) -> String {
+    let mut name = format!(
+        "{}:{}",
+        session_id.sender_comp_id(),
+        session_id.target_comp_id()
+    );
+    if !session_id.session_qualifier().is_empty() {
+        name.push('/');
+        name.push_str(session_id.session_qualifier());
+    }
+    name
+}
+
+/// Find session selected with `[SENDER:]TARGET[/QUALIFIER]`.
+fn select_session(sessions: &[SessionStats], selector: &str) -> Result<SessionId, String> {
+    let (comp_ids, qualifier) = selector.split_once('/').unwrap_or((selector, ""));
+    let (sender, target) = match comp_ids.split_once(':') {
+        Some((sender, target)) => (Some(sender), target),
+        None => (None, comp_ids),
+    };
+    let mut matching = sessions.iter().map(|stats| &stats.session_id).filter(|id| {
+        id.target_comp_id() == *target
+            && sender.is_none_or(|sender| id.sender_comp_id() == *sender)
+            && id.session_qualifier() == qualifier
+    });
+    match (matching.next(), matching.next()) {
+        (Some(session_id), None) => Ok(session_id.clone()),
+        (Some(_), Some(_)) => Err(format!("ambiguous session {selector}")),
+        (None, _) => Err(format!("unknown session {selector}")),
+    }
+}
+
+fn parse_seq_num(value: &str) -> Result<SeqNum, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid sequence number {value}"))
+}
+
+/// Captured chunk as single line, non-printable bytes escaped, e.g.
+/// `20240101-00:00:00.000000000 IN 8=FIXT.1.1\x019=...`.
+fn chunk_line(chunk: &CapturedChunk) -> String {
+    let direction = match chunk.direction {
+        MsgDirection::Inbound => "IN",
+        MsgDirection::Outbound => "OUT",
+    };
+    format!(
+        "{} {direction} {}\n",
+        chunk.timestamp,
+        chunk.bytes.escape_ascii()
+    )
+}
+
+fn text(words: &[&str]) -> Result<Option<FixString>, String> {
+    if words.is_empty() {
+        return Ok(None);
+    }
+    FixString::from_ascii(words.join(" ").into_bytes())
+        .map(Some)
+        .map_err(|err| format!("invalid text: {err}"))
+}
+
+impl<S: MessagesStorage + 'static> AdminServer<S> {
+    pub fn new(admin: AdminHandle<S>) -> AdminServer<S> {
+        AdminServer {
+            admin,
+            log_level: None,
+        }
+    }
+
+    /// Handle `loglevel` command, e.g. with `tracing_subscriber` reload
+    /// handle, command fails when not set.
+    pub fn with_log_level(
+        mut self,
+        handler: impl Fn(&str) -> Result<(), String> + 'static,
+    ) -> AdminServer<S> {
+        self.log_level = Some(Rc::new(handler));
+        self
+    }
+
+    /// Execute single command, returns complete response, terminated with
+    /// `OK` or `ERR <reason>` line.
+    pub fn execute(&self, command: &str) -> String {
+        match self.run_command(command) {
+            Ok(mut response) => {
+                response.push_str("OK\n");
+                response
+            }
+            Err(reason) => {
+                warn!("admin command `{command}` failed: {reason}");
+                format!("ERR {reason}\n")
+            }
+        }
+    }
+
+    fn run_command(&self, command: &str) -> Result<String, String> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let Some((name, args)) = words.split_first() else {
+            return Ok(String::new());
+        };
+        let sessions = self.admin.session_stats();
+        let session = |index: usize| {
+            args.get(index)
+                .ok_or_else(|| "session not given".to_owned())
+                .and_then(|selector| select_session(&sessions, selector))
+        };
+        let mut response = String::new();
+        match name.to_ascii_lowercase().as_str() {
+            "list" => {
+                for stats in &sessions {
+                    response.push_str(&format!(
+                        "{} {} connected={} started={} enabled={} next_sender_seq={} next_target_seq={}\n",
+                        session_name(&stats.session_id),
+                        stats.session_id.begin_string(),
+                        stats.connected,
+                        stats.started,
+                        stats.enabled,
+                        stats.state.next_sender_msg_seq_num,
+                        stats.state.next_target_msg_seq_num,
+                    ));
+                }
+            }
+            "seqnums" => {
+                let session_id = session(0)?;
+                let stats = sessions
+                    .iter()
+                    .find(|stats| stats.session_id == session_id)
+                    .expect("selected from stats");
+                response.push_str(&format!(
+                    "next_sender_seq={} next_target_seq={}\n",
+                    stats.state.next_sender_msg_seq_num, stats.state.next_target_msg_seq_num
+                ));
+            }
+            "reset" => self
+                .admin
+                .reset(&session(0)?)
+                .map_err(|err| err.to_string())?,
+            "logout" => self
+                .admin
+                .logout(&session(0)?, text(args.get(1..).unwrap_or_default())?)
+                .map_err(|err| err.to_string())?,
+            "resend" => {
+                let begin = parse_seq_num(args.get(1).ok_or("begin not given")?)?;
+                let end = args.get(2).map(|end| parse_seq_num(end)).transpose()?;
+                self.admin
+                    .request_resend(&session(0)?, begin, end)
+                    .map_err(|err| err.to_string())?
+            }
+            "start" => self
+                .admin
+                .start(&session(0)?)
+                .map_err(|err| err.to_string())?,
+            "stop" => self
+                .admin
+                .stop(&session(0)?, text(args.get(1..).unwrap_or_default())?)
+                .map_err(|err| err.to_string())?,
+            "enable" => self
+                .admin
+                .enable(&session(0)?)
+                .map_err(|err| err.to_string())?,
+            "disable" => self
+                .admin
+                .disable(&session(0)?)
+                .map_err(|err| err.to_string())?,
+            "capture" => {
+                let session_id = session(0)?;
+                match args
+                    .get(1)
+                    .map(|action| action.to_ascii_lowercase())
+                    .as_deref()
+                {
+                    Some("start") => {
+                        let max_bytes = match args.get(2) {
+                            Some(max_bytes) => max_bytes
+                                .parse()
+                                .map_err(|_| format!("invalid max_bytes {max_bytes}"))?,
+                            None => DEFAULT_CAPTURE_BYTES,
+                        };
+                        let sink = match args.get(3) {
+                            Some(path) => CaptureSink::File(PathBuf::from(path)),
+                            None => CaptureSink::Memory,
+                        };
+                        self.admin
+                            .start_capture(&session_id, CaptureSettings { sink, max_bytes })
+                            .map_err(|err| err.to_string())?;
+                    }
+                    Some("show") => {
+                        let chunks = self
+                            .admin
+                            .captured(&session_id)
+                            .map_err(|err| err.to_string())?;
+                        response.extend(chunks.iter().map(chunk_line));
+                    }
+                    Some("stop") => {
+                        let chunks = self
+                            .admin
+                            .stop_capture(&session_id)
+                            .map_err(|err| err.to_string())?;
+                        response.extend(chunks.iter().map(chunk_line));
+                    }
+                    Some(action) => return Err(format!("unknown capture action {action}")),
+                    None => return Err("capture action not given".to_owned()),
+                }
+            }
+            "loglevel" => {
+                let level = args.first().ok_or("level not given")?;
+                let handler = self.log_level.as_ref().ok_or("log level not supported")?;
+                handler(level)?;
+            }
+            "help" => {
+                response.push_str(HELP);
+                response.push('\n');
+            }
+            _ => return Err(format!("unknown command {name}")),
+        }
+        Ok(response)
+    }
+
+    /// Execute commands read from `reader` until it's closed.
+    pub async fn handle_connection(
+        &self,
+        reader: impl AsyncRead + Unpin,
+        mut writer: impl AsyncWrite + Unpin,
+    ) -> io::Result<()> {
+        let mut reader = BufReader::new(reader);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            let limit = MAX_COMMAND_LEN as u64 + 1;
+            let read = (&mut reader)
+                .take(limit)
+                .read_until(b'\n', &mut line)
+                .await?;
+            if read == 0 {
+                return Ok(());
+            }
+            if read as u64 == limit && !line.ends_with(b"\n") {
+                warn!("admin command longer than {MAX_COMMAND_LEN} bytes, closing connection");
+                writer.write_all(b"ERR command too long\n").await?;
+                writer.flush().await?;
+                return Ok(());
+            }
+            let line = std::str::from_utf8(&line)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?
+                .trim();
+            if line.is_empty() {
+                continue;
+            }
+            info!("admin command: {line}");
+            writer.write_all(self.execute(line).as_bytes()).await?;
+            writer.flush().await?;
+        }
+    }
+
+    /// Serve commands on connections accepted by `listener`.
+    pub fn start_tcp(self, listener: TcpListener) -> JoinHandle<()> {
+        tokio::task::spawn_local(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer_addr)) => {
+                        info!("admin connection from {peer_addr}");
+                        let server = self.clone();
+                        tokio::task::spawn_local(async move {
+                            let (reader, writer) = stream.into_split();
+                            if let Err(err) = server.handle_connection(reader, writer).await {
+                                warn!("admin connection failed: {err}");
+                            }
+                        });
+                    }
+                    Err(err) => error!("failed to accept admin connection: {err}"),
+                }
+            }
+        })
+    }
+
+    /// Serve commands on connections accepted by Unix socket `listener`.
+    #[cfg(unix)]
+    pub fn start_unix(self, listener: UnixListener) -> JoinHandle<()> {
+        tokio::task::spawn_local(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        info!("admin connection on Unix socket");
+                        let server = self.clone();
+                        tokio::task::spawn_local(async move {
+                            let (reader, writer) = stream.into_split();
+                            if let Err(err) = server.handle_connection(reader, writer).await {
+                                warn!("admin connection failed: {err}");
+                            }
+                        });
+                    }
+                    Err(err) => error!("failed to accept admin connection: {err}"),
+                }
+            }
+        })
+    }
+}
@@ -1,27 +1,42 @@
 use std::{
     fmt,
     marker::PhantomData,
+    ops::{Deref, RangeInclusive},
     pin::Pin,
+    rc::Rc,
+    sync::Arc,
     task::{Context, Poll},
 };
 
+use bytes::Bytes;
 use easyfix_messages::{
-    deserializer,
+    deserializer::{self, FramingMismatch},
     fields::{
-        parse_reject_reason_to_session_reject_reason, FixString, SeqNum, SessionRejectReason,
-        SessionStatus, TagNum,
+        parse_reject_reason_to_session_reject_reason, FixString, Int, MsgType, SeqNum,
+        SessionRejectReason, SessionStatus, TagNum, TimePrecision, Utc, UtcTimestamp,
     },
-    messages::FixtMessage,
+    messages::{FixtMessage, Header, Message},
 };
 use futures::Stream;
-use tokio::sync::{mpsc, oneshot};
-use tokio_stream::wrappers::ReceiverStream;
-use tracing::error;
+use tokio::{
+    sync::{broadcast, mpsc, oneshot},
+    time::{Duration, Instant},
+};
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream, ReceiverStream};
+use tracing::{error, warn};
 
-use crate::{session_id::SessionId, DisconnectReason, Sender};
+use crate::{
+    inspector::{Finding, MsgDirection},
+    session_id::{SessionId, SubIdRoute},
+    session_log::SessionLog,
+    settings::{
+        BeginStringMismatchPolicy, BufferSettings, SerializeFailurePolicy, StoreFailurePolicy,
+    },
+    DisconnectReason, Sender,
+};
 
 //
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum DeserializeError {
     // TODO: enum maybe?
     GarbledMessage(String),
@@ -31,6 +46,11 @@ pub enum DeserializeError {
         seq_num: SeqNum,
         tag: Option<TagNum>,
         reason: SessionRejectReason,
+        /// Rejected message as received
+        raw_message: Option<Bytes>,
+        /// Both occurrences of field rejected with TagAppearsMoreThanOnce
+        /// reason.
+        duplicate: Option<Box<deserializer::DuplicateTag>>,
     },
 }
 
@@ -53,6 +73,23 @@ impl fmt::Display for DeserializeError {
 
 impl std::error::Error for DeserializeError {}
 
+/// Invalid value of optional field, skipped when message was deserialized,
+/// see [`FieldErrorsPolicy`](crate::settings::FieldErrorsPolicy).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FieldError {
+    pub tag: TagNum,
+    pub reason: SessionRejectReason,
+}
+
+impl From<deserializer::FieldError> for FieldError {
+    fn from(error: deserializer::FieldError) -> Self {
+        FieldError {
+            tag: error.tag,
+            reason: parse_reject_reason_to_session_reject_reason(error.reason),
+        }
+    }
+}
+
 impl From<deserializer::DeserializeError> for DeserializeError {
     fn from(error: deserializer::DeserializeError) -> Self {
         use deserializer::DeserializeError as DeError;
@@ -64,16 +101,334 @@ impl From<deserializer::DeserializeError> for DeserializeError {
                 seq_num,
                 tag,
                 reason,
+                duplicate,
             } => DeserializeError::Reject {
                 msg_type,
                 seq_num,
                 tag,
                 reason: parse_reject_reason_to_session_reject_reason(reason),
+                raw_message: None,
+                duplicate,
             },
         }
     }
 }
 
+/// Summary of messages resent to the counterparty.
+#[derive(Clone, Copy, Debug)]
+pub struct ResendReport {
+    /// First sequence number of the resent range
+    pub begin_seq_num: SeqNum,
+    /// Last sequence number of the resent range (after adjustment to the
+    /// last sent message)
+    pub end_seq_num: SeqNum,
+    /// Number of messages replayed from storage with PossDupFlag<43> set
+    pub resent: u32,
+    /// Number of sequence numbers skipped with SequenceReset<4> GapFill
+    pub gap_filled: u32,
+}
+
+/// Direction of session-level Reject<3> message.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RejectDirection {
+    /// Reject sent to the counterparty
+    Sent,
+    /// Reject received from the counterparty
+    Received,
+}
+
+/// Session state at given point in time, see [`RejectReport::state`]
+/// and [`Acceptor::session_stats`](crate::acceptor::Acceptor::session_stats).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionStateSnapshot {
+    pub next_sender_msg_seq_num: SeqNum,
+    pub next_target_msg_seq_num: SeqNum,
+    pub logon_received: bool,
+    pub logon_sent: bool,
+    pub logout_sent: bool,
+    /// Range of pending ResendRequest<2>
+    pub resend_range: Option<RangeInclusive<SeqNum>>,
+    /// Messages received since session was registered
+    pub messages_received: u64,
+    /// Messages sent since session was registered
+    pub messages_sent: u64,
+    /// Times socket read buffer grew since session was registered, see
+    /// [`BufferSettings`](crate::settings::BufferSettings)
+    pub read_buffer_resizes: u64,
+    /// Times outgoing message didn't fit serialization buffer since
+    /// session was registered
+    pub serialize_buffer_resizes: u64,
+}
+
+/// Capture time of event, taken when input message is read or when
+/// state transition happens, before event is queued for delivery.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EventTimestamp {
+    /// Wall clock time, to correlate with SendingTime<52> and logs
+    pub wall_clock: UtcTimestamp,
+    /// Monotonic time, for latency measurements not affected by clock
+    /// adjustments
+    pub monotonic: Instant,
+}
+
+impl EventTimestamp {
+    pub fn now() -> EventTimestamp {
+        EventTimestamp {
+            wall_clock: UtcTimestamp::with_precision(Utc::now(), TimePrecision::Nanos),
+            monotonic: Instant::now(),
+        }
+    }
+}
+
+/// Outcome of terminated session connection, see
+/// [`Initiator::connect`](crate::initiator::Initiator::connect) and
+/// [`Acceptor::run_session_task`](crate::acceptor::Acceptor::run_session_task).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionSummary {
+    pub session_id: SessionId,
+    pub disconnect_reason: DisconnectReason,
+    /// Session state after disconnection
+    pub state: SessionStateSnapshot,
+}
+
+/// Complete context of session-level Reject<3> sent or received.
+#[derive(Clone, Debug)]
+pub struct RejectReport {
+    pub direction: RejectDirection,
+    /// RefMsgType<372>
+    pub ref_msg_type: Option<FixString>,
+    /// RefSeqNum<45>
+    pub ref_seq_num: SeqNum,
+    /// RefTagID<371>
+    pub ref_tag_id: Option<Int>,
+    /// SessionRejectReason<373>
+    pub reason: Option<SessionRejectReason>,
+    /// Text<58>
+    pub text: Option<FixString>,
+    /// Rejected message.
+    ///
+    /// For sent rejects it's the received message, exactly as received when
+    /// it failed to deserialize, or serialized again after successful
+    /// deserialization (so fields order may differ). It's not available
+    /// when message was rejected by the application.
+    ///
+    /// For received rejects it's our message read from the messages storage,
+    /// if it's still stored.
+    pub raw_message: Option<Bytes>,
+    /// Session state at the time of reject, before it was updated
+    /// by the rejected message.
+    pub state: SessionStateSnapshot,
+}
+
+/// Outcome of delayed Logout<5> response, see
+/// [`SessionSettings::logout_resend_grace_period`](crate::settings::SessionSettings::logout_resend_grace_period).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LogoutGraceOutcome {
+    /// Requested resend completed within grace period
+    ResendCompleted,
+    /// Grace period elapsed before resend completed
+    Expired,
+}
+
+/// Field of received Logon<A> not matching the session configuration,
+/// see [`FixEvent::LogonMismatch`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LogonMismatch {
+    pub tag: TagNum,
+    pub expected: FixString,
+    pub received: FixString,
+}
+
+/// Failed write of outgoing message to messages storage, see
+/// [`FixEvent::StoreFailed`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StoreFailure {
+    /// MsgSeqNum<34> of the message
+    pub msg_seq_num: SeqNum,
+    /// MsgType<35> of the message
+    pub msg_type: FixString,
+    /// Storage error description
+    pub error: String,
+    /// Reaction to the failure
+    pub policy: StoreFailurePolicy,
+}
+
+/// Outgoing message which could not be serialized, see
+/// [`FixEvent::SerializeFailed`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SerializeFailure {
+    /// The message, with header already filled
+    pub msg: FixtMessage,
+    /// Tag of the offending field
+    pub tag: TagNum,
+    /// Serialization error description
+    pub error: String,
+    /// Reaction to the failure
+    pub policy: SerializeFailurePolicy,
+}
+
+/// Received message with BeginString<8> not matching the session, see
+/// [`FixEvent::BeginStringMismatch`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BeginStringMismatch {
+    /// MsgSeqNum<34> of the message
+    pub msg_seq_num: SeqNum,
+    /// MsgType<35> of the message
+    pub msg_type: FixString,
+    /// BeginString<8> of the session
+    pub expected: FixString,
+    /// BeginString<8> of the message
+    pub received: FixString,
+    /// Reaction to the mismatch
+    pub policy: BeginStringMismatchPolicy,
+}
+
+/// Application message dropped without sending, because it was queued
+/// longer than its TTL, see [`FixEvent::AppMsgExpired`].
+#[derive(Clone, Debug)]
+pub struct ExpiredMsg {
+    pub msg: Box<FixtMessage>,
+    /// Time since the message was queued
+    pub age: Duration,
+    /// TTL of the message
+    pub ttl: Duration,
+}
+
+/// Why application message was discarded without sending, see
+/// [`DiscardedMsg`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DiscardReason {
+    /// Session is paused with [`PauseMode::Reject`](crate::PauseMode::Reject)
+    Paused,
+    /// Session is paused with [`PauseMode::Buffer`](crate::PauseMode::Buffer)
+    /// and buffer already holds
+    /// [`SessionSettings::paused_queue_limit`](crate::settings::SessionSettings::paused_queue_limit)
+    /// messages
+    PausedQueueFull,
+}
+
+/// Application message dropped without sending, because session was
+/// paused, see [`FixEvent::AppMsgDiscarded`].
+#[derive(Clone, Debug)]
+pub struct DiscardedMsg {
+    pub msg: Box<FixtMessage>,
+    pub reason: DiscardReason,
+}
+
+/// Application message flagged by
+/// [`Inspector`](crate::inspector::Inspector), see [`FixEvent::MsgFlagged`].
+#[derive(Clone, Debug)]
+pub struct FlaggedMsg {
+    pub direction: MsgDirection,
+    pub msg: Box<FixtMessage>,
+    pub findings: Arc<[Finding]>,
+}
+
+/// Inbound message dropped as garbled because of CheckSum<10>
+/// or BodyLength<9> mismatch, see [`FixEvent::FramingError`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FramingError {
+    /// Calculated and received values
+    pub mismatch: FramingMismatch,
+    /// Hexdump of received data around CheckSum<10> field
+    pub hexdump: String,
+}
+
+/// SessionStatus<1409> received in Logon<A> or Logout<5>, see
+/// [`FixEvent::SessionStatus`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionStatusReport {
+    /// MsgType<35> of the message, Logon<A> or Logout<5>
+    pub msg_type: MsgType,
+    pub status: SessionStatus,
+    /// Text<58> of the message
+    pub text: Option<FixString>,
+}
+
+impl SessionStatusReport {
+    /// Password is about to expire or already expired, it should be changed
+    /// with [`LogonCredentials::new_password`](crate::settings::LogonCredentials::new_password).
+    pub fn password_expiry(&self) -> bool {
+        matches!(
+            self.status,
+            SessionStatus::SessionPasswordDueToExpire | SessionStatus::PasswordExpired
+        )
+    }
+}
+
+/// Stage of message processing, see
+/// [`LatencyBudgets`](crate::settings::LatencyBudgets).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LatencyStage {
+    /// Deserialization of received message
+    Decode,
+    /// Verification of received message
+    Verify,
+    /// Handling of received message by application
+    AppHandler,
+    /// Serialization and write of outgoing message
+    SerializeWrite,
+}
+
+/// Processing stage which exceeded its budget, see
+/// [`FixEvent::LatencyBudgetExceeded`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LatencyAlert {
+    pub stage: LatencyStage,
+    /// MsgSeqNum<34> of the message
+    pub msg_seq_num: SeqNum,
+    /// MsgType<35> of the message
+    pub msg_type: FixString,
+    /// Time taken by the stage
+    pub elapsed: Duration,
+    /// Budget of the stage
+    pub budget: Duration,
+}
+
+/// Verification failure which caused session logout or disconnection,
+/// see [`FixEvent::LogoutDetails`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LogoutDetails {
+    /// SessionRejectReason<373> of message rejected with logout.
+    pub reject_reason: Option<SessionRejectReason>,
+    /// Tag which failed verification.
+    pub tag: Option<TagNum>,
+    /// MsgType<35> of message which failed verification.
+    pub msg_type: Option<FixString>,
+    /// MsgSeqNum<34> of message which failed verification.
+    pub msg_seq_num: Option<SeqNum>,
+    /// SenderCompID<49> of received message, when CompIDs verification failed.
+    pub sender_comp_id: Option<FixString>,
+    /// TargetCompID<56> of received message, when CompIDs verification failed.
+    pub target_comp_id: Option<FixString>,
+    /// Description of the failure.
+    pub text: String,
+}
+
+/// Decision about SequenceReset<4> in Reset mode, requested when
+/// [`SequenceResetPolicy::Confirm`](crate::settings::SequenceResetPolicy::Confirm)
+/// is configured.
+///
+/// New sequence number is rejected when dropped without decision.
+#[derive(Debug)]
+pub struct SequenceResetConfirmation<'a> {
+    sender: oneshot::Sender<bool>,
+    phantom_ref: PhantomData<&'a ()>,
+}
+
+impl SequenceResetConfirmation<'_> {
+    /// Set next expected MsgSeqNum<34> to NewSeqNo<36>.
+    pub fn accept(self) {
+        // Error means session is already disconnected
+        let _ = self.sender.send(true);
+    }
+
+    /// Keep next expected MsgSeqNum<34> and send Reject<3>.
+    pub fn reject(self) {
+        let _ = self.sender.send(false);
+    }
+}
+
 pub struct DoNotSend {
     pub gap_fill: bool,
 }
@@ -98,6 +453,49 @@ pub(crate) enum InputResponderMsg {
     Disconnect {
         reason: Option<String>,
     },
+    Unsupported {
+        ref_msg_type: FixString,
+        ref_seq_num: SeqNum,
+        business_reject_ref_id: Option<FixString>,
+    },
+}
+
+/// Business-level "ID" fields, value of the first one found in message
+/// is used as BusinessRejectRefID<379>.
+const BUSINESS_REJECT_REF_ID_TAGS: &[TagNum] = &[
+    11,   // ClOrdID
+    66,   // ListID
+    70,   // AllocID
+    117,  // QuoteID
+    131,  // QuoteReqID
+    262,  // MDReqID
+    320,  // SecurityReqID
+    324,  // SecurityStatusReqID
+    335,  // TradSesReqID
+    390,  // BidID
+    568,  // TradeRequestID
+    571,  // TradeReportID
+    584,  // MassStatusReqID
+    644,  // RFQReqID
+    664,  // ConfirmID
+    710,  // PosReqID
+    721,  // PosMaintRptID
+    791,  // SettlInstReqID
+    894,  // CollReqID
+    923,  // UserRequestID
+    1346, // ApplReqID
+];
+
+/// Find BusinessRejectRefID<379> value for `msg`.
+fn business_reject_ref_id(msg: &FixtMessage) -> Option<FixString> {
+    let buffer = msg.serialize();
+    let (_, raw_msg) = deserializer::raw_message(&buffer).ok()?;
+    let value = raw_msg
+        .tag_values()
+        .map_while(Result::ok)
+        .find(|(tag, _)| BUSINESS_REJECT_REF_ID_TAGS.contains(tag))
+        .map(|(_, value)| value)?;
+    FixString::from_ascii(value.to_vec()).ok()
 }
 
 #[derive(Debug)]
@@ -153,6 +551,121 @@ impl<'a> InputResponder<'a> {
             .send(InputResponderMsg::Disconnect { reason: None })
             .unwrap();
     }
+
+    /// Decline message `msg` of type not supported by the application,
+    /// session replies according to
+    /// [`SessionSettings::unsupported_msg_type`](crate::settings::SessionSettings::unsupported_msg_type).
+    ///
+    /// BusinessRejectRefID<379> is taken from the business-level ID field
+    /// of `msg` (ClOrdID<11>, QuoteReqID<131>, MDReqID<262> etc.), when
+    /// present.
+    pub fn unsupported(self, msg: &FixtMessage) {
+        self.sender
+            .send(InputResponderMsg::Unsupported {
+                ref_msg_type: msg.msg_type().as_fix_str().to_owned(),
+                ref_seq_num: msg.header.msg_seq_num,
+                business_reject_ref_id: business_reject_ref_id(msg),
+            })
+            .unwrap();
+    }
+}
+
+/// Application message of [`FixEvent::AppMsgOut`], readable as
+/// `FixtMessage`.
+///
+/// Header fields owned by session engine, like MsgSeqNum<34> or
+/// BeginString<8>, can't be modified unless message is explicitly unlocked
+/// with [`OutgoingMsg::unlock`].
+#[derive(Debug)]
+pub struct OutgoingMsg<'a>(&'a mut FixtMessage);
+
+impl Deref for OutgoingMsg<'_> {
+    type Target = FixtMessage;
+
+    fn deref(&self) -> &FixtMessage {
+        self.0
+    }
+}
+
+impl<'a> OutgoingMsg<'a> {
+    /// Header with only fields safe to change editable.
+    pub fn header_mut(&mut self) -> HeaderMut<'_> {
+        HeaderMut(&mut self.0.header)
+    }
+
+    pub fn body_mut(&mut self) -> &mut Message {
+        &mut self.0.body
+    }
+
+    /// Unrestricted access to whole message.
+    ///
+    /// Changing MsgSeqNum<34> breaks sequence of sent messages (and
+    /// messages stored for resend), counterparty will disconnect or
+    /// request resend.
+    pub fn unlock(self) -> &'a mut FixtMessage {
+        self.0
+    }
+}
+
+macro_rules! header_setters {
+    ($($(#[$attr:meta])* $setter:ident: $field:ident,)*) => {
+        $(
+            $(#[$attr])*
+            pub fn $setter(&mut self, value: Option<FixString>) {
+                self.0.$field = value;
+            }
+        )*
+    };
+}
+
+/// Header of [`OutgoingMsg`], readable as `Header`, with editable routing
+/// fields and SendingTime<52> precision.
+#[derive(Debug)]
+pub struct HeaderMut<'a>(&'a mut Header);
+
+impl Deref for HeaderMut<'_> {
+    type Target = Header;
+
+    fn deref(&self) -> &Header {
+        self.0
+    }
+}
+
+impl<'a> HeaderMut<'a> {
+    header_setters! {
+        /// SenderSubID<50>
+        set_sender_sub_id: sender_sub_id,
+        /// SenderLocationID<142>
+        set_sender_location_id: sender_location_id,
+        /// TargetSubID<57>
+        set_target_sub_id: target_sub_id,
+        /// TargetLocationID<143>
+        set_target_location_id: target_location_id,
+        /// OnBehalfOfCompID<115>
+        set_on_behalf_of_comp_id: on_behalf_of_comp_id,
+        /// OnBehalfOfSubID<116>
+        set_on_behalf_of_sub_id: on_behalf_of_sub_id,
+        /// OnBehalfOfLocationID<144>
+        set_on_behalf_of_location_id: on_behalf_of_location_id,
+        /// DeliverToCompID<128>
+        set_deliver_to_comp_id: deliver_to_comp_id,
+        /// DeliverToSubID<129>
+        set_deliver_to_sub_id: deliver_to_sub_id,
+        /// DeliverToLocationID<145>
+        set_deliver_to_location_id: deliver_to_location_id,
+    }
+
+    /// Serialize SendingTime<52> with given precision, finer precision
+    /// than the one of already set timestamp adds trailing zeros.
+    pub fn set_sending_time_precision(&mut self, precision: TimePrecision) {
+        self.0.sending_time =
+            UtcTimestamp::with_precision(self.0.sending_time.timestamp(), precision);
+    }
+
+    /// Unrestricted access to whole header, see [`OutgoingMsg::unlock`].
+    pub fn unlock(self) -> &'a mut Header {
+        self.0
+    }
 }
 
 #[derive(Debug)]
@@ -192,9 +705,45 @@ pub(crate) enum FixEventInternal {
         Option<Box<FixtMessage>>,
         Option<oneshot::Sender<InputResponderMsg>>,
     ),
+    PartialAppMsgIn(
+        Option<Box<FixtMessage>>,
+        Vec<FieldError>,
+        Option<oneshot::Sender<InputResponderMsg>>,
+    ),
+    TestAppMsgIn(
+        Option<Box<FixtMessage>>,
+        Option<oneshot::Sender<InputResponderMsg>>,
+    ),
     AppMsgOut(Option<Box<FixtMessage>>, Responder),
     AdmMsgOut(Option<Box<FixtMessage>>, Responder),
     DeserializeError(SessionId, DeserializeError),
+    Resent(SessionId, ResendReport),
+    Reject(SessionId, Box<RejectReport>),
+    SequenceResetConfirm {
+        session_id: SessionId,
+        next_target_msg_seq_num: SeqNum,
+        new_seq_no: SeqNum,
+        sender: Option<oneshot::Sender<bool>>,
+    },
+    LogoutGrace(SessionId, LogoutGraceOutcome),
+    LogonMismatch(SessionId, LogonMismatch),
+    LogoutDetails(SessionId, Box<LogoutDetails>),
+    StoreFailed(SessionId, Box<StoreFailure>),
+    AppMsgExpired(SessionId, Box<ExpiredMsg>),
+    AppMsgDiscarded(SessionId, Box<DiscardedMsg>),
+    BeginStringMismatch(SessionId, Box<BeginStringMismatch>),
+    SerializeFailed(SessionId, Box<SerializeFailure>),
+    HeartbeatReply {
+        session_id: SessionId,
+        test_request: Box<FixtMessage>,
+        reply: Option<Box<FixtMessage>>,
+        sender: Option<oneshot::Sender<Box<FixtMessage>>>,
+    },
+    ReadIdle(SessionId, Duration),
+    MsgFlagged(SessionId, Box<FlaggedMsg>),
+    LatencyBudgetExceeded(SessionId, Box<LatencyAlert>),
+    FramingError(SessionId, Box<FramingError>),
+    SessionStatus(SessionId, Box<SessionStatusReport>),
 }
 
 impl Drop for FixEventInternal {
@@ -210,6 +759,11 @@ impl Drop for FixEventInternal {
                     sender.send(msg.take().unwrap()).unwrap();
                 }
             }
+        } else if let FixEventInternal::HeartbeatReply { reply, sender, .. } = self {
+            if let (Some(reply), Some(sender)) = (reply.take(), sender.take()) {
+                // Session may be already gone
+                let _ = sender.send(reply);
+            }
         }
     }
 }
@@ -222,7 +776,9 @@ pub enum FixEvent<'a> {
 
     /// Successfull Logon<A> messages exchange.
     ///
-    /// Use `Sender` to send messages to connected peer.
+    /// Use `Sender` to send messages to connected peer. `Sender` is
+    /// privileged handle, use [`Sender::app_sender`] to get handle restricted
+    /// to application messages before passing it to business code.
     Logon(&'a SessionId, Sender),
 
     /// Session disconnected.
@@ -240,6 +796,24 @@ pub enum FixEvent<'a> {
     /// disconnection.
     AdmMsgIn(Box<FixtMessage>, InputResponder<'a>),
 
+    /// New application message received, with invalid values of optional
+    /// fields skipped, see [`FieldErrorsPolicy`](crate::settings::FieldErrorsPolicy).
+    ///
+    /// Use `InputResponder` to reject the message or to force logut or
+    /// disconnection.
+    PartialAppMsgIn(Box<FixtMessage>, &'a [FieldError], InputResponder<'a>),
+
+    /// New application message received from counterparty logged on with
+    /// TestMessageIndicator<464>=Y by production session, see
+    /// [`TestMessagesPolicy::Segregate`](crate::settings::TestMessagesPolicy::Segregate).
+    /// Invalid values of optional fields are skipped according to
+    /// [`FieldErrorsPolicy`](crate::settings::FieldErrorsPolicy), but not
+    /// reported.
+    ///
+    /// Use `InputResponder` to reject the message or to force logut or
+    /// disconnection.
+    TestAppMsgIn(Box<FixtMessage>, InputResponder<'a>),
+
     /// Application message is ready to be send.
     ///
     /// Use `Responder` to change the message to GapFill or to discard it.
@@ -247,7 +821,14 @@ pub enum FixEvent<'a> {
     /// This event may happen after session disconnection when output queue
     /// still has messages to send. In such case all messages will be stored
     /// and will be available thorough ResendRequest<2>.
-    AppMsgOut(&'a mut FixtMessage, &'a mut Responder), // TODO: Try pass by value but bind named
+    ///
+    /// Message is stored after this event, or before it when
+    /// [`SessionSettings::output_order`](crate::settings::SessionSettings::output_order)
+    /// is `StoreThenEmit`.
+    ///
+    /// Header fields owned by session engine are protected from
+    /// modification, see [`OutgoingMsg`].
+    AppMsgOut(OutgoingMsg<'a>, &'a mut Responder),
 
     /// Administration message is ready to be send.
     ///
@@ -256,43 +837,571 @@ pub enum FixEvent<'a> {
     /// This event may happen after session disconnection when output queue
     /// still has messages to send. In such case all messages will be stored
     /// and will be available thorough ResendRequest<2>.
+    ///
+    /// Message is stored after this event, or before it when
+    /// [`SessionSettings::output_order`](crate::settings::SessionSettings::output_order)
+    /// is `StoreThenEmit`.
     AdmMsgOut(&'a mut FixtMessage),
 
     /// Failed to deserialize input message.
     DeserializeError(&'a SessionId, &'a DeserializeError),
+
+    /// Messages resent in response to ResendRequest<2> or to
+    /// NextExpectedMsgSeqNum<789> received in Logon<A>.
+    Resent(&'a SessionId, ResendReport),
+
+    /// Session-level Reject<3> sent or received.
+    Reject(&'a SessionId, &'a RejectReport),
+
+    /// SequenceReset<4> in Reset mode received, new sequence number has to
+    /// be accepted or rejected using `SequenceResetConfirmation`.
+    SequenceResetConfirm {
+        session_id: &'a SessionId,
+        /// Currently expected MsgSeqNum<34>
+        next_target_msg_seq_num: SeqNum,
+        /// NewSeqNo<36>
+        new_seq_no: SeqNum,
+        confirmation: SequenceResetConfirmation<'a>,
+    },
+
+    /// Delayed response to Logout<5> sent, because requested resend
+    /// completed or grace period elapsed.
+    LogoutGrace(&'a SessionId, LogoutGraceOutcome),
+
+    /// Logon<A> response doesn't match expected SessionId or
+    /// DefaultApplVerID<1137>, session is logged out and disconnected.
+    ///
+    /// Also reported for RawData<96> of any received Logon<A> when
+    /// [`SessionSettings::dictionary_fingerprint`](crate::settings::SessionSettings::dictionary_fingerprint)
+    /// is set and counterparty's dictionary fingerprint differs, session
+    /// is logged out only when `refuse_mismatch` is set.
+    LogonMismatch(&'a SessionId, &'a LogonMismatch),
+
+    /// Details of verification failure which caused logout or
+    /// disconnection, emitted just before [`FixEvent::Logout`], or alone
+    /// when connection is dropped before logon completed.
+    ///
+    /// Not emitted when session ends for other reasons (Logout<5> requested
+    /// by either side, I/O errors).
+    LogoutDetails(&'a SessionId, &'a LogoutDetails),
+
+    /// Outgoing message could not be stored, it's handled according to
+    /// [`SessionSettings::store_failure`](crate::settings::SessionSettings::store_failure).
+    StoreFailed(&'a SessionId, &'a StoreFailure),
+
+    /// Application message queued longer than its TTL was dropped without
+    /// sending, see
+    /// [`SessionSettings::app_msg_ttl`](crate::settings::SessionSettings::app_msg_ttl).
+    AppMsgExpired(&'a SessionId, &'a ExpiredMsg),
+
+    /// Application message was dropped without sending, because session
+    /// was paused, see [`PauseMode`](crate::PauseMode).
+    AppMsgDiscarded(&'a SessionId, &'a DiscardedMsg),
+
+    /// Received message has BeginString<8> other than the session,
+    /// it's handled according to
+    /// [`SessionSettings::begin_string_mismatch`](crate::settings::SessionSettings::begin_string_mismatch).
+    BeginStringMismatch(&'a SessionId, &'a BeginStringMismatch),
+
+    /// Outgoing message could not be serialized and was not sent, it's
+    /// handled according to
+    /// [`SessionSettings::serialize_failure`](crate::settings::SessionSettings::serialize_failure).
+    SerializeFailed(&'a SessionId, &'a SerializeFailure),
+
+    /// TestRequest<1> received, `reply` is Heartbeat<0> to be sent
+    /// in response, with TestReqID<112> echoed. Fields of `reply` can be
+    /// modified, it's sent when the event is dropped.
+    ///
+    /// Emitted only when
+    /// [`SessionSettings::test_request_reply`](crate::settings::SessionSettings::test_request_reply)
+    /// is `Customize`.
+    HeartbeatReply {
+        session_id: &'a SessionId,
+        test_request: &'a FixtMessage,
+        reply: &'a mut FixtMessage,
+    },
+
+    /// No data read from socket for given time, it's handled according to
+    /// [`Settings::read_idle`](crate::settings::Settings::read_idle).
+    ReadIdle(&'a SessionId, Duration),
+
+    /// Application message flagged by
+    /// [`SessionSettings::inspector`](crate::settings::SessionSettings::inspector).
+    /// Inbound message is reported before it's delivered with
+    /// [`FixEvent::AppMsgIn`], outbound one just before it's serialized.
+    MsgFlagged(&'a SessionId, &'a FlaggedMsg),
+
+    /// Processing stage of a message took longer than its budget, see
+    /// [`SessionSettings::latency_budgets`](crate::settings::SessionSettings::latency_budgets).
+    /// Emitted after the stage completes, message is processed as usual.
+    LatencyBudgetExceeded(&'a SessionId, &'a LatencyAlert),
+
+    /// Received message has CheckSum<10> or BodyLength<9> not matching its
+    /// content. It's dropped as garbled anyway, this event only provides
+    /// details, see
+    /// [`Settings::framing_errors`](crate::settings::Settings::framing_errors).
+    /// Emitted before [`FixEvent::DeserializeError`] of the message.
+    FramingError(&'a SessionId, &'a FramingError),
+
+    /// SessionStatus<1409> received in accepted Logon<A> or in Logout<5>,
+    /// e.g. password change confirmation or expiry notice. Emitted before
+    /// [`FixEvent::Logon`] or [`FixEvent::Logout`] of the session.
+    SessionStatus(&'a SessionId, &'a SessionStatusReport),
+}
+
+/// Kind of [`SessionEvent`], used to filter events delivered to subscriber.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SessionEventKind {
+    Created,
+    Logon,
+    Logout,
+    AppMsgIn,
+    AdmMsgIn,
+    PartialAppMsgIn,
+    TestAppMsgIn,
+    AppMsgOut,
+    AdmMsgOut,
+    DeserializeError,
+    Resent,
+    Reject,
+    SequenceResetConfirm,
+    LogoutGrace,
+    LogonMismatch,
+    LogoutDetails,
+    StoreFailed,
+    AppMsgExpired,
+    AppMsgDiscarded,
+    BeginStringMismatch,
+    SerializeFailed,
+    ReadIdle,
+    MsgFlagged,
+    LatencyBudgetExceeded,
+    FramingError,
+    SessionStatus,
+}
+
+/// Read-only copy of FIX protocol event, delivered to every subscriber
+/// of [`EventBroadcast`].
+///
+/// Unlike [`FixEvent`], broadcast events can't be used to respond to
+/// the counterparty, they are meant for observers like metrics or audit.
+/// Outgoing messages are captured before [`FixEvent::AppMsgOut`] and
+/// [`FixEvent::AdmMsgOut`] are handled, so they don't reflect changes
+/// made there.
+#[derive(Clone, Debug)]
+pub enum SessionEvent {
+    Created(SessionId),
+    Logon(SessionId),
+    Logout(SessionId, DisconnectReason),
+    AppMsgIn(SessionId, Arc<FixtMessage>),
+    AdmMsgIn(SessionId, Arc<FixtMessage>),
+    PartialAppMsgIn(SessionId, Arc<FixtMessage>, Arc<[FieldError]>),
+    TestAppMsgIn(SessionId, Arc<FixtMessage>),
+    AppMsgOut(SessionId, Arc<FixtMessage>),
+    AdmMsgOut(SessionId, Arc<FixtMessage>),
+    DeserializeError(SessionId, Arc<DeserializeError>),
+    Resent(SessionId, ResendReport),
+    Reject(SessionId, Arc<RejectReport>),
+    SequenceResetConfirm {
+        session_id: SessionId,
+        next_target_msg_seq_num: SeqNum,
+        new_seq_no: SeqNum,
+    },
+    LogoutGrace(SessionId, LogoutGraceOutcome),
+    LogonMismatch(SessionId, LogonMismatch),
+    LogoutDetails(SessionId, Arc<LogoutDetails>),
+    StoreFailed(SessionId, Arc<StoreFailure>),
+    AppMsgExpired(SessionId, Arc<ExpiredMsg>),
+    AppMsgDiscarded(SessionId, Arc<DiscardedMsg>),
+    BeginStringMismatch(SessionId, Arc<BeginStringMismatch>),
+    SerializeFailed(SessionId, Arc<SerializeFailure>),
+    ReadIdle(SessionId, Duration),
+    MsgFlagged(SessionId, Arc<FlaggedMsg>),
+    LatencyBudgetExceeded(SessionId, Arc<LatencyAlert>),
+    FramingError(SessionId, Arc<FramingError>),
+    SessionStatus(SessionId, Arc<SessionStatusReport>),
+}
+
+impl SessionEvent {
+    pub(crate) fn from_internal(event: &FixEventInternal) -> Option<SessionEvent> {
+        let event = match event {
+            FixEventInternal::Created(id) => SessionEvent::Created(id.clone()),
+            FixEventInternal::Logon(id, _) => SessionEvent::Logon(id.clone()),
+            FixEventInternal::Logout(id, reason) => SessionEvent::Logout(id.clone(), *reason),
+            FixEventInternal::AppMsgIn(Some(msg), _) => {
+                SessionEvent::AppMsgIn(SessionId::from_input_msg(msg), Arc::new((**msg).clone()))
+            }
+            FixEventInternal::AdmMsgIn(Some(msg), _) => {
+                SessionEvent::AdmMsgIn(SessionId::from_input_msg(msg), Arc::new((**msg).clone()))
+            }
+            FixEventInternal::PartialAppMsgIn(Some(msg), field_errors, _) => {
+                SessionEvent::PartialAppMsgIn(
+                    SessionId::from_input_msg(msg),
+                    Arc::new((**msg).clone()),
+                    field_errors.as_slice().into(),
+                )
+            }
+            FixEventInternal::TestAppMsgIn(Some(msg), _) => SessionEvent::TestAppMsgIn(
+                SessionId::from_input_msg(msg),
+                Arc::new((**msg).clone()),
+            ),
+            FixEventInternal::AppMsgOut(Some(msg), _) => {
+                SessionEvent::AppMsgOut(SessionId::from_output_msg(msg), Arc::new((**msg).clone()))
+            }
+            FixEventInternal::AdmMsgOut(Some(msg), _) => {
+                SessionEvent::AdmMsgOut(SessionId::from_output_msg(msg), Arc::new((**msg).clone()))
+            }
+            FixEventInternal::DeserializeError(id, error) => {
+                SessionEvent::DeserializeError(id.clone(), Arc::new(error.clone()))
+            }
+            FixEventInternal::Resent(id, report) => SessionEvent::Resent(id.clone(), *report),
+            FixEventInternal::Reject(id, report) => {
+                SessionEvent::Reject(id.clone(), Arc::new((**report).clone()))
+            }
+            FixEventInternal::SequenceResetConfirm {
+                session_id,
+                next_target_msg_seq_num,
+                new_seq_no,
+                ..
+            } => SessionEvent::SequenceResetConfirm {
+                session_id: session_id.clone(),
+                next_target_msg_seq_num: *next_target_msg_seq_num,
+                new_seq_no: *new_seq_no,
+            },
+            FixEventInternal::LogoutGrace(id, outcome) => {
+                SessionEvent::LogoutGrace(id.clone(), *outcome)
+            }
+            FixEventInternal::LogonMismatch(id, mismatch) => {
+                SessionEvent::LogonMismatch(id.clone(), mismatch.clone())
+            }
+            FixEventInternal::LogoutDetails(id, details) => {
+                SessionEvent::LogoutDetails(id.clone(), Arc::new((**details).clone()))
+            }
+            FixEventInternal::StoreFailed(id, failure) => {
+                SessionEvent::StoreFailed(id.clone(), Arc::new((**failure).clone()))
+            }
+            FixEventInternal::AppMsgExpired(id, expired) => {
+                SessionEvent::AppMsgExpired(id.clone(), Arc::new((**expired).clone()))
+            }
+            FixEventInternal::AppMsgDiscarded(id, discarded) => {
+                SessionEvent::AppMsgDiscarded(id.clone(), Arc::new((**discarded).clone()))
+            }
+            FixEventInternal::BeginStringMismatch(id, mismatch) => {
+                SessionEvent::BeginStringMismatch(id.clone(), Arc::new((**mismatch).clone()))
+            }
+            FixEventInternal::SerializeFailed(id, failure) => {
+                SessionEvent::SerializeFailed(id.clone(), Arc::new((**failure).clone()))
+            }
+            FixEventInternal::ReadIdle(id, idle) => SessionEvent::ReadIdle(id.clone(), *idle),
+            FixEventInternal::MsgFlagged(id, flagged) => {
+                SessionEvent::MsgFlagged(id.clone(), Arc::new((**flagged).clone()))
+            }
+            FixEventInternal::LatencyBudgetExceeded(id, alert) => {
+                SessionEvent::LatencyBudgetExceeded(id.clone(), Arc::new((**alert).clone()))
+            }
+            FixEventInternal::FramingError(id, error) => {
+                SessionEvent::FramingError(id.clone(), Arc::new((**error).clone()))
+            }
+            FixEventInternal::SessionStatus(id, report) => {
+                SessionEvent::SessionStatus(id.clone(), Arc::new((**report).clone()))
+            }
+            FixEventInternal::AppMsgIn(None, _)
+            | FixEventInternal::AdmMsgIn(None, _)
+            | FixEventInternal::PartialAppMsgIn(None, ..)
+            | FixEventInternal::TestAppMsgIn(None, _)
+            | FixEventInternal::AppMsgOut(None, _)
+            | FixEventInternal::AdmMsgOut(None, _) => return None,
+            // Heartbeat<0> is broadcasted as `AdmMsgOut` when sent
+            FixEventInternal::HeartbeatReply { .. } => return None,
+        };
+        Some(event)
+    }
+
+    pub fn kind(&self) -> SessionEventKind {
+        match self {
+            SessionEvent::Created(_) => SessionEventKind::Created,
+            SessionEvent::Logon(_) => SessionEventKind::Logon,
+            SessionEvent::Logout(..) => SessionEventKind::Logout,
+            SessionEvent::AppMsgIn(..) => SessionEventKind::AppMsgIn,
+            SessionEvent::AdmMsgIn(..) => SessionEventKind::AdmMsgIn,
+            SessionEvent::PartialAppMsgIn(..) => SessionEventKind::PartialAppMsgIn,
+            SessionEvent::TestAppMsgIn(..) => SessionEventKind::TestAppMsgIn,
+            SessionEvent::AppMsgOut(..) => SessionEventKind::AppMsgOut,
+            SessionEvent::AdmMsgOut(..) => SessionEventKind::AdmMsgOut,
+            SessionEvent::DeserializeError(..) => SessionEventKind::DeserializeError,
+            SessionEvent::Resent(..) => SessionEventKind::Resent,
+            SessionEvent::Reject(..) => SessionEventKind::Reject,
+            SessionEvent::SequenceResetConfirm { .. } => SessionEventKind::SequenceResetConfirm,
+            SessionEvent::LogoutGrace(..) => SessionEventKind::LogoutGrace,
+            SessionEvent::LogonMismatch(..) => SessionEventKind::LogonMismatch,
+            SessionEvent::LogoutDetails(..) => SessionEventKind::LogoutDetails,
+            SessionEvent::StoreFailed(..) => SessionEventKind::StoreFailed,
+            SessionEvent::AppMsgExpired(..) => SessionEventKind::AppMsgExpired,
+            SessionEvent::AppMsgDiscarded(..) => SessionEventKind::AppMsgDiscarded,
+            SessionEvent::BeginStringMismatch(..) => SessionEventKind::BeginStringMismatch,
+            SessionEvent::SerializeFailed(..) => SessionEventKind::SerializeFailed,
+            SessionEvent::ReadIdle(..) => SessionEventKind::ReadIdle,
+            SessionEvent::MsgFlagged(..) => SessionEventKind::MsgFlagged,
+            SessionEvent::LatencyBudgetExceeded(..) => SessionEventKind::LatencyBudgetExceeded,
+            SessionEvent::FramingError(..) => SessionEventKind::FramingError,
+            SessionEvent::SessionStatus(..) => SessionEventKind::SessionStatus,
+        }
+    }
+
+    pub fn session_id(&self) -> &SessionId {
+        match self {
+            SessionEvent::Created(id)
+            | SessionEvent::Logon(id)
+            | SessionEvent::Logout(id, _)
+            | SessionEvent::AppMsgIn(id, _)
+            | SessionEvent::AdmMsgIn(id, _)
+            | SessionEvent::PartialAppMsgIn(id, ..)
+            | SessionEvent::TestAppMsgIn(id, _)
+            | SessionEvent::AppMsgOut(id, _)
+            | SessionEvent::AdmMsgOut(id, _)
+            | SessionEvent::DeserializeError(id, _)
+            | SessionEvent::Resent(id, _)
+            | SessionEvent::Reject(id, _)
+            | SessionEvent::SequenceResetConfirm { session_id: id, .. }
+            | SessionEvent::LogoutGrace(id, _)
+            | SessionEvent::LogonMismatch(id, _)
+            | SessionEvent::LogoutDetails(id, _)
+            | SessionEvent::StoreFailed(id, _)
+            | SessionEvent::AppMsgExpired(id, _)
+            | SessionEvent::AppMsgDiscarded(id, _)
+            | SessionEvent::BeginStringMismatch(id, _)
+            | SessionEvent::SerializeFailed(id, _)
+            | SessionEvent::ReadIdle(id, _)
+            | SessionEvent::MsgFlagged(id, _)
+            | SessionEvent::LatencyBudgetExceeded(id, _)
+            | SessionEvent::FramingError(id, _)
+            | SessionEvent::SessionStatus(id, _) => id,
+        }
+    }
+
+    /// SenderSubID<50>/TargetSubID<57> route of message events, from
+    /// local side perspective, see [`SubIdRoute`].
+    pub fn sub_id_route(&self) -> Option<SubIdRoute> {
+        match self {
+            SessionEvent::AppMsgIn(_, msg)
+            | SessionEvent::AdmMsgIn(_, msg)
+            | SessionEvent::PartialAppMsgIn(_, msg, _)
+            | SessionEvent::TestAppMsgIn(_, msg) => Some(SubIdRoute::from_input_msg(msg)),
+            SessionEvent::AppMsgOut(_, msg) | SessionEvent::AdmMsgOut(_, msg) => {
+                Some(SubIdRoute::from_output_msg(msg))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Handle used to subscribe to [`SessionEvent`]s.
+///
+/// Every subscriber receives its own copy of each event, independently of
+/// other subscribers and of the primary [`EventStream`] consumer.
+#[derive(Clone, Debug)]
+pub struct EventBroadcast {
+    sender: broadcast::Sender<(EventTimestamp, SessionEvent)>,
+}
+
+impl EventBroadcast {
+    /// Default capacity of the events buffer, subscribers lagging more
+    /// than that miss the oldest events, see
+    /// [`BufferSettings::subscriber_capacity`].
+    pub const CAPACITY: usize = 1024;
+
+    fn new(capacity: usize) -> EventBroadcast {
+        EventBroadcast {
+            sender: broadcast::channel(capacity).0,
+        }
+    }
+
+    /// Subscribe to all events emitted after this call.
+    pub fn subscribe(&self) -> EventSubscriber {
+        EventSubscriber {
+            receiver: BroadcastStream::new(self.sender.subscribe()),
+            kinds: None,
+            session_id: None,
+            sub_id_route: None,
+        }
+    }
+
+    fn send(&self, event: &FixEventInternal, timestamp: EventTimestamp) {
+        // Don't copy messages when nobody listens
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+        if let Some(event) = SessionEvent::from_internal(event) {
+            // Error means there are no subscribers
+            let _ = self.sender.send((timestamp, event));
+        }
+    }
+}
+
+/// Stream of [`SessionEvent`]s, optionally filtered by event kind,
+/// session and SubIDs route.
+#[derive(Debug)]
+pub struct EventSubscriber {
+    receiver: BroadcastStream<(EventTimestamp, SessionEvent)>,
+    kinds: Option<Vec<SessionEventKind>>,
+    session_id: Option<SessionId>,
+    sub_id_route: Option<SubIdRoute>,
+}
+
+impl EventSubscriber {
+    /// Receive only events of given kinds.
+    pub fn with_kinds(mut self, kinds: &[SessionEventKind]) -> EventSubscriber {
+        self.kinds = Some(kinds.to_vec());
+        self
+    }
+
+    /// Receive only events of given session.
+    pub fn with_session_id(mut self, session_id: SessionId) -> EventSubscriber {
+        self.session_id = Some(session_id);
+        self
+    }
+
+    /// Receive only message events of given SubIDs route, see
+    /// [`SubIdRoute::matches`]. Events not related to messages (e.g.
+    /// `Logon` or `Logout`) concern all routes, so they are not filtered.
+    pub fn with_sub_id_route(mut self, route: SubIdRoute) -> EventSubscriber {
+        self.sub_id_route = Some(route);
+        self
+    }
+
+    /// Receive events with their capture time.
+    pub fn with_timestamps(self) -> TimestampedEventSubscriber {
+        TimestampedEventSubscriber { subscriber: self }
+    }
+
+    fn accepts(&self, event: &SessionEvent) -> bool {
+        self.kinds
+            .as_ref()
+            .is_none_or(|kinds| kinds.contains(&event.kind()))
+            && self
+                .session_id
+                .as_ref()
+                .is_none_or(|session_id| session_id == event.session_id())
+            && self.sub_id_route.as_ref().is_none_or(|route| {
+                event
+                    .sub_id_route()
+                    .is_none_or(|event_route| route.matches(&event_route))
+            })
+    }
+}
+
+impl Stream for EventSubscriber {
+    type Item = SessionEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_timestamped(cx)
+            .map(|event| event.map(|(_, event)| event))
+    }
+}
+
+/// [`EventSubscriber`] yielding events with their capture time, see
+/// [`EventSubscriber::with_timestamps`].
+#[derive(Debug)]
+pub struct TimestampedEventSubscriber {
+    subscriber: EventSubscriber,
+}
+
+impl Stream for TimestampedEventSubscriber {
+    type Item = (EventTimestamp, SessionEvent);
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.subscriber).poll_next_timestamped(cx)
+    }
+}
+
+impl EventSubscriber {
+    fn poll_next_timestamped(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<(EventTimestamp, SessionEvent)>> {
+        loop {
+            match Pin::new(&mut self.receiver).poll_next(cx) {
+                Poll::Ready(Some(Ok(event))) => {
+                    if self.accepts(&event.1) {
+                        return Poll::Ready(Some(event));
+                    }
+                }
+                Poll::Ready(Some(Err(BroadcastStreamRecvError::Lagged(skipped)))) => {
+                    warn!("event subscriber lagged, {skipped} events skipped");
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct EventStream {
-    receiver: ReceiverStream<FixEventInternal>,
+    receiver: ReceiverStream<TimestampedEvent>,
+}
+
+/// Event queued for delivery with its capture time.
+#[derive(Debug)]
+pub(crate) struct TimestampedEvent {
+    timestamp: EventTimestamp,
+    event: FixEventInternal,
 }
 
 #[derive(Debug)]
 pub struct Emitter {
-    inner: mpsc::Sender<FixEventInternal>,
+    inner: mpsc::Sender<TimestampedEvent>,
+    broadcast: EventBroadcast,
+    session_log: Option<Rc<SessionLog>>,
 }
 
 impl Clone for Emitter {
     fn clone(&self) -> Self {
         Self {
             inner: self.inner.clone(),
+            broadcast: self.broadcast.clone(),
+            session_log: self.session_log.clone(),
         }
     }
 }
 
 impl Emitter {
+    /// Log all events sent with this emitter to `session_log`.
+    pub(crate) fn with_session_log(mut self, session_log: Option<Rc<SessionLog>>) -> Emitter {
+        self.session_log = session_log;
+        self
+    }
+
     pub(crate) async fn send(&self, event: FixEventInternal) {
-        if let Err(_e) = self.inner.send(event).await {
+        self.send_at(event, EventTimestamp::now()).await
+    }
+
+    /// Send event captured at `timestamp`, e.g. when input message
+    /// was read.
+    pub(crate) async fn send_at(&self, event: FixEventInternal, timestamp: EventTimestamp) {
+        if let Some(session_log) = &self.session_log {
+            session_log.event(&event);
+        }
+        self.broadcast.send(&event, timestamp);
+        if let Err(_e) = self.inner.send(TimestampedEvent { timestamp, event }).await {
             error!("Failed to send msg")
         }
     }
+
+    pub(crate) fn events(&self) -> &EventBroadcast {
+        &self.broadcast
+    }
 }
 
-pub(crate) fn events_channel() -> (Emitter, EventStream) {
-    let (sender, receiver) = mpsc::channel(16);
+pub(crate) fn events_channel(buffers: &BufferSettings) -> (Emitter, EventStream) {
+    let (sender, receiver) = mpsc::channel(buffers.events_capacity);
 
     (
-        Emitter { inner: sender },
+        Emitter {
+            inner: sender,
+            broadcast: EventBroadcast::new(buffers.subscriber_capacity),
+            session_log: None,
+        },
         EventStream {
             receiver: receiver.into(),
         },
@@ -302,15 +1411,28 @@ pub(crate) fn events_channel() -> (Emitter, EventStream) {
 mod private {
     pub trait Sealed {}
 
-    impl Sealed for super::FixEventInternal {}
+    impl Sealed for super::TimestampedEvent {}
 }
 
 /// This trait is sealed and not meant to be implemented outside of the current crate.
 pub trait AsEvent: private::Sealed {
     fn as_event(&mut self) -> FixEvent<'_>;
+
+    /// Capture time of event, see [`EventTimestamp`].
+    fn timestamp(&self) -> EventTimestamp;
 }
 
-impl AsEvent for FixEventInternal {
+impl AsEvent for TimestampedEvent {
+    fn as_event(&mut self) -> FixEvent<'_> {
+        self.event.as_event()
+    }
+
+    fn timestamp(&self) -> EventTimestamp {
+        self.timestamp
+    }
+}
+
+impl FixEventInternal {
     fn as_event(&mut self) -> FixEvent<'_> {
         match self {
             FixEventInternal::Created(id) => FixEvent::Created(id),
@@ -324,13 +1446,87 @@ impl AsEvent for FixEventInternal {
                 msg.take().unwrap(),
                 InputResponder::new(sender.take().unwrap()),
             ),
+            FixEventInternal::PartialAppMsgIn(msg, field_errors, sender) => {
+                FixEvent::PartialAppMsgIn(
+                    msg.take().unwrap(),
+                    field_errors,
+                    InputResponder::new(sender.take().unwrap()),
+                )
+            }
+            FixEventInternal::TestAppMsgIn(msg, sender) => FixEvent::TestAppMsgIn(
+                msg.take().unwrap(),
+                InputResponder::new(sender.take().unwrap()),
+            ),
             FixEventInternal::AppMsgOut(msg, resp) => {
-                FixEvent::AppMsgOut(msg.as_mut().unwrap(), resp)
+                FixEvent::AppMsgOut(OutgoingMsg(msg.as_mut().unwrap()), resp)
             }
             FixEventInternal::AdmMsgOut(msg, _) => FixEvent::AdmMsgOut(msg.as_mut().unwrap()),
             FixEventInternal::DeserializeError(session_id, deserialize_error) => {
                 FixEvent::DeserializeError(session_id, deserialize_error)
             }
+            FixEventInternal::Resent(session_id, report) => FixEvent::Resent(session_id, *report),
+            FixEventInternal::Reject(session_id, report) => FixEvent::Reject(session_id, report),
+            FixEventInternal::SequenceResetConfirm {
+                session_id,
+                next_target_msg_seq_num,
+                new_seq_no,
+                sender,
+            } => FixEvent::SequenceResetConfirm {
+                session_id,
+                next_target_msg_seq_num: *next_target_msg_seq_num,
+                new_seq_no: *new_seq_no,
+                confirmation: SequenceResetConfirmation {
+                    sender: sender.take().unwrap(),
+                    phantom_ref: PhantomData,
+                },
+            },
+            FixEventInternal::LogoutGrace(session_id, outcome) => {
+                FixEvent::LogoutGrace(session_id, *outcome)
+            }
+            FixEventInternal::LogonMismatch(session_id, mismatch) => {
+                FixEvent::LogonMismatch(session_id, mismatch)
+            }
+            FixEventInternal::LogoutDetails(session_id, details) => {
+                FixEvent::LogoutDetails(session_id, details)
+            }
+            FixEventInternal::StoreFailed(session_id, failure) => {
+                FixEvent::StoreFailed(session_id, failure)
+            }
+            FixEventInternal::AppMsgExpired(session_id, expired) => {
+                FixEvent::AppMsgExpired(session_id, expired)
+            }
+            FixEventInternal::AppMsgDiscarded(session_id, discarded) => {
+                FixEvent::AppMsgDiscarded(session_id, discarded)
+            }
+            FixEventInternal::BeginStringMismatch(session_id, mismatch) => {
+                FixEvent::BeginStringMismatch(session_id, mismatch)
+            }
+            FixEventInternal::SerializeFailed(session_id, failure) => {
+                FixEvent::SerializeFailed(session_id, failure)
+            }
+            FixEventInternal::HeartbeatReply {
+                session_id,
+                test_request,
+                reply,
+                ..
+            } => FixEvent::HeartbeatReply {
+                session_id,
+                test_request,
+                reply: reply.as_mut().unwrap(),
+            },
+            FixEventInternal::ReadIdle(session_id, idle) => FixEvent::ReadIdle(session_id, *idle),
+            FixEventInternal::MsgFlagged(session_id, flagged) => {
+                FixEvent::MsgFlagged(session_id, flagged)
+            }
+            FixEventInternal::LatencyBudgetExceeded(session_id, alert) => {
+                FixEvent::LatencyBudgetExceeded(session_id, alert)
+            }
+            FixEventInternal::FramingError(session_id, error) => {
+                FixEvent::FramingError(session_id, error)
+            }
+            FixEventInternal::SessionStatus(session_id, report) => {
+                FixEvent::SessionStatus(session_id, report)
+            }
         }
     }
 }
@@ -0,0 +1,259 @@
+//! Minimal blocking FIX client, for scripts and conformance probes which
+//! don't need tokio runtime.
+//!
+//! [`Client`] connects over [`std::net::TcpStream`], logs on, sends and
+//! receives messages and logs out. Sequence numbers are tracked in memory
+//! only, gaps are not detected and messages are never resent, `TestRequest<1>`
+//! is the only message answered automatically.
+//!
+//! ```no_run
+//! # use easyfix_session::{blocking::Client, settings::{SessionSettings, Settings}};
+//! # fn example(settings: &Settings, session_settings: SessionSettings) -> Result<(), easyfix_session::blocking::ClientError> {
+//! let mut client = Client::connect("127.0.0.1:10050", settings, session_settings)?;
+//! client.logon()?;
+//! // client.send(...)?;
+//! let msg = client.recv()?;
+//! client.logout(None)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    io::{self, Read, Write},
+    net::{Shutdown, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use bytes::BytesMut;
+use easyfix_messages::{
+    deserializer::{DuplicateTagPolicy, GroupLimits, RequiredTags},
+    fields::{DefaultApplVerId, EncryptMethod, FixString, Int, MsgType, SeqNum, UtcTimestamp},
+    messages::{FixtMessage, Heartbeat, Logon, Logout, Message},
+    serializer::{self, Serializer},
+};
+
+use crate::{
+    application::DeserializeError,
+    credentials::CredentialsError,
+    io::iter_messages,
+    new_header, new_trailer,
+    settings::{SessionSettings, Settings},
+};
+
+const READ_BUFFER_SIZE: usize = 4096;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("failed to serialize message: {0}")]
+    Serialize(#[from] serializer::SerializeError),
+    #[error("failed to deserialize message: {0}")]
+    Deserialize(DeserializeError),
+    #[error("connection closed")]
+    Closed,
+    #[error("failed to resolve logon credentials: {0}")]
+    Credentials(#[from] CredentialsError),
+    /// `Logout<5>` or other unexpected message received instead
+    /// of `Logon<A>`.
+    #[error("logon rejected with {:?}", .0.msg_type())]
+    LogonRejected(Box<FixtMessage>),
+}
+
+/// Blocking FIX session client, see [module documentation](self).
+#[derive(Debug)]
+pub struct Client {
+    stream: TcpStream,
+    buffer: BytesMut,
+    session_settings: SessionSettings,
+    heartbeat_interval: Duration,
+    duplicate_tags: DuplicateTagPolicy,
+    other_msg_types: bool,
+    required_tags: RequiredTags,
+    group_limits: GroupLimits,
+    next_sender_msg_seq_num: SeqNum,
+    next_target_msg_seq_num: SeqNum,
+}
+
+impl Client {
+    /// Connect to `addr`, [`Client::recv`] fails with
+    /// [`io::ErrorKind::WouldBlock`] or [`io::ErrorKind::TimedOut`]
+    /// when nothing was received within `settings.heartbeat_interval`.
+    pub fn connect(
+        addr: impl ToSocketAddrs,
+        settings: &Settings,
+        session_settings: SessionSettings,
+    ) -> Result<Client, ClientError> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(settings.heartbeat_interval))?;
+        Ok(Client {
+            stream,
+            buffer: BytesMut::with_capacity(READ_BUFFER_SIZE),
+            session_settings,
+            heartbeat_interval: settings.heartbeat_interval,
+            duplicate_tags: settings.duplicate_tags.into(),
+            other_msg_types: settings.other_msg_types,
+            required_tags: settings.required_tags.clone().into(),
+            group_limits: settings.group_limits.into(),
+            next_sender_msg_seq_num: 1,
+            next_target_msg_seq_num: 1,
+        })
+    }
+
+    pub fn next_sender_msg_seq_num(&self) -> SeqNum {
+        self.next_sender_msg_seq_num
+    }
+
+    /// Continue sequence of previous session, `1` by default.
+    pub fn set_next_sender_msg_seq_num(&mut self, seq_num: SeqNum) {
+        self.next_sender_msg_seq_num = seq_num;
+    }
+
+    pub fn next_target_msg_seq_num(&self) -> SeqNum {
+        self.next_target_msg_seq_num
+    }
+
+    /// Continue sequence of previous session, `1` by default.
+    pub fn set_next_target_msg_seq_num(&mut self, seq_num: SeqNum) {
+        self.next_target_msg_seq_num = seq_num;
+    }
+
+    /// Send `msg`, header fields are filled the same way as by session,
+    /// returns MsgSeqNum<34> of sent message.
+    pub fn send(&mut self, msg: Box<Message>) -> Result<SeqNum, ClientError> {
+        self.send_raw(FixtMessage {
+            header: Box::new(new_header(msg.msg_type())),
+            body: msg,
+            trailer: Box::new(new_trailer()),
+        })
+    }
+
+    /// Send `msg`, header fields which are not set are filled the same way
+    /// as by session, returns MsgSeqNum<34> of sent message.
+    pub fn send_raw(&mut self, mut msg: FixtMessage) -> Result<SeqNum, ClientError> {
+        let header = &mut msg.header;
+        if header.begin_string.is_empty() {
+            header.begin_string = self.session_settings.begin_string();
+        }
+        header.msg_type = msg.body.msg_type();
+        if header.sender_comp_id.is_empty() {
+            header.sender_comp_id = self.session_settings.sender_comp_id();
+        }
+        if header.target_comp_id.is_empty() {
+            header.target_comp_id = self.session_settings.target_comp_id();
+        }
+        if header.sending_time == UtcTimestamp::MIN_UTC {
+            header.sending_time = self.session_settings.clock.now();
+        }
+        if header.msg_seq_num == 0 {
+            header.msg_seq_num = self.next_sender_msg_seq_num;
+            self.next_sender_msg_seq_num += 1;
+        }
+        let buffer = msg.serialize_with(
+            Serializer::new().with_empty_strings(self.session_settings.empty_strings.into()),
+        )?;
+        self.stream.write_all(&buffer)?;
+        Ok(msg.header.msg_seq_num)
+    }
+
+    /// Receive next message, `TestRequest<1>` is answered with
+    /// `Heartbeat<0>` before it's returned.
+    pub fn recv(&mut self) -> Result<Box<FixtMessage>, ClientError> {
+        loop {
+            let next = iter_messages(&mut self.buffer)
+                .with_duplicate_tags(self.duplicate_tags)
+                .with_other_msg_types(self.other_msg_types)
+                .with_required_tags(&self.required_tags)
+                .with_group_limits(self.group_limits)
+                .next();
+            match next {
+                Some(Ok(msg)) => {
+                    if msg.header.msg_seq_num >= self.next_target_msg_seq_num {
+                        self.next_target_msg_seq_num = msg.header.msg_seq_num + 1;
+                    }
+                    if let Message::TestRequest(test_request) = &*msg.body {
+                        self.send(Box::new(Message::Heartbeat(Heartbeat {
+                            test_req_id: Some(test_request.test_req_id.clone()),
+                        })))?;
+                    }
+                    return Ok(msg);
+                }
+                Some(Err(error)) => return Err(ClientError::Deserialize(error)),
+                None => {}
+            }
+            let mut chunk = [0; READ_BUFFER_SIZE];
+            match self.stream.read(&mut chunk)? {
+                0 => return Err(ClientError::Closed),
+                read => self.buffer.extend_from_slice(&chunk[..read]),
+            }
+        }
+    }
+
+    /// Receive messages until one of type `msg_type` arrives, other
+    /// messages are dropped.
+    pub fn recv_until(&mut self, msg_type: MsgType) -> Result<Box<FixtMessage>, ClientError> {
+        loop {
+            let msg = self.recv()?;
+            if msg.msg_type() == msg_type {
+                return Ok(msg);
+            }
+        }
+    }
+
+    /// Send `Logon<A>` and wait for response, `ResetSeqNumFlag<141>` is set
+    /// under the same conditions as by session.
+    ///
+    /// Credentials are resolved with
+    /// [`SessionSettings::credentials_provider`](crate::settings::SessionSettings::credentials_provider),
+    /// blocking until provider completes.
+    pub fn logon(&mut self) -> Result<Box<FixtMessage>, ClientError> {
+        let credentials = futures::executor::block_on(
+            self.session_settings
+                .credentials_provider
+                .logon_credentials(
+                    &self.session_settings.session_id,
+                    &self.session_settings.credentials,
+                ),
+        )?;
+        let reset = (self.session_settings.reset_on_logon
+            || self.session_settings.reset_on_logout
+            || self.session_settings.reset_on_disconnect)
+            && self.next_sender_msg_seq_num == 1
+            && self.next_target_msg_seq_num == 1;
+        let next_expected_msg_seq_num = self.next_target_msg_seq_num;
+        self.send(Box::new(Message::Logon(Logon {
+            encrypt_method: EncryptMethod::NoneOther,
+            heart_bt_int: self.heartbeat_interval.as_secs() as Int,
+            reset_seq_num_flag: reset.then_some(true),
+            next_expected_msg_seq_num: self
+                .session_settings
+                .enable_next_expected_msg_seq_num
+                .then_some(next_expected_msg_seq_num),
+            test_message_indicator: self.session_settings.test_session.then_some(true),
+            username: credentials.username,
+            password: credentials.password,
+            new_password: credentials.new_password,
+            default_appl_ver_id: DefaultApplVerId::Fix50Sp2,
+            ..Default::default()
+        })))?;
+        let msg = self.recv()?;
+        match msg.msg_type() {
+            MsgType::Logon => Ok(msg),
+            _ => Err(ClientError::LogonRejected(msg)),
+        }
+    }
+
+    /// Send `Logout<5>`, wait for `Logout<5>` response and close connection.
+    /// Messages received in the meantime are dropped.
+    pub fn logout(mut self, text: Option<FixString>) -> Result<Box<FixtMessage>, ClientError> {
+        self.send(Box::new(Message::Logout(Logout {
+            text,
+            ..Default::default()
+        })))?;
+        let msg = self.recv_until(MsgType::Logout)?;
+        // Peer may already have closed the connection
+        let _ = self.stream.shutdown(Shutdown::Both);
+        Ok(msg)
+    }
+}
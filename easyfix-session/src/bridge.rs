@@ -0,0 +1,148 @@
+//! Adapter exposing a single session as a `Stream` of incoming application
+//! messages and a `Sink` of outgoing ones.
+//!
+//! Useful when messages have to be wired into generic stream processing
+//! pipelines instead of being handled through [`FixEvent`] directly.
+
+use std::{
+    cell::RefCell,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use easyfix_messages::messages::{FixtMessage, Message, MsgCat};
+use futures::{Sink, Stream};
+use pin_project::pin_project;
+
+use crate::{
+    application::{AsEvent, FixEvent},
+    session_id::SessionId,
+    AppSender, SendError,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BridgeError {
+    #[error("Session not logged on")]
+    NotLoggedOn(Box<Message>),
+    #[error("Admin messages can't be sent through bridge")]
+    AdminMessage(Box<Message>),
+    #[error(transparent)]
+    Send(#[from] SendError),
+}
+
+/// Create a stream/sink pair bound to session `session_id`.
+///
+/// `events` is the event stream of an `Acceptor` or `Initiator`. Events
+/// which do not belong to `session_id` and all non application events are
+/// consumed and discarded, so the bridge should own the only consumer of
+/// the event stream.
+///
+/// The sink becomes usable once the session is logged on, until then (and
+/// after logout) sending fails with [`BridgeError::NotLoggedOn`].
+pub fn bridge<E>(events: E, session_id: SessionId) -> (MessagesStream<E>, MessagesSink)
+where
+    E: Stream,
+    E::Item: AsEvent,
+{
+    let sender = Rc::new(RefCell::new(None));
+    (
+        MessagesStream {
+            events,
+            session_id,
+            sender: sender.clone(),
+        },
+        MessagesSink { sender },
+    )
+}
+
+/// Stream of application messages received by the session.
+#[pin_project]
+pub struct MessagesStream<E> {
+    #[pin]
+    events: E,
+    session_id: SessionId,
+    sender: Rc<RefCell<Option<AppSender>>>,
+}
+
+impl<E> MessagesStream<E> {
+    pub fn session_id(&self) -> &SessionId {
+        &self.session_id
+    }
+}
+
+impl<E> Stream for MessagesStream<E>
+where
+    E: Stream,
+    E::Item: AsEvent,
+{
+    type Item = Box<FixtMessage>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            let Some(mut entry) = futures::ready!(this.events.as_mut().poll_next(cx)) else {
+                this.sender.borrow_mut().take();
+                return Poll::Ready(None);
+            };
+            match entry.as_event() {
+                FixEvent::Logon(session_id, sender) if session_id == this.session_id => {
+                    *this.sender.borrow_mut() = Some(sender.into());
+                }
+                FixEvent::Logout(session_id, _) if session_id == this.session_id => {
+                    this.sender.borrow_mut().take();
+                }
+                // Dropping `InputResponder` accepts the message
+                FixEvent::AppMsgIn(msg, _responder)
+                    if SessionId::from_input_msg(&msg) == *this.session_id =>
+                {
+                    return Poll::Ready(Some(msg));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Sink of application messages to be sent by the session.
+///
+/// Messages are queued in the unbounded session output queue, so the sink
+/// is always ready.
+#[derive(Clone, Debug)]
+pub struct MessagesSink {
+    sender: Rc<RefCell<Option<AppSender>>>,
+}
+
+impl MessagesSink {
+    /// Returns `true` when the session is logged on and messages can be sent.
+    pub fn is_logged_on(&self) -> bool {
+        self.sender.borrow().is_some()
+    }
+}
+
+impl Sink<Box<Message>> for MessagesSink {
+    type Error = BridgeError;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, msg: Box<Message>) -> Result<(), Self::Error> {
+        if msg.msg_cat() == MsgCat::Admin {
+            return Err(BridgeError::AdminMessage(msg));
+        }
+        let sender = self.sender.borrow();
+        let Some(sender) = sender.as_ref() else {
+            return Err(BridgeError::NotLoggedOn(msg));
+        };
+        Ok(sender.send(msg)?)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
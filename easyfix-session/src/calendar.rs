@@ -0,0 +1,114 @@
+//! Business day calendars deciding on which days session is active.
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt,
+    ops::Deref,
+    sync::Arc,
+};
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Deserializer};
+
+/// Source of business days, e.g. market-specific holiday calendar.
+///
+/// Logon<A> is accepted only on business days (and within
+/// `SessionSettings::logon_time`). Dates are in UTC, as session times are.
+pub trait Calendar: fmt::Debug + Send + Sync {
+    /// Returns `true` when session is active on given date.
+    fn is_business_day(&self, date: NaiveDate) -> bool;
+}
+
+/// Calendar of active weekdays and list of holidays.
+///
+/// Default calendar has no holidays and every day of week is active.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct WeekdayCalendar {
+    /// Days of week session is active on.
+    pub weekdays: HashSet<Weekday>,
+    /// Dates session is not active on, even if it's an active weekday.
+    pub holidays: BTreeSet<NaiveDate>,
+}
+
+impl WeekdayCalendar {
+    /// Calendar active from Monday to Friday, without holidays.
+    pub fn business_week() -> WeekdayCalendar {
+        WeekdayCalendar {
+            weekdays: [
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ]
+            .into(),
+            holidays: BTreeSet::new(),
+        }
+    }
+
+    pub fn with_holidays(mut self, holidays: impl IntoIterator<Item = NaiveDate>) -> Self {
+        self.holidays.extend(holidays);
+        self
+    }
+}
+
+impl Default for WeekdayCalendar {
+    fn default() -> WeekdayCalendar {
+        WeekdayCalendar {
+            weekdays: [
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+                Weekday::Sat,
+                Weekday::Sun,
+            ]
+            .into(),
+            holidays: BTreeSet::new(),
+        }
+    }
+}
+
+impl Calendar for WeekdayCalendar {
+    fn is_business_day(&self, date: NaiveDate) -> bool {
+        self.weekdays.contains(&date.weekday()) && !self.holidays.contains(&date)
+    }
+}
+
+/// Calendar used by session, shared by all clones of its settings.
+///
+/// Deserialized as [`WeekdayCalendar`], custom calendars are set with
+/// [`SessionCalendar::new`].
+#[derive(Clone, Debug)]
+pub struct SessionCalendar(Arc<dyn Calendar>);
+
+impl SessionCalendar {
+    pub fn new(calendar: impl Calendar + 'static) -> SessionCalendar {
+        SessionCalendar(Arc::new(calendar))
+    }
+}
+
+impl Default for SessionCalendar {
+    fn default() -> SessionCalendar {
+        SessionCalendar::new(WeekdayCalendar::default())
+    }
+}
+
+impl Deref for SessionCalendar {
+    type Target = dyn Calendar;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for SessionCalendar {
+    fn deserialize<D>(deserializer: D) -> Result<SessionCalendar, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        WeekdayCalendar::deserialize(deserializer).map(SessionCalendar::new)
+    }
+}
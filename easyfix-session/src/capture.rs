@@ -0,0 +1,311 @@
+//! Capture of raw bytes read from and written to session connection, for
+//! incident investigation.
+//!
+//! Capture is started and stopped at runtime, without restart, with
+//! [`AdminHandle::start_capture`](crate::acceptor::AdminHandle::start_capture)
+//! or [`Initiator::start_capture`](crate::initiator::Initiator::start_capture).
+//! Bytes are captured exactly as they were read or written, including
+//! garbled input, in chunks of single read or write call. Once started,
+//! capture continues across reconnections until stopped.
+//!
+//! Files are written only to
+//! [`Settings::capture_dir`](crate::settings::Settings::capture_dir),
+//! capture file paths must be relative and can't leave that directory.
+
+use std::{
+    cell::{OnceCell, RefCell},
+    collections::VecDeque,
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    mem,
+    path::{Component, Path, PathBuf},
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use chrono::Utc;
+use easyfix_messages::fields::{TimePrecision, UtcTimestamp};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tracing::warn;
+
+use crate::inspector::MsgDirection;
+
+/// Bytes read before session of accepted connection is known (i.e. before
+/// Logon<A> is parsed) kept at most, so they can be captured as well.
+const PENDING_MAX_BYTES: usize = 64 * 1024;
+
+/// Where captured bytes are kept, see [`CaptureSettings`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CaptureSink {
+    /// Ring buffer of the most recent chunks, up to `max_bytes` in total,
+    /// oldest chunks are dropped to make room for new ones.
+    Memory,
+    /// Chunks appended to file, each as `<timestamp> <IN|OUT> <length>`
+    /// line followed by captured bytes and new line. Capture stops writing
+    /// when `max_bytes` of captured bytes is reached.
+    ///
+    /// Path is relative to
+    /// [`Settings::capture_dir`](crate::settings::Settings::capture_dir).
+    File(PathBuf),
+}
+
+/// Capture started at runtime, see [module documentation](self).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CaptureSettings {
+    pub sink: CaptureSink,
+    /// Limit of captured bytes, see [`CaptureSink`].
+    pub max_bytes: usize,
+}
+
+/// Bytes of single read from or write to connection.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CapturedChunk {
+    pub timestamp: UtcTimestamp,
+    pub direction: MsgDirection,
+    pub bytes: Bytes,
+}
+
+impl CapturedChunk {
+    fn new(direction: MsgDirection, bytes: &[u8]) -> CapturedChunk {
+        CapturedChunk {
+            timestamp: UtcTimestamp::with_precision(Utc::now(), TimePrecision::Nanos),
+            direction,
+            bytes: Bytes::copy_from_slice(bytes),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Target {
+    Memory {
+        chunks: VecDeque<CapturedChunk>,
+        size: usize,
+    },
+    File {
+        path: PathBuf,
+        file: File,
+        size: usize,
+    },
+}
+
+/// Path of capture file `path` in `capture_dir`, absolute paths and
+/// paths with `..` are refused.
+fn capture_file_path(capture_dir: Option<&Path>, path: &Path) -> io::Result<PathBuf> {
+    let Some(capture_dir) = capture_dir else {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "file capture disabled, capture directory not set",
+        ));
+    };
+    let relative = path.components().next().is_some()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !relative {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "capture file {} outside of capture directory",
+                path.display()
+            ),
+        ));
+    }
+    Ok(capture_dir.join(path))
+}
+
+/// Running capture of single session.
+#[derive(Debug)]
+pub(crate) struct TrafficCapture {
+    target: Target,
+    max_bytes: usize,
+}
+
+impl TrafficCapture {
+    pub(crate) fn start(
+        settings: CaptureSettings,
+        capture_dir: Option<&Path>,
+    ) -> io::Result<TrafficCapture> {
+        let target = match settings.sink {
+            CaptureSink::Memory => Target::Memory {
+                chunks: VecDeque::new(),
+                size: 0,
+            },
+            CaptureSink::File(path) => {
+                let path = capture_file_path(capture_dir, &path)?;
+                let file = OpenOptions::new().create(true).append(true).open(&path)?;
+                Target::File {
+                    path,
+                    file,
+                    size: 0,
+                }
+            }
+        };
+        Ok(TrafficCapture {
+            target,
+            max_bytes: settings.max_bytes,
+        })
+    }
+
+    fn record(&mut self, chunk: CapturedChunk) {
+        match &mut self.target {
+            Target::Memory { chunks, size } => {
+                *size += chunk.bytes.len();
+                chunks.push_back(chunk);
+                while *size > self.max_bytes {
+                    let Some(oldest) = chunks.pop_front() else {
+                        break;
+                    };
+                    *size -= oldest.bytes.len();
+                }
+            }
+            Target::File { path, file, size } => {
+                if *size >= self.max_bytes {
+                    return;
+                }
+                let direction = match chunk.direction {
+                    MsgDirection::Inbound => "IN",
+                    MsgDirection::Outbound => "OUT",
+                };
+                let mut entry =
+                    format!("{} {direction} {}\n", chunk.timestamp, chunk.bytes.len()).into_bytes();
+                entry.extend_from_slice(&chunk.bytes);
+                entry.push(b'\n');
+                if let Err(err) = file.write_all(&entry) {
+                    warn!("failed to write to {}: {err}", path.display());
+                }
+                *size += chunk.bytes.len();
+                if *size >= self.max_bytes {
+                    warn!(
+                        "capture limit reached, {} not written anymore",
+                        path.display()
+                    );
+                }
+            }
+        }
+    }
+
+    /// Chunks captured in memory, oldest first, empty for file sink.
+    pub(crate) fn chunks(&self) -> Vec<CapturedChunk> {
+        match &self.target {
+            Target::Memory { chunks, .. } => chunks.iter().cloned().collect(),
+            Target::File { .. } => Vec::new(),
+        }
+    }
+}
+
+/// Capture of session, `None` when not capturing. Kept in session state,
+/// so it's shared by all connections of the session.
+pub(crate) type CaptureSlot = Rc<RefCell<Option<TrafficCapture>>>;
+
+/// Connection side of capture, shared by reader and writer of single
+/// connection.
+///
+/// Session of accepted connection is known only after Logon<A> is read,
+/// bytes read until then are kept and passed to the session capture
+/// on [`CaptureTap::attach`].
+#[derive(Debug, Default)]
+pub(crate) struct CaptureTap {
+    slot: OnceCell<CaptureSlot>,
+    pending: RefCell<Vec<CapturedChunk>>,
+}
+
+impl CaptureTap {
+    pub(crate) fn new() -> Rc<CaptureTap> {
+        Rc::new(CaptureTap::default())
+    }
+
+    /// Start capturing to `slot` of connected session.
+    pub(crate) fn attach(&self, slot: CaptureSlot) {
+        let pending = mem::take(&mut *self.pending.borrow_mut());
+        if let Some(capture) = slot.borrow_mut().as_mut() {
+            for chunk in pending {
+                capture.record(chunk);
+            }
+        }
+        let _ = self.slot.set(slot);
+    }
+
+    fn record(&self, direction: MsgDirection, bytes: &[u8]) {
+        if bytes.is_empty() {
+            return;
+        }
+        match self.slot.get() {
+            Some(slot) => {
+                if let Some(capture) = slot.borrow_mut().as_mut() {
+                    capture.record(CapturedChunk::new(direction, bytes));
+                }
+            }
+            None => {
+                let mut pending = self.pending.borrow_mut();
+                let size: usize = pending.iter().map(|chunk| chunk.bytes.len()).sum();
+                if size + bytes.len() <= PENDING_MAX_BYTES {
+                    pending.push(CapturedChunk::new(direction, bytes));
+                }
+            }
+        }
+    }
+}
+
+/// Reader passing read bytes to [`CaptureTap`].
+pub(crate) struct CaptureReader<R> {
+    inner: R,
+    tap: Rc<CaptureTap>,
+}
+
+impl<R> CaptureReader<R> {
+    pub(crate) fn new(inner: R, tap: Rc<CaptureTap>) -> CaptureReader<R> {
+        CaptureReader { inner, tap }
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for CaptureReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled = buf.filled().len();
+        let result = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = result {
+            self.tap
+                .record(MsgDirection::Inbound, &buf.filled()[filled..]);
+        }
+        result
+    }
+}
+
+/// Writer passing written bytes to [`CaptureTap`].
+pub(crate) struct CaptureWriter<W> {
+    inner: W,
+    tap: Rc<CaptureTap>,
+}
+
+impl<W> CaptureWriter<W> {
+    pub(crate) fn new(inner: W, tap: Rc<CaptureTap>) -> CaptureWriter<W> {
+        CaptureWriter { inner, tap }
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for CaptureWriter<W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let result = Pin::new(&mut self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = result {
+            self.tap.record(MsgDirection::Outbound, &buf[..written]);
+        }
+        result
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
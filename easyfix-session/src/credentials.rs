@@ -0,0 +1,253 @@
+//! Credentials resolved when they are needed instead of being embedded
+//! in settings created at startup, e.g. read from environment variables,
+//! files mounted by secret manager or fetched from Vault-style store.
+//!
+//! Credentials of initiator are resolved with
+//! [`SessionSettings::credentials_provider`](crate::settings::SessionSettings::credentials_provider)
+//! before every Logon<A> is sent, so reconnection picks up rotated
+//! credentials. When no provider is set, static
+//! [`SessionSettings::credentials`](crate::settings::SessionSettings::credentials)
+//! are sent. Connection is closed with
+//! [`DisconnectReason::CredentialsUnavailable`](crate::DisconnectReason::CredentialsUnavailable)
+//! when provider fails.
+//!
+//! TLS key material is not used by session itself, it's resolved with
+//! [`Initiator::tls_key_material`](crate::initiator::Initiator::tls_key_material)
+//! when TLS connection passed to
+//! [`Initiator::run_session_task`](crate::initiator::Initiator::run_session_task)
+//! is established.
+
+use std::{env, fmt, fs, future::Future, io, path::PathBuf, sync::Arc};
+
+use bytes::Bytes;
+use easyfix_messages::fields::{FixString, FixStringError};
+use futures::future::LocalBoxFuture;
+use serde::Deserialize;
+
+use crate::{session_id::SessionId, settings::LogonCredentials};
+
+/// Failure to resolve credentials.
+#[derive(Debug, thiserror::Error)]
+pub enum CredentialsError {
+    #[error("environment variable {name}: {source}")]
+    Env { name: String, source: env::VarError },
+    #[error("failed to read {}: {source}", .path.display())]
+    File { path: PathBuf, source: io::Error },
+    /// Username or password is not a valid FIX string.
+    #[error("invalid credentials: {0}")]
+    Invalid(#[from] FixStringError),
+    /// Failure reported by custom provider.
+    #[error("credentials provider failed: {0}")]
+    Provider(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Source of credentials resolved at logon, see
+/// [module documentation](self).
+pub trait CredentialsProvider: Send + Sync {
+    /// Username<553>, Password<554> and NewPassword<925> sent in Logon<A>,
+    /// resolved before every Logon<A> is sent.
+    fn logon_credentials<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> LocalBoxFuture<'a, Result<LogonCredentials, CredentialsError>>;
+
+    /// Key material of TLS client identity (e.g. PEM encoded certificate
+    /// and private key), `None` by default.
+    fn tls_key_material<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+    ) -> LocalBoxFuture<'a, Result<Option<Bytes>, CredentialsError>> {
+        Box::pin(async { Ok(None) })
+    }
+}
+
+/// Single secret, read when resolved.
+#[derive(Clone, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretSource {
+    /// Value given directly, e.g. username which is not secret.
+    Value(String),
+    /// Value of environment variable.
+    Env(String),
+    /// Content of file, with trailing line break removed, e.g. secret
+    /// mounted by Kubernetes or Docker.
+    File(PathBuf),
+}
+
+impl SecretSource {
+    pub fn resolve(&self) -> Result<Bytes, CredentialsError> {
+        match self {
+            SecretSource::Value(value) => Ok(Bytes::copy_from_slice(value.as_bytes())),
+            SecretSource::Env(name) => {
+                env::var(name)
+                    .map(Bytes::from)
+                    .map_err(|source| CredentialsError::Env {
+                        name: name.clone(),
+                        source,
+                    })
+            }
+            SecretSource::File(path) => {
+                let mut content = fs::read(path).map_err(|source| CredentialsError::File {
+                    path: path.clone(),
+                    source,
+                })?;
+                if content.ends_with(b"\n") {
+                    content.pop();
+                    if content.ends_with(b"\r") {
+                        content.pop();
+                    }
+                }
+                Ok(Bytes::from(content))
+            }
+        }
+    }
+
+    fn resolve_fix_string(&self) -> Result<FixString, CredentialsError> {
+        Ok(FixString::from_ascii(self.resolve()?.to_vec())?)
+    }
+}
+
+impl fmt::Debug for SecretSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // Value may be secret, so it's not logged
+            SecretSource::Value(_) => f.write_str("Value(***)"),
+            SecretSource::Env(name) => f.debug_tuple("Env").field(name).finish(),
+            SecretSource::File(path) => f.debug_tuple("File").field(path).finish(),
+        }
+    }
+}
+
+/// Credentials read from [`SecretSource`]s, each one resolved again
+/// at every logon.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct SecretCredentials {
+    /// Username<553>
+    #[serde(default)]
+    pub username: Option<SecretSource>,
+    /// Password<554>
+    #[serde(default)]
+    pub password: Option<SecretSource>,
+    /// NewPassword<925>
+    #[serde(default)]
+    pub new_password: Option<SecretSource>,
+    /// See [`CredentialsProvider::tls_key_material`]
+    #[serde(default)]
+    pub tls_key_material: Option<SecretSource>,
+}
+
+impl CredentialsProvider for SecretCredentials {
+    fn logon_credentials<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+    ) -> LocalBoxFuture<'a, Result<LogonCredentials, CredentialsError>> {
+        let resolve = |source: &Option<SecretSource>| {
+            source
+                .as_ref()
+                .map(SecretSource::resolve_fix_string)
+                .transpose()
+        };
+        let credentials = (|| {
+            Ok(LogonCredentials {
+                username: resolve(&self.username)?,
+                password: resolve(&self.password)?,
+                new_password: resolve(&self.new_password)?,
+            })
+        })();
+        Box::pin(async { credentials })
+    }
+
+    fn tls_key_material<'a>(
+        &'a self,
+        _session_id: &'a SessionId,
+    ) -> LocalBoxFuture<'a, Result<Option<Bytes>, CredentialsError>> {
+        let key_material = self
+            .tls_key_material
+            .as_ref()
+            .map(SecretSource::resolve)
+            .transpose();
+        Box::pin(async { key_material })
+    }
+}
+
+/// Credentials resolved with callback, e.g. fetched from Vault-style
+/// secret store.
+///
+/// ```ignore
+/// let provider = CredentialsFn(|session_id: SessionId| async move {
+///     let secret = vault.read(&format!("fix/{}", session_id.target_comp_id())).await?;
+///     Ok(LogonCredentials {
+///         username: Some(secret.username.try_into()?),
+///         password: Some(secret.password.try_into()?),
+///         new_password: None,
+///     })
+/// });
+/// ```
+pub struct CredentialsFn<F>(pub F);
+
+impl<F, Fut> CredentialsProvider for CredentialsFn<F>
+where
+    F: Fn(SessionId) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<LogonCredentials, CredentialsError>> + 'static,
+{
+    fn logon_credentials<'a>(
+        &'a self,
+        session_id: &'a SessionId,
+    ) -> LocalBoxFuture<'a, Result<LogonCredentials, CredentialsError>> {
+        Box::pin((self.0)(session_id.clone()))
+    }
+}
+
+impl<F> fmt::Debug for CredentialsFn<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CredentialsFn")
+    }
+}
+
+/// Credentials provider used by session, shared by all clones of its
+/// settings.
+///
+/// Not deserialized, custom provider is set with
+/// [`SessionCredentialsProvider::new`]. By default static
+/// [`SessionSettings::credentials`](crate::settings::SessionSettings::credentials)
+/// are used.
+#[derive(Clone, Default)]
+pub struct SessionCredentialsProvider(Option<Arc<dyn CredentialsProvider>>);
+
+impl SessionCredentialsProvider {
+    pub fn new(provider: impl CredentialsProvider + 'static) -> SessionCredentialsProvider {
+        SessionCredentialsProvider(Some(Arc::new(provider)))
+    }
+
+    /// Credentials resolved with provider, `credentials` when provider
+    /// is not set.
+    pub(crate) async fn logon_credentials(
+        &self,
+        session_id: &SessionId,
+        credentials: &LogonCredentials,
+    ) -> Result<LogonCredentials, CredentialsError> {
+        match &self.0 {
+            Some(provider) => provider.logon_credentials(session_id).await,
+            None => Ok(credentials.clone()),
+        }
+    }
+
+    pub(crate) async fn tls_key_material(
+        &self,
+        session_id: &SessionId,
+    ) -> Result<Option<Bytes>, CredentialsError> {
+        match &self.0 {
+            Some(provider) => provider.tls_key_material(session_id).await,
+            None => Ok(None),
+        }
+    }
+}
+
+impl fmt::Debug for SessionCredentialsProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(_) => f.write_str("SessionCredentialsProvider(custom)"),
+            None => f.write_str("SessionCredentialsProvider(static)"),
+        }
+    }
+}
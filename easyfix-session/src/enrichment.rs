@@ -0,0 +1,146 @@
+use std::collections::HashSet;
+
+use easyfix_messages::{
+    deserializer::{raw_message, Deserializer, RawMessage},
+    fields::{FixString, TagNum},
+    messages::FixtMessage,
+};
+use tracing::error;
+
+use crate::settings::EnrichmentRule;
+
+type Fields = Vec<(TagNum, Vec<u8>)>;
+
+fn parse_fields(fields: &str) -> Result<Fields, String> {
+    fields
+        .split('|')
+        .filter(|field| !field.is_empty())
+        .map(|field| {
+            let (tag, value) = field
+                .split_once('=')
+                .ok_or_else(|| format!("missing `=` in `{field}`"))?;
+            let tag = tag
+                .parse::<TagNum>()
+                .ok()
+                .filter(|tag| *tag != 0)
+                .ok_or_else(|| format!("invalid tag in `{field}`"))?;
+            Ok((tag, value.as_bytes().to_vec()))
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+struct Rule {
+    msg_type: FixString,
+    fields: Fields,
+    /// First field of each group is its NumInGroup field
+    groups: Vec<Fields>,
+}
+
+impl Rule {
+    fn new(rule: &EnrichmentRule) -> Result<Rule, String> {
+        let groups = rule
+            .groups
+            .iter()
+            .map(|group| parse_fields(group))
+            .collect::<Result<Vec<_>, _>>()?;
+        if groups.iter().any(Vec::is_empty) {
+            return Err("empty group".to_owned());
+        }
+        Ok(Rule {
+            msg_type: rule.msg_type.clone(),
+            fields: parse_fields(&rule.fields)?,
+            groups,
+        })
+    }
+}
+
+/// Constant fields added to outgoing messages, see
+/// [`EnrichmentRule`].
+#[derive(Debug, Default)]
+pub(crate) struct Enrichment {
+    rules: Vec<Rule>,
+}
+
+impl Enrichment {
+    pub(crate) fn new(rules: &[EnrichmentRule]) -> Enrichment {
+        Enrichment {
+            rules: rules
+                .iter()
+                .filter_map(|rule| {
+                    Rule::new(rule)
+                        .map_err(|err| {
+                            error!(
+                                "invalid enrichment rule for {} message: {err}",
+                                rule.msg_type
+                            )
+                        })
+                        .ok()
+                })
+                .collect(),
+        }
+    }
+
+    /// Add missing fields to `msg` body.
+    ///
+    /// Message is serialized, fields are appended to its body, which is
+    /// then deserialized back, so header and trailer are not changed.
+    pub(crate) fn apply(&self, msg: &mut FixtMessage) {
+        let msg_type = msg.msg_type();
+        let Some(rule) = self
+            .rules
+            .iter()
+            .find(|rule| rule.msg_type == msg_type.as_fix_str())
+        else {
+            return;
+        };
+
+        let serialized = msg.serialize();
+        let Ok((_, raw_msg)) = raw_message(&serialized) else {
+            error!("failed to enrich {msg_type:?} message, invalid serialized message");
+            return;
+        };
+        let present: HashSet<TagNum> = raw_msg
+            .tag_values()
+            .filter_map(Result::ok)
+            .map(|(tag, _)| tag)
+            .collect();
+
+        let mut body = raw_msg.body.to_vec();
+        let mut append = |fields: &[(TagNum, Vec<u8>)]| {
+            for (tag, value) in fields {
+                body.extend_from_slice(tag.to_string().as_bytes());
+                body.push(b'=');
+                body.extend_from_slice(value);
+                body.push(b'\x01');
+            }
+        };
+        let body_len = raw_msg.body.len();
+        for field in rule.fields.iter().filter(|(tag, _)| !present.contains(tag)) {
+            append(std::slice::from_ref(field));
+        }
+        for group in rule
+            .groups
+            .iter()
+            .filter(|group| !present.contains(&group[0].0))
+        {
+            append(group);
+        }
+        if body.len() == body_len {
+            return;
+        }
+
+        let begin_strings = [msg.header.begin_string.clone()];
+        let enriched = RawMessage {
+            begin_string: raw_msg.begin_string,
+            body: &body,
+            checksum: raw_msg.checksum,
+        };
+        match FixtMessage::deserialize(
+            Deserializer::from_raw_message(enriched).with_begin_strings(&begin_strings),
+        ) {
+            Ok(enriched) => msg.body = enriched.body,
+            Err(err) => error!("failed to enrich {msg_type:?} message: {err:?}"),
+        }
+    }
+}
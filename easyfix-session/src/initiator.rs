@@ -1,18 +1,36 @@
-use std::{cell::RefCell, collections::HashMap, net::SocketAddr, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    future::Future,
+    io,
+    net::SocketAddr,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
 
+use bytes::Bytes;
+use easyfix_messages::fields::{FixString, SessionStatus};
+use futures::Stream;
 use pin_project::pin_project;
-use tokio::net::TcpStream;
-use tracing::{info, info_span, Instrument};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpStream,
+    task::JoinHandle,
+};
+use tracing::{info, info_span, warn, Instrument};
 
 use crate::{
-    application::{events_channel, Emitter, EventStream},
-    io::initiator_connection,
+    application::{events_channel, AsEvent, Emitter, EventBroadcast, EventStream, SessionSummary},
+    capture::{CaptureSettings, CapturedChunk, TrafficCapture},
+    credentials::CredentialsError,
+    io::{initiator_connection, set_tcp_keepalive},
     messages_storage::MessagesStorage,
     session::Session,
     session_id::SessionId,
     session_state::State,
     settings::{SessionSettings, Settings},
-    Error,
+    Error, PauseMode, SessionError,
 };
 
 // TODO: Same as in Acceptor, not need for duplicate
@@ -36,47 +54,182 @@ impl<S: MessagesStorage + 'static> Initiator<S> {
         session_settings: SessionSettings,
         messages_storage: S,
     ) -> Initiator<S> {
-        let (emitter, event_stream) = events_channel();
+        let (emitter, event_stream) = events_channel(&settings.buffers);
+        let mut state = State::new(messages_storage);
+        state.set_initiate(true);
         Initiator {
             id: session_settings.session_id.clone(),
             settings,
             session_settings,
-            state: Rc::new(RefCell::new(State::new(messages_storage))),
+            state: Rc::new(RefCell::new(state)),
             active_sessions: Rc::new(RefCell::new(HashMap::new())),
             emitter,
             event_stream,
         }
     }
 
-    pub async fn connect(&self, socket_addr: impl Into<SocketAddr>) -> Result<(), Error> {
+    /// Connect to the acceptor and start the session, returned handle
+    /// resolves to the session summary when connection is closed.
+    pub async fn connect(
+        &self,
+        socket_addr: impl Into<SocketAddr>,
+    ) -> Result<JoinHandle<SessionSummary>, Error> {
         info!("Initiator started");
 
+        if self.state.borrow().stopped() {
+            warn!("session {} is stopped, not connecting", self.id);
+            return Err(Error::SessionError(SessionError::Stopped));
+        }
+
         let addr = socket_addr.into();
         let tcp_stream = TcpStream::connect(addr).await?;
         tcp_stream.set_nodelay(true)?;
-        let emitter = self.emitter.clone();
-        let settings = self.settings.clone();
-        let session_settings = self.session_settings.clone();
-        let active_sessions = self.active_sessions.clone();
-        let state = self.state.clone();
+        if let Some(keepalive) = &self.settings.tcp_keepalive {
+            set_tcp_keepalive(&tcp_stream, keepalive)?;
+        }
+        let (reader, writer) = tcp_stream.into_split();
+        let session_task = self.run_session_task(reader, writer);
 
         let connection_span = info_span!("connection", %addr);
 
-        tokio::task::spawn_local(async move {
-            initiator_connection(
-                tcp_stream,
-                settings,
-                session_settings,
-                state,
-                active_sessions,
-                emitter,
-            )
-            .instrument(connection_span.clone())
-            .await;
+        let handle = tokio::task::spawn_local(async move {
+            let summary = session_task.instrument(connection_span.clone()).await;
             connection_span.in_scope(|| {
                 info!("Connection closed");
             });
+            summary
         });
+        Ok(handle)
+    }
+
+    /// Start the session over already established connection, e.g.
+    /// in-memory stream, returned future resolves to the session summary
+    /// when connection is closed.
+    pub fn run_session_task(
+        &self,
+        reader: impl AsyncRead + Unpin + 'static,
+        writer: impl AsyncWrite + Unpin + 'static,
+    ) -> impl Future<Output = SessionSummary> {
+        initiator_connection(
+            reader,
+            writer,
+            self.settings.clone(),
+            self.session_settings.clone(),
+            self.state.clone(),
+            self.active_sessions.clone(),
+            self.emitter.clone(),
+        )
+    }
+
+    /// TLS key material resolved with
+    /// [`SessionSettings::credentials_provider`], to establish TLS
+    /// connection passed to [`Initiator::run_session_task`]. Resolve it
+    /// again before every reconnection to pick up rotated key material.
+    pub async fn tls_key_material(&self) -> Result<Option<Bytes>, CredentialsError> {
+        self.session_settings
+            .credentials_provider
+            .tls_key_material(&self.id)
+            .await
+    }
+
+    /// Events of this session, available to any number of subscribers.
+    pub fn events(&self) -> &EventBroadcast {
+        self.emitter.events()
+    }
+
+    /// Send Logout<5>, connection is closed when Logout<5> response
+    /// is received.
+    pub fn logout(&self, session_status: Option<SessionStatus>, reason: Option<FixString>) {
+        let active_sessions = self.active_sessions.borrow();
+        let Some(session) = active_sessions.get(&self.id) else {
+            warn!("logout: session {} not connected", self.id);
+            return;
+        };
+
+        session.send_logout(&mut session.state().borrow_mut(), session_status, reason);
+    }
+
+    /// Pause sending of application messages, see [`Acceptor::pause`]
+    ///
+    /// [`Acceptor::pause`]: crate::acceptor::Acceptor::pause
+    pub fn pause(&self, mode: PauseMode) {
+        let active_sessions = self.active_sessions.borrow();
+        let Some(session) = active_sessions.get(&self.id) else {
+            warn!("pause: session {} not connected", self.id);
+            return;
+        };
+
+        session.pause(&mut session.state().borrow_mut(), mode);
+    }
+
+    /// Allow stopped session to connect again.
+    pub fn start(&self) {
+        info!("start session {}", self.id);
+        self.state.borrow_mut().set_stopped(false);
+    }
+
+    /// Log out the session, when connected, and refuse to connect until
+    /// [`Initiator::start`] is called, see
+    /// [`AdminHandle::stop`](crate::acceptor::AdminHandle::stop).
+    pub fn stop(&self, reason: Option<FixString>) {
+        info!("stop session {}", self.id);
+        self.state.borrow_mut().set_stopped(true);
+        if let Some(session) = self.active_sessions.borrow().get(&self.id) {
+            session.send_logout(&mut session.state().borrow_mut(), None, reason);
+        }
+    }
+
+    /// Session is stopped, see [`Initiator::stop`].
+    pub fn is_stopped(&self) -> bool {
+        self.state.borrow().stopped()
+    }
+
+    /// Start capturing raw traffic, see
+    /// [`AdminHandle::start_capture`](crate::acceptor::AdminHandle::start_capture).
+    pub fn start_capture(&self, settings: CaptureSettings) -> io::Result<()> {
+        info!("start capture of session {}: {settings:?}", self.id);
+        let capture = TrafficCapture::start(settings, self.settings.capture_dir.as_deref())?;
+        self.state.borrow().capture().replace(Some(capture));
         Ok(())
     }
+
+    /// Stop capturing raw traffic, returns chunks captured in memory.
+    pub fn stop_capture(&self) -> Vec<CapturedChunk> {
+        info!("stop capture of session {}", self.id);
+        let capture = self.state.borrow().capture().take();
+        capture.map(|capture| capture.chunks()).unwrap_or_default()
+    }
+
+    /// Chunks captured in memory so far, capture keeps running.
+    pub fn captured(&self) -> Vec<CapturedChunk> {
+        let state = self.state.borrow();
+        let capture = state.capture().borrow();
+        capture
+            .as_ref()
+            .map(TrafficCapture::chunks)
+            .unwrap_or_default()
+    }
+
+    /// Resume paused session, buffered messages are sent immediately
+    pub fn resume(&self) {
+        let active_sessions = self.active_sessions.borrow();
+        let Some(session) = active_sessions.get(&self.id) else {
+            warn!("resume: session {} not connected", self.id);
+            return;
+        };
+
+        session.resume(&mut session.state().borrow_mut());
+    }
+}
+
+impl<S: MessagesStorage> Stream for Initiator<S> {
+    type Item = impl AsEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().event_stream.poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.event_stream.size_hint()
+    }
 }
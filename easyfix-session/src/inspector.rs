@@ -0,0 +1,119 @@
+//! Inspection of application messages, e.g. compliance scanning for
+//! restricted symbols or fat-finger limits.
+//!
+//! Unlike [`FixEvent::AppMsgOut`](crate::application::FixEvent::AppMsgOut)
+//! handler, inspector can't change messages, it only flags them, which is
+//! reported with
+//! [`FixEvent::MsgFlagged`](crate::application::FixEvent::MsgFlagged).
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use easyfix_messages::messages::FixtMessage;
+
+/// Direction of inspected message.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MsgDirection {
+    /// Message received from the counterparty
+    Inbound,
+    /// Message sent to the counterparty
+    Outbound,
+}
+
+/// Reason of flagging inspected message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Finding {
+    /// Name of violated rule, e.g. `restricted-symbol`
+    pub rule: String,
+    pub text: String,
+}
+
+/// Synchronous inspection of application messages.
+///
+/// Invoked for every received application message after it's decoded and
+/// before it's delivered to the application, and for every sent one just
+/// before it's serialized (after
+/// [`FixEvent::AppMsgOut`](crate::application::FixEvent::AppMsgOut) is
+/// handled). Inspection runs on the session task, so it should be cheap;
+/// repeated decisions can be cached, see [`Inspector::cache_key`].
+pub trait Inspector: fmt::Debug + Send + Sync {
+    /// Key of cached decision, messages with equal key and direction get
+    /// the findings of the first one without calling [`Inspector::inspect`],
+    /// e.g. hash of symbol, side and quantity bucket. Messages are not
+    /// cached by default.
+    fn cache_key(&self, _direction: MsgDirection, _msg: &FixtMessage) -> Option<u64> {
+        None
+    }
+
+    /// Findings of message inspection, empty when message is not flagged.
+    fn inspect(&self, direction: MsgDirection, msg: &FixtMessage) -> Vec<Finding>;
+}
+
+/// Findings by direction and [`Inspector::cache_key`].
+type DecisionCache = HashMap<(MsgDirection, u64), Arc<[Finding]>>;
+
+#[derive(Debug)]
+struct InspectorState {
+    inspector: Box<dyn Inspector>,
+    cache: Mutex<DecisionCache>,
+    cache_capacity: usize,
+}
+
+/// Custom inspector used by session, shared by all clones of its settings
+/// together with its decision cache.
+///
+/// Not deserialized, custom inspector is set with [`SessionInspector::new`].
+/// By default messages are not inspected.
+#[derive(Clone, Debug, Default)]
+pub struct SessionInspector(Option<Arc<InspectorState>>);
+
+impl SessionInspector {
+    /// Default maximum number of cached decisions.
+    pub const CACHE_CAPACITY: usize = 4096;
+
+    pub fn new(inspector: impl Inspector + 'static) -> SessionInspector {
+        SessionInspector::with_cache_capacity(inspector, SessionInspector::CACHE_CAPACITY)
+    }
+
+    /// Inspector keeping up to `cache_capacity` decisions, the cache is
+    /// cleared when it's full.
+    pub fn with_cache_capacity(
+        inspector: impl Inspector + 'static,
+        cache_capacity: usize,
+    ) -> SessionInspector {
+        SessionInspector(Some(Arc::new(InspectorState {
+            inspector: Box::new(inspector),
+            cache: Mutex::new(HashMap::new()),
+            cache_capacity,
+        })))
+    }
+
+    /// Findings of application message, `None` when it's not flagged.
+    pub(crate) fn inspect(
+        &self,
+        direction: MsgDirection,
+        msg: &FixtMessage,
+    ) -> Option<Arc<[Finding]>> {
+        let state = self.0.as_ref()?;
+        let findings = match state.inspector.cache_key(direction, msg) {
+            Some(key) if state.cache_capacity > 0 => {
+                let mut cache = state.cache.lock().unwrap_or_else(|err| err.into_inner());
+                if let Some(findings) = cache.get(&(direction, key)) {
+                    findings.clone()
+                } else {
+                    let findings: Arc<[Finding]> = state.inspector.inspect(direction, msg).into();
+                    if cache.len() >= state.cache_capacity {
+                        cache.clear();
+                    }
+                    cache.insert((direction, key), findings.clone());
+                    findings
+                }
+            }
+            _ => state.inspector.inspect(direction, msg).into(),
+        };
+        (!findings.is_empty()).then_some(findings)
+    }
+}
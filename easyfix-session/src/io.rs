@@ -1,38 +1,50 @@
 use std::{
     cell::RefCell,
     collections::{hash_map::Entry, HashMap},
+    io,
+    net::SocketAddr,
     rc::Rc,
     sync::Mutex,
 };
 
-use easyfix_messages::messages::{FixtMessage, Message};
+use bytes::Bytes;
+use easyfix_messages::{
+    fields::{FixString, MsgType, SessionRejectReason},
+    messages::{FixtMessage, Message, Reject},
+};
 use futures_util::{pin_mut, Stream};
 use tokio::{
     self,
     io::{AsyncRead, AsyncWrite, AsyncWriteExt},
     net::TcpStream,
     sync::mpsc,
-    time::Duration,
+    time::Instant,
 };
 use tokio_stream::StreamExt;
-use tracing::{debug, error, info, info_span, Instrument};
+use tracing::{debug, error, info, info_span, warn, Instrument};
 
 use crate::{
-    acceptor::{ActiveSessionsMap, SessionsMap},
-    application::{Emitter, FixEventInternal},
+    acceptor::{
+        metrics::{ConnectionEvent, ConnectionMonitor, LogonFailure},
+        ActiveSessionsMap, SessionsMap,
+    },
+    application::{Emitter, EventTimestamp, FixEventInternal, LatencyStage, SessionSummary},
+    capture::{CaptureReader, CaptureTap, CaptureWriter},
     messages_storage::MessagesStorage,
+    new_header, new_trailer,
     session::Session,
     session_id::SessionId,
     session_state::State,
-    settings::{SessionSettings, Settings},
-    DisconnectReason, Error, Sender, SessionError, NO_INBOUND_TIMEOUT_PADDING,
+    settings::{PreLogonAction, ReadIdleAction, SessionSettings, Settings, TcpKeepalive},
+    AppSender, DisconnectReason, QueuedMsg, SendError, SendErrorReason, Sender,
+    NO_INBOUND_TIMEOUT_PADDING,
 };
 
 mod input_stream;
-pub use input_stream::{input_stream, InputEvent, InputStream};
+pub use input_stream::{input_stream, iter_messages, InputEvent, InputStream, MessagesIter};
 
 mod output_stream;
-use output_stream::{output_stream, OutputEvent};
+use output_stream::{output_stream, process_msg, OutputEvent};
 
 pub mod time;
 use time::{timeout, timeout_stream};
@@ -71,75 +83,281 @@ pub fn sender(session_id: &SessionId) -> Option<Sender> {
         .cloned()
 }
 
+/// Application messages sender of session `session_id`, see [`AppSender`].
+pub fn app_sender(session_id: &SessionId) -> Option<AppSender> {
+    sender(session_id).map(AppSender::from)
+}
+
 // TODO: Remove?
 pub fn send(session_id: &SessionId, msg: Box<Message>) -> Result<(), Box<Message>> {
     if let Some(sender) = sender(session_id) {
-        sender.send(msg).map_err(|msg| msg.body)
+        sender.send(msg).map_err(|err| err.msg.body)
     } else {
         Err(msg)
     }
 }
 
-pub fn send_raw(msg: Box<FixtMessage>) -> Result<(), Box<FixtMessage>> {
+pub fn send_raw(msg: Box<FixtMessage>) -> Result<(), SendError> {
     if let Some(sender) = sender(&SessionId::from_input_msg(&msg)) {
         sender.send_raw(msg)
     } else {
-        Err(msg)
+        Err(SendError {
+            reason: SendErrorReason::Disconnected,
+            msg,
+        })
     }
 }
 
+/// Enable TCP keepalive probes on connection socket.
+pub(crate) fn set_tcp_keepalive(
+    tcp_stream: &TcpStream,
+    keepalive: &TcpKeepalive,
+) -> io::Result<()> {
+    let params = socket2::TcpKeepalive::new().with_time(keepalive.time);
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        windows,
+    ))]
+    let params = match keepalive.interval {
+        Some(interval) => params.with_interval(interval),
+        None => params,
+    };
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "netbsd",
+    ))]
+    let params = match keepalive.retries {
+        Some(retries) => params.with_retries(retries),
+        None => params,
+    };
+    socket2::SockRef::from(tcp_stream).set_tcp_keepalive(&params)
+}
+
 async fn first_msg(
     stream: &mut (impl Stream<Item = InputEvent> + Unpin),
-    logon_timeout: Duration,
-) -> Result<Box<FixtMessage>, Error> {
+    logon_deadline: Instant,
+) -> Result<(Box<FixtMessage>, Bytes), LogonFailure> {
+    let logon_timeout = logon_deadline.saturating_duration_since(Instant::now());
     match timeout(logon_timeout, stream.next()).await {
-        Ok(Some(InputEvent::Message(msg))) => Ok(msg),
-        Ok(Some(InputEvent::IoError(error))) => Err(error.into()),
+        Ok(Some(InputEvent::Message { msg, raw_message })) => Ok((msg, raw_message)),
+        Ok(Some(InputEvent::IoError(error))) => Err(LogonFailure::Io(error.kind())),
+        Ok(Some(InputEvent::BufferLimitExceeded(limit))) => {
+            Err(LogonFailure::BufferLimitExceeded(limit))
+        }
+        Ok(None) => Err(LogonFailure::Closed),
         Ok(Some(InputEvent::DeserializeError(error))) => {
             error!("failed to deserialize first message: {error}");
-            Err(Error::SessionError(SessionError::LogonNeverReceived))
+            Err(LogonFailure::InvalidMessage)
+        }
+        Ok(Some(InputEvent::FramingError {
+            error,
+            framing_error,
+        })) => {
+            error!(
+                "failed to deserialize first message: {error}, {:?}\n{}",
+                framing_error.mismatch, framing_error.hexdump
+            );
+            Err(LogonFailure::InvalidMessage)
         }
-        _ => Err(Error::SessionError(SessionError::LogonNeverReceived)),
+        _ => Err(LogonFailure::Timeout),
     }
 }
 
+/// Reject<3> of message received before Logon<A>, sent on behalf of
+/// registered session the message claims to belong to, the same way as
+/// messages of established session (stored, captured, emitted and
+/// logged). Returns `false` when nothing was sent, e.g. when message
+/// doesn't belong to registered session or the session is connected, as
+/// its MsgSeqNum<34> can't be taken by unauthenticated connection.
+async fn send_pre_logon_reject<S: MessagesStorage>(
+    msg: &FixtMessage,
+    settings: &Settings,
+    sessions: &RefCell<SessionsMap<S>>,
+    emitter: &Emitter,
+    capture_tap: &CaptureTap,
+    writer: &mut (impl AsyncWrite + Unpin),
+) -> bool {
+    let Some((session_id, (session_settings, state))) = sessions
+        .borrow()
+        .get_session(&SessionId::from_input_msg(msg))
+    else {
+        return false;
+    };
+    if !state.borrow().disconnected() {
+        warn!("session {session_id} is connected, pre-logon message not rejected");
+        return false;
+    }
+    capture_tap.attach(state.borrow().capture().clone());
+    // Session is not established, nothing is sent through its sender
+    let (sender, _receiver) = mpsc::unbounded_channel();
+    let session = Session::new(
+        settings.clone(),
+        session_settings,
+        state,
+        Sender::new(sender),
+        emitter.clone(),
+    );
+    let reject = Box::new(FixtMessage {
+        header: Box::new(new_header(MsgType::Reject)),
+        body: Box::new(Message::Reject(Reject {
+            ref_seq_num: msg.header.msg_seq_num,
+            ref_msg_type: Some(msg.header.msg_type.as_fix_str().to_owned()),
+            session_reject_reason: Some(SessionRejectReason::Other),
+            text: Some(FixString::from_ascii_lossy(b"Logon<A> expected".to_vec())),
+            ..Default::default()
+        })),
+        trailer: Box::new(new_trailer()),
+    });
+    let Some(output) = process_msg(QueuedMsg::new(reject, None), &session).await else {
+        return false;
+    };
+    if let Err(err) = writer.write_all(&output.buffer).await {
+        error!("failed to send Reject<3>: {err}");
+    }
+    true
+}
+
 #[derive(Debug)]
 struct Connection<S> {
     session: Rc<Session<S>>,
 }
 
+#[expect(clippy::too_many_arguments)]
 pub(crate) async fn acceptor_connection<S>(
+    peer_addr: SocketAddr,
     reader: impl AsyncRead + Unpin,
     writer: impl AsyncWrite + Unpin,
     settings: Settings,
     sessions: Rc<RefCell<SessionsMap<S>>>,
     active_sessions: Rc<RefCell<ActiveSessionsMap<S>>>,
     emitter: Emitter,
-) where
+    monitor: ConnectionMonitor,
+) -> Option<SessionSummary>
+where
     S: MessagesStorage,
 {
-    let stream = input_stream(reader);
-    let logon_timeout =
-        settings.auto_disconnect_after_no_logon_received + NO_INBOUND_TIMEOUT_PADDING;
+    let begin_strings = sessions.borrow().begin_strings();
+    // Attached to session capture once Logon<A> is read
+    let capture_tap = CaptureTap::new();
+    let reader = CaptureReader::new(reader, capture_tap.clone());
+    let writer = CaptureWriter::new(writer, capture_tap.clone());
+    let stream = input_stream(reader, settings.buffer_pool.clone())
+        .with_duplicate_tags(settings.duplicate_tags.into())
+        .with_begin_strings(begin_strings)
+        .with_other_msg_types(settings.other_msg_types)
+        .with_required_tags(settings.required_tags.clone().into())
+        .with_group_limits(settings.group_limits.into())
+        .with_framing_errors(settings.framing_errors)
+        .with_max_buffered(settings.max_bytes_before_logon)
+        .with_read_buffer(&settings.buffers);
+    let stream = if settings.session_log.is_some() {
+        stream.with_pending_session_log()
+    } else {
+        stream
+    };
+    let logon_deadline = Instant::now()
+        + settings.auto_disconnect_after_no_logon_received
+        + NO_INBOUND_TIMEOUT_PADDING;
     pin_mut!(stream);
-    let msg = match first_msg(&mut stream, logon_timeout).await {
-        Ok(msg) => msg,
-        Err(err) => {
-            error!("failed to establish new session: {err}");
-            return;
+    let mut writer = writer;
+    let (msg, raw_message) = loop {
+        let (msg, raw_message) = match first_msg(&mut stream, logon_deadline).await {
+            Ok(first_msg) => first_msg,
+            Err(LogonFailure::InvalidMessage)
+                if settings.pre_logon.garbled != PreLogonAction::Disconnect =>
+            {
+                // Garbled message can't be referenced by Reject<3>
+                monitor.emit(ConnectionEvent::PreLogonMessage {
+                    peer_addr,
+                    msg_type: None,
+                    action: PreLogonAction::Ignore,
+                });
+                continue;
+            }
+            Err(reason) => {
+                if reason == LogonFailure::InvalidMessage {
+                    monitor.emit(ConnectionEvent::PreLogonMessage {
+                        peer_addr,
+                        msg_type: None,
+                        action: PreLogonAction::Disconnect,
+                    });
+                }
+                error!("failed to establish new session: {reason}");
+                monitor.emit(ConnectionEvent::LogonFailed { peer_addr, reason });
+                return None;
+            }
+        };
+        let msg_type = msg.header.msg_type;
+        if msg_type == MsgType::Logon {
+            break (msg, raw_message);
+        }
+        let action = match settings.pre_logon.not_logon {
+            PreLogonAction::Reject => {
+                let sent = send_pre_logon_reject(
+                    &msg,
+                    &settings,
+                    &sessions,
+                    &emitter,
+                    &capture_tap,
+                    &mut writer,
+                )
+                .await;
+                if sent {
+                    PreLogonAction::Reject
+                } else {
+                    PreLogonAction::Ignore
+                }
+            }
+            action => action,
+        };
+        warn!("received {msg_type:?} before logon: {action:?}");
+        monitor.emit(ConnectionEvent::PreLogonMessage {
+            peer_addr,
+            msg_type: Some(msg_type),
+            action,
+        });
+        if action == PreLogonAction::Disconnect {
+            // Refused by the session, as any message received
+            // in invalid logon state
+            break (msg, raw_message);
         }
     };
+    let read_at = EventTimestamp::now();
     let session_id = SessionId::from_input_msg(&msg);
     debug!("first_msg: {msg:?}");
+    stream.as_mut().set_max_buffered(None);
+    stream.as_mut().set_any_begin_string(true);
+    stream
+        .as_mut()
+        .set_read_idle_timeout(settings.read_idle.map(|read_idle| read_idle.timeout));
 
     let (sender, receiver) = mpsc::unbounded_channel();
     let sender = Sender::new(sender);
 
-    let Some((session_settings, session_state)) = sessions.borrow().get_session(&session_id) else {
+    let Some((session_id, (session_settings, session_state))) =
+        sessions.borrow().get_session(&session_id)
+    else {
         error!("failed to establish new session: unknown session id {session_id}");
-        return;
+        monitor.emit(ConnectionEvent::LogonFailed {
+            peer_addr,
+            reason: LogonFailure::UnknownSession(session_id),
+        });
+        return None;
     };
     session_state.borrow_mut().set_disconnected(false);
+    capture_tap.attach(session_state.borrow().capture().clone());
+    stream
+        .as_mut()
+        .set_resize_counter(session_state.borrow().read_buffer_resizes());
     register_sender(session_id.clone(), sender.clone());
     let session = Rc::new(Session::new(
         settings,
@@ -151,6 +369,10 @@ pub(crate) async fn acceptor_connection<S>(
     active_sessions
         .borrow_mut()
         .insert(session_id.clone(), session.clone());
+    stream
+        .as_mut()
+        .set_session_log(session.session_log().cloned());
+    stream.as_mut().set_decode_timer(session.decode_timer());
 
     let session_span = info_span!(
         "session",
@@ -160,13 +382,26 @@ pub(crate) async fn acceptor_connection<S>(
     let input_loop_span = info_span!(parent: &session_span, "in");
     let output_loop_span = info_span!(parent: &session_span, "out");
 
+    session.state().borrow_mut().incr_messages_received();
+    session.set_input_timestamp(read_at);
     let force_disconnection_with_reason = session
-        .on_message_in(msg)
+        .on_message_in(msg, raw_message)
         .instrument(input_loop_span.clone())
         .await;
+    match force_disconnection_with_reason {
+        Some(reason) => monitor.emit(ConnectionEvent::LogonFailed {
+            peer_addr,
+            reason: LogonFailure::Rejected(session_id.clone(), reason),
+        }),
+        None => monitor.emit(ConnectionEvent::LogonSucceeded {
+            peer_addr,
+            session_id: session_id.clone(),
+        }),
+    }
 
     // TODO: Not here!, send this event when SessionState is created!
-    emitter
+    session
+        .emitter()
         .send(FixEventInternal::Created(session_id.clone()))
         .await;
 
@@ -181,7 +416,7 @@ pub(crate) async fn acceptor_connection<S>(
     let connection = Connection::new(session);
     let (input_closed_tx, input_closed_rx) = tokio::sync::oneshot::channel();
 
-    tokio::join!(
+    let (_, disconnect_reason) = tokio::join!(
         connection
             .input_loop(
                 input_stream,
@@ -196,23 +431,48 @@ pub(crate) async fn acceptor_connection<S>(
     session_span.in_scope(|| {
         info!("connection closed");
     });
+    monitor.emit(ConnectionEvent::Disconnected {
+        peer_addr,
+        session_id: session_id.clone(),
+        reason: disconnect_reason,
+    });
     unregister_sender(&session_id);
-    active_sessions.borrow_mut().remove(&session_id);
+    let session = active_sessions.borrow_mut().remove(&session_id);
+    session.map(|session| SessionSummary {
+        session_id,
+        disconnect_reason,
+        state: session.state().borrow().snapshot(),
+    })
 }
 
 pub(crate) async fn initiator_connection<S>(
-    tcp_stream: TcpStream,
+    reader: impl AsyncRead + Unpin,
+    writer: impl AsyncWrite + Unpin,
     settings: Settings,
     session_settings: SessionSettings,
     state: Rc<RefCell<State<S>>>,
     active_sessions: Rc<RefCell<ActiveSessionsMap<S>>>,
     emitter: Emitter,
-) where
+) -> SessionSummary
+where
     S: MessagesStorage,
 {
-    let (source, sink) = tcp_stream.into_split();
     state.borrow_mut().set_disconnected(false);
     let session_id = session_settings.session_id.clone();
+    let buffer_pool = settings.buffer_pool.clone();
+    let duplicate_tags = settings.duplicate_tags.into();
+    let other_msg_types = settings.other_msg_types;
+    let required_tags = settings.required_tags.clone().into();
+    let group_limits = settings.group_limits.into();
+    let framing_errors = settings.framing_errors;
+    let begin_strings = vec![session_settings.begin_string()];
+    let read_idle_timeout = settings.read_idle.map(|read_idle| read_idle.timeout);
+    let buffers = settings.buffers;
+    let read_buffer_resizes = state.borrow().read_buffer_resizes();
+    let capture_tap = CaptureTap::new();
+    capture_tap.attach(state.borrow().capture().clone());
+    let reader = CaptureReader::new(reader, capture_tap.clone());
+    let writer = CaptureWriter::new(writer, capture_tap);
 
     let (sender, receiver) = mpsc::unbounded_channel();
     let sender = Sender::new(sender);
@@ -238,13 +498,29 @@ pub(crate) async fn initiator_connection<S>(
     let output_loop_span = info_span!(parent: &session_span, "out");
 
     // TODO: Not here!, send this event when SessionState is created!
-    emitter
+    session
+        .emitter()
         .send(FixEventInternal::Created(session_id.clone()))
         .await;
 
     let input_timeout_duration = session.heartbeat_interval() + NO_INBOUND_TIMEOUT_PADDING;
-    let input_stream = timeout_stream(input_timeout_duration, input_stream(source))
-        .map(|res| res.unwrap_or(InputEvent::Timeout));
+    let input_stream = timeout_stream(
+        input_timeout_duration,
+        input_stream(reader, buffer_pool)
+            .with_duplicate_tags(duplicate_tags)
+            .with_begin_strings(begin_strings)
+            .with_any_begin_string(true)
+            .with_other_msg_types(other_msg_types)
+            .with_required_tags(required_tags)
+            .with_group_limits(group_limits)
+            .with_framing_errors(framing_errors)
+            .with_read_idle_timeout(read_idle_timeout)
+            .with_read_buffer(&buffers)
+            .with_resize_counter(read_buffer_resizes)
+            .with_decode_timer(session.decode_timer())
+            .with_session_log(session.session_log().cloned()),
+    )
+    .map(|res| res.unwrap_or(InputEvent::Timeout));
     pin_mut!(input_stream);
 
     let output_stream = output_stream(session.clone(), session.heartbeat_interval(), receiver);
@@ -252,22 +528,43 @@ pub(crate) async fn initiator_connection<S>(
 
     // TODO: It's not so simple, add check if session time is within range,
     //       if not schedule timer to send logon at proper time
-    session.send_logon_request(&mut session.state().borrow_mut());
+    // Credentials are resolved for every connection, so rotated ones are
+    // picked up on reconnection
+    let force_disconnection_with_reason = match session.logon_credentials().await {
+        Ok(credentials) => {
+            session.send_logon_request(&mut session.state().borrow_mut(), credentials);
+            None
+        }
+        Err(err) => {
+            error!("failed to resolve logon credentials: {err}");
+            Some(DisconnectReason::CredentialsUnavailable)
+        }
+    };
 
     let connection = Connection::new(session);
     let (input_closed_tx, input_closed_rx) = tokio::sync::oneshot::channel();
 
-    tokio::join!(
+    let (_, disconnect_reason) = tokio::join!(
         connection
-            .input_loop(input_stream, input_closed_tx, None)
+            .input_loop(
+                input_stream,
+                input_closed_tx,
+                force_disconnection_with_reason
+            )
             .instrument(input_loop_span),
         connection
-            .output_loop(sink, output_stream, input_closed_rx)
+            .output_loop(writer, output_stream, input_closed_rx)
             .instrument(output_loop_span),
     );
     info!("connection closed");
     unregister_sender(&session_id);
     active_sessions.borrow_mut().remove(&session_id);
+    let state = connection.session.state().borrow().snapshot();
+    SessionSummary {
+        session_id,
+        state,
+        disconnect_reason,
+    }
 }
 
 impl<S: MessagesStorage> Connection<S> {
@@ -297,7 +594,24 @@ impl<S: MessagesStorage> Connection<S> {
 
         let mut disconnect_reason = DisconnectReason::Disconnected;
 
-        while let Some(event) = input_stream.next().await {
+        loop {
+            let pending_logout = self.session.state().borrow().pending_logout();
+            let event = match pending_logout {
+                Some(deadline) => {
+                    match tokio::time::timeout_at(deadline, input_stream.next()).await {
+                        Ok(event) => event,
+                        Err(_elapsed) => {
+                            disconnect_reason = self.session.on_logout_grace_expired().await;
+                            break;
+                        }
+                    }
+                }
+                None => input_stream.next().await,
+            };
+            let Some(event) = event else {
+                break;
+            };
+            self.session.set_input_timestamp(EventTimestamp::now());
             // Don't accept new messages if session is disconnected.
             if self.session.state().borrow().disconnected() {
                 info!("session disconnected, exit input processing");
@@ -309,9 +623,34 @@ impl<S: MessagesStorage> Connection<S> {
                     .expect("Failed to notify about closed inpout");
                 return;
             }
+            if matches!(
+                event,
+                InputEvent::Message { .. }
+                    | InputEvent::PartialMessage { .. }
+                    | InputEvent::DeserializeError(_)
+                    | InputEvent::FramingError { .. }
+            ) {
+                self.session.state().borrow_mut().incr_messages_received();
+            }
             match event {
-                InputEvent::Message(msg) => {
-                    if let Some(dr) = self.session.on_message_in(msg).await {
+                InputEvent::Message { msg, raw_message } => {
+                    if let Some(dr) = self.session.on_message_in(msg, raw_message).await {
+                        info!("disconnect ({dr:?}), exit input processing");
+                        disconnect_reason = dr;
+                        break;
+                    }
+                    self.session.state().borrow_mut().set_input_timoeut_cnt(0);
+                }
+                InputEvent::PartialMessage {
+                    msg,
+                    field_errors,
+                    raw_message,
+                } => {
+                    if let Some(dr) = self
+                        .session
+                        .on_partial_message_in(msg, field_errors, raw_message)
+                        .await
+                    {
                         info!("disconnect ({dr:?}), exit input processing");
                         disconnect_reason = dr;
                         break;
@@ -325,16 +664,38 @@ impl<S: MessagesStorage> Connection<S> {
                         break;
                     }
                 }
+                InputEvent::FramingError {
+                    error,
+                    framing_error,
+                } => {
+                    self.session.on_framing_error(framing_error).await;
+                    if let Some(dr) = self.session.on_deserialize_error(error).await {
+                        info!("disconnect ({dr:?}), exit input processing");
+                        disconnect_reason = dr;
+                        break;
+                    }
+                }
                 InputEvent::IoError(error) => {
                     error!("Input error: {error:?}");
                     disconnect_reason = DisconnectReason::IoError;
                     break;
                 }
+                InputEvent::BufferLimitExceeded(limit) => {
+                    error!("Input buffer limit ({limit} bytes) exceeded");
+                    disconnect_reason = DisconnectReason::IoError;
+                    break;
+                }
                 InputEvent::Timeout => {
                     if self.session.on_in_timeout().await {
                         break;
                     }
                 }
+                InputEvent::ReadIdle(idle) => {
+                    if self.session.on_read_idle(idle).await == ReadIdleAction::Disconnect {
+                        disconnect_reason = DisconnectReason::ReadIdle;
+                        break;
+                    }
+                }
             }
         }
         self.session
@@ -353,19 +714,22 @@ impl<S: MessagesStorage> Connection<S> {
         mut sink: impl AsyncWrite + Unpin,
         mut output_stream: impl Stream<Item = OutputEvent> + Unpin,
         input_closed_rx: tokio::sync::oneshot::Receiver<()>,
-    ) {
+    ) -> DisconnectReason {
         let mut sink_closed = false;
         let mut disconnect_reason = DisconnectReason::Disconnected;
         while let Some(event) = output_stream.next().await {
             match event {
-                OutputEvent::Message(msg) => {
+                OutputEvent::Message(output) => {
                     if sink_closed {
                         // Sink is closed - ignore message, but do not break
                         // the loop. Output stream has to process all enqueued
                         // messages to made them available
                         // for ResendRequest<2>.
                         info!("Client disconnected, message will be stored for further resend");
-                    } else if let Err(error) = sink.write_all(&msg).await {
+                        continue;
+                    }
+                    let started = Instant::now();
+                    if let Err(error) = sink.write_all(&output.buffer).await {
                         sink_closed = true;
                         error!("Output write error: {error:?}");
                         // XXX: Don't disconnect now. If IO error happened
@@ -379,7 +743,16 @@ impl<S: MessagesStorage> Connection<S> {
                         //     &mut self.session.state().borrow_mut(),
                         //     DisconnectReason::IoError,
                         // );
+                        continue;
                     }
+                    self.session
+                        .check_latency_budget(
+                            LatencyStage::SerializeWrite,
+                            output.serialize_time + started.elapsed(),
+                            output.msg_type,
+                            output.msg_seq_num,
+                        )
+                        .await;
                 }
                 OutputEvent::Timeout => self.session.on_out_timeout().await,
                 OutputEvent::Disconnect(reason) => {
@@ -406,5 +779,6 @@ impl<S: MessagesStorage> Connection<S> {
         // queue.
         let _ = input_closed_rx.await;
         info!("disconnect, exit output processing");
+        disconnect_reason
     }
 }
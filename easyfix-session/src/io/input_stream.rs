@@ -1,35 +1,176 @@
 use std::{
-    io,
+    cell::Cell,
+    fmt::Write,
+    future::Future,
+    io, mem,
     pin::Pin,
+    rc::Rc,
     task::{ready, Context, Poll},
 };
 
-use bytes::BytesMut;
+use bytes::{Buf, Bytes, BytesMut};
 use easyfix_messages::{
-    deserializer::{self, raw_message, RawMessageError},
+    buffer_pool::BufferPool,
+    deserializer::{
+        self, framing_mismatch, raw_message, split_raw_message, Deserializer, DuplicateTagPolicy,
+        FramingMismatch, GroupLimits, RawMessageBytes, RawMessageError, RequiredTags,
+    },
+    fields::FixString,
     messages::FixtMessage,
 };
 use futures_util::Stream;
-use pin_project::pin_project;
-use tokio::io::AsyncRead;
+use pin_project::{pin_project, pinned_drop};
+use tokio::{
+    io::AsyncRead,
+    time::{self, Duration, Instant, Sleep},
+};
 use tokio_util::io::poll_read_buf;
 use tracing::{debug, info, warn};
 
-use crate::application::DeserializeError;
+use crate::{
+    application::{DeserializeError, FieldError, FramingError},
+    session_log::SessionLog,
+    settings::{BufferGrowth, BufferSettings, FramingErrorReports},
+};
 
 #[derive(Debug)]
 pub enum InputEvent {
-    Message(Box<FixtMessage>),
+    Message {
+        msg: Box<FixtMessage>,
+        /// Message as received, attached to session-level rejects.
+        raw_message: Bytes,
+    },
+    /// Application message with invalid values of optional fields skipped.
+    PartialMessage {
+        msg: Box<FixtMessage>,
+        field_errors: Vec<FieldError>,
+        raw_message: Bytes,
+    },
     DeserializeError(DeserializeError),
+    /// Garbled message with CheckSum<10> or BodyLength<9> mismatch, see
+    /// [`InputStream::with_framing_errors`].
+    FramingError {
+        error: DeserializeError,
+        framing_error: Box<FramingError>,
+    },
     IoError(io::Error),
+    /// More than given number of bytes buffered without complete message,
+    /// see [`InputStream::set_max_buffered`].
+    BufferLimitExceeded(usize),
     Timeout,
+    /// No data read from the source for given time, see
+    /// [`InputStream::with_read_idle_timeout`].
+    ReadIdle(Duration),
+}
+
+/// Watchdog of reads, reset by any data read, including incomplete
+/// messages. Fires once per idle period, it's re-armed by the next read.
+#[derive(Debug)]
+struct ReadIdle {
+    timeout: Duration,
+    sleep: Pin<Box<Sleep>>,
+    armed: bool,
+}
+
+impl ReadIdle {
+    fn new(timeout: Duration) -> ReadIdle {
+        ReadIdle {
+            timeout,
+            sleep: Box::pin(time::sleep(timeout)),
+            armed: true,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.sleep.as_mut().reset(Instant::now() + self.timeout);
+        self.armed = true;
+    }
+
+    fn poll_elapsed(&mut self, cx: &mut Context<'_>) -> Poll<Duration> {
+        if !self.armed {
+            return Poll::Pending;
+        }
+        ready!(self.sleep.as_mut().poll(cx));
+        self.armed = false;
+        Poll::Ready(self.timeout)
+    }
+}
+
+/// Logging of raw inbound messages.
+#[derive(Debug, Default)]
+enum RawLog {
+    #[default]
+    Off,
+    /// Session is not known yet, the first message is kept until
+    /// session log is set.
+    Pending(Option<Bytes>),
+    On(Rc<SessionLog>),
+}
+
+impl RawLog {
+    fn message(&mut self, raw_msg: &RawMessageBytes) {
+        match self {
+            RawLog::Off => {}
+            RawLog::Pending(pending) => {
+                pending.get_or_insert_with(|| raw_msg.clone().into_bytes());
+            }
+            RawLog::On(session_log) => session_log.message_in(raw_msg.as_bytes()),
+        }
+    }
+}
+
+/// Bytes dumped before and after given offset, see [`hexdump`].
+const HEXDUMP_CONTEXT: usize = 32;
+
+/// Dump of bytes around `offset`, 16 bytes per line, in `hexdump -C`
+/// format.
+fn hexdump(bytes: &[u8], offset: usize) -> String {
+    let start = offset.saturating_sub(HEXDUMP_CONTEXT) / 16 * 16;
+    let end = bytes.len().min(offset + HEXDUMP_CONTEXT);
+    let mut output = String::new();
+    for (i, line) in bytes[start..end].chunks(16).enumerate() {
+        if i > 0 {
+            output.push('\n');
+        }
+        let _ = write!(output, "{:08x} ", start + i * 16);
+        for (j, byte) in line.iter().enumerate() {
+            let separator = if j == 8 { "  " } else { " " };
+            let _ = write!(output, "{separator}{byte:02x}");
+        }
+        for j in line.len()..16 {
+            output.push_str(if j == 8 { "    " } else { "   " });
+        }
+        output.push_str("  |");
+        output.extend(line.iter().map(|byte| match byte {
+            0x20..=0x7e => char::from(*byte),
+            _ => '.',
+        }));
+        output.push('|');
+    }
+    output
+}
+
+/// Details of garbled message at the beginning of `bytes`, when reporting
+/// of its mismatch is enabled.
+fn framing_error(bytes: &[u8], reports: FramingErrorReports) -> Option<FramingError> {
+    let (mismatch, offset) = framing_mismatch(bytes)?;
+    let reported = match mismatch {
+        FramingMismatch::CheckSum { .. } => reports.checksum,
+        FramingMismatch::BodyLength { .. } => reports.body_length,
+    };
+    reported.then(|| FramingError {
+        mismatch,
+        hexdump: hexdump(bytes, offset),
+    })
 }
 
 fn process_garbled_data(buf: &mut BytesMut) {
     let len = buf.len();
     for i in 1..buf.len() {
         if let Ok(_) | Err(RawMessageError::Incomplete) = raw_message(&buf[i..]) {
-            buf.split_to(i).freeze();
+            // `advance` keeps buffer unshared, so it can be given back
+            // to the buffer pool
+            buf.advance(i);
             info!("dropped {i} bytes of garbled message");
             return;
         }
@@ -38,9 +179,26 @@ fn process_garbled_data(buf: &mut BytesMut) {
     info!("dropped {len} bytes of garbled message");
 }
 
+// `deserializer::DeserializeError` is converted to `application::DeserializeError`
+// to prevent leaking ParseRejectReason to user code.
+//
+// When `tolerate_field_errors` is set, application message with invalid
+// optional fields is returned as `InputEvent::PartialMessage`, otherwise
+// only `InputEvent::Message` is returned.
+#[expect(clippy::too_many_arguments)]
 fn parse_message(
     bytes: &mut BytesMut,
-) -> Result<Option<Box<FixtMessage>>, deserializer::DeserializeError> {
+    buffer_pool: Option<&BufferPool>,
+    duplicate_tags: DuplicateTagPolicy,
+    begin_strings: &[FixString],
+    any_begin_string: bool,
+    other_msg_types: bool,
+    required_tags: Option<&RequiredTags>,
+    group_limits: GroupLimits,
+    tolerate_field_errors: bool,
+    framing_errors: FramingErrorReports,
+    raw_log: &mut RawLog,
+) -> Result<Option<InputEvent>, DeserializeError> {
     if bytes.is_empty() {
         return Ok(None);
     }
@@ -49,30 +207,412 @@ fn parse_message(
         String::from_utf8_lossy(bytes).replace('\x01', "|")
     );
 
-    let src_len = bytes.len();
-
-    match raw_message(bytes) {
-        Ok((leftover, raw_msg)) => {
-            let result = FixtMessage::from_raw_message(raw_msg).map(Some);
-            let leftover_len = leftover.len();
-            bytes.split_to(src_len - leftover_len).freeze();
-            result
+    match split_raw_message(bytes) {
+        Ok(raw_msg) => {
+            raw_log.message(&raw_msg);
+            let raw_message = raw_msg.as_raw_message();
+            let received;
+            let begin_strings = if any_begin_string
+                && !begin_strings.iter().any(|b| b == raw_message.begin_string)
+            {
+                // Mismatch is handled by the session
+                received = [raw_message.begin_string.to_owned()];
+                &received[..]
+            } else {
+                begin_strings
+            };
+            let mut deserializer = Deserializer::from_raw_message(raw_message)
+                .with_duplicate_tags(duplicate_tags)
+                .with_begin_strings(begin_strings)
+                .with_group_limits(group_limits);
+            #[cfg(feature = "pooled-data")]
+            if let Some(buffer_pool) = buffer_pool {
+                deserializer = deserializer.with_buffer_pool(buffer_pool);
+            }
+            #[cfg(not(feature = "pooled-data"))]
+            let _ = buffer_pool;
+            if other_msg_types {
+                deserializer = deserializer.with_other_msg_types();
+            }
+            if let Some(required_tags) = required_tags {
+                deserializer = deserializer.with_required_tags(required_tags);
+            }
+            let result = if tolerate_field_errors {
+                FixtMessage::deserialize_with_field_errors(deserializer).map(
+                    |(msg, field_errors)| {
+                        if field_errors.is_empty() {
+                            InputEvent::Message {
+                                msg,
+                                raw_message: raw_msg.clone().into_bytes(),
+                            }
+                        } else {
+                            InputEvent::PartialMessage {
+                                msg,
+                                field_errors: field_errors.into_iter().map(Into::into).collect(),
+                                raw_message: raw_msg.clone().into_bytes(),
+                            }
+                        }
+                    },
+                )
+            } else {
+                FixtMessage::deserialize(deserializer).map(|msg| InputEvent::Message {
+                    msg,
+                    raw_message: raw_msg.clone().into_bytes(),
+                })
+            }
+            .map(Some);
+            result.map_err(|err| match DeserializeError::from(err) {
+                DeserializeError::Reject {
+                    msg_type,
+                    seq_num,
+                    tag,
+                    reason,
+                    raw_message: _,
+                    duplicate,
+                } => DeserializeError::Reject {
+                    msg_type,
+                    seq_num,
+                    tag,
+                    reason,
+                    raw_message: Some(raw_msg.clone().into_bytes()),
+                    duplicate,
+                },
+                err => err,
+            })
         }
         Err(RawMessageError::Incomplete) => Ok(None),
         Err(err) => {
+            let framing_error = framing_error(bytes, framing_errors);
             process_garbled_data(bytes);
-            Err(err.into())
+            let error = deserializer::DeserializeError::from(err).into();
+            match framing_error {
+                Some(framing_error) => Ok(Some(InputEvent::FramingError {
+                    error,
+                    framing_error: Box::new(framing_error),
+                })),
+                None => Err(error),
+            }
+        }
+    }
+}
+
+/// Iterator over complete messages buffered in `BytesMut`, see
+/// [`iter_messages`].
+#[derive(Debug)]
+pub struct MessagesIter<'a> {
+    buffer: &'a mut BytesMut,
+    buffer_pool: Option<&'a BufferPool>,
+    duplicate_tags: DuplicateTagPolicy,
+    begin_strings: &'a [FixString],
+    other_msg_types: bool,
+    required_tags: Option<&'a RequiredTags>,
+    group_limits: GroupLimits,
+    consumed: usize,
+    last_consumed: usize,
+}
+
+impl<'a> MessagesIter<'a> {
+    /// Take buffers for Data/XmlData fields from `buffer_pool`.
+    #[cfg(feature = "pooled-data")]
+    pub fn with_buffer_pool(mut self, buffer_pool: &'a BufferPool) -> MessagesIter<'a> {
+        self.buffer_pool = Some(buffer_pool);
+        self
+    }
+
+    /// Handle fields appearing more than once in message body according
+    /// to `policy`, they are rejected by default.
+    pub fn with_duplicate_tags(mut self, policy: DuplicateTagPolicy) -> MessagesIter<'a> {
+        self.duplicate_tags = policy;
+        self
+    }
+
+    /// Accept messages with any of given BeginString<8> values, in addition
+    /// to BeginString<8> of generated messages.
+    pub fn with_begin_strings(mut self, begin_strings: &'a [FixString]) -> MessagesIter<'a> {
+        self.begin_strings = begin_strings;
+        self
+    }
+
+    /// Deserialize messages of types not defined in dictionary as
+    /// `Message::Other`, they are rejected by default.
+    pub fn with_other_msg_types(mut self, other_msg_types: bool) -> MessagesIter<'a> {
+        self.other_msg_types = other_msg_types;
+        self
+    }
+
+    /// Require header and trailer fields in addition to ones required
+    /// by the dictionary.
+    pub fn with_required_tags(mut self, required_tags: &'a RequiredTags) -> MessagesIter<'a> {
+        self.required_tags = Some(required_tags);
+        self
+    }
+
+    /// Limit NumInGroup values and nesting depth of repeating groups.
+    pub fn with_group_limits(mut self, group_limits: GroupLimits) -> MessagesIter<'a> {
+        self.group_limits = group_limits;
+        self
+    }
+
+    /// Number of bytes removed from the buffer so far, including dropped
+    /// garbled data.
+    pub fn consumed(&self) -> usize {
+        self.consumed
+    }
+
+    /// Number of bytes removed from the buffer by the last call to `next()`.
+    pub fn last_consumed(&self) -> usize {
+        self.last_consumed
+    }
+
+    /// Incomplete message data left in the buffer.
+    pub fn remaining(&self) -> &[u8] {
+        self.buffer
+    }
+}
+
+impl Iterator for MessagesIter<'_> {
+    type Item = Result<Box<FixtMessage>, DeserializeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = self.buffer.len();
+        let result = parse_message(
+            self.buffer,
+            self.buffer_pool,
+            self.duplicate_tags,
+            self.begin_strings,
+            false,
+            self.other_msg_types,
+            self.required_tags,
+            self.group_limits,
+            false,
+            FramingErrorReports::default(),
+            &mut RawLog::Off,
+        );
+        self.last_consumed = len - self.buffer.len();
+        self.consumed += self.last_consumed;
+        match result {
+            Ok(Some(InputEvent::Message { msg, .. })) => Some(Ok(msg)),
+            Ok(Some(event)) => unreachable!("unexpected input event: {event:?}"),
+            Ok(None) => None,
+            Err(error) => Some(Err(error)),
         }
     }
 }
 
-#[pin_project]
+/// Iterate over all complete messages in `buffer`, e.g. when many messages
+/// arrived in one read.
+///
+/// Parsed messages and garbled data are removed from the buffer, iteration
+/// stops at the first incomplete message, which is left in the buffer
+/// until more data is appended.
+pub fn iter_messages(buffer: &mut BytesMut) -> MessagesIter<'_> {
+    MessagesIter {
+        buffer,
+        buffer_pool: None,
+        duplicate_tags: DuplicateTagPolicy::Reject,
+        begin_strings: &[],
+        other_msg_types: false,
+        required_tags: None,
+        group_limits: GroupLimits::default(),
+        consumed: 0,
+        last_consumed: 0,
+    }
+}
+
+#[pin_project(PinnedDrop)]
 pub struct InputStream<S> {
     buffer: BytesMut,
+    buffer_pool: Option<BufferPool>,
+    duplicate_tags: DuplicateTagPolicy,
+    begin_strings: Vec<FixString>,
+    any_begin_string: bool,
+    other_msg_types: bool,
+    required_tags: RequiredTags,
+    group_limits: GroupLimits,
+    framing_errors: FramingErrorReports,
+    max_buffered: Option<usize>,
+    growth: BufferGrowth,
+    max_capacity: Option<usize>,
+    /// The largest capacity of buffer so far
+    allocated: usize,
+    resizes: Rc<Cell<u64>>,
+    /// Time taken to decode the last message
+    decode_time: Rc<Cell<Duration>>,
+    read_idle: Option<ReadIdle>,
+    raw_log: RawLog,
     #[pin]
     source: S,
 }
 
+#[pinned_drop]
+impl<S> PinnedDrop for InputStream<S> {
+    fn drop(self: Pin<&mut Self>) {
+        let this = self.project();
+        if let Some(buffer_pool) = this.buffer_pool {
+            buffer_pool.recycle(mem::take(this.buffer).into());
+        }
+    }
+}
+
+impl<S> InputStream<S> {
+    /// Handle fields appearing more than once in message body according
+    /// to `policy`, they are rejected by default.
+    pub fn with_duplicate_tags(mut self, policy: DuplicateTagPolicy) -> InputStream<S> {
+        self.duplicate_tags = policy;
+        self
+    }
+
+    /// Accept messages with any of given BeginString<8> values, in addition
+    /// to BeginString<8> of generated messages.
+    pub fn with_begin_strings(mut self, begin_strings: Vec<FixString>) -> InputStream<S> {
+        self.begin_strings = begin_strings;
+        self
+    }
+
+    /// Accept messages with any BeginString<8> value, e.g. once session
+    /// is established, so mismatch is handled according to
+    /// [`SessionSettings::begin_string_mismatch`](crate::settings::SessionSettings::begin_string_mismatch).
+    pub fn with_any_begin_string(mut self, any_begin_string: bool) -> InputStream<S> {
+        self.any_begin_string = any_begin_string;
+        self
+    }
+
+    /// Change setting made with [`InputStream::with_any_begin_string`].
+    pub fn set_any_begin_string(self: Pin<&mut Self>, any_begin_string: bool) {
+        *self.project().any_begin_string = any_begin_string;
+    }
+
+    /// Deserialize messages of types not defined in dictionary as
+    /// `Message::Other`, they are rejected by default.
+    pub fn with_other_msg_types(mut self, other_msg_types: bool) -> InputStream<S> {
+        self.other_msg_types = other_msg_types;
+        self
+    }
+
+    /// Require header and trailer fields in addition to ones required
+    /// by the dictionary.
+    pub fn with_required_tags(mut self, required_tags: RequiredTags) -> InputStream<S> {
+        self.required_tags = required_tags;
+        self
+    }
+
+    /// Limit NumInGroup values and nesting depth of repeating groups.
+    pub fn with_group_limits(mut self, group_limits: GroupLimits) -> InputStream<S> {
+        self.group_limits = group_limits;
+        self
+    }
+
+    /// Return `InputEvent::FramingError` instead of
+    /// `InputEvent::DeserializeError` for garbled messages with reported
+    /// CheckSum<10> or BodyLength<9> mismatch.
+    pub fn with_framing_errors(mut self, reports: FramingErrorReports) -> InputStream<S> {
+        self.framing_errors = reports;
+        self
+    }
+
+    /// Limit number of bytes buffered without complete message, when
+    /// exceeded, `InputEvent::BufferLimitExceeded` is returned. `None`
+    /// (default) disables the limit.
+    pub fn with_max_buffered(mut self, limit: Option<usize>) -> InputStream<S> {
+        self.max_buffered = limit;
+        self
+    }
+
+    /// Size read buffer according to `settings`.
+    pub fn with_read_buffer(mut self, settings: &BufferSettings) -> InputStream<S> {
+        if self.buffer_pool.is_none() && self.buffer.is_empty() {
+            self.buffer = BytesMut::with_capacity(settings.read_buffer_capacity);
+            self.allocated = self.buffer.capacity();
+        }
+        self.growth = settings.read_buffer_growth;
+        self.max_capacity = settings.read_buffer_max_capacity;
+        self
+    }
+
+    /// Number of times read buffer grew above its previous capacity.
+    pub fn buffer_resizes(&self) -> u64 {
+        self.resizes.get()
+    }
+
+    /// Count read buffer resizes in `resizes`, e.g. shared with session
+    /// state.
+    pub(crate) fn with_resize_counter(mut self, resizes: Rc<Cell<u64>>) -> InputStream<S> {
+        resizes.set(resizes.get() + self.resizes.get());
+        self.resizes = resizes;
+        self
+    }
+
+    /// Count read buffer resizes in `resizes`, including ones counted
+    /// so far, e.g. when session is established.
+    pub(crate) fn set_resize_counter(self: Pin<&mut Self>, resizes: Rc<Cell<u64>>) {
+        let this = self.project();
+        resizes.set(resizes.get() + this.resizes.get());
+        *this.resizes = resizes;
+    }
+
+    /// Report time taken to decode the last message in `decode_time`,
+    /// e.g. shared with session.
+    pub(crate) fn with_decode_timer(mut self, decode_time: Rc<Cell<Duration>>) -> InputStream<S> {
+        self.decode_time = decode_time;
+        self
+    }
+
+    /// Report time taken to decode the last message in `decode_time`,
+    /// including message decoded so far, e.g. when session is established.
+    pub(crate) fn set_decode_timer(self: Pin<&mut Self>, decode_time: Rc<Cell<Duration>>) {
+        let this = self.project();
+        decode_time.set(this.decode_time.get());
+        *this.decode_time = decode_time;
+    }
+
+    /// Change limit set with [`InputStream::with_max_buffered`], e.g. when
+    /// session is established.
+    pub fn set_max_buffered(self: Pin<&mut Self>, limit: Option<usize>) {
+        *self.project().max_buffered = limit;
+    }
+
+    /// Return `InputEvent::ReadIdle` when no data is read for `timeout`,
+    /// once until data is read again. `None` (default) disables the
+    /// watchdog.
+    pub fn with_read_idle_timeout(mut self, timeout: Option<Duration>) -> InputStream<S> {
+        self.read_idle = timeout.map(ReadIdle::new);
+        self
+    }
+
+    /// Change timeout set with [`InputStream::with_read_idle_timeout`],
+    /// e.g. when session is established, watchdog is restarted.
+    pub fn set_read_idle_timeout(self: Pin<&mut Self>, timeout: Option<Duration>) {
+        *self.project().read_idle = timeout.map(ReadIdle::new);
+    }
+
+    /// Log raw inbound messages to `session_log`.
+    pub(crate) fn with_session_log(
+        mut self,
+        session_log: Option<Rc<SessionLog>>,
+    ) -> InputStream<S> {
+        self.raw_log = session_log.map_or(RawLog::Off, RawLog::On);
+        self
+    }
+
+    /// Keep the first message until session log is set with
+    /// [`InputStream::set_session_log`], e.g. when session is not known
+    /// until `Logon<A>` is received.
+    pub(crate) fn with_pending_session_log(mut self) -> InputStream<S> {
+        self.raw_log = RawLog::Pending(None);
+        self
+    }
+
+    /// Log raw inbound messages to `session_log`, including the first
+    /// message kept since [`InputStream::with_pending_session_log`].
+    pub(crate) fn set_session_log(self: Pin<&mut Self>, session_log: Option<Rc<SessionLog>>) {
+        let raw_log = self.project().raw_log;
+        if let (RawLog::Pending(Some(pending)), Some(session_log)) = (&*raw_log, &session_log) {
+            session_log.message_in(pending);
+        }
+        *raw_log = session_log.map_or(RawLog::Off, RawLog::On);
+    }
+}
+
 impl<S> Stream for InputStream<S>
 where
     S: AsyncRead + Unpin,
@@ -85,15 +625,58 @@ where
         loop {
             // Attempt to parse a message from the buffered data.
             // If enough data has been buffered, the message is returned.
-            match parse_message(this.buffer) {
-                Ok(Some(msg)) => {
-                    return Poll::Ready(Some(InputEvent::Message(msg)));
+            // Field errors are always collected, it's up to the session
+            // to decide whether message should be rejected
+            let started = Instant::now();
+            match parse_message(
+                this.buffer,
+                this.buffer_pool.as_ref(),
+                *this.duplicate_tags,
+                this.begin_strings,
+                *this.any_begin_string,
+                *this.other_msg_types,
+                Some(this.required_tags),
+                *this.group_limits,
+                true,
+                *this.framing_errors,
+                this.raw_log,
+            ) {
+                Ok(Some(event)) => {
+                    this.decode_time.set(started.elapsed());
+                    return Poll::Ready(Some(event));
                 }
                 Ok(None) => {}
-                // Convert `deserializer::DeserializeError` to `application::DeserializeError`
-                // to prevent leaking ParseRejectReason to user code.
                 Err(error) => {
-                    return Poll::Ready(Some(InputEvent::DeserializeError(error.into())));
+                    return Poll::Ready(Some(InputEvent::DeserializeError(error)));
+                }
+            }
+
+            if let Some(limit) = *this.max_buffered {
+                if this.buffer.len() > limit {
+                    warn!(
+                        "{} bytes buffered without complete message, limit is {limit}",
+                        this.buffer.len()
+                    );
+                    return Poll::Ready(Some(InputEvent::BufferLimitExceeded(limit)));
+                }
+            }
+
+            if this.buffer.len() == this.buffer.capacity() {
+                let capacity = this.buffer.capacity();
+                let additional = this.growth.additional(capacity, *this.max_capacity);
+                if additional == 0 {
+                    warn!("read buffer full, max capacity is {capacity} bytes");
+                    return Poll::Ready(Some(InputEvent::BufferLimitExceeded(capacity)));
+                }
+                this.buffer.reserve(additional);
+                if this.buffer.capacity() > *this.allocated {
+                    debug!(
+                        "read buffer resized from {} to {} bytes",
+                        this.allocated,
+                        this.buffer.capacity()
+                    );
+                    *this.allocated = this.buffer.capacity();
+                    this.resizes.set(this.resizes.get() + 1);
                 }
             }
 
@@ -103,7 +686,15 @@ where
             // On success, the number of bytes is returned. `0` indicates "end
             // of stream".
             let future = poll_read_buf(Pin::new(&mut this.source), cx, this.buffer);
-            match ready!(future) {
+            let Poll::Ready(result) = future else {
+                if let Some(read_idle) = this.read_idle {
+                    let timeout = ready!(read_idle.poll_elapsed(cx));
+                    warn!("No data read for {timeout:?}");
+                    return Poll::Ready(Some(InputEvent::ReadIdle(timeout)));
+                }
+                return Poll::Pending;
+            };
+            match result {
                 Ok(0) => {
                     // The remote closed the connection. For this to be a clean
                     // shutdown, there should be no data in the read buffer. If
@@ -117,20 +708,49 @@ where
                         return Poll::Ready(None);
                     }
                 }
-                Ok(_n) => continue,
+                Ok(_n) => {
+                    if let Some(read_idle) = this.read_idle {
+                        read_idle.reset();
+                    }
+                    continue;
+                }
                 Err(err) => return Poll::Ready(Some(InputEvent::IoError(err))),
             }
         }
     }
 }
 
-pub fn input_stream<S>(source: S) -> InputStream<S>
+// TODO: Max MSG size
+const READ_BUFFER_SIZE: usize = 4096;
+
+pub fn input_stream<S>(source: S, buffer_pool: Option<BufferPool>) -> InputStream<S>
 where
     S: AsyncRead + Unpin,
 {
+    let buffer = match &buffer_pool {
+        Some(buffer_pool) => {
+            BytesMut::from(Bytes::from(buffer_pool.take(READ_BUFFER_SIZE).into_vec()))
+        }
+        None => BytesMut::with_capacity(READ_BUFFER_SIZE),
+    };
     InputStream {
-        // TODO: Max MSG size
-        buffer: BytesMut::with_capacity(4096),
+        allocated: buffer.capacity(),
+        buffer,
+        buffer_pool,
+        duplicate_tags: DuplicateTagPolicy::Reject,
+        begin_strings: Vec::new(),
+        any_begin_string: false,
+        other_msg_types: false,
+        required_tags: RequiredTags::default(),
+        group_limits: GroupLimits::default(),
+        framing_errors: FramingErrorReports::default(),
+        max_buffered: None,
+        growth: BufferGrowth::Double,
+        max_capacity: None,
+        resizes: Rc::new(Cell::new(0)),
+        decode_time: Rc::new(Cell::new(Duration::ZERO)),
+        read_idle: None,
+        raw_log: RawLog::Off,
         source,
     }
 }
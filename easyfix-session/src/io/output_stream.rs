@@ -2,8 +2,8 @@ use std::rc::Rc;
 
 use async_stream::stream;
 use easyfix_messages::{
-    fields::UtcTimestamp,
-    messages::{FixtMessage, BEGIN_STRING},
+    fields::{MsgType, SeqNum, UtcTimestamp},
+    messages::{FixtMessage, Message, SequenceReset},
 };
 use futures_util::Stream;
 use tokio::{
@@ -14,10 +14,22 @@ use tokio_stream::StreamExt;
 use tracing::{debug, instrument};
 
 use super::time::timeout_stream;
-use crate::{messages_storage::MessagesStorage, session::Session, DisconnectReason, SenderMsg};
+use crate::{
+    inspector::MsgDirection, messages_storage::MessagesStorage, session::Session,
+    settings::OutputOrder, DisconnectReason, QueuedMsg, SenderMsg,
+};
+
+/// Serialized message ready to be written.
+pub(crate) struct OutputMsg {
+    pub(crate) buffer: Vec<u8>,
+    pub(crate) msg_type: MsgType,
+    pub(crate) msg_seq_num: SeqNum,
+    /// Time taken to serialize the message
+    pub(crate) serialize_time: Duration,
+}
 
 pub(crate) enum OutputEvent {
-    Message(Vec<u8>),
+    Message(OutputMsg),
     Timeout,
     Disconnect(DisconnectReason),
 }
@@ -27,24 +39,32 @@ fn fill_header<S: MessagesStorage>(message: &mut FixtMessage, session: &Session<
 
     let header = &mut message.header;
     if header.begin_string.is_empty() {
-        header.begin_string = BEGIN_STRING.to_owned();
+        header.begin_string = session.session_settings().begin_string();
     }
 
     header.msg_type = message.body.msg_type();
 
     if header.sender_comp_id.is_empty() {
-        header.sender_comp_id = session.session_id().sender_comp_id().to_owned();
+        header.sender_comp_id = session.session_settings().sender_comp_id();
     }
     if header.target_comp_id.is_empty() {
-        header.target_comp_id = session.session_id().target_comp_id().to_owned();
+        header.target_comp_id = session.session_settings().target_comp_id();
     }
     if header.sending_time == UtcTimestamp::MIN_UTC {
-        header.sending_time = UtcTimestamp::now();
+        header.sending_time = session.session_settings().clock.now();
     }
 
     if header.msg_seq_num == 0 {
         header.msg_seq_num = state.next_sender_msg_seq_num();
-        state.incr_next_sender_msg_seq_num();
+        match *message.body {
+            // SequenceReset<4> in Reset mode, following messages continue
+            // from NewSeqNo<36>
+            Message::SequenceReset(SequenceReset {
+                gap_fill_flag: None | Some(false),
+                new_seq_no,
+            }) if new_seq_no > header.msg_seq_num => state.set_next_sender_msg_seq_num(new_seq_no),
+            _ => state.incr_next_sender_msg_seq_num(),
+        }
     }
 
     state.set_last_sent_time(Instant::now());
@@ -59,21 +79,79 @@ fn fill_header<S: MessagesStorage>(message: &mut FixtMessage, session: &Session<
         msg_type = ?message.msg_type()
     )
 )]
-fn output_handler<S: MessagesStorage>(message: &FixtMessage, session: &Session<S>) -> Vec<u8> {
+async fn output_handler<S: MessagesStorage>(
+    message: &FixtMessage,
+    session: &Session<S>,
+) -> Option<OutputMsg> {
+    session.inspect(MsgDirection::Outbound, message).await;
+    let started = Instant::now();
     // TODO: fn serialize_to(&mut buf) / fn serialize_to_buf(&mut buf)
-    let buffer = message.serialize();
+    let buffer = match session.serialize(message) {
+        Ok(buffer) => buffer,
+        Err(err) => {
+            // MsgSeqNum<34> is already taken, message not stored will be
+            // gap filled on resend
+            session.on_serialize_failed(message, err).await;
+            return None;
+        }
+    };
+    let serialize_time = started.elapsed();
     if !message.header.poss_dup_flag.unwrap_or(false) {
-        session
+        let result = session
             .state()
             .borrow_mut()
             .store(message.header.msg_seq_num, &buffer);
+        if let Err(error) = result {
+            if !session.on_store_failed(message, error).await {
+                return None;
+            }
+        }
     }
 
     debug!(
         "Encoded raw data: {}",
         String::from_utf8_lossy(&buffer).replace('\x01', "|")
     );
-    buffer
+    Some(OutputMsg {
+        buffer,
+        msg_type: message.msg_type(),
+        msg_seq_num: message.header.msg_seq_num,
+        serialize_time,
+    })
+}
+
+/// Process queued message, returns serialized message to write unless
+/// it was held, dropped or failed.
+pub(crate) async fn process_msg<S: MessagesStorage>(
+    msg: QueuedMsg,
+    session: &Session<S>,
+) -> Option<OutputMsg> {
+    let msg = session.hold_if_paused(msg).await?;
+    let mut msg = session.drop_if_expired(msg).await?;
+    fill_header(&mut msg, session);
+    session.enrich(&mut msg);
+    let output = match session.session_settings().output_order {
+        OutputOrder::EmitThenStore => match session.on_message_out(msg).await {
+            Some(msg) => output_handler(&msg, session).await,
+            None => None,
+        },
+        OutputOrder::StoreThenEmit => {
+            let output = output_handler(&msg, session).await;
+            // Message is already stored, so changes made
+            // by the event handler are ignored
+            if output.is_some() {
+                session.on_message_out(msg).await;
+            }
+            output
+        }
+    };
+    if let Some(output) = &output {
+        session.state().borrow_mut().incr_messages_sent();
+        if let Some(session_log) = session.session_log() {
+            session_log.message_out(&output.buffer);
+        }
+    }
+    output
 }
 
 pub(crate) fn output_stream<S: MessagesStorage>(
@@ -83,13 +161,19 @@ pub(crate) fn output_stream<S: MessagesStorage>(
 ) -> impl Stream<Item = OutputEvent> {
     let stream = stream! {
         while let Some(sender_msg) = receiver.recv().await {
+            // Messages buffered while paused go out before newer ones
+            while let Some(msg) = session.take_resumed_msg() {
+                if let Some(output) = process_msg(msg, &session).await {
+                    yield OutputEvent::Message(output);
+                }
+            }
             match sender_msg {
-                SenderMsg::Msg(mut msg) => {
-                    fill_header(&mut msg, &session);
-                    if let Some(msg) = session.on_message_out(msg).await {
-                        yield OutputEvent::Message(output_handler(&msg, &session));
+                SenderMsg::Msg(msg) => {
+                    if let Some(output) = process_msg(msg, &session).await {
+                        yield OutputEvent::Message(output);
                     }
                 }
+                SenderMsg::Resume => {}
                 SenderMsg::Disconnect(reason) => {
                     // Close stream, but don't break the loop now.
                     // It's possible there are still messages inside.
@@ -0,0 +1,180 @@
+//! Conversion between session messages log (see
+//! [`SessionLogSettings`](crate::settings::SessionLogSettings)) and
+//! QuickFIX `*.messages.log` format, so existing log analysis tools can be
+//! used with easyfix sessions and QuickFIX logs can be replayed (see
+//! `replay::parse_log`, enabled with `test-util` feature) or inspected as
+//! session logs.
+//!
+//! Session log lines are `<timestamp> IN|OUT <message>`, QuickFIX log lines
+//! are `<timestamp> : <message>`, with direction of message not recorded.
+//! Timestamps are in UTC, `YYYYMMDD-HH:MM:SS` with fraction of second.
+
+use std::io::{self, BufRead, Write};
+
+use chrono::NaiveDateTime;
+use easyfix_messages::{fields::FixStr, tag_value::TagValueIter};
+
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H:%M:%S%.f";
+
+/// Direction of logged message, relative to the local session.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
+    Inbound,
+    Outbound,
+}
+
+/// Message with the time it was logged.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JournalEntry {
+    pub timestamp: NaiveDateTime,
+    pub direction: Direction,
+    /// Raw message, fields separated with SOH
+    pub msg: Vec<u8>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum JournalError {
+    #[error("line {line}: {reason}")]
+    InvalidLine { line: usize, reason: &'static str },
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+}
+
+fn parse_timestamp(timestamp: &[u8]) -> Option<NaiveDateTime> {
+    let timestamp = std::str::from_utf8(timestamp).ok()?;
+    NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()
+}
+
+/// Split non-empty lines of `log`, with line numbers starting from 1.
+fn lines(log: impl BufRead) -> impl Iterator<Item = io::Result<(usize, Vec<u8>)>> {
+    log.split(b'\n')
+        .enumerate()
+        .map(|(idx, line)| {
+            line.map(|mut line| {
+                if line.last() == Some(&b'\r') {
+                    line.pop();
+                }
+                (idx + 1, line)
+            })
+        })
+        .filter(|line| !matches!(line, Ok((_, line)) if line.is_empty()))
+}
+
+/// Read session messages log.
+pub fn read_session_log(log: impl BufRead) -> Result<Vec<JournalEntry>, JournalError> {
+    let mut entries = Vec::new();
+    for line in lines(log) {
+        let (line, bytes) = line?;
+        let invalid = |reason| JournalError::InvalidLine { line, reason };
+        let mut parts = bytes.splitn(3, |b| *b == b' ');
+        let timestamp = parts
+            .next()
+            .and_then(parse_timestamp)
+            .ok_or_else(|| invalid("invalid timestamp"))?;
+        let direction = match parts.next() {
+            Some(b"IN") => Direction::Inbound,
+            Some(b"OUT") => Direction::Outbound,
+            _ => return Err(invalid("invalid direction")),
+        };
+        let msg = parts.next().ok_or_else(|| invalid("missing message"))?;
+        entries.push(JournalEntry {
+            timestamp,
+            direction,
+            msg: msg.to_vec(),
+        });
+    }
+    Ok(entries)
+}
+
+/// Read QuickFIX messages log, messages sent by `local_comp_id` are marked
+/// as outbound. Fields may be separated with SOH or `|`.
+pub fn read_quickfix_log(
+    log: impl BufRead,
+    local_comp_id: &FixStr,
+) -> Result<Vec<JournalEntry>, JournalError> {
+    let mut entries = Vec::new();
+    for line in lines(log) {
+        let (line, bytes) = line?;
+        let invalid = |reason| JournalError::InvalidLine { line, reason };
+        let separator = bytes
+            .windows(3)
+            .position(|window| window == b" : ")
+            .ok_or_else(|| invalid("missing ` : ` separator"))?;
+        let timestamp =
+            parse_timestamp(&bytes[..separator]).ok_or_else(|| invalid("invalid timestamp"))?;
+        let msg: Vec<u8> = bytes[separator + 3..]
+            .iter()
+            .map(|b| if *b == b'|' { b'\x01' } else { *b })
+            .collect();
+        let sender_comp_id = TagValueIter::new(&msg)
+            .map_while(Result::ok)
+            .find_map(|(tag, value)| (tag == 49).then_some(value))
+            .ok_or_else(|| invalid("missing SenderCompID<49>"))?;
+        let direction = if sender_comp_id == local_comp_id.as_bytes() {
+            Direction::Outbound
+        } else {
+            Direction::Inbound
+        };
+        entries.push(JournalEntry {
+            timestamp,
+            direction,
+            msg,
+        });
+    }
+    Ok(entries)
+}
+
+/// Write entries in session messages log format.
+pub fn write_session_log(entries: &[JournalEntry], mut output: impl Write) -> io::Result<()> {
+    for entry in entries {
+        let direction = match entry.direction {
+            Direction::Inbound => "IN",
+            Direction::Outbound => "OUT",
+        };
+        write!(
+            output,
+            "{} {direction} ",
+            entry.timestamp.format("%Y%m%d-%H:%M:%S%.6f")
+        )?;
+        output.write_all(&entry.msg)?;
+        output.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Write entries in QuickFIX messages log format, with millisecond
+/// timestamps and fields separated with SOH.
+pub fn write_quickfix_log(entries: &[JournalEntry], mut output: impl Write) -> io::Result<()> {
+    for entry in entries {
+        write!(
+            output,
+            "{} : ",
+            entry.timestamp.format("%Y%m%d-%H:%M:%S%.3f")
+        )?;
+        output.write_all(&entry.msg)?;
+        output.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Export session messages log to QuickFIX format.
+pub fn export_quickfix_log(
+    session_log: impl BufRead,
+    output: impl Write,
+) -> Result<(), JournalError> {
+    let entries = read_session_log(session_log)?;
+    write_quickfix_log(&entries, output)?;
+    Ok(())
+}
+
+/// Import QuickFIX messages log to session messages log format, messages
+/// sent by `local_comp_id` are logged as outbound.
+pub fn import_quickfix_log(
+    log: impl BufRead,
+    local_comp_id: &FixStr,
+    output: impl Write,
+) -> Result<(), JournalError> {
+    let entries = read_quickfix_log(log, local_comp_id)?;
+    write_session_log(&entries, output)?;
+    Ok(())
+}
@@ -2,23 +2,47 @@
 #![feature(type_alias_impl_trait)]
 
 pub mod acceptor;
+#[cfg(feature = "admin")]
+pub mod admin;
 pub mod application;
+pub mod blocking;
+pub mod bridge;
+pub mod calendar;
+pub mod capture;
+pub mod credentials;
+mod enrichment;
 pub mod initiator;
+pub mod inspector;
 pub mod io;
+pub mod journal;
+pub mod logon_guard;
 pub mod messages_storage;
+pub mod quickfix;
+mod reject_reasons;
+#[cfg(any(test, feature = "test-util"))]
+pub mod replay;
 mod session;
 pub mod session_id;
+mod session_log;
 mod session_state;
 pub mod settings;
+pub mod verifier;
 
-use std::time::Duration;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use easyfix_messages::{
     fields::{FixString, MsgType, UtcTimestamp},
-    messages::{FixtMessage, Header, Message, Trailer},
+    messages::{FixtMessage, Header, Message, MsgCat, Trailer},
 };
+use session_id::SubIdRoute;
 use settings::Settings;
-use tokio::sync::mpsc;
+use tokio::{sync::mpsc, time::Instant};
 
 const NO_INBOUND_TIMEOUT_PADDING: Duration = Duration::from_millis(250);
 
@@ -30,6 +54,15 @@ pub enum SessionError {
     LogonNeverReceived,
     #[error("Message does not point to any session.")]
     UnknownSession,
+    /// Session stopped, it doesn't log on until started again.
+    #[error("Session is stopped.")]
+    Stopped,
+    /// Operation requires established connection.
+    #[error("Session is not connected.")]
+    NotConnected,
+    /// Resend range is empty or starts at 0.
+    #[error("Invalid resend range.")]
+    InvalidResendRange,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -41,7 +74,7 @@ pub enum Error {
 }
 
 /// Disconnection reasons.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum DisconnectReason {
     /// Logout requested locally
     LocalRequestedLogout,
@@ -59,23 +92,162 @@ pub enum DisconnectReason {
     Disconnected,
     /// I/O Error
     IoError,
+    /// Outgoing message could not be stored, see
+    /// [`StoreFailurePolicy::Disconnect`](settings::StoreFailurePolicy::Disconnect)
+    StoreError,
+    /// Received message with BeginString<8> not matching the session, see
+    /// [`BeginStringMismatchPolicy`](settings::BeginStringMismatchPolicy)
+    BeginStringMismatch,
+    /// Outgoing message could not be serialized, see
+    /// [`SerializeFailurePolicy::Disconnect`](settings::SerializeFailurePolicy::Disconnect)
+    SerializeError,
+    /// No data read from socket, see
+    /// [`ReadIdleAction::Disconnect`](settings::ReadIdleAction::Disconnect)
+    ReadIdle,
+    /// Credentials could not be resolved before Logon<A> was sent, see
+    /// [`credentials`]
+    CredentialsUnavailable,
+}
+
+/// Reason of [`SendError`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum SendErrorReason {
+    /// Connection is closed, messages sent with [`Sender`] of this
+    /// connection are no longer accepted
+    #[error("session disconnected")]
+    Disconnected,
+    /// Logout<5> was sent or received, application messages are refused
+    #[error("session logged out")]
+    LoggedOut,
+    /// Administrative message sent through [`AppSender`]
+    #[error("admin message refused")]
+    AdminMsg,
+    /// Message resent with [`ResendSeqNum::Original`] has no MsgSeqNum<34>
+    #[error("MsgSeqNum not set")]
+    MsgSeqNumMissing,
+}
+
+/// Message which could not be sent, with the reason.
+#[derive(Debug, thiserror::Error)]
+#[error("failed to send {:?} message: {reason}", .msg.msg_type())]
+pub struct SendError {
+    pub reason: SendErrorReason,
+    pub msg: Box<FixtMessage>,
+}
+
+impl SendError {
+    fn new(reason: SendErrorReason, msg: Box<FixtMessage>) -> SendError {
+        SendError { reason, msg }
+    }
+}
+
+/// Handling of outbound application messages while session is paused.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PauseMode {
+    /// Keep messages and send them when session is resumed, at most
+    /// [`SessionSettings::paused_queue_limit`](settings::SessionSettings::paused_queue_limit)
+    /// messages are kept, the ones sent when the buffer is full are
+    /// discarded like with [`PauseMode::Reject`]
+    Buffer,
+    /// Discard messages, each one is reported with
+    /// [`FixEvent::AppMsgDiscarded`](application::FixEvent::AppMsgDiscarded)
+    Reject,
+}
+
+/// MsgSeqNum<34> of manually resent message, see [`Sender::resend`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ResendSeqNum {
+    /// Keep MsgSeqNum<34> of the original message, PossDupFlag<43> is set,
+    /// message is not stored again
+    #[default]
+    Original,
+    /// Assign the next MsgSeqNum<34>, PossResend<97> is set, message
+    /// is stored as a new one
+    New,
+}
+
+/// Header handling of manually resent message, see [`Sender::resend`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ResendOptions {
+    pub seq_num: ResendSeqNum,
+    /// OrigSendingTime<122> value, when not set, OrigSendingTime<122>
+    /// or SendingTime<52> of the original message is used.
+    pub orig_sending_time: Option<UtcTimestamp>,
+}
+
+impl ResendOptions {
+    /// Set resend header fields of `msg`, SendingTime<52> is cleared,
+    /// so it's set again before serialization.
+    fn apply(&self, msg: &mut FixtMessage) {
+        let header = &mut msg.header;
+        let orig_sending_time = self
+            .orig_sending_time
+            .or(header.orig_sending_time)
+            .or((header.sending_time != UtcTimestamp::MIN_UTC).then_some(header.sending_time))
+            .unwrap_or_else(UtcTimestamp::now);
+        header.orig_sending_time = Some(orig_sending_time);
+        header.sending_time = UtcTimestamp::MIN_UTC;
+        match self.seq_num {
+            ResendSeqNum::Original => {
+                header.poss_dup_flag = Some(true);
+                header.poss_resend = None;
+            }
+            ResendSeqNum::New => {
+                header.msg_seq_num = 0;
+                header.poss_dup_flag = None;
+                header.poss_resend = Some(true);
+            }
+        }
+    }
+}
+
+/// Outgoing message with the time it was queued, see
+/// `SessionSettings::app_msg_ttl`.
+#[derive(Debug)]
+pub(crate) struct QueuedMsg {
+    pub(crate) msg: Box<FixtMessage>,
+    pub(crate) queued_at: Instant,
+    /// Overrides `SessionSettings::app_msg_ttl`
+    pub(crate) ttl: Option<Duration>,
+}
+
+impl QueuedMsg {
+    pub(crate) fn new(msg: Box<FixtMessage>, ttl: Option<Duration>) -> QueuedMsg {
+        QueuedMsg {
+            msg,
+            queued_at: Instant::now(),
+            ttl,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub(crate) enum SenderMsg {
-    Msg(Box<FixtMessage>),
+    Msg(QueuedMsg),
+    /// Wake up output stream to send messages buffered while paused.
+    Resume,
     Disconnect(DisconnectReason),
 }
 
 #[derive(Clone, Debug)]
 pub struct Sender {
     inner: mpsc::UnboundedSender<SenderMsg>,
+    logged_out: Arc<AtomicBool>,
 }
 
 impl Sender {
     /// Create new `Sender` instance.
     pub(crate) fn new(writer: mpsc::UnboundedSender<SenderMsg>) -> Sender {
-        Sender { inner: writer }
+        Sender {
+            inner: writer,
+            logged_out: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Refuse application messages from now on, see
+    /// [`SendErrorReason::LoggedOut`].
+    pub(crate) fn set_logged_out(&self) {
+        self.logged_out.store(true, Ordering::Relaxed);
     }
 
     /// Send FIXT message.
@@ -93,18 +265,73 @@ impl Sender {
     ///
     /// The checksum(10) field value is always ignored - it is computed and set
     /// after serialziation.
-    pub fn send_raw(&self, msg: Box<FixtMessage>) -> Result<(), Box<FixtMessage>> {
-        if let Err(msg) = self.inner.send(SenderMsg::Msg(msg)) {
+    ///
+    /// Message is returned back in [`SendError`] when connection is closed,
+    /// or when it's application message and Logout<5> was already sent
+    /// or received.
+    pub fn send_raw(&self, msg: Box<FixtMessage>) -> Result<(), SendError> {
+        self.send_checked(QueuedMsg::new(msg, None))
+    }
+
+    /// Send FIXT message, see [`Sender::send_raw`]. Application message
+    /// not sent within `ttl` is dropped, regardless of
+    /// [`SessionSettings::app_msg_ttl`](settings::SessionSettings::app_msg_ttl).
+    pub fn send_raw_with_ttl(&self, msg: Box<FixtMessage>, ttl: Duration) -> Result<(), SendError> {
+        self.send_checked(QueuedMsg::new(msg, Some(ttl)))
+    }
+
+    fn send_checked(&self, queued: QueuedMsg) -> Result<(), SendError> {
+        // Disconnection takes precedence, it's final for this sender
+        if queued.msg.msg_cat() == MsgCat::App
+            && self.logged_out.load(Ordering::Relaxed)
+            && !self.inner.is_closed()
+        {
+            error!(
+                "refused to send {:?}<{}> message, session logged out",
+                queued.msg.msg_type(),
+                queued.msg.msg_type().as_fix_str()
+            );
+            return Err(SendError::new(SendErrorReason::LoggedOut, queued.msg));
+        }
+        self.send_queued(queued)
+            .map_err(|queued| SendError::new(SendErrorReason::Disconnected, queued.msg))
+    }
+
+    /// Resend previously sent message outside of ResendRequest<2> flow,
+    /// e.g. when application is not sure if it was delivered.
+    ///
+    /// Header fields are set according to `options`, see [`ResendOptions`].
+    /// With [`ResendSeqNum::Original`], MsgSeqNum<34> of `msg` must be set.
+    /// See [`Sender::send_raw`] for possible errors.
+    pub fn resend(
+        &self,
+        mut msg: Box<FixtMessage>,
+        options: ResendOptions,
+    ) -> Result<(), SendError> {
+        if options.seq_num == ResendSeqNum::Original && msg.header.msg_seq_num == 0 {
+            error!(
+                "refused to resend {:?}<{}> message, MsgSeqNum not set",
+                msg.msg_type(),
+                msg.msg_type().as_fix_str()
+            );
+            return Err(SendError::new(SendErrorReason::MsgSeqNumMissing, msg));
+        }
+        options.apply(&mut msg);
+        self.send_checked(QueuedMsg::new(msg, None))
+    }
+
+    pub(crate) fn send_queued(&self, queued: QueuedMsg) -> Result<(), QueuedMsg> {
+        if let Err(msg) = self.inner.send(SenderMsg::Msg(queued)) {
             match msg.0 {
-                SenderMsg::Msg(msg) => {
+                SenderMsg::Msg(queued) => {
                     error!(
                         "failed to send {:?}<{}> message, receiver closed or dropped",
-                        msg.msg_type(),
-                        msg.msg_type().as_fix_str()
+                        queued.msg.msg_type(),
+                        queued.msg.msg_type().as_fix_str()
                     );
-                    Err(msg)
+                    Err(queued)
                 }
-                SenderMsg::Disconnect(_) => unreachable!(),
+                SenderMsg::Resume | SenderMsg::Disconnect(_) => unreachable!(),
             }
         } else {
             Ok(())
@@ -118,7 +345,9 @@ impl Sender {
     ///
     /// All header and trailer fields can be also adjusted when handing
     /// `FixEvent::AppMsgOut` and `FixEvent::AdmMsgOut`.
-    pub fn send(&self, msg: Box<Message>) -> Result<(), Box<FixtMessage>> {
+    ///
+    /// See [`Sender::send_raw`] for possible errors.
+    pub fn send(&self, msg: Box<Message>) -> Result<(), SendError> {
         let msg = Box::new(FixtMessage {
             header: Box::new(new_header(msg.msg_type())),
             body: msg,
@@ -127,6 +356,24 @@ impl Sender {
         self.send_raw(msg)
     }
 
+    /// Send FIX message, see [`Sender::send`], with SenderSubID<50>
+    /// and TargetSubID<57> set from `route`.
+    pub fn send_routed(&self, msg: Box<Message>, route: &SubIdRoute) -> Result<(), SendError> {
+        let mut header = new_header(msg.msg_type());
+        route.apply(&mut header);
+        self.send_raw(Box::new(FixtMessage {
+            header: Box::new(header),
+            body: msg,
+            trailer: Box::new(new_trailer()),
+        }))
+    }
+
+    /// Wake up output stream after session is resumed, nothing happens
+    /// when connection is already closed.
+    pub(crate) fn resume(&self) {
+        let _ = self.inner.send(SenderMsg::Resume);
+    }
+
     /// Send disconnect message.
     ///
     /// Output stream will close output queue so no more message can be send
@@ -136,6 +383,92 @@ impl Sender {
             error!("failed to disconnect, receiver closed or dropped");
         }
     }
+
+    /// Create handle restricted to application messages.
+    pub fn app_sender(&self) -> AppSender {
+        AppSender {
+            inner: self.clone(),
+        }
+    }
+}
+
+/// Handle for sending application messages only.
+///
+/// Unlike [`Sender`], administrative messages (Heartbeat<0>, Logon<A>,
+/// SequenceReset<4>, etc.) are refused, so it can be safely passed to
+/// business code without risk of corrupting session state.
+#[derive(Clone, Debug)]
+pub struct AppSender {
+    inner: Sender,
+}
+
+impl AppSender {
+    fn refuse_admin(msg: Box<FixtMessage>) -> Result<Box<FixtMessage>, SendError> {
+        if msg.msg_cat() == MsgCat::Admin {
+            error!(
+                "refused to send {:?}<{}> admin message through application sender",
+                msg.msg_type(),
+                msg.msg_type().as_fix_str()
+            );
+            return Err(SendError::new(SendErrorReason::AdminMsg, msg));
+        }
+        Ok(msg)
+    }
+
+    /// Send FIXT application message, see [`Sender::send_raw`].
+    ///
+    /// Administrative messages are returned back as error.
+    pub fn send_raw(&self, msg: Box<FixtMessage>) -> Result<(), SendError> {
+        let msg = AppSender::refuse_admin(msg)?;
+        self.inner.send_raw(msg)
+    }
+
+    /// Send FIXT application message with TTL, see
+    /// [`Sender::send_raw_with_ttl`].
+    ///
+    /// Administrative messages are returned back as error.
+    pub fn send_raw_with_ttl(&self, msg: Box<FixtMessage>, ttl: Duration) -> Result<(), SendError> {
+        let msg = AppSender::refuse_admin(msg)?;
+        self.inner.send_raw_with_ttl(msg, ttl)
+    }
+
+    /// Send FIX application message, see [`Sender::send`].
+    ///
+    /// Administrative messages are returned back as error.
+    pub fn send(&self, msg: Box<Message>) -> Result<(), SendError> {
+        self.send_raw(Box::new(FixtMessage {
+            header: Box::new(new_header(msg.msg_type())),
+            body: msg,
+            trailer: Box::new(new_trailer()),
+        }))
+    }
+
+    /// Send FIX application message, see [`Sender::send_routed`].
+    ///
+    /// Administrative messages are returned back as error.
+    pub fn send_routed(&self, msg: Box<Message>, route: &SubIdRoute) -> Result<(), SendError> {
+        let mut header = new_header(msg.msg_type());
+        route.apply(&mut header);
+        self.send_raw(Box::new(FixtMessage {
+            header: Box::new(header),
+            body: msg,
+            trailer: Box::new(new_trailer()),
+        }))
+    }
+
+    /// Resend previously sent application message, see [`Sender::resend`].
+    ///
+    /// Administrative messages are returned back as error.
+    pub fn resend(&self, msg: Box<FixtMessage>, options: ResendOptions) -> Result<(), SendError> {
+        let msg = AppSender::refuse_admin(msg)?;
+        self.inner.resend(msg, options)
+    }
+}
+
+impl From<Sender> for AppSender {
+    fn from(sender: Sender) -> AppSender {
+        AppSender { inner: sender }
+    }
 }
 
 pub fn new_header(msg_type: MsgType) -> Header {
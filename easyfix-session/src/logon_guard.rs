@@ -0,0 +1,87 @@
+//! Replay protection of received Logon<A>.
+//!
+//! Logon<A> captured on the wire could be sent again by someone else,
+//! e.g. to acceptor facing the internet. When
+//! [`SessionSettings::logon_replay`](crate::settings::SessionSettings::logon_replay)
+//! is set, session rejects Logon<A> with SendingTime<52> too far from
+//! current time and, optionally, Logon<A> without nonce in RawData<96>
+//! or with nonce already seen.
+//!
+//! [`LogonGuard`] is public, so the same checks can be done by
+//! application, e.g. in [`Verifier`](crate::verifier::Verifier) shared
+//! by several sessions.
+
+use std::collections::HashMap;
+
+use easyfix_messages::fields::{Utc, UtcTimestamp};
+
+use crate::settings::LogonReplaySettings;
+
+type DateTime = chrono::DateTime<Utc>;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum StaleLogon {
+    #[error("SendingTime<52> of Logon<A> is too old")]
+    TooOld,
+    #[error("SendingTime<52> of Logon<A> is in the future")]
+    FromFuture,
+    #[error("Nonce in RawData<96> of Logon<A> is missing")]
+    MissingNonce,
+    #[error("Nonce in RawData<96> of Logon<A> was already used")]
+    ReplayedNonce,
+}
+
+/// Nonces of accepted Logon<A> messages, kept until they are too old
+/// to be accepted again.
+#[derive(Debug, Default)]
+pub struct LogonGuard {
+    nonces: HashMap<Vec<u8>, DateTime>,
+}
+
+impl LogonGuard {
+    pub fn new() -> LogonGuard {
+        LogonGuard::default()
+    }
+
+    /// Verify Logon<A> with given SendingTime<52> and RawData<96> received
+    /// at `now`, nonce of accepted Logon<A> is remembered.
+    pub fn check(
+        &mut self,
+        settings: &LogonReplaySettings,
+        sending_time: UtcTimestamp,
+        raw_data: Option<&[u8]>,
+        now: DateTime,
+    ) -> Result<(), StaleLogon> {
+        let max_age = chrono::Duration::from_std(settings.max_age).expect("duration");
+        let sending_time = sending_time.timestamp();
+        if sending_time < now - max_age {
+            return Err(StaleLogon::TooOld);
+        }
+        if sending_time > now + max_age {
+            return Err(StaleLogon::FromFuture);
+        }
+
+        self.nonces
+            .retain(|_, nonce_time| *nonce_time >= now - max_age);
+        match raw_data.filter(|nonce| !nonce.is_empty()) {
+            Some(nonce) => {
+                if self.nonces.contains_key(nonce) {
+                    return Err(StaleLogon::ReplayedNonce);
+                }
+                self.nonces.insert(nonce.to_vec(), sending_time);
+            }
+            None if settings.require_nonce => return Err(StaleLogon::MissingNonce),
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Number of remembered nonces.
+    pub fn len(&self) -> usize {
+        self.nonces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nonces.is_empty()
+    }
+}
@@ -1,11 +1,61 @@
-use std::{collections::BTreeMap, ops::RangeInclusive};
+use std::{collections::BTreeMap, io, ops::RangeInclusive};
 
 use easyfix_messages::fields::SeqNum;
 
+mod persistent;
+pub use persistent::{PersistentSeqNums, SeqNumsFile};
+
+/// Failed write of outgoing message to [`MessagesStorage`].
+#[derive(Debug, thiserror::Error)]
+pub enum StoreError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// Sender sequence number could not be persisted by
+    /// [`PersistentSeqNums`], message is never sent and session is
+    /// disconnected, regardless of
+    /// [`SessionSettings::store_failure`](crate::settings::SessionSettings::store_failure).
+    #[error("sequence number not persisted: {0}")]
+    SeqNumNotPersisted(io::Error),
+    #[error("{0}")]
+    Other(String),
+}
+
 pub trait MessagesStorage {
     fn fetch_range(&mut self, range: RangeInclusive<SeqNum>) -> Vec<Vec<u8>>;
     fn store(&mut self, seq_num: SeqNum, data: &[u8]);
 
+    /// Store outgoing message, reporting failure.
+    ///
+    /// Session stores messages with this method, storages which can fail
+    /// should implement it, failure is handled according to
+    /// [`SessionSettings::store_failure`](crate::settings::SessionSettings::store_failure).
+    /// Default implementation calls [`MessagesStorage::store`].
+    fn try_store(&mut self, seq_num: SeqNum, data: &[u8]) -> Result<(), StoreError> {
+        self.store(seq_num, data);
+        Ok(())
+    }
+
+    /// Keep raw inbound message received with too high MsgSeqNum<34>
+    /// until it can be processed, see
+    /// [`SessionSettings::queue_spill_threshold`](crate::settings::SessionSettings::queue_spill_threshold).
+    ///
+    /// Queued messages are kept apart from outgoing ones. Default
+    /// implementation fails, so messages are kept in memory.
+    fn store_queued(&mut self, seq_num: SeqNum, _data: &[u8]) -> Result<(), StoreError> {
+        Err(StoreError::Other(format!(
+            "queued message {seq_num} not stored, not supported by storage"
+        )))
+    }
+
+    /// Remove and return queued inbound message stored with
+    /// [`MessagesStorage::store_queued`].
+    fn take_queued(&mut self, _seq_num: SeqNum) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Remove all queued inbound messages.
+    fn clear_queued(&mut self) {}
+
     fn next_sender_msg_seq_num(&self) -> SeqNum;
     fn next_target_msg_seq_num(&self) -> SeqNum;
 
@@ -80,6 +130,7 @@ pub struct InMemoryStorage {
     next_sender_msg_seq_num: SeqNum,
     next_target_msg_seq_num: SeqNum,
     mem: BTreeMap<SeqNum, Vec<u8>>,
+    queued: BTreeMap<SeqNum, Vec<u8>>,
 }
 
 impl InMemoryStorage {
@@ -88,6 +139,7 @@ impl InMemoryStorage {
             next_sender_msg_seq_num: 1,
             next_target_msg_seq_num: 1,
             mem: BTreeMap::new(),
+            queued: BTreeMap::new(),
         }
     }
 }
@@ -100,14 +152,33 @@ impl Default for InMemoryStorage {
 
 impl MessagesStorage for InMemoryStorage {
     // TODO: Stream!
-    fn fetch_range(&mut self, _range: RangeInclusive<SeqNum>) -> Vec<Vec<u8>> {
-        Vec::new()
+    fn fetch_range(&mut self, range: RangeInclusive<SeqNum>) -> Vec<Vec<u8>> {
+        if range.is_empty() {
+            return Vec::new();
+        }
+        self.mem
+            .range(range)
+            .map(|(_, data)| data.clone())
+            .collect()
     }
 
     fn store(&mut self, seq_num: SeqNum, data: &[u8]) {
         self.mem.insert(seq_num, data.to_vec());
     }
 
+    fn store_queued(&mut self, seq_num: SeqNum, data: &[u8]) -> Result<(), StoreError> {
+        self.queued.insert(seq_num, data.to_vec());
+        Ok(())
+    }
+
+    fn take_queued(&mut self, seq_num: SeqNum) -> Option<Vec<u8>> {
+        self.queued.remove(&seq_num)
+    }
+
+    fn clear_queued(&mut self) {
+        self.queued.clear();
+    }
+
     fn next_sender_msg_seq_num(&self) -> SeqNum {
         self.next_sender_msg_seq_num
     }
@@ -0,0 +1,248 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    ops::RangeInclusive,
+    path::Path,
+};
+
+use easyfix_messages::fields::SeqNum;
+use tracing::error;
+
+use super::{MessagesStorage, StoreError};
+
+/// Record of sender limit and next target sequence number, both u32 LE.
+const RECORD_LEN: usize = 8;
+
+/// Storage wrapper persisting sequence numbers in a file, so sender
+/// sequence numbers never regress after unclean shutdown.
+///
+/// Sender sequence numbers are allocated write-ahead: before number is
+/// used, limit of allocated numbers is written and synced to disk, in
+/// blocks of [`PersistentSeqNums::with_block_size`] numbers. After crash,
+/// session continues from the limit, numbers allocated but not sent are
+/// gap filled when counterparty requests them. Next target sequence number
+/// is written on every change, without sync, as regressed one is only
+/// requested again.
+///
+/// Sync blocks the session thread for the time of disk flush, from tens of
+/// microseconds to milliseconds, so block size trades throughput for
+/// sequence numbers skipped after crash. With the default of
+/// [`PersistentSeqNums::DEFAULT_BLOCK_SIZE`] numbers, sync is done once per
+/// 100 sent messages and up to 99 numbers are skipped. Block size of 1
+/// never skips numbers, but syncs on every sent message, which limits
+/// session to a few hundred or thousand messages per second.
+///
+/// Sender limit is raised only after successful sync, until then every
+/// [`MessagesStorage::try_store`] of message with sequence number above
+/// the persisted limit retries the write and fails with
+/// [`StoreError::SeqNumNotPersisted`], so the message is not sent and
+/// session is disconnected. Other write failures (e.g. of target sequence
+/// number) fail the next [`MessagesStorage::try_store`] with
+/// [`StoreError::Io`], handled according to
+/// [`SessionSettings::store_failure`](crate::settings::SessionSettings::store_failure).
+pub struct PersistentSeqNums<S, F = File> {
+    inner: S,
+    file: F,
+    block_size: SeqNum,
+    /// Sender sequence numbers below the limit may have been used, the
+    /// limit is persisted
+    sender_limit: SeqNum,
+    error: Option<io::Error>,
+}
+
+/// Backing file of [`PersistentSeqNums`].
+pub trait SeqNumsFile: Read + Write + Seek {
+    /// Flush written data to durable storage.
+    fn sync(&mut self) -> io::Result<()>;
+}
+
+impl SeqNumsFile for File {
+    fn sync(&mut self) -> io::Result<()> {
+        self.sync_data()
+    }
+}
+
+impl<S: MessagesStorage> PersistentSeqNums<S> {
+    pub const DEFAULT_BLOCK_SIZE: SeqNum = 100;
+
+    /// Open (or create) file at `path`, sequence numbers of `inner` are
+    /// advanced to persisted ones.
+    pub fn open(path: impl AsRef<Path>, inner: S) -> io::Result<PersistentSeqNums<S>> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        PersistentSeqNums::with_file(file, inner)
+    }
+}
+
+impl<S: MessagesStorage, F: SeqNumsFile> PersistentSeqNums<S, F> {
+    /// Use already opened `file`, sequence numbers of `inner` are advanced
+    /// to persisted ones.
+    pub fn with_file(mut file: F, mut inner: S) -> io::Result<PersistentSeqNums<S, F>> {
+        file.seek(SeekFrom::Start(0))?;
+        let mut record = Vec::with_capacity(RECORD_LEN);
+        file.read_to_end(&mut record)?;
+        let sender_limit = match record.len() {
+            0 => inner.next_sender_msg_seq_num(),
+            RECORD_LEN => {
+                let sender_limit = SeqNum::from_le_bytes(record[..4].try_into().unwrap());
+                let next_target = SeqNum::from_le_bytes(record[4..].try_into().unwrap());
+                if sender_limit > inner.next_sender_msg_seq_num() {
+                    inner.set_next_sender_msg_seq_num(sender_limit);
+                }
+                if next_target > inner.next_target_msg_seq_num() {
+                    inner.set_next_target_msg_seq_num(next_target);
+                }
+                sender_limit.max(inner.next_sender_msg_seq_num())
+            }
+            len => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid sequence numbers record length {len}"),
+                ))
+            }
+        };
+        let mut storage = PersistentSeqNums {
+            inner,
+            file,
+            block_size: PersistentSeqNums::<S>::DEFAULT_BLOCK_SIZE,
+            sender_limit,
+            error: None,
+        };
+        storage.write(storage.sender_limit, true)?;
+        Ok(storage)
+    }
+
+    /// Allocate sender sequence numbers in blocks of `block_size`, so file
+    /// is synced once per block, at the cost of up to `block_size - 1`
+    /// numbers skipped after crash, see [`PersistentSeqNums`].
+    pub fn with_block_size(mut self, block_size: SeqNum) -> PersistentSeqNums<S, F> {
+        self.block_size = block_size.max(1);
+        self
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    fn write(&mut self, sender_limit: SeqNum, sync: bool) -> io::Result<()> {
+        let mut record = [0; RECORD_LEN];
+        record[..4].copy_from_slice(&sender_limit.to_le_bytes());
+        record[4..].copy_from_slice(&self.inner.next_target_msg_seq_num().to_le_bytes());
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.write_all(&record)?;
+        if sync {
+            self.file.sync()?;
+        }
+        Ok(())
+    }
+
+    /// Write record without sync, failure is reported by the next
+    /// `try_store()`.
+    fn persist(&mut self) {
+        if let Err(err) = self.write(self.sender_limit, false) {
+            error!("failed to persist sequence numbers: {err}");
+            self.error.get_or_insert(err);
+        }
+    }
+
+    /// Persist new sender limit, kept in memory only once it's synced.
+    /// Failure is reported by the next `try_store()`.
+    fn persist_sender_limit(&mut self, sender_limit: SeqNum) {
+        match self.write(sender_limit, true) {
+            Ok(()) => self.sender_limit = sender_limit,
+            Err(err) => {
+                error!("failed to persist sequence numbers: {err}");
+                self.error.get_or_insert(err);
+            }
+        }
+    }
+
+    /// Make sure `seq_num` is below persisted sender limit, allocating
+    /// next block if it's not.
+    fn allocate(&mut self, seq_num: SeqNum) -> io::Result<()> {
+        if seq_num < self.sender_limit {
+            return Ok(());
+        }
+        let sender_limit = seq_num + self.block_size;
+        self.write(sender_limit, true)?;
+        self.sender_limit = sender_limit;
+        Ok(())
+    }
+}
+
+impl<S: MessagesStorage, F: SeqNumsFile> MessagesStorage for PersistentSeqNums<S, F> {
+    fn fetch_range(&mut self, range: RangeInclusive<SeqNum>) -> Vec<Vec<u8>> {
+        self.inner.fetch_range(range)
+    }
+
+    fn store(&mut self, seq_num: SeqNum, data: &[u8]) {
+        self.inner.store(seq_num, data)
+    }
+
+    fn try_store(&mut self, seq_num: SeqNum, data: &[u8]) -> Result<(), StoreError> {
+        // Retry allocation which failed before, message with number above
+        // persisted limit must not be sent
+        if let Err(err) = self.allocate(seq_num) {
+            error!("failed to persist sequence numbers: {err}");
+            return Err(StoreError::SeqNumNotPersisted(err));
+        }
+        if let Some(err) = self.error.take() {
+            return Err(err.into());
+        }
+        self.inner.try_store(seq_num, data)
+    }
+
+    fn store_queued(&mut self, seq_num: SeqNum, data: &[u8]) -> Result<(), StoreError> {
+        self.inner.store_queued(seq_num, data)
+    }
+
+    fn take_queued(&mut self, seq_num: SeqNum) -> Option<Vec<u8>> {
+        self.inner.take_queued(seq_num)
+    }
+
+    fn clear_queued(&mut self) {
+        self.inner.clear_queued()
+    }
+
+    fn next_sender_msg_seq_num(&self) -> SeqNum {
+        self.inner.next_sender_msg_seq_num()
+    }
+
+    fn next_target_msg_seq_num(&self) -> SeqNum {
+        self.inner.next_target_msg_seq_num()
+    }
+
+    fn set_next_sender_msg_seq_num(&mut self, seq_num: SeqNum) {
+        self.inner.set_next_sender_msg_seq_num(seq_num);
+        self.persist_sender_limit(seq_num);
+    }
+
+    fn set_next_target_msg_seq_num(&mut self, seq_num: SeqNum) {
+        self.inner.set_next_target_msg_seq_num(seq_num);
+        self.persist();
+    }
+
+    fn incr_next_sender_msg_seq_num(&mut self) {
+        // Number being used is the current one, it's allocated before
+        // increment returns. Failure is reported by `try_store()` of
+        // message using the number, which retries the allocation.
+        if let Err(err) = self.allocate(self.inner.next_sender_msg_seq_num()) {
+            error!("failed to persist sequence numbers: {err}");
+        }
+        self.inner.incr_next_sender_msg_seq_num();
+    }
+
+    fn incr_next_target_msg_seq_num(&mut self) {
+        self.inner.incr_next_target_msg_seq_num();
+        self.persist();
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.persist_sender_limit(self.inner.next_sender_msg_seq_num());
+    }
+}
@@ -0,0 +1,722 @@
+//! QuickFIX configuration compatibility, to ease migration of existing
+//! deployments.
+//!
+//! [`QuickFixConfig::parse`] reads QuickFIX settings file (`[DEFAULT]`
+//! section followed by `[SESSION]` sections, `Key=Value` lines, `#`
+//! comments) and translates every session into [`Settings`] and
+//! [`SessionSettings`]. Values not set in the file are taken from QuickFIX
+//! defaults, see [`quickfix_settings`] and [`quickfix_session_settings`].
+//!
+//! | QuickFIX key | easyfix setting |
+//! |---|---|
+//! | `BeginString`, `SenderCompID`, `TargetCompID`, `SessionQualifier` | [`SessionSettings::session_id`] |
+//! | `SenderSubID` | [`Settings::sender_sub_id`] |
+//! | `ConnectionType` | [`QuickFixSession::connection_type`] |
+//! | `SocketAcceptAddress`, `SocketAcceptPort` | [`QuickFixSession::socket_accept`] |
+//! | `SocketConnectHost`, `SocketConnectPort` | [`QuickFixSession::socket_connect`] |
+//! | `StartTime`, `EndTime` | [`SessionSettings::session_time`] |
+//! | `LogonTime`, `LogoutTime` | [`SessionSettings::logon_time`], session time when not set |
+//! | `StartDay`, `EndDay`, `Weekdays` | [`SessionSettings::calendar`], see below |
+//! | `HeartBtInt` | [`Settings::heartbeat_interval`] |
+//! | `LogonTimeout` | [`Settings::auto_disconnect_after_no_logon_received`] |
+//! | `ResetOnLogon`, `ResetOnLogout`, `ResetOnDisconnect` | [`SessionSettings::reset_on_logon`], [`SessionSettings::reset_on_logout`], [`SessionSettings::reset_on_disconnect`] |
+//! | `RefreshOnLogon` | [`SessionSettings::refresh_on_logon`] |
+//! | `SendRedundantResendRequests` | [`SessionSettings::send_redundant_resend_requests`] |
+//! | `ResendRequestChunkSize` | [`ResendRequestPolicy::max_range`] |
+//! | `CheckCompID` | [`SessionSettings::check_comp_id`] |
+//! | `CheckLatency`, `MaxLatency` | [`SessionSettings::check_latency`], [`SessionSettings::max_latency`] |
+//! | `DefaultApplVerID` | [`SessionSettings::sender_default_appl_ver_id`], [`SessionSettings::target_default_appl_ver_id`] |
+//! | `EnableNextExpectedMsgSeqNum` | [`SessionSettings::enable_next_expected_msg_seq_num`] |
+//! | `PersistMessages` | [`SessionSettings::persist`] |
+//! | `FileLogPath` | [`Settings::session_log`] |
+//! | `FileStorePath` | [`QuickFixSession::file_store_path`] |
+//! | `DataDictionary`, `TransportDataDictionary`, `AppDataDictionary` | [`QuickFixSession::data_dictionaries`] |
+//!
+//! Differences to keep in mind:
+//! - Messages are generated from the dictionary at build time, so data
+//!   dictionary paths are only reported, e.g. to be passed to the code
+//!   generator, and messages storage is created by the application, using
+//!   the reported store path if needed.
+//! - QuickFIX weekly session (`StartDay`/`EndDay`) is approximated with
+//!   daily session active on days from `StartDay` to `EndDay`.
+//! - Times are in UTC, `TimeZone` other than UTC and `UseLocalTime=Y`
+//!   are rejected.
+//! - Keys not listed above are ignored and reported in
+//!   [`QuickFixSession::ignored`].
+//!
+//! Sessions persisted by QuickFIX `FileStore` can be cut over without
+//! sequence reset, [`QuickFixFileStore::read`] reads sequence numbers and
+//! sent messages of session from `FileStorePath` directory and
+//! [`QuickFixFileStore::import_into`] copies them to easyfix storage.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs, io,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+use chrono::{NaiveDateTime, NaiveTime, Weekday};
+use easyfix_messages::fields::{FixString, SeqNum};
+use tokio::time::Duration;
+
+use crate::{
+    calendar::{SessionCalendar, WeekdayCalendar},
+    messages_storage::{MessagesStorage, StoreError},
+    session_id::SessionId,
+    settings::{
+        MsgSeqNumTooLowAction, ResendRequestEndSeqNo, ResendRequestPolicy, SessionLogSettings,
+        SessionSettings, Settings,
+    },
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum QuickFixConfigError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("line {line}: {reason}")]
+    Syntax { line: usize, reason: &'static str },
+    #[error("session {session}: {key} not set")]
+    MissingKey { session: usize, key: &'static str },
+    #[error("session {session}: invalid {key} value `{value}`")]
+    InvalidValue {
+        session: usize,
+        key: &'static str,
+        value: String,
+    },
+    #[error("session {session}: {key}={value} is not supported")]
+    Unsupported {
+        session: usize,
+        key: &'static str,
+        value: String,
+    },
+}
+
+/// Role of session, QuickFIX `ConnectionType`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConnectionType {
+    Acceptor,
+    Initiator,
+}
+
+/// Session translated from QuickFIX configuration.
+#[derive(Clone, Debug)]
+pub struct QuickFixSession {
+    pub connection_type: ConnectionType,
+    pub settings: Settings,
+    pub session_settings: SessionSettings,
+    /// Address and port acceptor listens on.
+    pub socket_accept: Option<(String, u16)>,
+    /// Host and port initiator connects to.
+    pub socket_connect: Option<(String, u16)>,
+    /// Directory of QuickFIX file store.
+    pub file_store_path: Option<PathBuf>,
+    /// Data dictionaries the session was configured with.
+    pub data_dictionaries: Vec<PathBuf>,
+    /// Keys not translated to easyfix settings.
+    pub ignored: Vec<String>,
+}
+
+/// Sessions translated from QuickFIX configuration file.
+#[derive(Clone, Debug)]
+pub struct QuickFixConfig {
+    pub sessions: Vec<QuickFixSession>,
+}
+
+/// Keys translated to easyfix settings or reported in [`QuickFixSession`].
+const KNOWN_KEYS: &[&str] = &[
+    "AppDataDictionary",
+    "BeginString",
+    "CheckCompID",
+    "CheckLatency",
+    "ConnectionType",
+    "DataDictionary",
+    "DefaultApplVerID",
+    "EnableNextExpectedMsgSeqNum",
+    "EndDay",
+    "EndTime",
+    "FileLogPath",
+    "FileStorePath",
+    "HeartBtInt",
+    "LogonTime",
+    "LogonTimeout",
+    "LogoutTime",
+    "MaxLatency",
+    "PersistMessages",
+    "RefreshOnLogon",
+    "ResendRequestChunkSize",
+    "ResetOnDisconnect",
+    "ResetOnLogon",
+    "ResetOnLogout",
+    "SendRedundantResendRequests",
+    "SenderCompID",
+    "SenderSubID",
+    "SessionQualifier",
+    "SocketAcceptAddress",
+    "SocketAcceptPort",
+    "SocketConnectHost",
+    "SocketConnectPort",
+    "StartDay",
+    "StartTime",
+    "TargetCompID",
+    "TimeZone",
+    "TransportDataDictionary",
+    "UseLocalTime",
+    "Weekdays",
+];
+
+/// Global settings with QuickFIX defaults: 10 s `LogonTimeout` and
+/// disconnection when no message is received after TestRequest<1>.
+pub fn quickfix_settings(sender_comp_id: FixString, heartbeat_interval: Duration) -> Settings {
+    Settings {
+        sender_comp_id,
+        sender_sub_id: None,
+        heartbeat_interval,
+        auto_disconnect_after_no_logon_received: Duration::from_secs(10),
+        max_bytes_before_logon: None,
+        pre_logon: Default::default(),
+        auto_disconnect_after_no_heartbeat: 1,
+        buffer_pool: None,
+        duplicate_tags: Default::default(),
+        other_msg_types: false,
+        required_tags: Default::default(),
+        session_log: None,
+        tcp_keepalive: None,
+        read_idle: None,
+        buffers: Default::default(),
+        group_limits: Default::default(),
+        framing_errors: Default::default(),
+        capture_dir: None,
+    }
+}
+
+/// Session settings with QuickFIX defaults.
+///
+/// Unlike easyfix defaults, messages are persisted, Logout<5> sent on too
+/// low MsgSeqNum<34> has QuickFIX text and ResendRequest<2> asks for all
+/// following messages (EndSeqNo<16> is 0).
+pub fn quickfix_session_settings(session_id: SessionId) -> SessionSettings {
+    SessionSettings {
+        enable_next_expected_msg_seq_num: false,
+        persist: true,
+        msg_seq_num_too_low: MsgSeqNumTooLowAction::Logout {
+            text: Some("MsgSeqNum too low, expecting {expected} but received {received}".into()),
+            disconnect: true,
+        },
+        resend_request: ResendRequestPolicy {
+            end_seq_no: ResendRequestEndSeqNo::Infinity,
+            max_range: None,
+        },
+        ..SessionSettings::new(session_id)
+    }
+}
+
+/// Settings of one session, `[SESSION]` values override `[DEFAULT]` ones.
+struct Section<'a> {
+    session: usize,
+    defaults: &'a HashMap<String, String>,
+    values: HashMap<String, String>,
+}
+
+impl Section<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.values
+            .get(key)
+            .or_else(|| self.defaults.get(key))
+            .map(String::as_str)
+    }
+
+    fn required(&self, key: &'static str) -> Result<&str, QuickFixConfigError> {
+        self.get(key).ok_or(QuickFixConfigError::MissingKey {
+            session: self.session,
+            key,
+        })
+    }
+
+    fn invalid(&self, key: &'static str, value: &str) -> QuickFixConfigError {
+        QuickFixConfigError::InvalidValue {
+            session: self.session,
+            key,
+            value: value.to_owned(),
+        }
+    }
+
+    fn parse<T>(
+        &self,
+        key: &'static str,
+        parse: impl FnOnce(&str) -> Option<T>,
+    ) -> Result<Option<T>, QuickFixConfigError> {
+        self.get(key)
+            .map(|value| parse(value).ok_or_else(|| self.invalid(key, value)))
+            .transpose()
+    }
+
+    fn fix_string(&self, key: &'static str) -> Result<Option<FixString>, QuickFixConfigError> {
+        self.parse(key, |value| {
+            FixString::from_ascii(value.as_bytes().to_vec()).ok()
+        })
+    }
+
+    fn bool(&self, key: &'static str, default: bool) -> Result<bool, QuickFixConfigError> {
+        self.parse(key, |value| match value {
+            "Y" => Some(true),
+            "N" => Some(false),
+            _ => None,
+        })
+        .map(|value| value.unwrap_or(default))
+    }
+
+    fn seconds(&self, key: &'static str) -> Result<Option<Duration>, QuickFixConfigError> {
+        self.parse(key, |value| value.parse().ok().map(Duration::from_secs))
+    }
+
+    fn time(&self, key: &'static str) -> Result<Option<NaiveTime>, QuickFixConfigError> {
+        self.parse(key, |value| {
+            NaiveTime::parse_from_str(value, "%H:%M:%S").ok()
+        })
+    }
+
+    fn time_range(
+        &self,
+        start_key: &'static str,
+        end_key: &'static str,
+    ) -> Result<Option<RangeInclusive<NaiveTime>>, QuickFixConfigError> {
+        match (self.time(start_key)?, self.time(end_key)?) {
+            (Some(start), Some(end)) => Ok(Some(start..=end)),
+            (None, None) => Ok(None),
+            (Some(_), None) => Err(QuickFixConfigError::MissingKey {
+                session: self.session,
+                key: end_key,
+            }),
+            (None, Some(_)) => Err(QuickFixConfigError::MissingKey {
+                session: self.session,
+                key: start_key,
+            }),
+        }
+    }
+
+    fn weekday(&self, key: &'static str) -> Result<Option<Weekday>, QuickFixConfigError> {
+        self.parse(key, parse_weekday)
+    }
+
+    fn calendar(&self) -> Result<Option<SessionCalendar>, QuickFixConfigError> {
+        if let Some(weekdays) = self.get("Weekdays") {
+            let weekdays = weekdays
+                .split(',')
+                .map(|day| parse_weekday(day.trim()))
+                .collect::<Option<_>>()
+                .ok_or_else(|| self.invalid("Weekdays", weekdays))?;
+            return Ok(Some(SessionCalendar::new(WeekdayCalendar {
+                weekdays,
+                ..Default::default()
+            })));
+        }
+        match (self.weekday("StartDay")?, self.weekday("EndDay")?) {
+            (Some(start), Some(end)) => {
+                let mut weekdays = vec![start];
+                let mut day = start;
+                while day != end {
+                    day = day.succ();
+                    weekdays.push(day);
+                }
+                Ok(Some(SessionCalendar::new(WeekdayCalendar {
+                    weekdays: weekdays.into_iter().collect(),
+                    ..Default::default()
+                })))
+            }
+            (None, None) => Ok(None),
+            (Some(_), None) => Err(QuickFixConfigError::MissingKey {
+                session: self.session,
+                key: "EndDay",
+            }),
+            (None, Some(_)) => Err(QuickFixConfigError::MissingKey {
+                session: self.session,
+                key: "StartDay",
+            }),
+        }
+    }
+
+    fn host_port(
+        &self,
+        host_key: &'static str,
+        default_host: Option<&str>,
+        port_key: &'static str,
+    ) -> Result<Option<(String, u16)>, QuickFixConfigError> {
+        let Some(port) = self.parse(port_key, |value| value.parse().ok())? else {
+            return Ok(None);
+        };
+        let host = match (self.get(host_key), default_host) {
+            (Some(host), _) | (None, Some(host)) => host.to_owned(),
+            (None, None) => {
+                return Err(QuickFixConfigError::MissingKey {
+                    session: self.session,
+                    key: host_key,
+                })
+            }
+        };
+        Ok(Some((host, port)))
+    }
+
+    fn unsupported_unless(
+        &self,
+        key: &'static str,
+        supported: &[&str],
+    ) -> Result<(), QuickFixConfigError> {
+        match self.get(key) {
+            Some(value) if !supported.contains(&value) => Err(QuickFixConfigError::Unsupported {
+                session: self.session,
+                key,
+                value: value.to_owned(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn into_session(self) -> Result<QuickFixSession, QuickFixConfigError> {
+        self.unsupported_unless("TimeZone", &["UTC"])?;
+        self.unsupported_unless("UseLocalTime", &["N"])?;
+
+        let connection_type = match self.required("ConnectionType")? {
+            "acceptor" => ConnectionType::Acceptor,
+            "initiator" => ConnectionType::Initiator,
+            value => return Err(self.invalid("ConnectionType", value)),
+        };
+        let begin_string = self.required("BeginString")?;
+        let begin_string = FixString::from_ascii(begin_string.as_bytes().to_vec())
+            .map_err(|_| self.invalid("BeginString", begin_string))?;
+        let missing = |key| QuickFixConfigError::MissingKey {
+            session: self.session,
+            key,
+        };
+        let sender_comp_id = self
+            .fix_string("SenderCompID")?
+            .ok_or_else(|| missing("SenderCompID"))?;
+        let target_comp_id = self
+            .fix_string("TargetCompID")?
+            .ok_or_else(|| missing("TargetCompID"))?;
+        let session_id = SessionId::with_session_qualifier(
+            begin_string,
+            sender_comp_id.clone(),
+            target_comp_id,
+            self.get("SessionQualifier").unwrap_or_default().to_owned(),
+        );
+
+        let heartbeat_interval = match self.seconds("HeartBtInt")? {
+            Some(heartbeat_interval) => heartbeat_interval,
+            // Initiator sends HeartBtInt<108>, acceptor takes it from Logon<A>
+            None if connection_type == ConnectionType::Acceptor => Duration::from_secs(30),
+            None => return Err(missing("HeartBtInt")),
+        };
+        let mut settings = quickfix_settings(sender_comp_id, heartbeat_interval);
+        settings.sender_sub_id = self.fix_string("SenderSubID")?;
+        if let Some(logon_timeout) = self.seconds("LogonTimeout")? {
+            settings.auto_disconnect_after_no_logon_received = logon_timeout;
+        }
+        settings.session_log = self.get("FileLogPath").map(SessionLogSettings::new);
+
+        let mut session_settings = quickfix_session_settings(session_id);
+        if let Some(session_time) = self.time_range("StartTime", "EndTime")? {
+            session_settings.session_time = session_time.clone();
+            session_settings.logon_time = session_time;
+        } else {
+            return Err(missing("StartTime"));
+        }
+        if let Some(logon_time) = self.time_range("LogonTime", "LogoutTime")? {
+            session_settings.logon_time = logon_time;
+        }
+        if let Some(calendar) = self.calendar()? {
+            session_settings.calendar = calendar;
+        }
+        session_settings.reset_on_logon = self.bool("ResetOnLogon", false)?;
+        session_settings.reset_on_logout = self.bool("ResetOnLogout", false)?;
+        session_settings.reset_on_disconnect = self.bool("ResetOnDisconnect", false)?;
+        session_settings.refresh_on_logon = self.bool("RefreshOnLogon", false)?;
+        session_settings.send_redundant_resend_requests =
+            self.bool("SendRedundantResendRequests", false)?;
+        session_settings.resend_request.max_range = self
+            .parse("ResendRequestChunkSize", |value| {
+                value.parse::<SeqNum>().ok()
+            })?
+            .filter(|chunk_size| *chunk_size > 0);
+        session_settings.check_comp_id = self.bool("CheckCompID", true)?;
+        session_settings.check_latency = self.bool("CheckLatency", true)?;
+        if let Some(max_latency) = self.seconds("MaxLatency")? {
+            session_settings.max_latency = max_latency;
+        }
+        if let Some(appl_ver_id) = self.parse("DefaultApplVerID", parse_appl_ver_id)? {
+            session_settings.sender_default_appl_ver_id = appl_ver_id.clone();
+            session_settings.target_default_appl_ver_id = appl_ver_id;
+        }
+        session_settings.enable_next_expected_msg_seq_num =
+            self.bool("EnableNextExpectedMsgSeqNum", false)?;
+        session_settings.persist = self.bool("PersistMessages", true)?;
+
+        let socket_accept =
+            self.host_port("SocketAcceptAddress", Some("0.0.0.0"), "SocketAcceptPort")?;
+        let socket_connect = self.host_port("SocketConnectHost", None, "SocketConnectPort")?;
+        let data_dictionaries = [
+            "DataDictionary",
+            "TransportDataDictionary",
+            "AppDataDictionary",
+        ]
+        .iter()
+        .filter_map(|key| self.get(key))
+        .map(PathBuf::from)
+        .collect();
+        let mut ignored: Vec<_> = self
+            .defaults
+            .keys()
+            .chain(self.values.keys())
+            .filter(|key| !KNOWN_KEYS.contains(&key.as_str()))
+            .cloned()
+            .collect();
+        ignored.sort();
+        ignored.dedup();
+
+        Ok(QuickFixSession {
+            connection_type,
+            settings,
+            session_settings,
+            socket_accept,
+            socket_connect,
+            file_store_path: self.get("FileStorePath").map(PathBuf::from),
+            data_dictionaries,
+            ignored,
+        })
+    }
+}
+
+fn parse_weekday(day: &str) -> Option<Weekday> {
+    day.parse().ok().or_else(|| {
+        // Two letter abbreviations accepted by QuickFIX
+        match day.to_ascii_lowercase().as_str() {
+            "mo" => Some(Weekday::Mon),
+            "tu" => Some(Weekday::Tue),
+            "we" => Some(Weekday::Wed),
+            "th" => Some(Weekday::Thu),
+            "fr" => Some(Weekday::Fri),
+            "sa" => Some(Weekday::Sat),
+            "su" => Some(Weekday::Sun),
+            _ => None,
+        }
+    })
+}
+
+/// ApplVerID<1128> value, given directly or as FIX version name.
+fn parse_appl_ver_id(value: &str) -> Option<FixString> {
+    let appl_ver_id = match value {
+        "FIX.2.7" => "0",
+        "FIX.3.0" => "1",
+        "FIX.4.0" => "2",
+        "FIX.4.1" => "3",
+        "FIX.4.2" => "4",
+        "FIX.4.3" => "5",
+        "FIX.4.4" => "6",
+        "FIX.5.0" => "7",
+        "FIX.5.0SP1" => "8",
+        "FIX.5.0SP2" => "9",
+        value => value,
+    };
+    FixString::from_ascii(appl_ver_id.as_bytes().to_vec()).ok()
+}
+
+impl QuickFixConfig {
+    /// Parse QuickFIX settings file content.
+    pub fn parse(config: &str) -> Result<QuickFixConfig, QuickFixConfigError> {
+        let mut defaults = HashMap::new();
+        let mut sessions: Vec<HashMap<String, String>> = Vec::new();
+        // `None` before the first section
+        let mut current: Option<&mut HashMap<String, String>> = None;
+        for (idx, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let syntax = |reason| QuickFixConfigError::Syntax {
+                line: idx + 1,
+                reason,
+            };
+            if let Some(section) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+                current = match section.trim().to_ascii_uppercase().as_str() {
+                    "DEFAULT" => Some(&mut defaults),
+                    "SESSION" => {
+                        sessions.push(HashMap::new());
+                        sessions.last_mut()
+                    }
+                    _ => return Err(syntax("unknown section")),
+                };
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or_else(|| syntax("missing `=`"))?;
+            current
+                .as_mut()
+                .ok_or_else(|| syntax("setting outside of section"))?
+                .insert(key.trim().to_owned(), value.trim().to_owned());
+        }
+
+        let sessions = sessions
+            .into_iter()
+            .enumerate()
+            .map(|(idx, values)| {
+                Section {
+                    session: idx + 1,
+                    defaults: &defaults,
+                    values,
+                }
+                .into_session()
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(QuickFixConfig { sessions })
+    }
+
+    /// Read and parse QuickFIX settings file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<QuickFixConfig, QuickFixConfigError> {
+        QuickFixConfig::parse(&fs::read_to_string(path)?)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum QuickFixStoreError {
+    #[error("{}: {err}", .path.display())]
+    Io { path: PathBuf, err: io::Error },
+    #[error("{}: {reason}", .path.display())]
+    Invalid { path: PathBuf, reason: String },
+}
+
+/// Content of QuickFIX `FileStore` of one session.
+#[derive(Clone, Debug)]
+pub struct QuickFixFileStore {
+    pub next_sender_msg_seq_num: SeqNum,
+    pub next_target_msg_seq_num: SeqNum,
+    /// Sent messages, as serialized by QuickFIX.
+    pub messages: BTreeMap<SeqNum, Vec<u8>>,
+    /// Session creation time (UTC), from `.session` file if present.
+    pub creation_time: Option<NaiveDateTime>,
+}
+
+impl QuickFixFileStore {
+    /// Read files of `session_id` from QuickFIX `FileStorePath` directory:
+    /// - `.seqnums` - next sender and target sequence numbers,
+    /// - `.header` - MsgSeqNum<34>, offset and size of every message,
+    /// - `.body` - sent messages,
+    /// - `.session` - session creation time.
+    ///
+    /// Files are named after session, e.g. `FIX.4.4-SENDER-TARGET.seqnums`
+    /// (session qualifier is appended after another `-`). Only `.seqnums`
+    /// is required, when `.header` or `.body` is missing there are no
+    /// messages to import.
+    pub fn read(
+        dir: impl AsRef<Path>,
+        session_id: &SessionId,
+    ) -> Result<QuickFixFileStore, QuickFixStoreError> {
+        let mut prefix = format!(
+            "{}-{}-{}",
+            session_id.begin_string(),
+            session_id.sender_comp_id(),
+            session_id.target_comp_id()
+        );
+        if !session_id.session_qualifier().is_empty() {
+            prefix.push('-');
+            prefix.push_str(session_id.session_qualifier());
+        }
+        let path = |extension: &str| dir.as_ref().join(format!("{prefix}.{extension}"));
+
+        let invalid = |path: &Path, reason: String| QuickFixStoreError::Invalid {
+            path: path.to_owned(),
+            reason,
+        };
+
+        let seq_nums_path = path("seqnums");
+        let seq_nums = read_optional(&seq_nums_path)?.ok_or_else(|| QuickFixStoreError::Io {
+            path: seq_nums_path.clone(),
+            err: io::ErrorKind::NotFound.into(),
+        })?;
+        let seq_nums = String::from_utf8_lossy(&seq_nums);
+        // `%10.10d : %10.10d`
+        let (next_sender_msg_seq_num, next_target_msg_seq_num) = seq_nums
+            .split_once(':')
+            .and_then(|(sender, target)| {
+                Some((sender.trim().parse().ok()?, target.trim().parse().ok()?))
+            })
+            .ok_or_else(|| {
+                invalid(
+                    &seq_nums_path,
+                    format!("invalid sequence numbers `{}`", seq_nums.trim()),
+                )
+            })?;
+
+        let header_path = path("header");
+        let body_path = path("body");
+        let mut messages = BTreeMap::new();
+        if let (Some(header), Some(body)) =
+            (read_optional(&header_path)?, read_optional(&body_path)?)
+        {
+            // `seq_num,offset,size` entries separated with space, message
+            // stored again is recorded with new entry
+            for entry in String::from_utf8_lossy(&header).split_whitespace() {
+                let (seq_num, offset, size) = parse_header_entry(entry)
+                    .ok_or_else(|| invalid(&header_path, format!("invalid entry `{entry}`")))?;
+                let data = offset
+                    .checked_add(size)
+                    .and_then(|end| body.get(offset..end))
+                    .ok_or_else(|| {
+                        invalid(&body_path, format!("message {seq_num} out of file bounds"))
+                    })?;
+                messages.insert(seq_num, data.to_vec());
+            }
+        }
+
+        let session_path = path("session");
+        let creation_time = read_optional(&session_path)?
+            .map(|content| String::from_utf8_lossy(&content).trim().to_owned())
+            .filter(|content| !content.is_empty())
+            .map(|content| {
+                NaiveDateTime::parse_from_str(&content, "%Y%m%d-%H:%M:%S%.f").map_err(|_| {
+                    invalid(&session_path, format!("invalid creation time `{content}`"))
+                })
+            })
+            .transpose()?;
+
+        Ok(QuickFixFileStore {
+            next_sender_msg_seq_num,
+            next_target_msg_seq_num,
+            messages,
+            creation_time,
+        })
+    }
+
+    /// Store messages in `storage` and set its sequence numbers, so session
+    /// continues where QuickFIX stopped. Call it before session is started,
+    /// e.g. when storage is created.
+    pub fn import_into<S: MessagesStorage + ?Sized>(
+        &self,
+        storage: &mut S,
+    ) -> Result<(), StoreError> {
+        for (seq_num, data) in &self.messages {
+            storage.try_store(*seq_num, data)?;
+        }
+        storage.set_next_sender_msg_seq_num(self.next_sender_msg_seq_num);
+        storage.set_next_target_msg_seq_num(self.next_target_msg_seq_num);
+        Ok(())
+    }
+}
+
+/// Content of file, `None` if it doesn't exist.
+fn read_optional(path: &Path) -> Result<Option<Vec<u8>>, QuickFixStoreError> {
+    match fs::read(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(QuickFixStoreError::Io {
+            path: path.to_owned(),
+            err,
+        }),
+    }
+}
+
+fn parse_header_entry(entry: &str) -> Option<(SeqNum, usize, usize)> {
+    let mut parts = entry.split(',');
+    let seq_num = parts.next()?.parse().ok()?;
+    let offset = parts.next()?.parse().ok()?;
+    let size = parts.next()?.parse().ok()?;
+    parts.next().is_none().then_some((seq_num, offset, size))
+}
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use easyfix_messages::fields::{FixString, Int, SessionRejectReason};
+use tracing::error;
+
+use crate::settings::RejectReasonMapping;
+
+#[derive(Debug)]
+struct Mapping {
+    reason: Option<SessionRejectReason>,
+    text: Option<String>,
+}
+
+/// Venue specific SessionRejectReason<373> and Text<58> of sent Reject<3>,
+/// see [`RejectReasonMapping`].
+#[derive(Debug, Default)]
+pub(crate) struct RejectReasons {
+    mappings: HashMap<SessionRejectReason, Mapping>,
+}
+
+impl RejectReasons {
+    pub(crate) fn new(mappings: &[RejectReasonMapping]) -> RejectReasons {
+        let parse = |value: &FixString| {
+            SessionRejectReason::from_fix_str(value).ok_or_else(|| {
+                error!("invalid reject reason mapping, unknown SessionRejectReason `{value}`")
+            })
+        };
+        RejectReasons {
+            mappings: mappings
+                .iter()
+                .filter_map(|mapping| {
+                    let reason = parse(&mapping.reason).ok()?;
+                    let mapped = match &mapping.sent_reason {
+                        Some(sent_reason) => Some(parse(sent_reason).ok()?),
+                        None => None,
+                    };
+                    Some((
+                        reason,
+                        Mapping {
+                            reason: mapped,
+                            text: mapping.text.clone(),
+                        },
+                    ))
+                })
+                .collect(),
+        }
+    }
+
+    /// SessionRejectReason<373> and Text<58> sent instead of detected
+    /// `reason` and `text`.
+    pub(crate) fn apply(
+        &self,
+        reason: SessionRejectReason,
+        text: FixString,
+        ref_tag_id: Option<Int>,
+    ) -> (SessionRejectReason, FixString) {
+        let Some(mapping) = self.mappings.get(&reason) else {
+            return (reason, text);
+        };
+        let text = match &mapping.text {
+            Some(template) => {
+                let tag = ref_tag_id.map(|tag| tag.to_string()).unwrap_or_default();
+                let mapped = template
+                    .replace("{tag}", &tag)
+                    .replace("{text}", text.as_ref());
+                FixString::from_ascii_lossy(mapped.into_bytes())
+            }
+            None => text,
+        };
+        (mapping.reason.unwrap_or(reason), text)
+    }
+}
@@ -0,0 +1,235 @@
+//! Replay of recorded message logs against an acceptor session.
+//!
+//! Logs in QuickFIX `*.messages.log` format are supported: one message
+//! per line, optionally prefixed with `<timestamp> : `, fields separated
+//! with SOH or `|`.
+//!
+//! Inbound messages (the ones not sent by the local SenderCompID<49>) are fed
+//! to the session in recorded order, with SendingTime<52> set to the current
+//! time. After each inbound message, messages sent by the session are compared
+//! with outbound messages recorded up to the next inbound one. Field order
+//! and volatile fields (see [`Replay::ignore_tag`]) are not compared.
+//!
+//! Available with `test-util` feature, meant for tests of applications
+//! built on the sessions.
+
+use std::{net::SocketAddr, time::Duration};
+
+use easyfix_messages::{
+    deserializer::{raw_message, DeserializeError},
+    fields::{FixStr, TagNum, Utc, UtcTimestamp},
+    messages::FixtMessage,
+};
+use futures::StreamExt;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt, DuplexStream},
+    time::timeout,
+};
+
+pub use crate::journal::Direction;
+use crate::{
+    acceptor::Acceptor,
+    messages_storage::InMemoryStorage,
+    settings::{SessionSettings, Settings},
+};
+
+const PIPE_SIZE: usize = 64 * 1024;
+
+#[derive(Debug)]
+pub struct LogEntry {
+    /// Line number in the log, starting from 1.
+    pub line: usize,
+    pub direction: Direction,
+    pub msg: Box<FixtMessage>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    #[error("line {line}: {error}")]
+    Deserialize {
+        line: usize,
+        error: DeserializeError,
+    },
+    #[error("line {line}: outbound message recorded before first inbound one")]
+    UnexpectedOutbound { line: usize },
+    #[error("response to line {line}: expected {expected}, got {actual}")]
+    Mismatch {
+        line: usize,
+        expected: String,
+        actual: String,
+    },
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Parse log, messages sent by `local_comp_id` are marked as outbound.
+pub fn parse_log(log: &str, local_comp_id: &FixStr) -> Result<Vec<LogEntry>, ReplayError> {
+    let mut entries = Vec::new();
+    for (idx, line) in log.lines().enumerate() {
+        let Some(start) = line.find("8=FIX") else {
+            continue;
+        };
+        let bytes: Vec<u8> = line[start..]
+            .trim_end()
+            .bytes()
+            .map(|b| if b == b'|' { b'\x01' } else { b })
+            .collect();
+        let msg = FixtMessage::from_bytes(&bytes).map_err(|error| ReplayError::Deserialize {
+            line: idx + 1,
+            error,
+        })?;
+        let direction = if msg.header.sender_comp_id == *local_comp_id {
+            Direction::Outbound
+        } else {
+            Direction::Inbound
+        };
+        entries.push(LogEntry {
+            line: idx + 1,
+            direction,
+            msg,
+        });
+    }
+    Ok(entries)
+}
+
+/// Replays log entries against a fresh acceptor session.
+pub struct Replay {
+    settings: Settings,
+    session_settings: SessionSettings,
+    ignored_tags: Vec<TagNum>,
+    response_timeout: Duration,
+}
+
+impl Replay {
+    /// By default BodyLength<9>, CheckSum<10>, SendingTime<52>, Text<58>
+    /// and OrigSendingTime<122> are not compared.
+    pub fn new(settings: Settings, session_settings: SessionSettings) -> Replay {
+        Replay {
+            settings,
+            session_settings,
+            ignored_tags: vec![9, 10, 52, 58, 122],
+            response_timeout: Duration::from_millis(100),
+        }
+    }
+
+    /// Do not compare value of `tag`.
+    pub fn ignore_tag(mut self, tag: TagNum) -> Replay {
+        self.ignored_tags.push(tag);
+        self
+    }
+
+    /// How long to wait for more responses after each inbound message.
+    pub fn response_timeout(mut self, response_timeout: Duration) -> Replay {
+        self.response_timeout = response_timeout;
+        self
+    }
+
+    /// Run the replay, has to be called within `tokio::task::LocalSet`.
+    pub async fn run(&self, entries: &[LogEntry]) -> Result<(), ReplayError> {
+        if let Some(entry) = entries
+            .first()
+            .filter(|entry| entry.direction == Direction::Outbound)
+        {
+            return Err(ReplayError::UnexpectedOutbound { line: entry.line });
+        }
+
+        let mut acceptor =
+            Acceptor::new(self.settings.clone(), Box::new(|_| InMemoryStorage::new()));
+        acceptor.register_session(
+            self.session_settings.session_id.clone(),
+            self.session_settings.clone(),
+        );
+        let (local, mut remote) = tokio::io::duplex(PIPE_SIZE);
+        let (reader, writer) = tokio::io::split(local);
+        let session = tokio::task::spawn_local(acceptor.run_session_task(
+            SocketAddr::from(([127, 0, 0, 1], 0)),
+            reader,
+            writer,
+        ));
+        // Default responses for all events
+        let events = tokio::task::spawn_local(async move {
+            let mut acceptor = Box::pin(acceptor);
+            while acceptor.next().await.is_some() {}
+        });
+
+        let mut buffer = Vec::new();
+        let mut result = Ok(());
+        for (idx, entry) in entries.iter().enumerate() {
+            if entry.direction == Direction::Outbound {
+                continue;
+            }
+            let expected: Vec<_> = entries[idx + 1..]
+                .iter()
+                .take_while(|entry| entry.direction == Direction::Outbound)
+                .map(|entry| self.fields(&entry.msg.serialize()))
+                .collect();
+
+            let mut msg = entry.msg.clone();
+            msg.header.sending_time =
+                UtcTimestamp::with_precision(Utc::now(), msg.header.sending_time.precision());
+            remote.write_all(&msg.serialize()).await?;
+
+            let actual: Vec<_> = self
+                .read_responses(&mut remote, &mut buffer)
+                .await?
+                .iter()
+                .map(|msg| self.fields(msg))
+                .collect();
+
+            if let Some((expected, actual)) = (0..expected.len().max(actual.len()))
+                .map(|i| (expected.get(i), actual.get(i)))
+                .find(|(expected, actual)| expected != actual)
+            {
+                let none = "<none>".to_owned();
+                result = Err(ReplayError::Mismatch {
+                    line: entry.line,
+                    expected: expected.unwrap_or(&none).clone(),
+                    actual: actual.unwrap_or(&none).clone(),
+                });
+                break;
+            }
+        }
+
+        drop(remote);
+        let _ = session.await;
+        events.abort();
+        result
+    }
+
+    async fn read_responses(
+        &self,
+        remote: &mut DuplexStream,
+        buffer: &mut Vec<u8>,
+    ) -> Result<Vec<Vec<u8>>, ReplayError> {
+        while let Ok(read) = timeout(self.response_timeout, remote.read_buf(buffer)).await {
+            if read? == 0 {
+                break;
+            }
+        }
+
+        let mut messages = Vec::new();
+        while let Ok((leftover, _)) = raw_message(buffer) {
+            let len = buffer.len() - leftover.len();
+            messages.push(buffer.drain(..len).collect());
+        }
+        Ok(messages)
+    }
+
+    /// Fields of serialized message, sorted by tag, as `tag=value|...` string.
+    fn fields(&self, msg: &[u8]) -> String {
+        let mut fields: Vec<_> = msg
+            .split(|b| *b == b'\x01')
+            .filter_map(|field| {
+                let pos = field.iter().position(|b| *b == b'=')?;
+                let tag: TagNum = std::str::from_utf8(&field[..pos]).ok()?.parse().ok()?;
+                (!self.ignored_tags.contains(&tag)).then_some((tag, field))
+            })
+            .collect();
+        fields.sort_by_key(|(tag, _)| *tag);
+        fields
+            .iter()
+            .map(|(_, field)| String::from_utf8_lossy(field))
+            .collect::<Vec<_>>()
+            .join("|")
+    }
+}
@@ -1,28 +1,60 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
 
+use bytes::Bytes;
 use easyfix_messages::{
     fields::{
-        DefaultApplVerId, EncryptMethod, FixStr, FixString, Int, MsgType, SeqNum,
-        SessionRejectReason, SessionStatus, ToFixString, Utc, UtcTimestamp,
+        BusinessRejectReason, Data, DefaultApplVerId, EncryptMethod, FixStr, FixString, Int,
+        MsgType, SeqNum, SessionRejectReason, SessionStatus, TagNum, ToFixString, Utc,
+        UtcTimestamp,
     },
+    keep_alive::KeepAliveTemplate,
     messages::{
-        FieldTag, FixtMessage, Heartbeat, Logon, Logout, Message, MsgCat, Reject, ResendRequest,
-        SequenceReset, TestRequest,
+        BusinessMessageReject, FieldTag, FixtMessage, Heartbeat, Logon, Logout, Message, MsgCat,
+        Reject, ResendRequest, SequenceReset, TestRequest, DICTIONARY_FINGERPRINT,
     },
+    serializer::{SerializeError, Serializer},
 };
 use tokio::time::{Duration, Instant};
-use tracing::{error, info, instrument, trace, warn};
+use tracing::{debug, error, info, instrument, trace, warn};
 
 use crate::{
-    application::{DeserializeError, Emitter, FixEventInternal, InputResponderMsg, Responder},
-    messages_storage::MessagesStorage,
+    application::{
+        BeginStringMismatch, DeserializeError, DiscardReason, DiscardedMsg, Emitter,
+        EventTimestamp, ExpiredMsg, FieldError, FixEventInternal, FlaggedMsg, FramingError,
+        InputResponderMsg, LatencyAlert, LatencyStage, LogonMismatch, LogoutDetails,
+        LogoutGraceOutcome, RejectDirection, RejectReport, ResendReport, Responder,
+        SerializeFailure, SessionStatusReport, StoreFailure,
+    },
+    credentials::CredentialsError,
+    enrichment::Enrichment,
+    inspector::MsgDirection,
+    logon_guard::StaleLogon,
+    messages_storage::{MessagesStorage, StoreError},
     new_header, new_trailer,
+    reject_reasons::RejectReasons,
     session_id::SessionId,
+    session_log::SessionLog,
     session_state::State,
-    settings::{SessionSettings, Settings},
-    DisconnectReason, Sender,
+    settings::{
+        BeginStringMismatchPolicy, FieldErrorsPolicy, LogonCredentials, MsgSeqNumTooLowAction,
+        ReadIdleAction, ResendRequestEndSeqNo, SequenceResetPolicy, SerializeFailurePolicy,
+        SessionSettings, Settings, StoreFailurePolicy, TestMessagesPolicy, TestRequestReplyPolicy,
+        UnsupportedMsgTypePolicy,
+    },
+    verifier::Verdict,
+    DisconnectReason, PauseMode, QueuedMsg, Sender,
 };
 
+/// Invalid fields skipped in received application message.
+#[derive(Debug)]
+struct FieldErrors {
+    errors: Vec<FieldError>,
+    raw_message: Bytes,
+}
+
 #[derive(Debug, thiserror::Error)]
 enum VerifyError {
     #[error("Message already received")]
@@ -34,6 +66,10 @@ enum VerifyError {
         reason: SessionRejectReason,
         tag: Option<FieldTag>,
         logout: bool,
+        raw_message: Option<Bytes>,
+        /// SenderCompID<49> and TargetCompID<56> of received message,
+        /// when CompIDs verification failed
+        comp_ids: Option<(FixString, FixString)>,
     },
     #[error("Invalid logon state")]
     InvalidLogonState,
@@ -58,6 +94,12 @@ enum VerifyError {
     },
     #[error("User disconnected: {reason:?}")]
     UserForcedDisconnect { reason: Option<String> },
+    #[error("Unsupported message type {}", .ref_msg_type.as_utf8())]
+    Unsupported {
+        ref_msg_type: FixString,
+        ref_seq_num: SeqNum,
+        business_reject_ref_id: Option<FixString>,
+    },
 }
 
 impl VerifyError {
@@ -65,7 +107,9 @@ impl VerifyError {
         VerifyError::Reject {
             reason: SessionRejectReason::SendingtimeAccuracyProblem,
             tag: Some(FieldTag::SendingTime),
-            logout: false,
+            logout: true,
+            raw_message: None,
+            comp_ids: None,
         }
     }
 
@@ -74,6 +118,8 @@ impl VerifyError {
             reason: SessionRejectReason::CompidProblem,
             tag: Some(field_tag),
             logout: true,
+            raw_message: None,
+            comp_ids: None,
         }
     }
 
@@ -86,6 +132,8 @@ impl VerifyError {
             reason: SessionRejectReason::RequiredTagMissing,
             tag: Some(FieldTag::OrigSendingTime),
             logout: false,
+            raw_message: None,
+            comp_ids: None,
         }
     }
 
@@ -94,7 +142,25 @@ impl VerifyError {
             reason: SessionRejectReason::SendingtimeAccuracyProblem,
             tag: Some(FieldTag::OrigSendingTime),
             logout: true,
+            raw_message: None,
+            comp_ids: None,
+        }
+    }
+
+    /// Attach CompIDs of rejected message to `Reject` error.
+    fn with_comp_ids(mut self, sender_comp_id: &FixStr, target_comp_id: &FixStr) -> VerifyError {
+        if let VerifyError::Reject { comp_ids, .. } = &mut self {
+            *comp_ids = Some((sender_comp_id.to_owned(), target_comp_id.to_owned()));
+        }
+        self
+    }
+
+    /// Attach rejected message, as received, to `Reject` error.
+    fn with_raw_message(mut self, raw: Option<Bytes>) -> VerifyError {
+        if let VerifyError::Reject { raw_message, .. } = &mut self {
+            *raw_message = raw;
         }
+        self
     }
 }
 
@@ -108,6 +174,10 @@ impl MessageExt for FixtMessage {
     }
 }
 
+fn extend_gap_fill(gap_fill_range: &mut Option<(SeqNum, SeqNum)>, begin: SeqNum, end: SeqNum) {
+    gap_fill_range.get_or_insert((begin, end)).1 = end;
+}
+
 #[derive(Debug)]
 pub(crate) struct Session<S> {
     // XXX: To avoid borrow errors, borrow state only in async fn,
@@ -117,6 +187,21 @@ pub(crate) struct Session<S> {
     settings: Settings,
     session_settings: SessionSettings,
     emitter: Emitter,
+    session_log: Option<Rc<SessionLog>>,
+    enrichment: Enrichment,
+    reject_reasons: RejectReasons,
+    /// Not set when session header can't be templated, all messages are
+    /// serialized as usual then
+    keep_alive_template: Option<RefCell<KeepAliveTemplate>>,
+    /// Cause of logout, reported with `Logout` event
+    logout_details: RefCell<Option<LogoutDetails>>,
+    /// Capture time of input message being processed
+    input_timestamp: Cell<Option<EventTimestamp>>,
+    /// Input message being processed, as received, not known for queued
+    /// messages spilled to messages storage
+    raw_message_in: RefCell<Option<Bytes>>,
+    /// Decode time of input message being processed, set by input stream
+    decode_time: Rc<Cell<Duration>>,
 }
 
 impl<S: MessagesStorage> Session<S> {
@@ -127,12 +212,44 @@ impl<S: MessagesStorage> Session<S> {
         sender: Sender,
         emitter: Emitter,
     ) -> Session<S> {
+        // Same values as set in `fill_header()` in output_stream.rs
+        let mut header = new_header(MsgType::Heartbeat);
+        header.begin_string = session_settings.begin_string();
+        header.sender_comp_id = session_settings.sender_comp_id();
+        header.target_comp_id = session_settings.target_comp_id();
+
+        let keep_alive_template = KeepAliveTemplate::new(header)
+            .map_err(|err| warn!("keep alive template not created: {err}"))
+            .ok()
+            .map(RefCell::new);
+
+        let session_log = settings.session_log.as_ref().and_then(|session_log| {
+            SessionLog::open(session_log, &session_settings.session_id)
+                .map_err(|err| error!("failed to open session log: {err}"))
+                .ok()
+                .map(Rc::new)
+        });
+        let emitter = emitter.with_session_log(session_log.clone());
+        let enrichment = Enrichment::new(&session_settings.enrichment);
+        let reject_reasons = RejectReasons::new(&session_settings.reject_reasons);
+        state
+            .borrow_mut()
+            .set_queue_spill_threshold(session_settings.queue_spill_threshold);
+
         Session {
             state,
             settings,
             session_settings,
             sender,
             emitter,
+            session_log,
+            enrichment,
+            reject_reasons,
+            keep_alive_template,
+            logout_details: RefCell::new(None),
+            input_timestamp: Cell::new(None),
+            raw_message_in: RefCell::new(None),
+            decode_time: Rc::new(Cell::new(Duration::ZERO)),
         }
     }
 
@@ -140,18 +257,85 @@ impl<S: MessagesStorage> Session<S> {
         &self.session_settings.session_id
     }
 
+    pub(crate) fn session_settings(&self) -> &SessionSettings {
+        &self.session_settings
+    }
+
     pub(crate) fn state(&self) -> &Rc<RefCell<State<S>>> {
         &self.state
     }
 
+    pub(crate) fn emitter(&self) -> &Emitter {
+        &self.emitter
+    }
+
+    /// Set capture time reported with events of next input message.
+    pub(crate) fn set_input_timestamp(&self, timestamp: EventTimestamp) {
+        self.input_timestamp.set(Some(timestamp));
+    }
+
+    /// Shared with input stream, which reports decode time of every
+    /// input message.
+    pub(crate) fn decode_timer(&self) -> Rc<Cell<Duration>> {
+        self.decode_time.clone()
+    }
+
+    fn raw_message_in(&self) -> Option<Bytes> {
+        self.raw_message_in.borrow().clone()
+    }
+
+    fn input_timestamp(&self) -> EventTimestamp {
+        self.input_timestamp
+            .get()
+            .unwrap_or_else(EventTimestamp::now)
+    }
+
+    /// Add fields configured with [`SessionSettings::enrichment`].
+    pub(crate) fn enrich(&self, msg: &mut FixtMessage) {
+        self.enrichment.apply(msg);
+    }
+
+    pub(crate) fn session_log(&self) -> Option<&Rc<SessionLog>> {
+        self.session_log.as_ref()
+    }
+
+    /// Serialize message, Heartbeat<0> and TestRequest<1> messages
+    /// are serialized using preallocated template.
+    pub(crate) fn serialize(&self, msg: &FixtMessage) -> Result<Vec<u8>, SerializeError> {
+        if matches!(msg.msg_type(), MsgType::Heartbeat | MsgType::TestRequest) {
+            if let Some(buffer) = self
+                .keep_alive_template
+                .as_ref()
+                .and_then(|template| template.borrow_mut().serialize(msg))
+            {
+                return Ok(buffer);
+            }
+        }
+        let capacity = self.settings.buffers.serialize_buffer_capacity;
+        let buffer = msg.serialize_with(
+            Serializer::with_capacity(capacity)
+                .with_empty_strings(self.session_settings.empty_strings.into()),
+        )?;
+        if buffer.len() > capacity {
+            debug!(
+                "serialize buffer resized from {capacity} to {} bytes",
+                buffer.capacity()
+            );
+            self.state.borrow_mut().incr_serialize_buffer_resizes();
+        }
+        Ok(buffer)
+    }
+
     pub fn is_logged_on(state: &State<S>) -> bool {
         state.logon_received() && state.logon_sent()
     }
 
     pub fn is_logon_time(&self, time: UtcTimestamp) -> bool {
+        let time = time.timestamp();
         self.session_settings
-            .logon_time
-            .contains(&time.timestamp().time())
+            .calendar
+            .is_business_day(time.date_naive())
+            && self.session_settings.logon_time.contains(&time.time())
     }
 
     fn check_sending_time(&self, sending_time: UtcTimestamp) -> Result<(), VerifyError> {
@@ -191,17 +375,90 @@ impl<S: MessagesStorage> Session<S> {
         sender_comp_id: &FixStr,
         target_comp_id: &FixStr,
     ) -> Result<(), VerifyError> {
+        let session_id = &self.session_settings.session_id;
+        let policy = &self.session_settings.comp_id_policy;
         if !self.session_settings.check_comp_id {
             Ok(())
-        } else if self.session_settings.session_id.sender_comp_id() != target_comp_id {
+        } else if !policy.matches(session_id.sender_comp_id(), target_comp_id) {
             Err(VerifyError::invalid_comp_id(FieldTag::TargetCompId))
-        } else if self.session_settings.session_id.target_comp_id() != sender_comp_id {
+        } else if !policy.matches(session_id.target_comp_id(), sender_comp_id) {
             Err(VerifyError::invalid_comp_id(FieldTag::SenderCompId))
         } else {
             Ok(())
         }
     }
 
+    /// Verify CompIDs and, when configured, DefaultApplVerID<1137> of
+    /// Logon<A> response received by initiator.
+    fn check_logon_response(&self, msg: &FixtMessage) -> Option<LogonMismatch> {
+        let Message::Logon(ref logon) = *msg.body else {
+            unreachable!()
+        };
+        let session_id = &self.session_settings.session_id;
+        let policy = &self.session_settings.comp_id_policy;
+        let mismatch = |tag: FieldTag, expected: &FixStr, received: &FixStr| LogonMismatch {
+            tag: tag as TagNum,
+            expected: expected.to_owned(),
+            received: received.to_owned(),
+        };
+        if self.session_settings.check_comp_id {
+            if !policy.matches(session_id.target_comp_id(), &msg.header.sender_comp_id) {
+                return Some(mismatch(
+                    FieldTag::SenderCompId,
+                    session_id.target_comp_id(),
+                    &msg.header.sender_comp_id,
+                ));
+            }
+            if !policy.matches(session_id.sender_comp_id(), &msg.header.target_comp_id) {
+                return Some(mismatch(
+                    FieldTag::TargetCompId,
+                    session_id.sender_comp_id(),
+                    &msg.header.target_comp_id,
+                ));
+            }
+        }
+        // DefaultApplVerID<1137> is sent only in FIXT session protocol
+        if self.session_settings.check_default_appl_ver_id
+            && self.session_settings.session_id.is_fixt()
+            && logon.default_appl_ver_id.as_fix_str()
+                != self.session_settings.target_default_appl_ver_id
+        {
+            return Some(mismatch(
+                FieldTag::DefaultApplVerId,
+                &self.session_settings.target_default_appl_ver_id,
+                logon.default_appl_ver_id.as_fix_str(),
+            ));
+        }
+        None
+    }
+
+    /// Verify dictionary fingerprint sent by counterparty in RawData<96>
+    /// of Logon<A>, when fingerprint exchange is enabled.
+    fn check_dictionary_fingerprint(&self, msg: &FixtMessage) -> Option<LogonMismatch> {
+        let check = self.session_settings.dictionary_fingerprint.as_ref()?;
+        let Message::Logon(ref logon) = *msg.body else {
+            unreachable!()
+        };
+        let received = logon
+            .raw_data
+            .as_ref()
+            .map_or(&[][..], |raw_data| raw_data.as_ref());
+        (received != check.expected().as_bytes()).then(|| LogonMismatch {
+            tag: FieldTag::RawData as TagNum,
+            expected: check.expected().to_owned(),
+            received: FixString::from_ascii_lossy(received.to_vec()),
+        })
+    }
+
+    /// RawData<96> of sent Logon<A>, dictionary fingerprint when
+    /// fingerprint exchange is enabled.
+    fn logon_raw_data(&self) -> Option<Data> {
+        self.session_settings
+            .dictionary_fingerprint
+            .as_ref()
+            .map(|_| DICTIONARY_FINGERPRINT.as_bytes().into())
+    }
+
     fn should_send_reset(&self, state: &State<S>) -> bool {
         (self.session_settings.reset_on_logon
             || self.session_settings.reset_on_logout
@@ -210,6 +467,34 @@ impl<S: MessagesStorage> Session<S> {
             && state.next_sender_msg_seq_num() == 1
     }
 
+    /// Run [`SessionSettings::verifier`] on received message.
+    async fn check_custom(&self, msg: &FixtMessage) -> Result<(), VerifyError> {
+        match self.session_settings.verifier.verify(&msg.header).await {
+            Verdict::Accept => Ok(()),
+            Verdict::Reject {
+                reason,
+                tag,
+                logout,
+            } => Err(VerifyError::Reject {
+                reason,
+                tag,
+                logout,
+                raw_message: None,
+                comp_ids: None,
+            }
+            .with_raw_message(self.raw_message_in())),
+            Verdict::Logout {
+                session_status,
+                text,
+                disconnect,
+            } => Err(VerifyError::UserForcedLogout {
+                session_status,
+                text,
+                disconnect,
+            }),
+        }
+    }
+
     // current implementation is more readable than clippy proposal
     #[allow(clippy::if_same_then_else)]
     fn check_logon_state(state: &State<S>, msg_type: MsgType) -> Result<(), VerifyError> {
@@ -232,16 +517,58 @@ impl<S: MessagesStorage> Session<S> {
         }
     }
 
+    async fn verify(
+        &self,
+        msg: Box<FixtMessage>,
+        check_too_high: bool,
+        check_too_low: bool,
+    ) -> Result<(), VerifyError> {
+        self.verify_with_field_errors(msg, Vec::new(), check_too_high, check_too_low)
+            .await
+    }
+
+    /// Verify application message with invalid fields skipped.
+    ///
+    /// On success the message is rejected when configured so, otherwise
+    /// next target sequence number is incremented.
+    async fn verify_partial(
+        &self,
+        msg: Box<FixtMessage>,
+        field_errors: FieldErrors,
+    ) -> Result<(), VerifyError> {
+        let FieldErrors {
+            errors,
+            raw_message,
+        } = field_errors;
+        let first_error = errors[0];
+        self.verify_with_field_errors(msg, errors, true, true)
+            .await?;
+        if self.session_settings.field_errors == FieldErrorsPolicy::DeliverAndReject {
+            Err(VerifyError::Reject {
+                reason: first_error.reason,
+                tag: FieldTag::from_tag_num(first_error.tag),
+                logout: false,
+                raw_message: Some(raw_message),
+                comp_ids: None,
+            })
+        } else {
+            self.state.borrow_mut().incr_next_target_msg_seq_num();
+            Ok(())
+        }
+    }
+
     #[instrument(skip_all, err)]
     #[expect(clippy::await_holding_refcell_ref)]
     // Make sure `state` is dropped before await points, see
     // https://github.com/rust-lang/rust-clippy/issues/6353
-    async fn verify(
+    async fn verify_with_field_errors(
         &self,
         msg: Box<FixtMessage>,
+        field_errors: Vec<FieldError>,
         check_too_high: bool,
         check_too_low: bool,
     ) -> Result<(), VerifyError> {
+        let started = Instant::now();
         let msg_type = msg.header.msg_type;
 
         let sender_comp_id = &msg.header.sender_comp_id;
@@ -249,11 +576,17 @@ impl<S: MessagesStorage> Session<S> {
         let sending_time = msg.header.sending_time;
         let msg_seq_num = msg.header.msg_seq_num;
 
-        let state = self.state.borrow();
+        Self::check_logon_state(&self.state.borrow(), msg.header.msg_type)?;
+        self.check_sending_time(sending_time)
+            .map_err(|err| err.with_raw_message(self.raw_message_in()))?;
+        self.check_comp_id(sender_comp_id, target_comp_id)
+            .map_err(|err| {
+                err.with_raw_message(self.raw_message_in())
+                    .with_comp_ids(sender_comp_id, target_comp_id)
+            })?;
+        self.check_custom(&msg).await?;
 
-        Self::check_logon_state(&state, msg.header.msg_type)?;
-        self.check_sending_time(sending_time)?;
-        self.check_comp_id(sender_comp_id, target_comp_id)?;
+        let state = self.state.borrow();
 
         if check_too_high && Self::is_target_too_high(&state, msg_seq_num) {
             warn!(
@@ -261,18 +594,22 @@ impl<S: MessagesStorage> Session<S> {
                 state.next_target_msg_seq_num()
             );
             drop(state);
-            self.state.borrow_mut().enqueue_msg(msg);
+            self.state
+                .borrow_mut()
+                .enqueue_msg(msg, self.raw_message_in());
             Err(VerifyError::target_seq_num_too_high(msg_seq_num))
         } else if check_too_low && Self::is_target_too_low(&state, msg_seq_num) {
             if msg.header.poss_dup_flag.unwrap_or(false) {
                 if msg_type != MsgType::SequenceReset {
                     let Some(orig_sending_time) = msg.header.orig_sending_time else {
                         warn!("Target too low (orig sending time missing)");
-                        return Err(VerifyError::missing_orig_time());
+                        return Err(VerifyError::missing_orig_time()
+                            .with_raw_message(self.raw_message_in()));
                     };
                     if orig_sending_time.timestamp() > sending_time.timestamp() {
                         error!("Target too low (invalid orig sending time)");
-                        return Err(VerifyError::invalid_orig_time());
+                        return Err(VerifyError::invalid_orig_time()
+                            .with_raw_message(self.raw_message_in()));
                     }
                 }
                 warn!("Target too low (duplicate)");
@@ -295,25 +632,69 @@ impl<S: MessagesStorage> Session<S> {
 
                     if msg_seq_num >= end_seq_num {
                         info!("Resend request from {begin_seq_num} to {end_seq_num} has been satisfied");
-                        self.state.borrow_mut().set_resend_range(None);
+                        self.complete_resend_range(&mut self.state.borrow_mut(), msg_seq_num + 1);
                     }
                 }
             }
 
+            self.inspect(MsgDirection::Inbound, &msg).await;
+            self.check_latency_budget(
+                LatencyStage::Verify,
+                started.elapsed(),
+                msg_type,
+                msg_seq_num,
+            )
+            .await;
+
+            let started = Instant::now();
             let (sender, receiver) = tokio::sync::oneshot::channel();
             match msg.msg_cat() {
                 MsgCat::Admin => {
                     self.emitter
-                        .send(FixEventInternal::AdmMsgIn(Some(msg), Some(sender)))
+                        .send_at(
+                            FixEventInternal::AdmMsgIn(Some(msg), Some(sender)),
+                            self.input_timestamp(),
+                        )
+                        .await
+                }
+                MsgCat::App if self.state.borrow().test_peer() => {
+                    self.emitter
+                        .send_at(
+                            FixEventInternal::TestAppMsgIn(Some(msg), Some(sender)),
+                            self.input_timestamp(),
+                        )
+                        .await
+                }
+                MsgCat::App if field_errors.is_empty() => {
+                    self.emitter
+                        .send_at(
+                            FixEventInternal::AppMsgIn(Some(msg), Some(sender)),
+                            self.input_timestamp(),
+                        )
                         .await
                 }
                 MsgCat::App => {
                     self.emitter
-                        .send(FixEventInternal::AppMsgIn(Some(msg), Some(sender)))
+                        .send_at(
+                            FixEventInternal::PartialAppMsgIn(
+                                Some(msg),
+                                field_errors,
+                                Some(sender),
+                            ),
+                            self.input_timestamp(),
+                        )
                         .await
                 }
             }
-            match receiver.await {
+            let response = receiver.await;
+            self.check_latency_budget(
+                LatencyStage::AppHandler,
+                started.elapsed(),
+                msg_type,
+                msg_seq_num,
+            )
+            .await;
+            match response {
                 Ok(InputResponderMsg::Reject {
                     ref_msg_type,
                     ref_seq_num,
@@ -343,6 +724,17 @@ impl<S: MessagesStorage> Session<S> {
                 Ok(InputResponderMsg::Disconnect { reason }) => {
                     return Err(VerifyError::UserForcedDisconnect { reason })
                 }
+                Ok(InputResponderMsg::Unsupported {
+                    ref_msg_type,
+                    ref_seq_num,
+                    business_reject_ref_id,
+                }) => {
+                    return Err(VerifyError::Unsupported {
+                        ref_msg_type,
+                        ref_seq_num,
+                        business_reject_ref_id,
+                    })
+                }
                 Err(_) => {}
             }
 
@@ -350,11 +742,33 @@ impl<S: MessagesStorage> Session<S> {
         }
     }
 
-    pub(crate) fn send_logon_request(&self, state: &mut State<S>) {
+    /// Credentials sent in Logon<A>, resolved with
+    /// `SessionSettings::credentials_provider`.
+    pub(crate) async fn logon_credentials(&self) -> Result<LogonCredentials, CredentialsError> {
+        self.session_settings
+            .credentials_provider
+            .logon_credentials(
+                &self.session_settings.session_id,
+                &self.session_settings.credentials,
+            )
+            .await
+    }
+
+    pub(crate) fn send_logon_request(&self, state: &mut State<S>, credentials: LogonCredentials) {
         if self.session_settings.reset_on_logon {
             state.reset();
         }
 
+        // Password changed at previous logon is not changed again, unless
+        // credentials were rotated since then
+        let (password, new_password) = match state.password() {
+            Some(password) if credentials.new_password.as_ref() == Some(password) => {
+                (Some(password.clone()), None)
+            }
+            _ => (credentials.password, credentials.new_password),
+        };
+        state.set_new_password_sent(new_password.clone());
+
         self.send(Box::new(Message::Logon(Logon {
             // encrypt_method: EncryptMethod::None,
             encrypt_method: EncryptMethod::NoneOther,
@@ -367,9 +781,18 @@ impl<S: MessagesStorage> Session<S> {
             } else {
                 None
             },
+            test_message_indicator: self.session_settings.test_session.then_some(true),
+            raw_data: self.logon_raw_data(),
+            username: credentials.username,
+            password,
+            new_password,
             default_appl_ver_id: DefaultApplVerId::Fix50Sp2,
             ..Default::default()
         })));
+
+        state.set_last_received_time(Instant::now());
+        state.set_test_request(0);
+        state.set_logon_sent(true);
     }
 
     fn send_logon_response(&self, state: &mut State<S>, next_expected_msg_seq_num: Option<SeqNum>) {
@@ -383,6 +806,8 @@ impl<S: MessagesStorage> Session<S> {
             heart_bt_int: state.heart_bt_int(),
             reset_seq_num_flag: self.should_send_reset(state).then_some(true),
             next_expected_msg_seq_num,
+            test_message_indicator: self.session_settings.test_session.then_some(true),
+            raw_data: self.logon_raw_data(),
             // TODO: if self.session_settings.session_id().is_fixt()
             // default_appl_ver_id: self.sender_default_appl_ver_id().to_owned(),
             default_appl_ver_id: DefaultApplVerId::Fix50Sp2,
@@ -409,8 +834,12 @@ impl<S: MessagesStorage> Session<S> {
             ..Default::default()
         })));
         state.set_logout_sent(true);
+        self.sender.set_logged_out();
     }
 
+    /// Send Reject<3>, returned report should be emitted with `emit_reject()`
+    /// once `state` is released.
+    #[allow(clippy::too_many_arguments)]
     fn send_reject(
         &self,
         state: &mut State<S>,
@@ -419,7 +848,22 @@ impl<S: MessagesStorage> Session<S> {
         reason: SessionRejectReason,
         text: FixString,
         ref_tag_id: Option<i64>,
-    ) {
+        raw_message: Option<Bytes>,
+    ) -> Box<RejectReport> {
+        info!("Message {ref_seq_num} Rejected: {reason:?} (tag={ref_tag_id:?})");
+        let (reason, text) = self.reject_reasons.apply(reason, text, ref_tag_id);
+
+        let report = Box::new(RejectReport {
+            direction: RejectDirection::Sent,
+            ref_msg_type: ref_msg_type.clone(),
+            ref_seq_num,
+            ref_tag_id,
+            reason: Some(reason),
+            text: Some(text.clone()),
+            raw_message,
+            state: state.snapshot(),
+        });
+
         if !matches!(
             ref_msg_type.as_deref().and_then(MsgType::from_fix_str),
             Some(MsgType::Logon) | Some(MsgType::SequenceReset)
@@ -428,8 +872,6 @@ impl<S: MessagesStorage> Session<S> {
             state.incr_next_target_msg_seq_num();
         }
 
-        info!("Message {ref_seq_num} Rejected: {reason:?} (tag={ref_tag_id:?})");
-
         if !state.logon_received() {
             // TODO: Error
         }
@@ -442,6 +884,17 @@ impl<S: MessagesStorage> Session<S> {
             text: Some(text),
             ..Default::default()
         })));
+
+        report
+    }
+
+    async fn emit_reject(&self, report: Box<RejectReport>) {
+        self.emitter
+            .send(FixEventInternal::Reject(
+                self.session_settings.session_id.clone(),
+                report,
+            ))
+            .await;
     }
 
     fn send_sequence_reset(&self, seq_num: SeqNum, new_seq_num: SeqNum) {
@@ -456,7 +909,7 @@ impl<S: MessagesStorage> Session<S> {
 
         sequence_reset.header.msg_seq_num = seq_num;
         sequence_reset.header.poss_dup_flag = Some(true);
-        sequence_reset.header.sending_time = UtcTimestamp::now();
+        sequence_reset.header.sending_time = self.session_settings.clock.now();
         sequence_reset.header.orig_sending_time = Some(sequence_reset.header.sending_time);
 
         info!("SequenceReset sent (MsgSeqNum: {seq_num}, NewSeqNo: {new_seq_num})");
@@ -466,35 +919,66 @@ impl<S: MessagesStorage> Session<S> {
     #[instrument(level = "trace", skip_all)]
     fn send_resend_request(&self, state: &mut State<S>, msg_seq_num: SeqNum) {
         let begin_seq_no = state.next_target_msg_seq_num();
-        let end_seq_no = msg_seq_num - 1;
+        self.request_resend(state, begin_seq_no, msg_seq_num - 1);
+    }
+
+    /// Request missing messages from `begin_seq_num` to `end_seq_num`,
+    /// only the first part of the gap is requested when it's larger than
+    /// `ResendRequestPolicy::max_range`.
+    pub(crate) fn request_resend(
+        &self,
+        state: &mut State<S>,
+        begin_seq_num: SeqNum,
+        end_seq_num: SeqNum,
+    ) {
+        let policy = self.session_settings.resend_request;
+        let request_end_seq_num = match policy.max_range {
+            Some(max_range) if end_seq_num - begin_seq_num >= max_range => {
+                begin_seq_num + max_range.max(1) - 1
+            }
+            _ => end_seq_num,
+        };
+        let end_seq_no = match policy.end_seq_no {
+            ResendRequestEndSeqNo::Infinity if request_end_seq_num == end_seq_num => 0,
+            _ => request_end_seq_num,
+        };
 
+        info!("ResendRequest sent (BeginSeqNo: {begin_seq_num}, EndSeqNo: {end_seq_no})");
         self.send(Box::new(Message::ResendRequest(ResendRequest {
-            begin_seq_no,
+            begin_seq_no: begin_seq_num,
             end_seq_no,
         })));
 
-        state.set_resend_range(Some(begin_seq_no..=msg_seq_num - 1));
+        state.set_resend_range(Some(begin_seq_num..=request_end_seq_num));
+        state.set_resend_gap_end((request_end_seq_num < end_seq_num).then_some(end_seq_num));
+    }
+
+    /// Forget satisfied resend range and request the next part of the gap,
+    /// starting at `next_msg_seq_num`, when it was split.
+    fn complete_resend_range(&self, state: &mut State<S>, next_msg_seq_num: SeqNum) {
+        state.set_resend_range(None);
+        match state.resend_gap_end() {
+            Some(gap_end) if gap_end >= next_msg_seq_num => {
+                self.request_resend(state, next_msg_seq_num, gap_end);
+            }
+            _ => state.set_resend_gap_end(None),
+        }
     }
 
     /// Send FIX message.
     fn send(&self, msg: Box<Message>) {
-        if let Err(msg) = self.sender.send(msg) {
-            // This should never happen.
-            // See `fn input_loop()` and `fn output_loop()` in connection.rs
-            // Output loop always waits for input loop to finish, so it's not
-            // possible that output queue is closed when input message is still
-            // being processed.
-            unreachable!(
-                "Can't send message {:?}/{} - output stream is closed",
-                msg.msg_type(),
-                msg.header.msg_seq_num
-            );
-        }
+        self.send_raw(Box::new(FixtMessage {
+            header: Box::new(new_header(msg.msg_type())),
+            body: msg,
+            trailer: Box::new(new_trailer()),
+        }));
     }
 
-    /// Send FIXT message.
+    /// Send FIXT message, unlike [`Sender::send_raw`] application messages
+    /// are accepted after logout, e.g. when resend is completed during
+    /// logout grace period.
     fn send_raw(&self, msg: Box<FixtMessage>) {
-        if let Err(msg) = self.sender.send_raw(msg) {
+        if let Err(QueuedMsg { msg, .. }) = self.sender.send_queued(QueuedMsg::new(msg, None)) {
             // This should never happen.
             // See `fn input_loop()` and `fn output_loop()` in connection.rs
             // Output loop always waits for input loop to finish, so it's not
@@ -512,6 +996,16 @@ impl<S: MessagesStorage> Session<S> {
     // Make sure `state` is dropped before await points, see
     // https://github.com/rust-lang/rust-clippy/issues/6353
     pub(crate) async fn emit_logout(&self, reason: DisconnectReason) {
+        let details = self.logout_details.take();
+        if let Some(details) = details {
+            self.emitter
+                .send(FixEventInternal::LogoutDetails(
+                    self.session_settings.session_id.clone(),
+                    Box::new(details),
+                ))
+                .await;
+        }
+
         let mut state = self.state.borrow_mut();
 
         if state.logon_received() || state.logon_sent() {
@@ -528,6 +1022,11 @@ impl<S: MessagesStorage> Session<S> {
         }
     }
 
+    /// Remember cause of logout, only the first one is reported.
+    fn set_logout_details(&self, details: LogoutDetails) {
+        self.logout_details.borrow_mut().get_or_insert(details);
+    }
+
     pub(crate) fn disconnect(&self, state: &mut State<S>, reason: DisconnectReason) {
         if state.disconnected() {
             info!("already disconnected");
@@ -550,6 +1049,8 @@ impl<S: MessagesStorage> Session<S> {
         }
 
         state.set_resend_range(None);
+        state.set_resend_gap_end(None);
+        state.set_pending_logout(None);
         state.clear_queue();
         self.sender.disconnect(reason);
     }
@@ -558,8 +1059,123 @@ impl<S: MessagesStorage> Session<S> {
         state.reset();
     }
 
+    /// Stop sending new application messages.
+    ///
+    /// Administrative messages (heartbeats, test requests, resends) are not
+    /// affected, so the session stays alive during the pause.
+    pub(crate) fn pause(&self, state: &mut State<S>, mode: PauseMode) {
+        info!("session paused ({mode:?})");
+        state.set_paused(Some(mode));
+    }
+
+    /// Resume sending application messages, messages buffered while paused
+    /// are sent first.
+    pub(crate) fn resume(&self, state: &mut State<S>) {
+        if state.paused().is_none() {
+            return;
+        }
+        state.set_paused(None);
+        info!("session resumed");
+        self.sender.resume();
+    }
+
+    /// Returns message buffered while paused, once the session is resumed
+    /// and logged on. Output stream sends these before messages queued
+    /// after them, buffered messages left after disconnection are sent
+    /// after the next Logon<A>.
+    pub(crate) fn take_resumed_msg(&self) -> Option<QueuedMsg> {
+        let mut state = self.state.borrow_mut();
+        if state.paused().is_some() || !Session::is_logged_on(&state) {
+            return None;
+        }
+        state.retrieve_paused_msg()
+    }
+
+    /// Returns the message back if it can be sent, otherwise message is
+    /// buffered or discarded, depending on the pause mode. Discarded
+    /// messages are reported with `FixEvent::AppMsgDiscarded`.
+    pub(crate) async fn hold_if_paused(&self, queued: QueuedMsg) -> Option<QueuedMsg> {
+        let msg = &queued.msg;
+        // Resent messages already have MsgSeqNum assigned
+        if !matches!(msg.msg_cat(), MsgCat::App) || msg.header.msg_seq_num != 0 {
+            return Some(queued);
+        }
+        let reason = {
+            let mut state = self.state.borrow_mut();
+            match state.paused() {
+                None => return Some(queued),
+                Some(PauseMode::Buffer)
+                    if state.paused_queue_len() < self.session_settings.paused_queue_limit =>
+                {
+                    debug!("session paused, message {:?} buffered", msg.msg_type());
+                    state.enqueue_paused_msg(queued);
+                    return None;
+                }
+                Some(PauseMode::Buffer) => {
+                    warn!(
+                        "session paused, buffer full, message {:?} discarded",
+                        msg.msg_type()
+                    );
+                    DiscardReason::PausedQueueFull
+                }
+                Some(PauseMode::Reject) => {
+                    warn!("session paused, message {:?} discarded", msg.msg_type());
+                    DiscardReason::Paused
+                }
+            }
+        };
+        self.emitter
+            .send(FixEventInternal::AppMsgDiscarded(
+                self.session_id().clone(),
+                Box::new(DiscardedMsg {
+                    msg: queued.msg,
+                    reason,
+                }),
+            ))
+            .await;
+        None
+    }
+
+    /// Returns the message back if it can be sent, application message
+    /// queued longer than its TTL is dropped and reported with
+    /// `FixEvent::AppMsgExpired`.
+    pub(crate) async fn drop_if_expired(&self, queued: QueuedMsg) -> Option<Box<FixtMessage>> {
+        let QueuedMsg {
+            msg,
+            queued_at,
+            ttl,
+        } = queued;
+        // Resent messages already have MsgSeqNum assigned
+        if !matches!(msg.msg_cat(), MsgCat::App) || msg.header.msg_seq_num != 0 {
+            return Some(msg);
+        }
+        let Some(ttl) = ttl.or(self.session_settings.app_msg_ttl) else {
+            return Some(msg);
+        };
+        let age = queued_at.elapsed();
+        if age <= ttl {
+            return Some(msg);
+        }
+        warn!(
+            "message {:?} queued for {age:?}, longer than TTL {ttl:?}, dropped",
+            msg.msg_type()
+        );
+        self.emitter
+            .send(FixEventInternal::AppMsgExpired(
+                self.session_id().clone(),
+                Box::new(ExpiredMsg { msg, age, ttl }),
+            ))
+            .await;
+        None
+    }
+
     #[instrument(level = "trace", skip_all)]
-    fn resend_range(&self, state: &mut State<S>, begin_seq_num: SeqNum, mut end_seq_num: SeqNum) {
+    fn resend_range(
+        &self,
+        state: &mut State<S>,
+        begin_seq_num: SeqNum,
+        mut end_seq_num: SeqNum,
+    ) -> ResendReport {
         info!("resend range: ({begin_seq_num}, {end_seq_num})");
         let next_sender_msg_seq_num = state.next_sender_msg_seq_num();
         if end_seq_num == 0 || end_seq_num >= next_sender_msg_seq_num {
@@ -567,6 +1183,13 @@ impl<S: MessagesStorage> Session<S> {
             info!("adjust end_seq_num to {end_seq_num}");
         }
 
+        let mut report = ResendReport {
+            begin_seq_num,
+            end_seq_num,
+            resent: 0,
+            gap_filled: 0,
+        };
+
         // Just do a gap fill when messages aren't persisted
         if !self.session_settings.persist {
             let next_sender_msg_seq_num = state.next_sender_msg_seq_num();
@@ -575,32 +1198,59 @@ impl<S: MessagesStorage> Session<S> {
                 end_seq_num = next_sender_msg_seq_num;
             }
             self.send_sequence_reset(begin_seq_num, end_seq_num);
-            return;
+            report.gap_filled = end_seq_num.saturating_sub(begin_seq_num);
+            return report;
         }
 
         let mut gap_fill_range = None;
+        let mut send_gap_fill = |gap_fill_range: &mut Option<(SeqNum, SeqNum)>| {
+            if let Some((begin_seq_num, end_seq_num)) = gap_fill_range.take() {
+                info!("Resending messages from {begin_seq_num} to {end_seq_num} as gap fill");
+                self.send_sequence_reset(begin_seq_num, end_seq_num + 1);
+                report.gap_filled += end_seq_num + 1 - begin_seq_num;
+            }
+        };
+        let mut resent = 0;
+        let mut expected_seq_num = begin_seq_num;
+        let now = self.session_settings.clock.now();
+
         let messages = state.fetch_range(begin_seq_num..=end_seq_num);
         info!(
             "fetch messages range from {begin_seq_num} to {end_seq_num}, found {} messages",
             messages.len()
         );
         for msg_str in messages {
-            // TODO: log error! and resend as gap fill instead of unwrap
-            let mut msg = FixtMessage::from_bytes(&msg_str).unwrap();
-            if msg.resend_as_gap_fill() {
+            let mut msg = match FixtMessage::from_bytes(&msg_str) {
+                Ok(msg) => msg,
+                Err(error) => {
+                    // Sequence number is unknown, message will be
+                    // covered by gap fill of missing messages
+                    error!("Failed to decode stored message: {error}");
+                    continue;
+                }
+            };
+            let msg_seq_num = msg.header.msg_seq_num;
+            if msg_seq_num > expected_seq_num {
+                warn!(
+                    "Messages from {expected_seq_num} to {} not found in storage",
+                    msg_seq_num - 1
+                );
+                extend_gap_fill(&mut gap_fill_range, expected_seq_num, msg_seq_num - 1);
+            }
+            expected_seq_num = msg_seq_num + 1;
+
+            if msg.resend_as_gap_fill()
+                || (matches!(msg.msg_cat(), MsgCat::App)
+                    && self.session_settings.gap_fill.gap_fills(&msg, now))
+            {
                 info!(
                     "Message {:?}/{} changed to gap fill",
                     msg.msg_type(),
                     msg.header.msg_seq_num
                 );
-                gap_fill_range
-                    .get_or_insert((msg.header.msg_seq_num, msg.header.msg_seq_num - 1))
-                    .1 += 1;
+                extend_gap_fill(&mut gap_fill_range, msg_seq_num, msg_seq_num);
             } else {
-                if let Some((begin_seq_num, end_seq_num)) = gap_fill_range.take() {
-                    info!("Resending messages from {begin_seq_num} to {end_seq_num} as gap fill");
-                    self.send_sequence_reset(begin_seq_num, end_seq_num + 1);
-                }
+                send_gap_fill(&mut gap_fill_range);
                 info!(
                     "Resending message {:?}/{}",
                     msg.msg_type(),
@@ -610,12 +1260,26 @@ impl<S: MessagesStorage> Session<S> {
                 msg.header.poss_dup_flag = Some(true);
                 // TODO: emit event!
                 self.send_raw(msg);
+                resent += 1;
             }
         }
-        if let Some((begin_seq_num, end_seq_num)) = gap_fill_range {
-            info!("Resending messages from {begin_seq_num} to {end_seq_num} as gap fill");
-            self.send_sequence_reset(begin_seq_num, end_seq_num + 1);
+        if expected_seq_num <= end_seq_num {
+            warn!("Messages from {expected_seq_num} to {end_seq_num} not found in storage");
+            extend_gap_fill(&mut gap_fill_range, expected_seq_num, end_seq_num);
         }
+        send_gap_fill(&mut gap_fill_range);
+
+        report.resent = resent;
+        report
+    }
+
+    async fn emit_resent(&self, report: ResendReport) {
+        self.emitter
+            .send(FixEventInternal::Resent(
+                self.session_settings.session_id.clone(),
+                report,
+            ))
+            .await;
     }
 
     async fn on_heartbeat(&self, message: Box<FixtMessage>) -> Result<(), VerifyError> {
@@ -640,19 +1304,48 @@ impl<S: MessagesStorage> Session<S> {
         };
 
         let test_req_id = test_request.test_req_id.clone();
+        let customize_reply =
+            self.session_settings.test_request_reply == TestRequestReplyPolicy::Customize;
+        let test_request = customize_reply.then(|| message.clone());
 
         self.verify(message, true, true).await?;
 
-        trace!("Send Heartbeat");
-        self.send(Box::new(Message::Heartbeat(Heartbeat {
+        let heartbeat = Box::new(Message::Heartbeat(Heartbeat {
             test_req_id: Some(test_req_id),
-        })));
+        }));
+        if let Some(test_request) = test_request {
+            let reply = Box::new(FixtMessage {
+                header: Box::new(new_header(MsgType::Heartbeat)),
+                body: heartbeat,
+                trailer: Box::new(new_trailer()),
+            });
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            self.emitter
+                .send(FixEventInternal::HeartbeatReply {
+                    session_id: self.session_id().clone(),
+                    test_request,
+                    reply: Some(reply),
+                    sender: Some(sender),
+                })
+                .await;
+            trace!("Send customized Heartbeat");
+            match receiver.await {
+                Ok(reply) => self.send_raw(reply),
+                Err(_) => error!("Heartbeat reply to TestRequest lost"),
+            }
+        } else {
+            trace!("Send Heartbeat");
+            self.send(heartbeat);
+        }
 
         self.state.borrow_mut().incr_next_target_msg_seq_num();
 
         Ok(())
     }
 
+    #[expect(clippy::await_holding_refcell_ref)]
+    // Make sure `state` is dropped before await points, see
+    // https://github.com/rust-lang/rust-clippy/issues/6353
     async fn on_resend_request(&self, msg: Box<FixtMessage>) -> Result<(), VerifyError> {
         trace!("on_resend_request");
 
@@ -678,13 +1371,13 @@ impl<S: MessagesStorage> Session<S> {
 
         let mut state = self.state.borrow_mut();
 
-        self.resend_range(&mut state, begin_seq_no, end_seq_no);
+        let resend_report = self.resend_range(&mut state, begin_seq_no, end_seq_no);
 
         if Self::is_target_too_high(&state, msg_seq_num) {
             // XXX: This message will be ignored during queued messages
             //      processing, it's enqueued only to omaintain proper
             //      sequence numbers.
-            state.enqueue_msg(Box::new(FixtMessage {
+            state.enqueue_placeholder_msg(Box::new(FixtMessage {
                 header: Box::new(new_header(MsgType::ResendRequest)),
                 body: Box::new(Message::ResendRequest(ResendRequest {
                     begin_seq_no,
@@ -696,20 +1389,56 @@ impl<S: MessagesStorage> Session<S> {
         } else if state.next_target_msg_seq_num() == msg_seq_num {
             state.incr_next_target_msg_seq_num();
         }
+        drop(state);
+
+        self.emit_resent(resend_report).await;
 
         Ok(())
     }
 
+    #[expect(clippy::await_holding_refcell_ref)]
+    // Make sure `state` is dropped before await points, see
+    // https://github.com/rust-lang/rust-clippy/issues/6353
     async fn on_reject(&self, message: Box<FixtMessage>) -> Result<(), VerifyError> {
         trace!("on_reject");
 
+        let Message::Reject(ref reject) = *message.body else {
+            // Enum is matched in on_message_in_impl
+            unreachable!();
+        };
+        let ref_msg_type = reject.ref_msg_type.clone();
+        let ref_seq_num = reject.ref_seq_num;
+        let ref_tag_id = reject.ref_tag_id;
+        let reason = reject.session_reject_reason;
+        let text = reject.text.clone();
+
         self.verify(message, false, true).await?;
 
-        self.state.borrow_mut().incr_next_target_msg_seq_num();
+        let mut state = self.state.borrow_mut();
+        let report = Box::new(RejectReport {
+            direction: RejectDirection::Received,
+            ref_msg_type,
+            ref_seq_num,
+            ref_tag_id,
+            reason,
+            text,
+            raw_message: state
+                .fetch_range(ref_seq_num..=ref_seq_num)
+                .pop()
+                .map(Bytes::from),
+            state: state.snapshot(),
+        });
+        state.incr_next_target_msg_seq_num();
+        drop(state);
+
+        self.emit_reject(report).await;
 
         Ok(())
     }
 
+    #[expect(clippy::await_holding_refcell_ref)]
+    // Make sure `state` is dropped before await points, see
+    // https://github.com/rust-lang/rust-clippy/issues/6353
     async fn on_sequence_reset(&self, message: Box<FixtMessage>) -> Result<(), VerifyError> {
         trace!("on_sequence_reset");
 
@@ -722,38 +1451,152 @@ impl<S: MessagesStorage> Session<S> {
         let ref_seq_num = message.header.msg_seq_num;
         let is_gap_fill = sequence_reset.gap_fill_flag.unwrap_or(false);
         let new_seq_no = sequence_reset.new_seq_no;
+        let raw_message = self.raw_message_in();
 
         self.verify(message, is_gap_fill, is_gap_fill).await?;
 
+        let next_target_msg_seq_num = self.state.borrow().next_target_msg_seq_num();
+        let accept = if is_gap_fill {
+            // MsgSeqNum<34> is verified already, so NewSeqNo<36> not greater
+            // than next target means it's not greater than MsgSeqNum<34>
+            new_seq_no > next_target_msg_seq_num
+        } else if new_seq_no == next_target_msg_seq_num {
+            return Ok(());
+        } else {
+            match self.session_settings.sequence_reset {
+                SequenceResetPolicy::Accept => true,
+                SequenceResetPolicy::RejectLower => new_seq_no > next_target_msg_seq_num,
+                SequenceResetPolicy::Confirm => {
+                    self.confirm_sequence_reset(next_target_msg_seq_num, new_seq_no)
+                        .await
+                }
+            }
+        };
+
         let mut state = self.state().borrow_mut();
 
-        if new_seq_no > state.next_target_msg_seq_num() {
-            info!("Set next target MsgSeqNo to {new_seq_no}");
+        if accept {
+            if new_seq_no < next_target_msg_seq_num {
+                warn!(
+                    "Next target MsgSeqNo lowered from {next_target_msg_seq_num} to {new_seq_no}"
+                );
+            } else {
+                info!("Set next target MsgSeqNo to {new_seq_no}");
+            }
             state.set_next_target_msg_seq_num(new_seq_no);
-        } else if new_seq_no < state.next_sender_msg_seq_num() {
+            // GapFill usually covers the rest of requested range with single
+            // message, so range end is never received
+            if let Some(resend_range) = state.resend_range() {
+                let (begin_seq_num, end_seq_num) = (*resend_range.start(), *resend_range.end());
+                if end_seq_num != 0 && new_seq_no > end_seq_num {
+                    info!(
+                        "Resend request from {begin_seq_num} to {end_seq_num} has been satisfied"
+                    );
+                    self.complete_resend_range(&mut state, new_seq_no);
+                }
+            }
+        } else {
             let reject_reason = SessionRejectReason::ValueIsIncorrect;
             let tag = FieldTag::NewSeqNo as i64;
-            let text = format!("{reject_reason:?} (tag={tag}) - NewSeqNum too low");
-            self.send_reject(
+            if is_gap_fill {
+                // Rejected GapFill still consumes its MsgSeqNum<34>
+                state.incr_next_target_msg_seq_num();
+            }
+            let text = if is_gap_fill || new_seq_no < next_target_msg_seq_num {
+                format!("{reject_reason:?} (tag={tag}) - NewSeqNum too low")
+            } else {
+                format!("{reject_reason:?} (tag={tag}) - NewSeqNum not confirmed")
+            };
+            let report = self.send_reject(
                 &mut state,
                 Some(ref_msg_type),
                 ref_seq_num,
                 reject_reason,
                 FixString::from_ascii_lossy(text.into_bytes()),
                 Some(tag),
+                raw_message,
             );
+            drop(state);
+            self.emit_reject(report).await;
         }
 
         Ok(())
     }
 
-    async fn on_logout(&self, message: Box<FixtMessage>) -> Result<DisconnectReason, VerifyError> {
+    /// Ask application to accept SequenceReset<4> in Reset mode.
+    async fn confirm_sequence_reset(
+        &self,
+        next_target_msg_seq_num: SeqNum,
+        new_seq_no: SeqNum,
+    ) -> bool {
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        let confirmation = async {
+            self.emitter
+                .send(FixEventInternal::SequenceResetConfirm {
+                    session_id: self.session_settings.session_id.clone(),
+                    next_target_msg_seq_num,
+                    new_seq_no,
+                    sender: Some(sender),
+                })
+                .await;
+            // Reject when confirmation was dropped without decision
+            receiver.await.unwrap_or(false)
+        };
+        let timeout = self.session_settings.sequence_reset_confirm_timeout;
+        match tokio::time::timeout(timeout, confirmation).await {
+            Ok(accept) => accept,
+            Err(_) => {
+                warn!(
+                    "SequenceReset<4> to {new_seq_no} not confirmed within {timeout:?}, \
+                     accepted only if higher than {next_target_msg_seq_num}"
+                );
+                new_seq_no > next_target_msg_seq_num
+            }
+        }
+    }
+
+    async fn on_logout(
+        &self,
+        message: Box<FixtMessage>,
+    ) -> Result<Option<DisconnectReason>, VerifyError> {
+        self.sender.set_logged_out();
+        let session_status = match *message.body {
+            Message::Logout(ref logout) => logout
+                .session_status
+                .map(|status| (MsgType::Logout, status, logout.text.clone())),
+            _ => None,
+        };
+        if let Some(grace_period) = self.session_settings.logout_resend_grace_period {
+            let resend_pending = {
+                let state = self.state.borrow();
+                !state.logout_sent()
+                    && state.pending_logout().is_none()
+                    && state.resend_range().is_some()
+            };
+            if resend_pending {
+                // Logout<5> is not queued behind missing messages, it's
+                // answered once they are resent
+                if self.session_settings.verify_logout {
+                    self.verify(message, false, true).await?;
+                } else if let Err(e) = self.verify(message, false, false).await {
+                    error!("logout verification failed: {e}");
+                }
+                self.emit_session_status(session_status).await;
+                info!("received logout request, response delayed until resend completes");
+                self.state
+                    .borrow_mut()
+                    .set_pending_logout(Some(Instant::now() + grace_period));
+                return Ok(None);
+            }
+        }
+
         if self.session_settings.verify_logout {
             self.verify(message, true, true).await?;
         } else if let Err(e) = self.verify(message, false, false).await {
             // Nothing more we can do as client is disconnecting anyway
             error!("logout failed: {e}");
         }
+        self.emit_session_status(session_status).await;
 
         let mut state = self.state.borrow_mut();
         let disconnect_reason = if state.logout_sent() {
@@ -761,12 +1604,7 @@ impl<S: MessagesStorage> Session<S> {
             DisconnectReason::LocalRequestedLogout
         } else {
             info!("received logout request");
-            self.send_logout(
-                &mut state,
-                Some(SessionStatus::SessionLogoutComplete),
-                Some(FixString::from_ascii_lossy(b"Responding".to_vec())),
-            );
-            info!("sending logout response");
+            self.send_logout_response(&mut state);
             DisconnectReason::RemoteRequestedLogout
         };
 
@@ -775,7 +1613,66 @@ impl<S: MessagesStorage> Session<S> {
             state.reset();
         }
 
-        Ok(disconnect_reason)
+        Ok(Some(disconnect_reason))
+    }
+
+    fn send_logout_response(&self, state: &mut State<S>) {
+        self.send_logout(
+            state,
+            Some(SessionStatus::SessionLogoutComplete),
+            Some(FixString::from_ascii_lossy(b"Responding".to_vec())),
+        );
+        info!("sending logout response");
+    }
+
+    /// Send delayed Logout<5> response.
+    async fn end_logout_grace(&self, outcome: LogoutGraceOutcome) -> DisconnectReason {
+        {
+            let mut state = self.state.borrow_mut();
+            state.set_pending_logout(None);
+            self.send_logout_response(&mut state);
+            if outcome == LogoutGraceOutcome::ResendCompleted {
+                // Gap is filled, so Logout<5> is the next expected message
+                state.incr_next_target_msg_seq_num();
+            }
+            if self.session_settings.reset_on_logout {
+                state.reset();
+            }
+        }
+
+        self.emitter
+            .send(FixEventInternal::LogoutGrace(
+                self.session_settings.session_id.clone(),
+                outcome,
+            ))
+            .await;
+
+        DisconnectReason::RemoteRequestedLogout
+    }
+
+    /// Emit SessionStatus<1409> received in Logon<A> or Logout<5>.
+    async fn emit_session_status(
+        &self,
+        session_status: Option<(MsgType, SessionStatus, Option<FixString>)>,
+    ) {
+        if let Some((msg_type, status, text)) = session_status {
+            self.emitter
+                .send(FixEventInternal::SessionStatus(
+                    self.session_settings.session_id.clone(),
+                    Box::new(SessionStatusReport {
+                        msg_type,
+                        status,
+                        text,
+                    }),
+                ))
+                .await;
+        }
+    }
+
+    /// Grace period of delayed Logout<5> response elapsed.
+    pub async fn on_logout_grace_expired(&self) -> DisconnectReason {
+        warn!("resend not completed within logout grace period");
+        self.end_logout_grace(LogoutGraceOutcome::Expired).await
     }
 
     #[instrument(level = "trace", skip_all, err, ret)]
@@ -788,6 +1685,7 @@ impl<S: MessagesStorage> Session<S> {
     ) -> Result<Option<DisconnectReason>, VerifyError> {
         let (
             enabled,
+            stopped,
             initiate,
             should_send_logon,
             reset_received,
@@ -795,6 +1693,8 @@ impl<S: MessagesStorage> Session<S> {
             reset_seq_num_flag,
             heart_bt_int,
             next_expected_msg_seq_num,
+            test_logon,
+            session_status,
         ) = {
             let state = self.state.borrow_mut();
 
@@ -803,6 +1703,7 @@ impl<S: MessagesStorage> Session<S> {
             };
             (
                 state.enabled(),
+                state.stopped(),
                 state.initiate(),
                 state.should_send_logon(),
                 state.reset_received(),
@@ -810,20 +1711,80 @@ impl<S: MessagesStorage> Session<S> {
                 logon.reset_seq_num_flag.unwrap_or(false),
                 logon.heart_bt_int,
                 logon.next_expected_msg_seq_num,
+                logon.test_message_indicator.unwrap_or(false),
+                logon
+                    .session_status
+                    .map(|status| (MsgType::Logon, status, logon.text.clone())),
             )
         };
 
+        let msg_seq_num = message.header.msg_seq_num;
+        let logon_failed = |tag: Option<FieldTag>, text: &str| {
+            error!("{text}");
+            self.set_logout_details(LogoutDetails {
+                tag: tag.map(|tag| tag as TagNum),
+                msg_type: Some(MsgType::Logon.as_fix_str().to_owned()),
+                msg_seq_num: Some(msg_seq_num),
+                text: text.to_owned(),
+                ..Default::default()
+            });
+        };
+
+        if stopped {
+            logon_failed(None, "Session is stopped");
+            return Ok(Some(DisconnectReason::InvalidLogonState));
+        }
+
         if !enabled {
-            error!("Session is not enabled for logon");
+            logon_failed(None, "Session is not enabled for logon");
             return Ok(Some(DisconnectReason::InvalidLogonState));
         }
 
         if !self.is_logon_time(message.header.sending_time) {
-            error!("Received logon outside of valid logon time");
+            logon_failed(
+                Some(FieldTag::SendingTime),
+                "Received logon outside of valid logon time",
+            );
             return Ok(Some(DisconnectReason::InvalidLogonState));
         }
 
-        let msg_seq_num = message.header.msg_seq_num;
+        if let Some(logon_replay) = &self.session_settings.logon_replay {
+            let Message::Logon(ref logon) = *message.body else {
+                unreachable!()
+            };
+            let result = self.state.borrow_mut().logon_guard_mut().check(
+                logon_replay,
+                message.header.sending_time,
+                logon.raw_data.as_ref().map(|raw_data| raw_data.as_ref()),
+                Utc::now(),
+            );
+            if let Err(err) = result {
+                let tag = match err {
+                    StaleLogon::TooOld | StaleLogon::FromFuture => FieldTag::SendingTime,
+                    StaleLogon::MissingNonce | StaleLogon::ReplayedNonce => FieldTag::RawData,
+                };
+                logon_failed(Some(tag), &err.to_string());
+                return Ok(Some(DisconnectReason::InvalidLogonState));
+            }
+        }
+
+        let test_peer = test_logon
+            && !self.session_settings.test_session
+            && match self.session_settings.test_messages {
+                TestMessagesPolicy::Accept => false,
+                TestMessagesPolicy::Reject => {
+                    logon_failed(
+                        Some(FieldTag::TestMessageIndicator),
+                        "Test Logon<A> received by production session",
+                    );
+                    return Ok(Some(DisconnectReason::InvalidLogonState));
+                }
+                TestMessagesPolicy::Segregate => {
+                    warn!("Test Logon<A> received, application messages are segregated");
+                    true
+                }
+            };
+        self.state.borrow_mut().set_test_peer(test_peer);
 
         let enable_next_expected_msg_seq_num =
             self.session_settings.enable_next_expected_msg_seq_num
@@ -842,10 +1803,69 @@ impl<S: MessagesStorage> Session<S> {
         }
 
         if should_send_logon && !reset_received {
-            error!("Received logon response before sending request");
+            logon_failed(None, "Received logon response before sending request");
             return Ok(Some(DisconnectReason::InvalidLogonState));
         }
 
+        if initiate {
+            if let Some(mismatch) = self.check_logon_response(&message) {
+                let text = format!(
+                    "Unexpected value of tag {}: expected {}, received {}",
+                    mismatch.tag,
+                    mismatch.expected.as_utf8(),
+                    mismatch.received.as_utf8()
+                );
+                logon_failed(
+                    FieldTag::from_tag_num(mismatch.tag),
+                    &format!("Logon response rejected: {text}"),
+                );
+                self.send_logout(
+                    &mut self.state.borrow_mut(),
+                    None,
+                    Some(FixString::from_ascii_lossy(text.into_bytes())),
+                );
+                self.emitter
+                    .send(FixEventInternal::LogonMismatch(
+                        self.session_settings.session_id.clone(),
+                        mismatch,
+                    ))
+                    .await;
+                return Ok(Some(DisconnectReason::InvalidLogonState));
+            }
+        }
+
+        if let Some(mismatch) = self.check_dictionary_fingerprint(&message) {
+            let text = format!(
+                "Dictionary fingerprint mismatch: expected {}, received {}",
+                mismatch.expected.as_utf8(),
+                mismatch.received.as_utf8()
+            );
+            let refuse = self
+                .session_settings
+                .dictionary_fingerprint
+                .as_ref()
+                .is_some_and(|check| check.refuse_mismatch);
+            if refuse {
+                logon_failed(Some(FieldTag::RawData), &text);
+                self.send_logout(
+                    &mut self.state.borrow_mut(),
+                    None,
+                    Some(FixString::from_ascii_lossy(text.into_bytes())),
+                );
+            } else {
+                warn!("{text}");
+            }
+            self.emitter
+                .send(FixEventInternal::LogonMismatch(
+                    self.session_settings.session_id.clone(),
+                    mismatch,
+                ))
+                .await;
+            if refuse {
+                return Ok(Some(DisconnectReason::InvalidLogonState));
+            }
+        }
+
         if !initiate && self.session_settings.reset_on_logon {
             self.state.borrow_mut().reset();
         }
@@ -869,7 +1889,7 @@ impl<S: MessagesStorage> Session<S> {
                             (expected {next_sender_msg_seq_num}, \
                              got {next_expected_msg_seq_num})",
                     );
-                    error!(error_msg);
+                    logon_failed(Some(FieldTag::NextExpectedMsgSeqNum), &error_msg);
                     let err = FixString::from_ascii_lossy(error_msg.into_bytes());
                     self.send_logout(
                         &mut state,
@@ -907,6 +1927,18 @@ impl<S: MessagesStorage> Session<S> {
             }
         } else {
             info!("Received logon response");
+            match session_status {
+                Some((_, SessionStatus::SessionPasswordChanged, _)) => {
+                    if state.confirm_new_password() {
+                        info!("Password changed");
+                    }
+                }
+                Some((_, SessionStatus::NewSessionPasswordDoesNotComplyWithPolicy, _)) => {
+                    warn!("New password rejected");
+                    state.set_new_password_sent(None);
+                }
+                _ => state.set_new_password_sent(None),
+            }
         }
 
         state.set_reset_sent(false);
@@ -928,7 +1960,7 @@ impl<S: MessagesStorage> Session<S> {
                 state.next_target_msg_seq_num()
             );
 
-            state.enqueue_msg(
+            state.enqueue_placeholder_msg(
                 // No need to clone input message. Pass empty message
                 // as it will be skipped during enqueued messages processing.
                 Box::new(FixtMessage {
@@ -946,38 +1978,30 @@ impl<S: MessagesStorage> Session<S> {
             state.incr_next_target_msg_seq_num();
         }
 
+        let mut resend_report = None;
         if enable_next_expected_msg_seq_num {
             if let Some(next_expected_msg_seq_num) = next_expected_msg_seq_num {
                 // is the 789 lower (we checked for higher previously) than our next message after receiving the logon
                 if next_expected_msg_seq_num != next_sender_msg_num_at_logon_received {
-                    let mut end_seq_no = next_sender_msg_num_at_logon_received;
-
-                    // TODO: self.resend_range() will handle this !!!
-                    if !self.session_settings.persist {
-                        end_seq_no += 1;
-                        let next = state.next_sender_msg_seq_num();
-                        if end_seq_no > next {
-                            end_seq_no = next;
-                        }
-                        info!(
-                            "Received implicit ResendRequest via Logon FROM: {next_expected_msg_seq_num}, \
-                             TO: {next_sender_msg_num_at_logon_received} will be reset"
-                        );
-                        self.send_sequence_reset(next_expected_msg_seq_num, end_seq_no);
-                    } else {
-                        // resend missed messages
-                        info!(
-                            "Received implicit ResendRequest via Logon FROM: {next_expected_msg_seq_num} \
-                             TO: {next_sender_msg_num_at_logon_received} will be resent"
-                        );
-                        self.resend_range(&mut state, next_expected_msg_seq_num, end_seq_no)
-                    }
+                    info!(
+                        "Received implicit ResendRequest via Logon FROM: {next_expected_msg_seq_num} \
+                         TO: {next_sender_msg_num_at_logon_received}"
+                    );
+                    resend_report = Some(self.resend_range(
+                        &mut state,
+                        next_expected_msg_seq_num,
+                        next_sender_msg_num_at_logon_received,
+                    ));
                 }
             }
         }
 
-        if Self::is_logged_on(&state) {
-            drop(state);
+        let is_logged_on = Self::is_logged_on(&state);
+        drop(state);
+
+        self.emit_session_status(session_status).await;
+
+        if is_logged_on {
             self.emitter
                 .send(FixEventInternal::Logon(
                     self.session_settings.session_id.clone(),
@@ -986,6 +2010,10 @@ impl<S: MessagesStorage> Session<S> {
                 .await;
         }
 
+        if let Some(resend_report) = resend_report {
+            self.emit_resent(resend_report).await;
+        }
+
         ret
     }
 
@@ -1001,11 +2029,19 @@ impl<S: MessagesStorage> Session<S> {
     #[expect(clippy::await_holding_refcell_ref)]
     // Make sure `state` is dropped before await points, see
     // https://github.com/rust-lang/rust-clippy/issues/6353
-    async fn on_message_in_impl(&self, msg: Box<FixtMessage>) -> Option<DisconnectReason> {
+    async fn on_message_in_impl(
+        &self,
+        msg: Box<FixtMessage>,
+        field_errors: Option<FieldErrors>,
+    ) -> Option<DisconnectReason> {
         let msg_type = msg.header.msg_type;
         let msg_seq_num = msg.header.msg_seq_num;
         trace!(msg_type = format!("{msg_type:?}<{}>", msg_type.as_fix_str()));
 
+        if let Err(disconnect_reason) = self.verify_begin_string(&msg).await {
+            return disconnect_reason;
+        }
+
         let result = match *msg.body {
             Message::Heartbeat(ref _heartbeat) => self.on_heartbeat(msg).await,
             Message::TestRequest(ref _test_request) => self.on_test_request(msg).await,
@@ -1013,7 +2049,8 @@ impl<S: MessagesStorage> Session<S> {
             Message::Reject(ref _reject) => self.on_reject(msg).await,
             Message::SequenceReset(ref _sequence_reset) => self.on_sequence_reset(msg).await,
             Message::Logout(ref _logout) => match self.on_logout(msg).await {
-                Ok(disconnect_reason) => return Some(disconnect_reason),
+                Ok(Some(disconnect_reason)) => return Some(disconnect_reason),
+                Ok(None) => Ok(()),
                 Err(e) => Err(e),
             },
             Message::Logon(ref _logon) => match self.on_logon(msg).await {
@@ -1023,10 +2060,13 @@ impl<S: MessagesStorage> Session<S> {
                 Ok(None) => Ok(()),
                 Err(e) => Err(e),
             },
-            _ => self
-                .verify(msg, true, true)
-                .await
-                .map(|_| self.state.borrow_mut().incr_next_target_msg_seq_num()),
+            _ => match field_errors {
+                Some(field_errors) => self.verify_partial(msg, field_errors).await,
+                None => self
+                    .verify(msg, true, true)
+                    .await
+                    .map(|_| self.state.borrow_mut().incr_next_target_msg_seq_num()),
+            },
         };
 
         match result {
@@ -1054,10 +2094,24 @@ impl<S: MessagesStorage> Session<S> {
                 reason,
                 tag,
                 logout,
+                raw_message,
+                comp_ids,
             }) => {
+                if logout {
+                    let (sender_comp_id, target_comp_id) = comp_ids.unzip();
+                    self.set_logout_details(LogoutDetails {
+                        reject_reason: Some(reason),
+                        tag: tag.map(|t| t as TagNum),
+                        msg_type: Some(msg_type.as_fix_str().to_owned()),
+                        msg_seq_num: Some(msg_seq_num),
+                        sender_comp_id,
+                        target_comp_id,
+                        text: format!("Message rejected with logout ({reason:?})"),
+                    });
+                }
                 let mut state = self.state().borrow_mut();
                 let tag_as_i64 = tag.map(|t| t as i64);
-                self.send_reject(
+                let report = self.send_reject(
                     &mut state,
                     Some(msg_type.as_fix_str().to_owned()),
                     msg_seq_num,
@@ -1068,35 +2122,85 @@ impl<S: MessagesStorage> Session<S> {
                         FixString::from_ascii_lossy(format!("{reason:?}").into_bytes())
                     },
                     tag_as_i64,
+                    raw_message.clone(),
                 );
 
                 self.emitter
-                    .send(FixEventInternal::DeserializeError(
-                        self.session_id().clone(),
-                        DeserializeError::Reject {
-                            msg_type: Some(msg_type.as_fix_str().to_fix_string()),
-                            seq_num: msg_seq_num,
-                            tag: tag.map(|t| t as u16),
-                            reason,
-                        },
-                    ))
+                    .send_at(
+                        FixEventInternal::DeserializeError(
+                            self.session_id().clone(),
+                            DeserializeError::Reject {
+                                msg_type: Some(msg_type.as_fix_str().to_fix_string()),
+                                seq_num: msg_seq_num,
+                                tag: tag.map(|t| t as u16),
+                                reason,
+                                raw_message,
+                                duplicate: None,
+                            },
+                        ),
+                        self.input_timestamp(),
+                    )
                     .await;
+                self.emit_reject(report).await;
 
                 if logout {
                     self.send_logout(&mut state, None, None);
                 }
             }
-            Err(e @ VerifyError::SeqNumTooLow { .. }) => {
-                let mut state = self.state.borrow_mut();
-                self.send_logout(
-                    &mut state,
-                    Some(SessionStatus::ReceivedMsgSeqNumTooLow),
-                    Some(FixString::from_ascii_lossy(e.to_string().into_bytes())),
-                );
-                return Some(DisconnectReason::MsgSeqNumTooLow);
-            }
-            Err(VerifyError::InvalidLogonState) => {
+            Err(
+                e @ VerifyError::SeqNumTooLow {
+                    msg_seq_num,
+                    next_target_msg_seq_num,
+                },
+            ) => match &self.session_settings.msg_seq_num_too_low {
+                MsgSeqNumTooLowAction::Logout { text, disconnect } => {
+                    let text = match text {
+                        Some(template) => MsgSeqNumTooLowAction::logout_text(
+                            template,
+                            next_target_msg_seq_num,
+                            msg_seq_num,
+                        ),
+                        None => e.to_string(),
+                    };
+                    self.set_logout_details(LogoutDetails {
+                        tag: Some(FieldTag::MsgSeqNum as TagNum),
+                        msg_type: Some(msg_type.as_fix_str().to_owned()),
+                        msg_seq_num: Some(msg_seq_num),
+                        text: text.clone(),
+                        ..Default::default()
+                    });
+                    let mut state = self.state.borrow_mut();
+                    self.send_logout(
+                        &mut state,
+                        Some(SessionStatus::ReceivedMsgSeqNumTooLow),
+                        Some(FixString::from_ascii_lossy(text.into_bytes())),
+                    );
+                    if *disconnect {
+                        return Some(DisconnectReason::MsgSeqNumTooLow);
+                    }
+                }
+                MsgSeqNumTooLowAction::Disconnect => {
+                    self.set_logout_details(LogoutDetails {
+                        tag: Some(FieldTag::MsgSeqNum as TagNum),
+                        msg_type: Some(msg_type.as_fix_str().to_owned()),
+                        msg_seq_num: Some(msg_seq_num),
+                        text: e.to_string(),
+                        ..Default::default()
+                    });
+                    return Some(DisconnectReason::MsgSeqNumTooLow);
+                }
+                MsgSeqNumTooLowAction::Continue => {
+                    warn!("{e}, message ignored");
+                }
+            },
+            Err(e @ VerifyError::InvalidLogonState) => {
                 error!("disconnecting because of invalid logon state");
+                self.set_logout_details(LogoutDetails {
+                    msg_type: Some(msg_type.as_fix_str().to_owned()),
+                    msg_seq_num: Some(msg_seq_num),
+                    text: e.to_string(),
+                    ..Default::default()
+                });
                 return Some(DisconnectReason::InvalidLogonState);
             }
             Err(VerifyError::UserForcedReject {
@@ -1107,14 +2211,16 @@ impl<S: MessagesStorage> Session<S> {
                 ref_tag_id,
             }) => {
                 warn!("User rejected ({reason:?}: {text})");
-                self.send_reject(
+                let report = self.send_reject(
                     &mut self.state().borrow_mut(),
                     Some(ref_msg_type),
                     ref_seq_num,
                     reason,
                     text,
                     ref_tag_id,
+                    None,
                 );
+                self.emit_reject(report).await;
             }
             Err(VerifyError::UserForcedLogout {
                 session_status,
@@ -1125,6 +2231,15 @@ impl<S: MessagesStorage> Session<S> {
                     "User rejected with Logout<5> ({})",
                     text.as_ref().map(FixString::as_utf8).unwrap_or_default()
                 );
+                self.set_logout_details(LogoutDetails {
+                    msg_type: Some(msg_type.as_fix_str().to_owned()),
+                    msg_seq_num: Some(msg_seq_num),
+                    text: text
+                        .as_ref()
+                        .map(|text| text.as_utf8().to_owned())
+                        .unwrap_or_default(),
+                    ..Default::default()
+                });
                 let mut state = self.state.borrow_mut();
                 self.send_logout(&mut state, session_status, text);
                 if disconnect {
@@ -1133,21 +2248,135 @@ impl<S: MessagesStorage> Session<S> {
             }
             Err(VerifyError::UserForcedDisconnect { reason }) => {
                 error!("User disconnected: {reason:?}");
+                self.set_logout_details(LogoutDetails {
+                    msg_type: Some(msg_type.as_fix_str().to_owned()),
+                    msg_seq_num: Some(msg_seq_num),
+                    text: reason.unwrap_or_default(),
+                    ..Default::default()
+                });
                 return Some(DisconnectReason::UserForcedDisconnect);
             }
+            Err(VerifyError::Unsupported {
+                ref_msg_type,
+                ref_seq_num,
+                business_reject_ref_id,
+            }) => {
+                warn!(
+                    "Unsupported message type {} ({:?})",
+                    ref_msg_type.as_utf8(),
+                    self.session_settings.unsupported_msg_type
+                );
+                match self.session_settings.unsupported_msg_type {
+                    UnsupportedMsgTypePolicy::BusinessReject => {
+                        let mut state = self.state.borrow_mut();
+                        if ref_seq_num == state.next_target_msg_seq_num() {
+                            state.incr_next_target_msg_seq_num();
+                        }
+                        self.send(Box::new(Message::BusinessMessageReject(
+                            BusinessMessageReject {
+                                ref_seq_num: Some(ref_seq_num),
+                                ref_msg_type,
+                                business_reject_ref_id,
+                                business_reject_reason:
+                                    BusinessRejectReason::UnsupportedMessageType,
+                                text: Some(FixString::from_ascii_lossy(
+                                    b"Unsupported Message Type".to_vec(),
+                                )),
+                                ..Default::default()
+                            },
+                        )));
+                    }
+                    UnsupportedMsgTypePolicy::Reject => {
+                        let report = self.send_reject(
+                            &mut self.state.borrow_mut(),
+                            Some(ref_msg_type),
+                            ref_seq_num,
+                            SessionRejectReason::InvalidMsgtype,
+                            FixString::from_ascii_lossy(b"Unsupported Message Type".to_vec()),
+                            None,
+                            None,
+                        );
+                        self.emit_reject(report).await;
+                    }
+                    UnsupportedMsgTypePolicy::Ignore => {
+                        let mut state = self.state.borrow_mut();
+                        if ref_seq_num == state.next_target_msg_seq_num() {
+                            state.incr_next_target_msg_seq_num();
+                        }
+                    }
+                }
+            }
         }
 
         None
     }
 
-    pub async fn on_message_in(&self, msg: Box<FixtMessage>) -> Option<DisconnectReason> {
-        if let Some(disconnect_reason) = self.on_message_in_impl(msg).await {
+    pub async fn on_message_in(
+        &self,
+        msg: Box<FixtMessage>,
+        raw_message: Bytes,
+    ) -> Option<DisconnectReason> {
+        self.on_message_in_with_field_errors(msg, raw_message, None)
+            .await
+    }
+
+    /// Handle application message with invalid values of optional fields
+    /// skipped, according to [`FieldErrorsPolicy`].
+    pub async fn on_partial_message_in(
+        &self,
+        msg: Box<FixtMessage>,
+        field_errors: Vec<FieldError>,
+        raw_message: Bytes,
+    ) -> Option<DisconnectReason> {
+        // Messages received out of order are queued and processed later
+        // without field errors, so they are rejected as a whole
+        let queued = msg.header.msg_seq_num > self.state.borrow().next_target_msg_seq_num();
+        if self.session_settings.field_errors == FieldErrorsPolicy::Reject || queued {
+            let first_error = field_errors[0];
+            return self
+                .on_deserialize_error(DeserializeError::Reject {
+                    msg_type: Some(msg.header.msg_type.as_fix_str().to_owned()),
+                    seq_num: msg.header.msg_seq_num,
+                    tag: Some(first_error.tag),
+                    reason: first_error.reason,
+                    raw_message: Some(raw_message),
+                    duplicate: None,
+                })
+                .await;
+        }
+        self.on_message_in_with_field_errors(
+            msg,
+            raw_message.clone(),
+            Some(FieldErrors {
+                errors: field_errors,
+                raw_message,
+            }),
+        )
+        .await
+    }
+
+    async fn on_message_in_with_field_errors(
+        &self,
+        msg: Box<FixtMessage>,
+        raw_message: Bytes,
+        field_errors: Option<FieldErrors>,
+    ) -> Option<DisconnectReason> {
+        self.check_latency_budget(
+            LatencyStage::Decode,
+            self.decode_time.get(),
+            msg.header.msg_type,
+            msg.header.msg_seq_num,
+        )
+        .await;
+        *self.raw_message_in.borrow_mut() = Some(raw_message);
+        if let Some(disconnect_reason) = self.on_message_in_impl(msg, field_errors).await {
             return Some(disconnect_reason);
         }
         loop {
-            let Some(msg) = self.state.borrow_mut().retrieve_msg() else {
+            let Some((msg, raw_message)) = self.state.borrow_mut().retrieve_msg() else {
                 break;
             };
+            *self.raw_message_in.borrow_mut() = raw_message;
 
             info!("Processing queued message {}", msg.header.msg_seq_num);
 
@@ -1155,13 +2384,210 @@ impl<S: MessagesStorage> Session<S> {
                 // Logon and ResendRequest processing has already been done,
                 // just increment the target sequence nummber.
                 self.state.borrow_mut().incr_next_target_msg_seq_num();
-            } else if let Some(disconnect_reason) = self.on_message_in_impl(msg).await {
+            } else if let Some(disconnect_reason) = self.on_message_in_impl(msg, None).await {
                 return Some(disconnect_reason);
             }
         }
+        let resend_completed = {
+            let state = self.state.borrow();
+            state.pending_logout().is_some() && state.resend_range().is_none()
+        };
+        if resend_completed {
+            info!("resend completed, responding to logout");
+            return Some(
+                self.end_logout_grace(LogoutGraceOutcome::ResendCompleted)
+                    .await,
+            );
+        }
         None
     }
 
+    /// Verify BeginString<8> of received message, returns `Err` when message
+    /// should not be processed further, with disconnect reason if session
+    /// is going to be disconnected.
+    async fn verify_begin_string(&self, msg: &FixtMessage) -> Result<(), Option<DisconnectReason>> {
+        let expected = self.session_settings.begin_string();
+        if msg.header.begin_string == expected {
+            return Ok(());
+        }
+        let policy = self.session_settings.begin_string_mismatch;
+        let msg_type = msg.msg_type().as_fix_str().to_owned();
+        let msg_seq_num = msg.header.msg_seq_num;
+        let text = format!(
+            "BeginString mismatch, expected {expected}, got {}",
+            msg.header.begin_string
+        );
+        error!("{text} ({policy:?})");
+        self.emitter
+            .send(FixEventInternal::BeginStringMismatch(
+                self.session_settings.session_id.clone(),
+                Box::new(BeginStringMismatch {
+                    msg_seq_num,
+                    msg_type: msg_type.clone(),
+                    expected,
+                    received: msg.header.begin_string.clone(),
+                    policy,
+                }),
+            ))
+            .await;
+
+        let details = || LogoutDetails {
+            tag: Some(FieldTag::BeginString as TagNum),
+            msg_type: Some(msg_type.clone()),
+            msg_seq_num: Some(msg_seq_num),
+            text: text.clone(),
+            ..Default::default()
+        };
+        match policy {
+            BeginStringMismatchPolicy::Logout => {
+                self.set_logout_details(details());
+                self.send_logout(
+                    &mut self.state.borrow_mut(),
+                    None,
+                    Some(FixString::from_ascii_lossy(text.clone().into_bytes())),
+                );
+                Err(Some(DisconnectReason::BeginStringMismatch))
+            }
+            BeginStringMismatchPolicy::Disconnect => {
+                self.set_logout_details(details());
+                Err(Some(DisconnectReason::BeginStringMismatch))
+            }
+            BeginStringMismatchPolicy::Reject => {
+                let report = self.send_reject(
+                    &mut self.state.borrow_mut(),
+                    Some(msg_type.clone()),
+                    msg_seq_num,
+                    SessionRejectReason::ValueIsIncorrect,
+                    FixString::from_ascii_lossy(text.clone().into_bytes()),
+                    Some(FieldTag::BeginString as i64),
+                    None,
+                );
+                self.emit_reject(report).await;
+                Err(None)
+            }
+            BeginStringMismatchPolicy::Ignore => Ok(()),
+        }
+    }
+
+    /// Handle outgoing message which failed to serialize, according
+    /// to [`SessionSettings::serialize_failure`].
+    pub(crate) async fn on_serialize_failed(&self, msg: &FixtMessage, error: SerializeError) {
+        let policy = self.session_settings.serialize_failure;
+        error!(
+            "failed to serialize message {}: {error} ({policy:?})",
+            msg.header.msg_seq_num
+        );
+        self.emitter
+            .send(FixEventInternal::SerializeFailed(
+                self.session_settings.session_id.clone(),
+                Box::new(SerializeFailure {
+                    msg: msg.clone(),
+                    tag: error.tag(),
+                    error: error.to_string(),
+                    policy,
+                }),
+            ))
+            .await;
+        match policy {
+            SerializeFailurePolicy::Skip => {}
+            SerializeFailurePolicy::Disconnect => {
+                self.disconnect(
+                    &mut self.state.borrow_mut(),
+                    DisconnectReason::SerializeError,
+                );
+            }
+        }
+    }
+
+    /// Handle failed write of outgoing message to messages storage, returns
+    /// `true` when message should be sent anyway.
+    pub(crate) async fn on_store_failed(&self, msg: &FixtMessage, error: StoreError) -> bool {
+        let policy = match error {
+            // Number above persisted limit would regress after crash
+            StoreError::SeqNumNotPersisted(_) => StoreFailurePolicy::Disconnect,
+            _ => self.session_settings.store_failure,
+        };
+        error!(
+            "failed to store message {}: {error} ({policy:?})",
+            msg.header.msg_seq_num
+        );
+        self.emitter
+            .send(FixEventInternal::StoreFailed(
+                self.session_settings.session_id.clone(),
+                Box::new(StoreFailure {
+                    msg_seq_num: msg.header.msg_seq_num,
+                    msg_type: msg.msg_type().as_fix_str().to_owned(),
+                    error: error.to_string(),
+                    policy,
+                }),
+            ))
+            .await;
+        match policy {
+            StoreFailurePolicy::Send => true,
+            StoreFailurePolicy::Drop => false,
+            StoreFailurePolicy::Disconnect => {
+                self.disconnect(&mut self.state.borrow_mut(), DisconnectReason::StoreError);
+                false
+            }
+        }
+    }
+
+    /// Inspect application message with [`SessionSettings::inspector`],
+    /// flagged message is reported with `FixEvent::MsgFlagged`.
+    pub(crate) async fn inspect(&self, direction: MsgDirection, msg: &FixtMessage) {
+        if !matches!(msg.msg_cat(), MsgCat::App) {
+            return;
+        }
+        let Some(findings) = self.session_settings.inspector.inspect(direction, msg) else {
+            return;
+        };
+        warn!(
+            "{direction:?} message {} flagged: {findings:?}",
+            msg.header.msg_seq_num
+        );
+        self.emitter
+            .send(FixEventInternal::MsgFlagged(
+                self.session_settings.session_id.clone(),
+                Box::new(FlaggedMsg {
+                    direction,
+                    msg: Box::new(msg.clone()),
+                    findings,
+                }),
+            ))
+            .await;
+    }
+
+    /// Report `stage` of message processing taking longer than its budget
+    /// in [`SessionSettings::latency_budgets`] with
+    /// `FixEvent::LatencyBudgetExceeded`.
+    pub(crate) async fn check_latency_budget(
+        &self,
+        stage: LatencyStage,
+        elapsed: Duration,
+        msg_type: MsgType,
+        msg_seq_num: SeqNum,
+    ) {
+        let Some(budget) = self.session_settings.latency_budgets.get(stage) else {
+            return;
+        };
+        if elapsed <= budget {
+            return;
+        }
+        warn!("{stage:?} of message {msg_seq_num} took {elapsed:?}, budget is {budget:?}");
+        self.emitter
+            .send(FixEventInternal::LatencyBudgetExceeded(
+                self.session_settings.session_id.clone(),
+                Box::new(LatencyAlert {
+                    stage,
+                    msg_seq_num,
+                    msg_type: msg_type.as_fix_str().to_owned(),
+                    elapsed,
+                    budget,
+                }),
+            ))
+            .await;
+    }
+
     pub async fn on_message_out(&self, msg: Box<FixtMessage>) -> Option<Box<FixtMessage>> {
         let (sender, receiver) = tokio::sync::oneshot::channel();
         match msg.msg_cat() {
@@ -1215,9 +2641,17 @@ impl<S: MessagesStorage> Session<S> {
         let text = FixString::from_ascii_lossy(error.to_string().into_bytes());
         error!(deserialize_error = %text);
 
-        match &error {
-            DeserializeError::GarbledMessage(reason) => error!("Garbled message: {reason}"),
+        let report = match &error {
+            DeserializeError::GarbledMessage(reason) => {
+                error!("Garbled message: {reason}");
+                None
+            }
             DeserializeError::Logout => {
+                self.set_logout_details(LogoutDetails {
+                    tag: Some(FieldTag::MsgSeqNum as TagNum),
+                    text: "MsgSeqNum(34) not found".to_owned(),
+                    ..Default::default()
+                });
                 let mut state = self.state.borrow_mut();
                 self.send_logout(
                     &mut state,
@@ -1233,26 +2667,53 @@ impl<S: MessagesStorage> Session<S> {
                 seq_num,
                 tag,
                 reason,
-            } => self.send_reject(
-                &mut self.state().borrow_mut(),
-                msg_type.clone(),
-                *seq_num,
-                *reason,
-                text,
-                tag.map(Int::from),
-            ),
-        }
+                raw_message,
+                duplicate,
+            } => {
+                if let Some(duplicate) = duplicate {
+                    error!("Duplicated {duplicate}");
+                }
+                Some(self.send_reject(
+                    &mut self.state().borrow_mut(),
+                    msg_type.clone(),
+                    *seq_num,
+                    *reason,
+                    text,
+                    tag.map(Int::from),
+                    raw_message.clone(),
+                ))
+            }
+        };
 
         self.emitter
-            .send(FixEventInternal::DeserializeError(
-                self.session_id().clone(),
-                error,
-            ))
+            .send_at(
+                FixEventInternal::DeserializeError(self.session_id().clone(), error),
+                self.input_timestamp(),
+            )
             .await;
+        if let Some(report) = report {
+            self.emit_reject(report).await;
+        }
 
         None
     }
 
+    /// Report garbled message with CheckSum<10> or BodyLength<9> mismatch
+    /// enabled in [`Settings::framing_errors`], it's handled with
+    /// `on_deserialize_error()` afterwards.
+    pub(crate) async fn on_framing_error(&self, framing_error: Box<FramingError>) {
+        error!(
+            "{:?}, received data:\n{}",
+            framing_error.mismatch, framing_error.hexdump
+        );
+        self.emitter
+            .send_at(
+                FixEventInternal::FramingError(self.session_id().clone(), framing_error),
+                self.input_timestamp(),
+            )
+            .await;
+    }
+
     pub async fn on_in_timeout(self: &Rc<Self>) -> bool {
         trace!("on_in_timeout");
 
@@ -1276,6 +2737,24 @@ impl<S: MessagesStorage> Session<S> {
         false
     }
 
+    /// Handle socket with no data read for `idle`, according
+    /// to [`Settings::read_idle`].
+    pub(crate) async fn on_read_idle(&self, idle: Duration) -> ReadIdleAction {
+        let action = self
+            .settings
+            .read_idle
+            .map(|read_idle| read_idle.action)
+            .unwrap_or_default();
+        warn!("no data read for {idle:?} ({action:?})");
+        self.emitter
+            .send(FixEventInternal::ReadIdle(
+                self.session_settings.session_id.clone(),
+                idle,
+            ))
+            .await;
+        action
+    }
+
     pub async fn on_out_timeout(self: &Rc<Self>) {
         trace!("on_out_timeout");
         self.send(Box::new(Message::Heartbeat(Heartbeat {
@@ -1,4 +1,4 @@
-use core::fmt;
+use core::{fmt, str::FromStr};
 
 use easyfix_messages::{
     fields::{FixStr, FixString},
@@ -6,7 +6,18 @@ use easyfix_messages::{
 };
 use serde::Deserialize;
 
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq)]
+/// Session identifier, from local side perspective.
+///
+/// String form is `BeginString:SenderCompID->TargetCompID[/Qualifier]`,
+/// e.g. `FIX.4.4:SERVER->CLIENT` or `FIXT.1.1:SERVER->CLIENT/backup`,
+/// it's used by [`Display`](fmt::Display) and [`FromStr`], and accepted
+/// by deserializer besides map of fields. Formatted id parses back to
+/// equal one, as long as TargetCompID<56> doesn't contain `/`.
+///
+/// Ids are compared, ordered and hashed by all four parts, in order of
+/// the string form (ordering is lexicographic by bytes of each part).
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[serde(try_from = "SessionIdRepr")]
 pub struct SessionId {
     begin_string: FixString,
     sender_comp_id: FixString,
@@ -16,18 +27,87 @@ pub struct SessionId {
 
 impl fmt::Display for SessionId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.session_qualifier.is_empty() {
-            write!(
-                f,
-                "{}: {} -> {}",
-                self.begin_string, self.sender_comp_id, self.target_comp_id
-            )
-        } else {
-            write!(
-                f,
-                "{}: {} -> {} ({})",
-                self.begin_string, self.sender_comp_id, self.target_comp_id, self.session_qualifier
-            )
+        write!(
+            f,
+            "{}:{}->{}",
+            self.begin_string, self.sender_comp_id, self.target_comp_id
+        )?;
+        if !self.session_qualifier.is_empty() {
+            write!(f, "/{}", self.session_qualifier)?;
+        }
+        Ok(())
+    }
+}
+
+/// Invalid string form of [`SessionId`].
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum ParseSessionIdError {
+    #[error("missing `:` after BeginString")]
+    MissingColon,
+    #[error("missing `->` between SenderCompID and TargetCompID")]
+    MissingArrow,
+    #[error("empty {0}")]
+    Empty(&'static str),
+    #[error("invalid {0}, control or non-ASCII character")]
+    InvalidValue(&'static str),
+}
+
+impl FromStr for SessionId {
+    type Err = ParseSessionIdError;
+
+    fn from_str(s: &str) -> Result<SessionId, ParseSessionIdError> {
+        let (begin_string, comp_ids) =
+            s.split_once(':').ok_or(ParseSessionIdError::MissingColon)?;
+        let (sender_comp_id, target) = comp_ids
+            .split_once("->")
+            .ok_or(ParseSessionIdError::MissingArrow)?;
+        let (target_comp_id, session_qualifier) = target.split_once('/').unwrap_or((target, ""));
+        let part = |value: &str, name| {
+            if value.is_empty() {
+                return Err(ParseSessionIdError::Empty(name));
+            }
+            FixString::from_ascii(value.as_bytes().to_vec())
+                .map_err(|_| ParseSessionIdError::InvalidValue(name))
+        };
+        Ok(SessionId::with_session_qualifier(
+            part(begin_string, "BeginString")?,
+            part(sender_comp_id, "SenderCompID")?,
+            part(target_comp_id, "TargetCompID")?,
+            session_qualifier.to_owned(),
+        ))
+    }
+}
+
+/// Deserialized [`SessionId`], given in string form or as map of fields.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SessionIdRepr {
+    String(String),
+    Fields {
+        begin_string: FixString,
+        sender_comp_id: FixString,
+        target_comp_id: FixString,
+        session_qualifier: String,
+    },
+}
+
+impl TryFrom<SessionIdRepr> for SessionId {
+    type Error = ParseSessionIdError;
+
+    fn try_from(repr: SessionIdRepr) -> Result<SessionId, ParseSessionIdError> {
+        match repr {
+            SessionIdRepr::String(s) => s.parse(),
+            SessionIdRepr::Fields {
+                begin_string,
+                sender_comp_id,
+                target_comp_id,
+                session_qualifier,
+            } => Ok(SessionId::with_session_qualifier(
+                begin_string,
+                sender_comp_id,
+                target_comp_id,
+                session_qualifier,
+            )),
         }
     }
 }
@@ -109,3 +189,58 @@ impl SessionId {
         self.begin_string.as_utf8().starts_with("FIXT")
     }
 }
+
+/// Route of message within session, identified with SenderSubID<50>
+/// and TargetSubID<57>, e.g. trading desk when several desks share one
+/// CompIDs pair.
+///
+/// As [`SessionId`], route is given from local side perspective, so
+/// route of received message has sub IDs swapped.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq)]
+pub struct SubIdRoute {
+    pub sender_sub_id: Option<FixString>,
+    pub target_sub_id: Option<FixString>,
+}
+
+impl SubIdRoute {
+    pub fn new(sender_sub_id: Option<FixString>, target_sub_id: Option<FixString>) -> SubIdRoute {
+        SubIdRoute {
+            sender_sub_id,
+            target_sub_id,
+        }
+    }
+
+    pub fn from_input_msg(msg: &FixtMessage) -> SubIdRoute {
+        SubIdRoute::from_input_header(&msg.header)
+    }
+
+    pub fn from_input_header(header: &Header) -> SubIdRoute {
+        SubIdRoute::new(header.target_sub_id.clone(), header.sender_sub_id.clone())
+    }
+
+    pub fn from_output_msg(msg: &FixtMessage) -> SubIdRoute {
+        SubIdRoute::from_output_header(&msg.header)
+    }
+
+    pub fn from_output_header(header: &Header) -> SubIdRoute {
+        SubIdRoute::new(header.sender_sub_id.clone(), header.target_sub_id.clone())
+    }
+
+    /// Returns `true` when sub IDs set in this route are equal to these
+    /// of `other`, sub IDs not set match any value.
+    pub fn matches(&self, other: &SubIdRoute) -> bool {
+        self.sender_sub_id
+            .as_ref()
+            .is_none_or(|sub_id| other.sender_sub_id.as_ref() == Some(sub_id))
+            && self
+                .target_sub_id
+                .as_ref()
+                .is_none_or(|sub_id| other.target_sub_id.as_ref() == Some(sub_id))
+    }
+
+    /// Set SenderSubID<50> and TargetSubID<57> of outgoing message header.
+    pub(crate) fn apply(&self, header: &mut Header) {
+        header.sender_sub_id = self.sender_sub_id.clone();
+        header.target_sub_id = self.target_sub_id.clone();
+    }
+}
@@ -0,0 +1,172 @@
+use std::{
+    cell::RefCell,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use tracing::warn;
+
+use crate::{
+    application::{FixEventInternal, SessionEvent},
+    session_id::SessionId,
+    settings::SessionLogSettings,
+};
+
+/// Append-only file, rotated when `max_size` would be exceeded.
+#[derive(Debug)]
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf, max_size: u64, max_files: usize) -> io::Result<RotatingFile> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(RotatingFile {
+            path,
+            file,
+            size,
+            max_size,
+            max_files,
+        })
+    }
+
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{n}"));
+        path.into()
+    }
+
+    /// Shift `<path>.N` files by one, dropping the oldest one, and start
+    /// new file.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.max_files == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            for n in (1..self.max_files).rev() {
+                let from = self.rotated_path(n);
+                if from.exists() {
+                    fs::rename(&from, self.rotated_path(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated_path(1))?;
+        }
+        *self = RotatingFile::open(self.path.clone(), self.max_size, self.max_files)?;
+        Ok(())
+    }
+
+    fn write(&mut self, entry: &[u8]) -> io::Result<()> {
+        let len = entry.len() as u64;
+        if self.size > 0 && self.size + len > self.max_size {
+            self.rotate()?;
+        }
+        self.file.write_all(entry)?;
+        self.size += len;
+        Ok(())
+    }
+}
+
+fn open_file(
+    settings: &SessionLogSettings,
+    session_id: &SessionId,
+    suffix: &str,
+) -> io::Result<RefCell<RotatingFile>> {
+    let mut file_name = format!(
+        "{}-{}-{}",
+        session_id.begin_string(),
+        session_id.sender_comp_id(),
+        session_id.target_comp_id()
+    );
+    if !session_id.session_qualifier().is_empty() {
+        file_name.push('-');
+        file_name.push_str(session_id.session_qualifier());
+    }
+    file_name.push_str(suffix);
+    let path = Path::new(&settings.dir).join(file_name);
+    RotatingFile::open(path, settings.max_file_size, settings.max_files).map(RefCell::new)
+}
+
+fn timestamp() -> String {
+    Utc::now().format("%Y%m%d-%H:%M:%S%.6f").to_string()
+}
+
+/// Per-session messages and events log, see [`SessionLogSettings`].
+#[derive(Debug)]
+pub(crate) struct SessionLog {
+    messages: Option<RefCell<RotatingFile>>,
+    events: Option<RefCell<RotatingFile>>,
+}
+
+impl SessionLog {
+    pub(crate) fn open(
+        settings: &SessionLogSettings,
+        session_id: &SessionId,
+    ) -> io::Result<SessionLog> {
+        Ok(SessionLog {
+            messages: settings
+                .messages
+                .then(|| open_file(settings, session_id, ".messages.log"))
+                .transpose()?,
+            events: settings
+                .events
+                .then(|| open_file(settings, session_id, ".events.log"))
+                .transpose()?,
+        })
+    }
+
+    fn write(file: &RefCell<RotatingFile>, entry: &[u8]) {
+        let mut file = file.borrow_mut();
+        if let Err(err) = file.write(entry) {
+            warn!("failed to write to {}: {err}", file.path.display());
+        }
+    }
+
+    fn message(&self, direction: &str, msg: &[u8]) {
+        if let Some(file) = &self.messages {
+            let mut entry = format!("{} {direction} ", timestamp()).into_bytes();
+            entry.extend_from_slice(msg);
+            entry.push(b'\n');
+            SessionLog::write(file, &entry);
+        }
+    }
+
+    /// Log raw inbound message.
+    pub(crate) fn message_in(&self, msg: &[u8]) {
+        self.message("IN", msg);
+    }
+
+    /// Log serialized outbound message.
+    pub(crate) fn message_out(&self, msg: &[u8]) {
+        self.message("OUT", msg);
+    }
+
+    /// Log session event, messages are skipped as they are logged
+    /// in raw form by [`SessionLog::message_in`]/[`SessionLog::message_out`].
+    pub(crate) fn event(&self, event: &FixEventInternal) {
+        let Some(file) = &self.events else {
+            return;
+        };
+        if matches!(
+            event,
+            FixEventInternal::AppMsgIn(..)
+                | FixEventInternal::AdmMsgIn(..)
+                | FixEventInternal::PartialAppMsgIn(..)
+                | FixEventInternal::TestAppMsgIn(..)
+                | FixEventInternal::AppMsgOut(..)
+                | FixEventInternal::AdmMsgOut(..)
+        ) {
+            return;
+        }
+        if let Some(event) = SessionEvent::from_internal(event) {
+            let entry = format!("{} {event:?}\n", timestamp());
+            SessionLog::write(file, entry.as_bytes());
+        }
+    }
+}
@@ -1,37 +1,88 @@
-use std::{collections::BTreeMap, ops::RangeInclusive};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    ops::RangeInclusive,
+    rc::Rc,
+};
 
+use bytes::Bytes;
 use easyfix_messages::{
-    fields::{Int, SeqNum},
+    deserializer::{raw_message, Deserializer},
+    fields::{FixString, Int, SeqNum},
     messages::FixtMessage,
 };
 use tokio::time::Instant;
-
-use crate::messages_storage::MessagesStorage;
+use tracing::{error, warn};
+
+use crate::{
+    application::SessionStateSnapshot,
+    capture::CaptureSlot,
+    logon_guard::LogonGuard,
+    messages_storage::{MessagesStorage, StoreError},
+    PauseMode, QueuedMsg,
+};
 
 #[derive(Debug)]
-struct Messages(BTreeMap<SeqNum, Box<FixtMessage>>);
+struct Messages {
+    /// Messages with their raw bytes, as received
+    decoded: BTreeMap<SeqNum, (Box<FixtMessage>, Option<Bytes>)>,
+    /// Messages kept raw in messages storage
+    spilled: BTreeSet<SeqNum>,
+}
 
 impl Messages {
     fn new() -> Messages {
-        Messages(BTreeMap::new())
+        Messages {
+            decoded: BTreeMap::new(),
+            spilled: BTreeSet::new(),
+        }
     }
 
-    fn enqueue(&mut self, seq_num: SeqNum, msg: Box<FixtMessage>) {
-        self.0.insert(seq_num, msg);
+    fn enqueue(&mut self, seq_num: SeqNum, msg: Box<FixtMessage>, raw_message: Option<Bytes>) {
+        self.spilled.remove(&seq_num);
+        self.decoded.insert(seq_num, (msg, raw_message));
     }
 
-    fn retrieve(&mut self, seq_num: SeqNum) -> Option<Box<FixtMessage>> {
-        self.0.remove(&seq_num)
+    fn spill(&mut self, seq_num: SeqNum) {
+        self.decoded.remove(&seq_num);
+        self.spilled.insert(seq_num);
+    }
+
+    fn retrieve(&mut self, seq_num: SeqNum) -> Option<(Box<FixtMessage>, Option<Bytes>)> {
+        self.decoded.remove(&seq_num)
+    }
+
+    fn retrieve_spilled(&mut self, seq_num: SeqNum) -> bool {
+        self.spilled.remove(&seq_num)
     }
 
     fn clear(&mut self) {
-        self.0.clear();
+        self.decoded.clear();
+        self.spilled.clear();
     }
 }
 
+fn decode_queued(data: &[u8]) -> Option<Box<FixtMessage>> {
+    let (_, raw_msg) = raw_message(data)
+        .map_err(|err| error!("failed to decode queued message: {err:?}"))
+        .ok()?;
+    let begin_strings = [raw_msg.begin_string.to_owned()];
+    FixtMessage::deserialize(
+        Deserializer::from_raw_message(raw_msg)
+            .with_begin_strings(&begin_strings)
+            .with_other_msg_types(),
+    )
+    .map_err(|err| error!("failed to decode queued message: {err:?}"))
+    .ok()
+}
+
 #[derive(Debug)]
 pub(crate) struct State<S> {
+    /// Inbound Logon<A> is accepted, see `AdminHandle::disable`.
     enabled: bool,
+    /// Session is logged out and doesn't log on again until started,
+    /// see `AdminHandle::stop`.
+    stopped: bool,
     received_logon: bool,
     sent_logout: bool,
     sent_logon: bool,
@@ -40,6 +91,9 @@ pub(crate) struct State<S> {
     initiate: bool,
     test_request: Int,
     resend_range: Option<RangeInclusive<SeqNum>>,
+    /// Last missing MsgSeqNum<34> when gap is requested with consecutive
+    /// ResendRequest<2> messages, see `ResendRequestPolicy::max_range`.
+    resend_gap_end: Option<SeqNum>,
     heart_bt_int: Int,
     last_sent_time: Instant,
     last_received_time: Instant,
@@ -55,7 +109,44 @@ pub(crate) struct State<S> {
     /// This value is used to populate the resendRange if necessary.
     next_expected_msg_seq_num: SeqNum,
 
+    /// Set when outbound application messages are held back,
+    /// see `Session::pause`.
+    paused: Option<PauseMode>,
+    paused_queue: VecDeque<QueuedMsg>,
+
+    /// Deadline of delayed Logout<5> response, see
+    /// `SessionSettings::logout_resend_grace_period`.
+    pending_logout: Option<Instant>,
+
+    /// Messages received and sent since session was registered,
+    /// not affected by sequence numbers reset.
+    messages_received: u64,
+    messages_sent: u64,
+
+    /// Buffer resizes since session was registered, read buffer counter
+    /// is shared with input stream of connection
+    read_buffer_resizes: Rc<Cell<u64>>,
+    serialize_buffer_resizes: u64,
+
+    /// Nonces of accepted Logon<A> messages, see
+    /// `SessionSettings::logon_replay`.
+    logon_guard: LogonGuard,
+    /// Counterparty logged on with TestMessageIndicator<464>=Y and its
+    /// messages are segregated, see `TestMessagesPolicy::Segregate`.
+    test_peer: bool,
+    /// Password changed at logon, sent instead of the one from
+    /// `SessionSettings::credentials`.
+    password: Option<FixString>,
+    /// NewPassword<925> sent in the last Logon<A>, waiting for
+    /// confirmation.
+    new_password_sent: Option<FixString>,
+    /// Raw traffic capture, shared with connections of the session,
+    /// see `AdminHandle::start_capture`.
+    capture: CaptureSlot,
+
     queue: Messages,
+    /// See [`SessionSettings::queue_spill_threshold`](crate::settings::SessionSettings::queue_spill_threshold)
+    queue_spill_threshold: Option<usize>,
     messages_storage: S,
 }
 
@@ -63,6 +154,7 @@ impl<S: MessagesStorage> State<S> {
     pub(crate) fn new(messages_storage: S) -> State<S> {
         State {
             enabled: true,
+            stopped: false,
             received_logon: false,
             sent_logout: false,
             sent_logon: false,
@@ -71,13 +163,27 @@ impl<S: MessagesStorage> State<S> {
             initiate: false,
             test_request: 0,
             resend_range: None,
+            resend_gap_end: None,
             heart_bt_int: 10,
             last_sent_time: Instant::now(),
             last_received_time: Instant::now(),
             input_timeout_cnt: 0,
             disconnected: true,
             next_expected_msg_seq_num: 0,
+            paused: None,
+            paused_queue: VecDeque::new(),
+            pending_logout: None,
+            messages_received: 0,
+            messages_sent: 0,
+            read_buffer_resizes: Rc::new(Cell::new(0)),
+            serialize_buffer_resizes: 0,
+            logon_guard: LogonGuard::new(),
+            test_peer: false,
+            password: None,
+            new_password_sent: None,
+            capture: Rc::new(RefCell::new(None)),
             queue: Messages::new(),
+            queue_spill_threshold: None,
             messages_storage,
         }
     }
@@ -88,6 +194,18 @@ impl<S: MessagesStorage> State<S> {
         self.enabled
     }
 
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn stopped(&self) -> bool {
+        self.stopped
+    }
+
+    pub fn set_stopped(&mut self, stopped: bool) {
+        self.stopped = stopped;
+    }
+
     pub fn logon_received(&self) -> bool {
         self.received_logon
     }
@@ -132,6 +250,10 @@ impl<S: MessagesStorage> State<S> {
         self.initiate
     }
 
+    pub fn set_initiate(&mut self, initiate: bool) {
+        self.initiate = initiate;
+    }
+
     pub fn set_test_request(&mut self, test_request: Int) {
         self.test_request = test_request;
     }
@@ -144,6 +266,14 @@ impl<S: MessagesStorage> State<S> {
         self.resend_range.clone()
     }
 
+    pub fn set_resend_gap_end(&mut self, resend_gap_end: Option<SeqNum>) {
+        self.resend_gap_end = resend_gap_end;
+    }
+
+    pub fn resend_gap_end(&self) -> Option<SeqNum> {
+        self.resend_gap_end
+    }
+
     pub fn heart_bt_int(&self) -> Int {
         self.heart_bt_int
     }
@@ -186,24 +316,148 @@ impl<S: MessagesStorage> State<S> {
         self.next_expected_msg_seq_num != 0
     }
 
-    pub fn enqueue_msg(&mut self, msg: Box<FixtMessage>) {
-        self.queue.enqueue(msg.header.msg_seq_num, msg);
+    pub fn set_queue_spill_threshold(&mut self, queue_spill_threshold: Option<usize>) {
+        self.queue_spill_threshold = queue_spill_threshold;
+    }
+
+    pub fn logon_guard_mut(&mut self) -> &mut LogonGuard {
+        &mut self.logon_guard
+    }
+
+    pub fn test_peer(&self) -> bool {
+        self.test_peer
     }
 
-    pub fn retrieve_msg(&mut self) -> Option<Box<FixtMessage>> {
-        self.queue.retrieve(self.next_target_msg_seq_num())
+    pub fn set_test_peer(&mut self, test_peer: bool) {
+        self.test_peer = test_peer;
+    }
+
+    pub fn password(&self) -> Option<&FixString> {
+        self.password.as_ref()
+    }
+
+    pub fn set_new_password_sent(&mut self, new_password: Option<FixString>) {
+        self.new_password_sent = new_password;
+    }
+
+    /// Use NewPassword<925> sent in the last Logon<A> from now on.
+    /// Returns `false` if no new password was sent.
+    pub fn confirm_new_password(&mut self) -> bool {
+        match self.new_password_sent.take() {
+            Some(new_password) => {
+                self.password = Some(new_password);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Enqueue received message, spilling it to messages storage when
+    /// too many messages are already kept in memory.
+    ///
+    /// `raw_message` is not kept for spilled messages.
+    pub fn enqueue_msg(&mut self, msg: Box<FixtMessage>, raw_message: Option<Bytes>) {
+        let seq_num = msg.header.msg_seq_num;
+        if self
+            .queue_spill_threshold
+            .is_some_and(|threshold| self.queue.decoded.len() >= threshold)
+        {
+            match self
+                .messages_storage
+                .store_queued(seq_num, &msg.serialize())
+            {
+                Ok(()) => {
+                    self.queue.spill(seq_num);
+                    return;
+                }
+                Err(err) => warn!("failed to spill queued message {seq_num}: {err}"),
+            }
+        }
+        self.queue.enqueue(seq_num, msg, raw_message);
+    }
+
+    /// Enqueue placeholder message, always kept in memory.
+    pub fn enqueue_placeholder_msg(&mut self, msg: Box<FixtMessage>) {
+        self.queue.enqueue(msg.header.msg_seq_num, msg, None);
+    }
+
+    /// Next expected queued message, with its raw bytes when kept.
+    pub fn retrieve_msg(&mut self) -> Option<(Box<FixtMessage>, Option<Bytes>)> {
+        let seq_num = self.next_target_msg_seq_num();
+        if let Some(msg) = self.queue.retrieve(seq_num) {
+            return Some(msg);
+        }
+        if !self.queue.retrieve_spilled(seq_num) {
+            return None;
+        }
+        let Some(data) = self.messages_storage.take_queued(seq_num) else {
+            error!("queued message {seq_num} missing in messages storage");
+            return None;
+        };
+        decode_queued(&data).map(|msg| (msg, None))
     }
 
     pub fn clear_queue(&mut self) {
+        if !self.queue.spilled.is_empty() {
+            self.messages_storage.clear_queued();
+        }
         self.queue.clear();
     }
 
+    pub fn paused(&self) -> Option<PauseMode> {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: Option<PauseMode>) {
+        self.paused = paused;
+    }
+
+    pub fn paused_queue_len(&self) -> usize {
+        self.paused_queue.len()
+    }
+
+    pub fn enqueue_paused_msg(&mut self, msg: QueuedMsg) {
+        self.paused_queue.push_back(msg);
+    }
+
+    pub fn retrieve_paused_msg(&mut self) -> Option<QueuedMsg> {
+        self.paused_queue.pop_front()
+    }
+
+    pub fn pending_logout(&self) -> Option<Instant> {
+        self.pending_logout
+    }
+
+    pub fn set_pending_logout(&mut self, pending_logout: Option<Instant>) {
+        self.pending_logout = pending_logout;
+    }
+
+    pub fn incr_messages_received(&mut self) {
+        self.messages_received += 1;
+    }
+
+    pub fn incr_messages_sent(&mut self) {
+        self.messages_sent += 1;
+    }
+
+    pub fn read_buffer_resizes(&self) -> Rc<Cell<u64>> {
+        self.read_buffer_resizes.clone()
+    }
+
+    pub fn capture(&self) -> &CaptureSlot {
+        &self.capture
+    }
+
+    pub fn incr_serialize_buffer_resizes(&mut self) {
+        self.serialize_buffer_resizes += 1;
+    }
+
     pub fn fetch_range(&mut self, range: RangeInclusive<SeqNum>) -> Vec<Vec<u8>> {
         self.messages_storage.fetch_range(range)
     }
 
-    pub fn store(&mut self, seq_num: SeqNum, data: &[u8]) {
-        self.messages_storage.store(seq_num, data);
+    pub fn store(&mut self, seq_num: SeqNum, data: &[u8]) -> Result<(), StoreError> {
+        self.messages_storage.try_store(seq_num, data)
     }
 
     pub fn next_sender_msg_seq_num(&self) -> SeqNum {
@@ -242,6 +496,21 @@ impl<S: MessagesStorage> State<S> {
         self.disconnected = disconnected;
     }
 
+    pub fn snapshot(&self) -> SessionStateSnapshot {
+        SessionStateSnapshot {
+            next_sender_msg_seq_num: self.next_sender_msg_seq_num(),
+            next_target_msg_seq_num: self.next_target_msg_seq_num(),
+            logon_received: self.received_logon,
+            logon_sent: self.sent_logon,
+            logout_sent: self.sent_logout,
+            resend_range: self.resend_range.clone(),
+            messages_received: self.messages_received,
+            messages_sent: self.messages_sent,
+            read_buffer_resizes: self.read_buffer_resizes.get(),
+            serialize_buffer_resizes: self.serialize_buffer_resizes,
+        }
+    }
+
     pub fn input_timoeut_cnt(&self) -> u32 {
         self.input_timeout_cnt
     }
@@ -1,11 +1,24 @@
-use std::ops::RangeInclusive;
+use std::{fmt, ops::RangeInclusive, path::PathBuf, sync::Arc};
 
-use chrono::NaiveTime;
-use easyfix_messages::fields::FixString;
+use chrono::{NaiveTime, TimeDelta};
+use easyfix_messages::{
+    buffer_pool::BufferPool,
+    deserializer,
+    fields::{FixStr, FixString, NumInGroup, SeqNum, TagNum, UtcTimestamp},
+    messages::{FixtMessage, BEGIN_STRING, DICTIONARY_FINGERPRINT},
+    serializer,
+};
 use serde::{Deserialize, Deserializer};
 use tokio::time::Duration;
 
-use crate::session_id::SessionId;
+use crate::{
+    application::{EventBroadcast, LatencyStage},
+    calendar::SessionCalendar,
+    credentials::SessionCredentialsProvider,
+    inspector::SessionInspector,
+    session_id::SessionId,
+    verifier::SessionVerifier,
+};
 
 fn duration_from_seconds<'de, D>(deserializer: D) -> Result<Duration, D::Error>
 where
@@ -14,6 +27,13 @@ where
     Ok(Duration::from_secs(u64::deserialize(deserializer)?))
 }
 
+fn opt_duration_from_seconds<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_secs))
+}
+
 /// FIX Trading Port session configuration.
 #[derive(Clone, Debug, Deserialize)]
 pub struct Settings {
@@ -26,12 +46,680 @@ pub struct Settings {
     /// outbound message is missing.
     #[serde(deserialize_with = "duration_from_seconds")]
     pub heartbeat_interval: Duration,
-    /// Timeout \[s\] for `Logon<A>` message, when reached, connection is dropped.
+    /// Timeout \[s\] for `Logon<A>` message, counted from TCP accept, when
+    /// reached, connection is dropped.
     #[serde(deserialize_with = "duration_from_seconds")]
     pub auto_disconnect_after_no_logon_received: Duration,
+    /// Maximum number of bytes acceptor buffers before the first message
+    /// of connection is complete, connection is dropped when exceeded.
+    /// Unlimited when not set.
+    #[serde(default)]
+    pub max_bytes_before_logon: Option<usize>,
+    /// Handling of messages other than Logon<A> received by acceptor
+    /// before session is established.
+    #[serde(default)]
+    pub pre_logon: PreLogonPolicy,
     /// How many times `TestRequest<1> `is sent when inbound timeout is reached,
     /// before connection is dropped.
     pub auto_disconnect_after_no_heartbeat: u32,
+    /// Pool of buffers used for socket reads and, with `pooled-data`
+    /// feature, Data/XmlData fields of inbound messages, shared by all
+    /// connections.
+    #[serde(default)]
+    pub buffer_pool: Option<BufferPool>,
+    /// Handling of fields appearing more than once in inbound message
+    /// body, e.g. repeated Text<58> sent by some venues.
+    #[serde(default)]
+    pub duplicate_tags: DuplicateTagPolicy,
+    /// Deliver inbound messages of types not defined in dictionary
+    /// (e.g. venue specific ones not included at generation time) to
+    /// application as `Message::Other`, with raw MsgType<35> value and
+    /// body fields, instead of rejecting them with InvalidMsgType reason.
+    #[serde(default)]
+    pub other_msg_types: bool,
+    /// Header and trailer fields required in inbound messages, in
+    /// addition to ones required by the dictionary.
+    #[serde(default)]
+    pub required_tags: RequiredTags,
+    /// Per-session log files, disabled when not set.
+    #[serde(default)]
+    pub session_log: Option<SessionLogSettings>,
+    /// TCP keepalive probes of initiated connections, OS defaults are used
+    /// when not set. Acceptor applies it with
+    /// [`TcpConnection::with_keepalive`](crate::acceptor::TcpConnection::with_keepalive).
+    #[serde(default)]
+    pub tcp_keepalive: Option<TcpKeepalive>,
+    /// Watchdog of socket reads, independent of heartbeat timers, disabled
+    /// when not set.
+    #[serde(default)]
+    pub read_idle: Option<ReadIdleSettings>,
+    /// Sizes of internal buffers and queues, defaults favour latency
+    /// over memory.
+    #[serde(default)]
+    pub buffers: BufferSettings,
+    /// Limits of repeating groups in inbound messages, dictionary
+    /// nesting and NumInGroup type limits by default.
+    #[serde(default)]
+    pub group_limits: GroupLimits,
+    /// Detailed reports of inbound messages dropped because of CheckSum<10>
+    /// or BodyLength<9> mismatch, disabled by default.
+    #[serde(default)]
+    pub framing_errors: FramingErrorReports,
+    /// Directory of traffic captures written to file, paths of
+    /// [`CaptureSink::File`](crate::capture::CaptureSink::File) are
+    /// relative to it. File captures are refused when not set.
+    #[serde(default)]
+    pub capture_dir: Option<PathBuf>,
+}
+
+/// How buffer grows when it's full.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum BufferGrowth {
+    /// Double the capacity, few reallocations at the cost of memory.
+    #[default]
+    Double,
+    /// Grow by given number of bytes, less memory at the cost of more
+    /// reallocations.
+    Linear(usize),
+}
+
+impl BufferGrowth {
+    /// Number of bytes to reserve in full buffer of given `capacity`,
+    /// so it doesn't exceed `max_capacity`.
+    pub(crate) fn additional(self, capacity: usize, max_capacity: Option<usize>) -> usize {
+        let additional = match self {
+            BufferGrowth::Double => capacity,
+            BufferGrowth::Linear(step) => step,
+        }
+        .max(1);
+        match max_capacity {
+            Some(max_capacity) => additional.min(max_capacity.saturating_sub(capacity)),
+            None => additional,
+        }
+    }
+}
+
+/// Sizes of internal buffers and queues.
+///
+/// Buffer resizes are counted in
+/// [`SessionStateSnapshot`](crate::application::SessionStateSnapshot), if
+/// they happen often, initial capacities are too small.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct BufferSettings {
+    /// Initial capacity of socket read buffer, ignored when buffer is
+    /// taken from [`Settings::buffer_pool`].
+    pub read_buffer_capacity: usize,
+    /// Growth of read buffer when message doesn't fit.
+    pub read_buffer_growth: BufferGrowth,
+    /// Capacity read buffer never grows above, connection is dropped
+    /// when message doesn't fit. Unlimited when not set.
+    pub read_buffer_max_capacity: Option<usize>,
+    /// Initial capacity of buffer outgoing messages are serialized to.
+    pub serialize_buffer_capacity: usize,
+    /// Number of events queued for application before session waits for
+    /// application to consume them.
+    pub events_capacity: usize,
+    /// Number of events buffered for each subscriber of
+    /// [`EventBroadcast`](crate::application::EventBroadcast), subscribers
+    /// lagging more than that miss the oldest events.
+    pub subscriber_capacity: usize,
+}
+
+impl Default for BufferSettings {
+    fn default() -> BufferSettings {
+        BufferSettings {
+            read_buffer_capacity: 4096,
+            read_buffer_growth: BufferGrowth::Double,
+            read_buffer_max_capacity: None,
+            serialize_buffer_capacity: 4096,
+            events_capacity: 16,
+            subscriber_capacity: EventBroadcast::CAPACITY,
+        }
+    }
+}
+
+/// TCP keepalive configuration, used to detect half-open connections
+/// (peer gone without FIN) at TCP level.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+pub struct TcpKeepalive {
+    /// Idle time \[s\] before the first keepalive probe is sent.
+    #[serde(deserialize_with = "duration_from_seconds")]
+    pub time: Duration,
+    /// Time \[s\] between keepalive probes, OS default when not set.
+    #[serde(default, deserialize_with = "opt_duration_from_seconds")]
+    pub interval: Option<Duration>,
+    /// Number of unanswered probes before connection is dropped, OS
+    /// default when not set.
+    #[serde(default)]
+    pub retries: Option<u32>,
+}
+
+/// Reaction to socket with no data read for
+/// [`ReadIdleSettings::timeout`].
+///
+/// [`FixEvent::ReadIdle`](crate::application::FixEvent::ReadIdle)
+/// is emitted in every case.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum ReadIdleAction {
+    /// Disconnect without sending Logout<5>.
+    #[default]
+    Disconnect,
+    /// Only emit the event. It's emitted once per idle period, again
+    /// only after data is read.
+    Notify,
+}
+
+/// Watchdog of socket reads of established session.
+///
+/// Unlike heartbeat timers, which are reset by complete messages only,
+/// watchdog is reset by any data read from socket, so `timeout` can be
+/// set independently of HeartBtInt<108>, e.g. shorter to detect
+/// half-open connections sooner on active sessions.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+pub struct ReadIdleSettings {
+    /// Time \[s\] without any data read from socket.
+    #[serde(deserialize_with = "duration_from_seconds")]
+    pub timeout: Duration,
+    /// Reaction to idle socket.
+    #[serde(default)]
+    pub action: ReadIdleAction,
+}
+
+/// Reports of inbound messages with CheckSum<10> or BodyLength<9> not
+/// matching their content, e.g. to diagnose counterparty encoder.
+///
+/// Such messages are dropped as garbled regardless of these settings,
+/// as required by FIX session protocol, reported ones are additionally
+/// delivered with
+/// [`FixEvent::FramingError`](crate::application::FixEvent::FramingError),
+/// including calculated and received values and hexdump of received data.
+/// Mismatch detected before session is established is only logged.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct FramingErrorReports {
+    /// Report CheckSum<10> mismatch.
+    #[serde(default)]
+    pub checksum: bool,
+    /// Report BodyLength<9> mismatch.
+    #[serde(default)]
+    pub body_length: bool,
+}
+
+/// Per-session log files configuration.
+///
+/// Every session writes to its own files in `dir`, named after session ID,
+/// e.g. `FIXT.1.1-SENDER-TARGET.messages.log` for raw messages and
+/// `FIXT.1.1-SENDER-TARGET.events.log` for session events. Tracing events
+/// of session are emitted within `session` span with `id` field set to
+/// session ID, so they can be routed by subscriber the same way.
+#[derive(Clone, Debug, Deserialize)]
+pub struct SessionLogSettings {
+    /// Directory of log files, must exist.
+    pub dir: PathBuf,
+    /// Log raw inbound and outbound messages.
+    #[serde(default = "default_true")]
+    pub messages: bool,
+    /// Log session events, except of messages.
+    #[serde(default = "default_true")]
+    pub events: bool,
+    /// File is rotated when writing next entry would exceed this size
+    /// \[bytes\].
+    #[serde(default = "SessionLogSettings::default_max_file_size")]
+    pub max_file_size: u64,
+    /// Number of rotated files kept, e.g. `.messages.log.1` up to
+    /// `.messages.log.N`, older ones are removed.
+    #[serde(default = "SessionLogSettings::default_max_files")]
+    pub max_files: usize,
+}
+
+impl SessionLogSettings {
+    fn default_max_file_size() -> u64 {
+        64 * 1024 * 1024
+    }
+
+    fn default_max_files() -> usize {
+        5
+    }
+
+    /// Log messages and events to `dir`, with default rotation settings.
+    pub fn new(dir: impl Into<PathBuf>) -> SessionLogSettings {
+        SessionLogSettings {
+            dir: dir.into(),
+            messages: true,
+            events: true,
+            max_file_size: Self::default_max_file_size(),
+            max_files: Self::default_max_files(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Handling of field appearing more than once in message body. Header,
+/// trailer and repeating group fields are always rejected.
+///
+/// Fields generated as repeated (see
+/// `easyfix_messages_gen::GeneratorOptions::repeated_tags`) are always
+/// collected.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum DuplicateTagPolicy {
+    /// Reject message with Reject<3>, TagAppearsMoreThanOnce reason.
+    #[default]
+    Reject,
+    /// Keep the first value, ignore the following ones.
+    KeepFirst,
+    /// Keep the last value.
+    KeepLast,
+}
+
+impl From<DuplicateTagPolicy> for deserializer::DuplicateTagPolicy {
+    fn from(policy: DuplicateTagPolicy) -> deserializer::DuplicateTagPolicy {
+        match policy {
+            DuplicateTagPolicy::Reject => deserializer::DuplicateTagPolicy::Reject,
+            DuplicateTagPolicy::KeepFirst => deserializer::DuplicateTagPolicy::KeepFirst,
+            DuplicateTagPolicy::KeepLast => deserializer::DuplicateTagPolicy::KeepLast,
+        }
+    }
+}
+
+/// Header and trailer fields required in inbound messages, e.g. venue
+/// specific routing fields.
+///
+/// Fields must be defined in the dictionary header or trailer, so their
+/// values are validated when deserialized. Message with missing field
+/// is rejected with Reject<3>, RequiredTagMissing reason, message with
+/// field out of order (when `ordered` is set) or outside of its section
+/// with TagSpecifiedOutOfRequiredOrder reason.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct RequiredTags {
+    /// Required header fields.
+    #[serde(default)]
+    pub header: Vec<TagNum>,
+    /// Required trailer fields.
+    #[serde(default)]
+    pub trailer: Vec<TagNum>,
+    /// Require fields to appear in order of `header` and `trailer` lists.
+    #[serde(default)]
+    pub ordered: bool,
+}
+
+impl From<RequiredTags> for deserializer::RequiredTags {
+    fn from(required_tags: RequiredTags) -> deserializer::RequiredTags {
+        deserializer::RequiredTags {
+            header: required_tags.header,
+            trailer: required_tags.trailer,
+            ordered: required_tags.ordered,
+        }
+    }
+}
+
+/// Limits of repeating groups in inbound messages. Message with group
+/// exceeding them is rejected with Reject<3>,
+/// IncorrectNumInGroupCountForRepeatingGroup reason.
+///
+/// NumInGroup value is also verified against the message length, so
+/// group entries are never allocated for data which was not received.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct GroupLimits {
+    /// Maximum NumInGroup value.
+    pub max_entries: NumInGroup,
+    /// Maximum depth of nested groups, group not nested in other group
+    /// has depth 1.
+    pub max_depth: u8,
+}
+
+impl Default for GroupLimits {
+    fn default() -> GroupLimits {
+        deserializer::GroupLimits::default().into()
+    }
+}
+
+impl From<deserializer::GroupLimits> for GroupLimits {
+    fn from(group_limits: deserializer::GroupLimits) -> GroupLimits {
+        GroupLimits {
+            max_entries: group_limits.max_entries,
+            max_depth: group_limits.max_depth,
+        }
+    }
+}
+
+impl From<GroupLimits> for deserializer::GroupLimits {
+    fn from(group_limits: GroupLimits) -> deserializer::GroupLimits {
+        deserializer::GroupLimits {
+            max_entries: group_limits.max_entries,
+            max_depth: group_limits.max_depth,
+        }
+    }
+}
+
+/// Reaction to received message with MsgSeqNum<34> lower than expected
+/// and without PossDupFlag<43> set.
+#[derive(Clone, Debug, Deserialize)]
+pub enum MsgSeqNumTooLowAction {
+    /// Send Logout<5> and optionally disconnect without waiting for
+    /// the Logout<5> response.
+    ///
+    /// In `text` template `{expected}` and `{received}` are replaced with
+    /// expected and received MsgSeqNum<34> values. When not set, the default
+    /// description is used.
+    Logout {
+        text: Option<String>,
+        disconnect: bool,
+    },
+    /// Disconnect immediately without sending Logout<5>.
+    Disconnect,
+    /// Discard the message and continue, for testing purposes only.
+    Continue,
+}
+
+impl Default for MsgSeqNumTooLowAction {
+    fn default() -> MsgSeqNumTooLowAction {
+        MsgSeqNumTooLowAction::Logout {
+            text: None,
+            disconnect: true,
+        }
+    }
+}
+
+impl MsgSeqNumTooLowAction {
+    pub(crate) fn logout_text(template: &str, expected: SeqNum, received: SeqNum) -> String {
+        template
+            .replace("{expected}", &expected.to_string())
+            .replace("{received}", &received.to_string())
+    }
+}
+
+/// Handling of invalid values of optional fields in application messages.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum FieldErrorsPolicy {
+    /// Reject the whole message with Reject<3>.
+    #[default]
+    Reject,
+    /// Skip invalid fields and deliver the message with
+    /// [`FixEvent::PartialAppMsgIn`](crate::application::FixEvent::PartialAppMsgIn),
+    /// without rejecting it.
+    Deliver,
+    /// Deliver the message like [`Deliver`](FieldErrorsPolicy::Deliver)
+    /// and reject it with Reject<3> referring to the first invalid field,
+    /// unless application rejected it already.
+    DeliverAndReject,
+}
+
+/// Handling of empty values of optional String fields in outgoing messages.
+/// FIX forbids empty values, but some systems expect empty tags anyway.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum EmptyStringPolicy {
+    /// Send empty field as it is, with warning logged.
+    #[default]
+    SendAsIs,
+    /// Omit empty field.
+    Skip,
+    /// Fail serialization of the whole message, it's handled according
+    /// to [`SessionSettings::serialize_failure`].
+    Error,
+}
+
+/// Reply to received application message of type not supported by the
+/// application.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum UnsupportedMsgTypePolicy {
+    /// Send BusinessMessageReject<j> with UnsupportedMessageType reason,
+    /// as FIX specification requires.
+    #[default]
+    BusinessReject,
+    /// Send session-level Reject<3> with InvalidMsgType reason, for
+    /// counterparties not handling BusinessMessageReject<j>.
+    Reject,
+    /// Don't reply, message is just consumed.
+    Ignore,
+}
+
+/// Order of [`FixEvent::AppMsgOut`](crate::application::FixEvent::AppMsgOut)
+/// and [`FixEvent::AdmMsgOut`](crate::application::FixEvent::AdmMsgOut)
+/// events and writing outgoing message to messages storage.
+///
+/// In both cases message is written to the socket after it's stored.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum OutputOrder {
+    /// Emit event first, then store the message as modified by the event
+    /// handler. Messages discarded by the handler are not stored.
+    #[default]
+    EmitThenStore,
+    /// Store the message first, then emit event, so events are delivered
+    /// only for messages already stored. Message is sent as stored,
+    /// changes made by the event handler, including
+    /// [`Responder::do_not_send`](crate::application::Responder::do_not_send),
+    /// are ignored.
+    StoreThenEmit,
+}
+
+/// Reaction to failed write of outgoing message to messages storage, see
+/// [`MessagesStorage::try_store`](crate::messages_storage::MessagesStorage::try_store).
+///
+/// [`FixEvent::StoreFailed`](crate::application::FixEvent::StoreFailed)
+/// is emitted in every case. Message not stored can't be resent, it's
+/// replaced with SequenceReset<4> GapFill when resend is requested.
+/// Failure to persist sender sequence number
+/// ([`StoreError::SeqNumNotPersisted`](crate::messages_storage::StoreError::SeqNumNotPersisted))
+/// is always handled as [`StoreFailurePolicy::Disconnect`].
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum StoreFailurePolicy {
+    /// Send the message anyway.
+    #[default]
+    Send,
+    /// Drop the message.
+    Drop,
+    /// Drop the message and disconnect session.
+    Disconnect,
+}
+
+/// Reaction to outgoing message which could not be serialized, e.g.
+/// because of empty field value with [`EmptyStringPolicy::Error`].
+///
+/// [`FixEvent::SerializeFailed`](crate::application::FixEvent::SerializeFailed)
+/// is emitted in every case. MsgSeqNum<34> of the message is already
+/// taken, it's replaced with SequenceReset<4> GapFill when resend is
+/// requested.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum SerializeFailurePolicy {
+    /// Drop the message.
+    #[default]
+    Skip,
+    /// Drop the message and disconnect session.
+    Disconnect,
+}
+
+/// Reaction to received message with BeginString<8> other than the one
+/// of the session.
+///
+/// [`FixEvent::BeginStringMismatch`](crate::application::FixEvent::BeginStringMismatch)
+/// is emitted in every case.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum BeginStringMismatchPolicy {
+    /// Send Logout<5> and disconnect without waiting for the Logout<5>
+    /// response, as FIX specification requires.
+    #[default]
+    Logout,
+    /// Disconnect immediately without sending Logout<5>.
+    Disconnect,
+    /// Reject the message with Reject<3> and continue.
+    Reject,
+    /// Process the message as if BeginString<8> matched.
+    Ignore,
+}
+
+/// Population of Heartbeat<0> sent in response to TestRequest<1>.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum TestRequestReplyPolicy {
+    /// Echo TestReqID<112>.
+    #[default]
+    Auto,
+    /// Emit [`FixEvent::HeartbeatReply`](crate::application::FixEvent::HeartbeatReply)
+    /// with the Heartbeat<0>, so the application can add fields
+    /// or transform TestReqID<112> before it's sent. Input processing is
+    /// suspended until the event is dropped.
+    Customize,
+}
+
+/// EndSeqNo<16> value of sent ResendRequest<2>.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum ResendRequestEndSeqNo {
+    /// MsgSeqNum<34> of the last missing message.
+    #[default]
+    Explicit,
+    /// 0, all messages following BeginSeqNo<7> are requested. Used only
+    /// for the last request when range is split, see
+    /// [`ResendRequestPolicy::max_range`].
+    Infinity,
+}
+
+/// Population of sent ResendRequest<2> fields.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct ResendRequestPolicy {
+    #[serde(default)]
+    pub end_seq_no: ResendRequestEndSeqNo,
+    /// Maximum number of messages requested with single ResendRequest<2>.
+    /// Larger gaps are requested with consecutive requests, the next one
+    /// is sent when the previous one is satisfied. Unlimited when not set.
+    #[serde(default)]
+    pub max_range: Option<SeqNum>,
+}
+
+impl From<EmptyStringPolicy> for serializer::EmptyStringPolicy {
+    fn from(policy: EmptyStringPolicy) -> serializer::EmptyStringPolicy {
+        match policy {
+            EmptyStringPolicy::SendAsIs => serializer::EmptyStringPolicy::SendAsIs,
+            EmptyStringPolicy::Skip => serializer::EmptyStringPolicy::Skip,
+            EmptyStringPolicy::Error => serializer::EmptyStringPolicy::Error,
+        }
+    }
+}
+
+/// Handling of received SequenceReset<4> in Reset mode (GapFillFlag<123>
+/// not set), which sets next expected MsgSeqNum<34> regardless of
+/// MsgSeqNum<34> of SequenceReset<4> itself.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum SequenceResetPolicy {
+    /// Always set next expected MsgSeqNum<34> to NewSeqNo<36>, also when
+    /// it's lower than the current one.
+    Accept,
+    /// Set next expected MsgSeqNum<34> to NewSeqNo<36> only when it's
+    /// higher than the current one, lower value is rejected with
+    /// SessionRejectReason<373> ValueIsIncorrect.
+    #[default]
+    RejectLower,
+    /// Emit [`FixEvent::SequenceResetConfirm`](crate::application::FixEvent::SequenceResetConfirm)
+    /// and wait for the application to accept or reject new sequence number.
+    /// Input processing is suspended until decision is made, but not longer
+    /// than [`SessionSettings::sequence_reset_confirm_timeout`], then
+    /// `RejectLower` applies.
+    Confirm,
+}
+
+/// Letter case applied to CompIDs stamped on outgoing messages.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum CompIdCase {
+    /// Keep CompIDs as configured.
+    #[default]
+    Preserve,
+    /// Convert CompIDs to upper case.
+    Upper,
+    /// Convert CompIDs to lower case.
+    Lower,
+}
+
+/// SenderCompID<49>/TargetCompID<56> normalization and comparison rules.
+///
+/// Default policy keeps CompIDs unchanged and requires exact equality
+/// of received CompIDs.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct CompIdPolicy {
+    /// Compare received CompIDs ignoring ASCII letter case.
+    pub case_insensitive: bool,
+    /// Ignore trailing `pad_char` characters of received CompIDs.
+    pub ignore_padding: bool,
+    /// Letter case applied to outgoing CompIDs.
+    pub case: CompIdCase,
+    /// Right-pad outgoing CompIDs with `pad_char` up to given width.
+    pub pad_width: Option<usize>,
+    /// Padding character, has to be printable ASCII character.
+    pub pad_char: char,
+}
+
+impl Default for CompIdPolicy {
+    fn default() -> CompIdPolicy {
+        CompIdPolicy {
+            case_insensitive: false,
+            ignore_padding: false,
+            case: CompIdCase::Preserve,
+            pad_width: None,
+            pad_char: ' ',
+        }
+    }
+}
+
+impl CompIdPolicy {
+    fn pad_byte(&self) -> u8 {
+        // Non-ASCII characters are replaced the same way as in
+        // `FixString::from_ascii_lossy`
+        u8::try_from(self.pad_char)
+            .ok()
+            .filter(u8::is_ascii)
+            .unwrap_or(b'?')
+    }
+
+    fn strip_padding<'a>(&self, comp_id: &'a [u8]) -> &'a [u8] {
+        if !self.ignore_padding {
+            return comp_id;
+        }
+        let pad_byte = self.pad_byte();
+        let len = comp_id
+            .iter()
+            .rposition(|c| *c != pad_byte)
+            .map_or(0, |idx| idx + 1);
+        &comp_id[..len]
+    }
+
+    /// Returns CompID as it should be stamped on outgoing message.
+    pub fn normalize(&self, comp_id: &FixStr) -> FixString {
+        let mut buf = comp_id.as_bytes().to_vec();
+        match self.case {
+            CompIdCase::Preserve => {}
+            CompIdCase::Upper => buf.make_ascii_uppercase(),
+            CompIdCase::Lower => buf.make_ascii_lowercase(),
+        }
+        if let Some(pad_width) = self.pad_width {
+            if buf.len() < pad_width {
+                buf.resize(pad_width, self.pad_byte());
+            }
+        }
+        FixString::from_ascii_lossy(buf)
+    }
+
+    /// Checks if received CompID matches the expected one.
+    pub fn matches(&self, expected: &FixStr, received: &FixStr) -> bool {
+        let expected = self.strip_padding(expected.as_bytes());
+        let received = self.strip_padding(received.as_bytes());
+        if self.case_insensitive {
+            expected.eq_ignore_ascii_case(received)
+        } else {
+            expected == received
+        }
+    }
+
+    /// Checks if session identified from received message matches
+    /// the expected one, BeginString<8> and session qualifier have to be
+    /// equal, CompIDs are compared using [`CompIdPolicy::matches`].
+    pub fn matches_session_id(&self, expected: &SessionId, received: &SessionId) -> bool {
+        expected.begin_string() == received.begin_string()
+            && expected.session_qualifier() == received.session_qualifier()
+            && self.matches(expected.sender_comp_id(), received.sender_comp_id())
+            && self.matches(expected.target_comp_id(), received.target_comp_id())
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -40,9 +728,16 @@ pub struct SessionSettings {
     // TODO: Optional
     pub session_time: RangeInclusive<NaiveTime>,
     pub logon_time: RangeInclusive<NaiveTime>,
+    /// Business days, Logon<A> is not accepted on other days.
+    #[serde(default)]
+    pub calendar: SessionCalendar,
 
     pub send_redundant_resend_requests: bool,
     pub check_comp_id: bool,
+    /// CompIDs normalization applied to outgoing messages and comparison
+    /// rules used to verify received messages and find matching session.
+    #[serde(default)]
+    pub comp_id_policy: CompIdPolicy,
     pub check_latency: bool,
     pub max_latency: Duration,
 
@@ -54,6 +749,10 @@ pub struct SessionSettings {
 
     pub sender_default_appl_ver_id: FixString,
     pub target_default_appl_ver_id: FixString,
+    /// Verify DefaultApplVerID<1137> of Logon<A> response received by
+    /// initiator against `target_default_appl_ver_id`.
+    #[serde(default)]
+    pub check_default_appl_ver_id: bool,
 
     /// Enable the next expected message sequence number (optional tag 789
     /// on Logon) on sent Logon message and use value of tag 789 on received
@@ -65,4 +764,543 @@ pub struct SessionSettings {
 
     // Enable Logout<5> verification.
     pub verify_logout: bool,
+
+    /// Reaction to MsgSeqNum<34> too low without PossDupFlag<43>.
+    #[serde(default)]
+    pub msg_seq_num_too_low: MsgSeqNumTooLowAction,
+
+    /// Handling of SequenceReset<4> in Reset mode.
+    #[serde(default)]
+    pub sequence_reset: SequenceResetPolicy,
+
+    /// Time \[s\] application has to accept or reject SequenceReset<4>
+    /// with [`SequenceResetPolicy::Confirm`], when elapsed, SequenceReset<4>
+    /// is handled as with [`SequenceResetPolicy::RejectLower`].
+    #[serde(
+        default = "SessionSettings::default_sequence_reset_confirm_timeout",
+        deserialize_with = "duration_from_seconds"
+    )]
+    pub sequence_reset_confirm_timeout: Duration,
+
+    /// When Logout<5> is received while ResendRequest<2> sent by us is not
+    /// yet satisfied, delay Logout<5> response until resend completes,
+    /// but not longer than given period \[s\]. When not set, Logout<5> is
+    /// handled immediately.
+    #[serde(default, deserialize_with = "opt_duration_from_seconds")]
+    pub logout_resend_grace_period: Option<Duration>,
+
+    /// Handling of invalid values of optional fields in application
+    /// messages.
+    #[serde(default)]
+    pub field_errors: FieldErrorsPolicy,
+
+    /// Handling of empty values of optional String fields in outgoing
+    /// messages.
+    #[serde(default)]
+    pub empty_strings: EmptyStringPolicy,
+
+    /// Reply to application message declined with
+    /// [`InputResponder::unsupported`](crate::application::InputResponder::unsupported).
+    #[serde(default)]
+    pub unsupported_msg_type: UnsupportedMsgTypePolicy,
+
+    /// Order of outgoing message events and storing the message.
+    #[serde(default)]
+    pub output_order: OutputOrder,
+
+    /// Reaction to failed write of outgoing message to messages storage.
+    #[serde(default)]
+    pub store_failure: StoreFailurePolicy,
+
+    /// EndSeqNo<16> and range size of sent ResendRequest<2>.
+    #[serde(default)]
+    pub resend_request: ResendRequestPolicy,
+
+    /// Population of Heartbeat<0> sent in response to TestRequest<1>.
+    #[serde(default)]
+    pub test_request_reply: TestRequestReplyPolicy,
+
+    /// Maximum time \[s\] application message may wait in output queue
+    /// (also while session is paused or disconnected), older messages are
+    /// dropped with [`FixEvent::AppMsgExpired`](crate::application::FixEvent::AppMsgExpired)
+    /// instead of being sent. Can be overridden per message with
+    /// [`Sender::send_raw_with_ttl`](crate::Sender::send_raw_with_ttl).
+    /// Messages never expire when not set.
+    #[serde(default, deserialize_with = "opt_duration_from_seconds")]
+    pub app_msg_ttl: Option<Duration>,
+    /// Maximum number of application messages buffered while session is
+    /// paused with [`PauseMode::Buffer`](crate::PauseMode::Buffer), messages
+    /// sent when the buffer is full are dropped with
+    /// [`FixEvent::AppMsgDiscarded`](crate::application::FixEvent::AppMsgDiscarded).
+    #[serde(default = "SessionSettings::default_paused_queue_limit")]
+    pub paused_queue_limit: usize,
+    /// Constant field values added to outgoing messages, e.g. Account<1>
+    /// or Parties group required by venue.
+    #[serde(default)]
+    pub enrichment: Vec<EnrichmentRule>,
+    /// Reaction to received message with BeginString<8> not matching
+    /// the session.
+    #[serde(default)]
+    pub begin_string_mismatch: BeginStringMismatchPolicy,
+    /// Maximum number of decoded messages kept in memory while waiting
+    /// for the gap (received MsgSeqNum<34> too high) to be filled. Further
+    /// messages are kept raw in messages storage (see
+    /// [`MessagesStorage::store_queued`](crate::messages_storage::MessagesStorage::store_queued))
+    /// and decoded again when processed. All messages are kept in memory
+    /// when not set.
+    #[serde(default)]
+    pub queue_spill_threshold: Option<usize>,
+    /// Reaction to outgoing message which could not be serialized.
+    #[serde(default)]
+    pub serialize_failure: SerializeFailurePolicy,
+    /// Replay protection of received Logon<A>, see
+    /// [`LogonGuard`](crate::logon_guard::LogonGuard). Disabled when not set.
+    #[serde(default)]
+    pub logon_replay: Option<LogonReplaySettings>,
+    /// Exchange of dictionary fingerprint in Logon<A>, to detect
+    /// counterparty built from different dictionary. Disabled when not set.
+    #[serde(default)]
+    pub dictionary_fingerprint: Option<DictionaryFingerprintCheck>,
+    /// Test session, sent Logon<A> is flagged with
+    /// TestMessageIndicator<464>=Y and `test_messages` is not applied.
+    #[serde(default)]
+    pub test_session: bool,
+    /// Handling of Logon<A> with TestMessageIndicator<464>=Y received
+    /// by production session.
+    #[serde(default)]
+    pub test_messages: TestMessagesPolicy,
+    /// Venue specific SessionRejectReason<373> and Text<58> of sent
+    /// Reject<3>.
+    #[serde(default)]
+    pub reject_reasons: Vec<RejectReasonMapping>,
+    /// Time budgets of message processing stages, e.g. to detect slow
+    /// application handlers before they cause heartbeat misses.
+    #[serde(default)]
+    pub latency_budgets: LatencyBudgets,
+    /// Stored application messages sent as SequenceReset<4>-GapFill
+    /// instead of being resent in response to ResendRequest<2>.
+    #[serde(default)]
+    pub gap_fill: GapFillPolicy,
+    /// Username<553>, Password<554> and NewPassword<925> sent in Logon<A>
+    /// by initiator, unless `credentials_provider` is set.
+    #[serde(default)]
+    pub credentials: LogonCredentials,
+    /// Source of credentials resolved before every Logon<A> is sent,
+    /// instead of `credentials`, see [`credentials`](crate::credentials).
+    #[serde(skip)]
+    pub credentials_provider: SessionCredentialsProvider,
+    /// Custom verification of received messages, see
+    /// [`Verifier`](crate::verifier::Verifier).
+    #[serde(skip)]
+    pub verifier: SessionVerifier,
+    /// Inspection of received and sent application messages, see
+    /// [`Inspector`](crate::inspector::Inspector).
+    #[serde(skip)]
+    pub inspector: SessionInspector,
+    /// Source of SendingTime<52> stamped on outgoing messages.
+    #[serde(skip)]
+    pub clock: SessionClock,
+}
+
+/// Source of SendingTime<52> stamped on outgoing messages, shared by all
+/// clones of its settings.
+///
+/// Not deserialized, custom clock is set with [`SessionClock::new`] or
+/// [`SessionClock::fixed`], e.g. to compare serialized messages in tests.
+/// By default current system time is used.
+#[derive(Clone, Default)]
+pub struct SessionClock(Option<Arc<dyn Fn() -> UtcTimestamp + Send + Sync>>);
+
+impl SessionClock {
+    pub fn new(now: impl Fn() -> UtcTimestamp + Send + Sync + 'static) -> SessionClock {
+        SessionClock(Some(Arc::new(now)))
+    }
+
+    /// Clock always returning `timestamp`.
+    pub fn fixed(timestamp: UtcTimestamp) -> SessionClock {
+        SessionClock::new(move || timestamp)
+    }
+
+    pub fn now(&self) -> UtcTimestamp {
+        match &self.0 {
+            Some(now) => now(),
+            None => UtcTimestamp::now(),
+        }
+    }
+}
+
+impl fmt::Debug for SessionClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(_) => f.write_str("SessionClock(custom)"),
+            None => f.write_str("SessionClock(system)"),
+        }
+    }
+}
+
+/// Handling of Logon<A> with TestMessageIndicator<464>=Y received
+/// by production session, see [`SessionSettings::test_session`].
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum TestMessagesPolicy {
+    /// Accept Logon<A> and process messages as any other.
+    #[default]
+    Accept,
+    /// Refuse Logon<A> with Logout<5>.
+    Reject,
+    /// Accept Logon<A>, application messages received until disconnection
+    /// are delivered with
+    /// [`FixEvent::TestAppMsgIn`](crate::application::FixEvent::TestAppMsgIn)
+    /// instead of `AppMsgIn`/`PartialAppMsgIn`.
+    Segregate,
+}
+
+/// Credentials sent in Logon<A> by initiator, see
+/// [`SessionSettings::credentials`].
+///
+/// When `new_password` is set, it's sent to change the password at logon.
+/// Once counterparty confirms the change with
+/// SessionStatus<1409>=SessionPasswordChanged, the new password is sent as
+/// Password<554> in following Logon<A> messages of the session and
+/// NewPassword<925> is not sent again.
+#[derive(Clone, Default, Deserialize, Eq, PartialEq)]
+pub struct LogonCredentials {
+    /// Username<553>
+    #[serde(default)]
+    pub username: Option<FixString>,
+    /// Password<554>
+    #[serde(default)]
+    pub password: Option<FixString>,
+    /// NewPassword<925>
+    #[serde(default)]
+    pub new_password: Option<FixString>,
+}
+
+impl fmt::Debug for LogonCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Passwords are not logged
+        let hidden = |password: &Option<FixString>| password.as_ref().map(|_| "***");
+        f.debug_struct("LogonCredentials")
+            .field("username", &self.username)
+            .field("password", &hidden(&self.password))
+            .field("new_password", &hidden(&self.new_password))
+            .finish()
+    }
+}
+
+/// Handling of messages received by acceptor before Logon<A>, see
+/// [`Settings::pre_logon`].
+///
+/// Every such message is reported with
+/// [`ConnectionEvent::PreLogonMessage`](crate::acceptor::metrics::ConnectionEvent::PreLogonMessage).
+/// While messages are ignored or rejected, Logon<A> is still expected
+/// within `auto_disconnect_after_no_logon_received` since connection was
+/// accepted.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct PreLogonPolicy {
+    /// Message which could not be deserialized. It can't be referenced
+    /// by Reject<3>, so `Reject` is handled as `Ignore`.
+    #[serde(default)]
+    pub garbled: PreLogonAction,
+    /// Well-formed message other than Logon<A>.
+    #[serde(default)]
+    pub not_logon: PreLogonAction,
+}
+
+/// Handling of message received before Logon<A>, see [`PreLogonPolicy`].
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum PreLogonAction {
+    /// Close connection. Garbled message is reported with
+    /// [`LogonFailure::InvalidMessage`](crate::acceptor::metrics::LogonFailure::InvalidMessage),
+    /// other message is refused by its session with
+    /// [`DisconnectReason::InvalidLogonState`](crate::DisconnectReason::InvalidLogonState).
+    #[default]
+    Disconnect,
+    /// Drop message and wait for the next one.
+    Ignore,
+    /// Send Reject<3> with SessionRejectReason<373>=Other and wait for
+    /// the next message. Reject<3> consumes MsgSeqNum<34> of the session
+    /// and is stored and emitted as any message of the session. Message
+    /// not belonging to registered session, or belonging to session
+    /// connected with another connection, is ignored.
+    Reject,
+}
+
+/// Replay protection of received Logon<A>.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+pub struct LogonReplaySettings {
+    /// Maximum difference \[s\] between SendingTime<52> of Logon<A>
+    /// and current time, in both directions.
+    #[serde(deserialize_with = "duration_from_seconds")]
+    pub max_age: Duration,
+    /// Require unique nonce in RawData<96>. Nonces are remembered for
+    /// `max_age`, older Logon<A> is rejected as stale anyway.
+    #[serde(default)]
+    pub require_nonce: bool,
+}
+
+/// Exchange of [`DICTIONARY_FINGERPRINT`] of generated messages at logon.
+///
+/// Fingerprint is sent in RawData<96> of Logon<A>, so it can't be combined
+/// with [`LogonReplaySettings::require_nonce`] of counterparty. Fingerprint
+/// found in received Logon<A> (or its absence) which differs from
+/// `expected` is reported with
+/// [`FixEvent::LogonMismatch`](crate::application::FixEvent::LogonMismatch)
+/// for RawData<96>.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct DictionaryFingerprintCheck {
+    /// Fingerprint expected from counterparty, [`DICTIONARY_FINGERPRINT`]
+    /// when not set, e.g. to accept counterparty known to be built from
+    /// compatible dictionary during rolling upgrade.
+    #[serde(default)]
+    pub expected: Option<FixString>,
+    /// Refuse logon with Logout<5> when fingerprints differ, otherwise
+    /// the mismatch is only reported.
+    #[serde(default)]
+    pub refuse_mismatch: bool,
+}
+
+impl DictionaryFingerprintCheck {
+    /// Fingerprint expected from counterparty.
+    pub fn expected(&self) -> &FixStr {
+        self.expected.as_deref().unwrap_or(DICTIONARY_FINGERPRINT)
+    }
+}
+
+/// Replacement of SessionRejectReason<373> and Text<58> of Reject<3> sent
+/// for given reason, e.g. when venue requires specific value for given
+/// violation.
+///
+/// Values are given as sent on the wire, e.g. `5` for ValueIsIncorrect.
+/// Mapping with value not defined in generated messages is ignored, with
+/// error logged.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct RejectReasonMapping {
+    /// SessionRejectReason<373> detected by session.
+    pub reason: FixString,
+    /// SessionRejectReason<373> sent instead, detected one when not set.
+    #[serde(default)]
+    pub sent_reason: Option<FixString>,
+    /// Text<58> sent instead of the default description. In the template
+    /// `{tag}` is replaced with RefTagID<371> value (if any) and `{text}`
+    /// with the default description.
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+/// Fields added to outgoing messages of given type, before they are
+/// emitted with [`FixEvent::AppMsgOut`](crate::application::FixEvent::AppMsgOut)
+/// or [`FixEvent::AdmMsgOut`](crate::application::FixEvent::AdmMsgOut).
+///
+/// Fields are given in `tag=value` form separated with `|`. Fields and
+/// groups already present in the message are left untouched. Rule that
+/// can't be parsed is ignored, with error logged.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct EnrichmentRule {
+    /// MsgType<35> value, e.g. `D` for NewOrderSingle<D>.
+    pub msg_type: FixString,
+    /// Fields added when missing, e.g. `1=ACCOUNT|21=1`.
+    #[serde(default)]
+    pub fields: String,
+    /// Repeating groups added when missing, each given as NumInGroup field
+    /// followed by fields of all entries, e.g. `453=1|448=FIRM|447=D|452=1`.
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+/// Per message time budgets of processing stages, stage taking longer is
+/// reported with
+/// [`FixEvent::LatencyBudgetExceeded`](crate::application::FixEvent::LatencyBudgetExceeded).
+/// Stages without budget are not checked.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct LatencyBudgets {
+    /// Deserialization of received message.
+    #[serde(default)]
+    pub decode: Option<Duration>,
+    /// Verification of received message, including
+    /// [`SessionSettings::verifier`] and [`SessionSettings::inspector`].
+    #[serde(default)]
+    pub verify: Option<Duration>,
+    /// Handling of received message by application, from emitting
+    /// `AppMsgIn`/`AdmMsgIn` event until it's dropped or responded to.
+    #[serde(default)]
+    pub app_handler: Option<Duration>,
+    /// Serialization of outgoing message and its write to socket.
+    #[serde(default)]
+    pub serialize_write: Option<Duration>,
+}
+
+impl LatencyBudgets {
+    /// Budget of given stage.
+    pub fn get(&self, stage: LatencyStage) -> Option<Duration> {
+        match stage {
+            LatencyStage::Decode => self.decode,
+            LatencyStage::Verify => self.verify,
+            LatencyStage::AppHandler => self.app_handler,
+            LatencyStage::SerializeWrite => self.serialize_write,
+        }
+    }
+}
+
+/// Stored application messages gap filled on resend, e.g. stale quotes
+/// that shouldn't be acted upon when delivered late. Message matching
+/// any of the criteria is gap filled. Administration messages other than
+/// Reject<3> are always gap filled, regardless of this policy.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct GapFillPolicy {
+    /// MsgType<35> values, e.g. `S` for Quote<S>.
+    #[serde(default)]
+    pub msg_types: Vec<FixString>,
+    /// Maximum age \[s\] of message at resend, measured from its
+    /// SendingTime<52>.
+    #[serde(default, deserialize_with = "opt_duration_from_seconds")]
+    pub max_age: Option<Duration>,
+    /// Custom criterion, see [`GapFillPredicate::new`].
+    #[serde(skip)]
+    pub predicate: GapFillPredicate,
+}
+
+impl GapFillPolicy {
+    /// Whether stored application message `msg` resent at `now` is gap
+    /// filled.
+    pub fn gap_fills(&self, msg: &FixtMessage, now: UtcTimestamp) -> bool {
+        if self
+            .msg_types
+            .iter()
+            .any(|msg_type| *msg_type == msg.header.msg_type.as_fix_str())
+        {
+            return true;
+        }
+        if let Some(max_age) = self.max_age {
+            let max_age = TimeDelta::from_std(max_age).unwrap_or(TimeDelta::MAX);
+            if now.timestamp() - msg.header.sending_time.timestamp() > max_age {
+                return true;
+            }
+        }
+        self.predicate.gap_fills(msg)
+    }
+}
+
+/// Custom criterion of [`GapFillPolicy`], shared by all clones of its
+/// settings.
+///
+/// Not deserialized, custom predicate is set with
+/// [`GapFillPredicate::new`]. By default no message matches.
+#[derive(Clone, Default)]
+pub struct GapFillPredicate(Option<Arc<GapFillFn>>);
+
+type GapFillFn = dyn Fn(&FixtMessage) -> bool + Send + Sync;
+
+impl GapFillPredicate {
+    pub fn new(
+        predicate: impl Fn(&FixtMessage) -> bool + Send + Sync + 'static,
+    ) -> GapFillPredicate {
+        GapFillPredicate(Some(Arc::new(predicate)))
+    }
+
+    pub fn gap_fills(&self, msg: &FixtMessage) -> bool {
+        self.0.as_ref().is_some_and(|predicate| predicate(msg))
+    }
+}
+
+impl fmt::Debug for GapFillPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(_) => f.write_str("GapFillPredicate(custom)"),
+            None => f.write_str("GapFillPredicate(none)"),
+        }
+    }
+}
+
+impl SessionSettings {
+    pub const DEFAULT_PAUSED_QUEUE_LIMIT: usize = 10_000;
+    pub const DEFAULT_SEQUENCE_RESET_CONFIRM_TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn default_sequence_reset_confirm_timeout() -> Duration {
+        SessionSettings::DEFAULT_SEQUENCE_RESET_CONFIRM_TIMEOUT
+    }
+
+    fn default_paused_queue_limit() -> usize {
+        SessionSettings::DEFAULT_PAUSED_QUEUE_LIMIT
+    }
+
+    /// Settings of `session_id` with easyfix defaults: session active
+    /// whole day, CompIDs and latency (up to 2 minutes) checked,
+    /// DefaultApplVerID<1137> `9` (FIX.5.0SP2), NextExpectedMsgSeqNum<789>
+    /// enabled, messages not persisted. Remaining settings have the same
+    /// defaults as when deserialized.
+    pub fn new(session_id: SessionId) -> SessionSettings {
+        let whole_day = NaiveTime::MIN..=NaiveTime::from_hms_opt(23, 59, 59).unwrap();
+        SessionSettings {
+            session_id,
+            session_time: whole_day.clone(),
+            logon_time: whole_day,
+            calendar: Default::default(),
+            send_redundant_resend_requests: false,
+            check_comp_id: true,
+            comp_id_policy: Default::default(),
+            check_latency: true,
+            max_latency: Duration::from_secs(120),
+            reset_on_logon: false,
+            reset_on_logout: false,
+            reset_on_disconnect: false,
+            refresh_on_logon: false,
+            sender_default_appl_ver_id: FixString::from_ascii_lossy(b"9".to_vec()),
+            target_default_appl_ver_id: FixString::from_ascii_lossy(b"9".to_vec()),
+            check_default_appl_ver_id: false,
+            enable_next_expected_msg_seq_num: true,
+            persist: false,
+            verify_logout: true,
+            msg_seq_num_too_low: Default::default(),
+            sequence_reset: Default::default(),
+            sequence_reset_confirm_timeout: SessionSettings::DEFAULT_SEQUENCE_RESET_CONFIRM_TIMEOUT,
+            logout_resend_grace_period: None,
+            field_errors: Default::default(),
+            empty_strings: Default::default(),
+            unsupported_msg_type: Default::default(),
+            output_order: Default::default(),
+            store_failure: Default::default(),
+            resend_request: Default::default(),
+            test_request_reply: Default::default(),
+            app_msg_ttl: None,
+            paused_queue_limit: SessionSettings::DEFAULT_PAUSED_QUEUE_LIMIT,
+            enrichment: Vec::new(),
+            begin_string_mismatch: Default::default(),
+            queue_spill_threshold: None,
+            serialize_failure: Default::default(),
+            logon_replay: None,
+            dictionary_fingerprint: None,
+            test_session: false,
+            test_messages: Default::default(),
+            reject_reasons: Vec::new(),
+            latency_budgets: Default::default(),
+            gap_fill: Default::default(),
+            credentials: Default::default(),
+            credentials_provider: Default::default(),
+            verifier: Default::default(),
+            inspector: Default::default(),
+            clock: Default::default(),
+        }
+    }
+
+    /// BeginString<8> value stamped on outgoing messages, FIX version
+    /// of `session_id` or the one of generated messages if it's empty.
+    pub fn begin_string(&self) -> FixString {
+        if self.session_id.begin_string().is_empty() {
+            BEGIN_STRING.to_owned()
+        } else {
+            self.session_id.begin_string().to_owned()
+        }
+    }
+
+    /// SenderCompID<49> value stamped on outgoing messages.
+    pub fn sender_comp_id(&self) -> FixString {
+        self.comp_id_policy
+            .normalize(self.session_id.sender_comp_id())
+    }
+
+    /// TargetCompID<56> value stamped on outgoing messages.
+    pub fn target_comp_id(&self) -> FixString {
+        self.comp_id_policy
+            .normalize(self.session_id.target_comp_id())
+    }
 }
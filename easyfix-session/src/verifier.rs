@@ -0,0 +1,65 @@
+//! Custom verification of received messages, run together with built-in
+//! session checks.
+
+use std::{fmt, sync::Arc};
+
+use easyfix_messages::{
+    fields::{FixString, SessionRejectReason, SessionStatus},
+    messages::{FieldTag, Header},
+};
+use futures::future::LocalBoxFuture;
+
+/// Result of custom message verification.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum Verdict {
+    /// Message passes verification.
+    #[default]
+    Accept,
+    /// Reject message with Reject<3>, same as message failing built-in
+    /// check, optionally followed by Logout<5>.
+    Reject {
+        reason: SessionRejectReason,
+        /// Tag of the field causing rejection, sent as RefTagID<371>
+        tag: Option<FieldTag>,
+        logout: bool,
+    },
+    /// Terminate session with Logout<5>, optionally disconnecting without
+    /// waiting for Logout<5> response.
+    Logout {
+        session_status: Option<SessionStatus>,
+        text: Option<FixString>,
+        disconnect: bool,
+    },
+}
+
+/// Venue-specific verification of received messages, e.g. rejecting
+/// messages while market is halted.
+///
+/// Invoked for every received message after built-in header checks
+/// (logon state, SendingTime<52> accuracy and CompIDs) and before
+/// MsgSeqNum<34> is verified, so rejected message is handled exactly like
+/// one failing built-in check. Accepted message is then delivered to
+/// the application.
+pub trait Verifier: fmt::Debug + Send + Sync {
+    fn verify<'a>(&'a self, header: &'a Header) -> LocalBoxFuture<'a, Verdict>;
+}
+
+/// Custom verifier used by session, shared by all clones of its settings.
+///
+/// Not deserialized, custom verifier is set with [`SessionVerifier::new`].
+/// By default all messages are accepted.
+#[derive(Clone, Debug, Default)]
+pub struct SessionVerifier(Option<Arc<dyn Verifier>>);
+
+impl SessionVerifier {
+    pub fn new(verifier: impl Verifier + 'static) -> SessionVerifier {
+        SessionVerifier(Some(Arc::new(verifier)))
+    }
+
+    pub(crate) async fn verify(&self, header: &Header) -> Verdict {
+        match &self.0 {
+            Some(verifier) => verifier.verify(header).await,
+            None => Verdict::Accept,
+        }
+    }
+}
@@ -0,0 +1,197 @@
+mod common;
+
+use std::{cell::RefCell, rc::Rc};
+
+use common::scenario::{accept, acceptor, acceptor_with, basic_msg, block_on, drain};
+use easyfix_session::{
+    acceptor::Acceptor, admin::AdminServer, messages_storage::InMemoryStorage, settings::Settings,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, DuplexStream},
+    net::{TcpListener, TcpStream},
+    time::{timeout, Duration},
+};
+
+async fn read_output(remote: &mut DuplexStream) -> Vec<u8> {
+    let mut output = Vec::new();
+    let _ = timeout(Duration::from_millis(300), async {
+        let mut buf = [0; 1024];
+        while let Ok(read @ 1..) = remote.read(&mut buf).await {
+            output.extend_from_slice(&buf[..read]);
+        }
+    })
+    .await;
+    output
+}
+
+/// Log on, then execute `commands`, returns responses and data sent
+/// by acceptor after the commands.
+fn run(commands: &[&str]) -> (Vec<String>, Vec<u8>) {
+    let settings = Settings {
+        capture_dir: Some(std::env::temp_dir()),
+        ..common::settings()
+    };
+
+    block_on(async move {
+        let acceptor = acceptor_with(settings, common::session_settings());
+        let log_levels = Rc::new(RefCell::new(Vec::new()));
+        let server = AdminServer::new(acceptor.admin_handle()).with_log_level({
+            let log_levels = log_levels.clone();
+            move |level| match level {
+                "debug" | "info" => {
+                    log_levels.borrow_mut().push(level.to_owned());
+                    Ok(())
+                }
+                _ => Err(format!("invalid level {level}")),
+            }
+        });
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.send(&basic_msg(0)).await;
+        connection.read().await;
+
+        let responses = commands
+            .iter()
+            .map(|command| server.execute(command))
+            .collect();
+        let output = connection.read().await;
+        if commands.contains(&"loglevel debug") {
+            assert_eq!(*log_levels.borrow(), ["debug"]);
+        }
+        connection.close().await;
+        (responses, output)
+    })
+}
+
+#[test]
+fn sessions_listed() {
+    let (responses, _) = run(&["list", "seqnums CLIENT", "seqnums SERVER:CLIENT"]);
+    assert_eq!(
+        responses,
+        [
+            "SERVER:CLIENT FIXT.1.1 connected=true started=true enabled=true \
+             next_sender_seq=2 next_target_seq=2\nOK\n",
+            "next_sender_seq=2 next_target_seq=2\nOK\n",
+            "next_sender_seq=2 next_target_seq=2\nOK\n",
+        ]
+    );
+}
+
+#[test]
+fn session_commands_executed() {
+    let (responses, output) = run(&[
+        "resend CLIENT 1",
+        "disable CLIENT",
+        "logout CLIENT end of day",
+        "loglevel debug",
+    ]);
+    assert_eq!(responses, ["OK\n"; 4]);
+    let output = String::from_utf8_lossy(&output);
+    assert!(output.contains("\x0135=2\x01"), "{output}");
+    assert!(output.contains("\x0135=5\x01"), "{output}");
+    assert!(output.contains("\x0158=end of day\x01"), "{output}");
+}
+
+#[test]
+fn invalid_commands_rejected() {
+    let (responses, _) = run(&[
+        "unknown",
+        "seqnums OTHER",
+        "seqnums",
+        "resend CLIENT x",
+        "resend CLIENT 5 3",
+        "loglevel verbose",
+    ]);
+    assert_eq!(
+        responses,
+        [
+            "ERR unknown command unknown\n",
+            "ERR unknown session OTHER\n",
+            "ERR session not given\n",
+            "ERR invalid sequence number x\n",
+            "ERR Invalid resend range.\n",
+            "ERR invalid level verbose\n",
+        ]
+    );
+}
+
+#[test]
+fn capture_commands_executed() {
+    // Captured bytes are verified in tests/capture.rs, commands are
+    // executed before anything is exchanged
+    let (responses, _) = run(&[
+        "capture CLIENT start 1024",
+        "capture CLIENT show",
+        "capture CLIENT stop",
+    ]);
+    assert_eq!(responses, ["OK\n"; 3]);
+}
+
+#[test]
+fn invalid_capture_commands_rejected() {
+    let (responses, _) = run(&[
+        "capture CLIENT",
+        "capture CLIENT pause",
+        "capture CLIENT start x",
+        "capture CLIENT start 1024 /etc/capture.log",
+        "capture CLIENT start 1024 ../capture.log",
+    ]);
+    assert_eq!(
+        responses,
+        [
+            "ERR capture action not given\n",
+            "ERR unknown capture action pause\n",
+            "ERR invalid max_bytes x\n",
+            "ERR I/O error: capture file /etc/capture.log outside of capture directory\n",
+            "ERR I/O error: capture file ../capture.log outside of capture directory\n",
+        ]
+    );
+}
+
+#[test]
+fn too_long_command_refused() {
+    block_on(async move {
+        let acceptor: Acceptor<InMemoryStorage> =
+            Acceptor::new(common::settings(), Box::new(|_| InMemoryStorage::new()));
+        let server = AdminServer::new(acceptor.admin_handle());
+
+        let (local, mut remote) = tokio::io::duplex(64 * 1024);
+        let (reader, writer) = tokio::io::split(local);
+        let connection =
+            tokio::task::spawn_local(async move { server.handle_connection(reader, writer).await });
+
+        remote.write_all(b"list\n").await.unwrap();
+        remote.write_all(&[b'x'; 8192]).await.unwrap();
+        remote.write_all(b"\nlist\n").await.unwrap();
+        let output = read_output(&mut remote).await;
+
+        assert_eq!(output, b"OK\nERR command too long\n");
+        assert!(connection.await.unwrap().is_ok());
+    });
+}
+
+#[test]
+fn commands_served_over_tcp() {
+    block_on(async move {
+        let acceptor = acceptor(common::session_settings());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        AdminServer::new(acceptor.admin_handle()).start_tcp(listener);
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"seqnums CLIENT\nreset CLIENT\n")
+            .await
+            .unwrap();
+        let mut lines = BufReader::new(stream).lines();
+        let mut responses = Vec::new();
+        for _ in 0..3 {
+            responses.push(lines.next_line().await.unwrap().unwrap());
+        }
+        assert_eq!(
+            responses,
+            ["next_sender_seq=1 next_target_seq=1", "OK", "OK"]
+        );
+    });
+}
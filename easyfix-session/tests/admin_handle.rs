@@ -0,0 +1,122 @@
+mod common;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on, drain};
+use easyfix_macros::fix_str;
+use easyfix_messages::fields::FixStr;
+use easyfix_session::{
+    acceptor::AdminHandle, application::SessionSummary, messages_storage::InMemoryStorage,
+    session_id::SessionId, DisconnectReason, SessionError,
+};
+
+/// Send Logon<A> between `before_logon` and `after_logon`, returns what
+/// session task resolved to, with data sent by acceptor.
+fn run(
+    before_logon: impl FnOnce(&AdminHandle<InMemoryStorage>),
+    after_logon: impl FnOnce(&AdminHandle<InMemoryStorage>),
+) -> (
+    Option<SessionSummary>,
+    Vec<u8>,
+    AdminHandle<InMemoryStorage>,
+) {
+    block_on(async move {
+        let acceptor = acceptor(common::session_settings());
+        let admin = acceptor.admin_handle();
+        before_logon(&admin);
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.send(&basic_msg(0)).await;
+        let mut output = connection.read().await;
+        after_logon(&admin);
+        output.extend(connection.read().await);
+
+        let summary = connection.close().await.expect("session not terminated");
+        (summary, output, admin)
+    })
+}
+
+fn session_id() -> SessionId {
+    common::session_settings().session_id
+}
+
+fn msg_types(output: &[u8]) -> Vec<String> {
+    output
+        .split(|b| *b == b'\x01')
+        .filter_map(|field| field.strip_prefix(b"35="))
+        .map(|value| String::from_utf8_lossy(value).into_owned())
+        .collect()
+}
+
+#[test]
+fn disabled_session_refuses_logon() {
+    let (summary, output, admin) = run(|admin| admin.disable(&session_id()).unwrap(), |_| {});
+    assert_eq!(
+        summary.expect("no summary").disconnect_reason,
+        DisconnectReason::InvalidLogonState
+    );
+    assert!(!msg_types(&output).contains(&"A".to_owned()), "{output:?}");
+    let stats = admin.session_stats().remove(0);
+    assert!(!stats.enabled);
+    assert!(stats.started);
+
+    admin.enable(&session_id()).unwrap();
+    assert!(admin.session_stats().remove(0).enabled);
+}
+
+#[test]
+fn disable_keeps_established_session() {
+    let (summary, output, admin) = run(|_| {}, |admin| admin.disable(&session_id()).unwrap());
+    // Closed by peer, not by acceptor
+    assert_eq!(
+        summary.expect("no summary").disconnect_reason,
+        DisconnectReason::Disconnected
+    );
+    assert_eq!(msg_types(&output), ["A"]);
+    assert!(!admin.session_stats().remove(0).enabled);
+}
+
+#[test]
+fn stop_logs_out_session() {
+    let (_, output, admin) = run(
+        |_| {},
+        |admin| {
+            admin
+                .stop(&session_id(), Some(fix_str!("maintenance").to_owned()))
+                .unwrap()
+        },
+    );
+    assert_eq!(msg_types(&output), ["A", "5"]);
+    let stats = admin.session_stats().remove(0);
+    assert!(!stats.started);
+    assert!(stats.enabled);
+
+    admin.start(&session_id()).unwrap();
+    assert!(admin.session_stats().remove(0).started);
+}
+
+#[test]
+fn stopped_session_refuses_logon() {
+    let (summary, _, _) = run(|admin| admin.stop(&session_id(), None).unwrap(), |_| {});
+    assert_eq!(
+        summary.expect("no summary").disconnect_reason,
+        DisconnectReason::InvalidLogonState
+    );
+}
+
+#[test]
+fn unknown_session_rejected() {
+    let unknown = SessionId::new(
+        fix_str!("FIXT.1.1").to_owned(),
+        fix_str!("SERVER").to_owned(),
+        fix_str!("UNKNOWN").to_owned(),
+    );
+    run(
+        |admin| {
+            assert!(matches!(
+                admin.disable(&unknown),
+                Err(SessionError::UnknownSession)
+            ))
+        },
+        |_| {},
+    );
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, FixString},
+    groups::LinesOfTextGrp,
+    messages::{FixtMessage, Message, News},
+};
+use easyfix_session::{
+    application::{AsEvent, FixEvent},
+    new_header, new_trailer, PauseMode,
+};
+use futures::StreamExt;
+use tokio::time::{sleep, timeout, Duration};
+
+fn news(headline: &str) -> Box<FixtMessage> {
+    let body = Box::new(Message::News(News {
+        headline: FixString::from_ascii_lossy(headline.as_bytes().to_vec()),
+        lines_of_text_grp: vec![LinesOfTextGrp {
+            text: fix_str!("ttl").to_owned(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }));
+    Box::new(FixtMessage {
+        header: Box::new(new_header(body.msg_type())),
+        body,
+        trailer: Box::new(new_trailer()),
+    })
+}
+
+fn headline(msg: &FixtMessage) -> String {
+    let Message::News(ref news) = *msg.body else {
+        panic!("unexpected message: {msg:?}");
+    };
+    news.headline.to_string()
+}
+
+#[derive(Debug, Default)]
+struct Outcome {
+    /// Headlines of sent messages
+    sent: Vec<String>,
+    /// Headlines and TTLs of expired messages
+    expired: Vec<(String, Duration)>,
+}
+
+/// Log on and pause the session, queue three News<B> messages, one with
+/// TTL overridden to 10s, then resume the session after `pause`.
+fn run(app_msg_ttl: Option<Duration>, pause: Duration) -> Outcome {
+    let mut session_settings = common::session_settings();
+    session_settings.app_msg_ttl = app_msg_ttl;
+
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut connection = accept(&acceptor);
+        let events = tokio::task::spawn_local(async move {
+            let mut outcome = Outcome::default();
+            let mut acceptor = Box::pin(acceptor);
+            while let Some(mut event) = acceptor.next().await {
+                match event.as_event() {
+                    FixEvent::Logon(session_id, sender) => {
+                        let session_id = session_id.clone();
+                        acceptor.pause(&session_id, PauseMode::Buffer);
+                        sender.send_raw(news("first")).unwrap();
+                        sender
+                            .send_raw_with_ttl(news("second"), Duration::from_secs(10))
+                            .unwrap();
+                        sender.send_raw(news("third")).unwrap();
+                        // Output is held while paused, so no events are
+                        // missed while waiting here
+                        sleep(pause).await;
+                        acceptor.resume(&session_id);
+                    }
+                    FixEvent::AppMsgOut(msg, _) => outcome.sent.push(headline(&msg)),
+                    FixEvent::AppMsgExpired(_, expired) => {
+                        assert!(expired.age > expired.ttl);
+                        outcome.expired.push((headline(&expired.msg), expired.ttl));
+                    }
+                    FixEvent::Logout(..) => break,
+                    _ => {}
+                }
+            }
+            outcome
+        });
+
+        connection.send(&basic_msg(0)).await;
+        // Let the session resume and send what's left
+        sleep(pause).await;
+        connection.read().await;
+        connection.close().await;
+        timeout(Duration::from_secs(1), events)
+            .await
+            .expect("events not finished")
+            .unwrap()
+    })
+}
+
+#[test]
+fn no_ttl() {
+    let outcome = run(None, Duration::from_millis(100));
+    assert_eq!(outcome.sent, ["first", "second", "third"]);
+    assert!(outcome.expired.is_empty());
+}
+
+#[test]
+fn expired_while_paused() {
+    let ttl = Duration::from_millis(50);
+    let outcome = run(Some(ttl), Duration::from_millis(100));
+    // Message with TTL overridden is still sent
+    assert_eq!(outcome.sent, ["second"]);
+    assert_eq!(
+        outcome.expired,
+        [("first".to_owned(), ttl), ("third".to_owned(), ttl)]
+    );
+}
+
+#[test]
+fn sent_within_ttl() {
+    let outcome = run(Some(Duration::from_secs(5)), Duration::from_millis(100));
+    assert_eq!(outcome.sent, ["first", "second", "third"]);
+    assert!(outcome.expired.is_empty());
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on, collect, drain};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, MsgType},
+    messages::{FixtMessage, Heartbeat, Message},
+};
+use easyfix_session::{
+    application::{SessionEvent, SessionEventKind},
+    session_id::SessionId,
+    settings::{BeginStringMismatchPolicy, SessionSettings},
+    DisconnectReason,
+};
+
+/// Log on and send Heartbeat<0> with FIX.4.4 BeginString<8> to FIXT.1.1
+/// session, returns emitted events and raw data sent by session.
+fn run(policy: BeginStringMismatchPolicy) -> (Vec<SessionEvent>, String) {
+    let session_settings = SessionSettings {
+        begin_string_mismatch: policy,
+        ..common::session_settings()
+    };
+    let logon = basic_msg(0);
+    let mut heartbeat = FixtMessage {
+        header: logon.header.clone(),
+        body: Box::new(Message::Heartbeat(Heartbeat { test_req_id: None })),
+        trailer: logon.trailer.clone(),
+    };
+    heartbeat.header.begin_string = fix_str!("FIX.4.4").to_owned();
+    heartbeat.header.msg_type = MsgType::Heartbeat;
+    heartbeat.header.msg_seq_num = 2;
+
+    block_on(async move {
+        let mut acceptor = acceptor(session_settings);
+        // Registered, so FIX.4.4 messages are accepted by the acceptor
+        let fix44_session_id = SessionId::new(
+            fix_str!("FIX.4.4").to_owned(),
+            fix_str!("SERVER").to_owned(),
+            fix_str!("CLIENT").to_owned(),
+        );
+        acceptor.register_session(
+            fix44_session_id.clone(),
+            SessionSettings {
+                session_id: fix44_session_id,
+                ..common::session_settings()
+            },
+        );
+        let mut subscriber = acceptor.events().subscribe().with_kinds(&[
+            SessionEventKind::BeginStringMismatch,
+            SessionEventKind::AdmMsgIn,
+            SessionEventKind::Reject,
+            SessionEventKind::Logout,
+        ]);
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        for msg in [logon, heartbeat] {
+            connection.send(&msg).await;
+        }
+        let output = connection.read().await;
+        let collected = collect(&mut subscriber).await;
+        connection.close().await;
+        (
+            collected,
+            String::from_utf8_lossy(&output).replace('\x01', "|"),
+        )
+    })
+}
+
+fn mismatch(events: &[SessionEvent]) -> &SessionEvent {
+    events
+        .iter()
+        .find(|event| event.kind() == SessionEventKind::BeginStringMismatch)
+        .unwrap_or_else(|| panic!("BeginStringMismatch not emitted: {events:?}"))
+}
+
+fn heartbeat_delivered(events: &[SessionEvent]) -> bool {
+    events.iter().any(|event| {
+        matches!(event, SessionEvent::AdmMsgIn(_, msg) if matches!(*msg.body, Message::Heartbeat(_)))
+    })
+}
+
+#[test]
+fn mismatch_logout() {
+    let (events, output) = run(BeginStringMismatchPolicy::Logout);
+    let SessionEvent::BeginStringMismatch(_, mismatch) = mismatch(&events) else {
+        unreachable!()
+    };
+    assert_eq!(mismatch.expected, fix_str!("FIXT.1.1"));
+    assert_eq!(mismatch.received, fix_str!("FIX.4.4"));
+    assert_eq!(mismatch.msg_seq_num, 2);
+    assert_eq!(mismatch.policy, BeginStringMismatchPolicy::Logout);
+    assert!(!heartbeat_delivered(&events));
+    assert!(events.iter().any(|event| matches!(
+        event,
+        SessionEvent::Logout(_, DisconnectReason::BeginStringMismatch)
+    )));
+    assert!(output.contains("|35=5|"), "Logout<5> not sent: {output}");
+}
+
+#[test]
+fn mismatch_disconnect() {
+    let (events, output) = run(BeginStringMismatchPolicy::Disconnect);
+    mismatch(&events);
+    assert!(!heartbeat_delivered(&events));
+    assert!(events.iter().any(|event| matches!(
+        event,
+        SessionEvent::Logout(_, DisconnectReason::BeginStringMismatch)
+    )));
+    assert!(!output.contains("|35=5|"), "Logout<5> sent: {output}");
+}
+
+#[test]
+fn mismatch_reject() {
+    let (events, output) = run(BeginStringMismatchPolicy::Reject);
+    mismatch(&events);
+    assert!(!heartbeat_delivered(&events));
+    let Some(SessionEvent::Reject(_, report)) = events
+        .iter()
+        .find(|event| event.kind() == SessionEventKind::Reject)
+    else {
+        panic!("Reject not emitted: {events:?}");
+    };
+    assert_eq!(report.ref_seq_num, 2);
+    assert_eq!(report.ref_tag_id, Some(8));
+    assert!(output.contains("|35=3|"), "Reject<3> not sent: {output}");
+    assert!(!output.contains("|35=5|"), "Logout<5> sent: {output}");
+}
+
+#[test]
+fn mismatch_ignore() {
+    let (events, output) = run(BeginStringMismatchPolicy::Ignore);
+    mismatch(&events);
+    assert!(heartbeat_delivered(&events));
+    assert!(!output.contains("|35=3|"), "Reject<3> sent: {output}");
+    assert!(!output.contains("|35=5|"), "Logout<5> sent: {output}");
+}
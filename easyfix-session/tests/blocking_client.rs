@@ -0,0 +1,69 @@
+mod common;
+
+use std::{net::SocketAddr, sync::mpsc, thread};
+
+use common::scenario::{acceptor, block_on, drain};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, MsgType},
+    messages::{Message, TestRequest},
+};
+use easyfix_session::{blocking::Client, settings::SessionSettings};
+use tokio::net::TcpListener;
+
+/// Start acceptor handling single connection in separate thread, returns
+/// its address.
+fn start_acceptor() -> SocketAddr {
+    let (addr_tx, addr_rx) = mpsc::channel();
+    thread::spawn(move || {
+        block_on(async move {
+            let acceptor = acceptor(common::session_settings());
+
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            addr_tx.send(listener.local_addr().unwrap()).unwrap();
+            let (stream, peer_addr) = listener.accept().await.unwrap();
+            let (reader, writer) = stream.into_split();
+            let session =
+                tokio::task::spawn_local(acceptor.run_session_task(peer_addr, reader, writer));
+            let _drain = drain(acceptor);
+            let _ = session.await;
+        });
+    });
+    addr_rx.recv().unwrap()
+}
+
+fn client_session_settings() -> SessionSettings {
+    let session_settings = common::session_settings();
+    SessionSettings {
+        session_id: session_settings.session_id.clone().reverse_route(),
+        ..session_settings
+    }
+}
+
+#[test]
+fn logon_test_request_logout() {
+    let addr = start_acceptor();
+    let mut settings = common::settings();
+    settings.sender_comp_id = fix_str!("CLIENT").to_owned();
+    let mut client = Client::connect(addr, &settings, client_session_settings()).unwrap();
+
+    let logon = client.logon().unwrap();
+    assert_eq!(logon.header.msg_seq_num, 1);
+    assert_eq!(logon.header.sender_comp_id, fix_str!("SERVER"));
+
+    client
+        .send(Box::new(Message::TestRequest(TestRequest {
+            test_req_id: fix_str!("PROBE").to_owned(),
+        })))
+        .unwrap();
+    let heartbeat = client.recv_until(MsgType::Heartbeat).unwrap();
+    let Message::Heartbeat(heartbeat) = *heartbeat.body else {
+        unreachable!()
+    };
+    assert_eq!(heartbeat.test_req_id.as_deref(), Some(fix_str!("PROBE")));
+    assert_eq!(client.next_sender_msg_seq_num(), 3);
+    assert_eq!(client.next_target_msg_seq_num(), 3);
+
+    let logout = client.logout(None).unwrap();
+    assert_eq!(logout.header.msg_seq_num, 3);
+}
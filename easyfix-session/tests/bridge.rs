@@ -0,0 +1,158 @@
+mod common;
+
+use std::{cell::RefCell, rc::Rc};
+
+use assert_matches::assert_matches;
+use common::scenario::{accept, acceptor, basic_msg, block_on, Connection, IDLE};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, FixString, SeqNum},
+    groups::LinesOfTextGrp,
+    messages::{FixtMessage, Heartbeat, Message, News},
+};
+use easyfix_session::{
+    application::SessionSummary,
+    bridge::{bridge, BridgeError, MessagesSink},
+};
+use futures::{SinkExt, StreamExt};
+use tokio::time::sleep;
+
+fn news(headline: &str) -> Message {
+    Message::News(News {
+        headline: FixString::from_ascii_lossy(headline.as_bytes().to_vec()),
+        lines_of_text_grp: vec![LinesOfTextGrp {
+            text: fix_str!("bridge").to_owned(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    })
+}
+
+/// Message sent by counterparty.
+fn inbound(msg_seq_num: SeqNum, body: Message) -> FixtMessage {
+    let mut msg = basic_msg(0);
+    msg.header.msg_type = body.msg_type();
+    msg.header.msg_seq_num = msg_seq_num;
+    *msg.body = body;
+    msg
+}
+
+fn headlines<'a>(messages: impl IntoIterator<Item = &'a FixtMessage>) -> Vec<String> {
+    messages
+        .into_iter()
+        .filter_map(|msg| match *msg.body {
+            Message::News(ref news) => Some(news.headline.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Acceptor session wired through the bridge, messages from the stream are
+/// collected by background task (running until `LocalSet` is dropped).
+struct Bridged {
+    connection: Connection<Option<SessionSummary>>,
+    sink: MessagesSink,
+    received: Rc<RefCell<Vec<FixtMessage>>>,
+}
+
+impl Bridged {
+    fn new() -> Bridged {
+        let session_settings = common::session_settings();
+        let acceptor = acceptor(session_settings.clone());
+        let connection = accept(&acceptor);
+        let (stream, sink) = bridge(acceptor, session_settings.session_id);
+        let received = Rc::new(RefCell::new(Vec::new()));
+        tokio::task::spawn_local({
+            let received = received.clone();
+            async move {
+                let mut stream = Box::pin(stream);
+                while let Some(msg) = stream.next().await {
+                    received.borrow_mut().push(*msg);
+                }
+            }
+        });
+        Bridged {
+            connection,
+            sink,
+            received,
+        }
+    }
+
+    async fn log_on(&mut self) {
+        self.connection.send(&basic_msg(0)).await;
+        self.connection.read_messages().await;
+    }
+
+    fn received(&self) -> Vec<String> {
+        headlines(self.received.borrow().iter())
+    }
+}
+
+#[test]
+fn inbound_messages_streamed() {
+    block_on(async {
+        let mut bridged = Bridged::new();
+        bridged.log_on().await;
+
+        bridged
+            .connection
+            .send(&inbound(2, Message::Heartbeat(Heartbeat::default())))
+            .await;
+        bridged.connection.send(&inbound(3, news("inbound"))).await;
+        bridged.connection.read_messages().await;
+
+        // Admin messages are not streamed
+        assert_eq!(bridged.received.borrow().len(), 1);
+        assert_eq!(bridged.received(), ["inbound"]);
+    });
+}
+
+#[test]
+fn outbound_messages_sent() {
+    block_on(async {
+        let mut bridged = Bridged::new();
+        assert!(!bridged.sink.is_logged_on());
+        assert_matches!(
+            bridged.sink.send(Box::new(news("early"))).await,
+            Err(BridgeError::NotLoggedOn(_))
+        );
+
+        bridged.log_on().await;
+        assert!(bridged.sink.is_logged_on());
+        bridged.sink.send(Box::new(news("outbound"))).await.unwrap();
+        assert_matches!(
+            bridged
+                .sink
+                .send(Box::new(Message::Heartbeat(Heartbeat::default())))
+                .await,
+            Err(BridgeError::AdminMessage(_))
+        );
+
+        let sent = bridged.connection.read_messages().await;
+        assert_eq!(headlines(&sent), ["outbound"]);
+    });
+}
+
+#[test]
+fn counterparty_disconnected() {
+    block_on(async {
+        let mut bridged = Bridged::new();
+        bridged.log_on().await;
+        assert!(bridged.sink.is_logged_on());
+
+        let Bridged {
+            connection,
+            mut sink,
+            ..
+        } = bridged;
+        connection.close().await.expect("session not finished");
+        // Let the stream handle Logout event
+        sleep(IDLE).await;
+
+        assert!(!sink.is_logged_on());
+        assert_matches!(
+            sink.send(Box::new(news("late"))).await,
+            Err(BridgeError::NotLoggedOn(_))
+        );
+    });
+}
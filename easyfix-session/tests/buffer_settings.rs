@@ -0,0 +1,59 @@
+mod common;
+
+use common::scenario::{accept, acceptor_with, basic_msg, block_on, drain};
+use easyfix_session::{
+    application::SessionSummary,
+    settings::{BufferGrowth, BufferSettings},
+};
+
+/// Log on with given buffer settings, returns what session task resolved
+/// to and data sent by acceptor.
+fn run(buffers: BufferSettings) -> (Option<SessionSummary>, Vec<u8>) {
+    let mut settings = common::settings();
+    settings.buffers = buffers;
+
+    block_on(async move {
+        let acceptor = acceptor_with(settings, common::session_settings());
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.send(&basic_msg(0)).await;
+        let output = connection.read().await;
+
+        let summary = connection.close().await.expect("session not terminated");
+        (summary, output)
+    })
+}
+
+#[test]
+fn small_buffers_resized() {
+    let (summary, output) = run(BufferSettings {
+        read_buffer_capacity: 16,
+        read_buffer_growth: BufferGrowth::Linear(16),
+        serialize_buffer_capacity: 16,
+        ..Default::default()
+    });
+    let state = summary.expect("no summary").state;
+    assert!(output.windows(5).any(|field| field == b"\x0135=A"));
+    assert!(state.read_buffer_resizes > 1, "{state:?}");
+    assert_eq!(state.serialize_buffer_resizes, state.messages_sent);
+}
+
+#[test]
+fn default_buffers_not_resized() {
+    let (summary, _) = run(BufferSettings::default());
+    let state = summary.expect("no summary").state;
+    assert_eq!(state.read_buffer_resizes, 0);
+    assert_eq!(state.serialize_buffer_resizes, 0);
+}
+
+#[test]
+fn read_buffer_max_capacity_exceeded() {
+    let (summary, output) = run(BufferSettings {
+        read_buffer_capacity: 16,
+        read_buffer_max_capacity: Some(32),
+        ..Default::default()
+    });
+    assert!(summary.is_none());
+    assert!(output.is_empty());
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use chrono::{NaiveDate, Utc};
+use common::scenario::{accept, acceptor, basic_msg, block_on, drain, IDLE};
+use easyfix_session::{
+    acceptor::metrics::{ConnectionEvent, LogonFailure},
+    calendar::{Calendar, SessionCalendar, WeekdayCalendar},
+    DisconnectReason,
+};
+use futures::StreamExt;
+use tokio::time::timeout;
+
+/// Send Logon<A> to acceptor using given calendar, returns connection
+/// events.
+fn logon(calendar: SessionCalendar) -> Vec<ConnectionEvent> {
+    let mut session_settings = common::session_settings();
+    session_settings.calendar = calendar;
+
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut connection_events = acceptor.connection_events();
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.send(&basic_msg(0)).await;
+        connection.read().await;
+        connection.close().await;
+
+        let mut received = Vec::new();
+        while let Ok(Some(event)) = timeout(IDLE, connection_events.next()).await {
+            received.push(event);
+        }
+        received
+    })
+}
+
+#[test]
+fn weekday_calendar() {
+    let calendar = WeekdayCalendar::business_week()
+        .with_holidays([NaiveDate::from_ymd_opt(2024, 12, 25).unwrap()]);
+
+    // Tuesday
+    assert!(calendar.is_business_day(NaiveDate::from_ymd_opt(2024, 12, 24).unwrap()));
+    // Holiday
+    assert!(!calendar.is_business_day(NaiveDate::from_ymd_opt(2024, 12, 25).unwrap()));
+    // Saturday
+    assert!(!calendar.is_business_day(NaiveDate::from_ymd_opt(2024, 12, 28).unwrap()));
+    // Every day is a business day by default
+    assert!(
+        WeekdayCalendar::default().is_business_day(NaiveDate::from_ymd_opt(2024, 12, 28).unwrap())
+    );
+}
+
+#[test]
+fn logon_on_business_day() {
+    let events = logon(SessionCalendar::default());
+    assert!(
+        matches!(events[1], ConnectionEvent::LogonSucceeded { .. }),
+        "{events:?}"
+    );
+}
+
+#[test]
+fn logon_on_holiday_rejected() {
+    let today = Utc::now().date_naive();
+    let events = logon(SessionCalendar::new(
+        WeekdayCalendar::default().with_holidays([today]),
+    ));
+    assert!(
+        matches!(
+            events[1],
+            ConnectionEvent::LogonFailed {
+                reason: LogonFailure::Rejected(_, DisconnectReason::InvalidLogonState),
+                ..
+            }
+        ),
+        "{events:?}"
+    );
+}
@@ -0,0 +1,179 @@
+mod common;
+
+use std::{fs, io, path::PathBuf};
+
+use common::scenario::{accept, acceptor_with, basic_msg, block_on, drain};
+use easyfix_session::{
+    capture::{CaptureSettings, CaptureSink, CapturedChunk},
+    inspector::MsgDirection,
+    settings::Settings,
+};
+
+/// Bytes captured in `direction`, concatenated.
+fn captured(chunks: &[CapturedChunk], direction: MsgDirection) -> Vec<u8> {
+    chunks
+        .iter()
+        .filter(|chunk| chunk.direction == direction)
+        .flat_map(|chunk| chunk.bytes.iter().copied())
+        .collect()
+}
+
+struct Outcome {
+    /// Logon<A> sent to acceptor
+    logon: Vec<u8>,
+    /// Data sent by acceptor in response to Logon<A>
+    response: Vec<u8>,
+    /// Chunks captured in memory when capture was stopped
+    stopped: Vec<CapturedChunk>,
+    /// Chunks captured in memory after Heartbeat<0> sent once capture
+    /// was stopped
+    after_stop: Vec<CapturedChunk>,
+}
+
+/// Start capture with `settings`, log on, then stop the capture.
+fn run(settings: CaptureSettings) -> Outcome {
+    let acceptor_settings = Settings {
+        capture_dir: Some(std::env::temp_dir()),
+        ..common::settings()
+    };
+    let session_settings = common::session_settings();
+    let session_id = session_settings.session_id.clone();
+
+    block_on(async move {
+        let acceptor = acceptor_with(acceptor_settings, session_settings);
+        let admin = acceptor.admin_handle();
+        // Started before connection, so Logon<A> is captured as well
+        admin.start_capture(&session_id, settings).unwrap();
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        let logon = basic_msg(0).serialize();
+        connection.send_raw(&logon).await;
+        let response = connection.read().await;
+
+        let stopped = admin.stop_capture(&session_id).unwrap();
+
+        connection.send(&basic_msg(2)).await;
+        connection.read().await;
+        let after_stop = admin.captured(&session_id).unwrap();
+        connection.close().await;
+
+        Outcome {
+            logon,
+            response,
+            stopped,
+            after_stop,
+        }
+    })
+}
+
+#[test]
+fn exact_bytes_captured_in_memory() {
+    let outcome = run(CaptureSettings {
+        sink: CaptureSink::Memory,
+        max_bytes: 1024 * 1024,
+    });
+    assert!(!outcome.response.is_empty());
+    assert_eq!(
+        captured(&outcome.stopped, MsgDirection::Inbound),
+        outcome.logon
+    );
+    assert_eq!(
+        captured(&outcome.stopped, MsgDirection::Outbound),
+        outcome.response
+    );
+    assert!(outcome
+        .stopped
+        .windows(2)
+        .all(|pair| pair[0].timestamp <= pair[1].timestamp));
+    assert_eq!(outcome.after_stop, []);
+}
+
+#[test]
+fn oldest_chunks_dropped_when_limit_reached() {
+    let response_len = run(CaptureSettings {
+        sink: CaptureSink::Memory,
+        max_bytes: 1024 * 1024,
+    })
+    .response
+    .len();
+
+    let outcome = run(CaptureSettings {
+        sink: CaptureSink::Memory,
+        max_bytes: response_len,
+    });
+    assert_eq!(captured(&outcome.stopped, MsgDirection::Inbound), []);
+    assert_eq!(
+        captured(&outcome.stopped, MsgDirection::Outbound),
+        outcome.response
+    );
+}
+
+#[test]
+fn captured_to_file() {
+    let file_name = format!("easyfix-capture-{}.log", std::process::id());
+    let path = std::env::temp_dir().join(&file_name);
+    let _ = fs::remove_file(&path);
+
+    let outcome = run(CaptureSettings {
+        sink: CaptureSink::File(PathBuf::from(file_name)),
+        max_bytes: 1024 * 1024,
+    });
+    assert_eq!(outcome.stopped, []);
+
+    let content = fs::read(&path).unwrap();
+    let header_end = content.iter().position(|b| *b == b'\n').unwrap();
+    let header = String::from_utf8_lossy(&content[..header_end]);
+    let fields: Vec<_> = header.split(' ').collect();
+    assert_eq!(fields[1..], ["IN", &outcome.logon.len().to_string()]);
+    let logon_end = header_end + 1 + outcome.logon.len();
+    assert_eq!(content[header_end + 1..logon_end], outcome.logon);
+    assert_eq!(content[logon_end], b'\n');
+    let rest = String::from_utf8_lossy(&content[logon_end + 1..]);
+    assert!(rest.split('\n').next().unwrap().contains(" OUT "), "{rest}");
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn capture_file_outside_capture_dir_refused() {
+    let start = |capture_dir: Option<PathBuf>, path: PathBuf| {
+        block_on(async move {
+            let session_settings = common::session_settings();
+            let session_id = session_settings.session_id.clone();
+            let acceptor = acceptor_with(
+                Settings {
+                    capture_dir,
+                    ..common::settings()
+                },
+                session_settings,
+            );
+            let settings = CaptureSettings {
+                sink: CaptureSink::File(path),
+                max_bytes: 1024,
+            };
+            match acceptor.admin_handle().start_capture(&session_id, settings) {
+                Ok(()) => panic!("capture started"),
+                Err(easyfix_session::Error::Io(err)) => err.kind(),
+                Err(err) => panic!("unexpected error {err:?}"),
+            }
+        })
+    };
+
+    let capture_dir = std::env::temp_dir().join("easyfix-capture-dir");
+    for path in [
+        capture_dir.join("capture.log"),
+        PathBuf::from("../capture.log"),
+        PathBuf::from("nested/../../capture.log"),
+        PathBuf::new(),
+    ] {
+        assert_eq!(
+            start(Some(capture_dir.clone()), path),
+            io::ErrorKind::InvalidInput
+        );
+    }
+    assert_eq!(
+        start(None, PathBuf::from("capture.log")),
+        io::ErrorKind::PermissionDenied
+    );
+}
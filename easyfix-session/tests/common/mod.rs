@@ -0,0 +1,48 @@
+pub mod scenario;
+
+use std::time::Duration;
+
+use easyfix_macros::fix_str;
+use easyfix_messages::fields::FixStr;
+use easyfix_session::{
+    session_id::SessionId,
+    settings::{SessionSettings, Settings},
+};
+
+// Not used by every test
+#[allow(dead_code)]
+pub const SESSION_BASIC: &str = include_str!("../logs/session_basic.log");
+
+pub fn settings() -> Settings {
+    Settings {
+        sender_comp_id: fix_str!("SERVER").to_owned(),
+        sender_sub_id: None,
+        heartbeat_interval: Duration::from_secs(30),
+        auto_disconnect_after_no_logon_received: Duration::from_secs(3),
+        auto_disconnect_after_no_heartbeat: 3,
+        buffer_pool: None,
+        duplicate_tags: Default::default(),
+        other_msg_types: false,
+        required_tags: Default::default(),
+        max_bytes_before_logon: None,
+        pre_logon: Default::default(),
+        session_log: None,
+        tcp_keepalive: None,
+        read_idle: None,
+        buffers: Default::default(),
+        group_limits: Default::default(),
+        framing_errors: Default::default(),
+        capture_dir: None,
+    }
+}
+
+pub fn session_settings() -> SessionSettings {
+    SessionSettings {
+        enable_next_expected_msg_seq_num: false,
+        ..SessionSettings::new(SessionId::new(
+            fix_str!("FIXT.1.1").to_owned(),
+            fix_str!("SERVER").to_owned(),
+            fix_str!("CLIENT").to_owned(),
+        ))
+    }
+}
@@ -0,0 +1,430 @@
+//! Sessions connected to in-memory streams, driven by tests.
+//!
+//! [`Connection`] is the building block shared by all tests: a session
+//! task of acceptor or initiator connected to [`DuplexStream`] playing the
+//! counterparty. On top of it scripted scenarios ([`run`], [`run_with`],
+//! [`exchange`]) drive acceptor session with inbound messages and compare
+//! expected responses field by field, [`Connection::play`] does the same
+//! on any connection.
+
+// Not used by every test
+#![allow(dead_code)]
+
+use std::{
+    future::Future,
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+};
+
+use bytes::BytesMut;
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, Utc, UtcTimestamp},
+    messages::FixtMessage,
+    test_util::{pipes_from_soh, soh_from_pipes},
+};
+use easyfix_session::{
+    acceptor::Acceptor,
+    application::{EventSubscriber, SessionEvent, SessionSummary},
+    initiator::Initiator,
+    io::iter_messages,
+    messages_storage::{InMemoryStorage, MessagesStorage},
+    replay::parse_log,
+    settings::{SessionSettings, Settings},
+};
+use futures::{future::LocalBoxFuture, Stream, StreamExt};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt, DuplexStream},
+    runtime::{Builder, Runtime},
+    task::{JoinHandle, LocalSet},
+    time::{timeout, Duration},
+};
+
+/// Silence after which session is considered done with responding.
+pub const IDLE: Duration = Duration::from_millis(100);
+
+/// Peer address of connections made by [`accept()`].
+pub const PEER_ADDR: SocketAddr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0));
+
+/// Current thread runtime with IO and time drivers enabled.
+pub fn runtime() -> Runtime {
+    Builder::new_current_thread()
+        .enable_io()
+        .enable_time()
+        .build()
+        .unwrap()
+}
+
+/// Run `future` to completion on [`runtime()`], inside `LocalSet` as
+/// sessions are `!Send`.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    LocalSet::new().block_on(&runtime(), future)
+}
+
+/// Message `index` of `SESSION_BASIC` log, as seen by SERVER, with
+/// SendingTime<52> set to current time.
+pub fn basic_msg(index: usize) -> FixtMessage {
+    let entries = parse_log(super::SESSION_BASIC, fix_str!("SERVER")).expect("invalid log");
+    fresh(*entries[index].msg.clone())
+}
+
+/// `msg` with SendingTime<52> set to current time, so it passes latency
+/// check.
+pub fn fresh(mut msg: FixtMessage) -> FixtMessage {
+    msg.header.sending_time =
+        UtcTimestamp::with_precision(Utc::now(), msg.header.sending_time.precision());
+    msg
+}
+
+/// Acceptor with `InMemoryStorage`, with `session_settings` registered.
+pub fn acceptor(session_settings: SessionSettings) -> Acceptor<InMemoryStorage> {
+    acceptor_with(super::settings(), session_settings)
+}
+
+/// Acceptor configured with `settings`, see [`acceptor()`].
+pub fn acceptor_with(
+    settings: Settings,
+    session_settings: SessionSettings,
+) -> Acceptor<InMemoryStorage> {
+    let mut acceptor = Acceptor::new(settings, Box::new(|_| InMemoryStorage::new()));
+    acceptor.register_session(session_settings.session_id.clone(), session_settings);
+    acceptor
+}
+
+/// Events of acceptor or initiator handled with default responses until
+/// dropped.
+pub struct Drain(JoinHandle<()>);
+
+impl Drop for Drain {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Handle `events` with default responses in background task, subscribers
+/// and admin handles have to be created before.
+pub fn drain<E: Stream + 'static>(events: E) -> Drain {
+    Drain(tokio::task::spawn_local(async move {
+        let mut events = Box::pin(events);
+        while events.next().await.is_some() {}
+    }))
+}
+
+/// Events received by `subscriber` until it stays silent for [`IDLE`].
+pub async fn collect(subscriber: &mut EventSubscriber) -> Vec<SessionEvent> {
+    let mut events = Vec::new();
+    while let Ok(Some(event)) = timeout(IDLE, subscriber.next()).await {
+        events.push(event);
+    }
+    events
+}
+
+enum Task<T> {
+    Pending(LocalBoxFuture<'static, T>),
+    Running(JoinHandle<T>),
+}
+
+/// Session task connected to in-memory stream, `remote` is the
+/// counterparty side of it.
+pub struct Connection<T> {
+    pub remote: DuplexStream,
+    task: Option<Task<T>>,
+}
+
+impl<T: 'static> Connection<T> {
+    /// Spawn session task, if not running yet. Done by every method
+    /// using the connection, needed only when `remote` is used directly.
+    pub fn start(&mut self) {
+        self.task = match self.task.take() {
+            Some(Task::Pending(session)) => Some(Task::Running(tokio::task::spawn_local(session))),
+            task => task,
+        };
+    }
+
+    /// Send `msg` with SendingTime<52> set to current time.
+    pub async fn send(&mut self, msg: &FixtMessage) {
+        self.send_raw(&fresh(msg.clone()).serialize()).await;
+    }
+
+    pub async fn send_raw(&mut self, bytes: &[u8]) {
+        self.start();
+        self.remote.write_all(bytes).await.unwrap();
+    }
+
+    /// Play `steps` as counterparty, see [`run`]. Outbound steps before
+    /// the first inbound one are expected to be sent by session on its
+    /// own, e.g. Logon<A> of initiator.
+    pub async fn play(&mut self, steps: &[Step]) {
+        self.start();
+        play(&mut self.remote, steps).await;
+    }
+
+    /// Everything session sends until it stays silent for [`IDLE`] or
+    /// closes the connection.
+    pub async fn read(&mut self) -> Vec<u8> {
+        self.start();
+        let mut output = Vec::new();
+        let mut buf = [0; 4096];
+        while let Ok(Ok(read @ 1..)) = timeout(IDLE, self.remote.read(&mut buf)).await {
+            output.extend_from_slice(&buf[..read]);
+        }
+        output
+    }
+
+    /// Messages session sends until it stays silent for [`IDLE`] or closes
+    /// the connection.
+    pub async fn read_messages(&mut self) -> Vec<FixtMessage> {
+        let mut output = BytesMut::from(&self.read().await[..]);
+        iter_messages(&mut output)
+            .map(|msg| *msg.expect("invalid output"))
+            .collect()
+    }
+
+    /// Close counterparty side and wait (up to a second) until session
+    /// finishes, `None` when it doesn't.
+    pub async fn close(mut self) -> Option<T> {
+        self.start();
+        drop(self.remote);
+        let Some(Task::Running(session)) = self.task else {
+            unreachable!()
+        };
+        timeout(Duration::from_secs(1), session)
+            .await
+            .ok()
+            .map(|result| result.unwrap())
+    }
+
+    /// Wait (up to `within`) until session finishes on its own, `None`
+    /// when it doesn't.
+    pub async fn finish(mut self, within: Duration) -> Option<T> {
+        self.start();
+        let Some(Task::Running(session)) = self.task.take() else {
+            unreachable!()
+        };
+        timeout(within, session)
+            .await
+            .ok()
+            .map(|result| result.unwrap())
+    }
+}
+
+/// New session task of `acceptor` connected to in-memory stream.
+pub fn accept<S: MessagesStorage + 'static>(
+    acceptor: &Acceptor<S>,
+) -> Connection<Option<SessionSummary>> {
+    let (local, remote) = tokio::io::duplex(64 * 1024);
+    let (reader, writer) = tokio::io::split(local);
+    let mut connection = Connection {
+        remote,
+        task: Some(Task::Pending(Box::pin(
+            acceptor.run_session_task(PEER_ADDR, reader, writer),
+        ))),
+    };
+    connection.start();
+    connection
+}
+
+/// New session task of `initiator` connected to in-memory stream. Task is
+/// started with the first use of connection, so connections for
+/// reconnection can be prepared before initiator is moved to [`drain()`].
+pub fn initiate<S: MessagesStorage + 'static>(
+    initiator: &Initiator<S>,
+) -> Connection<SessionSummary> {
+    let (local, remote) = tokio::io::duplex(64 * 1024);
+    let (reader, writer) = tokio::io::split(local);
+    Connection {
+        remote,
+        task: Some(Task::Pending(Box::pin(
+            initiator.run_session_task(reader, writer),
+        ))),
+    }
+}
+
+/// Tags not compared in outbound messages.
+const IGNORED_TAGS: &[&str] = &["8", "9", "10", "49", "52", "56", "58", "122"];
+
+pub const LOGON: &str = "35=A|34=1|98=0|108=30|1137=9";
+pub const LOGON_RESPONSE: &str = "35=A|34=1|98=0|108=30|1137=9";
+
+pub enum Step {
+    /// Inbound message, see `inbound()`.
+    In(&'static str),
+    /// Inbound message, with `.1` replaced by `.2` after BodyLength<9> and
+    /// CheckSum<10> are calculated.
+    InPatched(&'static str, &'static str, &'static str),
+    /// Outbound message expected in response to last inbound one.
+    Out(&'static str),
+    /// Connection expected to be closed by session.
+    Disconnected,
+}
+
+fn now() -> String {
+    Utc::now().format("%Y%m%d-%H:%M:%S%.3f").to_string()
+}
+
+/// Build inbound message from `|` separated `fields`, starting with
+/// MsgType<35>. SenderCompID<49>, TargetCompID<56> and SendingTime<52> are
+/// added unless given, BodyLength<9> and CheckSum<10> are always added.
+/// BeginString<8> is `FIXT.1.1`, unless given before MsgType<35>.
+pub fn inbound(fields: &str) -> String {
+    let (begin_string, fields) = match fields.strip_prefix("8=") {
+        Some(rest) => rest.split_once('|').expect("BeginString without MsgType"),
+        None => ("FIXT.1.1", fields),
+    };
+    let has = |tag: &str| fields.split('|').any(|f| f.starts_with(&format!("{tag}=")));
+    let (msg_type, rest) = fields.split_once('|').unwrap_or((fields, ""));
+    let mut body = format!("{msg_type}|");
+    if !has("49") {
+        body.push_str("49=CLIENT|");
+    }
+    if !has("56") {
+        body.push_str("56=SERVER|");
+    }
+    if !has("52") {
+        body.push_str(&format!("52={}|", now()));
+    }
+    if !rest.is_empty() {
+        body.push_str(rest);
+        body.push('|');
+    }
+    let msg = format!("8={begin_string}|9={}|{body}", body.len());
+    let checksum = soh_from_pipes(&msg).into_iter().fold(0u8, u8::wrapping_add);
+    format!("{msg}10={checksum:03}|")
+}
+
+/// Sorted fields of message, without `IGNORED_TAGS`.
+fn normalize(msg: &str) -> Vec<String> {
+    let mut fields: Vec<_> = msg
+        .split(['|', '\x01'])
+        .filter(|f| !f.is_empty())
+        .filter(|f| !IGNORED_TAGS.contains(&f.split_once('=').map_or(*f, |(t, _)| t)))
+        .map(str::to_owned)
+        .collect();
+    fields.sort();
+    fields
+}
+
+/// Read everything session sends until it stays silent for a while,
+/// split into messages with `|` as separator. Closed connection is
+/// reported as `<disconnected>` message.
+async fn read_raw(remote: &mut DuplexStream) -> Vec<String> {
+    let mut disconnected = false;
+    let mut buf = Vec::new();
+    loop {
+        let mut chunk = [0; 4096];
+        match timeout(IDLE, remote.read(&mut chunk)).await {
+            Ok(Ok(0)) | Ok(Err(_)) => {
+                disconnected = true;
+                break;
+            }
+            Ok(Ok(n)) => buf.extend_from_slice(&chunk[..n]),
+            Err(_) => break,
+        }
+    }
+    let text = String::from_utf8(buf).expect("non UTF-8 output");
+    let mut msg_start = 0;
+    let mut messages = Vec::new();
+    while let Some(pos) = text[msg_start..].find("\x0110=") {
+        let end = msg_start + pos + 8;
+        messages.push(pipes_from_soh(&text[msg_start..end]));
+        msg_start = end;
+    }
+    if disconnected {
+        messages.push("<disconnected>".to_owned());
+    }
+    messages
+}
+
+/// Read everything session sends until it stays silent for a while.
+async fn read_responses(remote: &mut DuplexStream) -> Vec<Vec<String>> {
+    read_raw(remote)
+        .await
+        .into_iter()
+        .map(|msg| match msg.as_str() {
+            "<disconnected>" => vec![msg],
+            _ => normalize(&msg),
+        })
+        .collect()
+}
+
+/// Run `steps` against acceptor session with default settings.
+pub fn run(steps: &[Step]) {
+    run_with(super::session_settings(), steps)
+}
+
+/// Run `steps` against acceptor session configured with `session_settings`.
+pub fn run_with(session_settings: SessionSettings, steps: &[Step]) {
+    with_session(session_settings, |mut remote| async move {
+        play(&mut remote, steps).await;
+        remote
+    });
+}
+
+/// Play `steps` on `remote` side of session connection, see
+/// [`Connection::play`].
+async fn play(remote: &mut DuplexStream, steps: &[Step]) {
+    let mut steps = steps.iter().peekable();
+    let mut msg: Option<String> = None;
+    let mut disconnected = false;
+    loop {
+        let mut expected = Vec::new();
+        while let Some(Step::Out(_) | Step::Disconnected) = steps.peek() {
+            match steps.next() {
+                Some(Step::Out(out)) => expected.push(normalize(out)),
+                _ => expected.push(vec!["<disconnected>".to_owned()]),
+            }
+        }
+        if msg.is_some() || !expected.is_empty() {
+            let actual = read_responses(remote).await;
+            disconnected = actual.last().is_some_and(|r| r[0] == "<disconnected>");
+            match &msg {
+                Some(msg) => assert_eq!(expected, actual, "unexpected response to `{msg}`"),
+                None => assert_eq!(expected, actual, "unexpected output before inbound message"),
+            }
+        }
+
+        let next = match steps.next() {
+            Some(Step::In(fields)) => inbound(fields),
+            Some(Step::InPatched(fields, from, to)) => inbound(fields).replacen(from, to, 1),
+            Some(Step::Out(_) | Step::Disconnected) => unreachable!(),
+            None => break,
+        };
+        assert!(!disconnected, "session disconnected before `{next}`");
+        remote.write_all(&soh_from_pipes(&next)).await.unwrap();
+        msg = Some(next);
+    }
+}
+
+/// Send `inbound` messages (see `inbound()`) to acceptor session configured
+/// with `session_settings`, one by one. Returns messages sent in response
+/// to each of them exactly as serialized, with `|` as separator.
+pub fn exchange(session_settings: SessionSettings, inbound: &[&str]) -> Vec<Vec<String>> {
+    let mut responses = Vec::new();
+    with_session(session_settings, |mut remote| async {
+        for fields in inbound {
+            remote
+                .write_all(&soh_from_pipes(self::inbound(fields)))
+                .await
+                .unwrap();
+            responses.push(read_raw(&mut remote).await);
+        }
+        remote
+    });
+    responses
+}
+
+/// Run acceptor session connected to stream passed to `f`, wait until
+/// session finishes after stream returned by `f` is dropped.
+fn with_session<F, Fut>(session_settings: SessionSettings, f: F)
+where
+    F: FnOnce(DuplexStream) -> Fut,
+    Fut: Future<Output = DuplexStream>,
+{
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.remote = f(connection.remote).await;
+        connection.close().await.expect("session not finished");
+    });
+}
@@ -0,0 +1,48 @@
+use easyfix_macros::fix_str;
+use easyfix_messages::fields::FixStr;
+use easyfix_session::{
+    session_id::SessionId,
+    settings::{CompIdCase, CompIdPolicy},
+};
+
+#[test]
+fn default_policy_requires_exact_match() {
+    let policy = CompIdPolicy::default();
+    assert!(policy.matches(fix_str!("SERVER"), fix_str!("SERVER")));
+    assert!(!policy.matches(fix_str!("SERVER"), fix_str!("server")));
+    assert!(!policy.matches(fix_str!("SERVER"), fix_str!("SERVER  ")));
+    assert_eq!(policy.normalize(fix_str!("Server")), fix_str!("Server"));
+}
+
+#[test]
+fn case_insensitive_and_padded() {
+    let policy = CompIdPolicy {
+        case_insensitive: true,
+        ignore_padding: true,
+        case: CompIdCase::Upper,
+        pad_width: Some(8),
+        pad_char: '_',
+    };
+    assert!(policy.matches(fix_str!("SERVER"), fix_str!("server__")));
+    assert!(policy.matches(fix_str!("SERVER__"), fix_str!("Server")));
+    assert!(!policy.matches(fix_str!("SERVER"), fix_str!("SERVER1")));
+    assert_eq!(policy.normalize(fix_str!("Server")), fix_str!("SERVER__"));
+    // Longer CompIDs are never truncated
+    assert_eq!(
+        policy.normalize(fix_str!("LongServer")),
+        fix_str!("LONGSERVER")
+    );
+
+    let registered = SessionId::new(
+        fix_str!("FIXT.1.1").to_owned(),
+        fix_str!("SERVER").to_owned(),
+        fix_str!("CLIENT").to_owned(),
+    );
+    let received = SessionId::new(
+        fix_str!("FIXT.1.1").to_owned(),
+        fix_str!("server").to_owned(),
+        fix_str!("Client__").to_owned(),
+    );
+    assert!(policy.matches_session_id(&registered, &received));
+    assert!(!CompIdPolicy::default().matches_session_id(&registered, &received));
+}
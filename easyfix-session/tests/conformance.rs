@@ -0,0 +1,635 @@
+//! Session level test cases from FIX specification ("Test cases applicable
+//! to all FIX systems"), run against acceptor side of the session, cases
+//! 1A and 13.a against initiator.
+//!
+//! | Case | Description                                              | Test                                  |
+//! |------|----------------------------------------------------------|---------------------------------------|
+//! | 1A   | Valid Logon sent                                         | `case_1a_valid_logon`                 |
+//! | 1A   | Logon response with MsgSeqNum too high                   | `case_1a_logon_response_seq_num_too_high` |
+//! | 1B.a | Valid Logon                                              | `case_1b_a_valid_logon`               |
+//! | 1B.b | Logon with MsgSeqNum too high                            | `case_1b_b_logon_seq_num_too_high`    |
+//! | 1B.c | Logon with MsgSeqNum too low                             | `case_1b_c_logon_seq_num_too_low`     |
+//! | 1B.d | First message not Logon                                  | `case_1b_d_first_message_not_logon`   |
+//! | 2.a  | MsgSeqNum as expected                                    | `case_2_a_seq_num_expected`           |
+//! | 2.b  | MsgSeqNum higher than expected                           | `case_2_b_seq_num_too_high`           |
+//! | 2.c  | MsgSeqNum lower than expected, no PossDupFlag            | `case_2_c_seq_num_too_low`            |
+//! | 2.d  | Garbled message                                          | `case_2_d_garbled`                    |
+//! | 2.e  | PossDupFlag, OrigSendingTime <= SendingTime              | `case_2_e_poss_dup_ignored`           |
+//! | 2.f  | PossDupFlag, OrigSendingTime > SendingTime               | `case_2_f_orig_sending_time_too_late` |
+//! | 2.g  | PossDupFlag, OrigSendingTime missing                     | `case_2_g_orig_sending_time_missing`  |
+//! | 2.i  | SenderCompID/TargetCompID mismatch                       | `case_2_i_comp_id_mismatch`           |
+//! | 2.j  | BodyLength incorrect                                     | `case_2_j_body_length_incorrect`      |
+//! | 2.k  | SendingTime not accurate                                 | `case_2_k_sending_time_inaccurate`    |
+//! | 2.l  | BeginString incorrect                                    | `case_2_l_begin_string_incorrect`     |
+//! | 2.m  | MsgType invalid                                          | `case_2_m_msg_type_invalid`           |
+//! | 2.n  | MsgType valid, but not supported                         | `case_2_n_msg_type_unsupported`       |
+//! | 2.o  | BeginString, BodyLength, MsgType not first               | `case_2_o_header_not_first`           |
+//! | 3.a  | CheckSum incorrect                                       | `case_3_a_checksum_incorrect`         |
+//! | 4.b  | TestRequest answered with Heartbeat                      | `case_4_b_test_request`               |
+//! | 5    | Heartbeat received                                       | `case_5_heartbeat_received`           |
+//! | 7.a  | Reject received                                          | `case_7_a_reject_received`            |
+//! | 8.a  | ResendRequest received                                   | `case_8_a_resend_request`             |
+//! | 9    | SequenceReset-Reset sent                                 | `case_9_reset_sent`                   |
+//! | 10.a | SequenceReset-GapFill                                    | `case_10_a_gap_fill`                  |
+//! | 10.b | SequenceReset-GapFill, MsgSeqNum too high                | `case_10_b_gap_fill_seq_num_too_high` |
+//! | 10.c | SequenceReset-GapFill, MsgSeqNum too low, PossDupFlag    | `case_10_c_gap_fill_poss_dup`         |
+//! | 10.d | SequenceReset-GapFill, MsgSeqNum too low                 | `case_10_d_gap_fill_seq_num_too_low`  |
+//! | 10.e | SequenceReset-GapFill, NewSeqNo not greater than MsgSeqNum | `case_10_e_gap_fill_new_seq_no_lower` |
+//! | 11.a | SequenceReset-Reset, NewSeqNo higher                     | `case_11_a_reset_higher`              |
+//! | 11.b | SequenceReset-Reset, NewSeqNo equal                      | `case_11_b_reset_equal`               |
+//! | 11.c | SequenceReset-Reset, NewSeqNo lower                      | `case_11_c_reset_lower`               |
+//! | 13.a | Logout initiated                                         | `case_13_a_logout_initiated`          |
+//! | 13.b | Logout received                                          | `case_13_b_logout_received`           |
+//! | 14.a | Undefined tag                                            | `case_14_a_undefined_tag`             |
+//! | 14.b | Required tag missing                                     | `case_14_b_required_tag_missing`      |
+//! | 14.c | Tag not defined for message type                         | `case_14_c_tag_not_defined_for_msg`   |
+//! | 14.d | Tag without value                                        | `case_14_d_tag_without_value`         |
+//! | 14.e | Value out of range                                       | `case_14_e_value_incorrect`           |
+//! | 14.f | Incorrect data format                                    | `case_14_f_incorrect_data_format`     |
+//! | 14.g | Header field out of order                                | `case_14_g_header_out_of_order`       |
+//! | 14.h | Tag appears more than once                               | `case_14_h_tag_more_than_once`        |
+//! | 14.i | NumInGroup count incorrect                               | `case_14_i_num_in_group_incorrect`    |
+//!
+//! After sending Logout<5> for 2.f, 2.i and 2.k the session waits for
+//! Logout<5> response before disconnecting, as in case 13.a.
+//!
+//! Time driven cases (4.a, 6.a, 12) are not covered here, they take at
+//! least heartbeat interval of whole seconds to play.
+
+mod common;
+
+use common::scenario::{
+    accept, acceptor, block_on, drain, initiate, run, Step::*, LOGON, LOGON_RESPONSE,
+};
+use easyfix_messages::messages::{Message, SequenceReset};
+use easyfix_session::{
+    application::{AsEvent, FixEvent},
+    initiator::Initiator,
+    messages_storage::InMemoryStorage,
+};
+use futures::StreamExt;
+
+/// Logon<A> sent by initiator, HeartBtInt<108> from
+/// `common::session_settings()`.
+const INITIATOR_LOGON: &str = "35=A|34=1|98=0|108=10|1137=9";
+
+fn initiator() -> Initiator<InMemoryStorage> {
+    Initiator::new(
+        common::settings(),
+        common::session_settings(),
+        InMemoryStorage::new(),
+    )
+}
+
+#[test]
+fn case_1a_valid_logon() {
+    block_on(async {
+        let initiator = initiator();
+        let mut connection = initiate(&initiator);
+        let _drain = drain(initiator);
+        connection
+            .play(&[
+                Out(INITIATOR_LOGON),
+                In(LOGON_RESPONSE),
+                In("35=1|34=2|112=T"),
+                Out("35=0|34=2|112=T"),
+            ])
+            .await;
+        connection.close().await;
+    });
+}
+
+#[test]
+fn case_1a_logon_response_seq_num_too_high() {
+    block_on(async {
+        let initiator = initiator();
+        let mut connection = initiate(&initiator);
+        let _drain = drain(initiator);
+        connection
+            .play(&[
+                Out(INITIATOR_LOGON),
+                In("35=A|34=5|98=0|108=30|1137=9"),
+                Out("35=2|34=2|7=1|16=4"),
+            ])
+            .await;
+        connection.close().await;
+    });
+}
+
+#[test]
+fn case_1b_a_valid_logon() {
+    run(&[In(LOGON), Out(LOGON_RESPONSE)]);
+}
+
+#[test]
+fn case_1b_b_logon_seq_num_too_high() {
+    run(&[
+        In("35=A|34=5|98=0|108=30|1137=9"),
+        Out(LOGON_RESPONSE),
+        Out("35=2|34=2|7=1|16=4"),
+    ]);
+}
+
+#[test]
+fn case_1b_c_logon_seq_num_too_low() {
+    block_on(async {
+        let acceptor = acceptor(common::session_settings());
+        let mut first = accept(&acceptor);
+        let mut second = accept(&acceptor);
+        let _drain = drain(acceptor);
+        first
+            .play(&[In(LOGON), Out(LOGON_RESPONSE), In("35=0|34=2")])
+            .await;
+        first.close().await;
+        second
+            .play(&[In(LOGON), Out("35=5|34=2|1409=9"), Disconnected])
+            .await;
+        second.close().await;
+    });
+}
+
+#[test]
+fn case_1b_d_first_message_not_logon() {
+    run(&[In("35=0|34=1"), Disconnected]);
+}
+
+#[test]
+fn case_2_a_seq_num_expected() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=0|34=2"),
+        In("35=0|34=3"),
+    ]);
+}
+
+#[test]
+fn case_2_b_seq_num_too_high() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=0|34=5"),
+        Out("35=2|34=2|7=2|16=4"),
+    ]);
+}
+
+#[test]
+fn case_2_c_seq_num_too_low() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=0|34=2"),
+        In("35=0|34=2"),
+        Out("35=5|34=2|1409=9"),
+        Disconnected,
+    ]);
+}
+
+#[test]
+fn case_2_d_garbled() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        // SOH missing between fields
+        InPatched("35=0|34=2|112=X", "|112=X", "112=X"),
+        In("35=0|34=2"),
+    ]);
+}
+
+#[test]
+fn case_2_e_poss_dup_ignored() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=0|34=2"),
+        In("35=0|34=2|43=Y|122=20240315-09:30:00.000"),
+        In("35=0|34=3"),
+    ]);
+}
+
+#[test]
+fn case_2_f_orig_sending_time_too_late() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=0|34=2"),
+        In("35=0|34=2|43=Y|122=29991231-23:59:59.000"),
+        Out("35=3|34=2|45=2|371=122|372=0|373=10"),
+        Out("35=5|34=3"),
+        In("35=5|34=3"),
+        Disconnected,
+    ]);
+}
+
+#[test]
+fn case_2_g_orig_sending_time_missing() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=0|34=2"),
+        In("35=0|34=2|43=Y"),
+        Out("35=3|34=2|45=2|371=122|372=0|373=1"),
+        In("35=0|34=3"),
+    ]);
+}
+
+#[test]
+fn case_2_i_comp_id_mismatch() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=0|34=2|49=INTRUDER"),
+        Out("35=3|34=2|45=2|371=49|372=0|373=9"),
+        Out("35=5|34=3"),
+        In("35=5|34=3"),
+        Disconnected,
+    ]);
+}
+
+#[test]
+fn case_2_j_body_length_incorrect() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        InPatched("35=0|34=2", "|9=", "|9=1"),
+        In("35=0|34=2"),
+    ]);
+}
+
+#[test]
+fn case_2_k_sending_time_inaccurate() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=0|34=2|52=20000101-00:00:00.000"),
+        Out("35=3|34=2|45=2|371=52|372=0|373=10"),
+        Out("35=5|34=3"),
+        In("35=5|34=3"),
+        Disconnected,
+    ]);
+}
+
+#[test]
+fn case_2_l_begin_string_incorrect() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("8=FIX.4.4|35=0|34=2"),
+        Out("35=5|34=2"),
+        Disconnected,
+    ]);
+}
+
+#[test]
+fn case_2_m_msg_type_invalid() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=ZZ|34=2"),
+        Out("35=3|34=2|45=2|371=35|372=ZZ|373=11"),
+        In("35=0|34=3"),
+    ]);
+}
+
+#[test]
+fn case_2_n_msg_type_unsupported() {
+    block_on(async {
+        let acceptor = acceptor(common::session_settings());
+        let mut connection = accept(&acceptor);
+        let events = tokio::task::spawn_local(async move {
+            let mut acceptor = Box::pin(acceptor);
+            while let Some(mut entry) = acceptor.next().await {
+                if let FixEvent::AppMsgIn(msg, responder) = entry.as_event() {
+                    responder.unsupported(&msg);
+                }
+            }
+        });
+        connection
+            .play(&[
+                In(LOGON),
+                Out(LOGON_RESPONSE),
+                In("35=B|34=2|148=Headline|33=1|58=Text"),
+                Out("35=j|34=2|45=2|372=B|380=3"),
+            ])
+            .await;
+        connection.close().await;
+        events.abort();
+    });
+}
+
+#[test]
+fn case_2_o_header_not_first() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        InPatched("35=0|34=2", "|35=0|", "|34=2|35=0|"),
+        In("35=0|34=2"),
+    ]);
+}
+
+#[test]
+fn case_3_a_checksum_incorrect() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        InPatched("35=0|34=2|112=X", "112=X", "112=Y"),
+        In("35=0|34=2"),
+    ]);
+}
+
+#[test]
+fn case_4_b_test_request() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=1|34=2|112=TEST"),
+        Out("35=0|34=2|112=TEST"),
+    ]);
+}
+
+#[test]
+fn case_5_heartbeat_received() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=0|34=2"),
+        In("35=1|34=3|112=T"),
+        Out("35=0|34=2|112=T"),
+    ]);
+}
+
+#[test]
+fn case_7_a_reject_received() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=3|34=2|45=1|373=0"),
+        In("35=0|34=3"),
+    ]);
+}
+
+#[test]
+fn case_8_a_resend_request() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=1|34=2|112=TEST"),
+        Out("35=0|34=2|112=TEST"),
+        In("35=2|34=3|7=1|16=0"),
+        Out("35=4|34=1|43=Y|123=Y|36=3"),
+    ]);
+}
+
+#[test]
+fn case_9_reset_sent() {
+    block_on(async {
+        let acceptor = acceptor(common::session_settings());
+        let mut connection = accept(&acceptor);
+        let events = tokio::task::spawn_local(async move {
+            let mut acceptor = Box::pin(acceptor);
+            while let Some(mut entry) = acceptor.next().await {
+                if let FixEvent::Logon(_, sender) = entry.as_event() {
+                    let reset = Message::SequenceReset(SequenceReset {
+                        gap_fill_flag: None,
+                        new_seq_no: 10,
+                    });
+                    sender.send(Box::new(reset)).unwrap();
+                }
+            }
+        });
+        connection
+            .play(&[
+                In(LOGON),
+                Out(LOGON_RESPONSE),
+                Out("35=4|34=2|36=10"),
+                In("35=1|34=2|112=T"),
+                Out("35=0|34=10|112=T"),
+            ])
+            .await;
+        connection.close().await;
+        events.abort();
+    });
+}
+
+#[test]
+fn case_10_a_gap_fill() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=4|34=2|123=Y|36=5"),
+        In("35=0|34=5"),
+    ]);
+}
+
+#[test]
+fn case_10_b_gap_fill_seq_num_too_high() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=4|34=3|123=Y|36=5"),
+        Out("35=2|34=2|7=2|16=2"),
+    ]);
+}
+
+#[test]
+fn case_10_c_gap_fill_poss_dup() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=0|34=2"),
+        In("35=4|34=1|43=Y|122=20240315-09:30:00.000|123=Y|36=2"),
+        In("35=0|34=3"),
+    ]);
+}
+
+#[test]
+fn case_10_d_gap_fill_seq_num_too_low() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=0|34=2"),
+        In("35=4|34=1|123=Y|36=2"),
+        Out("35=5|34=2|1409=9"),
+        Disconnected,
+    ]);
+}
+
+#[test]
+fn case_10_e_gap_fill_new_seq_no_lower() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=0|34=2"),
+        In("35=4|34=3|123=Y|36=3"),
+        Out("35=3|34=2|45=3|371=36|372=4|373=5"),
+    ]);
+}
+
+#[test]
+fn case_11_a_reset_higher() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=4|34=2|36=10"),
+        In("35=0|34=10"),
+    ]);
+}
+
+#[test]
+fn case_11_b_reset_equal() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=4|34=2|36=2"),
+        In("35=0|34=2"),
+    ]);
+}
+
+#[test]
+fn case_11_c_reset_lower() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=0|34=2"),
+        In("35=0|34=3"),
+        In("35=4|34=4|36=2"),
+        Out("35=3|34=2|45=4|371=36|372=4|373=5"),
+    ]);
+}
+
+#[test]
+fn case_13_a_logout_initiated() {
+    block_on(async {
+        let initiator = initiator();
+        let mut connection = initiate(&initiator);
+        let events = tokio::task::spawn_local(async move {
+            let mut initiator = Box::pin(initiator);
+            while let Some(mut entry) = initiator.next().await {
+                if let FixEvent::Logon(..) = entry.as_event() {
+                    initiator.logout(None, None);
+                }
+            }
+        });
+        connection
+            .play(&[
+                Out(INITIATOR_LOGON),
+                In(LOGON_RESPONSE),
+                Out("35=5|34=2"),
+                In("35=5|34=2"),
+                Disconnected,
+            ])
+            .await;
+        connection.close().await;
+        events.abort();
+    });
+}
+
+#[test]
+fn case_13_b_logout_received() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=5|34=2"),
+        Out("35=5|34=2|1409=4"),
+        Disconnected,
+    ]);
+}
+
+#[test]
+fn case_14_a_undefined_tag() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=1|34=2|112=TEST|9999=X"),
+        Out("35=3|34=2|45=2|371=9999|372=1|373=0"),
+        In("35=0|34=3"),
+    ]);
+}
+
+#[test]
+fn case_14_b_required_tag_missing() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=1|34=2"),
+        Out("35=3|34=2|45=2|371=112|372=1|373=1"),
+        In("35=0|34=3"),
+    ]);
+}
+
+#[test]
+fn case_14_c_tag_not_defined_for_msg() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=1|34=2|112=TEST|55=EUR/USD"),
+        Out("35=3|34=2|45=2|371=55|372=1|373=2"),
+        In("35=0|34=3"),
+    ]);
+}
+
+#[test]
+fn case_14_d_tag_without_value() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=1|34=2|112="),
+        Out("35=3|34=2|45=2|371=112|372=1|373=4"),
+        In("35=0|34=3"),
+    ]);
+}
+
+#[test]
+fn case_14_e_value_incorrect() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=0|34=2|1128=99"),
+        Out("35=3|34=2|45=2|371=1128|372=0|373=5"),
+        In("35=0|34=3"),
+    ]);
+}
+
+#[test]
+fn case_14_f_incorrect_data_format() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=2|34=2|7=ONE|16=0"),
+        Out("35=3|34=2|45=2|371=7|372=2|373=6"),
+        In("35=0|34=3"),
+    ]);
+}
+
+#[test]
+fn case_14_g_header_out_of_order() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=1|34=2|112=TEST|50=DESK"),
+        Out("35=3|34=2|45=2|371=50|372=1|373=14"),
+        In("35=0|34=3"),
+    ]);
+}
+
+#[test]
+fn case_14_h_tag_more_than_once() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=1|34=2|112=TEST|112=TEST"),
+        Out("35=3|34=2|45=2|371=112|372=1|373=13"),
+        In("35=0|34=3"),
+    ]);
+}
+
+#[test]
+fn case_14_i_num_in_group_incorrect() {
+    run(&[
+        In(LOGON),
+        Out(LOGON_RESPONSE),
+        In("35=D|34=2|11=ORD1|453=2|448=PARTY|54=1|60=20240315-09:30:00.000|40=1"),
+        Out("35=3|34=2|45=2|371=453|372=D|373=16"),
+        In("35=0|34=3"),
+    ]);
+}
@@ -0,0 +1,140 @@
+mod common;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on, IDLE, PEER_ADDR};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, MsgType},
+    messages::{FixtMessage, Heartbeat, Message},
+};
+use easyfix_session::{
+    acceptor::metrics::{AcceptorMetrics, ConnectionEvent, LogonFailure},
+    settings::PreLogonAction,
+    DisconnectReason,
+};
+use futures::StreamExt;
+use tokio::{sync::oneshot, time::timeout};
+
+/// Send `msg` as the first message of new connection, then close
+/// the connection, returns connection events and final metrics.
+fn run(msg: FixtMessage) -> (Vec<ConnectionEvent>, AcceptorMetrics) {
+    block_on(async move {
+        let acceptor = acceptor(common::session_settings());
+        let mut connection_events = acceptor.connection_events();
+        let mut connection = accept(&acceptor);
+        let (done_tx, mut done_rx) = oneshot::channel::<()>();
+        let events = tokio::task::spawn_local(async move {
+            let mut acceptor = Box::pin(acceptor);
+            loop {
+                tokio::select! {
+                    _ = acceptor.next() => {}
+                    _ = &mut done_rx => break,
+                }
+            }
+            acceptor
+        });
+
+        connection.send(&msg).await;
+        // Wait for Logon<A> response or for disconnection
+        connection.read().await;
+        connection.close().await.expect("session not finished");
+
+        let mut received = Vec::new();
+        while let Ok(Some(event)) = timeout(IDLE, connection_events.next()).await {
+            received.push(event);
+        }
+        done_tx.send(()).unwrap();
+        let acceptor = events.await.unwrap();
+        (received, acceptor.metrics())
+    })
+}
+
+#[test]
+fn logon_and_disconnect() {
+    let (events, metrics) = run(basic_msg(0));
+
+    assert!(
+        matches!(
+            events.as_slice(),
+            [
+                ConnectionEvent::Accepted { .. },
+                ConnectionEvent::LogonSucceeded { session_id, .. },
+                ConnectionEvent::Disconnected {
+                    peer_addr,
+                    reason: DisconnectReason::Disconnected,
+                    ..
+                },
+            ] if *session_id == common::session_settings().session_id
+                && *peer_addr == PEER_ADDR
+        ),
+        "{events:?}"
+    );
+    assert_eq!(
+        metrics,
+        AcceptorMetrics {
+            accepted: 1,
+            logons: 1,
+            disconnects: [(DisconnectReason::Disconnected, 1)].into(),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn logon_unknown_session() {
+    let mut msg = basic_msg(0);
+    msg.header.sender_comp_id = fix_str!("UNKNOWN").to_owned();
+    let (events, metrics) = run(msg);
+
+    assert!(
+        matches!(
+            events.as_slice(),
+            [
+                ConnectionEvent::Accepted { .. },
+                ConnectionEvent::LogonFailed {
+                    reason: LogonFailure::UnknownSession(_),
+                    ..
+                },
+            ]
+        ),
+        "{events:?}"
+    );
+    assert_eq!(
+        metrics,
+        AcceptorMetrics {
+            accepted: 1,
+            logon_failures: 1,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn first_message_not_logon() {
+    let mut msg = basic_msg(0);
+    msg.header.msg_type = MsgType::Heartbeat;
+    *msg.body = Message::Heartbeat(Heartbeat { test_req_id: None });
+    let (events, metrics) = run(msg);
+
+    assert!(
+        matches!(
+            events.as_slice(),
+            [
+                ConnectionEvent::Accepted { .. },
+                ConnectionEvent::PreLogonMessage {
+                    msg_type: Some(MsgType::Heartbeat),
+                    action: PreLogonAction::Disconnect,
+                    ..
+                },
+                ConnectionEvent::LogonFailed {
+                    reason: LogonFailure::Rejected(..),
+                    ..
+                },
+                ConnectionEvent::Disconnected { .. },
+            ]
+        ),
+        "{events:?}"
+    );
+    assert_eq!(metrics.pre_logon_messages, 1);
+    assert_eq!(metrics.logon_failures, 1);
+    assert_eq!(metrics.open_connections, 0);
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on, collect, drain};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, TagNum},
+    messages::{FieldTag, FixtMessage, Message, DICTIONARY_FINGERPRINT},
+};
+use easyfix_session::{
+    application::{LogonMismatch, SessionEvent, SessionEventKind},
+    settings::DictionaryFingerprintCheck,
+};
+
+const FOREIGN: &FixStr = fix_str!("0123456789abcdef");
+
+/// Log on to acceptor with `fingerprint` in RawData<96>. Returns messages
+/// sent by acceptor and reported mismatches.
+fn run(
+    check: DictionaryFingerprintCheck,
+    fingerprint: Option<&FixStr>,
+) -> (Vec<FixtMessage>, Vec<LogonMismatch>) {
+    let mut logon = basic_msg(0);
+    let Message::Logon(ref mut body) = *logon.body else {
+        unreachable!()
+    };
+    body.raw_data = fingerprint.map(|fingerprint| fingerprint.as_bytes().into());
+
+    let mut session_settings = common::session_settings();
+    session_settings.dictionary_fingerprint = Some(check);
+
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut subscriber = acceptor
+            .events()
+            .subscribe()
+            .with_kinds(&[SessionEventKind::LogonMismatch]);
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.send(&logon).await;
+        let sent = connection.read_messages().await;
+
+        let mismatches = collect(&mut subscriber)
+            .await
+            .into_iter()
+            .map(|event| match event {
+                SessionEvent::LogonMismatch(_, mismatch) => mismatch,
+                event => panic!("unexpected event {event:?}"),
+            })
+            .collect();
+        connection.close().await;
+
+        (sent, mismatches)
+    })
+}
+
+fn logon_raw_data(sent: &[FixtMessage]) -> Option<&[u8]> {
+    sent.iter().find_map(|msg| match *msg.body {
+        Message::Logon(ref logon) => Some(
+            logon
+                .raw_data
+                .as_ref()
+                .map_or(&[][..], |raw_data| raw_data.as_ref()),
+        ),
+        _ => None,
+    })
+}
+
+fn mismatch(expected: &FixStr, received: &FixStr) -> LogonMismatch {
+    LogonMismatch {
+        tag: FieldTag::RawData as TagNum,
+        expected: expected.to_owned(),
+        received: received.to_owned(),
+    }
+}
+
+#[test]
+fn fingerprint_generated() {
+    assert_eq!(DICTIONARY_FINGERPRINT.len(), 16);
+    assert!(DICTIONARY_FINGERPRINT
+        .as_bytes()
+        .iter()
+        .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(b)));
+}
+
+#[test]
+fn matching_fingerprint_accepted() {
+    let (sent, mismatches) = run(
+        DictionaryFingerprintCheck {
+            expected: None,
+            refuse_mismatch: true,
+        },
+        Some(DICTIONARY_FINGERPRINT),
+    );
+    assert_eq!(
+        logon_raw_data(&sent),
+        Some(DICTIONARY_FINGERPRINT.as_bytes())
+    );
+    assert_eq!(mismatches, []);
+}
+
+#[test]
+fn missing_fingerprint_reported() {
+    let (sent, mismatches) = run(DictionaryFingerprintCheck::default(), None);
+    assert_eq!(
+        logon_raw_data(&sent),
+        Some(DICTIONARY_FINGERPRINT.as_bytes())
+    );
+    assert_eq!(mismatches, [mismatch(DICTIONARY_FINGERPRINT, fix_str!(""))]);
+}
+
+#[test]
+fn different_fingerprint_refused() {
+    let (sent, mismatches) = run(
+        DictionaryFingerprintCheck {
+            expected: None,
+            refuse_mismatch: true,
+        },
+        Some(FOREIGN),
+    );
+    assert_eq!(logon_raw_data(&sent), None);
+    let Some(Message::Logout(logout)) = sent.last().map(|msg| &*msg.body) else {
+        panic!("Logout<5> not sent: {sent:?}");
+    };
+    assert!(logout
+        .text
+        .as_ref()
+        .is_some_and(|text| text.as_utf8().contains("fingerprint mismatch")));
+    assert_eq!(mismatches, [mismatch(DICTIONARY_FINGERPRINT, FOREIGN)]);
+}
+
+#[test]
+fn expected_fingerprint_overridden() {
+    let (sent, mismatches) = run(
+        DictionaryFingerprintCheck {
+            expected: Some(FOREIGN.to_owned()),
+            refuse_mismatch: true,
+        },
+        Some(FOREIGN),
+    );
+    assert_eq!(
+        logon_raw_data(&sent),
+        Some(DICTIONARY_FINGERPRINT.as_bytes())
+    );
+    assert_eq!(mismatches, []);
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on, IDLE};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, FixString, RoutingType, Urgency},
+    groups::LinesOfTextGrp,
+    messages::{Message, News},
+};
+use easyfix_session::{
+    application::{AsEvent, EventSubscriber, FixEvent, SessionEvent, SessionEventKind},
+    settings::EnrichmentRule,
+};
+use futures::StreamExt;
+use tokio::time::timeout;
+
+fn news(urgency: Option<Urgency>) -> Box<Message> {
+    Box::new(Message::News(News {
+        urgency,
+        headline: fix_str!("headline").to_owned(),
+        lines_of_text_grp: vec![LinesOfTextGrp {
+            text: fix_str!("text").to_owned(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }))
+}
+
+async fn collect(subscriber: &mut EventSubscriber) -> Vec<News> {
+    let mut news = Vec::new();
+    while let Ok(Some(event)) = timeout(IDLE, subscriber.next()).await {
+        if let SessionEvent::AppMsgOut(_, msg) = event {
+            if let Message::News(msg) = &*msg.body {
+                news.push(msg.clone());
+            }
+        }
+    }
+    news
+}
+
+/// Log on and send News<B> messages with given Urgency<61>, returns
+/// messages as they were sent.
+fn run(rules: Vec<EnrichmentRule>, urgencies: Vec<Option<Urgency>>) -> Vec<News> {
+    let mut session_settings = common::session_settings();
+    session_settings.enrichment = rules;
+
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut app_out = acceptor
+            .events()
+            .subscribe()
+            .with_kinds(&[SessionEventKind::AppMsgOut]);
+
+        let mut connection = accept(&acceptor);
+        let events = tokio::task::spawn_local(async move {
+            let mut acceptor = Box::pin(acceptor);
+            while let Some(mut entry) = acceptor.next().await {
+                if let FixEvent::Logon(_, sender) = entry.as_event() {
+                    for urgency in &urgencies {
+                        sender.send(news(*urgency)).unwrap();
+                    }
+                }
+            }
+        });
+
+        connection.send(&basic_msg(0)).await;
+
+        let news = collect(&mut app_out).await;
+        connection.close().await;
+        events.abort();
+        news
+    })
+}
+
+#[test]
+fn missing_fields_and_groups_added() {
+    let news = run(
+        vec![EnrichmentRule {
+            msg_type: fix_str!("B").to_owned(),
+            fields: "61=2|149=http://example.com".to_owned(),
+            groups: vec!["215=2|216=1|217=FIRM1|216=2|217=FIRM2".to_owned()],
+        }],
+        vec![None, Some(Urgency::Flash)],
+    );
+    assert_eq!(news.len(), 2);
+
+    assert_eq!(news[0].urgency, Some(Urgency::Background));
+    assert_eq!(
+        news[0].url_link,
+        Some(FixString::from_ascii_lossy(b"http://example.com".to_vec()))
+    );
+    let routing_grp = news[0].routing_grp.as_ref().expect("RoutingGrp not added");
+    assert_eq!(routing_grp.len(), 2);
+    assert_eq!(routing_grp[0].routing_type, Some(RoutingType::TargetFirm));
+    assert_eq!(
+        routing_grp[1].routing_id.as_deref(),
+        Some(fix_str!("FIRM2"))
+    );
+    assert_eq!(news[0].headline, fix_str!("headline"));
+
+    // Present field not overwritten
+    assert_eq!(news[1].urgency, Some(Urgency::Flash));
+    assert!(news[1].url_link.is_some());
+}
+
+#[test]
+fn invalid_rule_ignored() {
+    let news = run(
+        vec![EnrichmentRule {
+            msg_type: fix_str!("B").to_owned(),
+            fields: "61".to_owned(),
+            groups: Vec::new(),
+        }],
+        vec![None],
+    );
+    assert_eq!(news.len(), 1);
+    assert_eq!(news[0].urgency, None);
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use std::{cell::RefCell, rc::Rc};
+
+use common::scenario::{accept, acceptor, basic_msg, block_on, IDLE};
+use easyfix_session::application::{
+    AsEvent, EventTimestamp, FixEvent, SessionEvent, SessionEventKind,
+};
+use futures::StreamExt;
+use tokio::time::timeout;
+
+#[test]
+fn events_timestamped_at_capture() {
+    block_on(async move {
+        let acceptor = acceptor(common::session_settings());
+        let mut subscriber = acceptor
+            .events()
+            .subscribe()
+            .with_kinds(&[SessionEventKind::AdmMsgIn, SessionEventKind::Logon])
+            .with_timestamps();
+
+        let mut connection = accept(&acceptor);
+        let adm_msg_in_timestamps = Rc::new(RefCell::new(Vec::new()));
+        let events = tokio::task::spawn_local({
+            let adm_msg_in_timestamps = adm_msg_in_timestamps.clone();
+            async move {
+                let mut acceptor = Box::pin(acceptor);
+                while let Some(mut event) = acceptor.next().await {
+                    let timestamp = event.timestamp();
+                    if let FixEvent::AdmMsgIn(_, _) = event.as_event() {
+                        adm_msg_in_timestamps.borrow_mut().push(timestamp);
+                    }
+                }
+            }
+        });
+
+        let before = EventTimestamp::now();
+        connection.send(&basic_msg(0)).await;
+
+        let mut received = Vec::new();
+        while let Ok(Some(event)) = timeout(IDLE, subscriber.next()).await {
+            received.push(event);
+        }
+        connection.close().await;
+        events.abort();
+
+        let [(adm_msg_in, SessionEvent::AdmMsgIn(..)), (logon, SessionEvent::Logon(..))] =
+            received[..]
+        else {
+            panic!("unexpected events: {received:?}");
+        };
+        // Logon<A> is captured when read, before session is logged on
+        assert!(before.monotonic <= adm_msg_in.monotonic);
+        assert!(adm_msg_in.monotonic <= logon.monotonic);
+        assert!(before.wall_clock.timestamp() <= adm_msg_in.wall_clock.timestamp());
+        // Same capture time is reported to subscribers and to event stream
+        assert_eq!(*adm_msg_in_timestamps.borrow(), [adm_msg_in]);
+    });
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use std::{cell::Cell, rc::Rc};
+
+use common::scenario::{accept, acceptor, basic_msg, block_on, collect, drain, inbound};
+use easyfix_messages::{
+    fields::{MsgType, SessionRejectReason},
+    messages::{Heartbeat, Message},
+    test_util::soh_from_pipes,
+};
+use easyfix_session::{
+    application::{AsEvent, FixEvent, RejectDirection, SessionEvent, SessionEventKind},
+    SendErrorReason,
+};
+use futures::StreamExt;
+
+#[test]
+fn events_delivered_to_all_subscribers() {
+    let session_settings = common::session_settings();
+    block_on(async {
+        let acceptor = acceptor(session_settings.clone());
+        let mut all = acceptor.events().subscribe();
+        let mut adm_in = acceptor
+            .events()
+            .subscribe()
+            .with_kinds(&[SessionEventKind::AdmMsgIn]);
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.send(&basic_msg(0)).await;
+
+        let all = collect(&mut all).await;
+        let adm_in = collect(&mut adm_in).await;
+        connection.close().await;
+
+        // Events are emitted by input and output tasks, so only the set
+        // of events is deterministic
+        let kinds: Vec<_> = all.iter().map(SessionEvent::kind).collect();
+        assert_eq!(kinds.len(), 4);
+        for kind in [
+            SessionEventKind::Created,
+            SessionEventKind::AdmMsgIn,
+            SessionEventKind::AdmMsgOut,
+            SessionEventKind::Logon,
+        ] {
+            assert!(kinds.contains(&kind), "{kind:?} missing in {kinds:?}");
+        }
+        assert!(all
+            .iter()
+            .all(|event| event.session_id() == &session_settings.session_id));
+
+        assert_eq!(adm_in.len(), 1);
+        let SessionEvent::AdmMsgIn(_, msg) = &adm_in[0] else {
+            panic!("unexpected event: {:?}", adm_in[0]);
+        };
+        assert_eq!(msg.msg_type(), MsgType::Logon);
+    });
+}
+
+#[test]
+fn reject_reported_with_context() {
+    block_on(async move {
+        let acceptor = acceptor(common::session_settings());
+        let mut rejects = acceptor
+            .events()
+            .subscribe()
+            .with_kinds(&[SessionEventKind::Reject]);
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.send(&basic_msg(0)).await;
+        // SendingTime<52> far beyond allowed latency, MsgSeqNum<34> with
+        // leading zeros to tell received bytes from re-encoded message
+        let raw_test_request =
+            soh_from_pipes(inbound("35=1|34=002|52=20000101-00:00:00.000|112=TEST"));
+        connection.send_raw(&raw_test_request).await;
+
+        let rejects = collect(&mut rejects).await;
+        connection.close().await;
+
+        assert_eq!(rejects.len(), 1);
+        let SessionEvent::Reject(_, report) = &rejects[0] else {
+            panic!("unexpected event: {:?}", rejects[0]);
+        };
+        assert_eq!(report.direction, RejectDirection::Sent);
+        assert_eq!(report.ref_seq_num, 2);
+        assert_eq!(report.ref_tag_id, Some(52));
+        assert_eq!(
+            report.reason,
+            Some(SessionRejectReason::SendingtimeAccuracyProblem)
+        );
+        assert_eq!(
+            report.raw_message.as_deref(),
+            Some(raw_test_request.as_slice())
+        );
+        assert_eq!(report.state.next_target_msg_seq_num, 2);
+        assert!(report.state.logon_received);
+    });
+}
+
+#[test]
+fn app_sender_refuses_admin_messages() {
+    block_on(async {
+        let acceptor = acceptor(common::session_settings());
+        let mut adm_out = acceptor
+            .events()
+            .subscribe()
+            .with_kinds(&[SessionEventKind::AdmMsgOut]);
+        let mut connection = accept(&acceptor);
+        let refused = Rc::new(Cell::new(None));
+        let events = tokio::task::spawn_local({
+            let refused = refused.clone();
+            async move {
+                let mut acceptor = Box::pin(acceptor);
+                while let Some(mut entry) = acceptor.next().await {
+                    if let FixEvent::Logon(_, sender) = entry.as_event() {
+                        let heartbeat = Message::Heartbeat(Heartbeat { test_req_id: None });
+                        let result = sender.app_sender().send(Box::new(heartbeat));
+                        refused.set(Some(result.err().map(|err| err.reason)));
+                    }
+                }
+            }
+        });
+
+        connection.send(&basic_msg(0)).await;
+
+        let adm_out = collect(&mut adm_out).await;
+        connection.close().await;
+        events.abort();
+
+        assert_eq!(refused.get(), Some(Some(SendErrorReason::AdminMsg)));
+        // Only Logon<A> response was sent
+        assert_eq!(adm_out.len(), 1, "{adm_out:?}");
+    });
+}
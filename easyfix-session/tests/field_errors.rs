@@ -0,0 +1,137 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on, drain, IDLE};
+use easyfix_messages::{
+    fields::{Decimal, FixString, MsgType, SessionRejectReason},
+    messages::{FixtMessage, Heartbeat, Message, NewOrderSingle},
+};
+use easyfix_session::{
+    application::{FieldError, SessionEvent, SessionEventKind},
+    settings::FieldErrorsPolicy,
+};
+use futures::StreamExt;
+use tokio::time::timeout;
+
+/// Result of session run.
+struct Outcome {
+    /// Admin messages sent after Logon<A> response
+    sent: Vec<Arc<FixtMessage>>,
+    /// Field errors of delivered partial messages
+    delivered: Vec<Arc<[FieldError]>>,
+}
+
+/// Serialize `msg` with `from` replaced by `to` (of the same length).
+fn serialize_patched(msg: &FixtMessage, from: &str, to: &str) -> Vec<u8> {
+    assert_eq!(from.len(), to.len());
+    let serialized = String::from_utf8(msg.serialize()).unwrap();
+    assert!(serialized.contains(from));
+    let mut patched = serialized.replace(from, to).into_bytes();
+    let checksum_pos = patched.len() - "10=000\x01".len();
+    let checksum = patched[..checksum_pos]
+        .iter()
+        .fold(0u8, |acc, b| acc.wrapping_add(*b));
+    patched.splice(checksum_pos.., format!("10={checksum:03}\x01").into_bytes());
+    patched
+}
+
+/// Log on, send NewOrderSingle<D> with invalid OrderQty<38> followed by
+/// Heartbeat<0>.
+fn run(policy: FieldErrorsPolicy) -> Outcome {
+    let mut session_settings = common::session_settings();
+    session_settings.field_errors = policy;
+    let message = |msg_seq_num, body: Message| {
+        let mut msg = basic_msg(0);
+        msg.header.msg_type = body.msg_type();
+        msg.header.msg_seq_num = msg_seq_num;
+        *msg.body = body;
+        msg
+    };
+
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut subscriber = acceptor.events().subscribe().with_kinds(&[
+            SessionEventKind::AdmMsgOut,
+            SessionEventKind::PartialAppMsgIn,
+        ]);
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.send(&basic_msg(0)).await;
+        let order = message(
+            2,
+            Message::NewOrderSingle(NewOrderSingle {
+                cl_ord_id: FixString::from_ascii_lossy(b"order".to_vec()),
+                order_qty: Some(Decimal::from(5)),
+                ..Default::default()
+            }),
+        );
+        connection
+            .send_raw(&serialize_patched(&order, "\x0138=5\x01", "\x0138=x\x01"))
+            .await;
+        let heartbeat = message(3, Message::Heartbeat(Heartbeat { test_req_id: None }));
+        connection.send(&heartbeat).await;
+
+        let mut outcome = Outcome {
+            sent: Vec::new(),
+            delivered: Vec::new(),
+        };
+        while let Ok(Some(event)) = timeout(IDLE, subscriber.next()).await {
+            match event {
+                SessionEvent::AdmMsgOut(_, msg) if msg.msg_type() != MsgType::Logon => {
+                    outcome.sent.push(msg)
+                }
+                SessionEvent::PartialAppMsgIn(_, msg, field_errors) => {
+                    assert_eq!(msg.msg_type(), MsgType::NewOrderSingle);
+                    outcome.delivered.push(field_errors);
+                }
+                _ => {}
+            }
+        }
+        connection.close().await;
+        outcome
+    })
+}
+
+fn assert_order_qty_rejected(sent: &[Arc<FixtMessage>]) {
+    // Only Reject<3>, Heartbeat<0> was expected, so no ResendRequest<2>
+    assert_eq!(sent.len(), 1, "{sent:?}");
+    let Message::Reject(ref reject) = *sent[0].body else {
+        panic!("unexpected message: {:?}", sent[0]);
+    };
+    assert_eq!(reject.ref_seq_num, 2);
+    assert_eq!(reject.ref_tag_id, Some(38));
+    assert_eq!(
+        reject.session_reject_reason,
+        Some(SessionRejectReason::IncorrectDataFormatForValue)
+    );
+}
+
+#[test]
+fn reject_by_default() {
+    let outcome = run(FieldErrorsPolicy::Reject);
+    assert!(outcome.delivered.is_empty());
+    assert_order_qty_rejected(&outcome.sent);
+}
+
+#[test]
+fn deliver_without_reject() {
+    let outcome = run(FieldErrorsPolicy::Deliver);
+    assert_eq!(outcome.delivered.len(), 1);
+    assert_eq!(
+        &*outcome.delivered[0],
+        [FieldError {
+            tag: 38,
+            reason: SessionRejectReason::IncorrectDataFormatForValue,
+        }]
+    );
+    assert!(outcome.sent.is_empty(), "{:?}", outcome.sent);
+}
+
+#[test]
+fn deliver_and_reject() {
+    let outcome = run(FieldErrorsPolicy::DeliverAndReject);
+    assert_eq!(outcome.delivered.len(), 1);
+    assert_order_qty_rejected(&outcome.sent);
+}
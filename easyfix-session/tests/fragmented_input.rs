@@ -0,0 +1,123 @@
+mod common;
+
+use std::{
+    collections::VecDeque,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use common::scenario::{basic_msg, block_on};
+use easyfix_messages::messages::{FixtMessage, Message};
+use easyfix_session::io::{input_stream, InputEvent};
+use futures::StreamExt;
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// Data with SOH, `10=` and `8=` sequences, which would end or start
+/// message if it was framed by scanning for SOH.
+const RAW_DATA: &[u8] = b"\x0110=000\x018=FIXT.1.1\x019=5\x01\x01";
+const XML_DATA: &[u8] = b"<a>\x01</a>\x0110=";
+
+/// Source returning one chunk per read.
+struct Fragmented(VecDeque<Vec<u8>>);
+
+impl AsyncRead for Fragmented {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        if let Some(chunk) = self.0.pop_front() {
+            buf.put_slice(&chunk);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+fn logon_with_data() -> FixtMessage {
+    let mut msg = basic_msg(0);
+    msg.header.xml_data = Some(XML_DATA.into());
+    let Message::Logon(ref mut logon) = *msg.body else {
+        panic!("unexpected message: {msg:?}");
+    };
+    logon.raw_data = Some(RAW_DATA.into());
+    msg
+}
+
+/// Feed `chunks` to input stream, returns all received messages.
+fn read(chunks: Vec<Vec<u8>>) -> Vec<FixtMessage> {
+    block_on(async move {
+        let mut stream = Box::pin(input_stream(Fragmented(chunks.into()), None));
+        let mut messages = Vec::new();
+        while let Some(event) = stream.next().await {
+            match event {
+                InputEvent::Message { msg, .. } => messages.push(*msg),
+                event => panic!("unexpected input event: {event:?}"),
+            }
+        }
+        messages
+    })
+}
+
+fn assert_data(messages: &[FixtMessage], count: usize) {
+    assert_eq!(messages.len(), count);
+    for msg in messages {
+        assert_eq!(
+            msg.header.xml_data.as_deref().map(|data| data.to_vec()),
+            Some(XML_DATA.to_vec())
+        );
+        let Message::Logon(ref logon) = *msg.body else {
+            panic!("unexpected message: {msg:?}");
+        };
+        assert_eq!(
+            logon.raw_data.as_deref().map(|data| data.to_vec()),
+            Some(RAW_DATA.to_vec())
+        );
+    }
+}
+
+#[test]
+fn split_at_every_position() {
+    let serialized = logon_with_data().serialize();
+    for i in 1..serialized.len() {
+        let messages = read(vec![serialized[..i].to_vec(), serialized[i..].to_vec()]);
+        assert_data(&messages, 1);
+    }
+}
+
+#[test]
+fn byte_by_byte() {
+    let serialized = logon_with_data().serialize();
+    let messages = read(serialized.iter().map(|b| vec![*b]).collect());
+    assert_data(&messages, 1);
+}
+
+#[test]
+fn split_after_every_soh() {
+    let serialized = logon_with_data().serialize();
+    let chunks = serialized
+        .split_inclusive(|b| *b == b'\x01')
+        .map(<[u8]>::to_vec)
+        .collect();
+    let messages = read(chunks);
+    assert_data(&messages, 1);
+}
+
+#[test]
+fn back_to_back_split_inside_data() {
+    let serialized = logon_with_data().serialize();
+    let mut stream = serialized.clone();
+    stream.extend_from_slice(&serialized);
+    let data_start = serialized
+        .windows(RAW_DATA.len())
+        .position(|window| window == RAW_DATA)
+        .expect("data not found");
+    // Second read starts inside Data field of the second message
+    let split = serialized.len() + data_start + 3;
+    let messages = read(vec![
+        stream[..split].to_vec(),
+        stream[split..split + 5].to_vec(),
+        stream[split + 5..].to_vec(),
+    ]);
+    assert_data(&messages, 2);
+}
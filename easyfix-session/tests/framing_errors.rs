@@ -0,0 +1,149 @@
+mod common;
+
+use std::time::Duration;
+
+use common::scenario::{accept, acceptor_with, basic_msg, block_on};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    deserializer::FramingMismatch,
+    fields::{FixStr, MsgType},
+    messages::{Message, TestRequest},
+};
+use easyfix_session::{
+    application::{AsEvent, DeserializeError, FixEvent, FramingError},
+    settings::FramingErrorReports,
+};
+use futures::StreamExt;
+use tokio::time::timeout;
+
+/// Log on, send TestRequest<1> with wrong CheckSum<10>, valid one and
+/// one with wrong BodyLength<9>, return framing errors and number of garbled
+/// messages reported with `DeserializeError`.
+fn run(framing_errors: FramingErrorReports) -> (Vec<FramingError>, usize) {
+    let mut settings = common::settings();
+    settings.framing_errors = framing_errors;
+
+    block_on(async move {
+        let acceptor = acceptor_with(settings, common::session_settings());
+        let mut connection = accept(&acceptor);
+        let events = tokio::task::spawn_local(async move {
+            let mut acceptor = Box::pin(acceptor);
+            let mut framing_errors = Vec::new();
+            let mut garbled = 0;
+            while let Ok(Some(mut entry)) =
+                timeout(Duration::from_millis(300), acceptor.next()).await
+            {
+                match entry.as_event() {
+                    FixEvent::FramingError(_, error) => framing_errors.push(error.clone()),
+                    FixEvent::DeserializeError(_, DeserializeError::GarbledMessage(_)) => {
+                        garbled += 1
+                    }
+                    _ => {}
+                }
+            }
+            (framing_errors, garbled)
+        });
+
+        let logon = basic_msg(0);
+        connection.send(&logon).await;
+
+        let mut msg = logon.clone();
+        msg.header.msg_type = MsgType::TestRequest;
+        msg.header.msg_seq_num = 2;
+        *msg.body = Message::TestRequest(TestRequest {
+            test_req_id: fix_str!("FRAMING").to_owned(),
+        });
+        let serialized = msg.serialize();
+
+        let mut wrong_checksum = serialized.clone();
+        let checksum = wrong_checksum.len() - 4..wrong_checksum.len() - 1;
+        let value = if &wrong_checksum[checksum.clone()] == b"000" {
+            b"001"
+        } else {
+            b"000"
+        };
+        wrong_checksum[checksum].copy_from_slice(value);
+        connection.send_raw(&wrong_checksum).await;
+        // Garbled data is dropped up to the next valid message
+        connection.send_raw(&serialized).await;
+
+        // BodyLength<9> one byte too short
+        let mut wrong_length = serialized.clone();
+        let start = wrong_length.iter().position(|b| *b == b'\x01').unwrap() + 3;
+        let end = start
+            + wrong_length[start..]
+                .iter()
+                .position(|b| *b == b'\x01')
+                .unwrap();
+        let value: usize = std::str::from_utf8(&wrong_length[start..end])
+            .unwrap()
+            .parse()
+            .unwrap();
+        wrong_length[start..end]
+            .copy_from_slice(format!("{:0width$}", value - 1, width = end - start).as_bytes());
+        connection.send_raw(&wrong_length).await;
+
+        let result = events.await.unwrap();
+        connection.close().await;
+        result
+    })
+}
+
+#[test]
+fn framing_errors_reported() {
+    let (framing_errors, garbled) = run(FramingErrorReports {
+        checksum: true,
+        body_length: true,
+    });
+    // Messages are dropped as garbled anyway
+    assert_eq!(garbled, 2);
+    assert_eq!(framing_errors.len(), 2, "{framing_errors:?}");
+
+    let FramingMismatch::CheckSum {
+        calculated,
+        received,
+    } = framing_errors[0].mismatch
+    else {
+        panic!("unexpected mismatch: {:?}", framing_errors[0].mismatch);
+    };
+    assert!(received <= 1);
+    assert_ne!(calculated, received);
+    // ASCII column of hexdump
+    let text: String = framing_errors[0]
+        .hexdump
+        .lines()
+        .map(|line| line.split_once('|').unwrap().1.trim_end_matches('|'))
+        .collect();
+    assert!(
+        text.contains(&format!("112=FRAMING.10={received:03}.")),
+        "{}",
+        framing_errors[0].hexdump
+    );
+
+    let FramingMismatch::BodyLength {
+        calculated,
+        received,
+    } = framing_errors[1].mismatch
+    else {
+        panic!("unexpected mismatch: {:?}", framing_errors[1].mismatch);
+    };
+    assert_eq!(calculated, received + 1);
+}
+
+#[test]
+fn framing_errors_toggled_independently() {
+    let (framing_errors, garbled) = run(FramingErrorReports {
+        checksum: false,
+        body_length: true,
+    });
+    assert_eq!(garbled, 2);
+    assert_eq!(framing_errors.len(), 1, "{framing_errors:?}");
+    assert!(matches!(
+        framing_errors[0].mismatch,
+        FramingMismatch::BodyLength { .. }
+    ));
+
+    let (framing_errors, garbled) = run(FramingErrorReports::default());
+    assert_eq!(garbled, 2);
+    assert!(framing_errors.is_empty(), "{framing_errors:?}");
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use std::time::Duration;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, FixString, MsgType, SeqNum},
+    groups::LinesOfTextGrp,
+    messages::{Message, News, ResendRequest},
+};
+use easyfix_session::{
+    application::{AsEvent, FixEvent},
+    settings::{GapFillPolicy, GapFillPredicate},
+};
+use futures::StreamExt;
+use tokio::time::{sleep, timeout};
+
+fn news(headline: &str) -> Box<Message> {
+    Box::new(Message::News(News {
+        headline: FixString::from_ascii_lossy(headline.as_bytes().to_vec()),
+        lines_of_text_grp: vec![LinesOfTextGrp {
+            text: fix_str!("gap fill").to_owned(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }))
+}
+
+/// Log on, send News<B> "fresh", "stale" and "fresh" (MsgSeqNum<34> 2..=4),
+/// then request their resend. Returns MsgSeqNum<34> of messages sent with
+/// PossDupFlag<43>=Y and NewSeqNo<36> of gap fills.
+fn run(gap_fill: GapFillPolicy) -> Vec<(SeqNum, Option<SeqNum>)> {
+    let mut session_settings = common::session_settings();
+    session_settings.persist = true;
+    session_settings.gap_fill = gap_fill;
+
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut connection = accept(&acceptor);
+        let events = tokio::task::spawn_local(async move {
+            let mut acceptor = Box::pin(acceptor);
+            while let Ok(Some(mut entry)) =
+                timeout(Duration::from_millis(500), acceptor.next()).await
+            {
+                if let FixEvent::Logon(_, sender) = entry.as_event() {
+                    for headline in ["fresh", "stale", "fresh"] {
+                        sender.send(news(headline)).unwrap();
+                    }
+                }
+            }
+        });
+
+        let logon = basic_msg(0);
+        connection.send(&logon).await;
+        sleep(Duration::from_millis(100)).await;
+
+        let mut msg = logon.clone();
+        *msg.body = Message::ResendRequest(ResendRequest {
+            begin_seq_no: 2,
+            end_seq_no: 0,
+        });
+        msg.header.msg_type = msg.body.msg_type();
+        msg.header.msg_seq_num = 2;
+        connection.send(&msg).await;
+
+        let sent = connection.read_messages().await;
+        events.await.unwrap();
+        connection.close().await;
+
+        sent.into_iter()
+            .filter(|msg| msg.header.poss_dup_flag == Some(true))
+            .map(|msg| match *msg.body {
+                Message::SequenceReset(ref sequence_reset) => {
+                    (msg.header.msg_seq_num, Some(sequence_reset.new_seq_no))
+                }
+                _ => (msg.header.msg_seq_num, None),
+            })
+            .collect()
+    })
+}
+
+#[test]
+fn app_messages_resent_by_default() {
+    let resent = run(GapFillPolicy::default());
+    assert_eq!(resent, [(2, None), (3, None), (4, None)]);
+}
+
+#[test]
+fn msg_types_gap_filled() {
+    let resent = run(GapFillPolicy {
+        msg_types: vec![MsgType::News.as_fix_str().to_owned()],
+        ..Default::default()
+    });
+    assert_eq!(resent, [(2, Some(5))]);
+}
+
+#[test]
+fn stale_messages_gap_filled() {
+    let resent = run(GapFillPolicy {
+        max_age: Some(Duration::ZERO),
+        ..Default::default()
+    });
+    assert_eq!(resent, [(2, Some(5))]);
+}
+
+#[test]
+fn out_of_range_max_age_never_gap_fills() {
+    let resent = run(GapFillPolicy {
+        max_age: Some(Duration::MAX),
+        ..Default::default()
+    });
+    assert_eq!(resent, [(2, None), (3, None), (4, None)]);
+}
+
+#[test]
+fn predicate_gap_fills() {
+    let resent = run(GapFillPolicy {
+        predicate: GapFillPredicate::new(
+            |msg| matches!(*msg.body, Message::News(ref news) if news.headline == fix_str!("stale")),
+        ),
+        ..Default::default()
+    });
+    assert_eq!(resent, [(2, None), (3, Some(4)), (4, None)]);
+}
@@ -0,0 +1,77 @@
+//! Byte-exact output of admin messages sent by session, compared against
+//! files in `tests/golden` (with `|` as separator). Run with
+//! `UPDATE_GOLDEN=1` to rewrite the files after intended change of wire
+//! format.
+
+mod common;
+
+use std::{env, fs, path::PathBuf};
+
+use chrono::{TimeZone, Utc};
+use common::{
+    scenario::{exchange, LOGON},
+    session_settings,
+};
+use easyfix_messages::fields::{TimePrecision, UtcTimestamp};
+use easyfix_session::settings::{SessionClock, SessionSettings};
+
+fn fixed_clock_settings() -> SessionSettings {
+    let mut session_settings = session_settings();
+    session_settings.clock = SessionClock::fixed(UtcTimestamp::with_precision(
+        Utc.with_ymd_and_hms(2024, 3, 15, 9, 30, 0).unwrap(),
+        TimePrecision::Millis,
+    ));
+    session_settings
+}
+
+/// Send `inbound` messages, return responses to the last one.
+fn last_responses(inbound: &[&str]) -> Vec<String> {
+    exchange(fixed_clock_settings(), inbound)
+        .pop()
+        .expect("no responses")
+}
+
+fn assert_golden(name: &str, actual: &[String]) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{name}.fix"));
+    let actual = actual
+        .iter()
+        .map(|msg| format!("{msg}\n"))
+        .collect::<String>();
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("failed to read {}: {err}", path.display()));
+    assert_eq!(expected, actual, "output differs from {}", path.display());
+}
+
+#[test]
+fn logon() {
+    assert_golden("logon", &last_responses(&[LOGON]));
+}
+
+#[test]
+fn heartbeat() {
+    assert_golden("heartbeat", &last_responses(&[LOGON, "35=1|34=2|112=TEST"]));
+}
+
+#[test]
+fn reject() {
+    assert_golden("reject", &last_responses(&[LOGON, "35=ZZ|34=2"]));
+}
+
+#[test]
+fn resend_request() {
+    assert_golden("resend_request", &last_responses(&[LOGON, "35=0|34=5"]));
+}
+
+#[test]
+fn sequence_reset() {
+    assert_golden(
+        "sequence_reset",
+        &last_responses(&[LOGON, "35=1|34=2|112=TEST", "35=2|34=3|7=1|16=0"]),
+    );
+}
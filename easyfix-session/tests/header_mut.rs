@@ -0,0 +1,111 @@
+mod common;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, FixString, MsgType, TimePrecision, Urgency},
+    groups::LinesOfTextGrp,
+    messages::{FixtMessage, Message, News},
+};
+use easyfix_session::{
+    application::{AsEvent, FixEvent},
+    new_header, new_trailer,
+};
+use futures::StreamExt;
+use tokio::time::{timeout, Duration};
+
+fn news(headline: &str) -> Box<FixtMessage> {
+    let body = Box::new(Message::News(News {
+        headline: FixString::from_ascii_lossy(headline.as_bytes().to_vec()),
+        lines_of_text_grp: vec![LinesOfTextGrp {
+            text: fix_str!("header").to_owned(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }));
+    Box::new(FixtMessage {
+        header: Box::new(new_header(body.msg_type())),
+        body,
+        trailer: Box::new(new_trailer()),
+    })
+}
+
+/// Log on, send News<B> "edited" and "unlocked" and modify them in
+/// `AppMsgOut` event, return News<B> messages received by counterparty.
+fn run() -> Vec<FixtMessage> {
+    block_on(async {
+        let acceptor = acceptor(common::session_settings());
+        let mut connection = accept(&acceptor);
+        let events = tokio::task::spawn_local(async move {
+            let mut acceptor = Box::pin(acceptor);
+            while let Ok(Some(mut entry)) =
+                timeout(Duration::from_millis(300), acceptor.next()).await
+            {
+                match entry.as_event() {
+                    FixEvent::Logon(_, sender) => {
+                        sender.send_raw(news("edited")).unwrap();
+                        sender.send_raw(news("unlocked")).unwrap();
+                    }
+                    FixEvent::AppMsgOut(mut msg, _) => {
+                        let Message::News(news) = msg.body_mut() else {
+                            continue;
+                        };
+                        if news.headline.as_bytes() == b"edited" {
+                            news.urgency = Some(Urgency::Flash);
+                            let mut header = msg.header_mut();
+                            header.set_target_sub_id(Some(fix_str!("DESK").to_owned()));
+                            header.set_on_behalf_of_comp_id(Some(fix_str!("CLIENT").to_owned()));
+                            header.set_sending_time_precision(TimePrecision::Secs);
+                        } else {
+                            msg.unlock().header.poss_dup_flag = Some(true);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        connection.send(&basic_msg(0)).await;
+
+        let sent = connection.read_messages().await;
+        events.await.unwrap();
+        connection.close().await;
+
+        sent.into_iter()
+            .filter(|msg| msg.header.msg_type == MsgType::News)
+            .collect()
+    })
+}
+
+#[test]
+fn permitted_header_fields_edited() {
+    let sent = run();
+    assert_eq!(sent.len(), 2, "{sent:?}");
+
+    let edited = &sent[0];
+    let Message::News(ref news) = *edited.body else {
+        panic!("unexpected message: {edited:?}");
+    };
+    assert_eq!(news.headline, fix_str!("edited"));
+    assert_eq!(news.urgency, Some(Urgency::Flash));
+    assert_eq!(
+        edited.header.target_sub_id.as_deref(),
+        Some(fix_str!("DESK"))
+    );
+    assert_eq!(
+        edited.header.on_behalf_of_comp_id.as_deref(),
+        Some(fix_str!("CLIENT"))
+    );
+    assert_eq!(edited.header.sending_time.precision(), TimePrecision::Secs);
+    // Engine owned fields untouched
+    assert_eq!(edited.header.msg_seq_num, 2);
+    assert_eq!(edited.header.poss_dup_flag, None);
+}
+
+#[test]
+fn unlocked_header_edited() {
+    let sent = run();
+    assert_eq!(sent.len(), 2, "{sent:?}");
+    assert_eq!(sent[1].header.msg_seq_num, 3);
+    assert_eq!(sent[1].header.poss_dup_flag, Some(true));
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, FixString, MsgType},
+    messages::{FixtMessage, Message},
+};
+use easyfix_session::{
+    application::{AsEvent, FixEvent},
+    settings::TestRequestReplyPolicy,
+};
+use futures::StreamExt;
+use tokio::time::{timeout, Duration};
+
+/// Log on and send TestRequest<1>, Heartbeat<0> reply is customized
+/// by prefixing TestReqID<112> with `ECHO-` and setting
+/// SenderLocationID<142>. Returns the Heartbeat<0> written to the socket
+/// and number of `HeartbeatReply` events.
+fn run(policy: TestRequestReplyPolicy) -> (FixtMessage, usize) {
+    let mut session_settings = common::session_settings();
+    session_settings.test_request_reply = policy;
+
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut connection = accept(&acceptor);
+        let events = tokio::task::spawn_local(async move {
+            let mut replies = 0;
+            let mut acceptor = Box::pin(acceptor);
+            while let Some(mut event) = acceptor.next().await {
+                match event.as_event() {
+                    FixEvent::HeartbeatReply {
+                        test_request,
+                        reply,
+                        ..
+                    } => {
+                        let Message::TestRequest(ref test_request) = *test_request.body else {
+                            panic!("unexpected message: {test_request:?}");
+                        };
+                        let Message::Heartbeat(ref mut heartbeat) = *reply.body else {
+                            panic!("unexpected message: {reply:?}");
+                        };
+                        assert_eq!(
+                            heartbeat.test_req_id.as_ref(),
+                            Some(&test_request.test_req_id)
+                        );
+                        heartbeat.test_req_id = Some(FixString::from_ascii_lossy(
+                            format!("ECHO-{}", test_request.test_req_id).into_bytes(),
+                        ));
+                        reply.header.sender_location_id = Some(fix_str!("LOC").to_owned());
+                        replies += 1;
+                    }
+                    FixEvent::Logout(..) => break,
+                    _ => {}
+                }
+            }
+            replies
+        });
+
+        for index in [0, 2] {
+            connection.send(&basic_msg(index)).await;
+        }
+
+        let output = connection.read_messages().await;
+        connection.close().await;
+        let replies = timeout(Duration::from_secs(1), events)
+            .await
+            .expect("events not finished")
+            .unwrap();
+        let heartbeat = output
+            .into_iter()
+            .find(|msg| msg.msg_type() == MsgType::Heartbeat)
+            .expect("Heartbeat not sent");
+        (heartbeat, replies)
+    })
+}
+
+fn test_req_id(heartbeat: &FixtMessage) -> Option<&FixStr> {
+    let Message::Heartbeat(ref heartbeat) = *heartbeat.body else {
+        panic!("unexpected message: {heartbeat:?}");
+    };
+    heartbeat.test_req_id.as_deref()
+}
+
+#[test]
+fn auto_reply() {
+    let (heartbeat, replies) = run(TestRequestReplyPolicy::Auto);
+    assert_eq!(replies, 0);
+    assert_eq!(test_req_id(&heartbeat), Some(fix_str!("TEST1")));
+    assert_eq!(heartbeat.header.sender_location_id, None);
+}
+
+#[test]
+fn customized_reply() {
+    let (heartbeat, replies) = run(TestRequestReplyPolicy::Customize);
+    assert_eq!(replies, 1);
+    assert_eq!(test_req_id(&heartbeat), Some(fix_str!("ECHO-TEST1")));
+    assert_eq!(
+        heartbeat.header.sender_location_id.as_deref(),
+        Some(fix_str!("LOC"))
+    );
+    assert_eq!(heartbeat.header.msg_seq_num, 2);
+}
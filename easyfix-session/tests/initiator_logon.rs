@@ -0,0 +1,78 @@
+//! Logon of initiator, regression tests of fixes made together with
+//! `examples/soak.rs`.
+
+mod common;
+
+use std::{cell::Cell, rc::Rc};
+
+use common::scenario::{basic_msg, block_on, collect, drain, initiate};
+use easyfix_messages::{fields::MsgType, messages::FixtMessage};
+use easyfix_session::{
+    application::{AsEvent, FixEvent, SessionEventKind},
+    initiator::Initiator,
+    messages_storage::InMemoryStorage,
+};
+use futures::StreamExt;
+
+fn initiator() -> Initiator<InMemoryStorage> {
+    Initiator::new(
+        common::settings(),
+        common::session_settings(),
+        InMemoryStorage::new(),
+    )
+}
+
+fn msg_types(messages: &[FixtMessage]) -> Vec<MsgType> {
+    messages.iter().map(FixtMessage::msg_type).collect()
+}
+
+#[test]
+fn logon_response_not_answered_with_logon() {
+    block_on(async {
+        let initiator = initiator();
+        let mut logons = initiator
+            .events()
+            .subscribe()
+            .with_kinds(&[SessionEventKind::Logon]);
+        let mut connection = initiate(&initiator);
+        let _drain = drain(initiator);
+
+        let sent = connection.read_messages().await;
+        connection.send(&basic_msg(0)).await;
+        let logons = collect(&mut logons).await;
+        let after_response = connection.read_messages().await;
+        connection.close().await;
+
+        assert_eq!(msg_types(&sent), [MsgType::Logon]);
+        assert_eq!(logons.len(), 1, "{logons:?}");
+        assert_eq!(msg_types(&after_response), []);
+    });
+}
+
+#[test]
+fn events_streamed_by_initiator() {
+    block_on(async {
+        let initiator = initiator();
+        let mut connection = initiate(&initiator);
+        let logged_on = Rc::new(Cell::new(false));
+        let events = tokio::task::spawn_local({
+            let logged_on = logged_on.clone();
+            async move {
+                let mut initiator = Box::pin(initiator);
+                while let Some(mut entry) = initiator.next().await {
+                    if let FixEvent::Logon(..) = entry.as_event() {
+                        logged_on.set(true);
+                    }
+                }
+            }
+        });
+
+        connection.read().await;
+        connection.send(&basic_msg(0)).await;
+        connection.read().await;
+        connection.close().await;
+        events.abort();
+
+        assert!(logged_on.get());
+    });
+}
@@ -0,0 +1,138 @@
+mod common;
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use common::scenario::{accept, acceptor, basic_msg, block_on};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, FixString, MsgType},
+    groups::LinesOfTextGrp,
+    messages::{FixtMessage, Message, News},
+};
+use easyfix_session::{
+    application::{AsEvent, FixEvent, FlaggedMsg},
+    inspector::{Finding, Inspector, MsgDirection, SessionInspector},
+};
+use futures::StreamExt;
+use tokio::time::timeout;
+
+/// News<B> with `RESTRICTED` headline is flagged, decisions are cached
+/// by headline.
+#[derive(Debug, Default)]
+struct RestrictedHeadline {
+    inspected: Arc<AtomicUsize>,
+}
+
+fn headline(msg: &FixtMessage) -> Option<&FixStr> {
+    match &*msg.body {
+        Message::News(news) => Some(&news.headline),
+        _ => None,
+    }
+}
+
+impl Inspector for RestrictedHeadline {
+    fn cache_key(&self, _direction: MsgDirection, msg: &FixtMessage) -> Option<u64> {
+        headline(msg).map(|headline| headline.as_bytes().len() as u64)
+    }
+
+    fn inspect(&self, _direction: MsgDirection, msg: &FixtMessage) -> Vec<Finding> {
+        self.inspected.fetch_add(1, Ordering::Relaxed);
+        if headline(msg) == Some(fix_str!("RESTRICTED")) {
+            vec![Finding {
+                rule: "restricted-headline".to_owned(),
+                text: "headline is restricted".to_owned(),
+            }]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+fn news(headline: &str) -> Message {
+    Message::News(News {
+        headline: FixString::from_ascii_lossy(headline.as_bytes().to_vec()),
+        lines_of_text_grp: vec![LinesOfTextGrp {
+            text: fix_str!("inspected").to_owned(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    })
+}
+
+/// Log on, receive and send News<B> with given headlines, return flagged
+/// messages and number of `inspect()` calls.
+fn run(
+    inbound: &'static [&'static str],
+    outbound: &'static [&'static str],
+) -> (Vec<FlaggedMsg>, usize) {
+    let inspector = RestrictedHeadline::default();
+    let inspected = inspector.inspected.clone();
+    let mut session_settings = common::session_settings();
+    session_settings.inspector = SessionInspector::new(inspector);
+
+    let flagged = block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut connection = accept(&acceptor);
+        let events = tokio::task::spawn_local(async move {
+            let mut acceptor = Box::pin(acceptor);
+            let mut flagged = Vec::new();
+            while let Ok(Some(mut entry)) =
+                timeout(Duration::from_millis(300), acceptor.next()).await
+            {
+                match entry.as_event() {
+                    FixEvent::Logon(_, sender) => {
+                        for headline in outbound {
+                            sender.send(Box::new(news(headline))).unwrap();
+                        }
+                    }
+                    FixEvent::MsgFlagged(_, msg) => flagged.push(msg.clone()),
+                    _ => {}
+                }
+            }
+            flagged
+        });
+
+        let logon = basic_msg(0);
+        connection.send(&logon).await;
+        for (seq_num, headline) in (2..).zip(inbound) {
+            let mut msg = logon.clone();
+            msg.header.msg_type = MsgType::News;
+            msg.header.msg_seq_num = seq_num;
+            *msg.body = news(headline);
+            connection.send(&msg).await;
+        }
+
+        let flagged = events.await.unwrap();
+        connection.close().await;
+        flagged
+    });
+    (flagged, inspected.load(Ordering::Relaxed))
+}
+
+#[test]
+fn flagged_in_both_directions() {
+    let (flagged, _) = run(&["RESTRICTED", "allowed"], &["open", "RESTRICTED"]);
+    assert_eq!(flagged.len(), 2, "{flagged:?}");
+
+    assert_eq!(flagged[0].direction, MsgDirection::Inbound);
+    assert_eq!(flagged[0].msg.header.msg_seq_num, 2);
+    assert_eq!(flagged[0].findings[0].rule, "restricted-headline");
+
+    assert_eq!(flagged[1].direction, MsgDirection::Outbound);
+    assert_eq!(headline(&flagged[1].msg), Some(fix_str!("RESTRICTED")));
+}
+
+#[test]
+fn decisions_cached() {
+    // Headlines of the same length share cached decision
+    let (flagged, inspected) = run(&["RESTRICTED", "ALLOWED!!!", "allowed"], &[]);
+    assert_eq!(inspected, 2);
+    assert_eq!(flagged.len(), 2, "{flagged:?}");
+    assert_eq!(flagged[1].msg.header.msg_seq_num, 3);
+}
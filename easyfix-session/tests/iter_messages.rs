@@ -0,0 +1,91 @@
+mod common;
+
+use bytes::BytesMut;
+use common::scenario::basic_msg;
+use easyfix_messages::{
+    deserializer::GroupLimits,
+    fields::{MsgDirection, MsgType, SessionRejectReason, ToFixString},
+    groups::MsgTypeGrp,
+    messages::Message,
+};
+use easyfix_session::{application::DeserializeError, io::iter_messages};
+
+#[test]
+fn iter_back_to_back_messages() {
+    let logon = basic_msg(0).serialize();
+    let test_request = basic_msg(2).serialize();
+
+    let mut buffer = BytesMut::new();
+    buffer.extend_from_slice(&logon);
+    buffer.extend_from_slice(&test_request);
+    buffer.extend_from_slice(&logon[..10]);
+
+    let mut iter = iter_messages(&mut buffer);
+    let msg = iter.next().unwrap().unwrap();
+    assert_eq!(msg.msg_type(), MsgType::Logon);
+    assert_eq!(iter.last_consumed(), logon.len());
+    let msg = iter.next().unwrap().unwrap();
+    assert_eq!(msg.msg_type(), MsgType::TestRequest);
+    assert!(iter.next().is_none());
+    assert_eq!(iter.consumed(), logon.len() + test_request.len());
+    assert_eq!(iter.remaining(), &logon[..10]);
+
+    // Trailing partial message completed by the next read
+    buffer.extend_from_slice(&logon[10..]);
+    let messages: Vec<_> = iter_messages(&mut buffer).collect();
+    assert_eq!(messages.len(), 1);
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn iter_skips_garbled_data() {
+    let logon = basic_msg(0).serialize();
+
+    let mut buffer = BytesMut::new();
+    buffer.extend_from_slice(b"garbage");
+    buffer.extend_from_slice(&logon);
+
+    let mut iter = iter_messages(&mut buffer);
+    assert!(matches!(
+        iter.next(),
+        Some(Err(DeserializeError::GarbledMessage(_)))
+    ));
+    assert_eq!(iter.last_consumed(), b"garbage".len());
+    assert!(iter.next().unwrap().is_ok());
+    assert!(iter.next().is_none());
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn iter_with_group_limits() {
+    let mut logon = basic_msg(0);
+    let Message::Logon(body) = &mut *logon.body else {
+        unreachable!()
+    };
+    let entry = MsgTypeGrp {
+        ref_msg_type: Some(MsgType::NewOrderSingle.to_fix_string()),
+        msg_direction: Some(MsgDirection::Send),
+        ..Default::default()
+    };
+    body.msg_type_grp = Some(vec![entry.clone(), entry]);
+    let logon = logon.serialize();
+    let group_limits = GroupLimits {
+        max_entries: 1,
+        ..Default::default()
+    };
+
+    let mut buffer = BytesMut::from(&logon[..]);
+    assert!(matches!(
+        iter_messages(&mut buffer)
+            .with_group_limits(group_limits)
+            .next(),
+        Some(Err(DeserializeError::Reject {
+            tag: Some(384),
+            reason: SessionRejectReason::IncorrectNumingroupCountForRepeatingGroup,
+            ..
+        }))
+    ));
+
+    let mut buffer = BytesMut::from(&logon[..]);
+    assert!(iter_messages(&mut buffer).next().unwrap().is_ok());
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use std::time::Duration;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, FixString, MsgType},
+    groups::LinesOfTextGrp,
+    messages::{Header, Message, News},
+};
+use easyfix_session::{
+    application::{AsEvent, FixEvent, LatencyAlert, LatencyStage},
+    settings::LatencyBudgets,
+    verifier::{SessionVerifier, Verdict, Verifier},
+};
+use futures::{future::LocalBoxFuture, StreamExt};
+use tokio::time::timeout;
+
+const SLOW: Duration = Duration::from_millis(50);
+const BUDGET: Duration = Duration::from_millis(20);
+
+/// Verification of messages addressed (with TargetSubID<57>) to `SLOW`
+/// takes longer than `BUDGET`.
+#[derive(Debug)]
+struct SlowVerifier;
+
+impl Verifier for SlowVerifier {
+    fn verify<'a>(&'a self, header: &'a Header) -> LocalBoxFuture<'a, Verdict> {
+        Box::pin(async move {
+            if header.target_sub_id.as_deref() == Some(fix_str!("SLOW")) {
+                tokio::time::sleep(SLOW).await;
+            }
+            Verdict::Accept
+        })
+    }
+}
+
+fn news(headline: &str) -> Message {
+    Message::News(News {
+        headline: FixString::from_ascii_lossy(headline.as_bytes().to_vec()),
+        lines_of_text_grp: vec![LinesOfTextGrp {
+            text: fix_str!("latency").to_owned(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    })
+}
+
+/// Log on and receive News<B> with given headlines, handling of `slow`
+/// ones takes longer than `BUDGET`, return latency alerts.
+fn run(latency_budgets: LatencyBudgets, headlines: &'static [&'static str]) -> Vec<LatencyAlert> {
+    let mut session_settings = common::session_settings();
+    session_settings.latency_budgets = latency_budgets;
+    session_settings.verifier = SessionVerifier::new(SlowVerifier);
+
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut connection = accept(&acceptor);
+        let events = tokio::task::spawn_local(async move {
+            let mut acceptor = Box::pin(acceptor);
+            let mut alerts = Vec::new();
+            while let Ok(Some(mut entry)) =
+                timeout(Duration::from_millis(300), acceptor.next()).await
+            {
+                match entry.as_event() {
+                    FixEvent::AppMsgIn(msg, _) => {
+                        if let Message::News(news) = &*msg.body {
+                            if news.headline.as_bytes() == b"slow" {
+                                tokio::time::sleep(SLOW).await;
+                            }
+                        }
+                    }
+                    FixEvent::LatencyBudgetExceeded(_, alert) => alerts.push(alert.clone()),
+                    _ => {}
+                }
+            }
+            alerts
+        });
+
+        let logon = basic_msg(0);
+        connection.send(&logon).await;
+        for (seq_num, headline) in (2..).zip(headlines) {
+            let mut msg = logon.clone();
+            msg.header.msg_type = MsgType::News;
+            msg.header.msg_seq_num = seq_num;
+            if *headline == "slow verify" {
+                msg.header.target_sub_id = Some(fix_str!("SLOW").to_owned());
+            }
+            *msg.body = news(headline);
+            connection.send(&msg).await;
+        }
+
+        let alerts = events.await.unwrap();
+        connection.close().await;
+        alerts
+    })
+}
+
+#[test]
+fn slow_app_handler_reported() {
+    let alerts = run(
+        LatencyBudgets {
+            app_handler: Some(BUDGET),
+            ..Default::default()
+        },
+        &["fast", "slow", "slow verify"],
+    );
+    assert_eq!(alerts.len(), 1, "{alerts:?}");
+    assert_eq!(alerts[0].stage, LatencyStage::AppHandler);
+    assert_eq!(alerts[0].msg_seq_num, 3);
+    assert_eq!(alerts[0].msg_type, MsgType::News.as_fix_str());
+    assert_eq!(alerts[0].budget, BUDGET);
+    assert!(alerts[0].elapsed >= SLOW, "{alerts:?}");
+}
+
+#[test]
+fn slow_verification_reported() {
+    let alerts = run(
+        LatencyBudgets {
+            verify: Some(BUDGET),
+            ..Default::default()
+        },
+        &["fast", "slow", "slow verify"],
+    );
+    assert_eq!(alerts.len(), 1, "{alerts:?}");
+    assert_eq!(alerts[0].stage, LatencyStage::Verify);
+    assert_eq!(alerts[0].msg_seq_num, 4);
+    assert!(alerts[0].elapsed >= SLOW, "{alerts:?}");
+}
+
+#[test]
+fn nothing_reported_within_budgets() {
+    let alerts = run(
+        LatencyBudgets {
+            decode: Some(Duration::from_secs(1)),
+            verify: Some(Duration::from_secs(1)),
+            app_handler: Some(Duration::from_secs(1)),
+            serialize_write: Some(Duration::from_secs(1)),
+        },
+        &["fast", "slow", "slow verify"],
+    );
+    assert!(alerts.is_empty(), "{alerts:?}");
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use common::scenario::{basic_msg, block_on, collect, drain, initiate};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, MsgType, SessionStatus},
+    messages::{FixtMessage, Logout, Message},
+};
+use easyfix_session::{
+    application::{SessionEvent, SessionEventKind, SessionStatusReport},
+    initiator::Initiator,
+    messages_storage::InMemoryStorage,
+    settings::LogonCredentials,
+};
+
+/// Messages sent by initiator in one connection.
+type Sent = Vec<FixtMessage>;
+
+/// Connect initiator twice to fake counterparty, first connection is
+/// answered with `response`. Returns reported session statuses and messages
+/// sent by initiator in each connection.
+fn run(response: FixtMessage) -> (Vec<SessionStatusReport>, Sent, Sent) {
+    let mut session_settings = common::session_settings();
+    session_settings.credentials = LogonCredentials {
+        username: Some(fix_str!("user").to_owned()),
+        password: Some(fix_str!("old").to_owned()),
+        new_password: Some(fix_str!("new").to_owned()),
+    };
+
+    block_on(async move {
+        let initiator =
+            Initiator::new(common::settings(), session_settings, InMemoryStorage::new());
+        let mut subscriber = initiator
+            .events()
+            .subscribe()
+            .with_kinds(&[SessionEventKind::SessionStatus]);
+        let mut first = initiate(&initiator);
+        let mut second = initiate(&initiator);
+        let _drain = drain(initiator);
+
+        first.send(&response).await;
+        let reports = collect(&mut subscriber)
+            .await
+            .into_iter()
+            .map(|event| match event {
+                SessionEvent::SessionStatus(_, report) => (*report).clone(),
+                event => panic!("unexpected event {event:?}"),
+            })
+            .collect();
+        let first_sent = first.read_messages().await;
+        first.close().await;
+
+        let second_sent = second.read_messages().await;
+        second.close().await;
+
+        (reports, first_sent, second_sent)
+    })
+}
+
+fn logon_response(session_status: Option<SessionStatus>) -> FixtMessage {
+    let mut msg = basic_msg(0);
+    let Message::Logon(ref mut logon) = *msg.body else {
+        unreachable!()
+    };
+    logon.session_status = session_status;
+    logon.text = Some(fix_str!("status").to_owned());
+    msg
+}
+
+fn logout(session_status: SessionStatus) -> FixtMessage {
+    let mut msg = logon_response(None);
+    *msg.body = Message::Logout(Logout {
+        session_status: Some(session_status),
+        text: Some(fix_str!("status").to_owned()),
+        ..Default::default()
+    });
+    msg.header.msg_type = msg.body.msg_type();
+    msg
+}
+
+/// Password<554> and NewPassword<925> of the first Logon<A> sent.
+fn passwords(sent: &[FixtMessage]) -> (Option<&FixStr>, Option<&FixStr>) {
+    let logon = sent
+        .iter()
+        .find_map(|msg| match *msg.body {
+            Message::Logon(ref logon) => Some(logon),
+            _ => None,
+        })
+        .expect("Logon<A> not sent");
+    assert_eq!(logon.username.as_deref(), Some(fix_str!("user")));
+    (logon.password.as_deref(), logon.new_password.as_deref())
+}
+
+fn report(msg_type: MsgType, status: SessionStatus) -> SessionStatusReport {
+    SessionStatusReport {
+        msg_type,
+        status,
+        text: Some(fix_str!("status").to_owned()),
+    }
+}
+
+#[test]
+fn new_password_used_once_changed() {
+    let (reports, first, second) = run(logon_response(Some(SessionStatus::SessionPasswordChanged)));
+    assert_eq!(
+        reports,
+        [report(
+            MsgType::Logon,
+            SessionStatus::SessionPasswordChanged
+        )]
+    );
+    assert_eq!(
+        passwords(&first),
+        (Some(fix_str!("old")), Some(fix_str!("new")))
+    );
+    assert_eq!(passwords(&second), (Some(fix_str!("new")), None));
+}
+
+#[test]
+fn new_password_sent_again_when_rejected() {
+    let status = SessionStatus::NewSessionPasswordDoesNotComplyWithPolicy;
+    let (reports, _, second) = run(logon_response(Some(status)));
+    assert_eq!(reports, [report(MsgType::Logon, status)]);
+    assert_eq!(
+        passwords(&second),
+        (Some(fix_str!("old")), Some(fix_str!("new")))
+    );
+}
+
+#[test]
+fn no_status_reported_without_session_status() {
+    let (reports, _, _) = run(logon_response(None));
+    assert_eq!(reports, []);
+}
+
+#[test]
+fn password_expiry_reported_in_logout() {
+    let (reports, first, _) = run(logout(SessionStatus::PasswordExpired));
+    assert_eq!(
+        reports,
+        [report(MsgType::Logout, SessionStatus::PasswordExpired)]
+    );
+    assert!(reports[0].password_expiry());
+    assert_eq!(first[0].header.msg_type, MsgType::Logon);
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use common::scenario::{basic_msg, block_on, collect, drain, initiate};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{DefaultApplVerId, FixStr, MsgType},
+    messages::{FieldTag, FixtMessage, Message},
+};
+use easyfix_session::{
+    application::{LogonMismatch, SessionEvent, SessionEventKind},
+    initiator::Initiator,
+    messages_storage::InMemoryStorage,
+};
+
+/// Connect initiator to fake counterparty responding with `logon` and
+/// return reported mismatch and messages sent by initiator.
+fn run(
+    logon: FixtMessage,
+    check_default_appl_ver_id: bool,
+) -> (Option<LogonMismatch>, Vec<FixtMessage>) {
+    let mut session_settings = common::session_settings();
+    session_settings.check_default_appl_ver_id = check_default_appl_ver_id;
+
+    block_on(async move {
+        let initiator =
+            Initiator::new(common::settings(), session_settings, InMemoryStorage::new());
+        let mut subscriber = initiator
+            .events()
+            .subscribe()
+            .with_kinds(&[SessionEventKind::Logon, SessionEventKind::LogonMismatch]);
+        let mut connection = initiate(&initiator);
+        let _drain = drain(initiator);
+
+        connection.send(&logon).await;
+
+        let mut mismatch = None;
+        for event in collect(&mut subscriber).await {
+            match event {
+                SessionEvent::LogonMismatch(_, event) => mismatch = Some(event),
+                SessionEvent::Logon(_) => {}
+                event => panic!("unexpected event {event:?}"),
+            }
+        }
+
+        let sent = connection.read_messages().await;
+        connection.close().await;
+        (mismatch, sent)
+    })
+}
+
+fn msg_types(sent: &[FixtMessage]) -> Vec<MsgType> {
+    sent.iter().map(|msg| msg.header.msg_type).collect()
+}
+
+#[test]
+fn logon_response_matches() {
+    let (mismatch, sent) = run(basic_msg(0), true);
+    assert_eq!(mismatch, None);
+    assert_eq!(msg_types(&sent), [MsgType::Logon]);
+}
+
+#[test]
+fn sender_comp_id_mismatch() {
+    let mut logon = basic_msg(0);
+    logon.header.sender_comp_id = fix_str!("VENUE").to_owned();
+    let (mismatch, sent) = run(logon, false);
+    assert_eq!(
+        mismatch,
+        Some(LogonMismatch {
+            tag: FieldTag::SenderCompId as u16,
+            expected: fix_str!("CLIENT").to_owned(),
+            received: fix_str!("VENUE").to_owned(),
+        })
+    );
+    assert_eq!(msg_types(&sent), [MsgType::Logon, MsgType::Logout]);
+}
+
+#[test]
+fn default_appl_ver_id_mismatch() {
+    let mut logon = basic_msg(0);
+    let Message::Logon(ref mut body) = *logon.body else {
+        unreachable!()
+    };
+    body.default_appl_ver_id = DefaultApplVerId::Fix44;
+
+    let (mismatch, _) = run(logon.clone(), false);
+    assert_eq!(mismatch, None);
+
+    let (mismatch, sent) = run(logon, true);
+    assert_eq!(
+        mismatch,
+        Some(LogonMismatch {
+            tag: FieldTag::DefaultApplVerId as u16,
+            expected: fix_str!("9").to_owned(),
+            received: fix_str!("6").to_owned(),
+        })
+    );
+    assert_eq!(msg_types(&sent), [MsgType::Logon, MsgType::Logout]);
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use std::time::Duration;
+
+use chrono::TimeDelta;
+use common::scenario::{accept, acceptor, basic_msg, block_on, drain};
+use easyfix_messages::{
+    fields::{Utc, UtcTimestamp},
+    messages::{FixtMessage, Message},
+};
+use easyfix_session::{
+    logon_guard::{LogonGuard, StaleLogon},
+    settings::LogonReplaySettings,
+};
+
+const REPLAY: LogonReplaySettings = LogonReplaySettings {
+    max_age: Duration::from_secs(30),
+    require_nonce: false,
+};
+
+fn logon(age: TimeDelta, nonce: Option<&[u8]>) -> FixtMessage {
+    let mut logon = basic_msg(0);
+    logon.header.sending_time =
+        UtcTimestamp::with_precision(Utc::now() - age, logon.header.sending_time.precision());
+    let Message::Logon(ref mut body) = *logon.body else {
+        unreachable!()
+    };
+    body.raw_data = nonce.map(Into::into);
+    // Each connection starts from MsgSeqNum<34> 1
+    body.reset_seq_num_flag = Some(true);
+    logon
+}
+
+/// Log on to the same acceptor session with each Logon<A> in turn,
+/// returns whether they were accepted.
+fn run(replay: LogonReplaySettings, logons: Vec<FixtMessage>) -> Vec<bool> {
+    let mut session_settings = common::session_settings();
+    session_settings.logon_replay = Some(replay);
+
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let connections: Vec<_> = logons.iter().map(|_| accept(&acceptor)).collect();
+        let _drain = drain(acceptor);
+
+        let mut accepted = Vec::new();
+        for (mut connection, logon) in connections.into_iter().zip(logons) {
+            // Sent as is, with SendingTime<52> shifted by `age`
+            connection.send_raw(&logon.serialize()).await;
+            let output = connection.read().await;
+            connection.close().await;
+            accepted.push(output.windows(5).any(|field| field == b"\x0135=A"));
+        }
+        accepted
+    })
+}
+
+#[test]
+fn fresh_logon_accepted() {
+    assert_eq!(run(REPLAY, vec![logon(TimeDelta::zero(), None)]), [true]);
+}
+
+#[test]
+fn stale_logon_rejected() {
+    // Still within SessionSettings::max_latency, but too old for replay
+    // protection
+    assert_eq!(
+        run(REPLAY, vec![logon(TimeDelta::seconds(60), None)]),
+        [false]
+    );
+}
+
+#[test]
+fn replayed_nonce_rejected() {
+    let replay = LogonReplaySettings {
+        require_nonce: true,
+        ..REPLAY
+    };
+    let accepted = run(
+        replay,
+        vec![
+            logon(TimeDelta::zero(), None),
+            logon(TimeDelta::zero(), Some(b"nonce-1")),
+            logon(TimeDelta::zero(), Some(b"nonce-1")),
+            logon(TimeDelta::zero(), Some(b"nonce-2")),
+        ],
+    );
+    assert_eq!(accepted, [false, true, false, true]);
+}
+
+#[test]
+fn guard_forgets_expired_nonces() {
+    let mut guard = LogonGuard::new();
+    let now = Utc::now();
+    let sending_time = UtcTimestamp::with_precision(now, Default::default());
+    assert_eq!(
+        guard.check(&REPLAY, sending_time, Some(b"nonce"), now),
+        Ok(())
+    );
+    assert_eq!(
+        guard.check(&REPLAY, sending_time, Some(b"nonce"), now),
+        Err(StaleLogon::ReplayedNonce)
+    );
+    assert_eq!(
+        guard.check(&REPLAY, sending_time, None, now - TimeDelta::seconds(31)),
+        Err(StaleLogon::FromFuture)
+    );
+
+    let later = now + TimeDelta::seconds(31);
+    assert_eq!(
+        guard.check(&REPLAY, sending_time, Some(b"nonce"), later),
+        Err(StaleLogon::TooOld)
+    );
+    let sending_time = UtcTimestamp::with_precision(later, Default::default());
+    assert_eq!(guard.check(&REPLAY, sending_time, None, later), Ok(()));
+    assert!(guard.is_empty());
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use chrono::TimeDelta;
+use common::scenario::{accept, acceptor, basic_msg, block_on, collect, drain};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, SessionRejectReason, Utc, UtcTimestamp},
+    messages::{FieldTag, FixtMessage},
+};
+use easyfix_session::{
+    application::{LogoutDetails, SessionEvent, SessionEventKind},
+    settings::{MsgSeqNumTooLowAction, SessionSettings},
+    DisconnectReason,
+};
+
+/// Send `msgs` to acceptor as they are, then disconnect. Returns
+/// `LogoutDetails` and `Logout` events.
+fn run(msgs: Vec<FixtMessage>) -> Vec<SessionEvent> {
+    run_with(common::session_settings(), msgs).0
+}
+
+/// Send `msgs` to acceptor configured with `session_settings`, see
+/// [`run`]. Returns also raw data sent by acceptor.
+fn run_with(
+    session_settings: SessionSettings,
+    msgs: Vec<FixtMessage>,
+) -> (Vec<SessionEvent>, String) {
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut subscriber = acceptor
+            .events()
+            .subscribe()
+            .with_kinds(&[SessionEventKind::LogoutDetails, SessionEventKind::Logout]);
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        for msg in msgs {
+            connection.send_raw(&msg.serialize()).await;
+        }
+        let sent = connection.read().await;
+        connection.close().await;
+        (
+            collect(&mut subscriber).await,
+            String::from_utf8(sent).unwrap(),
+        )
+    })
+}
+
+#[test]
+fn stale_logon() {
+    let mut logon = basic_msg(0);
+    logon.header.sending_time = UtcTimestamp::with_precision(
+        Utc::now() - TimeDelta::minutes(10),
+        logon.header.sending_time.precision(),
+    );
+
+    let events = run(vec![logon]);
+
+    // Logon<A> was not accepted, so only details are reported
+    let [SessionEvent::LogoutDetails(_, details)] = &events[..] else {
+        panic!("unexpected events {events:?}");
+    };
+    assert_eq!(
+        **details,
+        LogoutDetails {
+            reject_reason: Some(SessionRejectReason::SendingtimeAccuracyProblem),
+            tag: Some(FieldTag::SendingTime as u16),
+            msg_type: Some(fix_str!("A").to_owned()),
+            msg_seq_num: Some(1),
+            sender_comp_id: None,
+            target_comp_id: None,
+            text: "Message rejected with logout (SendingtimeAccuracyProblem)".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn seq_num_too_low() {
+    let mut test_request = basic_msg(2);
+    test_request.header.msg_seq_num = 1;
+
+    let events = run(vec![basic_msg(0), test_request]);
+
+    let [SessionEvent::LogoutDetails(_, details), SessionEvent::Logout(_, reason)] = &events[..]
+    else {
+        panic!("unexpected events {events:?}");
+    };
+    assert_eq!(*reason, DisconnectReason::MsgSeqNumTooLow);
+    assert_eq!(details.tag, Some(FieldTag::MsgSeqNum as u16));
+    assert_eq!(details.msg_type.as_deref(), Some(fix_str!("1")));
+    assert_eq!(details.msg_seq_num, Some(1));
+    assert_eq!(details.text, "MsgSeqNum too low, expected 2, got 1");
+}
+
+#[test]
+fn seq_num_too_low_custom_text() {
+    let mut session_settings = common::session_settings();
+    session_settings.msg_seq_num_too_low = MsgSeqNumTooLowAction::Logout {
+        text: Some("Expected {expected}, received {received}".to_owned()),
+        disconnect: true,
+    };
+    let mut test_request = basic_msg(2);
+    test_request.header.msg_seq_num = 1;
+
+    let (events, sent) = run_with(session_settings, vec![basic_msg(0), test_request]);
+
+    let [SessionEvent::LogoutDetails(_, details), SessionEvent::Logout(..)] = &events[..] else {
+        panic!("unexpected events {events:?}");
+    };
+    // Details match Text<58> of sent Logout<5>
+    assert_eq!(details.text, "Expected 2, received 1");
+    assert!(sent.contains("\x0158=Expected 2, received 1\x01"), "{sent}");
+}
+
+#[test]
+fn remote_logout_without_details() {
+    let mut logout = basic_msg(7);
+    logout.header.msg_seq_num = 2;
+
+    let events = run(vec![basic_msg(0), logout]);
+
+    let [SessionEvent::Logout(_, reason)] = &events[..] else {
+        panic!("unexpected events {events:?}");
+    };
+    assert_eq!(*reason, DisconnectReason::RemoteRequestedLogout);
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::TimeDelta;
+use common::scenario::{accept, acceptor, basic_msg, block_on, drain};
+use easyfix_messages::{
+    fields::{MsgType, SeqNum, Utc, UtcTimestamp},
+    messages::{FixtMessage, Heartbeat, Logout, Message},
+};
+use easyfix_session::application::{LogoutGraceOutcome, SessionEvent, SessionEventKind};
+use futures::StreamExt;
+use tokio::time::timeout;
+
+fn heartbeat() -> Message {
+    Message::Heartbeat(Heartbeat { test_req_id: None })
+}
+
+fn logout() -> Message {
+    Message::Logout(Logout::default())
+}
+
+/// Log on and send `messages`, returns admin messages sent after Logon<A>
+/// response and logout grace outcomes.
+fn run(
+    grace_period: Duration,
+    messages: Vec<(SeqNum, Message)>,
+) -> (Vec<Arc<FixtMessage>>, Vec<LogoutGraceOutcome>) {
+    run_patched(grace_period, messages, |_| {})
+}
+
+/// Like [`run`], each message is modified by `patch` before it's sent.
+fn run_patched(
+    grace_period: Duration,
+    messages: Vec<(SeqNum, Message)>,
+    patch: impl Fn(&mut FixtMessage) + 'static,
+) -> (Vec<Arc<FixtMessage>>, Vec<LogoutGraceOutcome>) {
+    let mut session_settings = common::session_settings();
+    session_settings.logout_resend_grace_period = Some(grace_period);
+
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut subscriber = acceptor
+            .events()
+            .subscribe()
+            .with_kinds(&[SessionEventKind::AdmMsgOut, SessionEventKind::LogoutGrace]);
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        for (msg_seq_num, body) in [(1, None)]
+            .into_iter()
+            .chain(messages.into_iter().map(|(seq, body)| (seq, Some(body))))
+        {
+            let mut msg = basic_msg(0);
+            if let Some(body) = body {
+                msg.header.msg_type = body.msg_type();
+                *msg.body = body;
+            }
+            msg.header.msg_seq_num = msg_seq_num;
+            patch(&mut msg);
+            connection.send_raw(&msg.serialize()).await;
+        }
+
+        let mut sent = Vec::new();
+        let mut outcomes = Vec::new();
+        while let Ok(Some(event)) = timeout(grace_period * 2, subscriber.next()).await {
+            match event {
+                SessionEvent::AdmMsgOut(_, msg) if msg.msg_type() != MsgType::Logon => {
+                    sent.push(msg)
+                }
+                SessionEvent::LogoutGrace(_, outcome) => outcomes.push(outcome),
+                _ => {}
+            }
+        }
+        connection.close().await;
+        (sent, outcomes)
+    })
+}
+
+fn msg_types(sent: &[Arc<FixtMessage>]) -> Vec<MsgType> {
+    sent.iter().map(|msg| msg.msg_type()).collect()
+}
+
+#[test]
+fn logout_response_after_resend() {
+    let (sent, outcomes) = run(
+        Duration::from_millis(500),
+        vec![
+            (5, heartbeat()),
+            (6, logout()),
+            (2, heartbeat()),
+            (3, heartbeat()),
+            (4, heartbeat()),
+        ],
+    );
+    assert_eq!(
+        msg_types(&sent),
+        [MsgType::ResendRequest, MsgType::Logout],
+        "{sent:?}"
+    );
+    assert_eq!(outcomes, [LogoutGraceOutcome::ResendCompleted]);
+}
+
+#[test]
+fn logout_response_after_grace_period() {
+    let (sent, outcomes) = run(
+        Duration::from_millis(200),
+        vec![(5, heartbeat()), (6, logout()), (2, heartbeat())],
+    );
+    assert_eq!(
+        msg_types(&sent),
+        [MsgType::ResendRequest, MsgType::Logout],
+        "{sent:?}"
+    );
+    assert_eq!(outcomes, [LogoutGraceOutcome::Expired]);
+}
+
+#[test]
+fn logout_without_pending_resend() {
+    let (sent, outcomes) = run(Duration::from_millis(200), vec![(2, logout())]);
+    assert_eq!(msg_types(&sent), [MsgType::Logout], "{sent:?}");
+    assert!(outcomes.is_empty());
+}
+
+#[test]
+fn invalid_logout_rejected_with_pending_resend() {
+    let (sent, outcomes) = run_patched(
+        Duration::from_millis(200),
+        vec![(5, heartbeat()), (6, logout())],
+        |msg| {
+            if msg.msg_type() == MsgType::Logout {
+                msg.header.sending_time = UtcTimestamp::with_precision(
+                    Utc::now() - TimeDelta::minutes(10),
+                    msg.header.sending_time.precision(),
+                );
+            }
+        },
+    );
+    assert_eq!(
+        msg_types(&sent),
+        [MsgType::ResendRequest, MsgType::Reject, MsgType::Logout],
+        "{sent:?}"
+    );
+    assert!(outcomes.is_empty());
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use std::{cell::RefCell, rc::Rc};
+
+use common::scenario::{accept, acceptor, basic_msg, block_on};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, Utc, UtcTimestamp},
+    groups::LinesOfTextGrp,
+    messages::{FixtMessage, Message, News},
+};
+use easyfix_session::{
+    application::{AsEvent, FixEvent},
+    new_header, new_trailer, ResendOptions, ResendSeqNum, SendErrorReason, Sender,
+};
+use futures::StreamExt;
+
+fn news() -> Box<Message> {
+    Box::new(Message::News(News {
+        headline: fix_str!("headline").to_owned(),
+        lines_of_text_grp: vec![LinesOfTextGrp {
+            text: fix_str!("text").to_owned(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }))
+}
+
+/// Log on, send News<B> and resend it with given options, returns
+/// all News<B> messages as they were sent and reason why resend
+/// of message without MsgSeqNum<34> was refused.
+fn run(options: Vec<ResendOptions>) -> (Vec<FixtMessage>, Option<SendErrorReason>) {
+    block_on(async move {
+        let acceptor = acceptor(common::session_settings());
+        let mut connection = accept(&acceptor);
+        let sent = Rc::new(RefCell::new(Vec::new()));
+        let refused = Rc::new(RefCell::new(None));
+        let events = tokio::task::spawn_local({
+            let sent = sent.clone();
+            let refused = refused.clone();
+            async move {
+                let mut sender: Option<Sender> = None;
+                let mut acceptor = Box::pin(acceptor);
+                while let Some(mut entry) = acceptor.next().await {
+                    match entry.as_event() {
+                        FixEvent::Logon(_, logon_sender) => {
+                            let unsent = Box::new(FixtMessage {
+                                header: Box::new(new_header(news().msg_type())),
+                                body: news(),
+                                trailer: Box::new(new_trailer()),
+                            });
+                            *refused.borrow_mut() = logon_sender
+                                .resend(unsent, ResendOptions::default())
+                                .err()
+                                .map(|err| err.reason);
+                            logon_sender.send(news()).unwrap();
+                            sender = Some(logon_sender);
+                        }
+                        FixEvent::AppMsgOut(msg, _) => {
+                            let original = sent.borrow().is_empty();
+                            sent.borrow_mut().push(msg.clone());
+                            if original {
+                                for options in &options {
+                                    let msg = Box::new(msg.clone());
+                                    sender.as_ref().unwrap().resend(msg, *options).unwrap();
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        });
+
+        connection.send(&basic_msg(0)).await;
+        connection.read().await;
+
+        connection.close().await;
+        events.abort();
+        (sent.take(), refused.take())
+    })
+}
+
+#[test]
+fn resend_with_original_and_new_seq_num() {
+    let orig_sending_time = UtcTimestamp::with_millis(Utc::now());
+    let (sent, _) = run(vec![
+        ResendOptions::default(),
+        ResendOptions {
+            seq_num: ResendSeqNum::New,
+            orig_sending_time: Some(orig_sending_time),
+        },
+    ]);
+    assert_eq!(sent.len(), 3);
+    let [original, poss_dup, poss_resend] = &sent[..] else {
+        unreachable!()
+    };
+
+    assert_eq!(original.header.msg_seq_num, 2);
+    assert_eq!(original.header.poss_dup_flag, None);
+    assert_eq!(original.header.orig_sending_time, None);
+
+    assert_eq!(poss_dup.header.msg_seq_num, 2);
+    assert_eq!(poss_dup.header.poss_dup_flag, Some(true));
+    assert_eq!(poss_dup.header.poss_resend, None);
+    assert_eq!(
+        poss_dup.header.orig_sending_time,
+        Some(original.header.sending_time)
+    );
+    assert!(poss_dup.header.sending_time >= original.header.sending_time);
+
+    assert_eq!(poss_resend.header.msg_seq_num, 3);
+    assert_eq!(poss_resend.header.poss_dup_flag, None);
+    assert_eq!(poss_resend.header.poss_resend, Some(true));
+    assert_eq!(
+        poss_resend.header.orig_sending_time,
+        Some(orig_sending_time)
+    );
+}
+
+#[test]
+fn resend_without_seq_num_refused() {
+    let (sent, refused) = run(vec![]);
+    assert_eq!(sent.len(), 1);
+    assert_eq!(refused, Some(SendErrorReason::MsgSeqNumMissing));
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use bytes::BytesMut;
+use common::scenario::{accept, acceptor, basic_msg, block_on, drain};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, FixString, MsgType},
+    messages::FixtMessage,
+};
+use easyfix_session::{io::iter_messages, session_id::SessionId};
+
+/// Acceptor with FIXT.1.1 and FIX.4.4 sessions of the same counterparty
+/// receives Logon<A> with given BeginString<8>, returns raw response.
+fn logon(begin_string: &FixStr) -> Vec<u8> {
+    let fixt_settings = common::session_settings();
+    let mut fix44_settings = common::session_settings();
+    fix44_settings.session_id = SessionId::new(
+        fix_str!("FIX.4.4").to_owned(),
+        fixt_settings.session_id.sender_comp_id().to_owned(),
+        fixt_settings.session_id.target_comp_id().to_owned(),
+    );
+    let mut msg = basic_msg(0);
+    msg.header.begin_string = begin_string.to_owned();
+
+    block_on(async move {
+        let mut acceptor = acceptor(fixt_settings);
+        acceptor.register_session(fix44_settings.session_id.clone(), fix44_settings);
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.send(&msg).await;
+        let response = connection.read().await;
+        connection.close().await;
+        response
+    })
+}
+
+fn parse(response: Vec<u8>, begin_strings: &[FixString]) -> Box<FixtMessage> {
+    let mut buffer = BytesMut::from(&response[..]);
+    iter_messages(&mut buffer)
+        .with_begin_strings(begin_strings)
+        .next()
+        .expect("no response")
+        .expect("invalid response")
+}
+
+#[test]
+fn fixt_logon() {
+    let response = logon(fix_str!("FIXT.1.1"));
+    assert!(response.starts_with(b"8=FIXT.1.1\x01"));
+    assert!(response.windows(6).any(|field| field == b"\x011137="));
+
+    let msg = parse(response, &[]);
+    assert_eq!(msg.header.msg_type, MsgType::Logon);
+}
+
+#[test]
+fn fix44_logon() {
+    let response = logon(fix_str!("FIX.4.4"));
+    assert!(response.starts_with(b"8=FIX.4.4\x01"));
+    // DefaultApplVerID<1137> is FIXT only
+    assert!(!response.windows(6).any(|field| field == b"\x011137="));
+
+    let msg = parse(response, &[fix_str!("FIX.4.4").to_owned()]);
+    assert_eq!(msg.header.msg_type, MsgType::Logon);
+    assert_eq!(msg.header.target_comp_id, "CLIENT");
+}
+
+#[test]
+fn unknown_version_logon() {
+    let response = logon(fix_str!("FIX.4.2"));
+    assert!(response.is_empty());
+}
+
+#[test]
+fn other_version_rejected_by_default() {
+    let response = logon(fix_str!("FIX.4.4"));
+    let mut buffer = BytesMut::from(&response[..]);
+    assert!(iter_messages(&mut buffer).next().unwrap().is_err());
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::scenario::{accept, acceptor_with, basic_msg, block_on, collect, drain};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, FixString, MsgType, MsgTypeCode, SessionRejectReason},
+    messages::{FixtMessage, Message, OtherMessage},
+};
+use easyfix_session::application::{SessionEvent, SessionEventKind};
+
+/// Log on and send message of type not defined in dictionary, return
+/// application messages received and messages sent after Logon<A> response.
+fn run(other_msg_types: bool) -> (Vec<Arc<FixtMessage>>, Vec<Arc<FixtMessage>>) {
+    let mut settings = common::settings();
+    settings.other_msg_types = other_msg_types;
+    let code = MsgTypeCode::from_bytes(b"U9").unwrap();
+    let mut venue_msg = basic_msg(0);
+    venue_msg.header.msg_type = MsgType::Other(code);
+    venue_msg.header.msg_seq_num = 2;
+    *venue_msg.body = Message::Other(OtherMessage {
+        msg_type: code,
+        fields: vec![(5001, FixString::from_ascii_lossy(b"OPEN".to_vec()))],
+    });
+
+    block_on(async move {
+        let acceptor = acceptor_with(settings, common::session_settings());
+        let mut subscriber = acceptor.events().subscribe().with_kinds(&[
+            SessionEventKind::AppMsgIn,
+            SessionEventKind::AppMsgOut,
+            SessionEventKind::AdmMsgOut,
+        ]);
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.send(&basic_msg(0)).await;
+        connection.send(&venue_msg).await;
+
+        let mut received = Vec::new();
+        let mut sent = Vec::new();
+        for event in collect(&mut subscriber).await {
+            match event {
+                SessionEvent::AppMsgIn(_, msg) => received.push(msg),
+                SessionEvent::AppMsgOut(_, msg) | SessionEvent::AdmMsgOut(_, msg)
+                    if msg.msg_type() != MsgType::Logon =>
+                {
+                    sent.push(msg)
+                }
+                _ => {}
+            }
+        }
+        connection.close().await;
+        (received, sent)
+    })
+}
+
+#[test]
+fn other_msg_type_delivered_to_application() {
+    let (received, sent) = run(true);
+    assert!(sent.is_empty(), "{sent:?}");
+    assert_eq!(received.len(), 1, "{received:?}");
+    assert_eq!(received[0].msg_type().as_bytes(), b"U9");
+    let Message::Other(ref msg) = *received[0].body else {
+        panic!("unexpected message: {:?}", received[0]);
+    };
+    assert_eq!(msg.field(5001), Some(fix_str!("OPEN")));
+}
+
+#[test]
+fn other_msg_type_rejected_by_default() {
+    let (received, sent) = run(false);
+    assert!(received.is_empty(), "{received:?}");
+    assert_eq!(sent.len(), 1, "{sent:?}");
+    let Message::Reject(ref reject) = *sent[0].body else {
+        panic!("unexpected message: {:?}", sent[0]);
+    };
+    assert_eq!(reject.ref_seq_num, 2);
+    assert_eq!(
+        reject.session_reject_reason,
+        Some(SessionRejectReason::InvalidMsgtype)
+    );
+}
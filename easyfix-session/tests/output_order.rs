@@ -0,0 +1,206 @@
+mod common;
+
+use std::{cell::RefCell, ops::RangeInclusive, rc::Rc};
+
+use common::scenario::{accept, basic_msg, block_on};
+use easyfix_macros::fix_str;
+use easyfix_messages::fields::{FixStr, MsgType, SeqNum};
+use easyfix_session::{
+    acceptor::Acceptor,
+    application::{AsEvent, FixEvent, StoreFailure},
+    messages_storage::{InMemoryStorage, MessagesStorage, StoreError},
+    settings::{OutputOrder, StoreFailurePolicy},
+    DisconnectReason,
+};
+use futures::StreamExt;
+use tokio::time::{timeout, Duration};
+
+/// In-memory storage recording stored sequence numbers, failing to store
+/// message with `fail_seq_num`.
+#[derive(Clone)]
+struct TestStorage {
+    inner: Rc<RefCell<InMemoryStorage>>,
+    stored: Rc<RefCell<Vec<SeqNum>>>,
+    fail_seq_num: Option<SeqNum>,
+}
+
+impl MessagesStorage for TestStorage {
+    fn fetch_range(&mut self, range: RangeInclusive<SeqNum>) -> Vec<Vec<u8>> {
+        self.inner.borrow_mut().fetch_range(range)
+    }
+
+    fn store(&mut self, seq_num: SeqNum, data: &[u8]) {
+        self.stored.borrow_mut().push(seq_num);
+        self.inner.borrow_mut().store(seq_num, data)
+    }
+
+    fn try_store(&mut self, seq_num: SeqNum, data: &[u8]) -> Result<(), StoreError> {
+        if self.fail_seq_num == Some(seq_num) {
+            return Err(StoreError::Other("disk full".to_owned()));
+        }
+        self.store(seq_num, data);
+        Ok(())
+    }
+
+    fn next_sender_msg_seq_num(&self) -> SeqNum {
+        self.inner.borrow().next_sender_msg_seq_num()
+    }
+
+    fn next_target_msg_seq_num(&self) -> SeqNum {
+        self.inner.borrow().next_target_msg_seq_num()
+    }
+
+    fn set_next_sender_msg_seq_num(&mut self, seq_num: SeqNum) {
+        self.inner.borrow_mut().set_next_sender_msg_seq_num(seq_num)
+    }
+
+    fn set_next_target_msg_seq_num(&mut self, seq_num: SeqNum) {
+        self.inner.borrow_mut().set_next_target_msg_seq_num(seq_num)
+    }
+
+    fn incr_next_sender_msg_seq_num(&mut self) {
+        self.inner.borrow_mut().incr_next_sender_msg_seq_num()
+    }
+
+    fn incr_next_target_msg_seq_num(&mut self) {
+        self.inner.borrow_mut().incr_next_target_msg_seq_num()
+    }
+
+    fn reset(&mut self) {
+        self.inner.borrow_mut().reset()
+    }
+}
+
+#[derive(Debug, Default)]
+struct Outcome {
+    /// Whether the message was stored when AdmMsgOut event was emitted,
+    /// by MsgSeqNum<34>
+    stored_at_event: Vec<(SeqNum, bool)>,
+    store_failures: Vec<StoreFailure>,
+    logout: Option<DisconnectReason>,
+    /// Message types written to the socket
+    written: Vec<MsgType>,
+}
+
+/// Log on and send TestRequest<1>, so Heartbeat<0> with MsgSeqNum<34> 2
+/// is sent in response.
+fn run(
+    output_order: OutputOrder,
+    store_failure: StoreFailurePolicy,
+    fail_seq_num: Option<SeqNum>,
+) -> Outcome {
+    let mut session_settings = common::session_settings();
+    session_settings.output_order = output_order;
+    session_settings.store_failure = store_failure;
+
+    block_on(async move {
+        let storage = TestStorage {
+            inner: Rc::new(RefCell::new(InMemoryStorage::new())),
+            stored: Rc::new(RefCell::new(Vec::new())),
+            fail_seq_num,
+        };
+        let stored = storage.stored.clone();
+        let mut acceptor = Acceptor::new(common::settings(), Box::new(move |_| storage.clone()));
+        acceptor.register_session(session_settings.session_id.clone(), session_settings);
+        let mut connection = accept(&acceptor);
+        let events = tokio::task::spawn_local(async move {
+            let mut outcome = Outcome::default();
+            let mut acceptor = Box::pin(acceptor);
+            while let Some(mut event) = acceptor.next().await {
+                match event.as_event() {
+                    FixEvent::AdmMsgOut(msg) => {
+                        let seq_num = msg.header.msg_seq_num;
+                        outcome
+                            .stored_at_event
+                            .push((seq_num, stored.borrow().contains(&seq_num)));
+                    }
+                    FixEvent::StoreFailed(_, failure) => {
+                        outcome.store_failures.push(failure.clone())
+                    }
+                    FixEvent::Logout(_, reason) => {
+                        outcome.logout = Some(reason);
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            outcome
+        });
+
+        connection.send(&basic_msg(0)).await;
+        connection.send(&basic_msg(2)).await;
+
+        let output = connection.read_messages().await;
+        connection.close().await;
+        let mut outcome = timeout(Duration::from_secs(1), events)
+            .await
+            .expect("events not finished")
+            .unwrap();
+        outcome.written = output.iter().map(|msg| msg.msg_type()).collect();
+        outcome
+    })
+}
+
+fn heartbeat_written(outcome: &Outcome) -> bool {
+    outcome.written.contains(&MsgType::Heartbeat)
+}
+
+#[test]
+fn emit_then_store() {
+    let outcome = run(OutputOrder::EmitThenStore, StoreFailurePolicy::Send, None);
+    assert_eq!(outcome.stored_at_event, [(1, false), (2, false)]);
+    assert!(heartbeat_written(&outcome));
+}
+
+#[test]
+fn store_then_emit() {
+    let outcome = run(OutputOrder::StoreThenEmit, StoreFailurePolicy::Send, None);
+    assert_eq!(outcome.stored_at_event, [(1, true), (2, true)]);
+    assert!(heartbeat_written(&outcome));
+}
+
+#[test]
+fn store_failure_send() {
+    let outcome = run(
+        OutputOrder::EmitThenStore,
+        StoreFailurePolicy::Send,
+        Some(2),
+    );
+    assert_eq!(
+        outcome.store_failures,
+        [StoreFailure {
+            msg_seq_num: 2,
+            msg_type: fix_str!("0").to_owned(),
+            error: "disk full".to_owned(),
+            policy: StoreFailurePolicy::Send,
+        }]
+    );
+    assert!(heartbeat_written(&outcome));
+    assert_eq!(outcome.logout, Some(DisconnectReason::Disconnected));
+}
+
+#[test]
+fn store_failure_drop() {
+    let outcome = run(
+        OutputOrder::StoreThenEmit,
+        StoreFailurePolicy::Drop,
+        Some(2),
+    );
+    assert_eq!(outcome.store_failures.len(), 1);
+    // Not stored message is not reported with AdmMsgOut
+    assert_eq!(outcome.stored_at_event, [(1, true)]);
+    assert!(!heartbeat_written(&outcome));
+    assert_eq!(outcome.logout, Some(DisconnectReason::Disconnected));
+}
+
+#[test]
+fn store_failure_disconnect() {
+    let outcome = run(
+        OutputOrder::EmitThenStore,
+        StoreFailurePolicy::Disconnect,
+        Some(2),
+    );
+    assert_eq!(outcome.store_failures.len(), 1);
+    assert!(!heartbeat_written(&outcome));
+    assert_eq!(outcome.logout, Some(DisconnectReason::StoreError));
+}
@@ -0,0 +1,171 @@
+mod common;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on, IDLE};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, FixString},
+    groups::LinesOfTextGrp,
+    messages::{FixtMessage, Message, News},
+};
+use easyfix_session::{
+    application::{AsEvent, DiscardReason, FixEvent},
+    new_header, new_trailer,
+    session_id::SessionId,
+    settings::SessionSettings,
+    PauseMode, Sender,
+};
+use futures::StreamExt;
+use tokio::time::{timeout, Duration};
+
+fn news(headline: &str) -> Box<FixtMessage> {
+    let body = Box::new(Message::News(News {
+        headline: FixString::from_ascii_lossy(headline.as_bytes().to_vec()),
+        lines_of_text_grp: vec![LinesOfTextGrp {
+            text: fix_str!("pause").to_owned(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }));
+    Box::new(FixtMessage {
+        header: Box::new(new_header(body.msg_type())),
+        body,
+        trailer: Box::new(new_trailer()),
+    })
+}
+
+fn headline(msg: &FixtMessage) -> Option<String> {
+    match *msg.body {
+        Message::News(ref news) => Some(news.headline.to_string()),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Default)]
+struct Outcome {
+    /// Headlines of News<B> messages sent to counterparty
+    sent: Vec<String>,
+    /// Headlines and reasons of discarded messages
+    discarded: Vec<(String, DiscardReason)>,
+}
+
+/// Log on and pause the session in `mode`, send three News<B> messages,
+/// then resume the session once it's idle and send one more.
+fn run(mode: PauseMode, paused_queue_limit: usize) -> Outcome {
+    run_with(mode, paused_queue_limit, &[])
+}
+
+/// See [`run`], News<B> messages with `before_resume` headlines are
+/// queued right before the session is resumed, so they are still waiting
+/// in the output queue when it's resumed.
+fn run_with(mode: PauseMode, paused_queue_limit: usize, before_resume: &'static [&str]) -> Outcome {
+    let mut session_settings = common::session_settings();
+    session_settings.paused_queue_limit = paused_queue_limit;
+
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut connection = accept(&acceptor);
+        let events = tokio::task::spawn_local(async move {
+            let mut discarded = Vec::new();
+            let mut paused: Option<(SessionId, Sender)> = None;
+            let mut acceptor = Box::pin(acceptor);
+            loop {
+                let mut entry = match timeout(IDLE / 2, acceptor.next()).await {
+                    Ok(Some(entry)) => entry,
+                    Ok(None) => break,
+                    // Messages sent while paused are processed by now
+                    Err(_) => {
+                        if let Some((session_id, sender)) = paused.take() {
+                            for headline in before_resume {
+                                sender.send_raw(news(headline)).unwrap();
+                            }
+                            acceptor.resume(&session_id);
+                            sender.send_raw(news("resumed")).unwrap();
+                        }
+                        continue;
+                    }
+                };
+                match entry.as_event() {
+                    FixEvent::Logon(session_id, sender) => {
+                        acceptor.pause(session_id, mode);
+                        for headline in ["first", "second", "third"] {
+                            sender.send_raw(news(headline)).unwrap();
+                        }
+                        paused = Some((session_id.clone(), sender));
+                    }
+                    FixEvent::AppMsgDiscarded(_, msg) => {
+                        discarded.push((headline(&msg.msg).unwrap(), msg.reason));
+                    }
+                    FixEvent::Logout(..) => break,
+                    _ => {}
+                }
+            }
+            discarded
+        });
+
+        connection.send(&basic_msg(0)).await;
+        let sent = connection
+            .read_messages()
+            .await
+            .iter()
+            .filter_map(headline)
+            .collect();
+        connection.close().await;
+        let discarded = timeout(Duration::from_secs(1), events)
+            .await
+            .expect("events not finished")
+            .unwrap();
+
+        Outcome { sent, discarded }
+    })
+}
+
+#[test]
+fn buffered_until_resumed() {
+    let outcome = run(
+        PauseMode::Buffer,
+        SessionSettings::DEFAULT_PAUSED_QUEUE_LIMIT,
+    );
+    assert_eq!(outcome.sent, ["first", "second", "third", "resumed"]);
+    assert_eq!(outcome.discarded, []);
+}
+
+#[test]
+fn buffered_sent_before_queued_on_resume() {
+    let outcome = run_with(
+        PauseMode::Buffer,
+        SessionSettings::DEFAULT_PAUSED_QUEUE_LIMIT,
+        &["queued"],
+    );
+    assert_eq!(
+        outcome.sent,
+        ["first", "second", "third", "queued", "resumed"]
+    );
+    assert_eq!(outcome.discarded, []);
+}
+
+#[test]
+fn buffer_overflow_discarded() {
+    let outcome = run(PauseMode::Buffer, 2);
+    assert_eq!(outcome.sent, ["first", "second", "resumed"]);
+    assert_eq!(
+        outcome.discarded,
+        [("third".to_owned(), DiscardReason::PausedQueueFull)]
+    );
+}
+
+#[test]
+fn rejected_while_paused() {
+    let outcome = run(
+        PauseMode::Reject,
+        SessionSettings::DEFAULT_PAUSED_QUEUE_LIMIT,
+    );
+    assert_eq!(outcome.sent, ["resumed"]);
+    assert_eq!(
+        outcome.discarded,
+        [
+            ("first".to_owned(), DiscardReason::Paused),
+            ("second".to_owned(), DiscardReason::Paused),
+            ("third".to_owned(), DiscardReason::Paused),
+        ]
+    );
+}
@@ -0,0 +1,240 @@
+mod common;
+
+use std::{
+    cell::Cell,
+    io::{self, Cursor, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use common::scenario::{accept, block_on, Step::*, LOGON, LOGON_RESPONSE};
+use easyfix_session::{
+    acceptor::Acceptor,
+    application::{AsEvent, FixEvent},
+    messages_storage::{InMemoryStorage, MessagesStorage, PersistentSeqNums, SeqNumsFile},
+    settings::StoreFailurePolicy,
+    DisconnectReason,
+};
+use futures::StreamExt;
+use tokio::time::{timeout, Duration};
+
+fn seq_nums_file(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("easyfix-seq-nums-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Open storage as after restart, with nothing but persisted file.
+fn reopen(path: &Path) -> PersistentSeqNums<InMemoryStorage> {
+    PersistentSeqNums::open(path, InMemoryStorage::new()).unwrap()
+}
+
+#[test]
+fn sequence_numbers_survive_crash() {
+    let path = seq_nums_file("crash");
+    let mut storage = reopen(&path).with_block_size(1);
+    for _ in 0..3 {
+        storage.incr_next_sender_msg_seq_num();
+    }
+    storage.incr_next_target_msg_seq_num();
+    // Dropped without any shutdown, like crashed process
+    drop(storage);
+
+    let storage = reopen(&path);
+    assert_eq!(storage.next_sender_msg_seq_num(), 4);
+    assert_eq!(storage.next_target_msg_seq_num(), 2);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn sender_sequence_numbers_allocated_in_blocks() {
+    let path = seq_nums_file("blocks");
+    let mut storage = reopen(&path).with_block_size(10);
+    for _ in 0..2 {
+        storage.incr_next_sender_msg_seq_num();
+    }
+    assert_eq!(storage.next_sender_msg_seq_num(), 3);
+    drop(storage);
+
+    // Numbers of allocated block are skipped, never reused
+    let mut storage = reopen(&path).with_block_size(10);
+    assert_eq!(storage.next_sender_msg_seq_num(), 11);
+    storage.incr_next_sender_msg_seq_num();
+    drop(storage);
+    assert_eq!(reopen(&path).next_sender_msg_seq_num(), 21);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn default_block_size() {
+    let path = seq_nums_file("default");
+    let mut storage = reopen(&path);
+    for _ in 0..3 {
+        storage.incr_next_sender_msg_seq_num();
+    }
+    drop(storage);
+
+    assert_eq!(
+        reopen(&path).next_sender_msg_seq_num(),
+        1 + PersistentSeqNums::<InMemoryStorage>::DEFAULT_BLOCK_SIZE
+    );
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn reset_persisted() {
+    let path = seq_nums_file("reset");
+    let mut storage = reopen(&path).with_block_size(1);
+    storage.incr_next_sender_msg_seq_num();
+    storage.set_next_target_msg_seq_num(5);
+    drop(storage);
+
+    let mut storage = reopen(&path);
+    assert_eq!(storage.next_sender_msg_seq_num(), 2);
+    assert_eq!(storage.next_target_msg_seq_num(), 5);
+    storage.reset();
+    drop(storage);
+
+    let storage = reopen(&path);
+    assert_eq!(storage.next_sender_msg_seq_num(), 1);
+    assert_eq!(storage.next_target_msg_seq_num(), 1);
+    let _ = std::fs::remove_file(path);
+}
+
+/// In-memory file, data written since the last successful sync is lost
+/// on crash, write and sync fail while `failing` is set.
+#[derive(Clone, Default)]
+struct FlakyFile {
+    data: Cursor<Vec<u8>>,
+    synced: Rc<Cell<Option<[u8; 8]>>>,
+    failing: Rc<Cell<bool>>,
+}
+
+impl FlakyFile {
+    /// File as seen after crash, with synced data only.
+    fn crash(&self) -> FlakyFile {
+        let data = self.synced.get().map(Vec::from).unwrap_or_default();
+        FlakyFile {
+            data: Cursor::new(data),
+            ..Default::default()
+        }
+    }
+}
+
+impl Read for FlakyFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.data.read(buf)
+    }
+}
+
+impl Write for FlakyFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.failing.get() {
+            return Err(io::Error::other("write failed"));
+        }
+        self.data.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for FlakyFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.data.seek(pos)
+    }
+}
+
+impl SeqNumsFile for FlakyFile {
+    fn sync(&mut self) -> io::Result<()> {
+        if self.failing.get() {
+            return Err(io::Error::other("sync failed"));
+        }
+        self.synced
+            .set(Some(self.data.get_ref()[..8].try_into().unwrap()));
+        Ok(())
+    }
+}
+
+#[test]
+fn failed_sync_fails_store_until_persisted() {
+    let file = FlakyFile::default();
+    let mut storage = PersistentSeqNums::with_file(file.clone(), InMemoryStorage::new())
+        .unwrap()
+        .with_block_size(2);
+    storage.incr_next_sender_msg_seq_num();
+    storage.try_store(1, b"1").unwrap();
+
+    file.failing.set(true);
+    // Number from already persisted block
+    storage.incr_next_sender_msg_seq_num();
+    storage.try_store(2, b"2").unwrap();
+    // Numbers above persisted limit, every store retries and fails
+    for seq_num in 3..=4 {
+        storage.incr_next_sender_msg_seq_num();
+        assert!(storage.try_store(seq_num, b"n").is_err());
+    }
+
+    let crashed = PersistentSeqNums::with_file(file.crash(), InMemoryStorage::new()).unwrap();
+    assert_eq!(crashed.next_sender_msg_seq_num(), 3);
+
+    file.failing.set(false);
+    storage.incr_next_sender_msg_seq_num();
+    storage.try_store(5, b"5").unwrap();
+    let crashed = PersistentSeqNums::with_file(file.crash(), InMemoryStorage::new()).unwrap();
+    assert_eq!(crashed.next_sender_msg_seq_num(), 7);
+}
+
+#[test]
+fn message_not_sent_when_seq_num_not_persisted() {
+    let file = FlakyFile::default();
+    let session_settings = common::session_settings();
+    // Default policy sends messages which failed to store
+    assert_eq!(session_settings.store_failure, StoreFailurePolicy::Send);
+
+    block_on(async {
+        let mut acceptor = Acceptor::new(
+            common::settings(),
+            Box::new({
+                let file = file.clone();
+                move |_| {
+                    PersistentSeqNums::with_file(file.clone(), InMemoryStorage::new())
+                        .unwrap()
+                        .with_block_size(1)
+                }
+            }),
+        );
+        acceptor.register_session(session_settings.session_id.clone(), session_settings);
+        let mut connection = accept(&acceptor);
+        let events = tokio::task::spawn_local(async move {
+            let mut failures = Vec::new();
+            let mut acceptor = Box::pin(acceptor);
+            while let Some(mut event) = acceptor.next().await {
+                match event.as_event() {
+                    FixEvent::StoreFailed(_, failure) => failures.push(failure.policy),
+                    FixEvent::Logout(_, reason) => return (failures, reason),
+                    _ => {}
+                }
+            }
+            unreachable!("events finished before Logout");
+        });
+
+        connection.play(&[In(LOGON), Out(LOGON_RESPONSE)]).await;
+        file.failing.set(true);
+        // Heartbeat<0> response would take MsgSeqNum<34> 2, above persisted
+        // limit, nothing is sent
+        connection.play(&[In("35=1|34=2|112=T")]).await;
+        let (failures, reason) = timeout(Duration::from_secs(1), events)
+            .await
+            .expect("session not disconnected")
+            .unwrap();
+        connection.close().await;
+
+        assert_eq!(failures, [StoreFailurePolicy::Disconnect]);
+        assert_eq!(reason, DisconnectReason::StoreError);
+    });
+
+    let crashed = PersistentSeqNums::with_file(file.crash(), InMemoryStorage::new()).unwrap();
+    assert_eq!(crashed.next_sender_msg_seq_num(), 2);
+}
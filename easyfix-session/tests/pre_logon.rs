@@ -0,0 +1,309 @@
+mod common;
+
+use common::scenario::{
+    accept, acceptor_with, basic_msg, block_on, collect, drain, Step::*, IDLE, LOGON,
+    LOGON_RESPONSE,
+};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, MsgType, SessionRejectReason},
+    messages::{FixtMessage, Heartbeat, Message},
+};
+use easyfix_session::{
+    acceptor::metrics::{ConnectionEvent, LogonFailure},
+    application::{SessionEvent, SessionEventKind},
+    settings::{PreLogonAction, PreLogonPolicy, Settings},
+};
+use futures::StreamExt;
+use tokio::{
+    io::AsyncWriteExt,
+    time::{sleep, timeout, Duration},
+};
+
+/// Send `data` to acceptor without completing the first message, then
+/// close connection if `close` is set or wait until acceptor drops it.
+/// Returns reason of logon failure.
+fn connect(settings: Settings, data: &'static [u8], close: bool) -> LogonFailure {
+    block_on(async move {
+        let acceptor = acceptor_with(settings, common::session_settings());
+        let mut connection_events = acceptor.connection_events();
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.send_raw(data).await;
+        if close {
+            connection.close().await;
+        } else {
+            connection.finish(Duration::from_secs(2)).await;
+        }
+
+        loop {
+            match timeout(IDLE, connection_events.next()).await {
+                Ok(Some(ConnectionEvent::LogonFailed { reason, .. })) => break reason,
+                Ok(Some(_)) => {}
+                _ => panic!("logon failure not reported"),
+            }
+        }
+    })
+}
+
+#[test]
+fn logon_timeout() {
+    let mut settings = common::settings();
+    settings.auto_disconnect_after_no_logon_received = Duration::from_millis(100);
+    assert_eq!(
+        connect(settings, b"8=FIXT.1.1\x019=", false),
+        LogonFailure::Timeout
+    );
+}
+
+#[test]
+fn closed_before_logon() {
+    assert_eq!(
+        connect(common::settings(), b"8=FIXT.1.1\x019=", true),
+        LogonFailure::Closed
+    );
+}
+
+#[test]
+fn too_many_bytes_before_logon() {
+    let mut settings = common::settings();
+    settings.max_bytes_before_logon = Some(16);
+    // Incomplete message with huge BodyLength<9>
+    assert_eq!(
+        connect(
+            settings,
+            b"8=FIXT.1.1\x019=60000\x0135=A\x0134=1\x01",
+            false
+        ),
+        LogonFailure::BufferLimitExceeded(16)
+    );
+}
+
+/// Complete message with invalid CheckSum<10>.
+const GARBLED: &[u8] = b"8=FIXT.1.1\x019=5\x0135=0\x0110=000\x01";
+
+fn heartbeat() -> FixtMessage {
+    let mut msg = basic_msg(0);
+    msg.header.msg_type = MsgType::Heartbeat;
+    *msg.body = Message::Heartbeat(Heartbeat { test_req_id: None });
+    msg
+}
+
+/// Send `first` and then Logon<A> to acceptor with `policy`, returns
+/// connection events and messages sent by acceptor.
+fn run(policy: PreLogonPolicy, first: Vec<u8>) -> (Vec<ConnectionEvent>, Vec<FixtMessage>) {
+    let mut settings = common::settings();
+    settings.pre_logon = policy;
+
+    block_on(async move {
+        let acceptor = acceptor_with(settings, common::session_settings());
+        let mut connection_events = acceptor.connection_events();
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.send_raw(&first).await;
+        // Written separately, so the first message is processed alone
+        sleep(Duration::from_millis(50)).await;
+        // Connection is already closed when first message disconnects
+        let _ = connection.remote.write_all(&basic_msg(0).serialize()).await;
+        let sent = connection.read_messages().await;
+        connection.close().await;
+
+        let mut received = Vec::new();
+        while let Ok(Some(event)) = timeout(IDLE, connection_events.next()).await {
+            received.push(event);
+        }
+        (received, sent)
+    })
+}
+
+/// Actions reported with `PreLogonMessage` events.
+fn pre_logon_actions(events: &[ConnectionEvent]) -> Vec<(Option<MsgType>, PreLogonAction)> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            ConnectionEvent::PreLogonMessage {
+                msg_type, action, ..
+            } => Some((*msg_type, *action)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn logged_on(events: &[ConnectionEvent]) -> bool {
+    events
+        .iter()
+        .any(|event| matches!(event, ConnectionEvent::LogonSucceeded { .. }))
+}
+
+#[test]
+fn garbled_message_disconnects_by_default() {
+    let (events, sent) = run(PreLogonPolicy::default(), GARBLED.to_vec());
+    assert_eq!(
+        pre_logon_actions(&events),
+        [(None, PreLogonAction::Disconnect)]
+    );
+    assert!(
+        events.iter().any(|event| matches!(
+            event,
+            ConnectionEvent::LogonFailed {
+                reason: LogonFailure::InvalidMessage,
+                ..
+            }
+        )),
+        "{events:?}"
+    );
+    assert!(!logged_on(&events));
+    assert!(sent.is_empty());
+}
+
+#[test]
+fn garbled_message_ignored() {
+    for action in [PreLogonAction::Ignore, PreLogonAction::Reject] {
+        let policy = PreLogonPolicy {
+            garbled: action,
+            ..Default::default()
+        };
+        let (events, sent) = run(policy, GARBLED.to_vec());
+        // Reject<3> can't reference garbled message. Garbled data may be
+        // skipped in more than one step, each one is reported
+        let actions = pre_logon_actions(&events);
+        assert!(!actions.is_empty());
+        assert!(
+            actions
+                .iter()
+                .all(|action| *action == (None, PreLogonAction::Ignore)),
+            "{actions:?}"
+        );
+        assert!(logged_on(&events), "{events:?}");
+        assert_eq!(sent[0].header.msg_type, MsgType::Logon);
+    }
+}
+
+#[test]
+fn not_logon_ignored() {
+    let policy = PreLogonPolicy {
+        not_logon: PreLogonAction::Ignore,
+        ..Default::default()
+    };
+    let (events, sent) = run(policy, heartbeat().serialize());
+    assert_eq!(
+        pre_logon_actions(&events),
+        [(Some(MsgType::Heartbeat), PreLogonAction::Ignore)]
+    );
+    assert!(logged_on(&events), "{events:?}");
+    assert_eq!(sent[0].header.msg_type, MsgType::Logon);
+    assert_eq!(sent[0].header.msg_seq_num, 1);
+}
+
+#[test]
+fn not_logon_rejected() {
+    let policy = PreLogonPolicy {
+        not_logon: PreLogonAction::Reject,
+        ..Default::default()
+    };
+    let (events, sent) = run(policy, heartbeat().serialize());
+    assert_eq!(
+        pre_logon_actions(&events),
+        [(Some(MsgType::Heartbeat), PreLogonAction::Reject)]
+    );
+    assert!(logged_on(&events), "{events:?}");
+
+    let Message::Reject(ref reject) = *sent[0].body else {
+        panic!("Reject<3> not sent: {:?}", sent[0]);
+    };
+    assert_eq!(sent[0].header.msg_seq_num, 1);
+    assert_eq!(sent[0].header.sender_comp_id, "SERVER");
+    assert_eq!(sent[0].header.target_comp_id, "CLIENT");
+    assert_eq!(reject.ref_seq_num, 1);
+    assert_eq!(reject.ref_msg_type.as_deref(), Some(fix_str!("0")));
+    assert_eq!(
+        reject.session_reject_reason,
+        Some(SessionRejectReason::Other)
+    );
+    // Reject<3> consumed MsgSeqNum<34>
+    assert_eq!(sent[1].header.msg_type, MsgType::Logon);
+    assert_eq!(sent[1].header.msg_seq_num, 2);
+}
+
+#[test]
+fn pre_logon_reject_emitted_by_session() {
+    let mut settings = common::settings();
+    settings.pre_logon.not_logon = PreLogonAction::Reject;
+
+    block_on(async move {
+        let acceptor = acceptor_with(settings, common::session_settings());
+        let mut subscriber = acceptor
+            .events()
+            .subscribe()
+            .with_kinds(&[SessionEventKind::AdmMsgOut]);
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.send(&heartbeat()).await;
+        let sent = connection.read_messages().await;
+        let events = collect(&mut subscriber).await;
+        connection.close().await;
+
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].header.msg_type, MsgType::Reject);
+        let [SessionEvent::AdmMsgOut(_, msg)] = &events[..] else {
+            panic!("unexpected events: {events:?}");
+        };
+        assert_eq!(msg.header.msg_type, MsgType::Reject);
+        assert_eq!(msg.header.msg_seq_num, 1);
+    });
+}
+
+#[test]
+fn not_logon_of_connected_session_ignored_instead_of_rejected() {
+    let mut settings = common::settings();
+    settings.pre_logon.not_logon = PreLogonAction::Reject;
+
+    block_on(async move {
+        let acceptor = acceptor_with(settings, common::session_settings());
+        let mut connection_events = acceptor.connection_events();
+        let mut logged_on = accept(&acceptor);
+        let mut intruder = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        logged_on.play(&[In(LOGON), Out(LOGON_RESPONSE)]).await;
+        // Claims CompIDs of the session logged on above
+        intruder.send(&heartbeat()).await;
+        let intruder_sent = intruder.read_messages().await;
+        // No gap in MsgSeqNum<34> sent to connected counterparty
+        logged_on
+            .play(&[In("35=1|34=2|112=T"), Out("35=0|34=2|112=T")])
+            .await;
+        intruder.close().await;
+        logged_on.close().await;
+
+        let mut events = Vec::new();
+        while let Ok(Some(event)) = timeout(IDLE, connection_events.next()).await {
+            events.push(event);
+        }
+        assert_eq!(
+            pre_logon_actions(&events),
+            [(Some(MsgType::Heartbeat), PreLogonAction::Ignore)]
+        );
+        assert!(intruder_sent.is_empty(), "{intruder_sent:?}");
+    });
+}
+
+#[test]
+fn not_logon_of_unknown_session_ignored_instead_of_rejected() {
+    let policy = PreLogonPolicy {
+        not_logon: PreLogonAction::Reject,
+        ..Default::default()
+    };
+    let mut msg = heartbeat();
+    msg.header.sender_comp_id = fix_str!("UNKNOWN").to_owned();
+    let (events, sent) = run(policy, msg.serialize());
+    assert_eq!(
+        pre_logon_actions(&events),
+        [(Some(MsgType::Heartbeat), PreLogonAction::Ignore)]
+    );
+    assert!(logged_on(&events), "{events:?}");
+    assert_eq!(sent[0].header.msg_type, MsgType::Logon);
+}
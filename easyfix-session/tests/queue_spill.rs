@@ -0,0 +1,144 @@
+mod common;
+
+use std::{cell::RefCell, ops::RangeInclusive, rc::Rc};
+
+use common::scenario::{accept, basic_msg, block_on, collect, drain};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, FixString, MsgType, SeqNum},
+    groups::LinesOfTextGrp,
+    messages::{FixtMessage, Message, News},
+};
+use easyfix_session::{
+    acceptor::Acceptor,
+    application::{SessionEvent, SessionEventKind},
+    messages_storage::{InMemoryStorage, MessagesStorage, StoreError},
+};
+
+/// In-memory storage recording sequence numbers of spilled messages.
+#[derive(Clone, Default)]
+struct TestStorage {
+    inner: Rc<RefCell<InMemoryStorage>>,
+    spilled: Rc<RefCell<Vec<SeqNum>>>,
+}
+
+impl MessagesStorage for TestStorage {
+    fn fetch_range(&mut self, range: RangeInclusive<SeqNum>) -> Vec<Vec<u8>> {
+        self.inner.borrow_mut().fetch_range(range)
+    }
+
+    fn store(&mut self, seq_num: SeqNum, data: &[u8]) {
+        self.inner.borrow_mut().store(seq_num, data)
+    }
+
+    fn store_queued(&mut self, seq_num: SeqNum, data: &[u8]) -> Result<(), StoreError> {
+        self.spilled.borrow_mut().push(seq_num);
+        self.inner.borrow_mut().store_queued(seq_num, data)
+    }
+
+    fn take_queued(&mut self, seq_num: SeqNum) -> Option<Vec<u8>> {
+        self.inner.borrow_mut().take_queued(seq_num)
+    }
+
+    fn clear_queued(&mut self) {
+        self.inner.borrow_mut().clear_queued()
+    }
+
+    fn next_sender_msg_seq_num(&self) -> SeqNum {
+        self.inner.borrow().next_sender_msg_seq_num()
+    }
+
+    fn next_target_msg_seq_num(&self) -> SeqNum {
+        self.inner.borrow().next_target_msg_seq_num()
+    }
+
+    fn set_next_sender_msg_seq_num(&mut self, seq_num: SeqNum) {
+        self.inner.borrow_mut().set_next_sender_msg_seq_num(seq_num)
+    }
+
+    fn set_next_target_msg_seq_num(&mut self, seq_num: SeqNum) {
+        self.inner.borrow_mut().set_next_target_msg_seq_num(seq_num)
+    }
+
+    fn incr_next_sender_msg_seq_num(&mut self) {
+        self.inner.borrow_mut().incr_next_sender_msg_seq_num()
+    }
+
+    fn incr_next_target_msg_seq_num(&mut self) {
+        self.inner.borrow_mut().incr_next_target_msg_seq_num()
+    }
+
+    fn reset(&mut self) {
+        self.inner.borrow_mut().reset()
+    }
+}
+
+fn headline(msg: &FixtMessage) -> String {
+    let Message::News(ref news) = *msg.body else {
+        panic!("unexpected message: {msg:?}");
+    };
+    news.headline.to_string()
+}
+
+/// Log on, send News<B> messages with MsgSeqNum<34> 3 and 4 before 2,
+/// return headlines of received messages and spilled sequence numbers.
+fn run(queue_spill_threshold: Option<usize>) -> (Vec<String>, Vec<SeqNum>) {
+    let mut session_settings = common::session_settings();
+    session_settings.queue_spill_threshold = queue_spill_threshold;
+
+    block_on(async move {
+        let storage = TestStorage::default();
+        let builder_storage = storage.clone();
+        let mut acceptor = Acceptor::new(
+            common::settings(),
+            Box::new(move |_| builder_storage.clone()),
+        );
+        acceptor.register_session(session_settings.session_id.clone(), session_settings);
+        let mut subscriber = acceptor
+            .events()
+            .subscribe()
+            .with_kinds(&[SessionEventKind::AppMsgIn]);
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.send(&basic_msg(0)).await;
+        for seq_num in [3, 4, 2] {
+            let mut news = basic_msg(0);
+            news.header.msg_type = MsgType::News;
+            news.header.msg_seq_num = seq_num;
+            *news.body = Message::News(News {
+                headline: FixString::from_ascii_lossy(format!("news {seq_num}").into_bytes()),
+                lines_of_text_grp: vec![LinesOfTextGrp {
+                    text: fix_str!("spill").to_owned(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            });
+            connection.send(&news).await;
+        }
+
+        let mut received = Vec::new();
+        for event in collect(&mut subscriber).await {
+            if let SessionEvent::AppMsgIn(_, msg) = event {
+                received.push(headline(&msg));
+            }
+        }
+        connection.close().await;
+        let spilled = storage.spilled.borrow().clone();
+        (received, spilled)
+    })
+}
+
+#[test]
+fn queued_messages_kept_in_memory_by_default() {
+    let (received, spilled) = run(None);
+    assert_eq!(received, ["news 2", "news 3", "news 4"]);
+    assert!(spilled.is_empty(), "{spilled:?}");
+}
+
+#[test]
+fn queued_messages_spilled_above_threshold() {
+    let (received, spilled) = run(Some(1));
+    assert_eq!(received, ["news 2", "news 3", "news 4"]);
+    assert_eq!(spilled, [4]);
+}
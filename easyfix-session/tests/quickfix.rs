@@ -0,0 +1,224 @@
+use std::{path::PathBuf, time::Duration};
+
+use chrono::{NaiveDate, NaiveTime};
+use easyfix_macros::fix_str;
+use easyfix_messages::fields::FixStr;
+use easyfix_session::{
+    messages_storage::{InMemoryStorage, MessagesStorage},
+    quickfix::{
+        ConnectionType, QuickFixConfig, QuickFixConfigError, QuickFixFileStore, QuickFixStoreError,
+    },
+    session_id::SessionId,
+    settings::{MsgSeqNumTooLowAction, ResendRequestEndSeqNo},
+};
+
+const CONFIG: &str = "
+# Settings shared by all sessions
+[DEFAULT]
+ConnectionType=acceptor
+SenderCompID=SERVER
+StartTime=08:00:00
+EndTime=17:30:00
+StartDay=Mon
+EndDay=Fri
+SocketAcceptPort=10000
+FileStorePath=store
+DataDictionary=FIX44.xml
+ValidateUserDefinedFields=N
+
+[SESSION]
+BeginString=FIXT.1.1
+TargetCompID=CLIENT
+DefaultApplVerID=FIX.5.0SP2
+ResetOnLogon=Y
+CheckLatency=N
+ResendRequestChunkSize=500
+
+[SESSION]
+ConnectionType=initiator
+BeginString=FIX.4.4
+SenderCompID=CLIENT
+TargetCompID=SERVER
+SessionQualifier=backup
+HeartBtInt=15
+LogonTimeout=5
+SocketConnectHost=10.0.0.1
+SocketConnectPort=10001
+LogonTime=08:30:00
+LogoutTime=17:00:00
+Weekdays=Mo,We,Fr
+";
+
+#[test]
+fn sessions_translated() {
+    let config = QuickFixConfig::parse(CONFIG).unwrap();
+    assert_eq!(config.sessions.len(), 2);
+
+    let acceptor = &config.sessions[0];
+    assert_eq!(acceptor.connection_type, ConnectionType::Acceptor);
+    assert_eq!(acceptor.socket_accept, Some(("0.0.0.0".to_owned(), 10000)));
+    assert_eq!(acceptor.socket_connect, None);
+    assert_eq!(acceptor.file_store_path, Some(PathBuf::from("store")));
+    assert_eq!(acceptor.data_dictionaries, [PathBuf::from("FIX44.xml")]);
+    assert_eq!(acceptor.ignored, ["ValidateUserDefinedFields"]);
+    assert_eq!(acceptor.settings.sender_comp_id, fix_str!("SERVER"));
+    assert_eq!(
+        acceptor.settings.auto_disconnect_after_no_logon_received,
+        Duration::from_secs(10)
+    );
+
+    let session_settings = &acceptor.session_settings;
+    assert_eq!(
+        session_settings.session_id.target_comp_id(),
+        fix_str!("CLIENT")
+    );
+    assert_eq!(
+        session_settings.session_time,
+        NaiveTime::from_hms_opt(8, 0, 0).unwrap()..=NaiveTime::from_hms_opt(17, 30, 0).unwrap()
+    );
+    assert_eq!(session_settings.logon_time, session_settings.session_time);
+    // 2024-01-05 is Friday
+    assert!(session_settings
+        .calendar
+        .is_business_day(NaiveDate::from_ymd_opt(2024, 1, 5).unwrap()));
+    assert!(!session_settings
+        .calendar
+        .is_business_day(NaiveDate::from_ymd_opt(2024, 1, 6).unwrap()));
+    assert_eq!(session_settings.sender_default_appl_ver_id, fix_str!("9"));
+    assert!(session_settings.reset_on_logon);
+    assert!(!session_settings.check_latency);
+    assert!(session_settings.persist);
+    assert_eq!(session_settings.resend_request.max_range, Some(500));
+    assert_eq!(
+        session_settings.resend_request.end_seq_no,
+        ResendRequestEndSeqNo::Infinity
+    );
+    assert!(matches!(
+        session_settings.msg_seq_num_too_low,
+        MsgSeqNumTooLowAction::Logout { text: Some(ref text), disconnect: true }
+            if text.starts_with("MsgSeqNum too low")
+    ));
+
+    let initiator = &config.sessions[1];
+    assert_eq!(initiator.connection_type, ConnectionType::Initiator);
+    assert_eq!(
+        initiator.socket_connect,
+        Some(("10.0.0.1".to_owned(), 10001))
+    );
+    assert_eq!(
+        initiator.settings.heartbeat_interval,
+        Duration::from_secs(15)
+    );
+    assert_eq!(
+        initiator.settings.auto_disconnect_after_no_logon_received,
+        Duration::from_secs(5)
+    );
+    let session_settings = &initiator.session_settings;
+    assert_eq!(session_settings.session_id.session_qualifier(), "backup");
+    assert_eq!(
+        session_settings.logon_time,
+        NaiveTime::from_hms_opt(8, 30, 0).unwrap()..=NaiveTime::from_hms_opt(17, 0, 0).unwrap()
+    );
+    // 2024-01-03 is Wednesday
+    assert!(session_settings
+        .calendar
+        .is_business_day(NaiveDate::from_ymd_opt(2024, 1, 3).unwrap()));
+    assert!(!session_settings
+        .calendar
+        .is_business_day(NaiveDate::from_ymd_opt(2024, 1, 4).unwrap()));
+}
+
+#[test]
+fn invalid_config_rejected() {
+    assert!(matches!(
+        QuickFixConfig::parse("SenderCompID=SERVER"),
+        Err(QuickFixConfigError::Syntax { line: 1, .. })
+    ));
+    assert!(matches!(
+        QuickFixConfig::parse(&CONFIG.replace("BeginString=FIX.4.4", "")),
+        Err(QuickFixConfigError::MissingKey {
+            session: 2,
+            key: "BeginString"
+        })
+    ));
+    assert!(matches!(
+        QuickFixConfig::parse(&CONFIG.replace("ResetOnLogon=Y", "ResetOnLogon=yes")),
+        Err(QuickFixConfigError::InvalidValue {
+            session: 1,
+            key: "ResetOnLogon",
+            ..
+        })
+    ));
+    assert!(matches!(
+        QuickFixConfig::parse(&format!("{CONFIG}TimeZone=Europe/Warsaw\n")),
+        Err(QuickFixConfigError::Unsupported {
+            session: 2,
+            key: "TimeZone",
+            ..
+        })
+    ));
+}
+
+const MSG_1: &[u8] = b"8=FIX.4.4\x019=5\x0135=0\x0110=163\x01";
+const MSG_2: &[u8] = b"8=FIX.4.4\x019=5\x0135=1\x0110=164\x01";
+
+fn file_store_dir(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("easyfix-quickfix-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    std::fs::create_dir(&path).unwrap();
+    path
+}
+
+fn session_id() -> SessionId {
+    SessionId::with_session_qualifier(
+        fix_str!("FIX.4.4").to_owned(),
+        fix_str!("SERVER").to_owned(),
+        fix_str!("CLIENT").to_owned(),
+        "backup".to_owned(),
+    )
+}
+
+#[test]
+fn file_store_imported() {
+    let dir = file_store_dir("import");
+    let file = |extension: &str| dir.join(format!("FIX.4.4-SERVER-CLIENT-backup.{extension}"));
+    let body = [MSG_1, MSG_2].concat();
+    std::fs::write(file("body"), body).unwrap();
+    std::fs::write(
+        file("header"),
+        format!("1,0,{} 2,{},{} ", MSG_1.len(), MSG_1.len(), MSG_2.len()),
+    )
+    .unwrap();
+    std::fs::write(file("seqnums"), "0000000003 : 0000000007").unwrap();
+    std::fs::write(file("session"), "20240315-08:00:00").unwrap();
+
+    let file_store = QuickFixFileStore::read(&dir, &session_id()).unwrap();
+    assert_eq!(file_store.next_sender_msg_seq_num, 3);
+    assert_eq!(file_store.next_target_msg_seq_num, 7);
+    assert_eq!(
+        file_store.creation_time,
+        NaiveDate::from_ymd_opt(2024, 3, 15)
+            .unwrap()
+            .and_hms_opt(8, 0, 0)
+    );
+
+    let mut storage = InMemoryStorage::new();
+    file_store.import_into(&mut storage).unwrap();
+    assert_eq!(storage.next_sender_msg_seq_num(), 3);
+    assert_eq!(storage.next_target_msg_seq_num(), 7);
+    assert_eq!(storage.fetch_range(1..=2), [MSG_1, MSG_2]);
+
+    // Message offset past the end of body
+    std::fs::write(file("header"), "1,0,1000 ").unwrap();
+    assert!(matches!(
+        QuickFixFileStore::read(&dir, &session_id()),
+        Err(QuickFixStoreError::Invalid { path, .. }) if path == file("body")
+    ));
+
+    std::fs::remove_file(file("seqnums")).unwrap();
+    assert!(matches!(
+        QuickFixFileStore::read(&dir, &session_id()),
+        Err(QuickFixStoreError::Io { .. })
+    ));
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use std::time::Duration;
+
+use common::scenario::{accept, acceptor_with, basic_msg, block_on, drain, fresh};
+use easyfix_messages::messages::{Heartbeat, Message};
+use easyfix_session::{
+    application::{SessionEvent, SessionEventKind, SessionSummary},
+    settings::{ReadIdleAction, ReadIdleSettings},
+    DisconnectReason,
+};
+use futures::StreamExt;
+use tokio::{io::AsyncWriteExt, time::timeout};
+
+const READ_IDLE_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Log on, stay idle, send Heartbeat<0> and stay idle again, returns
+/// read idle events and session summary.
+fn run(action: ReadIdleAction) -> (Vec<Duration>, Option<SessionSummary>) {
+    let mut settings = common::settings();
+    settings.read_idle = Some(ReadIdleSettings {
+        timeout: READ_IDLE_TIMEOUT,
+        action,
+    });
+
+    block_on(async move {
+        let acceptor = acceptor_with(settings, common::session_settings());
+        let mut subscriber = acceptor
+            .events()
+            .subscribe()
+            .with_kinds(&[SessionEventKind::ReadIdle]);
+
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        let mut idle_events = Vec::new();
+        let mut msg = basic_msg(0);
+        for (msg_seq_num, body) in [
+            (1, None),
+            (2, Some(Message::Heartbeat(Heartbeat { test_req_id: None }))),
+        ] {
+            if let Some(body) = body {
+                msg.header.msg_type = body.msg_type();
+                *msg.body = body;
+            }
+            msg.header.msg_seq_num = msg_seq_num;
+            let serialized = fresh(msg.clone()).serialize();
+            // Write fails when session is already disconnected
+            if connection.remote.write_all(&serialized).await.is_err() {
+                break;
+            }
+            while let Ok(Some(event)) = timeout(READ_IDLE_TIMEOUT * 3, subscriber.next()).await {
+                if let SessionEvent::ReadIdle(_, idle) = event {
+                    idle_events.push(idle);
+                }
+            }
+        }
+
+        let summary = connection.close().await.expect("session not terminated");
+        (idle_events, summary)
+    })
+}
+
+#[test]
+fn read_idle_disconnect() {
+    let (idle_events, summary) = run(ReadIdleAction::Disconnect);
+    assert_eq!(idle_events, [READ_IDLE_TIMEOUT]);
+    assert_eq!(
+        summary.expect("no summary").disconnect_reason,
+        DisconnectReason::ReadIdle
+    );
+}
+
+#[test]
+fn read_idle_notify() {
+    let (idle_events, summary) = run(ReadIdleAction::Notify);
+    // Once per idle period, re-armed by Heartbeat<0>
+    assert_eq!(idle_events, [READ_IDLE_TIMEOUT, READ_IDLE_TIMEOUT]);
+    assert_eq!(
+        summary.expect("no summary").disconnect_reason,
+        DisconnectReason::Disconnected
+    );
+}
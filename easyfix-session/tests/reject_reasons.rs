@@ -0,0 +1,66 @@
+mod common;
+
+use common::{
+    scenario::{exchange, run_with, Step::*, LOGON, LOGON_RESPONSE},
+    session_settings,
+};
+use easyfix_macros::fix_str;
+use easyfix_messages::fields::FixStr;
+use easyfix_session::settings::{RejectReasonMapping, SessionSettings};
+
+fn mapped_settings() -> SessionSettings {
+    let mut session_settings = session_settings();
+    session_settings.reject_reasons = vec![
+        // InvalidMsgtype sent as Other
+        RejectReasonMapping {
+            reason: fix_str!("11").to_owned(),
+            sent_reason: Some(fix_str!("99").to_owned()),
+            text: Some("Unsupported message ({text})".to_owned()),
+        },
+        // Unknown value, ignored
+        RejectReasonMapping {
+            reason: fix_str!("5").to_owned(),
+            sent_reason: Some(fix_str!("1000").to_owned()),
+            text: None,
+        },
+    ];
+    session_settings
+}
+
+#[test]
+fn reject_reason_mapped() {
+    run_with(
+        mapped_settings(),
+        &[
+            In(LOGON),
+            Out(LOGON_RESPONSE),
+            In("35=ZZ|34=2"),
+            Out("35=3|34=2|45=2|371=35|372=ZZ|373=99"),
+        ],
+    );
+}
+
+#[test]
+fn reject_text_mapped() {
+    let responses = exchange(mapped_settings(), &[LOGON, "35=ZZ|34=2"]);
+    assert_eq!(responses[1].len(), 1);
+    assert!(
+        responses[1][0].contains("|373=99|58=Unsupported message (InvalidMsgtype (tag=35))|"),
+        "{}",
+        responses[1][0]
+    );
+}
+
+#[test]
+fn invalid_mapping_ignored() {
+    run_with(
+        mapped_settings(),
+        &[
+            In(LOGON),
+            Out(LOGON_RESPONSE),
+            In("35=0|34=2"),
+            In("35=4|34=3|123=Y|36=3"),
+            Out("35=3|34=2|45=3|371=36|372=4|373=5"),
+        ],
+    );
+}
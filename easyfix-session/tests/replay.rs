@@ -0,0 +1,73 @@
+mod common;
+
+use assert_matches::assert_matches;
+use common::{scenario::block_on, SESSION_BASIC};
+use easyfix_macros::fix_str;
+use easyfix_messages::fields::{FixStr, FixString};
+use easyfix_session::replay::{parse_log, Direction, Replay, ReplayError};
+
+fn replay() -> Replay {
+    Replay::new(common::settings(), common::session_settings())
+}
+
+fn run(log: &str) -> Result<(), ReplayError> {
+    let entries = parse_log(log, fix_str!("SERVER")).expect("invalid log");
+    block_on(replay().run(&entries))
+}
+
+#[test]
+fn parse_quickfix_log() {
+    let entries = parse_log(SESSION_BASIC, fix_str!("SERVER")).expect("invalid log");
+    let directions: Vec<_> = entries.iter().map(|entry| entry.direction).collect();
+    assert_eq!(
+        directions,
+        [
+            Direction::Inbound,
+            Direction::Outbound,
+            Direction::Inbound,
+            Direction::Outbound,
+            Direction::Inbound,
+            Direction::Outbound,
+            Direction::Inbound,
+            Direction::Inbound,
+            Direction::Outbound,
+        ]
+    );
+    assert_eq!(entries[2].line, 3);
+    assert_eq!(
+        entries[2].msg.header.sender_comp_id,
+        FixString::from_ascii_lossy(b"CLIENT".to_vec())
+    );
+}
+
+#[test]
+fn parse_invalid_checksum() {
+    let log = SESSION_BASIC.replacen("10=021", "10=022", 1);
+    assert_matches!(
+        parse_log(&log, fix_str!("SERVER")),
+        Err(ReplayError::Deserialize { line: 1, .. })
+    );
+}
+
+#[test]
+fn replay_session_basic() {
+    run(SESSION_BASIC).expect("replay failed");
+}
+
+#[test]
+fn replay_detects_missing_response() {
+    // Recorded Heartbeat<0> response to TestRequest<1> with other TestReqID<112>
+    let log = SESSION_BASIC
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            if idx == 3 {
+                line.replace("112=TEST1|10=237", "112=TEST2|10=238")
+            } else {
+                line.to_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_matches!(run(&log), Err(ReplayError::Mismatch { line: 3, .. }));
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::scenario::{accept, acceptor_with, basic_msg, block_on, collect, drain};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, FixString, MsgType, SessionRejectReason},
+    groups::LinesOfTextGrp,
+    messages::{FixtMessage, Message, News},
+};
+use easyfix_session::{
+    application::{SessionEvent, SessionEventKind},
+    settings::RequiredTags,
+};
+
+/// Log on with SenderSubID<50> required in header, send News<B> without
+/// and with it, return application messages received and messages sent
+/// after Logon<A> response.
+fn run() -> (Vec<Arc<FixtMessage>>, Vec<Arc<FixtMessage>>) {
+    let mut settings = common::settings();
+    settings.required_tags = RequiredTags {
+        header: vec![49, 56, 50],
+        trailer: Vec::new(),
+        ordered: true,
+    };
+
+    block_on(async move {
+        let acceptor = acceptor_with(settings, common::session_settings());
+        let mut subscriber = acceptor.events().subscribe().with_kinds(&[
+            SessionEventKind::AppMsgIn,
+            SessionEventKind::AppMsgOut,
+            SessionEventKind::AdmMsgOut,
+        ]);
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        let mut logon = basic_msg(0);
+        logon.header.sender_sub_id = Some(fix_str!("DESK").to_owned());
+        connection.send(&logon).await;
+        for (seq_num, sender_sub_id) in [(2, None), (3, Some(fix_str!("DESK").to_owned()))] {
+            let mut news = logon.clone();
+            news.header.msg_type = MsgType::News;
+            news.header.msg_seq_num = seq_num;
+            news.header.sender_sub_id = sender_sub_id;
+            *news.body = Message::News(News {
+                headline: FixString::from_ascii_lossy(format!("news {seq_num}").into_bytes()),
+                lines_of_text_grp: vec![LinesOfTextGrp {
+                    text: fix_str!("required").to_owned(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            });
+            connection.send(&news).await;
+        }
+
+        let mut received = Vec::new();
+        let mut sent = Vec::new();
+        for event in collect(&mut subscriber).await {
+            match event {
+                SessionEvent::AppMsgIn(_, msg) => received.push(msg),
+                SessionEvent::AppMsgOut(_, msg) | SessionEvent::AdmMsgOut(_, msg)
+                    if msg.msg_type() != MsgType::Logon =>
+                {
+                    sent.push(msg)
+                }
+                _ => {}
+            }
+        }
+        connection.close().await;
+        (received, sent)
+    })
+}
+
+#[test]
+fn missing_required_header_field_rejected() {
+    let (received, sent) = run();
+    assert_eq!(received.len(), 1, "{received:?}");
+    assert_eq!(received[0].header.msg_seq_num, 3);
+    assert_eq!(sent.len(), 1, "{sent:?}");
+    let Message::Reject(ref reject) = *sent[0].body else {
+        panic!("unexpected message: {:?}", sent[0]);
+    };
+    assert_eq!(reject.ref_seq_num, 2);
+    assert_eq!(reject.ref_tag_id, Some(50));
+    assert_eq!(
+        reject.session_reject_reason,
+        Some(SessionRejectReason::RequiredTagMissing)
+    );
+}
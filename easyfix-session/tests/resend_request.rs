@@ -0,0 +1,91 @@
+mod common;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on, collect, drain};
+use easyfix_messages::{
+    fields::SeqNum,
+    messages::{Heartbeat, Message},
+};
+use easyfix_session::{
+    application::{SessionEvent, SessionEventKind},
+    settings::{ResendRequestEndSeqNo, ResendRequestPolicy},
+};
+
+/// Log on, send Heartbeat<0> with MsgSeqNum<34> 12, then the missing
+/// messages 2..=11, returns `(BeginSeqNo<7>, EndSeqNo<16>)` of sent
+/// ResendRequest<2> messages.
+fn run(policy: ResendRequestPolicy) -> Vec<(SeqNum, SeqNum)> {
+    let mut session_settings = common::session_settings();
+    session_settings.resend_request = policy;
+
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut subscriber = acceptor
+            .events()
+            .subscribe()
+            .with_kinds(&[SessionEventKind::AdmMsgOut]);
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.send(&basic_msg(0)).await;
+        for msg_seq_num in [12].into_iter().chain(2..=11) {
+            let mut msg = basic_msg(0);
+            *msg.body = Message::Heartbeat(Heartbeat { test_req_id: None });
+            msg.header.msg_type = msg.body.msg_type();
+            msg.header.msg_seq_num = msg_seq_num;
+            connection.send(&msg).await;
+        }
+
+        let mut requests = Vec::new();
+        for event in collect(&mut subscriber).await {
+            if let SessionEvent::AdmMsgOut(_, msg) = event {
+                if let Message::ResendRequest(ref resend_request) = *msg.body {
+                    requests.push((resend_request.begin_seq_no, resend_request.end_seq_no));
+                }
+            }
+        }
+        connection.close().await;
+        requests
+    })
+}
+
+#[test]
+fn explicit_end_seq_no() {
+    let requests = run(ResendRequestPolicy::default());
+    assert_eq!(requests, [(2, 11)]);
+}
+
+#[test]
+fn infinite_end_seq_no() {
+    let requests = run(ResendRequestPolicy {
+        end_seq_no: ResendRequestEndSeqNo::Infinity,
+        max_range: None,
+    });
+    assert_eq!(requests, [(2, 0)]);
+}
+
+#[test]
+fn range_capped() {
+    let requests = run(ResendRequestPolicy {
+        end_seq_no: ResendRequestEndSeqNo::Explicit,
+        max_range: Some(4),
+    });
+    assert_eq!(requests, [(2, 5), (6, 9), (10, 11)]);
+}
+
+#[test]
+fn range_capped_last_infinite() {
+    let requests = run(ResendRequestPolicy {
+        end_seq_no: ResendRequestEndSeqNo::Infinity,
+        max_range: Some(4),
+    });
+    assert_eq!(requests, [(2, 5), (6, 9), (10, 0)]);
+}
+
+#[test]
+fn range_within_cap() {
+    let requests = run(ResendRequestPolicy {
+        end_seq_no: ResendRequestEndSeqNo::Explicit,
+        max_range: Some(10),
+    });
+    assert_eq!(requests, [(2, 11)]);
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use std::{cell::RefCell, rc::Rc};
+
+use common::scenario::{accept, acceptor, basic_msg, block_on};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, MsgType},
+    groups::LinesOfTextGrp,
+    messages::{Heartbeat, Message, News},
+};
+use easyfix_session::{
+    application::{AsEvent, FixEvent},
+    SendErrorReason, Sender,
+};
+use futures::StreamExt;
+use tokio::time::{timeout, Duration};
+
+fn news() -> Box<Message> {
+    Box::new(Message::News(News {
+        headline: fix_str!("headline").to_owned(),
+        lines_of_text_grp: vec![LinesOfTextGrp {
+            text: fix_str!("text").to_owned(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }))
+}
+
+fn heartbeat() -> Box<Message> {
+    Box::new(Message::Heartbeat(Heartbeat { test_req_id: None }))
+}
+
+#[test]
+fn send_refused_after_logout_and_disconnect() {
+    block_on(async move {
+        let acceptor = acceptor(common::session_settings());
+        let mut connection = accept(&acceptor);
+        let sender: Rc<RefCell<Option<Sender>>> = Rc::default();
+        let on_logout = Rc::new(RefCell::new(None));
+        let events = tokio::task::spawn_local({
+            let sender = sender.clone();
+            let on_logout = on_logout.clone();
+            async move {
+                let mut acceptor = Box::pin(acceptor);
+                while let Some(mut entry) = acceptor.next().await {
+                    match entry.as_event() {
+                        FixEvent::Logon(_, logon_sender) => {
+                            assert!(logon_sender.send(news()).is_ok());
+                            *sender.borrow_mut() = Some(logon_sender);
+                        }
+                        FixEvent::AdmMsgIn(msg, _) if msg.msg_type() == MsgType::Logout => {
+                            let sender = sender.borrow();
+                            let sender = sender.as_ref().unwrap();
+                            *on_logout.borrow_mut() = Some((
+                                sender.send(news()).err().map(|err| err.reason),
+                                sender.send(heartbeat()).err().map(|err| err.reason),
+                            ));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        });
+
+        let mut logout = basic_msg(7);
+        logout.header.msg_seq_num = 2;
+        for msg in [basic_msg(0), logout] {
+            // Logout<5> sent once Logon<A> is handled
+            timeout(Duration::from_secs(1), async {
+                while msg.msg_type() == MsgType::Logout && sender.borrow().is_none() {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .expect("Logon<A> not handled");
+            connection.send(&msg).await;
+        }
+        connection.read().await;
+        connection.finish(Duration::from_secs(1)).await;
+        events.abort();
+
+        // Application message refused, admin messages still accepted
+        assert_eq!(
+            *on_logout.borrow(),
+            Some((Some(SendErrorReason::LoggedOut), None))
+        );
+        let err = sender.borrow().as_ref().unwrap().send(news()).unwrap_err();
+        assert_eq!(err.reason, SendErrorReason::Disconnected);
+        assert_eq!(err.msg.msg_type(), MsgType::News);
+    });
+}
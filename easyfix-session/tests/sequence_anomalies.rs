@@ -0,0 +1,209 @@
+//! Sequence number anomalies caused by counterparty, with session response
+//! checked for relevant settings.
+//!
+//! | Anomaly                                              | Tests                          |
+//! |------------------------------------------------------|--------------------------------|
+//! | MsgSeqNum<34> unexpectedly reset to 1                | `reset_to_one_*`               |
+//! | SequenceReset<4>-Reset back to 1                     | `sequence_reset_to_one_*`      |
+//! | MsgSeqNum<34> far in the future                      | `seq_num_far_in_future_*`      |
+//! | ResendRequest<2> answered with wrong range           | `wrong_resend_range_*`         |
+//! | SequenceReset<4>-GapFill overlapping delivered ones  | `overlapping_gap_fill_*`       |
+
+mod common;
+
+use common::scenario::{run_with, Step::*, LOGON, LOGON_RESPONSE};
+use easyfix_session::settings::{
+    MsgSeqNumTooLowAction, ResendRequestEndSeqNo, ResendRequestPolicy, SequenceResetPolicy,
+    SessionSettings,
+};
+
+fn settings(configure: impl FnOnce(&mut SessionSettings)) -> SessionSettings {
+    let mut session_settings = common::session_settings();
+    configure(&mut session_settings);
+    session_settings
+}
+
+#[test]
+fn reset_to_one_logout() {
+    run_with(
+        common::session_settings(),
+        &[
+            In(LOGON),
+            Out(LOGON_RESPONSE),
+            In("35=0|34=2"),
+            In("35=0|34=3"),
+            In("35=0|34=1"),
+            Out("35=5|34=2|1409=9"),
+            Disconnected,
+        ],
+    );
+}
+
+#[test]
+fn reset_to_one_disconnect() {
+    run_with(
+        settings(|s| s.msg_seq_num_too_low = MsgSeqNumTooLowAction::Disconnect),
+        &[
+            In(LOGON),
+            Out(LOGON_RESPONSE),
+            In("35=0|34=2"),
+            In("35=0|34=3"),
+            In("35=0|34=1"),
+            Disconnected,
+        ],
+    );
+}
+
+#[test]
+fn reset_to_one_continue() {
+    run_with(
+        settings(|s| s.msg_seq_num_too_low = MsgSeqNumTooLowAction::Continue),
+        &[
+            In(LOGON),
+            Out(LOGON_RESPONSE),
+            In("35=0|34=2"),
+            In("35=0|34=3"),
+            In("35=1|34=1|112=LOW"),
+            // Discarded, expected MsgSeqNum<34> unchanged
+            In("35=1|34=4|112=NEXT"),
+            Out("35=0|34=2|112=NEXT"),
+        ],
+    );
+}
+
+#[test]
+fn sequence_reset_to_one_rejected() {
+    run_with(
+        common::session_settings(),
+        &[
+            In(LOGON),
+            Out(LOGON_RESPONSE),
+            In("35=0|34=2"),
+            In("35=4|34=3|36=1"),
+            Out("35=3|34=2|45=3|371=36|372=4|373=5"),
+            In("35=1|34=3|112=NEXT"),
+            Out("35=0|34=3|112=NEXT"),
+        ],
+    );
+}
+
+#[test]
+fn sequence_reset_to_one_accepted() {
+    run_with(
+        settings(|s| s.sequence_reset = SequenceResetPolicy::Accept),
+        &[
+            In(LOGON),
+            Out(LOGON_RESPONSE),
+            In("35=0|34=2"),
+            In("35=4|34=3|36=1"),
+            In("35=1|34=1|112=NEXT"),
+            Out("35=0|34=2|112=NEXT"),
+        ],
+    );
+}
+
+#[test]
+fn seq_num_far_in_future_whole_range() {
+    run_with(
+        common::session_settings(),
+        &[
+            In(LOGON),
+            Out(LOGON_RESPONSE),
+            In("35=0|34=100000"),
+            Out("35=2|34=2|7=2|16=99999"),
+        ],
+    );
+}
+
+#[test]
+fn seq_num_far_in_future_split_range() {
+    run_with(
+        settings(|s| {
+            s.resend_request = ResendRequestPolicy {
+                end_seq_no: ResendRequestEndSeqNo::Infinity,
+                max_range: Some(1000),
+            }
+        }),
+        &[
+            In(LOGON),
+            Out(LOGON_RESPONSE),
+            In("35=0|34=100000"),
+            Out("35=2|34=2|7=2|16=1001"),
+            In("35=4|34=2|43=Y|122=20240315-09:30:00.000|123=Y|36=1002"),
+            Out("35=2|34=3|7=1002|16=2001"),
+        ],
+    );
+}
+
+#[test]
+fn wrong_resend_range_waits() {
+    run_with(
+        common::session_settings(),
+        &[
+            In(LOGON),
+            Out(LOGON_RESPONSE),
+            In("35=0|34=5"),
+            Out("35=2|34=2|7=2|16=4"),
+            // MsgSeqNum<34> 2 never resent
+            In("35=0|34=3|43=Y|122=20240315-09:30:00.000"),
+            In("35=0|34=4|43=Y|122=20240315-09:30:00.000"),
+            // Queued until the gap is filled, not answered
+            In("35=1|34=6|112=QUEUED"),
+            In("35=4|34=2|43=Y|122=20240315-09:30:00.000|123=Y|36=3"),
+            Out("35=0|34=3|112=QUEUED"),
+        ],
+    );
+}
+
+#[test]
+fn wrong_resend_range_requested_again() {
+    run_with(
+        settings(|s| s.send_redundant_resend_requests = true),
+        &[
+            In(LOGON),
+            Out(LOGON_RESPONSE),
+            In("35=0|34=5"),
+            Out("35=2|34=2|7=2|16=4"),
+            In("35=0|34=3|43=Y|122=20240315-09:30:00.000"),
+            Out("35=2|34=3|7=2|16=2"),
+        ],
+    );
+}
+
+#[test]
+fn overlapping_gap_fill_ignored() {
+    run_with(
+        common::session_settings(),
+        &[
+            In(LOGON),
+            Out(LOGON_RESPONSE),
+            In("35=0|34=2"),
+            In("35=0|34=5"),
+            Out("35=2|34=2|7=3|16=4"),
+            // Starts at already delivered MsgSeqNum<34>, so it's ignored
+            // as possible duplicate
+            In("35=4|34=2|43=Y|122=20240315-09:30:00.000|123=Y|36=5"),
+            In("35=1|34=6|112=QUEUED"),
+            In("35=4|34=3|43=Y|122=20240315-09:30:00.000|123=Y|36=5"),
+            Out("35=0|34=3|112=QUEUED"),
+        ],
+    );
+}
+
+#[test]
+fn overlapping_gap_fill_skips_queued() {
+    run_with(
+        common::session_settings(),
+        &[
+            In(LOGON),
+            Out(LOGON_RESPONSE),
+            In("35=0|34=2"),
+            In("35=1|34=5|112=SKIPPED"),
+            Out("35=2|34=2|7=3|16=4"),
+            // Covers queued MsgSeqNum<34> 5 as well
+            In("35=4|34=3|43=Y|122=20240315-09:30:00.000|123=Y|36=6"),
+            In("35=1|34=6|112=NEXT"),
+            Out("35=0|34=3|112=NEXT"),
+        ],
+    );
+}
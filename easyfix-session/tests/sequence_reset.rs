@@ -0,0 +1,213 @@
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::scenario::{accept, acceptor, basic_msg, block_on};
+use easyfix_messages::{
+    fields::{MsgType, SeqNum, SessionRejectReason},
+    messages::{FixtMessage, Heartbeat, Message, SequenceReset},
+};
+use easyfix_session::{
+    application::{AsEvent, FixEvent, SessionEvent, SessionEventKind},
+    settings::SequenceResetPolicy,
+};
+use futures::StreamExt;
+use tokio::time::timeout;
+
+/// Confirmation timeout, confirmation requests not answered are held for
+/// twice as long.
+const CONFIRM_TIMEOUT: Duration = Duration::from_millis(50);
+
+fn sequence_reset(new_seq_no: SeqNum) -> Message {
+    Message::SequenceReset(SequenceReset {
+        gap_fill_flag: None,
+        new_seq_no,
+    })
+}
+
+fn heartbeat() -> Message {
+    Message::Heartbeat(Heartbeat { test_req_id: None })
+}
+
+/// Result of session run.
+struct Outcome {
+    /// Admin messages sent after Logon<A> response
+    sent: Vec<Arc<FixtMessage>>,
+    /// `(next_target_msg_seq_num, new_seq_no)` of confirmation requests
+    confirmations: Vec<(SeqNum, SeqNum)>,
+}
+
+/// Log on and send `messages`, confirmation requests are answered with
+/// `confirm`, or only after [`CONFIRM_TIMEOUT`] elapsed when it's `None`.
+fn run(
+    policy: SequenceResetPolicy,
+    confirm: Option<bool>,
+    messages: Vec<(SeqNum, Message)>,
+) -> Outcome {
+    let mut session_settings = common::session_settings();
+    session_settings.sequence_reset = policy;
+    session_settings.sequence_reset_confirm_timeout = CONFIRM_TIMEOUT;
+
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut subscriber = acceptor.events().subscribe().with_kinds(&[
+            SessionEventKind::AdmMsgOut,
+            SessionEventKind::SequenceResetConfirm,
+        ]);
+
+        let mut connection = accept(&acceptor);
+        let events = tokio::task::spawn_local(async move {
+            let mut acceptor = Box::pin(acceptor);
+            while let Some(mut entry) = acceptor.next().await {
+                if let FixEvent::SequenceResetConfirm { confirmation, .. } = entry.as_event() {
+                    match confirm {
+                        Some(true) => confirmation.accept(),
+                        Some(false) => confirmation.reject(),
+                        // Dropped, too late to be taken into account
+                        None => tokio::time::sleep(CONFIRM_TIMEOUT * 2).await,
+                    }
+                }
+            }
+        });
+
+        connection.send(&basic_msg(0)).await;
+        for (msg_seq_num, body) in messages {
+            let mut msg = basic_msg(0);
+            msg.header.msg_type = body.msg_type();
+            msg.header.msg_seq_num = msg_seq_num;
+            *msg.body = body;
+            connection.send(&msg).await;
+        }
+
+        let mut outcome = Outcome {
+            sent: Vec::new(),
+            confirmations: Vec::new(),
+        };
+        while let Ok(Some(event)) = timeout(CONFIRM_TIMEOUT * 4, subscriber.next()).await {
+            match event {
+                SessionEvent::AdmMsgOut(_, msg) if msg.msg_type() != MsgType::Logon => {
+                    outcome.sent.push(msg)
+                }
+                SessionEvent::SequenceResetConfirm {
+                    next_target_msg_seq_num,
+                    new_seq_no,
+                    ..
+                } => outcome
+                    .confirmations
+                    .push((next_target_msg_seq_num, new_seq_no)),
+                _ => {}
+            }
+        }
+        connection.close().await;
+        events.abort();
+        outcome
+    })
+}
+
+fn assert_new_seq_no_rejected(msg: &FixtMessage, ref_seq_num: SeqNum) {
+    let Message::Reject(ref reject) = *msg.body else {
+        panic!("unexpected message: {msg:?}");
+    };
+    assert_eq!(reject.ref_seq_num, ref_seq_num);
+    assert_eq!(reject.ref_tag_id, Some(36));
+    assert_eq!(
+        reject.session_reject_reason,
+        Some(SessionRejectReason::ValueIsIncorrect)
+    );
+}
+
+#[test]
+fn reject_lower_jumps_forward() {
+    let outcome = run(
+        SequenceResetPolicy::RejectLower,
+        None,
+        vec![(2, sequence_reset(10)), (10, heartbeat())],
+    );
+    // Heartbeat<0> was expected, no ResendRequest<2> sent
+    assert!(outcome.sent.is_empty(), "{:?}", outcome.sent);
+}
+
+#[test]
+fn reject_lower_rejects_lower_value() {
+    let outcome = run(
+        SequenceResetPolicy::RejectLower,
+        None,
+        vec![
+            (2, sequence_reset(10)),
+            (10, sequence_reset(5)),
+            // Still expected after rejected reset
+            (10, heartbeat()),
+        ],
+    );
+    assert_eq!(outcome.sent.len(), 1, "{:?}", outcome.sent);
+    assert_new_seq_no_rejected(&outcome.sent[0], 10);
+}
+
+#[test]
+fn accept_lowers_sequence() {
+    let outcome = run(
+        SequenceResetPolicy::Accept,
+        None,
+        vec![
+            (2, sequence_reset(10)),
+            (10, sequence_reset(5)),
+            // Would be too low without accepted reset
+            (5, heartbeat()),
+        ],
+    );
+    assert!(outcome.sent.is_empty(), "{:?}", outcome.sent);
+}
+
+#[test]
+fn confirm_accepted() {
+    let outcome = run(
+        SequenceResetPolicy::Confirm,
+        Some(true),
+        vec![(2, sequence_reset(10)), (10, heartbeat())],
+    );
+    assert_eq!(outcome.confirmations, [(2, 10)]);
+    assert!(outcome.sent.is_empty(), "{:?}", outcome.sent);
+}
+
+#[test]
+fn confirm_rejected() {
+    let outcome = run(
+        SequenceResetPolicy::Confirm,
+        Some(false),
+        vec![(2, sequence_reset(10)), (2, heartbeat())],
+    );
+    assert_eq!(outcome.confirmations, [(2, 10)]);
+    assert_eq!(outcome.sent.len(), 1, "{:?}", outcome.sent);
+    assert_new_seq_no_rejected(&outcome.sent[0], 2);
+}
+
+#[test]
+fn equal_value_ignored() {
+    let outcome = run(
+        SequenceResetPolicy::Confirm,
+        Some(false),
+        vec![(2, sequence_reset(2)), (2, heartbeat())],
+    );
+    assert!(outcome.confirmations.is_empty());
+    assert!(outcome.sent.is_empty(), "{:?}", outcome.sent);
+}
+
+#[test]
+fn confirm_timed_out_rejects_lower() {
+    let outcome = run(
+        SequenceResetPolicy::Confirm,
+        None,
+        vec![(2, sequence_reset(10)), (10, heartbeat())],
+    );
+    assert_eq!(outcome.confirmations, [(2, 10)]);
+    assert!(outcome.sent.is_empty(), "{:?}", outcome.sent);
+
+    let outcome = run(
+        SequenceResetPolicy::Confirm,
+        None,
+        vec![(2, sequence_reset(1)), (2, heartbeat())],
+    );
+    assert_eq!(outcome.confirmations, [(2, 1)]);
+    assert_eq!(outcome.sent.len(), 1, "{:?}", outcome.sent);
+    assert_new_seq_no_rejected(&outcome.sent[0], 2);
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, FixString, SeqNum, TagNum},
+    groups::LinesOfTextGrp,
+    messages::{FixtMessage, Message, News},
+};
+use easyfix_session::{
+    application::{AsEvent, FixEvent},
+    new_header, new_trailer,
+    settings::{EmptyStringPolicy, SerializeFailurePolicy},
+    DisconnectReason,
+};
+use futures::StreamExt;
+use tokio::time::{timeout, Duration};
+
+fn news(headline: &str, url_link: &str) -> Box<FixtMessage> {
+    let body = Box::new(Message::News(News {
+        headline: FixString::from_ascii_lossy(headline.as_bytes().to_vec()),
+        url_link: Some(FixString::from_ascii_lossy(url_link.as_bytes().to_vec())),
+        lines_of_text_grp: vec![LinesOfTextGrp {
+            text: fix_str!("serialize").to_owned(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }));
+    Box::new(FixtMessage {
+        header: Box::new(new_header(body.msg_type())),
+        body,
+        trailer: Box::new(new_trailer()),
+    })
+}
+
+#[derive(Debug, Default)]
+struct Outcome {
+    /// MsgSeqNum<34> and offending tag of messages failed to serialize
+    failures: Vec<(SeqNum, TagNum)>,
+    logout: Option<DisconnectReason>,
+    /// Data written to the socket
+    written: Vec<u8>,
+}
+
+/// Log on and send News<B> with empty URLLink<149>, followed by valid one.
+fn run(policy: SerializeFailurePolicy) -> Outcome {
+    let mut session_settings = common::session_settings();
+    session_settings.empty_strings = EmptyStringPolicy::Error;
+    session_settings.serialize_failure = policy;
+
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut connection = accept(&acceptor);
+        let events = tokio::task::spawn_local(async move {
+            let mut outcome = Outcome::default();
+            let mut acceptor = Box::pin(acceptor);
+            while let Some(mut event) = acceptor.next().await {
+                match event.as_event() {
+                    FixEvent::Logon(_, sender) => {
+                        sender.send_raw(news("invalid", "")).unwrap();
+                        sender.send_raw(news("valid", "link")).unwrap();
+                    }
+                    FixEvent::SerializeFailed(_, failure) => {
+                        assert_eq!(failure.policy, policy);
+                        outcome
+                            .failures
+                            .push((failure.msg.header.msg_seq_num, failure.tag));
+                    }
+                    FixEvent::Logout(_, reason) => {
+                        outcome.logout = Some(reason);
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            outcome
+        });
+
+        connection.send(&basic_msg(0)).await;
+        let written = connection.read().await;
+        connection.close().await;
+        let mut outcome = timeout(Duration::from_secs(1), events)
+            .await
+            .expect("events not finished")
+            .unwrap();
+        outcome.written = written;
+        outcome
+    })
+}
+
+fn contains(data: &[u8], needle: &[u8]) -> bool {
+    data.windows(needle.len()).any(|window| window == needle)
+}
+
+#[test]
+fn failed_message_skipped() {
+    let outcome = run(SerializeFailurePolicy::Skip);
+    assert_eq!(outcome.failures, [(2, 149)]);
+    assert!(!contains(&outcome.written, b"148=invalid\x01"));
+    assert!(contains(&outcome.written, b"148=valid\x01"));
+    assert_eq!(outcome.logout, Some(DisconnectReason::Disconnected));
+}
+
+#[test]
+fn failed_message_disconnects() {
+    let outcome = run(SerializeFailurePolicy::Disconnect);
+    assert_eq!(outcome.failures, [(2, 149)]);
+    assert!(!contains(&outcome.written, b"148=invalid\x01"));
+    assert_eq!(outcome.logout, Some(DisconnectReason::SerializeError));
+}
@@ -0,0 +1,76 @@
+use std::collections::BTreeSet;
+
+use easyfix_macros::fix_str;
+use easyfix_messages::fields::FixStr;
+use easyfix_session::session_id::{ParseSessionIdError, SessionId};
+use serde::{
+    de::value::{Error, StrDeserializer},
+    Deserialize,
+};
+
+fn session_id(sender: &FixStr, target: &FixStr, qualifier: &str) -> SessionId {
+    SessionId::with_session_qualifier(
+        fix_str!("FIX.4.4").to_owned(),
+        sender.to_owned(),
+        target.to_owned(),
+        qualifier.to_owned(),
+    )
+}
+
+#[test]
+fn string_form_round_trip() {
+    let plain = session_id(fix_str!("SERVER"), fix_str!("CLIENT"), "");
+    assert_eq!(plain.to_string(), "FIX.4.4:SERVER->CLIENT");
+    assert_eq!("FIX.4.4:SERVER->CLIENT".parse(), Ok(plain));
+
+    let qualified = session_id(fix_str!("SERVER"), fix_str!("CLIENT"), "backup");
+    assert_eq!(qualified.to_string(), "FIX.4.4:SERVER->CLIENT/backup");
+    assert_eq!("FIX.4.4:SERVER->CLIENT/backup".parse(), Ok(qualified));
+}
+
+#[test]
+fn invalid_string_form_rejected() {
+    let parse = |s: &str| s.parse::<SessionId>();
+    assert_eq!(
+        parse("FIX.4.4 SERVER->CLIENT"),
+        Err(ParseSessionIdError::MissingColon)
+    );
+    assert_eq!(
+        parse("FIX.4.4:SERVER-CLIENT"),
+        Err(ParseSessionIdError::MissingArrow)
+    );
+    assert_eq!(
+        parse("FIX.4.4:->CLIENT"),
+        Err(ParseSessionIdError::Empty("SenderCompID"))
+    );
+    assert_eq!(
+        parse("FIX.4.4:SERVER->CLIENT\u{1}"),
+        Err(ParseSessionIdError::InvalidValue("TargetCompID"))
+    );
+}
+
+#[test]
+fn deserialized_from_string() {
+    let session_id = SessionId::deserialize(StrDeserializer::<Error>::new(
+        "FIX.4.4:SERVER->CLIENT/backup",
+    ))
+    .unwrap();
+    assert_eq!(
+        session_id,
+        self::session_id(fix_str!("SERVER"), fix_str!("CLIENT"), "backup")
+    );
+    assert!(SessionId::deserialize(StrDeserializer::<Error>::new("FIX.4.4")).is_err());
+}
+
+#[test]
+fn ordered_by_parts() {
+    let ids: BTreeSet<_> = [
+        session_id(fix_str!("B"), fix_str!("A"), ""),
+        session_id(fix_str!("A"), fix_str!("B"), "backup"),
+        session_id(fix_str!("A"), fix_str!("B"), ""),
+    ]
+    .into_iter()
+    .collect();
+    let ids: Vec<_> = ids.iter().map(ToString::to_string).collect();
+    assert_eq!(ids, ["FIX.4.4:A->B", "FIX.4.4:A->B/backup", "FIX.4.4:B->A"]);
+}
@@ -0,0 +1,173 @@
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use common::{
+    scenario::{accept, acceptor_with, basic_msg, block_on, drain},
+    SESSION_BASIC,
+};
+use easyfix_macros::fix_str;
+use easyfix_messages::fields::FixStr;
+use easyfix_session::{
+    journal::{export_quickfix_log, import_quickfix_log, read_session_log},
+    replay::{parse_log, Direction},
+    settings::SessionLogSettings,
+};
+
+/// Empty directory for log files of test `name`.
+fn log_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("easyfix-session-log-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Log on and send TestRequest<1>, then disconnect.
+fn run(session_log: SessionLogSettings) {
+    let mut settings = common::settings();
+    settings.session_log = Some(session_log);
+
+    block_on(async move {
+        let acceptor = acceptor_with(settings, common::session_settings());
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        for index in [0, 2] {
+            connection.send(&basic_msg(index)).await;
+        }
+        connection.read().await;
+
+        connection.close().await;
+    })
+}
+
+#[test]
+fn messages_and_events_logged() {
+    let dir = log_dir("basic");
+    run(SessionLogSettings::new(&dir));
+
+    let messages = fs::read(dir.join("FIXT.1.1-SERVER-CLIENT.messages.log")).unwrap();
+    let messages: Vec<_> = messages
+        .split(|b| *b == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| String::from_utf8_lossy(line).into_owned())
+        .collect();
+    assert_eq!(messages.len(), 4, "{messages:?}");
+    // Logon<A> is logged even though session is identified after it's parsed
+    assert!(messages[0].contains(" IN 8=FIXT.1.1\x01"));
+    assert!(messages[0].contains("\x0135=A\x01"));
+    assert_eq!(
+        messages.iter().filter(|line| line.contains(" IN ")).count(),
+        2
+    );
+    assert_eq!(
+        messages
+            .iter()
+            .filter(|line| line.contains(" OUT "))
+            .count(),
+        2
+    );
+    assert!(messages
+        .iter()
+        .any(|line| line.contains(" OUT ") && line.contains("\x0135=0\x01")));
+
+    let events = fs::read_to_string(dir.join("FIXT.1.1-SERVER-CLIENT.events.log")).unwrap();
+    assert!(events.contains("Created("), "{events}");
+    assert!(events.contains("Logon("), "{events}");
+    assert!(events.contains("Logout("), "{events}");
+    assert!(!events.contains("MsgIn("), "{events}");
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn files_rotated() {
+    let dir = log_dir("rotation");
+    run(SessionLogSettings {
+        events: false,
+        max_file_size: 1,
+        max_files: 2,
+        ..SessionLogSettings::new(&dir)
+    });
+
+    let mut files: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    files.sort();
+    // One message per file, the oldest one removed
+    assert_eq!(
+        files,
+        [
+            "FIXT.1.1-SERVER-CLIENT.messages.log",
+            "FIXT.1.1-SERVER-CLIENT.messages.log.1",
+            "FIXT.1.1-SERVER-CLIENT.messages.log.2",
+        ]
+    );
+    let last = fs::read_to_string(dir.join("FIXT.1.1-SERVER-CLIENT.messages.log")).unwrap();
+    assert_eq!(last.lines().count(), 1);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn exported_to_quickfix_format_and_imported_back() {
+    let dir = log_dir("quickfix");
+    run(SessionLogSettings {
+        events: false,
+        ..SessionLogSettings::new(&dir)
+    });
+
+    let messages = fs::read(dir.join("FIXT.1.1-SERVER-CLIENT.messages.log")).unwrap();
+    let mut quickfix_log = Vec::new();
+    export_quickfix_log(messages.as_slice(), &mut quickfix_log).unwrap();
+    let quickfix_log = String::from_utf8(quickfix_log).unwrap();
+    let line = quickfix_log.lines().next().unwrap();
+    assert_eq!(&line[21..32], " : 8=FIXT.1", "{line}");
+
+    let entries = parse_log(&quickfix_log, fix_str!("SERVER")).unwrap();
+    assert_eq!(entries.len(), 4);
+    assert_eq!(entries[0].direction, Direction::Inbound);
+    assert_eq!(
+        entries
+            .iter()
+            .filter(|entry| entry.direction == Direction::Outbound)
+            .count(),
+        2
+    );
+
+    let mut imported = Vec::new();
+    import_quickfix_log(quickfix_log.as_bytes(), fix_str!("SERVER"), &mut imported).unwrap();
+    let original = read_session_log(messages.as_slice()).unwrap();
+    let imported = read_session_log(imported.as_slice()).unwrap();
+    assert_eq!(imported.len(), original.len());
+    for (imported, original) in imported.iter().zip(&original) {
+        assert_eq!(imported.direction, original.direction);
+        assert_eq!(imported.msg, original.msg);
+        // QuickFIX log has millisecond precision
+        assert!((original.timestamp - imported.timestamp).num_milliseconds() < 1);
+    }
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn quickfix_log_imported() {
+    let mut imported = Vec::new();
+    import_quickfix_log(SESSION_BASIC.as_bytes(), fix_str!("SERVER"), &mut imported).unwrap();
+    let entries = read_session_log(imported.as_slice()).unwrap();
+    assert_eq!(entries.len(), SESSION_BASIC.lines().count());
+    assert_eq!(entries[0].direction, Direction::Inbound);
+    assert_eq!(entries[1].direction, Direction::Outbound);
+    assert_eq!(
+        entries[0]
+            .timestamp
+            .format("%Y%m%d-%H:%M:%S%.3f")
+            .to_string(),
+        "20240315-09:30:00.000"
+    );
+    assert!(entries[0]
+        .msg
+        .starts_with(b"8=FIXT.1.1\x019=74\x0135=A\x01"));
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on, drain};
+use easyfix_session::acceptor::metrics::SessionStats;
+
+/// Log on and send TestRequest<1>, returns session statistics taken
+/// while connected and after disconnection.
+fn run() -> (SessionStats, SessionStats) {
+    block_on(async move {
+        let acceptor = acceptor(common::session_settings());
+        let sessions = acceptor.sessions_map();
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        for index in [0, 2] {
+            connection.send(&basic_msg(index)).await;
+        }
+        connection.read().await;
+        let connected = sessions.borrow().session_stats().remove(0);
+
+        connection.close().await;
+        let disconnected = sessions.borrow().session_stats().remove(0);
+        (connected, disconnected)
+    })
+}
+
+#[test]
+fn messages_counted() {
+    let (connected, disconnected) = run();
+    assert!(connected.connected);
+    assert_eq!(connected.session_id, common::session_settings().session_id);
+    // Logon<A> and TestRequest<1> received, Logon<A> and Heartbeat<0> sent
+    assert_eq!(connected.state.messages_received, 2);
+    assert_eq!(connected.state.messages_sent, 2);
+    assert_eq!(connected.state.next_sender_msg_seq_num, 3);
+    assert_eq!(connected.state.next_target_msg_seq_num, 3);
+
+    assert!(!disconnected.connected);
+    assert_eq!(disconnected.state.messages_received, 2);
+}
+
+#[cfg(feature = "prometheus")]
+#[test]
+fn prometheus_encoding() {
+    use easyfix_session::acceptor::metrics::{prometheus, AcceptorMetrics};
+
+    let (connected, _) = run();
+    let metrics = AcceptorMetrics {
+        accepted: 1,
+        logons: 1,
+        open_connections: 1,
+        ..Default::default()
+    };
+    let output = prometheus::encode(&metrics, &[connected]);
+    assert!(output.contains("# TYPE easyfix_logons_total counter\neasyfix_logons_total 1\n"));
+    assert!(output.contains(
+        "easyfix_session_messages_sent_total{sender=\"SERVER\",target=\"CLIENT\",qualifier=\"\"} 2\n"
+    ));
+    assert!(output.contains(
+        "easyfix_session_connected{sender=\"SERVER\",target=\"CLIENT\",qualifier=\"\"} 1\n"
+    ));
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on, drain};
+use easyfix_macros::fix_str;
+use easyfix_messages::{fields::FixStr, messages::FixtMessage};
+use easyfix_session::{application::SessionSummary, DisconnectReason};
+
+/// Messages from log entries with given indices.
+fn messages(indices: &[usize]) -> Vec<FixtMessage> {
+    indices.iter().map(|index| basic_msg(*index)).collect()
+}
+
+/// Send given messages, then disconnect and return what session task
+/// resolved to.
+fn run(messages: Vec<FixtMessage>) -> Option<SessionSummary> {
+    block_on(async move {
+        let acceptor = acceptor(common::session_settings());
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        for msg in &messages {
+            connection.send(msg).await;
+        }
+        connection.read().await;
+
+        connection.close().await.expect("session not terminated")
+    })
+}
+
+#[test]
+fn summary_after_disconnect() {
+    let summary = run(messages(&[0, 2])).expect("no summary");
+    assert_eq!(summary.session_id, common::session_settings().session_id);
+    assert_eq!(summary.disconnect_reason, DisconnectReason::Disconnected);
+    // Logon<A> and TestRequest<1> received, Logon<A> and Heartbeat<0> sent
+    assert_eq!(summary.state.next_sender_msg_seq_num, 3);
+    assert_eq!(summary.state.next_target_msg_seq_num, 3);
+    assert_eq!(summary.state.messages_received, 2);
+    assert_eq!(summary.state.messages_sent, 2);
+}
+
+#[test]
+fn summary_without_logon() {
+    // TestRequest<1> as first message
+    let summary = run(messages(&[2])).expect("no summary");
+    assert_eq!(
+        summary.disconnect_reason,
+        DisconnectReason::InvalidLogonState
+    );
+    assert_eq!(summary.state.next_target_msg_seq_num, 1);
+    assert!(!summary.state.logon_received);
+}
+
+#[test]
+fn no_summary_for_unknown_session() {
+    let mut logon = messages(&[0]);
+    logon[0].header.sender_comp_id = fix_str!("UNKNOWN").to_owned();
+    assert_eq!(run(logon), None);
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on, collect};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, MsgType},
+    groups::LinesOfTextGrp,
+    messages::{Message, News},
+};
+use easyfix_session::{
+    application::{AsEvent, FixEvent, SessionEvent, SessionEventKind},
+    session_id::SubIdRoute,
+    Sender,
+};
+use futures::StreamExt;
+
+fn news(headline: &FixStr) -> Box<Message> {
+    Box::new(Message::News(News {
+        headline: headline.to_owned(),
+        lines_of_text_grp: vec![LinesOfTextGrp {
+            text: fix_str!("text").to_owned(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }))
+}
+
+/// Log on, send News<B> from TRADER to DESK_A and DESK_B, each one
+/// answered with News<B> routed back, return events of DESK_A route.
+fn run() -> Vec<SessionEvent> {
+    block_on(async move {
+        let acceptor = acceptor(common::session_settings());
+        let mut subscriber = acceptor
+            .events()
+            .subscribe()
+            .with_kinds(&[SessionEventKind::AppMsgIn, SessionEventKind::AppMsgOut])
+            .with_sub_id_route(SubIdRoute::new(Some(fix_str!("DESK_A").to_owned()), None));
+
+        let mut connection = accept(&acceptor);
+        let events = tokio::task::spawn_local(async move {
+            let mut sender: Option<Sender> = None;
+            let mut acceptor = Box::pin(acceptor);
+            while let Some(mut entry) = acceptor.next().await {
+                match entry.as_event() {
+                    FixEvent::Logon(_, logon_sender) => sender = Some(logon_sender),
+                    FixEvent::AppMsgIn(msg, _) => {
+                        let route = SubIdRoute::from_input_msg(&msg);
+                        sender
+                            .as_ref()
+                            .unwrap()
+                            .send_routed(news(fix_str!("reply")), &route)
+                            .unwrap();
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        connection.send(&basic_msg(0)).await;
+        for (seq_num, desk) in [(2, fix_str!("DESK_A")), (3, fix_str!("DESK_B"))] {
+            let mut msg = basic_msg(0);
+            msg.header.msg_type = MsgType::News;
+            msg.header.msg_seq_num = seq_num;
+            msg.header.sender_sub_id = Some(fix_str!("TRADER").to_owned());
+            msg.header.target_sub_id = Some(desk.to_owned());
+            msg.body = news(desk);
+            connection.send(&msg).await;
+        }
+
+        let received = collect(&mut subscriber).await;
+        connection.close().await;
+        events.abort();
+        received
+    })
+}
+
+#[test]
+fn events_demultiplexed_by_sub_id() {
+    let events = run();
+    assert_eq!(events.len(), 2, "{events:?}");
+    let route = SubIdRoute::new(
+        Some(fix_str!("DESK_A").to_owned()),
+        Some(fix_str!("TRADER").to_owned()),
+    );
+
+    let SessionEvent::AppMsgIn(_, ref msg_in) = events[0] else {
+        panic!("unexpected event: {:?}", events[0]);
+    };
+    assert_eq!(msg_in.header.msg_seq_num, 2);
+    assert_eq!(events[0].sub_id_route(), Some(route.clone()));
+
+    let SessionEvent::AppMsgOut(_, ref msg_out) = events[1] else {
+        panic!("unexpected event: {:?}", events[1]);
+    };
+    assert_eq!(
+        msg_out.header.sender_sub_id.as_deref(),
+        Some(fix_str!("DESK_A"))
+    );
+    assert_eq!(
+        msg_out.header.target_sub_id.as_deref(),
+        Some(fix_str!("TRADER"))
+    );
+    assert_eq!(events[1].sub_id_route(), Some(route));
+}
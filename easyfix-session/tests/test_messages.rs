@@ -0,0 +1,121 @@
+mod common;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on, collect, drain};
+use easyfix_messages::{
+    fields::FixString,
+    messages::{Message, NewOrderSingle},
+};
+use easyfix_session::{
+    application::{SessionEvent, SessionEventKind},
+    settings::{SessionSettings, TestMessagesPolicy},
+};
+
+/// Log on with given TestMessageIndicator<464> and send
+/// NewOrderSingle<D>, returns Logon<A> and application message events.
+fn run(session_settings: SessionSettings, test_logon: bool) -> Vec<SessionEvent> {
+    let mut logon = basic_msg(0);
+    let mut order = logon.clone();
+    let Message::Logon(body) = &mut *logon.body else {
+        unreachable!()
+    };
+    body.test_message_indicator = test_logon.then_some(true);
+    let body = Message::NewOrderSingle(NewOrderSingle {
+        cl_ord_id: FixString::from_ascii_lossy(b"order".to_vec()),
+        ..Default::default()
+    });
+    order.header.msg_type = body.msg_type();
+    order.header.msg_seq_num = 2;
+    *order.body = body;
+
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut subscriber = acceptor.events().subscribe().with_kinds(&[
+            SessionEventKind::AdmMsgOut,
+            SessionEventKind::AppMsgIn,
+            SessionEventKind::TestAppMsgIn,
+        ]);
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.send(&logon).await;
+        connection.send(&order).await;
+
+        let received = collect(&mut subscriber).await;
+        connection.close().await;
+        received
+    })
+}
+
+fn session_settings(test_session: bool, test_messages: TestMessagesPolicy) -> SessionSettings {
+    let mut session_settings = common::session_settings();
+    session_settings.test_session = test_session;
+    session_settings.test_messages = test_messages;
+    session_settings
+}
+
+/// Logon<A> response sent by acceptor, if any.
+fn logon_response(events: &[SessionEvent]) -> Option<&Message> {
+    events.iter().find_map(|event| match event {
+        SessionEvent::AdmMsgOut(_, msg) => match &*msg.body {
+            body @ Message::Logon(_) => Some(body),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+fn kinds(events: &[SessionEvent]) -> Vec<SessionEventKind> {
+    events
+        .iter()
+        .map(SessionEvent::kind)
+        .filter(|kind| *kind != SessionEventKind::AdmMsgOut)
+        .collect()
+}
+
+#[test]
+fn test_logon_accepted_by_default() {
+    let events = run(session_settings(false, TestMessagesPolicy::Accept), true);
+    assert!(logon_response(&events).is_some());
+    assert_eq!(kinds(&events), [SessionEventKind::AppMsgIn]);
+}
+
+#[test]
+fn test_logon_rejected() {
+    let events = run(session_settings(false, TestMessagesPolicy::Reject), true);
+    assert!(logon_response(&events).is_none());
+    assert!(kinds(&events).is_empty());
+
+    // Production Logon<A> is not affected
+    let events = run(session_settings(false, TestMessagesPolicy::Reject), false);
+    assert_eq!(kinds(&events), [SessionEventKind::AppMsgIn]);
+}
+
+#[test]
+fn test_messages_segregated() {
+    let events = run(session_settings(false, TestMessagesPolicy::Segregate), true);
+    assert!(logon_response(&events).is_some());
+    assert_eq!(kinds(&events), [SessionEventKind::TestAppMsgIn]);
+
+    let events = run(
+        session_settings(false, TestMessagesPolicy::Segregate),
+        false,
+    );
+    assert_eq!(kinds(&events), [SessionEventKind::AppMsgIn]);
+}
+
+#[test]
+fn test_session_flags_logon() {
+    let events = run(session_settings(true, TestMessagesPolicy::Reject), true);
+    let Some(Message::Logon(logon)) = logon_response(&events) else {
+        panic!("Logon<A> not sent: {events:?}");
+    };
+    assert_eq!(logon.test_message_indicator, Some(true));
+    // Test session expects test messages
+    assert_eq!(kinds(&events), [SessionEventKind::AppMsgIn]);
+
+    let events = run(session_settings(false, TestMessagesPolicy::Accept), false);
+    let Some(Message::Logon(logon)) = logon_response(&events) else {
+        panic!("Logon<A> not sent: {events:?}");
+    };
+    assert_eq!(logon.test_message_indicator, None);
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on, collect};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{BusinessRejectReason, FixStr, FixString, MsgType, SessionRejectReason},
+    messages::{FixtMessage, Heartbeat, Message, NewOrderSingle},
+};
+use easyfix_session::{
+    application::{AsEvent, FixEvent, SessionEvent, SessionEventKind},
+    settings::UnsupportedMsgTypePolicy,
+};
+use futures::StreamExt;
+
+/// Log on, send NewOrderSingle<D> declined by application followed by
+/// Heartbeat<0>, return messages sent after Logon<A> response.
+fn run(policy: UnsupportedMsgTypePolicy) -> Vec<Arc<FixtMessage>> {
+    let mut session_settings = common::session_settings();
+    session_settings.unsupported_msg_type = policy;
+    let message = |msg_seq_num, body: Message| {
+        let mut msg = basic_msg(0);
+        msg.header.msg_type = body.msg_type();
+        msg.header.msg_seq_num = msg_seq_num;
+        *msg.body = body;
+        msg
+    };
+
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut subscriber = acceptor
+            .events()
+            .subscribe()
+            .with_kinds(&[SessionEventKind::AppMsgOut, SessionEventKind::AdmMsgOut]);
+        let mut connection = accept(&acceptor);
+        let events = tokio::task::spawn_local(async move {
+            let mut acceptor = Box::pin(acceptor);
+            while let Some(mut event) = acceptor.next().await {
+                if let FixEvent::AppMsgIn(msg, responder) = event.as_event() {
+                    responder.unsupported(&msg);
+                }
+            }
+        });
+
+        connection.send(&basic_msg(0)).await;
+        let order = message(
+            2,
+            Message::NewOrderSingle(NewOrderSingle {
+                cl_ord_id: FixString::from_ascii_lossy(b"order".to_vec()),
+                ..Default::default()
+            }),
+        );
+        connection.send(&order).await;
+        let heartbeat = message(3, Message::Heartbeat(Heartbeat { test_req_id: None }));
+        connection.send(&heartbeat).await;
+
+        let mut sent = Vec::new();
+        for event in collect(&mut subscriber).await {
+            match event {
+                SessionEvent::AppMsgOut(_, msg) | SessionEvent::AdmMsgOut(_, msg)
+                    if msg.msg_type() != MsgType::Logon =>
+                {
+                    sent.push(msg)
+                }
+                _ => {}
+            }
+        }
+        connection.close().await;
+        events.abort();
+        sent
+    })
+}
+
+#[test]
+fn business_reject_by_default() {
+    // Only BusinessMessageReject<j>, Heartbeat<0> was expected, so no
+    // ResendRequest<2>
+    let sent = run(UnsupportedMsgTypePolicy::default());
+    assert_eq!(sent.len(), 1, "{sent:?}");
+    let Message::BusinessMessageReject(ref reject) = *sent[0].body else {
+        panic!("unexpected message: {:?}", sent[0]);
+    };
+    assert_eq!(reject.ref_seq_num, Some(2));
+    assert_eq!(reject.ref_msg_type, "D");
+    assert_eq!(
+        reject.business_reject_ref_id.as_deref(),
+        Some(fix_str!("order"))
+    );
+    assert_eq!(
+        reject.business_reject_reason,
+        BusinessRejectReason::UnsupportedMessageType
+    );
+}
+
+#[test]
+fn session_reject() {
+    let sent = run(UnsupportedMsgTypePolicy::Reject);
+    assert_eq!(sent.len(), 1, "{sent:?}");
+    let Message::Reject(ref reject) = *sent[0].body else {
+        panic!("unexpected message: {:?}", sent[0]);
+    };
+    assert_eq!(reject.ref_seq_num, 2);
+    assert_eq!(reject.ref_msg_type.as_deref(), Some(fix_str!("D")));
+    assert_eq!(
+        reject.session_reject_reason,
+        Some(SessionRejectReason::InvalidMsgtype)
+    );
+}
+
+#[test]
+fn ignore() {
+    let sent = run(UnsupportedMsgTypePolicy::Ignore);
+    assert!(sent.is_empty(), "{sent:?}");
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::scenario::{accept, acceptor, basic_msg, block_on, collect, drain};
+use easyfix_macros::fix_str;
+use easyfix_messages::{
+    fields::{FixStr, FixString, MsgType, SessionRejectReason},
+    groups::LinesOfTextGrp,
+    messages::{FieldTag, FixtMessage, Header, Message, News},
+};
+use easyfix_session::{
+    application::{SessionEvent, SessionEventKind},
+    verifier::{SessionVerifier, Verdict, Verifier},
+};
+use futures::future::LocalBoxFuture;
+
+/// Messages addressed (with TargetSubID<57>) to halted market are rejected,
+/// or session is terminated if `logout` is set.
+#[derive(Debug)]
+struct MarketHalt {
+    logout: bool,
+}
+
+impl Verifier for MarketHalt {
+    fn verify<'a>(&'a self, header: &'a Header) -> LocalBoxFuture<'a, Verdict> {
+        Box::pin(async move {
+            if header.target_sub_id.as_deref() != Some(fix_str!("HALTED")) {
+                Verdict::Accept
+            } else if self.logout {
+                Verdict::Logout {
+                    session_status: None,
+                    text: Some(fix_str!("Market halted").to_owned()),
+                    disconnect: false,
+                }
+            } else {
+                Verdict::Reject {
+                    reason: SessionRejectReason::Other,
+                    tag: Some(FieldTag::TargetSubId),
+                    logout: false,
+                }
+            }
+        })
+    }
+}
+
+/// Log on, send News<B> to halted and then to open market, return
+/// application messages received and messages sent after Logon<A> response.
+fn run(verifier: MarketHalt) -> (Vec<Arc<FixtMessage>>, Vec<Arc<FixtMessage>>) {
+    let mut session_settings = common::session_settings();
+    session_settings.verifier = SessionVerifier::new(verifier);
+
+    block_on(async move {
+        let acceptor = acceptor(session_settings);
+        let mut subscriber = acceptor.events().subscribe().with_kinds(&[
+            SessionEventKind::AppMsgIn,
+            SessionEventKind::AppMsgOut,
+            SessionEventKind::AdmMsgOut,
+        ]);
+        let mut connection = accept(&acceptor);
+        let _drain = drain(acceptor);
+
+        connection.send(&basic_msg(0)).await;
+        for (seq_num, market) in [(2, fix_str!("HALTED")), (3, fix_str!("OPEN"))] {
+            let mut news = basic_msg(0);
+            news.header.msg_type = MsgType::News;
+            news.header.msg_seq_num = seq_num;
+            news.header.target_sub_id = Some(market.to_owned());
+            *news.body = Message::News(News {
+                headline: FixString::from_ascii_lossy(format!("news {seq_num}").into_bytes()),
+                lines_of_text_grp: vec![LinesOfTextGrp {
+                    text: fix_str!("verified").to_owned(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            });
+            connection.send(&news).await;
+        }
+
+        let mut received = Vec::new();
+        let mut sent = Vec::new();
+        for event in collect(&mut subscriber).await {
+            match event {
+                SessionEvent::AppMsgIn(_, msg) => received.push(msg),
+                SessionEvent::AppMsgOut(_, msg) | SessionEvent::AdmMsgOut(_, msg)
+                    if msg.msg_type() != MsgType::Logon =>
+                {
+                    sent.push(msg)
+                }
+                _ => {}
+            }
+        }
+        connection.close().await;
+        (received, sent)
+    })
+}
+
+#[test]
+fn rejected_by_custom_verifier() {
+    let (received, sent) = run(MarketHalt { logout: false });
+    assert_eq!(received.len(), 1, "{received:?}");
+    assert_eq!(received[0].header.msg_seq_num, 3);
+    assert_eq!(sent.len(), 1, "{sent:?}");
+    let Message::Reject(ref reject) = *sent[0].body else {
+        panic!("unexpected message: {:?}", sent[0]);
+    };
+    assert_eq!(reject.ref_seq_num, 2);
+    assert_eq!(reject.ref_tag_id, Some(FieldTag::TargetSubId as i64));
+    assert_eq!(
+        reject.session_reject_reason,
+        Some(SessionRejectReason::Other)
+    );
+}
+
+#[test]
+fn logout_by_custom_verifier() {
+    let (received, sent) = run(MarketHalt { logout: true });
+    assert!(received.is_empty(), "{received:?}");
+    let Message::Logout(ref logout) = *sent[0].body else {
+        panic!("unexpected message: {:?}", sent[0]);
+    };
+    assert_eq!(logout.text.as_deref(), Some(fix_str!("Market halted")));
+}